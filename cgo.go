@@ -156,6 +156,10 @@ func (we *WasmEngine) SetWasmBinary(wasmBinary []byte) {
 	C.set_wasm_binary(C.int(we.id), cVec, cLen)
 }
 
+// ComputeResult executes the `main` function of the Wasm module.
+//
+// Returns 0 if `main` returned, the exit status if a host function exited
+// the execution and -1 if the execution trapped. See LastBacktrace for traps.
 func (we *WasmEngine) ComputeResult() (traceJson int32, err error) {
 	res := C.compute_result(C.int(we.id))
 	return int32(res), nil
@@ -200,6 +204,14 @@ func (we *WasmEngine) ComputeTrace() (traceJson []byte, err error) {
 	return traceJson, nil
 }
 
+// LastBacktrace returns the Wasm backtrace of the last trapped ComputeResult or ComputeTrace
+// call as a JSON array of frames, innermost first. It is empty if the last call did not trap.
+func (we *WasmEngine) LastBacktrace() (backtraceJson []byte, err error) {
+	res := C.last_backtrace(C.int(we.id))
+	backtraceJson = C.GoBytes(unsafe.Pointer(res.ptr), C.int(res.len))
+	return backtraceJson, nil
+}
+
 func (we *WasmEngine) MemoryData() (data []byte, err error) {
 	res := C.memory_data(C.int(we.id))
 	data = C.GoBytes(unsafe.Pointer(res.ptr), C.int(res.len))
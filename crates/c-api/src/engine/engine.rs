@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use std::sync::{Mutex};
 use wasmi::{AsContext, AsContextMut, Config, Engine, Error, ExternType, Func, Instance, IntoFunc, Linker, Module, OpCodeState, ResumableCall, Store, TypedResumableCall};
-use wasmi::core::Trap;
+use wasmi::core::{Trap, WasmBacktrace};
 use wasmi::ResumableCall::Resumable;
 
 #[derive(Debug)]
@@ -13,6 +13,7 @@ pub struct WasmEngine {
     host_fns: HashMap<String, Func>,
     lock: Mutex<i32>,
     instance: Option<Instance>,
+    last_backtrace: Option<WasmBacktrace>,
     // memory_data_ptr: *mut u8,
 }
 
@@ -33,6 +34,7 @@ impl WasmEngine {
             host_fns: HashMap::new(),
             lock: Mutex::new(0),
             instance: None,
+            last_backtrace: None,
             // memory_data_ptr: null_mut(),
         };
 
@@ -96,7 +98,8 @@ impl WasmEngine {
             Err(_) => panic!("lock failed")
         }
         // do not lock the lines below: wasm calls host functions which may call back to wasmi containing lock
-        let call = func.call_resumable(&mut self.store, ()).unwrap();
+        let result = func.call_resumable(&mut self.store, ());
+        let call = self.record_trap(result)?;
         match call {
             TypedResumableCall::Finished(_) => {}
             TypedResumableCall::Resumable(invocation) => {
@@ -107,6 +110,33 @@ impl WasmEngine {
         Ok(0)
     }
 
+    /// Remembers the Wasm backtrace of a trapped call so that it can be queried later.
+    fn record_trap<T>(&mut self, result: Result<T, Trap>) -> Result<T, Error> {
+        self.last_backtrace = None;
+        result.map_err(|trap| {
+            self.last_backtrace = trap.backtrace().cloned();
+            trap.into()
+        })
+    }
+
+    /// Returns the Wasm backtrace of the last trapped call as a JSON array of frames.
+    pub fn last_backtrace(&self) -> Option<String> {
+        let frames = self
+            .last_backtrace
+            .as_ref()?
+            .frames()
+            .iter()
+            .map(|frame| {
+                serde_json::json!({
+                    "func_index": frame.func_index(),
+                    "func_name": frame.func_name(),
+                    "source_pc": frame.source_pc(),
+                })
+            })
+            .collect::<Vec<_>>();
+        Some(serde_json::Value::from(frames).to_string())
+    }
+
     pub fn dump_trace(&mut self) -> Result<String, Error> {
         let json_body = match self.lock.lock() {
             Ok(_) => {
@@ -133,7 +163,8 @@ impl WasmEngine {
             Err(_) => panic!("lock failed")
         }
         // do not lock the lines below: wasm calls host functions which may call back to wasmi containing lock
-        let call = func.call_resumable(&mut self.store, ()).unwrap();
+        let result = func.call_resumable(&mut self.store, ());
+        let call = self.record_trap(result)?;
         match call {
             TypedResumableCall::Finished(_) => {}
             TypedResumableCall::Resumable(invocation) => {
@@ -162,8 +193,10 @@ impl WasmEngine {
                 let export = instance.get_export(&self.store, export.name());
                 if let Some(export) = export {
                     if let Some(memory) = export.into_memory() {
-                        let mem_data = memory.data(&self.store);
-                        memory_data = mem_data.into();
+                        memory_data = vec![0x00; memory.data_size(&self.store)];
+                        memory
+                            .read(&self.store, 0, &mut memory_data)
+                            .expect("the whole linear memory is in bounds");
                     }
                 }
             }
@@ -73,8 +73,7 @@ impl<'a> ProxyFactory {
 
     pub fn compute_result(&mut self, engine_id: i32) -> Option<i32> {
         let we = self.get_wasm_engine(engine_id);
-        let trace: i32 = unsafe { (*we.as_ptr()).compute_result().unwrap() };
-        Some(trace)
+        unsafe { (*we.as_ptr()).compute_result().ok() }
     }
 
     pub fn dump_trace(&mut self, engine_id: i32) -> Option<String> {
@@ -90,8 +89,12 @@ impl<'a> ProxyFactory {
 
     pub fn compute_trace(&mut self, engine_id: i32) -> Option<String> {
         let we = self.get_wasm_engine(engine_id);
-        let trace: String = unsafe { (*we.as_ptr()).compute_trace().unwrap() };
-        Some(trace)
+        unsafe { (*we.as_ptr()).compute_trace().ok() }
+    }
+
+    pub fn last_backtrace(&mut self, engine_id: i32) -> Option<String> {
+        let we = self.get_wasm_engine(engine_id);
+        unsafe { (*we.as_ptr()).last_backtrace() }
     }
 
     pub fn memory_data(&mut self, engine_id: i32) -> Option<Vec<u8>> {
@@ -52,6 +52,11 @@ extern "C" fn set_wasm_binary(
     unsafe {FACTORY.set_wasm_binary(engine_id, &wasm_binary.to_vec())};
 }
 
+/// Executes the `main` function of the Wasm module of the engine.
+///
+/// Returns `0` if `main` returned, the exit status if a host function
+/// exited the execution and `-1` if the execution trapped.
+/// The backtrace of a trap is available via `last_backtrace`.
 #[ffi_export]
 extern "C" fn compute_result(
     engine_id: i32,
@@ -59,7 +64,7 @@ extern "C" fn compute_result(
     let res = unsafe {FACTORY.compute_result(engine_id)};
     match res {
         Some(r) => r,
-        None => 0,
+        None => -1,
     }
 }
 
@@ -92,6 +97,21 @@ extern "C" fn compute_trace(
     }
 }
 
+/// Returns the Wasm backtrace of the last trapped `compute_result` or `compute_trace`
+/// call as a JSON array of `{ func_index, func_name, source_pc }` frames, innermost first.
+///
+/// Returns an empty vector if the last call did not trap.
+#[ffi_export]
+extern "C" fn last_backtrace(
+    engine_id: i32,
+) -> repr_c::Vec<u8> {
+    let res = unsafe {FACTORY.last_backtrace(engine_id)};
+    match res {
+        Some(r) => repr_c::Vec::from(r.as_bytes().to_vec()),
+        None => repr_c::Vec::from(Vec::new())
+    }
+}
+
 #[ffi_export]
 extern "C" fn memory_data(
     engine_id: i32,
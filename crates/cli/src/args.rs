@@ -1,11 +1,13 @@
 use anyhow::{Context, Error, Result};
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use std::{
     ffi::OsStr,
     net::SocketAddr,
+    ops::Range,
     path::{Path, PathBuf},
     str::FromStr,
 };
+use wasmi::ProfileWeight;
 use wasmi_wasi::{ambient_authority, Dir, TcpListener, WasiCtx, WasiCtxBuilder};
 
 /// A CLI flag value key-value argument.
@@ -28,7 +30,7 @@ impl FromStr for KeyValue {
             .find('=')
             .ok_or_else(|| anyhow::anyhow!("invalid KEY=value: no `=` found in `{}`", s))?;
         let (key, eq_value) = s.split_at(eq_pos);
-        assert!(s.starts_with('='));
+        assert!(eq_value.starts_with('='));
         let value = &eq_value[1..];
         let key = key.to_string();
         let value = value.to_string();
@@ -36,10 +38,120 @@ impl FromStr for KeyValue {
     }
 }
 
+/// Parses a CLI flag value as [`ProfileWeight`].
+///
+/// # Errors
+///
+/// If the string is neither `instructions` nor `fuel`.
+fn parse_profile_weight(s: &str) -> Result<ProfileWeight, Error> {
+    match s {
+        "instructions" => Ok(ProfileWeight::Instructions),
+        "fuel" => Ok(ProfileWeight::Fuel),
+        _ => Err(anyhow::anyhow!(
+            "invalid profile weight `{s}`: expected `instructions` or `fuel`"
+        )),
+    }
+}
+
+/// Parses a CLI flag value as a range of execution steps.
+///
+/// Accepts `START..END`, `START..` and `..END` where `END` is exclusive.
+///
+/// # Errors
+///
+/// If the string is not a range of non-negative integers or if `START` exceeds `END`.
+pub fn parse_step_range(s: &str) -> Result<Range<usize>, Error> {
+    let (start, end) = s
+        .split_once("..")
+        .ok_or_else(|| anyhow::anyhow!("invalid step range `{s}`: no `..` found"))?;
+    let parse = |bound: &str, default: usize| -> Result<usize, Error> {
+        match bound {
+            "" => Ok(default),
+            bound => bound
+                .parse()
+                .map_err(|error| anyhow::anyhow!("invalid step range `{s}`: {error}")),
+        }
+    };
+    let (start, end) = (parse(start, 0)?, parse(end, usize::MAX)?);
+    if start > end {
+        anyhow::bail!("invalid step range `{s}`: start exceeds end")
+    }
+    Ok(start..end)
+}
+
 /// Simple program to greet a person
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None, trailing_var_arg = true)]
+#[clap(
+    author,
+    version,
+    about,
+    long_about = None,
+    trailing_var_arg = true,
+    subcommand_negates_reqs = true,
+    args_conflicts_with_subcommands = true,
+)]
 pub struct Args {
+    /// The subcommand to run instead of executing the Wasm module.
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// The Wasm module to execute and how to execute it.
+    #[clap(flatten)]
+    module: ModuleArgs,
+
+    /// Run the invoked function in the interactive step debugger.
+    ///
+    /// The execution pauses before the first instruction and reads debugger
+    /// commands from `stdin`. Type `help` at the prompt for a list of commands.
+    #[clap(long = "debug")]
+    debug: bool,
+
+    /// Profile the invoked function and write its folded call stacks to FILE.
+    ///
+    /// The folded stacks can be rendered by flamegraph tooling such as `inferno-flamegraph`.
+    /// A per-function summary and a per-opcode histogram are printed after the execution.
+    #[clap(
+        long = "profile",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    profile: Option<PathBuf>,
+
+    /// The weight of the profiled call stacks: `instructions` or `fuel`.
+    #[clap(
+        long = "profile-weight",
+        value_name = "WEIGHT",
+        value_parser(parse_profile_weight),
+        default_value = "instructions"
+    )]
+    profile_weight: ProfileWeight,
+
+    /// Only record every N-th executed instruction while profiling.
+    #[clap(
+        long = "profile-interval",
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..),
+        requires = "profile",
+    )]
+    profile_interval: Option<u64>,
+}
+
+/// The subcommands of the `wasmi` CLI application.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Execute a function and write its execution trace to a file.
+    Trace(Box<TraceArgs>),
+    /// Print the imports, exports and entities of a Wasm module without executing it.
+    Inspect(InspectArgs),
+    /// Keep a Wasm module instance alive and execute commands against it.
+    Repl(Box<ReplArgs>),
+    /// Run the directives of `.wast` script files and report their outcomes.
+    Wast(WastArgs),
+}
+
+/// The Wasm module to execute and the environment to execute it in.
+#[derive(clap::Args, Debug)]
+pub struct ModuleArgs {
     /// The host directory to pre-open for the `guest` to use.
     #[clap(
         long = "dir",
@@ -66,12 +178,32 @@ pub struct Args {
     )]
     envs: Vec<KeyValue>,
 
+    /// Instantiate the Wasm module at PATH and link its exports under the module name NAME.
+    ///
+    /// Can be given multiple times. Modules are instantiated in the given order
+    /// and may import the exports of previously preloaded modules.
+    #[clap(
+        long = "preload",
+        value_name = "NAME=PATH",
+        value_parser(KeyValue::from_str),
+        action = clap::ArgAction::Append,
+    )]
+    preloads: Vec<KeyValue>,
+
+    /// Define imported functions without a definition as stubs.
+    ///
+    /// `log` stubs print their arguments and return zeros, `trap` stubs trap when called.
+    #[clap(long = "stub-imports", value_name = "MODE", value_enum)]
+    stub_imports: Option<StubMode>,
+
+    // Note: This is only optional for subcommands that do not execute a Wasm module.
     /// The file containing the WebAssembly module to execute.
     #[clap(
         value_name = "MODULE",
         value_hint = clap::ValueHint::FilePath,
+        required = true,
     )]
-    wasm_file: PathBuf,
+    wasm_file: Option<PathBuf>,
 
     /// The function to invoke.
     ///
@@ -93,10 +225,179 @@ pub struct Args {
     func_args: Vec<String>,
 }
 
+/// How imported functions without a definition are stubbed.
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StubMode {
+    /// Print the name and arguments of every call and return zeros.
+    Log,
+    /// Trap with the name of the import when called.
+    Trap,
+}
+
+/// The format of a written execution trace.
+#[derive(ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// The JSON document produced by `Tracer::to_json`.
+    Json,
+    /// One JSON object per executed instruction and line.
+    Jsonl,
+    /// One human readable line per executed instruction.
+    Text,
+}
+
+/// Execute a function and write its execution trace to a file.
+#[derive(clap::Args, Debug)]
+#[clap(trailing_var_arg = true)]
+pub struct TraceArgs {
+    /// The Wasm module to execute and how to execute it.
+    #[clap(flatten)]
+    module: ModuleArgs,
+
+    /// The file to write the execution trace to.
+    #[clap(
+        long = "output",
+        short = 'o',
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        default_value = "trace.json",
+    )]
+    output: PathBuf,
+
+    /// The format of the written execution trace.
+    #[clap(
+        long = "format",
+        value_name = "FORMAT",
+        value_enum,
+        default_value = "json"
+    )]
+    format: TraceFormat,
+
+    /// Only write instructions executed by the function with NAME or index.
+    ///
+    /// Can be given multiple times to allow multiple functions.
+    #[clap(
+        long = "func",
+        value_name = "NAME",
+        action = clap::ArgAction::Append,
+    )]
+    funcs: Vec<String>,
+
+    /// Only write instructions executed within the steps START..END.
+    ///
+    /// Steps count executed instructions starting at zero. Either bound may be omitted.
+    #[clap(
+        long = "steps",
+        value_name = "START..END",
+        value_parser(parse_step_range)
+    )]
+    steps: Option<Range<usize>>,
+}
+
+/// Print the imports, exports and entities of a Wasm module without executing it.
+#[derive(clap::Args, Debug)]
+pub struct InspectArgs {
+    /// The file containing the WebAssembly module to inspect.
+    #[clap(value_name = "MODULE", value_hint = clap::ValueHint::FilePath)]
+    wasm_file: PathBuf,
+
+    /// Also print the translated `wasmi` bytecode of every internal function.
+    ///
+    /// Every instruction is printed alongside the position and opcode of
+    /// the Wasm operator it was translated from.
+    #[clap(long = "disasm")]
+    disasm: bool,
+}
+
+/// Keep a Wasm module instance alive and execute commands against it.
+///
+/// Commands are read from `stdin` or from a script file given via `--script`.
+/// Type `help` at the prompt for a list of commands.
+#[derive(clap::Args, Debug)]
+#[clap(trailing_var_arg = true)]
+pub struct ReplArgs {
+    /// The Wasm module to instantiate and the environment to instantiate it in.
+    ///
+    /// The function given via `--invoke` is invoked before the first command.
+    #[clap(flatten)]
+    module: ModuleArgs,
+
+    /// Read the commands from FILE instead of `stdin`.
+    ///
+    /// Stops at the first failing command and exits with an error.
+    #[clap(
+        long = "script",
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+    )]
+    script: Option<PathBuf>,
+}
+
+/// Run the directives of `.wast` script files and report their outcomes.
+///
+/// The `spectest` host module of the Wasm spec test suite is provided to all scripts.
+/// Exits with an error if any directive fails.
+#[derive(clap::Args, Debug)]
+pub struct WastArgs {
+    /// The `.wast` script files to run.
+    #[clap(
+        value_name = "FILE",
+        value_hint = clap::ValueHint::FilePath,
+        required = true,
+    )]
+    wast_files: Vec<PathBuf>,
+
+    /// Print one JSON report per script instead of one line per directive.
+    #[clap(long = "json")]
+    json: bool,
+
+    /// Also run every script with deterministic tracing disabled and
+    /// report all directives with a differing outcome.
+    #[clap(long = "compare-tracing")]
+    compare_tracing: bool,
+}
+
 impl Args {
+    /// Returns the subcommand given to the CLI app if any.
+    pub fn command(&self) -> Option<&Command> {
+        self.command.as_ref()
+    }
+
+    /// Returns the Wasm module to execute and how to execute it.
+    pub fn module(&self) -> &ModuleArgs {
+        &self.module
+    }
+
+    /// Returns `true` if the invoked function shall run in the interactive step debugger.
+    pub fn debug(&self) -> bool {
+        self.debug
+    }
+
+    /// Returns the file to write the folded call stacks of the profiled execution to if any.
+    pub fn profile(&self) -> Option<&Path> {
+        self.profile.as_deref()
+    }
+
+    /// Returns the weight of the profiled call stacks.
+    pub fn profile_weight(&self) -> ProfileWeight {
+        self.profile_weight
+    }
+
+    /// Returns the sample interval of the profiler if any.
+    pub fn profile_interval(&self) -> Option<u64> {
+        self.profile_interval
+    }
+}
+
+impl ModuleArgs {
     /// Returns the Wasm file path given to the CLI app.
+    ///
+    /// # Panics
+    ///
+    /// If no Wasm file was given which clap prevents unless a subcommand was given.
     pub fn wasm_file(&self) -> &Path {
-        &self.wasm_file
+        self.wasm_file
+            .as_deref()
+            .expect("clap requires the MODULE argument")
     }
 
     /// Returns the name of the invoked function if any.
@@ -114,6 +415,18 @@ impl Args {
         self.fuel
     }
 
+    /// Returns the module names and paths of the Wasm modules given via `--preload` in order.
+    pub fn preloads(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.preloads
+            .iter()
+            .map(|KeyValue { key, value }| (key.as_str(), Path::new(value)))
+    }
+
+    /// Returns how imported functions without a definition are stubbed if at all.
+    pub fn stub_imports(&self) -> Option<StubMode> {
+        self.stub_imports
+    }
+
     /// Pre-opens all directories given in `--dir` and returns them for use by the [`WasiCtx`].
     ///
     /// # Errors
@@ -159,7 +472,7 @@ impl Args {
         // The WebAssembly filename is expected to be the first argument to WASI.
         // Note that the module name still has it's `.wasm` file extension.
         let module_name = self
-            .wasm_file
+            .wasm_file()
             .file_name()
             .and_then(OsStr::to_str)
             .unwrap_or("")
@@ -191,3 +504,73 @@ impl Args {
         Ok(wasi_builder.build())
     }
 }
+
+impl TraceArgs {
+    /// Returns the Wasm module to execute and how to execute it.
+    pub fn module(&self) -> &ModuleArgs {
+        &self.module
+    }
+
+    /// Returns the file to write the execution trace to.
+    pub fn output(&self) -> &Path {
+        &self.output
+    }
+
+    /// Returns the format of the written execution trace.
+    pub fn format(&self) -> TraceFormat {
+        self.format
+    }
+
+    /// Returns the names or indices of the functions whose instructions are written.
+    ///
+    /// All functions are written if this is empty.
+    pub fn funcs(&self) -> &[String] {
+        &self.funcs
+    }
+
+    /// Returns the range of execution steps that are written if any.
+    pub fn steps(&self) -> Option<Range<usize>> {
+        self.steps.clone()
+    }
+}
+
+impl InspectArgs {
+    /// Returns the Wasm file path given to the `inspect` subcommand.
+    pub fn wasm_file(&self) -> &Path {
+        &self.wasm_file
+    }
+
+    /// Returns `true` if the translated `wasmi` bytecode shall be printed.
+    pub fn disasm(&self) -> bool {
+        self.disasm
+    }
+}
+
+impl WastArgs {
+    /// Returns the `.wast` script files given to the `wast` subcommand.
+    pub fn wast_files(&self) -> &[PathBuf] {
+        &self.wast_files
+    }
+
+    /// Returns `true` if the reports shall be printed as JSON.
+    pub fn json(&self) -> bool {
+        self.json
+    }
+
+    /// Returns `true` if the scripts shall also be run with deterministic tracing disabled.
+    pub fn compare_tracing(&self) -> bool {
+        self.compare_tracing
+    }
+}
+
+impl ReplArgs {
+    /// Returns the Wasm module to instantiate and the environment to instantiate it in.
+    pub fn module(&self) -> &ModuleArgs {
+        &self.module
+    }
+
+    /// Returns the script file to read the commands from if any.
+    pub fn script(&self) -> Option<&Path> {
+        self.script.as_deref()
+    }
+}
@@ -1,7 +1,24 @@
-use crate::utils;
+use crate::{
+    args::{ModuleArgs, StubMode},
+    display::{DisplayFuncType, DisplaySequence, DisplayValue},
+    utils,
+};
 use anyhow::{anyhow, Error};
 use std::path::Path;
-use wasmi::{Config, ExternType, Func, FuncType, Instance, Module, Store};
+use wasmi::{
+    Config,
+    Engine,
+    ExternType,
+    Func,
+    FuncType,
+    Global,
+    Instance,
+    Linker,
+    Memory,
+    Module,
+    Store,
+    Value,
+};
 use wasmi_wasi::WasiCtx;
 
 /// The [`Context`] for the `wasmi` CLI application.
@@ -17,24 +34,24 @@ pub struct Context {
 }
 
 impl Context {
-    /// Creates a new [`Context`].
+    /// Creates a new [`Context`] for the Wasm module and environment given by `args`.
+    ///
+    /// Modules given via `--preload` are instantiated first and linked by their names.
     ///
     /// # Errors
     ///
     /// - If parsing, validating, compiling or instantiating the Wasm module failed.
+    /// - If parsing, validating, compiling or instantiating a preloaded Wasm module failed.
     /// - If adding WASI defintions to the linker failed.
-    pub fn new(wasm_file: &Path, wasi_ctx: WasiCtx, fuel: Option<u64>) -> Result<Self, Error> {
+    pub fn new(args: &ModuleArgs) -> Result<Self, Error> {
         let mut config = Config::default();
-        if fuel.is_some() {
+        if args.fuel().is_some() {
             config.consume_fuel(true);
         }
         let engine = wasmi::Engine::new(&config);
-        let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
-        let module = wasmi::Module::new(&engine, &mut &wasm_bytes[..]).map_err(|error| {
-            anyhow!("failed to parse and validate Wasm module {wasm_file:?}: {error}")
-        })?;
-        let mut store = wasmi::Store::new(&engine, wasi_ctx);
-        if let Some(fuel) = fuel {
+        let module = read_module(&engine, args.wasm_file())?;
+        let mut store = wasmi::Store::new(&engine, args.wasi_context()?);
+        if let Some(fuel) = args.fuel() {
             store.add_fuel(fuel).unwrap_or_else(|error| {
                 panic!("error: fuel metering is enabled but encountered: {error}")
             });
@@ -42,6 +59,14 @@ impl Context {
         let mut linker = <wasmi::Linker<WasiCtx>>::new(&engine);
         wasmi_wasi::add_to_linker(&mut linker, |ctx| ctx)
             .map_err(|error| anyhow!("failed to add WASI definitions to the linker: {error}"))?;
+        for (name, path) in args.preloads() {
+            let preloaded = read_module(&engine, path)?;
+            define_stubs(&mut linker, &preloaded, args.stub_imports());
+            linker
+                .module(&mut store, name, &preloaded)
+                .map_err(|error| anyhow!("failed to preload Wasm module {path:?}: {error}"))?;
+        }
+        define_stubs(&mut linker, &module, args.stub_imports());
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre| pre.start(&mut store))
@@ -66,6 +91,11 @@ impl Context {
         })
     }
 
+    /// Returns a shared reference to the Wasm [`Module`] of the [`Context`].
+    pub fn module(&self) -> &Module {
+        &self.module
+    }
+
     /// Returns a shared reference to the [`Store`] of the [`Context`].
     pub fn store(&self) -> &Store<WasiCtx> {
         &self.store
@@ -82,4 +112,81 @@ impl Context {
             .get_func(&self.store, name)
             .ok_or_else(|| anyhow!("failed to find function named {name:?} in the Wasm module"))
     }
+
+    /// Returns the exported global variable named `name` if any.
+    pub fn get_global(&self, name: &str) -> Result<Global, Error> {
+        self.instance
+            .get_global(&self.store, name)
+            .ok_or_else(|| anyhow!("failed to find global named {name:?} in the Wasm module"))
+    }
+
+    /// Returns the exported linear memory named `name` if any.
+    pub fn get_memory(&self, name: &str) -> Result<Memory, Error> {
+        self.instance
+            .get_memory(&self.store, name)
+            .ok_or_else(|| anyhow!("failed to find memory named {name:?} in the Wasm module"))
+    }
+}
+
+/// Reads, parses, validates and compiles the Wasm module in `wasm_file`.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read, parsed, validated or compiled.
+fn read_module(engine: &Engine, wasm_file: &Path) -> Result<Module, Error> {
+    let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
+    Module::new(engine, &mut &wasm_bytes[..])
+        .map_err(|error| anyhow!("failed to parse and validate Wasm module {wasm_file:?}: {error}"))
+}
+
+/// Defines the imported functions of the `module` without a definition as stubs if requested.
+fn define_stubs(linker: &mut Linker<WasiCtx>, module: &Module, mode: Option<StubMode>) {
+    match mode {
+        None => {}
+        Some(StubMode::Trap) => {
+            linker.define_unknown_imports_as_traps(module);
+        }
+        Some(StubMode::Log) => {
+            for import in module.imports() {
+                let ExternType::Func(func_type) = import.ty() else {
+                    continue;
+                };
+                let (module_name, name) = (import.module(), import.name());
+                if linker.has_definition(module_name, name) {
+                    continue;
+                }
+                let stub_name = format!("{module_name}::{name}");
+                println!(
+                    "stubbed import {}",
+                    DisplayFuncType::new(&stub_name, func_type)
+                );
+                linker
+                    .func_new(
+                        module_name,
+                        name,
+                        func_type.clone(),
+                        move |_caller, params, results| {
+                            println!(
+                                "called {stub_name}({})",
+                                DisplaySequence::new(", ", params.iter().map(display_param))
+                            );
+                            for result in results {
+                                *result = Value::default(result.ty());
+                            }
+                            Ok(())
+                        },
+                    )
+                    .unwrap_or_else(|error| panic!("failed to define stub: {error}"));
+            }
+        }
+    }
+}
+
+/// Returns the parameter `value` of a stub call as string.
+fn display_param(value: &Value) -> String {
+    match value {
+        Value::FuncRef(_) => String::from("funcref"),
+        Value::ExternRef(_) => String::from("externref"),
+        value => DisplayValue::from(value).to_string(),
+    }
 }
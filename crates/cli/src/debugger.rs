@@ -0,0 +1,141 @@
+use crate::{
+    display::DisplayValue,
+    utils::{parse_u32, print_memory},
+};
+use std::{
+    io::{self, BufRead, Write},
+    process,
+};
+use wasmi::{core::UntypedValue, Breakpoint, DebugAction, DebugContext, Debugger, Store, Value};
+
+/// The help text listing all commands of the interactive debugger.
+const HELP: &str = "\
+commands:
+  s, step                      execute the paused instruction and pause again
+  c, continue                  resume execution until the next breakpoint
+  b, break <FUNC> [PC]         pause at entry of function FUNC or at its Wasm operator at PC
+  d, delete <FUNC> [PC]        delete the breakpoint at FUNC or at PC of FUNC
+  breakpoints                  list all breakpoints
+  l, locals                    print the parameters and locals of the paused function
+  o, operands                  print the operand stack of the paused function
+  g, global <N>                print the global variable N
+  m, memory <ADDR> [LEN]       print LEN bytes of the linear memory starting at ADDR
+  q, quit                      abort the execution and exit
+  h, help                      print this help text";
+
+/// Installs the interactive step debugger into the `store`.
+///
+/// The execution pauses before the first executed instruction
+/// and then reads debugger commands from `stdin`.
+pub fn install<T>(store: &mut Store<T>) {
+    let mut debugger = Debugger::new(handle_pause);
+    debugger.set_stepping(true);
+    store.set_debugger(debugger);
+    println!("debugger attached, type `help` for a list of commands");
+}
+
+/// Reads and executes debugger commands from `stdin` until execution shall resume.
+///
+/// Resumes execution without stepping if `stdin` has been closed.
+fn handle_pause(ctx: &mut DebugContext) -> DebugAction {
+    println!(
+        "paused ({:?}) in function {} at {:#08x}: {:?}",
+        ctx.reason(),
+        ctx.func_index(),
+        ctx.source_pc(),
+        ctx.instr(),
+    );
+    let stdin = io::stdin();
+    let mut line = String::new();
+    loop {
+        print!("(wasmi) ");
+        let _ = io::stdout().flush();
+        line.clear();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) | Err(_) => return DebugAction::Continue,
+            Ok(_) => {}
+        }
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let operands = words.collect::<Vec<_>>();
+        match (command, &operands[..]) {
+            ("s" | "step", []) => return DebugAction::Step,
+            ("c" | "continue", []) => return DebugAction::Continue,
+            ("b" | "break", operands) => match parse_breakpoint(operands) {
+                Some(breakpoint) => {
+                    if !ctx.add_breakpoint(breakpoint) {
+                        println!("breakpoint already exists");
+                    }
+                }
+                None => println!("usage: break <FUNC> [PC]"),
+            },
+            ("d" | "delete", operands) => match parse_breakpoint(operands) {
+                Some(breakpoint) => {
+                    if !ctx.remove_breakpoint(breakpoint) {
+                        println!("no such breakpoint");
+                    }
+                }
+                None => println!("usage: delete <FUNC> [PC]"),
+            },
+            ("breakpoints", []) => {
+                for breakpoint in ctx.breakpoints() {
+                    match breakpoint {
+                        Breakpoint::Func(func_index) => println!("function {func_index}"),
+                        Breakpoint::Instr {
+                            func_index,
+                            source_pc,
+                        } => println!("function {func_index} at {source_pc:#08x}"),
+                    }
+                }
+            }
+            ("l" | "locals", []) => print_values(ctx.locals()),
+            ("o" | "operands", []) => print_values(ctx.operands()),
+            ("g" | "global", [index]) => match parse_u32(index).and_then(|n| ctx.global(n)) {
+                Some(Value::FuncRef(_)) => println!("funcref"),
+                Some(Value::ExternRef(_)) => println!("externref"),
+                Some(value) => println!("{}", DisplayValue::from(&value)),
+                None => println!("no such global variable"),
+            },
+            ("m" | "memory", [address, rest @ ..]) if rest.len() <= 1 => {
+                let address = parse_u32(address);
+                let len = rest.first().map_or(Some(16), |len| parse_u32(len));
+                match (address, len) {
+                    (Some(address), Some(len)) => {
+                        match ctx.read_memory(0, address as usize, len as usize) {
+                            Some(bytes) => print_memory(&bytes, address as usize),
+                            None => println!("no linear memory"),
+                        }
+                    }
+                    _ => println!("usage: memory <ADDR> [LEN]"),
+                }
+            }
+            ("q" | "quit", []) => process::exit(1),
+            ("h" | "help", []) => println!("{HELP}"),
+            _ => println!(
+                "unknown command `{}`, type `help` for a list of commands",
+                line.trim()
+            ),
+        }
+    }
+}
+
+/// Parses the `<FUNC> [PC]` operands of a breakpoint command.
+fn parse_breakpoint(operands: &[&str]) -> Option<Breakpoint> {
+    match operands {
+        [func_index] => Some(Breakpoint::Func(parse_u32(func_index)?)),
+        [func_index, source_pc] => Some(Breakpoint::Instr {
+            func_index: parse_u32(func_index)?,
+            source_pc: parse_u32(source_pc)?,
+        }),
+        _ => None,
+    }
+}
+
+/// Prints the untyped `values` as their raw bits.
+fn print_values(values: &[UntypedValue]) {
+    for (n, value) in values.iter().enumerate() {
+        println!("{n:>4}: {:#018x}", value.to_bits());
+    }
+}
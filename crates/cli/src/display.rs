@@ -1,6 +1,6 @@
 use crate::context::Context;
 use std::fmt::{self, Display};
-use wasmi::{core::ValueType, FuncType, Value};
+use wasmi::{core::ValueType, ExternType, FuncType, Mutability, Value};
 
 /// [`Display`]-wrapper type for [`ValueType`].
 pub struct DisplayValueType<'a>(&'a ValueType);
@@ -104,6 +104,56 @@ impl fmt::Display for DisplayFuncType<'_> {
     }
 }
 
+/// [`Display`]-wrapper type around [`ExternType`].
+pub struct DisplayExternType<'a>(&'a ExternType);
+
+impl<'a> From<&'a ExternType> for DisplayExternType<'a> {
+    fn from(extern_type: &'a ExternType) -> Self {
+        Self(extern_type)
+    }
+}
+
+impl Display for DisplayExternType<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ExternType::Func(func_type) => write!(f, "{}", DisplayFuncType::from(func_type)),
+            ExternType::Global(global_type) => {
+                let content = global_type.content();
+                let content = DisplayValueType::from(&content);
+                match global_type.mutability() {
+                    Mutability::Const => write!(f, "global {content}"),
+                    Mutability::Var => write!(f, "global mut {content}"),
+                }
+            }
+            ExternType::Table(table_type) => {
+                let element = table_type.element();
+                let element = DisplayValueType::from(&element);
+                write!(f, "table {element} {}..", table_type.minimum())?;
+                if let Some(maximum) = table_type.maximum() {
+                    write!(f, "{maximum}")?;
+                }
+                Ok(())
+            }
+            ExternType::Memory(memory_type) => {
+                write!(f, "memory {}..", u32::from(memory_type.initial_pages()))?;
+                if let Some(maximum) = memory_type.maximum_pages() {
+                    write!(f, "{}", u32::from(maximum))?;
+                }
+                write!(f, " pages")?;
+                if memory_type.is_shared() {
+                    write!(f, " shared")?;
+                }
+                Ok(())
+            }
+            ExternType::Tag(tag_type) => write!(
+                f,
+                "tag({})",
+                DisplaySequence::new(", ", tag_type.params().iter().map(DisplayValueType::from))
+            ),
+        }
+    }
+}
+
 /// [`Display`]-wrapper for generic sequences with separators.
 #[derive(Debug)]
 pub struct DisplaySequence<'a, T> {
@@ -0,0 +1,178 @@
+//! The `inspect` subcommand printing the contents of a Wasm module without executing it.
+
+use crate::{
+    args::InspectArgs,
+    display::{DisplayExternType, DisplayFuncType},
+    utils,
+};
+use anyhow::{anyhow, Result};
+use wasmi::{
+    core::{UntypedValue, ValueType, F32, F64},
+    DataSegmentKind,
+    Engine,
+    ExternType,
+    GlobalType,
+    Module,
+};
+
+/// Prints the imports, exports, memories, tables, globals and data segments of the Wasm module.
+///
+/// Also prints the translated `wasmi` bytecode of all internal functions if `--disasm` is given.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be read, parsed, validated or compiled.
+pub fn run(args: &InspectArgs) -> Result<()> {
+    let wasm_file = args.wasm_file();
+    let wasm_bytes = utils::read_wasm_or_wat(wasm_file)?;
+    let engine = Engine::default();
+    let module = Module::new(&engine, &mut &wasm_bytes[..]).map_err(|error| {
+        anyhow!("failed to parse and validate Wasm module {wasm_file:?}: {error}")
+    })?;
+    print_imports(&module);
+    print_exports(&module);
+    print_entities(&module);
+    print_data_segments(&module);
+    if args.disasm() {
+        print_disasm(&module);
+    }
+    Ok(())
+}
+
+/// Prints the imports of the `module` with their types.
+fn print_imports(module: &Module) {
+    println!("imports:");
+    for import in module.imports() {
+        println!(
+            "  {}::{}: {}",
+            import.module(),
+            import.name(),
+            DisplayExternType::from(import.ty())
+        );
+    }
+}
+
+/// Prints the exports of the `module` with their types.
+fn print_exports(module: &Module) {
+    println!("exports:");
+    for export in module.exports() {
+        println!(
+            "  {}: {}",
+            export.name(),
+            DisplayExternType::from(export.ty())
+        );
+    }
+}
+
+/// Prints the internal memories, tables and globals of the `module`.
+///
+/// # Note
+///
+/// Entities are printed with their module level index which accounts for imported entities.
+fn print_entities(module: &Module) {
+    println!("memories:");
+    let len_imported = module.imports.len_memories;
+    for (index, memory_type) in module.internal_memories().enumerate() {
+        let ty = ExternType::from(*memory_type);
+        println!(
+            "  {}: {}",
+            len_imported + index,
+            DisplayExternType::from(&ty)
+        );
+    }
+    println!("tables:");
+    let len_imported = module.imports.len_tables;
+    for (index, table_type) in module.internal_tables().enumerate() {
+        let ty = ExternType::from(*table_type);
+        println!(
+            "  {}: {}",
+            len_imported + index,
+            DisplayExternType::from(&ty)
+        );
+    }
+    println!("globals:");
+    let len_imported = module.imports.len_globals;
+    for (index, (global_type, init)) in module.internal_globals().enumerate() {
+        let index = len_imported + index;
+        let ty = ExternType::from(*global_type);
+        let init = match init.eval_const() {
+            Some(value) => display_const(global_type, value),
+            None => String::from("<non-constant>"),
+        };
+        println!("  {index}: {} = {init}", DisplayExternType::from(&ty));
+    }
+}
+
+/// Returns the constant initial `value` of a global variable of type `global_type` as string.
+fn display_const(global_type: &GlobalType, value: UntypedValue) -> String {
+    match global_type.content() {
+        ValueType::I32 => i32::from(value).to_string(),
+        ValueType::I64 => i64::from(value).to_string(),
+        ValueType::F32 => F32::from(value).to_string(),
+        ValueType::F64 => F64::from(value).to_string(),
+        ValueType::FuncRef | ValueType::ExternRef => match value.to_bits() {
+            0 => String::from("null"),
+            _ => String::from("<reference>"),
+        },
+    }
+}
+
+/// Prints the kind and length of the data segments of the `module`.
+fn print_data_segments(module: &Module) {
+    println!("data segments:");
+    for (index, segment) in module.data_segments().enumerate() {
+        let len = segment.bytes().len();
+        match segment.kind() {
+            DataSegmentKind::Passive => println!("  {index}: passive, {len} bytes"),
+            DataSegmentKind::Active(segment) => {
+                let memory = segment.memory_index().into_u32();
+                let offset = match segment.offset().eval_const() {
+                    Some(offset) => u32::from(offset).to_string(),
+                    None => String::from("<non-constant>"),
+                };
+                println!("  {index}: active, memory {memory}, offset {offset}, {len} bytes");
+            }
+        }
+    }
+}
+
+/// Prints the translated `wasmi` bytecode of all internal functions of the `module`.
+///
+/// Every instruction is printed with its index, the position and opcode of the Wasm
+/// operator it was translated from and the height of the operand stack before it.
+fn print_disasm(module: &Module) {
+    let engine = module.engine();
+    for (func_index, func_type, func_body) in module.compiled_funcs() {
+        let name = module
+            .names()
+            .func(func_index)
+            .map(String::from)
+            .unwrap_or_else(|| format!("{func_index}"));
+        let header = engine.func_header(func_body);
+        println!();
+        println!(
+            "func {func_index}: {}",
+            DisplayFuncType::new(&name, &func_type)
+        );
+        println!(
+            "  params: {}, locals: {}, max stack height: {}",
+            header.len_params(),
+            header.len_locals(),
+            header.max_stack_height()
+        );
+        println!(
+            "  {:>6}  {:>10}  {:>6}  {:>5}  instruction",
+            "index", "source_pc", "opcode", "stack"
+        );
+        let instrs = engine.instr_vec(func_body);
+        let metas = engine.instr_metas(func_body);
+        for (index, (instr, meta)) in instrs.iter().zip(&metas).enumerate() {
+            println!(
+                "  {index:>6}  {:>#10x}  {:>#6x}  {:>5}  {instr:?}",
+                meta.source_pc(),
+                meta.opcode(),
+                meta.stack_height()
+            );
+        }
+    }
+}
@@ -1,45 +1,77 @@
 use crate::{
-    args::Args,
+    args::{Args, Command, ModuleArgs},
     display::{DisplayExportedFuncs, DisplayFuncType, DisplaySequence, DisplayValue},
 };
 use anyhow::{anyhow, bail, Error, Result};
 use clap::Parser;
 use context::Context;
-use std::{path::Path, process};
-use wasmi::{Func, FuncType, Value};
+use std::{fs, path::Path, process};
+use wasmi::{Func, FuncType, Profiler, Value};
 
 mod args;
 mod context;
+mod debugger;
 mod display;
+mod inspect;
+mod repl;
+mod trace;
 mod utils;
+mod wast;
 
 #[cfg(test)]
 mod tests;
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    let wasm_file = args.wasm_file();
-    let wasi_ctx = args.wasi_context()?;
-    let mut ctx = Context::new(wasm_file, wasi_ctx, args.fuel())?;
-    let (func_name, func) = get_invoked_func(&args, &ctx)?;
+    match args.command() {
+        Some(Command::Trace(trace_args)) => trace::run(trace_args),
+        Some(Command::Inspect(inspect_args)) => inspect::run(inspect_args),
+        Some(Command::Repl(repl_args)) => repl::run(repl_args),
+        Some(Command::Wast(wast_args)) => wast::run(wast_args),
+        None => run(&args),
+    }
+}
+
+/// Executes the invoked function of the Wasm module given to the CLI app.
+///
+/// # Errors
+///
+/// If the Wasm module cannot be instantiated or the invoked function fails to execute.
+fn run(args: &Args) -> Result<()> {
+    let module_args = args.module();
+    let wasm_file = module_args.wasm_file();
+    let mut ctx = Context::new(module_args)?;
+    let (func_name, func) = get_invoked_func(module_args, &ctx)?;
     let ty = func.ty(ctx.store());
-    let func_args = utils::decode_func_args(&ty, args.func_args())?;
+    let func_args = utils::decode_func_args(&ty, module_args.func_args())?;
     let mut func_results = utils::prepare_func_results(&ty);
     typecheck_args(&func_name, &ty, &func_args)?;
 
-    print_execution_start(args.wasm_file(), &func_name, &func_args);
-    if args.invoked().is_some() && ty.params().len() != args.func_args().len() {
+    print_execution_start(wasm_file, &func_name, &func_args);
+    if args.debug() {
+        debugger::install(ctx.store_mut());
+    }
+    if args.profile().is_some() {
+        let profiler = match args.profile_interval() {
+            Some(interval) => Profiler::sampling(interval),
+            None => Profiler::new(),
+        };
+        ctx.store_mut().set_profiler(profiler);
+    }
+    if module_args.invoked().is_some() && ty.params().len() != module_args.func_args().len() {
         bail!(
             "invalid amount of arguments given to function {}. expected {} but received {}",
             DisplayFuncType::new(&func_name, &ty),
             ty.params().len(),
-            args.func_args().len()
+            module_args.func_args().len()
         )
     }
 
-    match func.call(ctx.store_mut(), &func_args, &mut func_results) {
+    let result = func.call(ctx.store_mut(), &func_args, &mut func_results);
+    write_profile(args, &ctx)?;
+    match result {
         Ok(()) => {
-            print_remaining_fuel(&args, &ctx);
+            print_remaining_fuel(module_args, &ctx);
             print_pretty_results(&func_results);
             Ok(())
         }
@@ -49,10 +81,19 @@ fn main() -> Result<()> {
                     // We received an exit code from the WASI program,
                     // therefore we exit with the same exit code after
                     // pretty printing the results.
-                    print_remaining_fuel(&args, &ctx);
+                    print_remaining_fuel(module_args, &ctx);
                     print_pretty_results(&func_results);
                     process::exit(exit_code)
                 }
+                if let Some(backtrace) = trap.backtrace() {
+                    let backtrace = match ctx.module().debug_info() {
+                        Some(debug_info) => debug_info.display_backtrace(backtrace).to_string(),
+                        None => backtrace.to_string(),
+                    };
+                    bail!(
+                        "failed during execution of {func_name}: {error}\nwasm backtrace:\n{backtrace}"
+                    )
+                }
             }
             bail!("failed during execution of {func_name}: {error}")
         }
@@ -60,7 +101,7 @@ fn main() -> Result<()> {
 }
 
 /// Prints the remaining fuel so far if fuel metering was enabled.
-fn print_remaining_fuel(args: &Args, ctx: &Context) {
+fn print_remaining_fuel(args: &ModuleArgs, ctx: &Context) {
     if let Some(total_fuel) = args.fuel() {
         let consumed = ctx.store().fuel_consumed().unwrap_or_else(|| {
             panic!("fuel metering is enabled but could not query consumed fuel")
@@ -70,6 +111,42 @@ fn print_remaining_fuel(args: &Args, ctx: &Context) {
     }
 }
 
+/// Writes the folded call stacks of the profiled execution if profiling was enabled.
+///
+/// Also prints a per-function summary and a per-opcode histogram of the profile.
+///
+/// # Errors
+///
+/// If the folded call stacks cannot be written to the `--profile` file.
+fn write_profile(args: &Args, ctx: &Context) -> Result<(), Error> {
+    let (Some(path), Some(profiler)) = (args.profile(), ctx.store().profiler()) else {
+        return Ok(());
+    };
+    fs::write(path, profiler.folded(args.profile_weight()))
+        .map_err(|error| anyhow!("failed to write profile to {path:?}: {error}"))?;
+    println!("profile written to {path:?}");
+    println!(
+        "{:>12} {:>12} {:>12} {:>12} {:>12}  function",
+        "calls", "self instrs", "total instrs", "self fuel", "total fuel"
+    );
+    for func in profiler.funcs() {
+        println!(
+            "{:>12} {:>12} {:>12} {:>12} {:>12}  {}",
+            func.calls(),
+            func.self_instrs(),
+            func.total_instrs(),
+            func.self_fuel(),
+            func.total_fuel(),
+            func.name(),
+        );
+    }
+    println!("{:>12}  opcode", "count");
+    for (opcode, count) in profiler.opcode_histogram() {
+        println!("{count:>12}  {opcode}");
+    }
+    Ok(())
+}
+
 /// Performs minor typecheck on the function signature.
 ///
 /// # Note
@@ -97,7 +174,7 @@ fn typecheck_args(func_name: &str, func_ty: &FuncType, args: &[Value]) -> Result
 ///
 /// - If the function given via `--invoke` could not be found in the Wasm module.
 /// - If `--invoke` was not given and no WASI entry points were exported.
-fn get_invoked_func(args: &Args, ctx: &Context) -> Result<(String, Func), Error> {
+fn get_invoked_func(args: &ModuleArgs, ctx: &Context) -> Result<(String, Func), Error> {
     match args.invoked() {
         Some(func_name) => {
             let func = ctx
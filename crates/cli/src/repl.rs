@@ -0,0 +1,270 @@
+//! The `repl` subcommand keeping a Wasm module instance alive across commands.
+
+use crate::{
+    args::{ModuleArgs, ReplArgs},
+    context::Context,
+    display::{DisplayExternType, DisplayValue},
+    print_pretty_results,
+    typecheck_args,
+    utils::{self, parse_u32, print_memory},
+};
+use anyhow::{anyhow, bail, Error, Result};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+};
+use wasmi::Value;
+
+/// The prompt printed before every command.
+const PROMPT: &str = "wasmi> ";
+
+/// The help text listing all commands of the REPL.
+const HELP: &str = "\
+commands:
+  i, invoke <FUNC> [ARGS]*       invoke the exported function FUNC with ARGS
+  exports                        list the exports of the Wasm module
+  g, global <NAME> [VALUE]       print or set the exported global variable NAME
+  m, memory <NAME> <ADDR> [LEN]  print LEN bytes of the exported memory NAME starting at ADDR
+  w, write <NAME> <ADDR> <HEX>   write the hex encoded bytes to the exported memory NAME at ADDR
+  fuel [N]                       print the remaining fuel or add N units of fuel
+  reload                         re-read and re-instantiate the Wasm module
+  q, quit                        end the session
+  h, help                        print this help text";
+
+/// Whether the session continues after a command.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Flow {
+    /// Continue with the next command.
+    Continue,
+    /// End the session.
+    Quit,
+}
+
+/// Runs the REPL session on the Wasm module given to the `repl` subcommand.
+///
+/// Commands are read from the `--script` file if given and from `stdin` otherwise.
+///
+/// # Errors
+///
+/// - If the Wasm module cannot be instantiated.
+/// - If the function given via `--invoke` fails to execute.
+/// - If a command of the `--script` file fails.
+pub fn run(args: &ReplArgs) -> Result<()> {
+    let mut session = Session::new(args.module())?;
+    if let Some(func_name) = args.module().invoked() {
+        session.invoke(func_name, args.module().func_args())?;
+    }
+    match args.script() {
+        Some(path) => {
+            let script = fs::read_to_string(path)
+                .map_err(|error| anyhow!("failed to read script {path:?}: {error}"))?;
+            for (n, line) in script.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                println!("{PROMPT}{line}");
+                let flow = session
+                    .execute(line)
+                    .map_err(|error| anyhow!("{path:?}:{}: {error}", n + 1))?;
+                if flow == Flow::Quit {
+                    break;
+                }
+            }
+        }
+        None => {
+            println!("type `help` for a list of commands");
+            let stdin = io::stdin();
+            let mut line = String::new();
+            loop {
+                print!("{PROMPT}");
+                let _ = io::stdout().flush();
+                line.clear();
+                match stdin.lock().read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {}
+                }
+                match session.execute(line.trim()) {
+                    Ok(Flow::Continue) => {}
+                    Ok(Flow::Quit) => break,
+                    Err(error) => println!("error: {error}"),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A REPL session operating on a single Wasm module instance.
+struct Session<'a> {
+    /// The Wasm module to (re-)instantiate and its environment.
+    args: &'a ModuleArgs,
+    /// The current Wasm module instance.
+    ctx: Context,
+    /// The total amount of fuel added to the current instance if fuel metering is enabled.
+    fuel: Option<u64>,
+}
+
+impl<'a> Session<'a> {
+    /// Creates a new [`Session`] by instantiating the Wasm module.
+    ///
+    /// # Errors
+    ///
+    /// If the Wasm module cannot be instantiated.
+    fn new(args: &'a ModuleArgs) -> Result<Self, Error> {
+        let ctx = Context::new(args)?;
+        Ok(Self {
+            args,
+            ctx,
+            fuel: args.fuel(),
+        })
+    }
+
+    /// Executes a single command `line`.
+    ///
+    /// Empty lines are ignored.
+    ///
+    /// # Errors
+    ///
+    /// If the command is unknown, malformed or fails.
+    fn execute(&mut self, line: &str) -> Result<Flow, Error> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Flow::Continue);
+        };
+        let operands = words.collect::<Vec<_>>();
+        match (command, &operands[..]) {
+            ("i" | "invoke", [func_name, args @ ..]) => {
+                let args = args.iter().map(ToString::to_string).collect::<Vec<_>>();
+                self.invoke(func_name, &args)?
+            }
+            ("exports", []) => {
+                for export in self.ctx.module().exports() {
+                    println!(
+                        "{}: {}",
+                        export.name(),
+                        DisplayExternType::from(export.ty())
+                    );
+                }
+            }
+            ("g" | "global", [name]) => {
+                let value = self.ctx.get_global(name)?.get(self.ctx.store());
+                print_value(&value);
+            }
+            ("g" | "global", [name, value]) => {
+                let global = self.ctx.get_global(name)?;
+                let ty = global.ty(self.ctx.store());
+                let value = utils::decode_value(&ty.content(), value)?;
+                global
+                    .set(self.ctx.store_mut(), value)
+                    .map_err(|error| anyhow!("failed to set global {name:?}: {error}"))?;
+            }
+            ("m" | "memory", [name, address, rest @ ..]) if rest.len() <= 1 => {
+                let memory = self.ctx.get_memory(name)?;
+                let address = parse_u32(address);
+                let len = rest.first().map_or(Some(16), |len| parse_u32(len));
+                let (Some(address), Some(len)) = (address, len) else {
+                    bail!("usage: memory <NAME> <ADDR> [LEN]")
+                };
+                let store = self.ctx.store();
+                let size = memory.data_size(store);
+                let start = (address as usize).min(size);
+                let end = (address as usize).saturating_add(len as usize).min(size);
+                let mut bytes = vec![0x00; end - start];
+                memory
+                    .read(store, start, &mut bytes)
+                    .map_err(|error| anyhow!("failed to read memory {name:?}: {error}"))?;
+                print_memory(&bytes, start);
+            }
+            ("w" | "write", [name, address, bytes]) => {
+                let memory = self.ctx.get_memory(name)?;
+                let (Some(address), Some(bytes)) = (parse_u32(address), decode_hex(bytes)) else {
+                    bail!("usage: write <NAME> <ADDR> <HEX>")
+                };
+                memory
+                    .write(self.ctx.store_mut(), address as usize, &bytes)
+                    .map_err(|error| anyhow!("failed to write to memory {name:?}: {error}"))?;
+            }
+            ("fuel", []) => {
+                let remaining = self.fuel_remaining()?;
+                println!("fuel remaining: {remaining}");
+            }
+            ("fuel", [delta]) => {
+                let delta = delta
+                    .parse::<u64>()
+                    .map_err(|error| anyhow!("invalid amount of fuel {delta:?}: {error}"))?;
+                self.fuel_remaining()?;
+                self.ctx
+                    .store_mut()
+                    .add_fuel(delta)
+                    .map_err(|error| anyhow!("failed to add fuel: {error}"))?;
+                self.fuel = self.fuel.map(|fuel| fuel + delta);
+            }
+            ("reload", []) => {
+                *self = Self::new(self.args)?;
+                println!("reloaded {:?}", self.args.wasm_file());
+            }
+            ("q" | "quit", []) => return Ok(Flow::Quit),
+            ("h" | "help", []) => println!("{HELP}"),
+            _ => bail!("unknown command `{line}`, type `help` for a list of commands"),
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Invokes the exported function `func_name` with `args` and prints its results.
+    ///
+    /// Also prints the fuel consumed by the invocation if fuel metering is enabled.
+    ///
+    /// # Errors
+    ///
+    /// - If the function cannot be found or the `args` do not match its type.
+    /// - If the function fails to execute.
+    fn invoke(&mut self, func_name: &str, args: &[String]) -> Result<(), Error> {
+        let func = self.ctx.get_func(func_name)?;
+        let ty = func.ty(self.ctx.store());
+        let func_args = utils::decode_func_args(&ty, args)?;
+        typecheck_args(func_name, &ty, &func_args)?;
+        let mut func_results = utils::prepare_func_results(&ty);
+        let fuel_before = self.ctx.store().fuel_consumed();
+        let result = func.call(self.ctx.store_mut(), &func_args, &mut func_results);
+        if let (Some(before), Some(after)) = (fuel_before, self.ctx.store().fuel_consumed()) {
+            println!("fuel consumed: {}", after - before);
+        }
+        result.map_err(|error| anyhow!("failed during execution of {func_name}: {error}"))?;
+        print_pretty_results(&func_results);
+        Ok(())
+    }
+
+    /// Returns the remaining fuel of the current instance.
+    ///
+    /// # Errors
+    ///
+    /// If fuel metering is disabled.
+    fn fuel_remaining(&self) -> Result<u64, Error> {
+        match (self.fuel, self.ctx.store().fuel_consumed()) {
+            (Some(total), Some(consumed)) => Ok(total - consumed),
+            _ => bail!("fuel metering is disabled, enable it via `--fuel`"),
+        }
+    }
+}
+
+/// Prints the `value` of a global variable.
+fn print_value(value: &Value) {
+    match value {
+        Value::FuncRef(_) => println!("funcref"),
+        Value::ExternRef(_) => println!("externref"),
+        value => println!("{}", DisplayValue::from(value)),
+    }
+}
+
+/// Decodes the hex encoded `bytes` with an optional `0x` prefix.
+fn decode_hex(bytes: &str) -> Option<Vec<u8>> {
+    let bytes = bytes.strip_prefix("0x").unwrap_or(bytes);
+    if bytes.len() % 2 != 0 {
+        return None;
+    }
+    (0..bytes.len())
+        .step_by(2)
+        .map(|n| u8::from_str_radix(bytes.get(n..n + 2)?, 16).ok())
+        .collect()
+}
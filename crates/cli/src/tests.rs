@@ -84,3 +84,41 @@ fn display_4in_4out() {
         "fn(i32, i64, f32, f64) -> (i32, i64, f32, f64)",
     );
 }
+
+#[test]
+fn args_are_consistent() {
+    use clap::CommandFactory;
+    <Args as CommandFactory>::command().debug_assert();
+}
+
+#[test]
+fn parse_step_ranges() {
+    use crate::args::parse_step_range;
+    assert_eq!(parse_step_range("2..5").unwrap(), 2..5);
+    assert_eq!(parse_step_range("2..").unwrap(), 2..usize::MAX);
+    assert_eq!(parse_step_range("..5").unwrap(), 0..5);
+    assert!(parse_step_range("5").is_err());
+    assert!(parse_step_range("5..2").is_err());
+    assert!(parse_step_range("a..b").is_err());
+}
+
+#[test]
+fn parse_key_value_flags() {
+    let args = Args::try_parse_from([
+        "wasmi_cli",
+        "--env",
+        "KEY=VALUE",
+        "--preload",
+        "lib=lib.wasm",
+        "--preload",
+        "empty=",
+        "app.wasm",
+    ])
+    .unwrap();
+    let preloads: Vec<_> = args.module().preloads().collect();
+    assert_eq!(
+        preloads,
+        [("lib", Path::new("lib.wasm")), ("empty", Path::new(""))]
+    );
+    assert!(Args::try_parse_from(["wasmi_cli", "--env", "KEY", "app.wasm"]).is_err());
+}
@@ -0,0 +1,208 @@
+//! The `trace` subcommand writing the execution trace of an invoked function.
+
+use crate::{
+    args::{TraceArgs, TraceFormat},
+    context::Context,
+    get_invoked_func,
+    print_execution_start,
+    print_pretty_results,
+    typecheck_args,
+    utils,
+};
+use anyhow::{anyhow, bail, Error, Result};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    fs,
+    ops::Range,
+    process,
+};
+use wasmi::{OpCodeState, Tracer};
+
+/// Executes the invoked function and writes its execution trace.
+///
+/// The trace is written even if the execution fails so that failures can be inspected.
+///
+/// # Errors
+///
+/// - If the Wasm module cannot be instantiated or the invoked function cannot be found.
+/// - If the execution trace cannot be written.
+/// - If the invoked function fails to execute.
+pub fn run(args: &TraceArgs) -> Result<()> {
+    let module_args = args.module();
+    let wasm_file = module_args.wasm_file();
+    let mut ctx = Context::new(module_args)?;
+    let (func_name, func) = get_invoked_func(module_args, &ctx)?;
+    let ty = func.ty(ctx.store());
+    let func_args = utils::decode_func_args(&ty, module_args.func_args())?;
+    let mut func_results = utils::prepare_func_results(&ty);
+    typecheck_args(&func_name, &ty, &func_args)?;
+    let filter = TraceFilter {
+        steps: args.steps(),
+        funcs: resolve_funcs(args.funcs(), &ctx)?,
+    };
+
+    print_execution_start(wasm_file, &func_name, &func_args);
+    let result = func.call(ctx.store_mut(), &func_args, &mut func_results);
+    let tracer = &ctx.store().tracer;
+    let written = write_trace(args, tracer, &filter)?;
+    print_summary(tracer, written);
+    if let Err(error) = result {
+        if let wasmi::Error::Trap(trap) = &error {
+            if let Some(exit_code) = trap.i32_exit_status() {
+                // Just like WASI programs executed without tracing
+                // we exit with the exit code of the WASI program.
+                process::exit(exit_code)
+            }
+        }
+        bail!("failed during execution of {func_name}: {error}")
+    }
+    print_pretty_results(&func_results);
+    Ok(())
+}
+
+/// Resolves the function allow-list given via `--func` to function indices.
+///
+/// Returns `None` if all functions are allowed.
+///
+/// # Errors
+///
+/// If a function is neither a function index nor a name in the `name` section of the Wasm module.
+fn resolve_funcs(funcs: &[String], ctx: &Context) -> Result<Option<BTreeSet<u32>>, Error> {
+    if funcs.is_empty() {
+        return Ok(None);
+    }
+    let names = ctx.module().names();
+    funcs
+        .iter()
+        .map(|func| {
+            if let Ok(index) = func.parse::<u32>() {
+                return Ok(index);
+            }
+            names
+                .funcs()
+                .find_map(|(index, name)| (name == func).then_some(index))
+                .ok_or_else(|| {
+                    anyhow!("failed to find function named {func:?} in the `name` section")
+                })
+        })
+        .collect::<Result<BTreeSet<_>>>()
+        .map(Some)
+}
+
+/// Decides which steps of the execution trace are written.
+struct TraceFilter {
+    /// The range of written steps if any.
+    steps: Option<Range<usize>>,
+    /// The function indices whose steps are written if any.
+    funcs: Option<BTreeSet<u32>>,
+}
+
+impl TraceFilter {
+    /// Returns `true` if no steps are filtered out.
+    fn is_empty(&self) -> bool {
+        self.steps.is_none() && self.funcs.is_none()
+    }
+
+    /// Returns `true` if the `step` with its traced `log` is written.
+    fn allows(&self, step: usize, log: &OpCodeState) -> bool {
+        if let Some(steps) = &self.steps {
+            if !steps.contains(&step) {
+                return false;
+            }
+        }
+        if let Some(funcs) = &self.funcs {
+            return matches!(log.func_index, Some(func_index) if funcs.contains(&func_index));
+        }
+        true
+    }
+}
+
+/// The traced `log` of a step annotated with its `step` index.
+#[derive(Serialize)]
+struct Step<'a> {
+    #[serde(flatten)]
+    log: &'a OpCodeState,
+    step: usize,
+}
+
+/// Writes the steps traced by the `tracer` allowed by the `filter` in the requested format.
+///
+/// Returns the number of written steps.
+///
+/// # Note
+///
+/// Steps of filtered JSON traces are annotated with their `step` index.
+///
+/// # Errors
+///
+/// If the trace cannot be written to the `--output` file.
+fn write_trace(args: &TraceArgs, tracer: &Tracer, filter: &TraceFilter) -> Result<usize, Error> {
+    let steps = tracer
+        .logs()
+        .iter()
+        .enumerate()
+        .filter(|(step, log)| filter.allows(*step, log))
+        .map(|(step, log)| Step { log, step })
+        .collect::<Vec<_>>();
+    let written = steps.len();
+    let output = match args.format() {
+        TraceFormat::Json if filter.is_empty() => tracer.to_json(),
+        TraceFormat::Json => tracer.to_json_with_logs(&steps),
+        TraceFormat::Jsonl => steps
+            .iter()
+            .map(|step| Ok(format!("{}\n", serde_json::to_string(step)?)))
+            .collect::<Result<String>>()?,
+        TraceFormat::Text => steps
+            .iter()
+            .map(|step| format!("{}\n", display_step(step)))
+            .collect(),
+    };
+    let path = args.output();
+    fs::write(path, output)
+        .map_err(|error| anyhow!("failed to write trace to {path:?}: {error}"))?;
+    println!("trace written to {path:?}");
+    Ok(written)
+}
+
+/// Returns a human readable line describing the traced log of the `step`.
+fn display_step(step: &Step) -> String {
+    let Step { log, step } = step;
+    let mut line = format!("{step:>8} {:>#10x} {}", log.source_pc, log.opcode.name());
+    for param in log.opcode.params().into_iter().flatten() {
+        let _ = write!(line, " {param}");
+    }
+    for change in &log.memory_changes {
+        let _ = write!(line, " mem[{}..+{}]=", change.offset, change.len);
+        for byte in &change.data {
+            let _ = write!(line, "{byte:02x}");
+        }
+    }
+    line
+}
+
+/// Prints the number of executed instructions, memory writes and called functions.
+///
+/// The numbers are taken from the steps and function calls traced by the `tracer`.
+fn print_summary(tracer: &Tracer, written: usize) {
+    let logs = tracer.logs();
+    let (memory_writes, memory_bytes) = logs
+        .iter()
+        .flat_map(|log| &log.memory_changes)
+        .fold((0, 0), |(writes, bytes), change| {
+            (writes + 1, bytes + u64::from(change.len))
+        });
+    println!("instructions: {} ({written} written)", logs.len());
+    println!("memory writes: {memory_writes} ({memory_bytes} bytes)");
+    let mut calls = BTreeMap::<&str, u64>::new();
+    for meta in tracer.fn_metas() {
+        *calls.entry(meta.fn_name.as_str()).or_default() += 1;
+    }
+    println!("functions called: {}", calls.len());
+    println!("{:>12}  function", "calls");
+    for (name, count) in calls {
+        let name = if name.is_empty() { "<unnamed>" } else { name };
+        println!("{count:>12}  {name}");
+    }
+}
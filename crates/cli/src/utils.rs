@@ -56,37 +56,61 @@ pub fn decode_func_args(ty: &FuncType, args: &[String]) -> Result<Box<[Value]>,
         .zip(args)
         .enumerate()
         .map(|(n, (param_type, arg))| {
-            macro_rules! make_err {
-                () => {
-                    |_| {
-                        anyhow!(
-                            "failed to parse function argument \
-                            {arg} at index {n} as {}",
-                            DisplayValueType::from(param_type)
-                        )
-                    }
-                };
-            }
-            match param_type {
-                ValueType::I32 => arg.parse::<i32>().map(Value::from).map_err(make_err!()),
-                ValueType::I64 => arg.parse::<i64>().map(Value::from).map_err(make_err!()),
-                ValueType::F32 => arg
-                    .parse::<f32>()
-                    .map(F32::from)
-                    .map(Value::from)
-                    .map_err(make_err!()),
-                ValueType::F64 => arg
-                    .parse::<f64>()
-                    .map(F64::from)
-                    .map(Value::from)
-                    .map_err(make_err!()),
-                ValueType::FuncRef => {
-                    bail!("the wasmi CLI cannot take arguments of type funcref")
-                }
-                ValueType::ExternRef => {
-                    bail!("the wasmi CLI cannot take arguments of type externref")
-                }
-            }
+            decode_value(param_type, arg)
+                .map_err(|error| anyhow!("invalid function argument at index {n}: {error}"))
         })
         .collect::<Result<Box<[_]>, _>>()
 }
+
+/// Decode the given `arg` as [`Value`] of type `ty`.
+///
+/// # Errors
+///
+/// - If `arg` cannot be parsed as a value of type `ty`.
+/// - If `ty` is the unsupported [`ExternRef`] or [`FuncRef`] type.
+///
+/// [`FuncRef`]: wasmi::FuncRef
+/// [`ExternRef`]: wasmi::ExternRef
+pub fn decode_value(ty: &ValueType, arg: &str) -> Result<Value, Error> {
+    let make_err = || anyhow!("failed to parse {arg} as {}", DisplayValueType::from(ty));
+    match ty {
+        ValueType::I32 => arg.parse::<i32>().map(Value::from).map_err(|_| make_err()),
+        ValueType::I64 => arg.parse::<i64>().map(Value::from).map_err(|_| make_err()),
+        ValueType::F32 => arg
+            .parse::<f32>()
+            .map(F32::from)
+            .map(Value::from)
+            .map_err(|_| make_err()),
+        ValueType::F64 => arg
+            .parse::<f64>()
+            .map(F64::from)
+            .map(Value::from)
+            .map_err(|_| make_err()),
+        ValueType::FuncRef => {
+            bail!("the wasmi CLI cannot take arguments of type funcref")
+        }
+        ValueType::ExternRef => {
+            bail!("the wasmi CLI cannot take arguments of type externref")
+        }
+    }
+}
+
+/// Parses a decimal or `0x` prefixed hexadecimal `u32`.
+pub fn parse_u32(input: &str) -> Option<u32> {
+    match input.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => input.parse().ok(),
+    }
+}
+
+/// Prints the `bytes` of a linear memory starting at `address` as hex dump.
+pub fn print_memory(bytes: &[u8], address: usize) {
+    for (n, chunk) in bytes.chunks(16).enumerate() {
+        let bytes = chunk
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("{:#010x}: {bytes}", address + n * 16);
+    }
+}
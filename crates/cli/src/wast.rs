@@ -0,0 +1,89 @@
+//! The `wast` subcommand running `.wast` script files and reporting every directive.
+
+use crate::args::WastArgs;
+use anyhow::{bail, Result};
+use wasmi::Config;
+use wasmi_wast::{DirectiveReport, Outcome, WastReport};
+
+/// Runs all `.wast` script files given to the `wast` subcommand and prints their reports.
+///
+/// # Errors
+///
+/// - If a script cannot be read or parsed.
+/// - If any directive of the scripts fails.
+/// - If `--compare-tracing` is given and disabling deterministic tracing
+///   changes the outcome of any directive.
+pub fn run(args: &WastArgs) -> Result<()> {
+    let mut failed = 0;
+    let mut mismatches = 0;
+    for path in args.wast_files() {
+        let report = wasmi_wast::run_wast_file(path, &make_config(true))?;
+        if args.json() {
+            println!("{}", report.to_json());
+        } else {
+            print_report(&report);
+        }
+        failed += report.len_failed();
+        if args.compare_tracing() {
+            let untraced = wasmi_wast::run_wast_file(path, &make_config(false))?;
+            for (traced, untraced) in report.diff(&untraced) {
+                eprintln!(
+                    "{}:{}:{} {}: deterministic tracing changed the outcome from `{}` to `{}`",
+                    report.path,
+                    traced.line,
+                    traced.col,
+                    traced.kind,
+                    display_outcome(&untraced.outcome),
+                    display_outcome(&traced.outcome),
+                );
+                mismatches += 1;
+            }
+        }
+    }
+    if mismatches != 0 {
+        bail!("deterministic tracing changed the outcome of {mismatches} directive(s)")
+    }
+    if failed != 0 {
+        bail!("{failed} directive(s) failed")
+    }
+    Ok(())
+}
+
+/// Creates the [`Config`] with all Wasm proposals supported by `wasmi` enabled.
+///
+/// Disabling `deterministic_tracing` also enables superinstructions
+/// which are never used in deterministic tracing mode.
+fn make_config(deterministic_tracing: bool) -> Config {
+    let mut config = Config::default();
+    config
+        .wasm_tail_call(true)
+        .wasm_extended_const(true)
+        .deterministic_tracing(deterministic_tracing)
+        .superinstructions(!deterministic_tracing);
+    config
+}
+
+/// Prints one line per directive of the `report` followed by its summary.
+fn print_report(report: &WastReport) {
+    for directive in &report.directives {
+        print_directive(&report.path, directive);
+    }
+    println!("{}", report.summary());
+}
+
+/// Prints the outcome and the number of traced instructions of the `directive`.
+fn print_directive(path: &str, directive: &DirectiveReport) {
+    match directive.traced_steps {
+        0 => println!("{path}:{directive}"),
+        steps => println!("{path}:{directive} ({steps} traced steps)"),
+    }
+}
+
+/// Returns the short name of the `outcome`.
+fn display_outcome(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Passed => "passed",
+        Outcome::Failed(_) => "failed",
+        Outcome::Skipped(_) => "skipped",
+    }
+}
@@ -30,6 +30,258 @@ fn test_proc_exit() {
     assert.failure().code(1);
 }
 
+#[test]
+fn test_trace() {
+    let output = std::env::temp_dir().join("wasmi_cli_test_trace.json");
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("trace")
+        .arg(get_bin_path("trace"))
+        .args(["--invoke", "add", "--output"])
+        .arg(&output)
+        .args(["1", "2"])
+        .assert()
+        .success();
+    let stdout = &assert.get_output().stdout;
+    assert!(contains_slice(stdout, b"memory writes: 1 (4 bytes)"));
+    assert!(contains_slice(stdout, b"functions called: 2"));
+    let trace = std::fs::read_to_string(&output).unwrap();
+    assert!(trace.starts_with('{'));
+    assert!(trace.contains(r#""fn_name":"store""#));
+}
+
+#[test]
+fn test_trace_filters() {
+    let output = std::env::temp_dir().join("wasmi_cli_test_trace_filters.jsonl");
+    let mut cmd = get_cmd();
+    cmd.arg("trace")
+        .arg(get_bin_path("trace"))
+        .args(["--invoke", "add", "--format", "jsonl", "--func", "store"])
+        .args(["--steps", "..5", "--output"])
+        .arg(&output)
+        .args(["1", "2"])
+        .assert()
+        .success();
+    let trace = std::fs::read_to_string(&output).unwrap();
+    let steps = trace
+        .lines()
+        .map(|line| line.split(r#""step":"#).nth(1).unwrap())
+        .collect::<Vec<_>>();
+    // Steps 3 and 4 are the first two steps executed by `$store`.
+    assert_eq!(steps, ["3}", "4}"]);
+}
+
+#[test]
+fn test_trace_func_filter_after_start() {
+    let output = std::env::temp_dir().join("wasmi_cli_test_trace_func_filter_after_start.jsonl");
+    let mut cmd = get_cmd();
+    cmd.arg("trace")
+        .arg(get_bin_path("trace_start"))
+        .args(["--invoke", "add", "--format", "jsonl", "--func", "store"])
+        .arg("--output")
+        .arg(&output)
+        .args(["1", "2"])
+        .assert()
+        .success();
+    // The steps of the start function `$init` are traced before but not attributed to `$store`.
+    assert_eq!(
+        traced_names(&output),
+        ["local_get", "local_get", "i32_store", "return"]
+    );
+    cmd = get_cmd();
+    cmd.arg("trace")
+        .arg(get_bin_path("trace_start"))
+        .args(["--invoke", "add", "--format", "jsonl", "--func", "init"])
+        .arg("--output")
+        .arg(&output)
+        .args(["1", "2"])
+        .assert()
+        .success();
+    assert_eq!(traced_names(&output), ["const", "global_set", "return"]);
+}
+
+/// Returns the instruction names of the steps of the JSON lines trace at `output`.
+fn traced_names(output: &std::path::Path) -> Vec<String> {
+    std::fs::read_to_string(output)
+        .unwrap()
+        .lines()
+        .map(|line| line.split(r#""name":""#).nth(1).unwrap())
+        .map(|name| name.split('"').next().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_inspect() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("inspect")
+        .arg(get_bin_path("inspect"))
+        .assert()
+        .success();
+    let stdout = &assert.get_output().stdout;
+    assert!(contains_slice(stdout, b"env::log: fn(i32)"));
+    assert!(contains_slice(stdout, b"bump: fn() -> i32"));
+    assert!(contains_slice(stdout, b"0: memory 1..2 pages"));
+    assert!(contains_slice(stdout, b"0: table funcref 1.."));
+    assert!(contains_slice(stdout, b"0: global mut i32 = 42"));
+    assert!(contains_slice(
+        stdout,
+        b"0: active, memory 0, offset 16, 5 bytes"
+    ));
+    assert!(contains_slice(stdout, b"1: passive, 7 bytes"));
+    assert!(!contains_slice(stdout, b"max stack height"));
+}
+
+#[test]
+fn test_inspect_disasm() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("inspect")
+        .arg(get_bin_path("inspect"))
+        .arg("--disasm")
+        .assert()
+        .success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    assert!(stdout.contains("func 1: fn bump() -> i32"));
+    assert!(stdout.contains("params: 0, locals: 0, max stack height: 2"));
+    // The `call $log` is printed with the position and opcode of the Wasm operator.
+    let call = stdout
+        .lines()
+        .find(|line| line.ends_with("Call(FuncIdx(0))"))
+        .unwrap();
+    assert!(call.contains("0x10"));
+}
+
+#[test]
+fn test_repl_script() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("repl")
+        .arg(get_bin_path("repl"))
+        .args(["--fuel", "1000", "--script", "tests/wats/repl.script"])
+        .assert()
+        .success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    let results = stdout
+        .lines()
+        .filter(|line| !line.starts_with("wasmi> "))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        results,
+        [
+            "fuel consumed: 10",
+            "5",
+            "fuel consumed: 10",
+            "7",
+            "7",
+            "0x00000000: 07 00 00 00",
+            "fuel consumed: 4",
+            "42",
+            "fuel consumed: 10",
+            "101",
+            "fuel remaining: 966",
+            "reloaded \"tests/wats/repl.wat\"",
+            "0",
+        ]
+    );
+}
+
+#[test]
+fn test_repl_stdin() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("repl")
+        .arg(get_bin_path("repl"))
+        .write_stdin("invoke missing\ninvoke bump 3\nquit\ninvoke bump 3\n")
+        .assert()
+        .success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    // Failing commands do not end interactive sessions but `quit` does.
+    assert!(stdout.contains("error: failed to find function named \"missing\""));
+    assert!(stdout.contains("wasmi> 3\n"));
+    assert!(!stdout.contains("wasmi> 6\n"));
+}
+
+#[test]
+fn test_preload_with_log_stubs() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--preload")
+        .arg(format!("lib={}", get_bin_path("preload_lib").display()))
+        .args(["--stub-imports", "log"])
+        .arg(get_bin_path("preload"))
+        .args(["--invoke", "run", "3"])
+        .assert()
+        .success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    assert!(stdout.contains("stubbed import fn env::_evm_return(i32, i32) -> i64\n"));
+    // The preloaded `lib::double` is called instead of a stub.
+    assert!(!stdout.contains("lib::double"));
+    assert!(stdout.contains("called env::_evm_return(6, 7)\n"));
+    assert!(stdout.ends_with("\n1\n"));
+}
+
+#[test]
+fn test_trap_stubs() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("--preload")
+        .arg(format!("lib={}", get_bin_path("preload_lib").display()))
+        .args(["--stub-imports", "trap"])
+        .arg(get_bin_path("preload"))
+        .args(["--invoke", "run", "3"])
+        .assert()
+        .failure();
+    let stderr = &assert.get_output().stderr;
+    assert!(contains_slice(stderr, b"unknown import `env::_evm_return`"));
+    // Without stubs the Wasm module cannot be instantiated at all.
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg(get_bin_path("preload"))
+        .args(["--invoke", "run", "3"])
+        .assert()
+        .failure();
+    let stderr = &assert.get_output().stderr;
+    assert!(contains_slice(stderr, b"failed to instantiate"));
+}
+
+#[test]
+fn test_wast() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("wast")
+        .arg(get_wast_path("assertions"))
+        .arg("--compare-tracing")
+        .assert()
+        .success();
+    let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+    assert!(stdout.contains("assertions.wast:26:2 assert_return: ok ("));
+    assert!(stdout.contains("assertions.wast:29:2 assert_trap: ok ("));
+    assert!(stdout.contains("assertions.wast:30:2 assert_invalid: ok"));
+    assert!(stdout.contains("assertions.wast:31:9 module quote: skipped"));
+    assert!(stdout.contains("assertions.wast: 8 passed, 0 failed, 1 skipped"));
+}
+
+#[test]
+fn test_wast_json() {
+    let mut cmd = get_cmd();
+    let assert = cmd
+        .arg("wast")
+        .arg(get_wast_path("failing"))
+        .arg("--json")
+        .assert()
+        .failure();
+    let output = assert.get_output();
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["passed"], 2);
+    assert_eq!(report["failed"], 1);
+    assert_eq!(report["directives"][2]["kind"], "assert_return");
+    assert_eq!(report["directives"][2]["line"], 7);
+    assert_eq!(report["directives"][2]["outcome"], "failed");
+    assert!(report["directives"][2]["traced_steps"].as_u64().unwrap() > 0);
+    assert!(contains_slice(&output.stderr, b"1 directive(s) failed"));
+}
+
 /// UTILS
 
 /// gets the path to a wasm binary given it's name
@@ -41,6 +293,15 @@ fn get_bin_path(name: &str) -> PathBuf {
     path
 }
 
+/// gets the path to a `.wast` script given its name
+fn get_wast_path(name: &str) -> PathBuf {
+    let mut path = PathBuf::new();
+    path.push("tests");
+    path.push("wats");
+    path.push(format!("{name}.wast"));
+    path
+}
+
 fn get_cmd() -> assert_cmd::Command {
     Command::cargo_bin("wasmi_cli").expect("could not create wasmi_cli command")
 }
@@ -0,0 +1,31 @@
+(module $lib
+    (memory (export "memory") 1)
+    (func (export "store") (param i32 i32)
+        (i32.store (local.get 0) (local.get 1))
+    )
+)
+(register "lib" $lib)
+
+(module
+    (import "lib" "memory" (memory 1))
+    (import "lib" "store" (func $store (param i32 i32)))
+    (func (export "add") (param i32 i32) (result i32)
+        (call $store (i32.const 16) (local.get 0))
+        (i32.add (local.get 0) (local.get 1))
+    )
+    (func (export "load") (param i32) (result i32)
+        (i32.load (local.get 0))
+    )
+    (func $fac (export "fac") (param i64) (result i64)
+        (if (result i64) (i64.eqz (local.get 0))
+            (then (i64.const 1))
+            (else (i64.mul (local.get 0) (call $fac (i64.sub (local.get 0) (i64.const 1)))))
+        )
+    )
+)
+(assert_return (invoke "add" (i32.const 1) (i32.const 2)) (i32.const 3))
+(assert_return (invoke "load" (i32.const 16)) (i32.const 1))
+(assert_return (invoke "fac" (i64.const 10)) (i64.const 3628800))
+(assert_trap (invoke "load" (i32.const 65536)) "out of bounds memory access")
+(assert_invalid (module (func (result i32) (i64.const 0))) "type mismatch")
+(module quote "(func)")
@@ -0,0 +1,13 @@
+(module
+    (import "env" "log" (func $log (param i32)))
+    (memory (export "memory") 1 2)
+    (table 1 funcref)
+    (global $counter (export "counter") (mut i32) (i32.const 42))
+    (data (i32.const 16) "hello")
+    (data "passive")
+    (func $bump (export "bump") (result i32)
+        (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+        (call $log (global.get $counter))
+        (global.get $counter)
+    )
+)
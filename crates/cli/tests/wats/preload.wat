@@ -0,0 +1,9 @@
+(module
+    (import "lib" "double" (func $double (param i32) (result i32)))
+    (import "env" "_evm_return" (func $ret (param i32 i32) (result i64)))
+    (func (export "run") (param i32) (result i64)
+        (i64.add
+            (call $ret (call $double (local.get 0)) (i32.const 7))
+            (i64.const 1))
+    )
+)
@@ -0,0 +1,10 @@
+(module
+    (memory (export "memory") 1)
+    (global $counter (export "counter") (mut i32) (i32.const 0))
+    (func (export "bump") (param i32) (result i32)
+        (global.set $counter (i32.add (global.get $counter) (local.get 0)))
+        (i32.store (i32.const 0) (global.get $counter))
+        (global.get $counter)
+    )
+    (func (export "load") (result i32) (i32.load (i32.const 4)))
+)
@@ -0,0 +1,15 @@
+(module
+    (memory (export "memory") 1)
+    (global $initialized (mut i32) (i32.const 0))
+    (func $init
+        (global.set $initialized (i32.const 1))
+    )
+    (func $store (param i32 i32)
+        (i32.store (local.get 0) (local.get 1))
+    )
+    (func $add (export "add") (param i32 i32) (result i32)
+        (call $store (i32.const 16) (local.get 0))
+        (i32.add (local.get 0) (local.get 1))
+    )
+    (start $init)
+)
@@ -0,0 +1,1187 @@
+//! Lifting and lowering of component values via the canonical ABI.
+//!
+//! Only the `utf8` string encoding is supported.
+
+use crate::{Error, Type, Val};
+use wasmi::{
+    core::{ValueType, F32, F64},
+    AsContext,
+    AsContextMut,
+    Func,
+    Memory,
+    Value,
+};
+
+/// The maximum number of flattened core parameters before parameters are spilled to memory.
+pub(crate) const MAX_FLAT_PARAMS: usize = 16;
+
+/// The maximum number of flattened core results before results are spilled to memory.
+pub(crate) const MAX_FLAT_RESULTS: usize = 1;
+
+/// The maximum length of lifted lists of zero-sized element types.
+///
+/// Unlike other lists their length is not bounded by the size of the linear memory.
+const MAX_ZERO_SIZED_LIST_LEN: u32 = 1 << 16;
+
+/// The canonical ABI options of a lifted or lowered function.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct Options {
+    /// The linear memory used to pass strings, lists and spilled values if any.
+    pub memory: Option<Memory>,
+    /// The `cabi_realloc` function used to allocate guest memory if any.
+    pub realloc: Option<Func>,
+}
+
+/// Aligns `offset` upwards to the next multiple of `align`.
+fn align_to(offset: u32, align: u32) -> u32 {
+    debug_assert!(align.is_power_of_two());
+    (offset + align - 1) & !(align - 1)
+}
+
+/// Returns the size and alignment of the discriminant of a variant with `len_cases` cases.
+fn discriminant_size(len_cases: usize) -> u32 {
+    match len_cases {
+        0..=0x100 => 1,
+        0x101..=0x1_0000 => 2,
+        _ => 4,
+    }
+}
+
+/// Returns the number of 32-bit words required to store `len_flags` flags.
+fn flag_words(len_flags: usize) -> usize {
+    (len_flags + 31) / 32
+}
+
+/// Returns the common core type of `a` and `b` used for flattened variant payloads.
+fn join(a: ValueType, b: ValueType) -> ValueType {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (ValueType::I32, ValueType::F32) | (ValueType::F32, ValueType::I32) => ValueType::I32,
+        _ => ValueType::I64,
+    }
+}
+
+/// Returns the payload types of all cases if `ty` is a variant-like type.
+///
+/// Variants, enums, options and results share the same canonical ABI representation.
+fn cases_of(ty: &Type) -> Option<Vec<Option<&Type>>> {
+    let cases = match ty {
+        Type::Variant(cases) => cases.iter().map(|case| case.ty.as_ref()).collect(),
+        Type::Enum(names) => names.iter().map(|_| None).collect(),
+        Type::Option(ty) => vec![None, Some(&**ty)],
+        Type::Result { ok, err } => vec![ok.as_deref(), err.as_deref()],
+        _ => return None,
+    };
+    Some(cases)
+}
+
+/// Returns the case index and payload of the variant-like `val` of type `ty`.
+///
+/// # Errors
+///
+/// If `val` is not a variant-like value of type `ty`.
+fn case_of<'a>(ty: &Type, val: &'a Val) -> Result<(usize, Option<&'a Val>), Error> {
+    let (index, payload) = match (ty, val) {
+        (Type::Variant(cases), Val::Variant(name, payload)) => {
+            let index = cases.iter().position(|case| &case.name == name);
+            (index, payload.as_deref())
+        }
+        (Type::Enum(names), Val::Enum(name)) => (names.iter().position(|n| n == name), None),
+        (Type::Option(_), Val::Option(None)) => (Some(0), None),
+        (Type::Option(_), Val::Option(Some(value))) => (Some(1), Some(&**value)),
+        (Type::Result { .. }, Val::Result(Ok(payload))) => (Some(0), payload.as_deref()),
+        (Type::Result { .. }, Val::Result(Err(payload))) => (Some(1), payload.as_deref()),
+        _ => (None, None),
+    };
+    let cases = cases_of(ty).ok_or_else(|| Error::type_mismatch(ty))?;
+    match index.map(|index| (index, cases[index])) {
+        Some((index, case)) if case.is_some() == payload.is_some() => Ok((index, payload)),
+        _ => Err(Error::type_mismatch(ty)),
+    }
+}
+
+/// Creates the variant-like value of type `ty` for the case `index` and its `payload`.
+fn case_val(ty: &Type, index: usize, payload: Option<Val>) -> Val {
+    let payload = payload.map(Box::new);
+    match ty {
+        Type::Variant(cases) => Val::Variant(cases[index].name.clone(), payload),
+        Type::Enum(names) => Val::Enum(names[index].clone()),
+        Type::Option(_) => Val::Option(payload),
+        Type::Result { .. } if index == 0 => Val::Result(Ok(payload)),
+        Type::Result { .. } => Val::Result(Err(payload)),
+        _ => unreachable!("encountered non-variant type: {ty}"),
+    }
+}
+
+/// Returns the size and alignment of a record with the fields `types`.
+fn record_layout<'a>(types: impl IntoIterator<Item = &'a Type>) -> (u32, u32) {
+    let mut size = 0;
+    let mut align = 1;
+    for ty in types {
+        size = align_to(size, ty.align()) + ty.size();
+        align = align.max(ty.align());
+    }
+    (align_to(size, align), align)
+}
+
+/// Returns the payload offset, size and alignment of a variant with the payload `cases`.
+fn variant_layout(cases: &[Option<&Type>]) -> (u32, u32, u32) {
+    let discriminant = discriminant_size(cases.len());
+    let mut payload_size = 0;
+    let mut payload_align = 1;
+    for ty in cases.iter().flatten() {
+        payload_size = payload_size.max(ty.size());
+        payload_align = payload_align.max(ty.align());
+    }
+    let align = discriminant.max(payload_align);
+    let payload_offset = align_to(discriminant, payload_align);
+    (
+        payload_offset,
+        align_to(payload_offset + payload_size, align),
+        align,
+    )
+}
+
+/// Returns the flattened core types of the payloads of all variant `cases`.
+fn flatten_cases(cases: &[Option<&Type>]) -> Vec<ValueType> {
+    let mut joined = Vec::new();
+    for ty in cases.iter().flatten() {
+        for (n, ty) in ty.flatten().into_iter().enumerate() {
+            match joined.get_mut(n) {
+                Some(joined) => *joined = join(*joined, ty),
+                None => joined.push(ty),
+            }
+        }
+    }
+    joined
+}
+
+/// Returns the flattened core types of all `types`.
+pub(crate) fn flatten_all<'a>(types: impl IntoIterator<Item = &'a Type>) -> Vec<ValueType> {
+    types.into_iter().flat_map(Type::flatten).collect()
+}
+
+impl Type {
+    /// Returns the size of the [`Type`] in linear memory.
+    pub(crate) fn size(&self) -> u32 {
+        match self {
+            Self::Bool | Self::S8 | Self::U8 => 1,
+            Self::S16 | Self::U16 => 2,
+            Self::S32 | Self::U32 | Self::Float32 | Self::Char => 4,
+            Self::S64 | Self::U64 | Self::Float64 => 8,
+            Self::String | Self::List(_) => 8,
+            Self::Record(fields) => record_layout(fields.iter().map(|field| &field.ty)).0,
+            Self::Tuple(types) => record_layout(types).0,
+            Self::Flags(names) => match names.len() {
+                0 => 0,
+                1..=8 => 1,
+                9..=16 => 2,
+                len => 4 * flag_words(len) as u32,
+            },
+            _ => {
+                let cases = cases_of(self).expect("all other types are variant-like");
+                variant_layout(&cases).1
+            }
+        }
+    }
+
+    /// Returns the alignment of the [`Type`] in linear memory.
+    pub(crate) fn align(&self) -> u32 {
+        match self {
+            Self::Bool | Self::S8 | Self::U8 => 1,
+            Self::S16 | Self::U16 => 2,
+            Self::S32 | Self::U32 | Self::Float32 | Self::Char => 4,
+            Self::S64 | Self::U64 | Self::Float64 => 8,
+            Self::String | Self::List(_) => 4,
+            Self::Record(fields) => record_layout(fields.iter().map(|field| &field.ty)).1,
+            Self::Tuple(types) => record_layout(types).1,
+            Self::Flags(names) => match names.len() {
+                0..=8 => 1,
+                9..=16 => 2,
+                _ => 4,
+            },
+            _ => {
+                let cases = cases_of(self).expect("all other types are variant-like");
+                variant_layout(&cases).2
+            }
+        }
+    }
+
+    /// Returns the core types of the flattened [`Type`].
+    pub(crate) fn flatten(&self) -> Vec<ValueType> {
+        match self {
+            Self::Bool
+            | Self::S8
+            | Self::U8
+            | Self::S16
+            | Self::U16
+            | Self::S32
+            | Self::U32
+            | Self::Char => vec![ValueType::I32],
+            Self::S64 | Self::U64 => vec![ValueType::I64],
+            Self::Float32 => vec![ValueType::F32],
+            Self::Float64 => vec![ValueType::F64],
+            Self::String | Self::List(_) => vec![ValueType::I32, ValueType::I32],
+            Self::Record(fields) => flatten_all(fields.iter().map(|field| &field.ty)),
+            Self::Tuple(types) => flatten_all(types),
+            Self::Flags(names) => vec![ValueType::I32; flag_words(names.len())],
+            _ => {
+                let cases = cases_of(self).expect("all other types are variant-like");
+                let mut flat = vec![ValueType::I32];
+                flat.extend(flatten_cases(&cases));
+                flat
+            }
+        }
+    }
+}
+
+/// Converts the core `value` of a variant case into the `joined` core type of all cases.
+fn widen(value: Value, joined: ValueType) -> Value {
+    match (value, joined) {
+        (Value::I32(value), ValueType::I64) => Value::I64(i64::from(value as u32)),
+        (Value::F32(value), ValueType::I32) => Value::I32(value.to_bits() as i32),
+        (Value::F32(value), ValueType::I64) => Value::I64(i64::from(value.to_bits())),
+        (Value::F64(value), ValueType::I64) => Value::I64(value.to_bits() as i64),
+        (value, _) => value,
+    }
+}
+
+/// Converts the `joined` core `value` of all variant cases into the core type `ty` of a case.
+fn narrow(value: Value, ty: ValueType) -> Value {
+    match (value, ty) {
+        (Value::I64(value), ValueType::I32) => Value::I32(value as i32),
+        (Value::I32(value), ValueType::F32) => Value::F32(F32::from_bits(value as u32)),
+        (Value::I64(value), ValueType::F32) => Value::F32(F32::from_bits(value as u32)),
+        (Value::I64(value), ValueType::F64) => Value::F64(F64::from_bits(value as u64)),
+        (value, _) => value,
+    }
+}
+
+/// Returns the bit words of the set `flags` of all flag `names`.
+///
+/// # Errors
+///
+/// If `flags` contains a name that is not part of `names`.
+fn flag_bits(ty: &Type, names: &[String], flags: &[String]) -> Result<Vec<u32>, Error> {
+    let mut words = vec![0_u32; flag_words(names.len())];
+    for flag in flags {
+        let index = names
+            .iter()
+            .position(|name| name == flag)
+            .ok_or_else(|| Error::type_mismatch(ty))?;
+        words[index / 32] |= 1 << (index % 32);
+    }
+    Ok(words)
+}
+
+/// Returns the set flags of all flag `names` given their bit `words`.
+fn flags_from_bits(names: &[String], words: &[u32]) -> Val {
+    let flags = names
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| words[index / 32] & (1 << (index % 32)) != 0)
+        .map(|(_, name)| name.clone())
+        .collect();
+    Val::Flags(flags)
+}
+
+/// Calls the `realloc` function of the `opts` to allocate `size` bytes aligned to `align`.
+///
+/// # Errors
+///
+/// - If no `realloc` function or `memory` is configured.
+/// - If the returned pointer is misaligned or out of bounds.
+fn realloc(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    align: u32,
+    size: u32,
+) -> Result<u32, Error> {
+    let realloc = opts
+        .realloc
+        .ok_or_else(|| Error::invalid_data("missing `realloc` option"))?;
+    let inputs = [
+        Value::I32(0),
+        Value::I32(0),
+        Value::I32(align as i32),
+        Value::I32(size as i32),
+    ];
+    let mut outputs = [Value::I32(0)];
+    realloc.call(&mut *ctx, &inputs, &mut outputs)?;
+    let ptr = outputs[0]
+        .i32()
+        .ok_or_else(|| Error::invalid_data("`realloc` returned a non-i32 value"))?
+        as u32;
+    if ptr % align != 0 {
+        return Err(Error::invalid_data(format!(
+            "`realloc` returned misaligned pointer {ptr}"
+        )));
+    }
+    check_bounds(&*ctx, opts, ptr, size)?;
+    Ok(ptr)
+}
+
+/// Returns the linear memory of the `opts`.
+///
+/// # Errors
+///
+/// If no linear memory is configured.
+fn memory(opts: &Options) -> Result<Memory, Error> {
+    opts.memory
+        .ok_or_else(|| Error::invalid_data("missing `memory` option"))
+}
+
+/// Checks that `len` bytes at `offset` are within the bounds of the linear memory.
+///
+/// # Errors
+///
+/// If the access is out of bounds or no linear memory is configured.
+fn check_bounds(ctx: &impl AsContext, opts: &Options, offset: u32, len: u32) -> Result<(), Error> {
+    let memory = memory(opts)?;
+    let end = u64::from(offset) + u64::from(len);
+    if end > memory.data_size(ctx) as u64 {
+        return Err(Error::invalid_data(format!(
+            "out of bounds access of {len} bytes at {offset}"
+        )));
+    }
+    Ok(())
+}
+
+/// Writes `bytes` to the linear memory at `offset`.
+///
+/// # Errors
+///
+/// If the access is out of bounds or no linear memory is configured.
+fn write(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    offset: u32,
+    bytes: &[u8],
+) -> Result<(), Error> {
+    check_bounds(&*ctx, opts, offset, bytes.len() as u32)?;
+    memory(opts)?
+        .write(ctx, offset as usize, bytes)
+        .map_err(Error::invalid_data)
+}
+
+/// Reads `buffer.len()` bytes from the linear memory at `offset` into `buffer`.
+///
+/// # Note
+///
+/// The bytes are copied out of the linear memory since
+/// the bytes of shared linear memories cannot be borrowed.
+///
+/// # Errors
+///
+/// If the access is out of bounds or no linear memory is configured.
+fn read_into(
+    ctx: &impl AsContext,
+    opts: &Options,
+    offset: u32,
+    buffer: &mut [u8],
+) -> Result<(), Error> {
+    check_bounds(ctx, opts, offset, buffer.len() as u32)?;
+    memory(opts)?
+        .read(ctx, offset as usize, buffer)
+        .map_err(Error::invalid_data)
+}
+
+/// Reads `len` bytes from the linear memory at `offset`.
+///
+/// # Errors
+///
+/// If the access is out of bounds or no linear memory is configured.
+fn read(ctx: &impl AsContext, opts: &Options, offset: u32, len: u32) -> Result<Vec<u8>, Error> {
+    // Check the bounds before allocating so that guests cannot request huge allocations.
+    check_bounds(ctx, opts, offset, len)?;
+    let mut bytes = vec![0x00; len as usize];
+    read_into(ctx, opts, offset, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads `N` bytes from the linear memory at `offset`.
+///
+/// # Errors
+///
+/// If the access is out of bounds or no linear memory is configured.
+fn read_array<const N: usize>(
+    ctx: &impl AsContext,
+    opts: &Options,
+    offset: u32,
+) -> Result<[u8; N], Error> {
+    let mut bytes = [0x00; N];
+    read_into(ctx, opts, offset, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Stores the `bytes` of a string in newly allocated guest memory.
+///
+/// Returns the pointer and length of the stored string.
+fn store_string(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    string: &str,
+) -> Result<(u32, u32), Error> {
+    let len = u32::try_from(string.len())
+        .map_err(|_| Error::invalid_data("string length exceeds 32-bit range"))?;
+    let ptr = realloc(ctx, opts, 1, len)?;
+    write(ctx, opts, ptr, string.as_bytes())?;
+    Ok((ptr, len))
+}
+
+/// Stores the `values` of a list with elements of type `ty` in newly allocated guest memory.
+///
+/// Returns the pointer and length of the stored list.
+fn store_list(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    ty: &Type,
+    values: &[Val],
+) -> Result<(u32, u32), Error> {
+    let len = u32::try_from(values.len())
+        .map_err(|_| Error::invalid_data("list length exceeds 32-bit range"))?;
+    let size = len
+        .checked_mul(ty.size())
+        .ok_or_else(|| Error::invalid_data("list size exceeds 32-bit range"))?;
+    let ptr = realloc(ctx, opts, ty.align(), size)?;
+    for (n, val) in values.iter().enumerate() {
+        store(ctx, opts, ty, val, ptr + n as u32 * ty.size())?;
+    }
+    Ok((ptr, len))
+}
+
+/// Loads a string with `len` bytes at `ptr` from the linear memory.
+///
+/// # Errors
+///
+/// If the access is out of bounds or the string is not valid UTF-8.
+fn load_string(ctx: &impl AsContext, opts: &Options, ptr: u32, len: u32) -> Result<Val, Error> {
+    let bytes = read(ctx, opts, ptr, len)?;
+    let string =
+        String::from_utf8(bytes).map_err(|_| Error::invalid_data("invalid utf-8 string"))?;
+    Ok(Val::String(string))
+}
+
+/// Loads a list with `len` elements of type `ty` at `ptr` from the linear memory.
+///
+/// # Errors
+///
+/// If the access is misaligned, out of bounds or an element is invalid.
+fn load_list(
+    ctx: &impl AsContext,
+    opts: &Options,
+    ty: &Type,
+    ptr: u32,
+    len: u32,
+) -> Result<Val, Error> {
+    if ptr % ty.align() != 0 {
+        return Err(Error::invalid_data(format!(
+            "misaligned list pointer {ptr}"
+        )));
+    }
+    let size = len
+        .checked_mul(ty.size())
+        .ok_or_else(|| Error::invalid_data("list size exceeds 32-bit range"))?;
+    check_bounds(ctx, opts, ptr, size)?;
+    if size == 0 && len > MAX_ZERO_SIZED_LIST_LEN {
+        return Err(Error::invalid_data(format!(
+            "list of {len} zero-sized elements exceeds the maximum length"
+        )));
+    }
+    let values = (0..len)
+        .map(|n| load(ctx, opts, ty, ptr + n * ty.size()))
+        .collect::<Result<_, _>>()?;
+    Ok(Val::List(values))
+}
+
+/// Stores `val` of type `ty` at `offset` in the linear memory.
+///
+/// # Errors
+///
+/// - If `val` does not match the type `ty`.
+/// - If the access is out of bounds or allocating guest memory fails.
+pub(crate) fn store(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    ty: &Type,
+    val: &Val,
+    offset: u32,
+) -> Result<(), Error> {
+    match (ty, val) {
+        (Type::Bool, Val::Bool(value)) => write(ctx, opts, offset, &[u8::from(*value)]),
+        (Type::S8, Val::S8(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::U8, Val::U8(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::S16, Val::S16(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::U16, Val::U16(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::S32, Val::S32(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::U32, Val::U32(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::S64, Val::S64(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::U64, Val::U64(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::Float32, Val::Float32(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::Float64, Val::Float64(value)) => write(ctx, opts, offset, &value.to_le_bytes()),
+        (Type::Char, Val::Char(value)) => {
+            write(ctx, opts, offset, &u32::from(*value).to_le_bytes())
+        }
+        (Type::String, Val::String(value)) => {
+            let (ptr, len) = store_string(ctx, opts, value)?;
+            write(ctx, opts, offset, &ptr.to_le_bytes())?;
+            write(ctx, opts, offset + 4, &len.to_le_bytes())
+        }
+        (Type::List(ty), Val::List(values)) => {
+            let (ptr, len) = store_list(ctx, opts, ty, values)?;
+            write(ctx, opts, offset, &ptr.to_le_bytes())?;
+            write(ctx, opts, offset + 4, &len.to_le_bytes())
+        }
+        (Type::Record(fields), Val::Record(values)) if fields.len() == values.len() => {
+            let mut field_offset = 0;
+            for (field, (name, val)) in fields.iter().zip(values) {
+                if &field.name != name {
+                    return Err(Error::type_mismatch(ty));
+                }
+                field_offset = align_to(field_offset, field.ty.align());
+                store(ctx, opts, &field.ty, val, offset + field_offset)?;
+                field_offset += field.ty.size();
+            }
+            Ok(())
+        }
+        (Type::Tuple(types), Val::Tuple(values)) if types.len() == values.len() => {
+            let mut field_offset = 0;
+            for (ty, val) in types.iter().zip(values) {
+                field_offset = align_to(field_offset, ty.align());
+                store(ctx, opts, ty, val, offset + field_offset)?;
+                field_offset += ty.size();
+            }
+            Ok(())
+        }
+        (Type::Flags(names), Val::Flags(flags)) => {
+            let words = flag_bits(ty, names, flags)?;
+            match ty.size() {
+                0 => Ok(()),
+                1 => write(ctx, opts, offset, &[words[0] as u8]),
+                2 => write(ctx, opts, offset, &(words[0] as u16).to_le_bytes()),
+                _ => {
+                    for (n, word) in words.iter().enumerate() {
+                        write(ctx, opts, offset + 4 * n as u32, &word.to_le_bytes())?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        _ => {
+            let cases = cases_of(ty).ok_or_else(|| Error::type_mismatch(ty))?;
+            let (index, payload) = case_of(ty, val)?;
+            let discriminant = index as u32;
+            match discriminant_size(cases.len()) {
+                1 => write(ctx, opts, offset, &[discriminant as u8])?,
+                2 => write(ctx, opts, offset, &(discriminant as u16).to_le_bytes())?,
+                _ => write(ctx, opts, offset, &discriminant.to_le_bytes())?,
+            }
+            if let (Some(case), Some(payload)) = (cases[index], payload) {
+                let (payload_offset, _, _) = variant_layout(&cases);
+                store(ctx, opts, case, payload, offset + payload_offset)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Loads a value of type `ty` at `offset` from the linear memory.
+///
+/// # Errors
+///
+/// If the access is out of bounds or the loaded value is invalid for `ty`.
+pub(crate) fn load(
+    ctx: &impl AsContext,
+    opts: &Options,
+    ty: &Type,
+    offset: u32,
+) -> Result<Val, Error> {
+    let val = match ty {
+        Type::Bool => Val::Bool(read_array::<1>(ctx, opts, offset)?[0] != 0),
+        Type::S8 => Val::S8(i8::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::U8 => Val::U8(u8::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::S16 => Val::S16(i16::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::U16 => Val::U16(u16::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::S32 => Val::S32(i32::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::U32 => Val::U32(u32::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::S64 => Val::S64(i64::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::U64 => Val::U64(u64::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::Float32 => Val::Float32(f32::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::Float64 => Val::Float64(f64::from_le_bytes(read_array(ctx, opts, offset)?)),
+        Type::Char => char_from_u32(u32::from_le_bytes(read_array(ctx, opts, offset)?))?,
+        Type::String | Type::List(_) => {
+            let ptr = u32::from_le_bytes(read_array(ctx, opts, offset)?);
+            let len = u32::from_le_bytes(read_array(ctx, opts, offset + 4)?);
+            match ty {
+                Type::List(ty) => load_list(ctx, opts, ty, ptr, len)?,
+                _ => load_string(ctx, opts, ptr, len)?,
+            }
+        }
+        Type::Record(fields) => {
+            let mut field_offset = 0;
+            let mut values = Vec::with_capacity(fields.len());
+            for field in fields {
+                field_offset = align_to(field_offset, field.ty.align());
+                let val = load(ctx, opts, &field.ty, offset + field_offset)?;
+                values.push((field.name.clone(), val));
+                field_offset += field.ty.size();
+            }
+            Val::Record(values)
+        }
+        Type::Tuple(types) => {
+            let mut field_offset = 0;
+            let mut values = Vec::with_capacity(types.len());
+            for ty in types {
+                field_offset = align_to(field_offset, ty.align());
+                values.push(load(ctx, opts, ty, offset + field_offset)?);
+                field_offset += ty.size();
+            }
+            Val::Tuple(values)
+        }
+        Type::Flags(names) => {
+            let words = match ty.size() {
+                0 => Vec::new(),
+                1 => vec![u32::from(read_array::<1>(ctx, opts, offset)?[0])],
+                2 => vec![u32::from(u16::from_le_bytes(read_array(
+                    ctx, opts, offset,
+                )?))],
+                _ => (0..flag_words(names.len()) as u32)
+                    .map(|n| read_array(ctx, opts, offset + 4 * n).map(u32::from_le_bytes))
+                    .collect::<Result<_, _>>()?,
+            };
+            flags_from_bits(names, &words)
+        }
+        _ => {
+            let cases = cases_of(ty).expect("all other types are variant-like");
+            let discriminant = match discriminant_size(cases.len()) {
+                1 => u32::from(read_array::<1>(ctx, opts, offset)?[0]),
+                2 => u32::from(u16::from_le_bytes(read_array(ctx, opts, offset)?)),
+                _ => u32::from_le_bytes(read_array(ctx, opts, offset)?),
+            };
+            let index = discriminant as usize;
+            let case = cases.get(index).ok_or_else(|| {
+                Error::invalid_data(format!("invalid discriminant {discriminant}"))
+            })?;
+            let payload = match case {
+                Some(case) => {
+                    let (payload_offset, _, _) = variant_layout(&cases);
+                    Some(load(ctx, opts, case, offset + payload_offset)?)
+                }
+                None => None,
+            };
+            case_val(ty, index, payload)
+        }
+    };
+    Ok(val)
+}
+
+/// Converts `value` into a [`Val::Char`].
+///
+/// # Errors
+///
+/// If `value` is not a Unicode scalar value.
+fn char_from_u32(value: u32) -> Result<Val, Error> {
+    char::from_u32(value)
+        .map(Val::Char)
+        .ok_or_else(|| Error::invalid_data(format!("invalid char {value:#x}")))
+}
+
+/// Lowers `val` of type `ty` into flattened core values pushed to `out`.
+///
+/// # Errors
+///
+/// - If `val` does not match the type `ty`.
+/// - If allocating or writing guest memory for strings or lists fails.
+pub(crate) fn lower_flat(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    ty: &Type,
+    val: &Val,
+    out: &mut Vec<Value>,
+) -> Result<(), Error> {
+    match (ty, val) {
+        (Type::Bool, Val::Bool(value)) => out.push(Value::I32(i32::from(*value))),
+        (Type::S8, Val::S8(value)) => out.push(Value::I32(i32::from(*value))),
+        (Type::U8, Val::U8(value)) => out.push(Value::I32(i32::from(*value))),
+        (Type::S16, Val::S16(value)) => out.push(Value::I32(i32::from(*value))),
+        (Type::U16, Val::U16(value)) => out.push(Value::I32(i32::from(*value))),
+        (Type::S32, Val::S32(value)) => out.push(Value::I32(*value)),
+        (Type::U32, Val::U32(value)) => out.push(Value::I32(*value as i32)),
+        (Type::S64, Val::S64(value)) => out.push(Value::I64(*value)),
+        (Type::U64, Val::U64(value)) => out.push(Value::I64(*value as i64)),
+        (Type::Float32, Val::Float32(value)) => out.push(Value::F32(F32::from_float(*value))),
+        (Type::Float64, Val::Float64(value)) => out.push(Value::F64(F64::from_float(*value))),
+        (Type::Char, Val::Char(value)) => out.push(Value::I32(u32::from(*value) as i32)),
+        (Type::String, Val::String(value)) => {
+            let (ptr, len) = store_string(ctx, opts, value)?;
+            out.extend([Value::I32(ptr as i32), Value::I32(len as i32)]);
+        }
+        (Type::List(ty), Val::List(values)) => {
+            let (ptr, len) = store_list(ctx, opts, ty, values)?;
+            out.extend([Value::I32(ptr as i32), Value::I32(len as i32)]);
+        }
+        (Type::Record(fields), Val::Record(values)) if fields.len() == values.len() => {
+            for (field, (name, val)) in fields.iter().zip(values) {
+                if &field.name != name {
+                    return Err(Error::type_mismatch(ty));
+                }
+                lower_flat(ctx, opts, &field.ty, val, out)?;
+            }
+        }
+        (Type::Tuple(types), Val::Tuple(values)) if types.len() == values.len() => {
+            for (ty, val) in types.iter().zip(values) {
+                lower_flat(ctx, opts, ty, val, out)?;
+            }
+        }
+        (Type::Flags(names), Val::Flags(flags)) => {
+            let words = flag_bits(ty, names, flags)?;
+            out.extend(words.into_iter().map(|word| Value::I32(word as i32)));
+        }
+        _ => {
+            let cases = cases_of(ty).ok_or_else(|| Error::type_mismatch(ty))?;
+            let (index, payload) = case_of(ty, val)?;
+            let mut flat = Vec::new();
+            if let (Some(case), Some(payload)) = (cases[index], payload) {
+                lower_flat(ctx, opts, case, payload, &mut flat)?;
+            }
+            out.push(Value::I32(index as i32));
+            let mut flat = flat.into_iter();
+            for joined in flatten_cases(&cases) {
+                let value = match flat.next() {
+                    Some(value) => widen(value, joined),
+                    None => Value::default(joined),
+                };
+                out.push(value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the next flattened core value of `src`.
+///
+/// # Errors
+///
+/// If `src` is exhausted.
+fn next(src: &mut dyn Iterator<Item = Value>) -> Result<Value, Error> {
+    src.next()
+        .ok_or_else(|| Error::invalid_data("missing flattened core value"))
+}
+
+/// Returns the next flattened `i32` core value of `src`.
+///
+/// # Errors
+///
+/// If `src` is exhausted or the next value is not an `i32`.
+fn next_i32(src: &mut dyn Iterator<Item = Value>) -> Result<i32, Error> {
+    next(src)?
+        .i32()
+        .ok_or_else(|| Error::invalid_data("expected an `i32` core value"))
+}
+
+/// Returns the next flattened `i64` core value of `src`.
+///
+/// # Errors
+///
+/// If `src` is exhausted or the next value is not an `i64`.
+fn next_i64(src: &mut dyn Iterator<Item = Value>) -> Result<i64, Error> {
+    next(src)?
+        .i64()
+        .ok_or_else(|| Error::invalid_data("expected an `i64` core value"))
+}
+
+/// Lifts a value of type `ty` from the flattened core values of `src`.
+///
+/// # Errors
+///
+/// If the core values are invalid for `ty` or reading guest memory fails.
+pub(crate) fn lift_flat(
+    ctx: &impl AsContext,
+    opts: &Options,
+    ty: &Type,
+    src: &mut dyn Iterator<Item = Value>,
+) -> Result<Val, Error> {
+    let val = match ty {
+        Type::Bool => Val::Bool(next_i32(src)? != 0),
+        Type::S8 => Val::S8(next_i32(src)? as i8),
+        Type::U8 => Val::U8(next_i32(src)? as u8),
+        Type::S16 => Val::S16(next_i32(src)? as i16),
+        Type::U16 => Val::U16(next_i32(src)? as u16),
+        Type::S32 => Val::S32(next_i32(src)?),
+        Type::U32 => Val::U32(next_i32(src)? as u32),
+        Type::S64 => Val::S64(next_i64(src)?),
+        Type::U64 => Val::U64(next_i64(src)? as u64),
+        Type::Float32 => match next(src)? {
+            Value::F32(value) => Val::Float32(value.to_float()),
+            _ => return Err(Error::invalid_data("expected an `f32` core value")),
+        },
+        Type::Float64 => match next(src)? {
+            Value::F64(value) => Val::Float64(value.to_float()),
+            _ => return Err(Error::invalid_data("expected an `f64` core value")),
+        },
+        Type::Char => char_from_u32(next_i32(src)? as u32)?,
+        Type::String => {
+            let ptr = next_i32(src)? as u32;
+            let len = next_i32(src)? as u32;
+            load_string(ctx, opts, ptr, len)?
+        }
+        Type::List(ty) => {
+            let ptr = next_i32(src)? as u32;
+            let len = next_i32(src)? as u32;
+            load_list(ctx, opts, ty, ptr, len)?
+        }
+        Type::Record(fields) => Val::Record(
+            fields
+                .iter()
+                .map(|field| Ok((field.name.clone(), lift_flat(ctx, opts, &field.ty, src)?)))
+                .collect::<Result<_, Error>>()?,
+        ),
+        Type::Tuple(types) => Val::Tuple(
+            types
+                .iter()
+                .map(|ty| lift_flat(ctx, opts, ty, src))
+                .collect::<Result<_, Error>>()?,
+        ),
+        Type::Flags(names) => {
+            let words = (0..flag_words(names.len()))
+                .map(|_| next_i32(src).map(|word| word as u32))
+                .collect::<Result<Vec<_>, Error>>()?;
+            flags_from_bits(names, &words)
+        }
+        _ => {
+            let cases = cases_of(ty).expect("all other types are variant-like");
+            let discriminant = next_i32(src)? as u32;
+            let joined = flatten_cases(&cases)
+                .into_iter()
+                .map(|_| next(src))
+                .collect::<Result<Vec<_>, Error>>()?;
+            let index = discriminant as usize;
+            let case = cases.get(index).ok_or_else(|| {
+                Error::invalid_data(format!("invalid discriminant {discriminant}"))
+            })?;
+            let payload = match case {
+                Some(case) => {
+                    let mut flat = joined
+                        .into_iter()
+                        .zip(case.flatten())
+                        .map(|(value, ty)| narrow(value, ty));
+                    Some(lift_flat(ctx, opts, case, &mut flat)?)
+                }
+                None => None,
+            };
+            case_val(ty, index, payload)
+        }
+    };
+    Ok(val)
+}
+
+/// Returns the [`Type::Tuple`] of all `types`.
+///
+/// Used to spill parameters or results to linear memory.
+pub(crate) fn tuple_of<'a>(types: impl IntoIterator<Item = &'a Type>) -> Type {
+    Type::Tuple(types.into_iter().cloned().collect())
+}
+
+/// Lowers the `values` of the `types` as parameters of a core function call.
+///
+/// Spills the values to newly allocated guest memory if they do not fit into
+/// [`MAX_FLAT_PARAMS`] core values.
+///
+/// # Errors
+///
+/// If lowering any of the `values` fails.
+pub(crate) fn lower_params<'a>(
+    ctx: &mut impl AsContextMut,
+    opts: &Options,
+    types: impl IntoIterator<Item = &'a Type> + Clone,
+    values: &[Val],
+) -> Result<Vec<Value>, Error> {
+    let mut flat = Vec::new();
+    if flatten_all(types.clone()).len() <= MAX_FLAT_PARAMS {
+        for (ty, val) in types.into_iter().zip(values) {
+            lower_flat(ctx, opts, ty, val, &mut flat)?;
+        }
+    } else {
+        let tuple = tuple_of(types);
+        let ptr = realloc(ctx, opts, tuple.align(), tuple.size())?;
+        store(ctx, opts, &tuple, &Val::Tuple(values.to_vec()), ptr)?;
+        flat.push(Value::I32(ptr as i32));
+    }
+    Ok(flat)
+}
+
+/// Lifts the values of the `types` from the core values `flat` into `values`.
+///
+/// Loads the values from guest memory if they do not fit into `max_flat` core values.
+///
+/// # Errors
+///
+/// If lifting any of the values fails.
+pub(crate) fn lift_values<'a>(
+    ctx: &impl AsContext,
+    opts: &Options,
+    types: impl IntoIterator<Item = &'a Type> + Clone,
+    flat: &[Value],
+    max_flat: usize,
+    values: &mut [Val],
+) -> Result<(), Error> {
+    let mut src = flat.iter().cloned();
+    if flatten_all(types.clone()).len() <= max_flat {
+        for (ty, val) in types.into_iter().zip(values) {
+            *val = lift_flat(ctx, opts, ty, &mut src)?;
+        }
+    } else {
+        let ptr = next_i32(&mut src)? as u32;
+        let tuple = tuple_of(types);
+        if ptr % tuple.align() != 0 {
+            return Err(Error::invalid_data(format!("misaligned pointer {ptr}")));
+        }
+        match load(ctx, opts, &tuple, ptr)? {
+            Val::Tuple(loaded) => {
+                for (val, loaded) in values.iter_mut().zip(loaded) {
+                    *val = loaded;
+                }
+            }
+            _ => unreachable!("loaded a tuple type"),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Case, Field};
+    use wasmi::{Engine, Linker, Module, Store};
+
+    /// A core module with a linear memory and a bump allocating `cabi_realloc`.
+    const LIBC: &str = r#"
+        (module
+            (memory (export "memory") 1)
+            (global $heap (mut i32) (i32.const 8))
+            (func (export "cabi_realloc") (param i32 i32 i32 i32) (result i32)
+                (local $ptr i32)
+                (local.set $ptr
+                    (i32.and
+                        (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+                        (i32.sub (i32.const 0) (local.get 2))
+                    )
+                )
+                (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+                (local.get $ptr)
+            )
+        )
+    "#;
+
+    /// Instantiates [`LIBC`] and returns the [`Store`] and canonical ABI [`Options`].
+    fn setup() -> (Store<()>, Options) {
+        let engine = Engine::default();
+        let wasm = wat::parse_str(LIBC).unwrap();
+        let module = Module::new(&engine, &wasm[..]).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = <Linker<()>>::new(&engine)
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let opts = Options {
+            memory: instance.get_memory(&store, "memory"),
+            realloc: instance.get_func(&store, "cabi_realloc"),
+        };
+        (store, opts)
+    }
+
+    fn field(name: &str, ty: Type) -> Field {
+        Field {
+            name: name.into(),
+            ty,
+        }
+    }
+
+    fn case(name: &str, ty: Option<Type>) -> Case {
+        Case {
+            name: name.into(),
+            ty,
+        }
+    }
+
+    /// Returns a record type and value containing strings, lists and variants.
+    fn sample() -> (Type, Val) {
+        let shape = Type::Variant(vec![
+            case("circle", Some(Type::Float32)),
+            case("rect", Some(Type::Tuple(vec![Type::U8, Type::U64]))),
+            case("empty", None),
+        ]);
+        let ty = Type::Record(vec![
+            field("name", Type::String),
+            field("tags", Type::List(Box::new(Type::String))),
+            field("shapes", Type::List(Box::new(shape))),
+            field("id", Type::Option(Box::new(Type::S64))),
+            field(
+                "status",
+                Type::Result {
+                    ok: Some(Box::new(Type::Char)),
+                    err: None,
+                },
+            ),
+            field("color", Type::Enum(vec!["red".into(), "green".into()])),
+            field("perms", Type::Flags(vec!["read".into(), "write".into()])),
+        ]);
+        let val = Val::Record(vec![
+            ("name".into(), Val::String("héllo".into())),
+            (
+                "tags".into(),
+                Val::List(vec![Val::String("a".into()), Val::String("bc".into())]),
+            ),
+            (
+                "shapes".into(),
+                Val::List(vec![
+                    Val::Variant("circle".into(), Some(Box::new(Val::Float32(1.5)))),
+                    Val::Variant(
+                        "rect".into(),
+                        Some(Box::new(Val::Tuple(vec![Val::U8(3), Val::U64(u64::MAX)]))),
+                    ),
+                    Val::Variant("empty".into(), None),
+                ]),
+            ),
+            ("id".into(), Val::Option(Some(Box::new(Val::S64(-7))))),
+            (
+                "status".into(),
+                Val::Result(Ok(Some(Box::new(Val::Char('ß'))))),
+            ),
+            ("color".into(), Val::Enum("green".into())),
+            ("perms".into(), Val::Flags(vec!["write".into()])),
+        ]);
+        (ty, val)
+    }
+
+    #[test]
+    fn layout_works() {
+        assert_eq!((Type::String.size(), Type::String.align()), (8, 4));
+        let record = Type::Record(vec![
+            field("a", Type::U8),
+            field("b", Type::U64),
+            field("c", Type::U16),
+        ]);
+        assert_eq!((record.size(), record.align()), (24, 8));
+        let option = Type::Option(Box::new(Type::U32));
+        assert_eq!((option.size(), option.align()), (8, 4));
+        let flags = Type::Flags((0..40).map(|n| n.to_string()).collect());
+        assert_eq!((flags.size(), flags.align()), (8, 4));
+        assert_eq!(flags.flatten(), [ValueType::I32; 2]);
+        let variant = Type::Variant(vec![
+            case("a", Some(Type::Float32)),
+            case("b", Some(Type::U64)),
+            case("c", None),
+        ]);
+        assert_eq!(variant.flatten(), [ValueType::I32, ValueType::I64]);
+        let variant = Type::Variant(vec![
+            case("a", Some(Type::Float32)),
+            case("b", Some(Type::U32)),
+        ]);
+        assert_eq!(variant.flatten(), [ValueType::I32, ValueType::I32]);
+    }
+
+    #[test]
+    fn memory_roundtrip_works() {
+        let (mut store, opts) = setup();
+        let (ty, val) = sample();
+        let ptr = realloc(&mut store, &opts, ty.align(), ty.size()).unwrap();
+        store_val(&mut store, &opts, &ty, &val, ptr);
+        assert_eq!(load(&store, &opts, &ty, ptr).unwrap(), val);
+    }
+
+    fn store_val(store: &mut Store<()>, opts: &Options, ty: &Type, val: &Val, ptr: u32) {
+        store_fn(store, opts, ty, val, ptr).unwrap()
+    }
+
+    fn store_fn(
+        store: &mut Store<()>,
+        opts: &Options,
+        ty: &Type,
+        val: &Val,
+        ptr: u32,
+    ) -> Result<(), Error> {
+        super::store(store, opts, ty, val, ptr)
+    }
+
+    #[test]
+    fn flat_roundtrip_works() {
+        let (mut store, opts) = setup();
+        let (ty, val) = sample();
+        let mut flat = Vec::new();
+        lower_flat(&mut store, &opts, &ty, &val, &mut flat).unwrap();
+        assert_eq!(flat.len(), ty.flatten().len());
+        for (value, ty) in flat.iter().zip(ty.flatten()) {
+            assert_eq!(value.ty(), ty);
+        }
+        let lifted = lift_flat(&store, &opts, &ty, &mut flat.into_iter()).unwrap();
+        assert_eq!(lifted, val);
+    }
+
+    #[test]
+    fn spilled_params_work() {
+        let (mut store, opts) = setup();
+        let types = vec![Type::U64; MAX_FLAT_PARAMS + 1];
+        let values = (0..types.len() as u64).map(Val::U64).collect::<Vec<_>>();
+        let flat = lower_params(&mut store, &opts, &types, &values).unwrap();
+        assert_eq!(flat.len(), 1);
+        let mut lifted = vec![Val::Bool(false); types.len()];
+        lift_values(&store, &opts, &types, &flat, MAX_FLAT_PARAMS, &mut lifted).unwrap();
+        assert_eq!(lifted, values);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let (mut store, opts) = setup();
+        let mut flat = Vec::new();
+        assert!(matches!(
+            lower_flat(&mut store, &opts, &Type::U32, &Val::S32(1), &mut flat),
+            Err(Error::TypeMismatch { .. })
+        ));
+        let ty = Type::Enum(vec!["a".into()]);
+        assert!(matches!(
+            lower_flat(&mut store, &opts, &ty, &Val::Enum("b".into()), &mut flat),
+            Err(Error::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            lift_flat(&store, &opts, &ty, &mut [Value::I32(1)].into_iter()),
+            Err(Error::InvalidData(_))
+        ));
+        let string = [Value::I32(0), Value::I32(0x1_0001)];
+        assert!(matches!(
+            lift_flat(&store, &opts, &Type::String, &mut string.into_iter()),
+            Err(Error::InvalidData(_))
+        ));
+        assert!(matches!(
+            lift_flat(
+                &store,
+                &opts,
+                &Type::Char,
+                &mut [Value::I32(0xD800)].into_iter()
+            ),
+            Err(Error::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn huge_lists_are_rejected() {
+        let (store, opts) = setup();
+        let ty = Type::List(Box::new(Type::U8));
+        let list = [Value::I32(0), Value::I32(-1)];
+        assert!(matches!(
+            lift_flat(&store, &opts, &ty, &mut list.clone().into_iter()),
+            Err(Error::InvalidData(_))
+        ));
+        let ty = Type::List(Box::new(Type::Tuple(Vec::new())));
+        assert!(matches!(
+            lift_flat(&store, &opts, &ty, &mut list.into_iter()),
+            Err(Error::InvalidData(_))
+        ));
+        let list = [Value::I32(0), Value::I32(3)];
+        assert_eq!(
+            lift_flat(&store, &opts, &ty, &mut list.into_iter()).unwrap(),
+            Val::List(vec![Val::Tuple(Vec::new()); 3])
+        );
+    }
+}
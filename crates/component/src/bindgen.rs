@@ -0,0 +1,798 @@
+//! Generates typed host bindings from WIT documents.
+//!
+//! For a WIT `world` the generated Rust source contains:
+//!
+//! - A Rust type implementing [`ComponentValue`](crate::ComponentValue) for every
+//!   named WIT type of the world and its interfaces.
+//! - A module for every interface used by the world. For imported interfaces
+//!   the module contains a `Host` trait and an `add_to_linker` function.
+//! - An `{World}Imports` trait for functions directly imported by the world
+//!   and an `add_to_linker` function that adds all imports to a [`Linker`](crate::Linker).
+//! - A `{World}` struct with typed `call_*` methods for all exported functions.
+//!
+//! The generated source refers to this crate as `wasmi_component` and is
+//! meant to be written to a file and included via `include!`.
+
+use crate::{
+    wit::{
+        self,
+        Document,
+        Function,
+        Interface,
+        TypeDef,
+        TypeDefKind,
+        TypeRef,
+        WitError,
+        World,
+        WorldItem,
+    },
+    Type,
+};
+use std::fmt::Write;
+
+/// Generates host bindings for the `world` of the WIT `source`.
+///
+/// # Errors
+///
+/// - If `source` is not a valid WIT document.
+/// - If `world` or any of the interfaces or types it refers to are not defined.
+/// - If a tuple type has more than 6 fields.
+pub fn generate(source: &str, world: &str) -> Result<String, WitError> {
+    let document = wit::parse(source)?;
+    let world = document
+        .world(world)
+        .ok_or_else(|| WitError::unresolved(format!("cannot find world `{world}`")))?;
+    let mut generator = Generator {
+        document: &document,
+        out: String::new(),
+        indent: 0,
+    };
+    generator.world(world)?;
+    Ok(generator.out)
+}
+
+/// Rust keywords that are escaped with a trailing underscore.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Converts the kebab-case `name` into a snake_case Rust identifier.
+fn snake_case(name: &str) -> String {
+    let ident = name.to_ascii_lowercase().replace('-', "_");
+    match KEYWORDS.contains(&ident.as_str()) {
+        true => format!("{ident}_"),
+        false => ident,
+    }
+}
+
+/// Converts the kebab-case `name` into a CamelCase Rust identifier.
+fn camel_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let word = word.to_ascii_lowercase();
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Returns the Rust type of the primitive WIT type `ty`.
+fn primitive_type(ty: &Type) -> &'static str {
+    match ty {
+        Type::Bool => "bool",
+        Type::S8 => "i8",
+        Type::U8 => "u8",
+        Type::S16 => "i16",
+        Type::U16 => "u16",
+        Type::S32 => "i32",
+        Type::U32 => "u32",
+        Type::S64 => "i64",
+        Type::U64 => "u64",
+        Type::Float32 => "f32",
+        Type::Float64 => "f64",
+        Type::Char => "char",
+        Type::String => "String",
+        _ => unreachable!("encountered non-primitive WIT type: {ty}"),
+    }
+}
+
+/// The named types visible to a function or type definition.
+#[derive(Copy, Clone)]
+struct Scope<'a> {
+    /// The named types defined in the scope.
+    types: &'a [TypeDef],
+    /// The Rust path prefix of the named types, e.g. `math::`.
+    prefix: &'a str,
+}
+
+impl Scope<'_> {
+    /// Returns the Rust type of the WIT type `ty`.
+    ///
+    /// # Errors
+    ///
+    /// If `ty` refers to an undefined type or is a tuple with more than 6 fields.
+    fn rust_type(&self, ty: &TypeRef) -> Result<String, WitError> {
+        let ty = match ty {
+            TypeRef::Primitive(ty) => primitive_type(ty).to_string(),
+            TypeRef::Named(name) => {
+                if !self.types.iter().any(|def| &def.name == name) {
+                    return Err(WitError::unresolved(format!("cannot find type `{name}`")));
+                }
+                format!("{}{}", self.prefix, camel_case(name))
+            }
+            TypeRef::List(ty) => format!("Vec<{}>", self.rust_type(ty)?),
+            TypeRef::Option(ty) => format!("Option<{}>", self.rust_type(ty)?),
+            TypeRef::Result { ok, err } => {
+                let ok = self.optional_type(ok.as_deref())?;
+                let err = self.optional_type(err.as_deref())?;
+                format!("Result<{ok}, {err}>")
+            }
+            TypeRef::Tuple(types) => {
+                if types.len() > 6 {
+                    return Err(WitError::unresolved("tuples with more than 6 fields"));
+                }
+                let types = types
+                    .iter()
+                    .map(|ty| self.rust_type(ty))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match types.as_slice() {
+                    [ty] => format!("({ty},)"),
+                    types => format!("({})", types.join(", ")),
+                }
+            }
+        };
+        Ok(ty)
+    }
+
+    /// Returns the Rust type of the optional `ok` or `err` WIT type of a `result`.
+    fn optional_type(&self, ty: Option<&TypeRef>) -> Result<String, WitError> {
+        match ty {
+            Some(ty) => self.rust_type(ty),
+            None => Ok("()".into()),
+        }
+    }
+
+    /// Returns the Rust result type of the function `func`.
+    fn result_type(&self, func: &Function) -> Result<String, WitError> {
+        let results = func
+            .results
+            .iter()
+            .map(|(_, ty)| self.rust_type(ty))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ty = match results.as_slice() {
+            [ty] => ty.clone(),
+            results => format!("({})", results.join(", ")),
+        };
+        Ok(ty)
+    }
+
+    /// Returns the Rust parameter list of the function `func`.
+    fn params(&self, func: &Function) -> Result<String, WitError> {
+        let mut params = String::new();
+        for (name, ty) in &func.params {
+            write!(params, ", {}: {}", snake_case(name), self.rust_type(ty)?).unwrap();
+        }
+        Ok(params)
+    }
+}
+
+/// Generates the Rust source of host bindings.
+struct Generator<'a> {
+    /// The parsed WIT document.
+    document: &'a Document,
+    /// The generated Rust source.
+    out: String,
+    /// The current indentation level.
+    indent: usize,
+}
+
+impl<'a> Generator<'a> {
+    /// Pushes a `line` of Rust source, adjusting the indentation for brackets.
+    fn line(&mut self, line: &str) {
+        if line.starts_with(['}', ')', ']']) {
+            self.indent -= 1;
+        }
+        if !line.is_empty() {
+            for _ in 0..self.indent {
+                self.out.push_str("    ");
+            }
+        }
+        self.out.push_str(line);
+        self.out.push('\n');
+        if line.ends_with(['{', '(', '[']) {
+            self.indent += 1;
+        }
+    }
+
+    /// Returns the interface `name` of the document.
+    fn interface(&self, name: &str) -> Result<&'a Interface, WitError> {
+        self.document
+            .interface(name)
+            .ok_or_else(|| WitError::unresolved(format!("cannot find interface `{name}`")))
+    }
+
+    /// Generates the bindings of the `world`.
+    fn world(&mut self, world: &World) -> Result<(), WitError> {
+        let world_scope = Scope {
+            types: &world.types,
+            prefix: "",
+        };
+        self.line(&format!(
+            "// Generated by `wasmi_component::bindgen` from the WIT world `{}`.",
+            world.name
+        ));
+        self.line("");
+        self.line("#[allow(unused_imports)]");
+        self.line("use wasmi_component::{");
+        self.line("wasmi::{core::Trap, AsContextMut},");
+        self.line(
+            "Case, Component, ComponentValue, Error, Field, Func, Instance, Linker, Type, Val,",
+        );
+        self.line("};");
+        for def in &world.types {
+            self.type_def(world_scope, def)?;
+        }
+        let mut interfaces = Vec::new();
+        for item in world.imports.iter().chain(&world.exports) {
+            if let WorldItem::Interface(name) = item {
+                if !interfaces.contains(&name) {
+                    interfaces.push(name);
+                }
+            }
+        }
+        for name in interfaces {
+            let imported = world.imports.contains(&WorldItem::Interface(name.clone()));
+            self.interface_module(self.interface(name)?, imported)?;
+        }
+        self.imports(world, world_scope)?;
+        self.exports(world, world_scope)
+    }
+
+    /// Generates a Rust type implementing `ComponentValue` for the named type `def`.
+    fn type_def(&mut self, scope: Scope, def: &TypeDef) -> Result<(), WitError> {
+        let name = camel_case(&def.name);
+        self.line("");
+        match &def.kind {
+            TypeDefKind::Alias(ty) => {
+                self.line(&format!("pub type {name} = {};", scope.rust_type(ty)?));
+                return Ok(());
+            }
+            TypeDefKind::Record(fields) => {
+                self.line("#[derive(Debug, Clone, PartialEq)]");
+                self.line(&format!("pub struct {name} {{"));
+                for (field, ty) in fields {
+                    self.line(&format!(
+                        "pub {}: {},",
+                        snake_case(field),
+                        scope.rust_type(ty)?
+                    ));
+                }
+                self.line("}");
+            }
+            TypeDefKind::Variant(cases) => {
+                self.line("#[derive(Debug, Clone, PartialEq)]");
+                self.line(&format!("pub enum {name} {{"));
+                for (case, ty) in cases {
+                    match ty {
+                        Some(ty) => {
+                            self.line(&format!("{}({}),", camel_case(case), scope.rust_type(ty)?))
+                        }
+                        None => self.line(&format!("{},", camel_case(case))),
+                    }
+                }
+                self.line("}");
+            }
+            TypeDefKind::Enum(cases) => {
+                self.line("#[derive(Debug, Copy, Clone, PartialEq, Eq)]");
+                self.line(&format!("pub enum {name} {{"));
+                for case in cases {
+                    self.line(&format!("{},", camel_case(case)));
+                }
+                self.line("}");
+            }
+            TypeDefKind::Flags(flags) => {
+                self.line("#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]");
+                self.line(&format!("pub struct {name} {{"));
+                for flag in flags {
+                    self.line(&format!("pub {}: bool,", snake_case(flag)));
+                }
+                self.line("}");
+            }
+        }
+        self.line("");
+        self.line(&format!("impl ComponentValue for {name} {{"));
+        self.component_value_ty(scope, &def.kind)?;
+        self.line("");
+        self.component_value_into_val(&def.kind);
+        self.line("");
+        self.component_value_from_val(&def.kind);
+        self.line("}");
+        Ok(())
+    }
+
+    /// Generates `ComponentValue::ty` for the type definition `kind`.
+    fn component_value_ty(&mut self, scope: Scope, kind: &TypeDefKind) -> Result<(), WitError> {
+        self.line("fn ty() -> Type {");
+        match kind {
+            TypeDefKind::Record(fields) => {
+                self.line("Type::Record(vec![");
+                for (field, ty) in fields {
+                    self.line(&format!(
+                        "Field {{ name: {field:?}.into(), ty: <{} as ComponentValue>::ty() }},",
+                        scope.rust_type(ty)?
+                    ));
+                }
+                self.line("])");
+            }
+            TypeDefKind::Variant(cases) => {
+                self.line("Type::Variant(vec![");
+                for (case, ty) in cases {
+                    let ty = match ty {
+                        Some(ty) => {
+                            format!("Some(<{} as ComponentValue>::ty())", scope.rust_type(ty)?)
+                        }
+                        None => "None".into(),
+                    };
+                    self.line(&format!("Case {{ name: {case:?}.into(), ty: {ty} }},"));
+                }
+                self.line("])");
+            }
+            TypeDefKind::Enum(names) | TypeDefKind::Flags(names) => {
+                let variant = match kind {
+                    TypeDefKind::Enum(_) => "Enum",
+                    _ => "Flags",
+                };
+                let names = names
+                    .iter()
+                    .map(|name| format!("{name:?}.into()"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                self.line(&format!("Type::{variant}(vec![{names}])"));
+            }
+            TypeDefKind::Alias(_) => unreachable!("aliases do not implement `ComponentValue`"),
+        }
+        self.line("}");
+        Ok(())
+    }
+
+    /// Generates `ComponentValue::into_val` for the type definition `kind`.
+    fn component_value_into_val(&mut self, kind: &TypeDefKind) {
+        self.line("fn into_val(self) -> Val {");
+        match kind {
+            TypeDefKind::Record(fields) => {
+                self.line("Val::Record(vec![");
+                for (field, _) in fields {
+                    self.line(&format!(
+                        "({field:?}.into(), self.{}.into_val()),",
+                        snake_case(field)
+                    ));
+                }
+                self.line("])");
+            }
+            TypeDefKind::Variant(cases) => {
+                self.line("match self {");
+                for (case, ty) in cases {
+                    let variant = camel_case(case);
+                    match ty {
+                        Some(_) => self.line(&format!(
+                            "Self::{variant}(payload) => Val::Variant({case:?}.into(), Some(Box::new(payload.into_val()))),"
+                        )),
+                        None => self.line(&format!(
+                            "Self::{variant} => Val::Variant({case:?}.into(), None),"
+                        )),
+                    }
+                }
+                self.line("}");
+            }
+            TypeDefKind::Enum(cases) => {
+                self.line("let case = match self {");
+                for case in cases {
+                    self.line(&format!("Self::{} => {case:?},", camel_case(case)));
+                }
+                self.line("};");
+                self.line("Val::Enum(case.into())");
+            }
+            TypeDefKind::Flags(flags) => {
+                self.line("let mut flags = Vec::new();");
+                for flag in flags {
+                    self.line(&format!("if self.{} {{", snake_case(flag)));
+                    self.line(&format!("flags.push({flag:?}.into());"));
+                    self.line("}");
+                }
+                self.line("Val::Flags(flags)");
+            }
+            TypeDefKind::Alias(_) => unreachable!("aliases do not implement `ComponentValue`"),
+        }
+        self.line("}");
+    }
+
+    /// Generates `ComponentValue::from_val` for the type definition `kind`.
+    fn component_value_from_val(&mut self, kind: &TypeDefKind) {
+        let mismatch = "Err(Error::TypeMismatch { expected: Self::ty() })";
+        self.line("fn from_val(val: Val) -> Result<Self, Error> {");
+        match kind {
+            TypeDefKind::Record(fields) => {
+                self.line("match val {");
+                self.line("Val::Record(mut fields) => Ok(Self {");
+                for (field, _) in fields {
+                    self.line(&format!(
+                        "{}: ComponentValue::from_val(Val::take_field(&mut fields, {field:?}, &Self::ty())?)?,",
+                        snake_case(field)
+                    ));
+                }
+                self.line("}),");
+                self.line(&format!("_ => {mismatch},"));
+                self.line("}");
+            }
+            TypeDefKind::Variant(cases) => {
+                self.line("match val {");
+                self.line("Val::Variant(case, payload) => match (case.as_str(), payload) {");
+                for (case, ty) in cases {
+                    let variant = camel_case(case);
+                    match ty {
+                        Some(_) => self.line(&format!(
+                            "({case:?}, Some(payload)) => Ok(Self::{variant}(ComponentValue::from_val(*payload)?)),"
+                        )),
+                        None => self.line(&format!("({case:?}, None) => Ok(Self::{variant}),")),
+                    }
+                }
+                self.line(&format!("_ => {mismatch},"));
+                self.line("},");
+                self.line(&format!("_ => {mismatch},"));
+                self.line("}");
+            }
+            TypeDefKind::Enum(cases) => {
+                self.line("match val {");
+                self.line("Val::Enum(case) => match case.as_str() {");
+                for case in cases {
+                    self.line(&format!("{case:?} => Ok(Self::{}),", camel_case(case)));
+                }
+                self.line(&format!("_ => {mismatch},"));
+                self.line("},");
+                self.line(&format!("_ => {mismatch},"));
+                self.line("}");
+            }
+            TypeDefKind::Flags(flags) => {
+                self.line("if !val.is_instance_of(&Self::ty()) {");
+                self.line(&format!("return {mismatch};"));
+                self.line("}");
+                self.line("match val {");
+                self.line("Val::Flags(flags) => Ok(Self {");
+                for flag in flags {
+                    self.line(&format!(
+                        "{}: flags.iter().any(|flag| flag == {flag:?}),",
+                        snake_case(flag)
+                    ));
+                }
+                self.line("}),");
+                self.line(&format!("_ => {mismatch},"));
+                self.line("}");
+            }
+            TypeDefKind::Alias(_) => unreachable!("aliases do not implement `ComponentValue`"),
+        }
+        self.line("}");
+    }
+
+    /// Generates the module of an `interface` used by a world.
+    ///
+    /// The module contains a `Host` trait and `add_to_linker` if the interface is `imported`.
+    fn interface_module(&mut self, interface: &Interface, imported: bool) -> Result<(), WitError> {
+        let scope = Scope {
+            types: &interface.types,
+            prefix: "",
+        };
+        self.line("");
+        self.line(&format!("pub mod {} {{", snake_case(&interface.name)));
+        self.line("#[allow(unused_imports)]");
+        self.line("use super::*;");
+        for def in &interface.types {
+            self.type_def(scope, def)?;
+        }
+        if imported {
+            self.line("");
+            self.line(&format!(
+                "/// The host functions of the imported `{}` interface.",
+                interface.name
+            ));
+            self.host_trait("Host", scope, &interface.funcs)?;
+            self.line("");
+            self.line(&format!(
+                "/// Adds the `{}` interface to the `linker` using `get` to access its [`Host`].",
+                interface.name
+            ));
+            self.line("///");
+            self.line("/// # Errors");
+            self.line("///");
+            self.line("/// If the `linker` already defines any of the functions.");
+            self.line("pub fn add_to_linker<T: 'static, U: Host + 'static>(");
+            self.line("linker: &mut Linker<T>,");
+            self.line("get: fn(&mut T) -> &mut U,");
+            self.line(") -> Result<(), Error> {");
+            self.line(&format!(
+                "let mut instance = linker.instance({:?});",
+                interface.name
+            ));
+            for func in &interface.funcs {
+                self.host_func("instance", func);
+            }
+            self.line("Ok(())");
+            self.line("}");
+        }
+        self.line("}");
+        Ok(())
+    }
+
+    /// Generates the trait `name` with a method for every host function of `funcs`.
+    fn host_trait<'f>(
+        &mut self,
+        name: &str,
+        scope: Scope,
+        funcs: impl IntoIterator<Item = &'f Function>,
+    ) -> Result<(), WitError> {
+        self.line(&format!("pub trait {name} {{"));
+        for func in funcs {
+            self.line(&format!(
+                "fn {}(&mut self{}) -> Result<{}, Trap>;",
+                snake_case(&func.name),
+                scope.params(func)?,
+                scope.result_type(func)?,
+            ));
+        }
+        self.line("}");
+        Ok(())
+    }
+
+    /// Generates the definition of the host function `func` via `{definer}.func_new`.
+    ///
+    /// The host function forwards its lifted parameters to the method of the `Host` returned by `get`.
+    fn host_func(&mut self, definer: &str, func: &Function) {
+        let params = match func.params.is_empty() {
+            true => "_params",
+            false => "params",
+        };
+        let results = match func.results.is_empty() {
+            true => "_results",
+            false => "results",
+        };
+        self.line(&format!(
+            "{definer}.func_new({:?}, move |mut store, {params}, {results}| {{",
+            func.name
+        ));
+        self.line("let host = get(store.data_mut());");
+        let call = format!("host.{}(", snake_case(&func.name));
+        match func.results.len() {
+            0 => self.line(&call),
+            1 => self.line(&format!("let result = {call}")),
+            len => {
+                let names = (0..len).map(|n| format!("r{n}")).collect::<Vec<_>>();
+                self.line(&format!("let ({}) = {call}", names.join(", ")));
+            }
+        }
+        for n in 0..func.params.len() {
+            self.line(&format!("ComponentValue::from_val(params[{n}].clone())?,"));
+        }
+        self.line(")?;");
+        match func.results.len() {
+            0 => {}
+            1 => self.line("results[0] = result.into_val();"),
+            len => {
+                for n in 0..len {
+                    self.line(&format!("results[{n}] = r{n}.into_val();"));
+                }
+            }
+        }
+        self.line("Ok(())");
+        self.line("})?;");
+    }
+
+    /// Generates the `{World}Imports` trait and `add_to_linker` for the imports of the `world`.
+    fn imports(&mut self, world: &World, scope: Scope) -> Result<(), WitError> {
+        if world.imports.is_empty() {
+            return Ok(());
+        }
+        let world_name = camel_case(&world.name);
+        let funcs = world
+            .imports
+            .iter()
+            .filter_map(|item| match item {
+                WorldItem::Func(func) => Some(func),
+                WorldItem::Interface(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let mut bounds = Vec::new();
+        if !funcs.is_empty() {
+            self.line("");
+            self.line(&format!(
+                "/// The host functions directly imported by the `{}` world.",
+                world.name
+            ));
+            self.host_trait(
+                &format!("{world_name}Imports"),
+                scope,
+                funcs.iter().copied(),
+            )?;
+            bounds.push(format!("{world_name}Imports"));
+        }
+        for item in &world.imports {
+            if let WorldItem::Interface(name) = item {
+                bounds.push(format!("{}::Host", snake_case(name)));
+            }
+        }
+        self.line("");
+        self.line(&format!(
+            "/// Adds all imports of the `{}` world to the `linker` using `get` to access their hosts.",
+            world.name
+        ));
+        self.line("///");
+        self.line("/// # Errors");
+        self.line("///");
+        self.line("/// If the `linker` already defines any of the functions.");
+        self.line("pub fn add_to_linker<T: 'static, U>(");
+        self.line("linker: &mut Linker<T>,");
+        self.line("get: fn(&mut T) -> &mut U,");
+        self.line(") -> Result<(), Error>");
+        self.line("where");
+        self.line(&format!("    U: {} + 'static,", bounds.join(" + ")));
+        self.line("{");
+        for func in funcs {
+            self.host_func("linker", func);
+        }
+        for item in &world.imports {
+            if let WorldItem::Interface(name) = item {
+                self.line(&format!(
+                    "{}::add_to_linker(linker, get)?;",
+                    snake_case(name)
+                ));
+            }
+        }
+        self.line("Ok(())");
+        self.line("}");
+        Ok(())
+    }
+
+    /// Generates the `{World}` struct with `call_*` methods for the exports of the `world`.
+    fn exports(&mut self, world: &World, world_scope: Scope) -> Result<(), WitError> {
+        // The exported functions with their scope, field name, instance and function name.
+        let mut exports = Vec::new();
+        for item in &world.exports {
+            match item {
+                WorldItem::Func(func) => {
+                    exports.push((world_scope, snake_case(&func.name), None, func));
+                }
+                WorldItem::Interface(name) => {
+                    let interface = self.interface(name)?;
+                    for func in &interface.funcs {
+                        let field = snake_case(&format!("{name}-{}", func.name));
+                        exports.push((
+                            Scope {
+                                types: &interface.types,
+                                prefix: "",
+                            },
+                            field,
+                            Some(name),
+                            func,
+                        ));
+                    }
+                }
+            }
+        }
+        let prefixes = exports
+            .iter()
+            .map(|(_, _, instance, _)| match instance {
+                Some(instance) => format!("{}::", snake_case(instance)),
+                None => String::new(),
+            })
+            .collect::<Vec<_>>();
+        let world_name = camel_case(&world.name);
+        self.line("");
+        self.line(&format!(
+            "/// The exports of an instantiated component of the `{}` world.",
+            world.name
+        ));
+        self.line("#[derive(Debug, Clone)]");
+        self.line(&format!("pub struct {world_name} {{"));
+        for (_, field, _, _) in &exports {
+            self.line(&format!("{field}: Func,"));
+        }
+        self.line("}");
+        self.line("");
+        self.line(&format!("impl {world_name} {{"));
+        self.line("/// Looks up the exports of the `instance`.");
+        self.line("///");
+        self.line("/// # Errors");
+        self.line("///");
+        self.line("/// If the `instance` lacks any of the exports.");
+        self.line("pub fn new(instance: &Instance) -> Result<Self, Error> {");
+        self.line("Ok(Self {");
+        for (_, field, instance, func) in &exports {
+            let (lookup, name) = match instance {
+                Some(instance) => (
+                    format!("get_instance_func({instance:?}, {:?})", func.name),
+                    format!("{instance}/{}", func.name),
+                ),
+                None => (format!("get_func({:?})", func.name), func.name.clone()),
+            };
+            self.line(&format!("{field}: instance"));
+            self.line(&format!("    .{lookup}"));
+            self.line(&format!(
+                "    .ok_or_else(|| Error::MissingExport({name:?}.into()))?,"
+            ));
+        }
+        self.line("})");
+        self.line("}");
+        self.line("");
+        self.line("/// Instantiates the `component` with the `linker` and looks up its exports.");
+        self.line("///");
+        self.line("/// # Errors");
+        self.line("///");
+        self.line("/// If instantiation fails or the component lacks any of the exports.");
+        self.line("pub fn instantiate<T: 'static>(");
+        self.line("store: impl AsContextMut<UserState = T>,");
+        self.line("component: &Component,");
+        self.line("linker: &Linker<T>,");
+        self.line(") -> Result<Self, Error> {");
+        self.line("Self::new(&linker.instantiate(store, component)?)");
+        self.line("}");
+        for ((scope, field, _, func), prefix) in exports.iter().zip(&prefixes) {
+            let scope = Scope {
+                types: scope.types,
+                prefix,
+            };
+            self.line("");
+            self.line(&format!("/// Calls the exported `{}` function.", func.name));
+            self.line("///");
+            self.line("/// # Errors");
+            self.line("///");
+            self.line("/// If the execution traps or lifting or lowering values fails.");
+            self.line(&format!(
+                "pub fn call_{field}(&self, mut store: impl AsContextMut{}) -> Result<{}, Error> {{",
+                scope.params(func)?,
+                scope.result_type(func)?,
+            ));
+            let params = func
+                .params
+                .iter()
+                .map(|(name, _)| format!("{}.into_val()", snake_case(name)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let names = (0..func.results.len())
+                .map(|n| format!("r{n}"))
+                .collect::<Vec<_>>();
+            match names.len() {
+                0 => self.line("let mut results: [Val; 0] = [];"),
+                len => self.line(&format!(
+                    "let mut results = [{}];",
+                    vec!["Val::Bool(false)"; len].join(", ")
+                )),
+            }
+            self.line(&format!(
+                "self.{field}.call(&mut store, &[{params}], &mut results)?;"
+            ));
+            match names.as_slice() {
+                [] => self.line("Ok(())"),
+                [name] => {
+                    self.line(&format!("let [{name}] = results;"));
+                    self.line(&format!("ComponentValue::from_val({name})"));
+                }
+                names => {
+                    self.line(&format!("let [{}] = results;", names.join(", ")));
+                    let values = names
+                        .iter()
+                        .map(|name| format!("ComponentValue::from_val({name})?"))
+                        .collect::<Vec<_>>();
+                    self.line(&format!("Ok(({}))", values.join(", ")));
+                }
+            }
+            self.line("}");
+        }
+        self.line("}");
+        Ok(())
+    }
+}
@@ -0,0 +1,547 @@
+use crate::{Error, FuncType};
+use std::{collections::BTreeMap, sync::Arc};
+use wasmi::{Engine, Module};
+use wasmparser::{
+    CanonicalFunction,
+    CanonicalOption,
+    ComponentAlias,
+    ComponentExternalKind,
+    ComponentTypeRef,
+    Encoding,
+    ExternalKind,
+    InstantiationArgKind,
+    Parser,
+    Payload,
+    Validator,
+    WasmFeatures,
+};
+
+/// A validated and compiled Wasm component.
+///
+/// # Note
+///
+/// Only a subset of the Component Model is supported so far:
+///
+/// - Components may import functions and instances of functions.
+/// - Components may embed and instantiate core modules.
+/// - Components may export lifted functions and instances of lifted functions.
+///
+/// Nested components, component instantiation, start functions as well as
+/// value, module and component imports are rejected with [`Error::Unsupported`].
+#[derive(Debug, Clone)]
+pub struct Component {
+    /// The [`Engine`] that compiled the core modules of the [`Component`].
+    engine: Engine,
+    /// The definitions of the [`Component`].
+    inner: Arc<ComponentInner>,
+}
+
+/// The definitions of a [`Component`] in their index spaces.
+#[derive(Debug, Default)]
+pub(crate) struct ComponentInner {
+    /// The embedded core modules.
+    pub modules: Vec<Module>,
+    /// The core instance index space.
+    pub core_instances: Vec<CoreInstance>,
+    /// The core function index space.
+    pub core_funcs: Vec<CoreFunc>,
+    /// The core memory index space.
+    pub core_memories: Vec<CoreExport>,
+    /// The core table index space.
+    pub core_tables: Vec<CoreExport>,
+    /// The core global index space.
+    pub core_globals: Vec<CoreExport>,
+    /// The lowered imported component functions.
+    pub lowerings: Vec<Lowering>,
+    /// The component function index space.
+    pub funcs: Vec<ComponentFunc>,
+    /// The types of all component functions.
+    pub func_types: Vec<FuncType>,
+    /// The component instance index space.
+    pub instances: Vec<ComponentInstance>,
+    /// The exports of the component.
+    pub exports: BTreeMap<String, ComponentExport>,
+}
+
+/// An export of a core instance.
+#[derive(Debug, Clone)]
+pub(crate) struct CoreExport {
+    /// The index of the core instance.
+    pub instance: u32,
+    /// The name of the export.
+    pub name: String,
+}
+
+/// A core item used to create a core instance from exports.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum CoreItem {
+    /// A core function index.
+    Func(u32),
+    /// A core table index.
+    Table(u32),
+    /// A core memory index.
+    Memory(u32),
+    /// A core global index.
+    Global(u32),
+}
+
+/// A core instance definition.
+#[derive(Debug)]
+pub(crate) enum CoreInstance {
+    /// Instantiates a core module with named core instances as arguments.
+    Instantiate {
+        /// The index of the instantiated core module.
+        module: u32,
+        /// The module names and core instance indices used to resolve imports.
+        args: Vec<(String, u32)>,
+    },
+    /// Bundles core items into a core instance.
+    FromExports(Vec<(String, CoreItem)>),
+}
+
+/// A core function definition.
+#[derive(Debug)]
+pub(crate) enum CoreFunc {
+    /// The export of a core instance.
+    Export(CoreExport),
+    /// The lowering with the given index.
+    Lowered(u32),
+}
+
+/// The canonical ABI options of a lifted or lowered function by index.
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct CanonOptions {
+    /// The core memory index if any.
+    pub memory: Option<u32>,
+    /// The core function index of `cabi_realloc` if any.
+    pub realloc: Option<u32>,
+    /// The core function index of the post-return function if any.
+    pub post_return: Option<u32>,
+}
+
+/// An imported component function lowered into a core function.
+#[derive(Debug)]
+pub(crate) struct Lowering {
+    /// The index of the lowered component function.
+    pub func: u32,
+    /// The canonical ABI options of the lowering.
+    pub options: CanonOptions,
+}
+
+/// A component function definition.
+#[derive(Debug, Clone)]
+pub(crate) enum ComponentFunc {
+    /// An imported function, optionally exported by an imported instance.
+    Import {
+        /// The name of the imported instance if any.
+        instance: Option<String>,
+        /// The name of the imported function.
+        name: String,
+    },
+    /// A core function lifted into a component function.
+    Lifted {
+        /// The index of the lifted core function.
+        core_func: u32,
+        /// The canonical ABI options of the lifting.
+        options: CanonOptions,
+    },
+}
+
+/// A component instance definition.
+#[derive(Debug, Clone)]
+pub(crate) enum ComponentInstance {
+    /// An imported instance.
+    Import(String),
+    /// Bundles component functions into an instance.
+    FromExports(BTreeMap<String, u32>),
+}
+
+/// An export of a component.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum ComponentExport {
+    /// An exported component function index.
+    Func(u32),
+    /// An exported component instance index.
+    Instance(u32),
+}
+
+impl Component {
+    /// Validates and compiles the Wasm component `bytes` for the `engine`.
+    ///
+    /// # Errors
+    ///
+    /// - If the `bytes` are not a valid Wasm component.
+    /// - If the component uses features that are not yet supported.
+    /// - If compiling any of the embedded core modules fails.
+    pub fn new(engine: &Engine, bytes: &[u8]) -> Result<Self, Error> {
+        let features = WasmFeatures {
+            component_model: true,
+            ..WasmFeatures::default()
+        };
+        let types = Validator::new_with_features(features).validate_all(bytes)?;
+        let mut builder = ComponentBuilder {
+            engine,
+            bytes,
+            inner: ComponentInner::default(),
+        };
+        let mut depth = 0_usize;
+        for payload in Parser::new(0).parse_all(bytes) {
+            let payload = payload?;
+            if depth > 0 {
+                // Payloads of embedded core modules are handled by `Module::new`.
+                if let Payload::End(_) = payload {
+                    depth -= 1;
+                }
+                continue;
+            }
+            if let Payload::ModuleSection { .. } = payload {
+                depth += 1;
+            }
+            builder.process_payload(payload)?;
+        }
+        let mut inner = builder.inner;
+        inner.func_types = (0..inner.funcs.len() as u32)
+            .map(|index| {
+                let ty = types
+                    .component_function_at(index)
+                    .expect("validated component function index");
+                FuncType::from_parser(types.as_ref(), ty)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            engine: engine.clone(),
+            inner: Arc::new(inner),
+        })
+    }
+
+    /// Returns the [`Engine`] used to compile the [`Component`].
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Returns the names of all imports of the [`Component`].
+    ///
+    /// Functions of imported instances are named `instance/func`.
+    pub fn imports(&self) -> impl Iterator<Item = (String, &FuncType)> + '_ {
+        self.inner
+            .funcs
+            .iter()
+            .zip(&self.inner.func_types)
+            .filter_map(|(func, ty)| match func {
+                ComponentFunc::Import {
+                    instance: Some(instance),
+                    name,
+                } => Some((format!("{instance}/{name}"), ty)),
+                ComponentFunc::Import {
+                    instance: None,
+                    name,
+                } => Some((name.clone(), ty)),
+                ComponentFunc::Lifted { .. } => None,
+            })
+    }
+
+    /// Returns the definitions of the [`Component`].
+    pub(crate) fn inner(&self) -> &ComponentInner {
+        &self.inner
+    }
+}
+
+/// Builds up the [`ComponentInner`] from the payloads of a validated component.
+struct ComponentBuilder<'a> {
+    /// The [`Engine`] used to compile embedded core modules.
+    engine: &'a Engine,
+    /// The bytes of the whole component.
+    bytes: &'a [u8],
+    /// The definitions built so far.
+    inner: ComponentInner,
+}
+
+impl ComponentBuilder<'_> {
+    /// Processes a single top-level `payload` of the component.
+    fn process_payload(&mut self, payload: Payload) -> Result<(), Error> {
+        match payload {
+            Payload::Version {
+                encoding: Encoding::Module,
+                range,
+                ..
+            } => Err(Error::unsupported(format_args!(
+                "core module at offset {}, use `wasmi::Module` instead",
+                range.start
+            ))),
+            Payload::Version { .. }
+            | Payload::CustomSection(_)
+            | Payload::CoreTypeSection(_)
+            | Payload::ComponentTypeSection(_)
+            | Payload::End(_) => Ok(()),
+            Payload::ModuleSection { range, .. } => {
+                let module = Module::new(self.engine, &self.bytes[range])?;
+                self.inner.modules.push(module);
+                Ok(())
+            }
+            Payload::InstanceSection(section) => {
+                for instance in section {
+                    self.process_core_instance(instance?);
+                }
+                Ok(())
+            }
+            Payload::ComponentAliasSection(section) => {
+                for alias in section {
+                    self.process_alias(alias?)?;
+                }
+                Ok(())
+            }
+            Payload::ComponentCanonicalSection(section) => {
+                for func in section {
+                    self.process_canonical(func?)?;
+                }
+                Ok(())
+            }
+            Payload::ComponentImportSection(section) => {
+                for import in section {
+                    let import = import?;
+                    self.process_import(import.name, import.ty)?;
+                }
+                Ok(())
+            }
+            Payload::ComponentExportSection(section) => {
+                for export in section {
+                    let export = export?;
+                    self.process_export(export.name, export.kind, export.index)?;
+                }
+                Ok(())
+            }
+            Payload::ComponentInstanceSection(section) => {
+                for instance in section {
+                    match instance? {
+                        wasmparser::ComponentInstance::Instantiate { .. } => {
+                            return Err(Error::unsupported("component instantiation"))
+                        }
+                        wasmparser::ComponentInstance::FromExports(exports) => {
+                            let mut funcs = BTreeMap::new();
+                            for export in exports.iter() {
+                                if export.kind != ComponentExternalKind::Func {
+                                    return Err(Error::unsupported(format!(
+                                        "instance export `{}` of kind {:?}",
+                                        export.name, export.kind
+                                    )));
+                                }
+                                funcs.insert(export.name.to_string(), export.index);
+                            }
+                            self.inner
+                                .instances
+                                .push(ComponentInstance::FromExports(funcs));
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Payload::ComponentSection { .. } => Err(Error::unsupported("nested components")),
+            Payload::ComponentStartSection { .. } => {
+                Err(Error::unsupported("component start functions"))
+            }
+            payload => Err(Error::unsupported(format!(
+                "core module payload in component: {payload:?}"
+            ))),
+        }
+    }
+
+    /// Processes a core `instance` definition.
+    fn process_core_instance(&mut self, instance: wasmparser::Instance) {
+        let instance = match instance {
+            wasmparser::Instance::Instantiate { module_index, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| match arg.kind {
+                        InstantiationArgKind::Instance => (arg.name.to_string(), arg.index),
+                    })
+                    .collect();
+                CoreInstance::Instantiate {
+                    module: module_index,
+                    args,
+                }
+            }
+            wasmparser::Instance::FromExports(exports) => {
+                let items = exports
+                    .iter()
+                    .filter_map(|export| {
+                        let item = match export.kind {
+                            ExternalKind::Func => CoreItem::Func(export.index),
+                            ExternalKind::Table => CoreItem::Table(export.index),
+                            ExternalKind::Memory => CoreItem::Memory(export.index),
+                            ExternalKind::Global => CoreItem::Global(export.index),
+                            // Tags are rejected by the validator without the exceptions proposal.
+                            ExternalKind::Tag => return None,
+                        };
+                        Some((export.name.to_string(), item))
+                    })
+                    .collect();
+                CoreInstance::FromExports(items)
+            }
+        };
+        self.inner.core_instances.push(instance);
+    }
+
+    /// Processes an `alias` definition.
+    fn process_alias(&mut self, alias: ComponentAlias) -> Result<(), Error> {
+        match alias {
+            ComponentAlias::CoreInstanceExport {
+                kind,
+                instance_index,
+                name,
+            } => {
+                let export = CoreExport {
+                    instance: instance_index,
+                    name: name.to_string(),
+                };
+                match kind {
+                    ExternalKind::Func => self.inner.core_funcs.push(CoreFunc::Export(export)),
+                    ExternalKind::Table => self.inner.core_tables.push(export),
+                    ExternalKind::Memory => self.inner.core_memories.push(export),
+                    ExternalKind::Global => self.inner.core_globals.push(export),
+                    ExternalKind::Tag => return Err(Error::unsupported("core tags")),
+                }
+                Ok(())
+            }
+            ComponentAlias::InstanceExport {
+                kind: ComponentExternalKind::Type,
+                ..
+            } => {
+                // Types are resolved by the validator.
+                Ok(())
+            }
+            ComponentAlias::InstanceExport {
+                kind: ComponentExternalKind::Func,
+                instance_index,
+                name,
+            } => {
+                let func = match &self.inner.instances[instance_index as usize] {
+                    ComponentInstance::Import(instance) => ComponentFunc::Import {
+                        instance: Some(instance.clone()),
+                        name: name.to_string(),
+                    },
+                    ComponentInstance::FromExports(funcs) => {
+                        self.inner.funcs[funcs[name] as usize].clone()
+                    }
+                };
+                self.inner.funcs.push(func);
+                Ok(())
+            }
+            ComponentAlias::InstanceExport { kind, .. } => Err(Error::unsupported(format!(
+                "instance export aliases of kind {kind:?}"
+            ))),
+            ComponentAlias::Outer { .. } => Err(Error::unsupported("outer aliases")),
+        }
+    }
+
+    /// Processes a canonical `func` definition.
+    fn process_canonical(&mut self, func: CanonicalFunction) -> Result<(), Error> {
+        match func {
+            CanonicalFunction::Lift {
+                core_func_index,
+                options,
+                ..
+            } => {
+                let options = Self::canon_options(&options)?;
+                self.inner.funcs.push(ComponentFunc::Lifted {
+                    core_func: core_func_index,
+                    options,
+                });
+            }
+            CanonicalFunction::Lower {
+                func_index,
+                options,
+            } => {
+                if let ComponentFunc::Lifted { .. } = self.inner.funcs[func_index as usize] {
+                    return Err(Error::unsupported("lowering of lifted functions"));
+                }
+                let options = Self::canon_options(&options)?;
+                let index = self.inner.lowerings.len() as u32;
+                self.inner.lowerings.push(Lowering {
+                    func: func_index,
+                    options,
+                });
+                self.inner.core_funcs.push(CoreFunc::Lowered(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the [`CanonOptions`] of the canonical `options`.
+    ///
+    /// # Errors
+    ///
+    /// If a string encoding other than UTF-8 is used.
+    fn canon_options(options: &[CanonicalOption]) -> Result<CanonOptions, Error> {
+        let mut result = CanonOptions::default();
+        for option in options {
+            match option {
+                CanonicalOption::UTF8 => {}
+                CanonicalOption::UTF16 | CanonicalOption::CompactUTF16 => {
+                    return Err(Error::unsupported("non UTF-8 string encodings"))
+                }
+                CanonicalOption::Memory(index) => result.memory = Some(*index),
+                CanonicalOption::Realloc(index) => result.realloc = Some(*index),
+                CanonicalOption::PostReturn(index) => result.post_return = Some(*index),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Processes an import of `name` with type `ty`.
+    fn process_import(&mut self, name: &str, ty: ComponentTypeRef) -> Result<(), Error> {
+        match ty {
+            ComponentTypeRef::Func(_) => self.inner.funcs.push(ComponentFunc::Import {
+                instance: None,
+                name: name.to_string(),
+            }),
+            ComponentTypeRef::Instance(_) => self
+                .inner
+                .instances
+                .push(ComponentInstance::Import(name.to_string())),
+            ComponentTypeRef::Type(..) => {}
+            ty => {
+                return Err(Error::unsupported(format!(
+                    "import `{name}` of kind {:?}",
+                    ty.kind()
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Processes an export of `name` with the item `index` of `kind`.
+    fn process_export(
+        &mut self,
+        name: &str,
+        kind: ComponentExternalKind,
+        index: u32,
+    ) -> Result<(), Error> {
+        // Exports introduce new items into their index spaces.
+        let export = match kind {
+            ComponentExternalKind::Func => {
+                let func = self.inner.funcs[index as usize].clone();
+                if let ComponentFunc::Import { .. } = func {
+                    return Err(Error::unsupported("exports of imported functions"));
+                }
+                self.inner.funcs.push(func);
+                ComponentExport::Func(index)
+            }
+            ComponentExternalKind::Instance => {
+                let instance = self.inner.instances[index as usize].clone();
+                if let ComponentInstance::Import(_) = instance {
+                    return Err(Error::unsupported("exports of imported instances"));
+                }
+                self.inner.instances.push(instance);
+                ComponentExport::Instance(index)
+            }
+            ComponentExternalKind::Type => return Ok(()),
+            kind => {
+                return Err(Error::unsupported(format!(
+                    "export `{name}` of kind {kind:?}"
+                )))
+            }
+        };
+        self.inner.exports.insert(name.to_string(), export);
+        Ok(())
+    }
+}
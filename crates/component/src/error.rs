@@ -0,0 +1,125 @@
+use crate::Type;
+use std::fmt::{self, Display};
+use wasmi::core::Trap;
+use wasmparser::BinaryReaderError;
+
+/// An error that may occur upon operating with Wasm components.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Encountered when parsing or validating a component binary fails.
+    Parser(BinaryReaderError),
+    /// Encountered a Component Model feature that is not yet supported.
+    Unsupported(String),
+    /// Encountered duplicate definitions for the same name in a [`Linker`].
+    ///
+    /// [`Linker`]: crate::Linker
+    DuplicateDefinition(String),
+    /// Encountered when no definition for an import of a component is found.
+    MissingImport(String),
+    /// Encountered when a component does not export an expected item.
+    MissingExport(String),
+    /// Encountered a value that does not match its expected component type.
+    TypeMismatch {
+        /// The expected component type of the value.
+        expected: Type,
+    },
+    /// Encountered when a component function is called with the wrong number of values.
+    ArityMismatch {
+        /// The expected number of values.
+        expected: usize,
+        /// The found number of values.
+        found: usize,
+    },
+    /// Encountered invalid data while lifting or lowering values via the canonical ABI.
+    InvalidData(String),
+    /// Encountered an error in the underlying `wasmi` runtime.
+    Wasmi(wasmi::Error),
+}
+
+impl Error {
+    /// Creates a new [`Error`] for an unsupported Component Model feature.
+    pub(crate) fn unsupported(feature: impl Display) -> Self {
+        Self::Unsupported(feature.to_string())
+    }
+
+    /// Creates a new [`Error`] for invalid canonical ABI data.
+    pub(crate) fn invalid_data(message: impl Display) -> Self {
+        Self::InvalidData(message.to_string())
+    }
+
+    /// Creates a new [`Error`] for a value that does not match the `expected` type.
+    pub(crate) fn type_mismatch(expected: &Type) -> Self {
+        Self::TypeMismatch {
+            expected: expected.clone(),
+        }
+    }
+
+    /// Converts the [`Error`] into a [`Trap`] to be returned from host functions.
+    ///
+    /// Traps raised by the underlying `wasmi` runtime are forwarded as is.
+    pub(crate) fn into_trap(self) -> Trap {
+        match self {
+            Self::Wasmi(wasmi::Error::Trap(trap)) => trap,
+            error => Trap::new(error.to_string()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parser(error) => Display::fmt(error, f),
+            Self::Unsupported(feature) => {
+                write!(f, "unsupported Component Model feature: {feature}")
+            }
+            Self::DuplicateDefinition(name) => {
+                write!(f, "encountered duplicate definition for `{name}`")
+            }
+            Self::MissingImport(name) => {
+                write!(f, "cannot find definition for import `{name}`")
+            }
+            Self::MissingExport(name) => write!(f, "cannot find export `{name}`"),
+            Self::TypeMismatch { expected } => {
+                write!(f, "value does not match the expected type `{expected}`")
+            }
+            Self::ArityMismatch { expected, found } => {
+                write!(f, "expected {expected} values but found {found}")
+            }
+            Self::InvalidData(message) => write!(f, "invalid canonical ABI data: {message}"),
+            Self::Wasmi(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl From<BinaryReaderError> for Error {
+    fn from(error: BinaryReaderError) -> Self {
+        Self::Parser(error)
+    }
+}
+
+impl From<wasmi::Error> for Error {
+    fn from(error: wasmi::Error) -> Self {
+        Self::Wasmi(error)
+    }
+}
+
+impl From<Trap> for Error {
+    fn from(error: Trap) -> Self {
+        Self::Wasmi(wasmi::Error::Trap(error))
+    }
+}
+
+impl From<Error> for Trap {
+    fn from(error: Error) -> Self {
+        error.into_trap()
+    }
+}
+
+impl From<wasmi::errors::LinkerError> for Error {
+    fn from(error: wasmi::errors::LinkerError) -> Self {
+        Self::Wasmi(error.into())
+    }
+}
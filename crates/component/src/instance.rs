@@ -0,0 +1,178 @@
+use crate::{
+    abi::{self, Options, MAX_FLAT_PARAMS, MAX_FLAT_RESULTS},
+    Error,
+    FuncType,
+    Val,
+};
+use std::{collections::BTreeMap, sync::Arc};
+use wasmi::{core::ValueType, AsContextMut, Value};
+
+/// An instantiated Wasm component.
+///
+/// Created via [`Linker::instantiate`](crate::Linker::instantiate).
+#[derive(Debug, Clone)]
+pub struct Instance {
+    /// The exported functions and instances of the component.
+    exports: BTreeMap<String, Export>,
+}
+
+/// An export of an [`Instance`].
+#[derive(Debug, Clone)]
+pub(crate) enum Export {
+    /// An exported component function.
+    Func(Func),
+    /// An exported instance of component functions.
+    Instance(BTreeMap<String, Func>),
+}
+
+impl Instance {
+    /// Creates a new [`Instance`] from its `exports`.
+    pub(crate) fn new(exports: BTreeMap<String, Export>) -> Self {
+        Self { exports }
+    }
+
+    /// Returns the exported function `name` if any.
+    pub fn get_func(&self, name: &str) -> Option<Func> {
+        match self.exports.get(name) {
+            Some(Export::Func(func)) => Some(func.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the function `name` of the exported instance `instance` if any.
+    pub fn get_instance_func(&self, instance: &str, name: &str) -> Option<Func> {
+        match self.exports.get(instance) {
+            Some(Export::Instance(funcs)) => funcs.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Returns the names of all exported functions.
+    ///
+    /// Functions of exported instances are named `instance/func`.
+    pub fn exported_funcs(&self) -> impl Iterator<Item = (String, &Func)> + '_ {
+        self.exports.iter().flat_map(|(name, export)| {
+            let funcs: Vec<_> = match export {
+                Export::Func(func) => vec![(name.clone(), func)],
+                Export::Instance(funcs) => funcs
+                    .iter()
+                    .map(|(func_name, func)| (format!("{name}/{func_name}"), func))
+                    .collect(),
+            };
+            funcs
+        })
+    }
+}
+
+/// A component function lifted from a core [`wasmi::Func`].
+#[derive(Debug, Clone)]
+pub struct Func {
+    inner: Arc<FuncInner>,
+}
+
+/// The internals of a lifted [`Func`].
+#[derive(Debug)]
+struct FuncInner {
+    /// The lifted core function.
+    core_func: wasmi::Func,
+    /// The component type of the function.
+    ty: FuncType,
+    /// The canonical ABI options of the lifting.
+    options: Options,
+    /// The core function called after the results have been lifted if any.
+    post_return: Option<wasmi::Func>,
+}
+
+impl Func {
+    /// Creates a new [`Func`] lifting the `core_func`.
+    pub(crate) fn new(
+        core_func: wasmi::Func,
+        ty: FuncType,
+        options: Options,
+        post_return: Option<wasmi::Func>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(FuncInner {
+                core_func,
+                ty,
+                options,
+                post_return,
+            }),
+        }
+    }
+
+    /// Returns the component [`FuncType`] of the [`Func`].
+    pub fn ty(&self) -> &FuncType {
+        &self.inner.ty
+    }
+
+    /// Calls the [`Func`] with the given `params` and writes its `results`.
+    ///
+    /// # Errors
+    ///
+    /// - If the number of `params` or `results` does not match the [`FuncType`].
+    /// - If any of the `params` does not match its parameter type.
+    /// - If lifting or lowering values via the canonical ABI fails.
+    /// - If the execution of the core function traps.
+    pub fn call(
+        &self,
+        mut ctx: impl AsContextMut,
+        params: &[Val],
+        results: &mut [Val],
+    ) -> Result<(), Error> {
+        let FuncInner {
+            core_func,
+            ty,
+            options,
+            post_return,
+        } = &*self.inner;
+        check_arity(ty.params().len(), params.len())?;
+        check_arity(ty.results().len(), results.len())?;
+        let inputs = abi::lower_params(&mut ctx, options, ty.param_types(), params)?;
+        let len_outputs = core_func.ty(&ctx).results().len();
+        let mut outputs = vec![Value::I32(0); len_outputs];
+        core_func.call(&mut ctx, &inputs, &mut outputs)?;
+        abi::lift_values(
+            &ctx,
+            options,
+            ty.result_types(),
+            &outputs,
+            MAX_FLAT_RESULTS,
+            results,
+        )?;
+        if let Some(post_return) = post_return {
+            post_return.call(&mut ctx, &outputs, &mut [])?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the `found` number of values matches the `expected` number.
+///
+/// # Errors
+///
+/// If `found` and `expected` differ.
+pub(crate) fn check_arity(expected: usize, found: usize) -> Result<(), Error> {
+    if expected != found {
+        return Err(Error::ArityMismatch { expected, found });
+    }
+    Ok(())
+}
+
+/// Returns the core [`wasmi::FuncType`] of a lowered function of type `ty`.
+///
+/// Parameters that exceed [`MAX_FLAT_PARAMS`] are passed via a pointer and
+/// results that exceed [`MAX_FLAT_RESULTS`] are written to a pointer passed
+/// as the last parameter.
+pub(crate) fn lowered_func_type(ty: &FuncType) -> wasmi::FuncType {
+    let mut params = abi::flatten_all(ty.param_types());
+    if params.len() > MAX_FLAT_PARAMS {
+        params = vec![ValueType::I32];
+    }
+    let mut results = abi::flatten_all(ty.result_types());
+    if results.len() > MAX_FLAT_RESULTS {
+        params.push(ValueType::I32);
+        results = Vec::new();
+    }
+    wasmi::FuncType::new(params, results)
+}
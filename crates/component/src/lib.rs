@@ -0,0 +1,47 @@
+//! Initial WebAssembly Component Model support for the `wasmi` interpreter.
+//!
+//! Components are parsed via [`Component::new`] and instantiated with host
+//! functions defined in a [`Linker`]. Values are passed as dynamically typed
+//! [`Val`]s and lifted and lowered via the canonical ABI on top of core
+//! [`wasmi::Func`] calls and [`wasmi::Memory`] accesses.
+//!
+//! The [`bindgen`] module generates typed host bindings from WIT documents
+//! so that host functions no longer need to marshal bytes through `i32` pointers.
+//!
+//! # Supported Subset
+//!
+//! - All value types except `union` and refined variant cases.
+//! - The `utf8` string encoding with the `memory`, `realloc` and `post-return` options.
+//! - Imports of functions and instances of functions.
+//! - Embedded core modules and exports of lifted functions and instances thereof.
+
+#![warn(
+    clippy::cast_lossless,
+    clippy::missing_errors_doc,
+    clippy::used_underscore_binding,
+    clippy::redundant_closure_for_method_calls,
+    clippy::type_repetition_in_bounds,
+    clippy::inconsistent_struct_constructor,
+    clippy::default_trait_access,
+    clippy::items_after_statements
+)]
+
+mod abi;
+pub mod bindgen;
+mod component;
+mod error;
+mod instance;
+mod linker;
+mod types;
+mod values;
+pub mod wit;
+
+pub use self::{
+    component::Component,
+    error::Error,
+    instance::{Func, Instance},
+    linker::{Linker, LinkerInstance},
+    types::{Case, Field, FuncType, Type},
+    values::{ComponentValue, Val},
+};
+pub use wasmi;
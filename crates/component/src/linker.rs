@@ -0,0 +1,431 @@
+use crate::{
+    abi::{self, Options, MAX_FLAT_PARAMS, MAX_FLAT_RESULTS},
+    component::{
+        CanonOptions,
+        ComponentExport,
+        ComponentFunc,
+        ComponentInner,
+        ComponentInstance,
+        CoreExport,
+        CoreFunc,
+        CoreInstance,
+        CoreItem,
+    },
+    instance::{lowered_func_type, Export},
+    Component,
+    Error,
+    Func,
+    FuncType,
+    Instance,
+    Val,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug},
+    sync::{Arc, Mutex, PoisonError},
+};
+use wasmi::{core::Trap, AsContext, AsContextMut, Engine, Extern, StoreContextMut, Value};
+
+/// A host function defined in a [`Linker`].
+type HostFunc<T> =
+    Arc<dyn Fn(StoreContextMut<T>, &[Val], &mut [Val]) -> Result<(), Trap> + Send + Sync>;
+
+/// A linker used to define host functions for the imports of Wasm components.
+pub struct Linker<T> {
+    /// The underlying [`Engine`] of the [`Linker`].
+    engine: Engine,
+    /// The top-level host functions by name.
+    funcs: BTreeMap<String, HostFunc<T>>,
+    /// The host functions of instances by instance and function name.
+    instances: BTreeMap<String, BTreeMap<String, HostFunc<T>>>,
+}
+
+impl<T> Debug for Linker<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let instances = self
+            .instances
+            .iter()
+            .map(|(name, funcs)| (name, funcs.keys().collect::<Vec<_>>()))
+            .collect::<BTreeMap<_, _>>();
+        f.debug_struct("Linker")
+            .field("engine", &self.engine)
+            .field("funcs", &self.funcs.keys().collect::<Vec<_>>())
+            .field("instances", &instances)
+            .finish()
+    }
+}
+
+/// Defines the host functions of an instance imported by Wasm components.
+///
+/// Created via [`Linker::instance`].
+pub struct LinkerInstance<'a, T> {
+    /// The name of the instance.
+    name: &'a str,
+    /// The host functions of the instance.
+    funcs: &'a mut BTreeMap<String, HostFunc<T>>,
+}
+
+/// Inserts the host function `func` under `name` into `funcs`.
+///
+/// # Errors
+///
+/// If there already is a definition under `name`.
+fn insert<T>(
+    funcs: &mut BTreeMap<String, HostFunc<T>>,
+    name: &str,
+    display_name: impl FnOnce() -> String,
+    func: HostFunc<T>,
+) -> Result<(), Error> {
+    if funcs.contains_key(name) {
+        return Err(Error::DuplicateDefinition(display_name()));
+    }
+    funcs.insert(name.to_string(), func);
+    Ok(())
+}
+
+impl<T> Linker<T> {
+    /// Creates a new [`Linker`].
+    pub fn new(engine: &Engine) -> Self {
+        Self {
+            engine: engine.clone(),
+            funcs: BTreeMap::new(),
+            instances: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the underlying [`Engine`] of the [`Linker`].
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Defines the top-level host function `name`.
+    ///
+    /// The `func` receives the lifted parameters of a call and writes its results
+    /// which are then lowered into the memory of the calling component.
+    ///
+    /// # Errors
+    ///
+    /// If there already is a definition under `name`.
+    pub fn func_new(
+        &mut self,
+        name: &str,
+        func: impl Fn(StoreContextMut<T>, &[Val], &mut [Val]) -> Result<(), Trap>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Result<&mut Self, Error> {
+        insert(&mut self.funcs, name, || name.to_string(), Arc::new(func))?;
+        Ok(self)
+    }
+
+    /// Returns a [`LinkerInstance`] to define the host functions of the instance `name`.
+    pub fn instance<'a>(&'a mut self, name: &'a str) -> LinkerInstance<'a, T> {
+        let funcs = self.instances.entry(name.to_string()).or_default();
+        LinkerInstance { name, funcs }
+    }
+
+    /// Returns the host function for the imported component function `func` if any.
+    fn resolve_import(&self, func: &ComponentFunc) -> Result<HostFunc<T>, Error> {
+        let (instance, name) = match func {
+            ComponentFunc::Import { instance, name } => (instance, name),
+            ComponentFunc::Lifted { .. } => {
+                return Err(Error::unsupported("lowering of lifted functions"))
+            }
+        };
+        let func = match instance {
+            Some(instance) => self
+                .instances
+                .get(instance)
+                .and_then(|funcs| funcs.get(name)),
+            None => self.funcs.get(name),
+        };
+        func.cloned().ok_or_else(|| match instance {
+            Some(instance) => Error::MissingImport(format!("{instance}/{name}")),
+            None => Error::MissingImport(name.clone()),
+        })
+    }
+}
+
+impl<T: 'static> Linker<T> {
+    /// Instantiates the `component` using the host functions of this [`Linker`].
+    ///
+    /// Embedded core modules are instantiated and started in the order of their
+    /// definition within the `component`.
+    ///
+    /// # Panics
+    ///
+    /// If the [`Engine`] of this [`Linker`], the `component` and `context` are not the same.
+    ///
+    /// # Errors
+    ///
+    /// - If the [`Linker`] does not define all imports of the `component`.
+    /// - If instantiating or starting any of the core modules fails.
+    pub fn instantiate(
+        &self,
+        mut context: impl AsContextMut<UserState = T>,
+        component: &Component,
+    ) -> Result<Instance, Error> {
+        assert!(Engine::same(self.engine(), component.engine()));
+        assert!(Engine::same(self.engine(), context.as_context().engine()));
+        let inner = component.inner();
+        let mut instantiator = Instantiator {
+            inner,
+            lowered: Vec::with_capacity(inner.lowerings.len()),
+            core_instances: Vec::with_capacity(inner.core_instances.len()),
+        };
+        let mut slots = Vec::with_capacity(inner.lowerings.len());
+        for lowering in &inner.lowerings {
+            let host = self.resolve_import(&inner.funcs[lowering.func as usize])?;
+            let ty = inner.func_types[lowering.func as usize].clone();
+            let slot = Arc::new(Mutex::new(Options::default()));
+            slots.push(slot.clone());
+            let core_ty = lowered_func_type(&ty);
+            let func =
+                wasmi::Func::new(&mut context, core_ty, move |mut caller, inputs, outputs| {
+                    let options = *slot.lock().unwrap_or_else(PoisonError::into_inner);
+                    call_host(
+                        &host,
+                        &ty,
+                        &options,
+                        caller.as_context_mut(),
+                        inputs,
+                        outputs,
+                    )
+                    .map_err(Error::into_trap)
+                });
+            instantiator.lowered.push(func);
+        }
+        for index in 0..inner.core_instances.len() {
+            instantiator.instantiate_core(&mut context, index)?;
+        }
+        for (lowering, slot) in inner.lowerings.iter().zip(slots) {
+            let options = instantiator.options(&context, &lowering.options)?;
+            *slot.lock().unwrap_or_else(PoisonError::into_inner) = options;
+        }
+        let mut exports = BTreeMap::new();
+        for (name, export) in &inner.exports {
+            let export = match *export {
+                ComponentExport::Func(index) => Export::Func(instantiator.func(&context, index)?),
+                ComponentExport::Instance(index) => match &inner.instances[index as usize] {
+                    ComponentInstance::FromExports(funcs) => Export::Instance(
+                        funcs
+                            .iter()
+                            .map(|(name, index)| {
+                                Ok((name.clone(), instantiator.func(&context, *index)?))
+                            })
+                            .collect::<Result<_, Error>>()?,
+                    ),
+                    ComponentInstance::Import(_) => {
+                        return Err(Error::unsupported("exports of imported instances"))
+                    }
+                },
+            };
+            exports.insert(name.clone(), export);
+        }
+        Ok(Instance::new(exports))
+    }
+}
+
+impl<'a, T> LinkerInstance<'a, T> {
+    /// Defines the host function `name` of the instance.
+    ///
+    /// See [`Linker::func_new`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If there already is a definition under `name` within the instance.
+    pub fn func_new(
+        &mut self,
+        name: &str,
+        func: impl Fn(StoreContextMut<T>, &[Val], &mut [Val]) -> Result<(), Trap>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Result<&mut Self, Error> {
+        let instance = self.name;
+        insert(
+            self.funcs,
+            name,
+            || format!("{instance}/{name}"),
+            Arc::new(func),
+        )?;
+        Ok(self)
+    }
+}
+
+/// Calls the `host` function of type `ty` with the flattened core `inputs`.
+///
+/// Lifts the parameters from `inputs` and lowers the results into `outputs`
+/// or into the memory pointed to by the last input if they do not fit.
+fn call_host<T>(
+    host: &HostFunc<T>,
+    ty: &FuncType,
+    options: &Options,
+    mut ctx: StoreContextMut<T>,
+    inputs: &[Value],
+    outputs: &mut [Value],
+) -> Result<(), Error> {
+    let mut params = vec![Val::Bool(false); ty.params().len()];
+    abi::lift_values(
+        &ctx,
+        options,
+        ty.param_types(),
+        inputs,
+        MAX_FLAT_PARAMS,
+        &mut params,
+    )?;
+    let mut results = vec![Val::Bool(false); ty.results().len()];
+    host(ctx.as_context_mut(), &params, &mut results)?;
+    if abi::flatten_all(ty.result_types()).len() <= MAX_FLAT_RESULTS {
+        let mut flat = Vec::with_capacity(outputs.len());
+        for (ty, val) in ty.result_types().zip(&results) {
+            abi::lower_flat(&mut ctx, options, ty, val, &mut flat)?;
+        }
+        outputs.clone_from_slice(&flat);
+    } else {
+        let retptr = match inputs.last() {
+            Some(Value::I32(retptr)) => *retptr as u32,
+            _ => return Err(Error::invalid_data("missing return pointer")),
+        };
+        let tuple = abi::tuple_of(ty.result_types());
+        if retptr % tuple.align() != 0 {
+            return Err(Error::invalid_data(format!(
+                "misaligned return pointer {retptr}"
+            )));
+        }
+        abi::store(&mut ctx, options, &tuple, &Val::Tuple(results), retptr)?;
+    }
+    Ok(())
+}
+
+/// An instantiated core instance of a component.
+#[derive(Debug)]
+enum CoreInstanceItems {
+    /// An instantiated core module.
+    Instance(wasmi::Instance),
+    /// A core instance bundling core items.
+    Exports(BTreeMap<String, Extern>),
+}
+
+/// Resolves the items of a [`Component`] during instantiation.
+struct Instantiator<'a> {
+    /// The definitions of the instantiated component.
+    inner: &'a ComponentInner,
+    /// The core functions of all lowerings.
+    lowered: Vec<wasmi::Func>,
+    /// The core instances instantiated so far.
+    core_instances: Vec<CoreInstanceItems>,
+}
+
+impl Instantiator<'_> {
+    /// Instantiates the core instance at `index`.
+    ///
+    /// All core instances with lower indices must have been instantiated before.
+    fn instantiate_core<T>(
+        &mut self,
+        mut context: impl AsContextMut<UserState = T>,
+        index: usize,
+    ) -> Result<(), Error> {
+        let items = match &self.inner.core_instances[index] {
+            CoreInstance::Instantiate { module, args } => {
+                let mut linker = <wasmi::Linker<T>>::new(context.as_context().engine());
+                for (name, index) in args {
+                    match &self.core_instances[*index as usize] {
+                        CoreInstanceItems::Instance(instance) => {
+                            linker.instance(&context, name, *instance)?;
+                        }
+                        CoreInstanceItems::Exports(items) => {
+                            for (item_name, item) in items {
+                                linker.define(name, item_name, *item)?;
+                            }
+                        }
+                    }
+                }
+                let instance = linker
+                    .instantiate(&mut context, &self.inner.modules[*module as usize])?
+                    .start(&mut context)?;
+                CoreInstanceItems::Instance(instance)
+            }
+            CoreInstance::FromExports(items) => {
+                let items = items
+                    .iter()
+                    .map(|(name, item)| {
+                        let item = match *item {
+                            CoreItem::Func(index) => Extern::Func(self.core_func(&context, index)?),
+                            CoreItem::Table(index) => {
+                                self.core_export(&context, &self.inner.core_tables[index as usize])?
+                            }
+                            CoreItem::Memory(index) => self
+                                .core_export(&context, &self.inner.core_memories[index as usize])?,
+                            CoreItem::Global(index) => self
+                                .core_export(&context, &self.inner.core_globals[index as usize])?,
+                        };
+                        Ok((name.clone(), item))
+                    })
+                    .collect::<Result<_, Error>>()?;
+                CoreInstanceItems::Exports(items)
+            }
+        };
+        self.core_instances.push(items);
+        Ok(())
+    }
+
+    /// Returns the item of the core `export`.
+    fn core_export(&self, context: impl AsContext, export: &CoreExport) -> Result<Extern, Error> {
+        let item = match &self.core_instances[export.instance as usize] {
+            CoreInstanceItems::Instance(instance) => instance.get_export(&context, &export.name),
+            CoreInstanceItems::Exports(items) => items.get(&export.name).cloned(),
+        };
+        item.ok_or_else(|| Error::MissingExport(export.name.clone()))
+    }
+
+    /// Returns the core function at `index`.
+    fn core_func(&self, context: impl AsContext, index: u32) -> Result<wasmi::Func, Error> {
+        match &self.inner.core_funcs[index as usize] {
+            CoreFunc::Export(export) => self
+                .core_export(context, export)?
+                .into_func()
+                .ok_or_else(|| Error::MissingExport(export.name.clone())),
+            CoreFunc::Lowered(index) => Ok(self.lowered[*index as usize]),
+        }
+    }
+
+    /// Returns the resolved canonical ABI [`Options`] of the `options`.
+    fn options(&self, context: impl AsContext, options: &CanonOptions) -> Result<Options, Error> {
+        let memory = match options.memory {
+            Some(index) => {
+                let export = &self.inner.core_memories[index as usize];
+                let memory = self
+                    .core_export(&context, export)?
+                    .into_memory()
+                    .ok_or_else(|| Error::MissingExport(export.name.clone()))?;
+                Some(memory)
+            }
+            None => None,
+        };
+        let realloc = options
+            .realloc
+            .map(|index| self.core_func(&context, index))
+            .transpose()?;
+        Ok(Options { memory, realloc })
+    }
+
+    /// Returns the exported component function at `index`.
+    fn func(&self, context: impl AsContext, index: u32) -> Result<Func, Error> {
+        match &self.inner.funcs[index as usize] {
+            ComponentFunc::Lifted { core_func, options } => {
+                let core_func = self.core_func(&context, *core_func)?;
+                let post_return = options
+                    .post_return
+                    .map(|index| self.core_func(&context, index))
+                    .transpose()?;
+                let options = self.options(&context, options)?;
+                let ty = self.inner.func_types[index as usize].clone();
+                Ok(Func::new(core_func, ty, options, post_return))
+            }
+            ComponentFunc::Import { .. } => {
+                Err(Error::unsupported("exports of imported functions"))
+            }
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use crate::Error;
+use std::fmt::{self, Display};
+use wasmparser::{
+    types::{self as parser, TypesRef},
+    PrimitiveValType,
+};
+
+/// The type of a component value.
+///
+/// Component types are structural, so named types of a WIT document
+/// are represented by their underlying definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+    /// A boolean value.
+    Bool,
+    /// A signed 8-bit integer.
+    S8,
+    /// An unsigned 8-bit integer.
+    U8,
+    /// A signed 16-bit integer.
+    S16,
+    /// An unsigned 16-bit integer.
+    U16,
+    /// A signed 32-bit integer.
+    S32,
+    /// An unsigned 32-bit integer.
+    U32,
+    /// A signed 64-bit integer.
+    S64,
+    /// An unsigned 64-bit integer.
+    U64,
+    /// A 32-bit floating point number.
+    Float32,
+    /// A 64-bit floating point number.
+    Float64,
+    /// A Unicode scalar value.
+    Char,
+    /// A Unicode string.
+    String,
+    /// A list of values of the same type.
+    List(Box<Type>),
+    /// A record of named fields.
+    Record(Vec<Field>),
+    /// A tuple of unnamed fields.
+    Tuple(Vec<Type>),
+    /// A variant of named cases with optional payloads.
+    Variant(Vec<Case>),
+    /// An enumeration of named cases without payloads.
+    Enum(Vec<String>),
+    /// An optional value.
+    Option(Box<Type>),
+    /// A result with optional `ok` and `err` payloads.
+    Result {
+        /// The payload type of the `ok` case if any.
+        ok: Option<Box<Type>>,
+        /// The payload type of the `err` case if any.
+        err: Option<Box<Type>>,
+    },
+    /// A set of named flags.
+    Flags(Vec<String>),
+}
+
+/// A named field of a [`Type::Record`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// The name of the field.
+    pub name: String,
+    /// The type of the field.
+    pub ty: Type,
+}
+
+/// A named case of a [`Type::Variant`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    /// The name of the case.
+    pub name: String,
+    /// The payload type of the case if any.
+    pub ty: Option<Type>,
+}
+
+impl Type {
+    /// Creates a [`Type`] from the validated `ty` of a component.
+    ///
+    /// # Errors
+    ///
+    /// If `ty` uses a type that is not yet supported.
+    pub(crate) fn from_parser(
+        types: TypesRef,
+        ty: &parser::ComponentValType,
+    ) -> Result<Self, Error> {
+        let id = match ty {
+            parser::ComponentValType::Primitive(ty) => return Ok(Self::from_primitive(*ty)),
+            parser::ComponentValType::Type(id) => *id,
+        };
+        let defined = match types.type_from_id(id) {
+            Some(parser::Type::Defined(defined)) => defined,
+            _ => return Err(Error::unsupported("non-value type used as value type")),
+        };
+        let convert = |ty: &parser::ComponentValType| Self::from_parser(types, ty);
+        let convert_opt = |ty: &Option<parser::ComponentValType>| {
+            ty.as_ref()
+                .map(convert)
+                .transpose()
+                .map(|ty| ty.map(Box::new))
+        };
+        let ty = match defined {
+            parser::ComponentDefinedType::Primitive(ty) => Self::from_primitive(*ty),
+            parser::ComponentDefinedType::Record(record) => Self::Record(
+                record
+                    .fields
+                    .iter()
+                    .map(|(name, ty)| {
+                        Ok(Field {
+                            name: name.to_string(),
+                            ty: convert(ty)?,
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?,
+            ),
+            parser::ComponentDefinedType::Variant(variant) => Self::Variant(
+                variant
+                    .cases
+                    .iter()
+                    .map(|(name, case)| {
+                        if case.refines.is_some() {
+                            return Err(Error::unsupported("refined variant cases"));
+                        }
+                        Ok(Case {
+                            name: name.to_string(),
+                            ty: case.ty.as_ref().map(convert).transpose()?,
+                        })
+                    })
+                    .collect::<Result<_, Error>>()?,
+            ),
+            parser::ComponentDefinedType::List(ty) => Self::List(Box::new(convert(ty)?)),
+            parser::ComponentDefinedType::Tuple(tuple) => Self::Tuple(
+                tuple
+                    .types
+                    .iter()
+                    .map(convert)
+                    .collect::<Result<_, Error>>()?,
+            ),
+            parser::ComponentDefinedType::Flags(names) => {
+                Self::Flags(names.iter().map(ToString::to_string).collect())
+            }
+            parser::ComponentDefinedType::Enum(names) => {
+                Self::Enum(names.iter().map(ToString::to_string).collect())
+            }
+            parser::ComponentDefinedType::Union(_) => {
+                return Err(Error::unsupported("union types"))
+            }
+            parser::ComponentDefinedType::Option(ty) => Self::Option(Box::new(convert(ty)?)),
+            parser::ComponentDefinedType::Result { ok, err } => Self::Result {
+                ok: convert_opt(ok)?,
+                err: convert_opt(err)?,
+            },
+        };
+        Ok(ty)
+    }
+
+    /// Creates a [`Type`] from the primitive value type `ty`.
+    fn from_primitive(ty: PrimitiveValType) -> Self {
+        match ty {
+            PrimitiveValType::Bool => Self::Bool,
+            PrimitiveValType::S8 => Self::S8,
+            PrimitiveValType::U8 => Self::U8,
+            PrimitiveValType::S16 => Self::S16,
+            PrimitiveValType::U16 => Self::U16,
+            PrimitiveValType::S32 => Self::S32,
+            PrimitiveValType::U32 => Self::U32,
+            PrimitiveValType::S64 => Self::S64,
+            PrimitiveValType::U64 => Self::U64,
+            PrimitiveValType::Float32 => Self::Float32,
+            PrimitiveValType::Float64 => Self::Float64,
+            PrimitiveValType::Char => Self::Char,
+            PrimitiveValType::String => Self::String,
+        }
+    }
+}
+
+/// Writes the `items` separated by commas using `write` for each item.
+fn write_list<T>(
+    f: &mut fmt::Formatter,
+    items: impl IntoIterator<Item = T>,
+    mut write: impl FnMut(&mut fmt::Formatter, T) -> fmt::Result,
+) -> fmt::Result {
+    for (n, item) in items.into_iter().enumerate() {
+        if n != 0 {
+            write!(f, ", ")?;
+        }
+        write(f, item)?;
+    }
+    Ok(())
+}
+
+impl Display for Type {
+    /// Formats the [`Type`] using the WIT syntax.
+    ///
+    /// Records, variants, enums and flags have no name and are written inline.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bool => write!(f, "bool"),
+            Self::S8 => write!(f, "s8"),
+            Self::U8 => write!(f, "u8"),
+            Self::S16 => write!(f, "s16"),
+            Self::U16 => write!(f, "u16"),
+            Self::S32 => write!(f, "s32"),
+            Self::U32 => write!(f, "u32"),
+            Self::S64 => write!(f, "s64"),
+            Self::U64 => write!(f, "u64"),
+            Self::Float32 => write!(f, "float32"),
+            Self::Float64 => write!(f, "float64"),
+            Self::Char => write!(f, "char"),
+            Self::String => write!(f, "string"),
+            Self::List(ty) => write!(f, "list<{ty}>"),
+            Self::Record(fields) => {
+                write!(f, "record {{ ")?;
+                write_list(f, fields, |f, field| {
+                    write!(f, "{}: {}", field.name, field.ty)
+                })?;
+                write!(f, " }}")
+            }
+            Self::Tuple(types) => {
+                write!(f, "tuple<")?;
+                write_list(f, types, |f, ty| write!(f, "{ty}"))?;
+                write!(f, ">")
+            }
+            Self::Variant(cases) => {
+                write!(f, "variant {{ ")?;
+                write_list(f, cases, |f, case| match &case.ty {
+                    Some(ty) => write!(f, "{}({ty})", case.name),
+                    None => write!(f, "{}", case.name),
+                })?;
+                write!(f, " }}")
+            }
+            Self::Enum(names) => {
+                write!(f, "enum {{ ")?;
+                write_list(f, names, |f, name| write!(f, "{name}"))?;
+                write!(f, " }}")
+            }
+            Self::Option(ty) => write!(f, "option<{ty}>"),
+            Self::Result { ok, err } => match (ok, err) {
+                (None, None) => write!(f, "result"),
+                (Some(ok), None) => write!(f, "result<{ok}>"),
+                (None, Some(err)) => write!(f, "result<_, {err}>"),
+                (Some(ok), Some(err)) => write!(f, "result<{ok}, {err}>"),
+            },
+            Self::Flags(names) => {
+                write!(f, "flags {{ ")?;
+                write_list(f, names, |f, name| write!(f, "{name}"))?;
+                write!(f, " }}")
+            }
+        }
+    }
+}
+
+/// The type of a component function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncType {
+    /// The named parameters of the function.
+    params: Vec<(String, Type)>,
+    /// The optionally named results of the function.
+    results: Vec<(Option<String>, Type)>,
+}
+
+impl FuncType {
+    /// Creates a new [`FuncType`] from its named `params` and optionally named `results`.
+    pub fn new(
+        params: impl IntoIterator<Item = (String, Type)>,
+        results: impl IntoIterator<Item = (Option<String>, Type)>,
+    ) -> Self {
+        Self {
+            params: params.into_iter().collect(),
+            results: results.into_iter().collect(),
+        }
+    }
+
+    /// Creates a [`FuncType`] from the validated `ty` of a component.
+    ///
+    /// # Errors
+    ///
+    /// If `ty` uses a type that is not yet supported.
+    pub(crate) fn from_parser(
+        types: TypesRef,
+        ty: &parser::ComponentFuncType,
+    ) -> Result<Self, Error> {
+        let params = ty
+            .params
+            .iter()
+            .map(|(name, ty)| Ok((name.to_string(), Type::from_parser(types, ty)?)))
+            .collect::<Result<_, Error>>()?;
+        let results = ty
+            .results
+            .iter()
+            .map(|(name, ty)| {
+                let name = name.as_ref().map(ToString::to_string);
+                Ok((name, Type::from_parser(types, ty)?))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { params, results })
+    }
+
+    /// Returns the named parameters of the function.
+    pub fn params(&self) -> &[(String, Type)] {
+        &self.params
+    }
+
+    /// Returns the optionally named results of the function.
+    pub fn results(&self) -> &[(Option<String>, Type)] {
+        &self.results
+    }
+
+    /// Returns an iterator over the parameter types of the function.
+    pub(crate) fn param_types(&self) -> impl Iterator<Item = &Type> + Clone {
+        self.params.iter().map(|(_, ty)| ty)
+    }
+
+    /// Returns an iterator over the result types of the function.
+    pub(crate) fn result_types(&self) -> impl Iterator<Item = &Type> + Clone {
+        self.results.iter().map(|(_, ty)| ty)
+    }
+}
+
+impl Display for FuncType {
+    /// Formats the [`FuncType`] using the WIT syntax.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "func(")?;
+        write_list(f, &self.params, |f, (name, ty)| write!(f, "{name}: {ty}"))?;
+        write!(f, ")")?;
+        match self.results.as_slice() {
+            [] => Ok(()),
+            [(None, ty)] => write!(f, " -> {ty}"),
+            results => {
+                write!(f, " -> (")?;
+                write_list(f, results, |f, (name, ty)| match name {
+                    Some(name) => write!(f, "{name}: {ty}"),
+                    None => write!(f, "{ty}"),
+                })?;
+                write!(f, ")")
+            }
+        }
+    }
+}
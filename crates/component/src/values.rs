@@ -0,0 +1,337 @@
+use crate::{types::Field, Error, Type};
+
+/// A dynamically typed component value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Val {
+    /// A boolean value.
+    Bool(bool),
+    /// A signed 8-bit integer.
+    S8(i8),
+    /// An unsigned 8-bit integer.
+    U8(u8),
+    /// A signed 16-bit integer.
+    S16(i16),
+    /// An unsigned 16-bit integer.
+    U16(u16),
+    /// A signed 32-bit integer.
+    S32(i32),
+    /// An unsigned 32-bit integer.
+    U32(u32),
+    /// A signed 64-bit integer.
+    S64(i64),
+    /// An unsigned 64-bit integer.
+    U64(u64),
+    /// A 32-bit floating point number.
+    Float32(f32),
+    /// A 64-bit floating point number.
+    Float64(f64),
+    /// A Unicode scalar value.
+    Char(char),
+    /// A Unicode string.
+    String(String),
+    /// A list of values of the same type.
+    List(Vec<Val>),
+    /// A record of named fields in the order of their type.
+    Record(Vec<(String, Val)>),
+    /// A tuple of unnamed fields.
+    Tuple(Vec<Val>),
+    /// A variant case with its optional payload.
+    Variant(String, Option<Box<Val>>),
+    /// An enumeration case.
+    Enum(String),
+    /// An optional value.
+    Option(Option<Box<Val>>),
+    /// A result with optional `ok` and `err` payloads.
+    Result(Result<Option<Box<Val>>, Option<Box<Val>>>),
+    /// The names of all set flags.
+    Flags(Vec<String>),
+}
+
+/// Rust types that can be converted to and from component values.
+///
+/// This is used by the bindings generated via [`bindgen`](crate::bindgen).
+pub trait ComponentValue: Sized {
+    /// Returns the component [`Type`] of `Self`.
+    fn ty() -> Type;
+
+    /// Converts `self` into a component value.
+    fn into_val(self) -> Val;
+
+    /// Converts the component value `val` into `Self`.
+    ///
+    /// # Errors
+    ///
+    /// If `val` does not match the component [`Type`] of `Self`.
+    fn from_val(val: Val) -> Result<Self, Error>;
+
+    /// Returns the payload type of `Self` in variants, options and results.
+    ///
+    /// Only the unit type `()` has no payload.
+    fn payload_ty() -> Option<Type> {
+        Some(Self::ty())
+    }
+
+    /// Converts `self` into a payload of variants, options and results.
+    fn into_payload(self) -> Option<Box<Val>> {
+        Some(Box::new(self.into_val()))
+    }
+
+    /// Converts the `payload` of variants, options and results into `Self`.
+    ///
+    /// # Errors
+    ///
+    /// If `payload` does not match the payload type of `Self`.
+    fn from_payload(payload: Option<Box<Val>>) -> Result<Self, Error> {
+        match payload {
+            Some(val) => Self::from_val(*val),
+            None => Err(Error::type_mismatch(&Self::ty())),
+        }
+    }
+}
+
+macro_rules! impl_component_value {
+    ( $( $rust:ty => $variant:ident ),* $(,)? ) => {
+        $(
+            impl ComponentValue for $rust {
+                fn ty() -> Type {
+                    Type::$variant
+                }
+
+                fn into_val(self) -> Val {
+                    Val::$variant(self)
+                }
+
+                fn from_val(val: Val) -> Result<Self, Error> {
+                    match val {
+                        Val::$variant(value) => Ok(value),
+                        _ => Err(Error::type_mismatch(&Self::ty())),
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_component_value! {
+    bool => Bool,
+    i8 => S8,
+    u8 => U8,
+    i16 => S16,
+    u16 => U16,
+    i32 => S32,
+    u32 => U32,
+    i64 => S64,
+    u64 => U64,
+    f32 => Float32,
+    f64 => Float64,
+    char => Char,
+    String => String,
+}
+
+impl ComponentValue for () {
+    fn ty() -> Type {
+        Type::Tuple(Vec::new())
+    }
+
+    fn into_val(self) -> Val {
+        Val::Tuple(Vec::new())
+    }
+
+    fn from_val(val: Val) -> Result<Self, Error> {
+        match val {
+            Val::Tuple(values) if values.is_empty() => Ok(()),
+            _ => Err(Error::type_mismatch(&Self::ty())),
+        }
+    }
+
+    fn payload_ty() -> Option<Type> {
+        None
+    }
+
+    fn into_payload(self) -> Option<Box<Val>> {
+        None
+    }
+
+    fn from_payload(payload: Option<Box<Val>>) -> Result<Self, Error> {
+        match payload {
+            None => Ok(()),
+            Some(_) => Err(Error::type_mismatch(&Self::ty())),
+        }
+    }
+}
+
+impl<T: ComponentValue> ComponentValue for Vec<T> {
+    fn ty() -> Type {
+        Type::List(Box::new(T::ty()))
+    }
+
+    fn into_val(self) -> Val {
+        Val::List(self.into_iter().map(T::into_val).collect())
+    }
+
+    fn from_val(val: Val) -> Result<Self, Error> {
+        match val {
+            Val::List(values) => values.into_iter().map(T::from_val).collect(),
+            _ => Err(Error::type_mismatch(&Self::ty())),
+        }
+    }
+}
+
+impl<T: ComponentValue> ComponentValue for Option<T> {
+    fn ty() -> Type {
+        Type::Option(Box::new(T::ty()))
+    }
+
+    fn into_val(self) -> Val {
+        Val::Option(self.map(|value| Box::new(value.into_val())))
+    }
+
+    fn from_val(val: Val) -> Result<Self, Error> {
+        match val {
+            Val::Option(value) => value.map(|value| T::from_val(*value)).transpose(),
+            _ => Err(Error::type_mismatch(&Self::ty())),
+        }
+    }
+}
+
+impl<T: ComponentValue, E: ComponentValue> ComponentValue for Result<T, E> {
+    fn ty() -> Type {
+        Type::Result {
+            ok: T::payload_ty().map(Box::new),
+            err: E::payload_ty().map(Box::new),
+        }
+    }
+
+    fn into_val(self) -> Val {
+        Val::Result(match self {
+            Ok(value) => Ok(value.into_payload()),
+            Err(error) => Err(error.into_payload()),
+        })
+    }
+
+    fn from_val(val: Val) -> Result<Self, Error> {
+        match val {
+            Val::Result(Ok(payload)) => T::from_payload(payload).map(Ok),
+            Val::Result(Err(payload)) => E::from_payload(payload).map(Err),
+            _ => Err(Error::type_mismatch(&Self::ty())),
+        }
+    }
+}
+
+macro_rules! impl_component_value_for_tuple {
+    ( $( ($($name:ident),*) ),* $(,)? ) => {
+        $(
+            impl<$($name: ComponentValue),*> ComponentValue for ($($name,)*) {
+                fn ty() -> Type {
+                    Type::Tuple(vec![$($name::ty()),*])
+                }
+
+                #[allow(non_snake_case)]
+                fn into_val(self) -> Val {
+                    let ($($name,)*) = self;
+                    Val::Tuple(vec![$($name.into_val()),*])
+                }
+
+                #[allow(non_snake_case)]
+                fn from_val(val: Val) -> Result<Self, Error> {
+                    let values = match val {
+                        Val::Tuple(values) => values,
+                        _ => return Err(Error::type_mismatch(&Self::ty())),
+                    };
+                    match <[Val; count!($($name)*)]>::try_from(values) {
+                        Ok([$($name),*]) => Ok(($($name::from_val($name)?,)*)),
+                        Err(_) => Err(Error::type_mismatch(&Self::ty())),
+                    }
+                }
+            }
+        )*
+    };
+}
+macro_rules! count {
+    () => { 0 };
+    ( $head:ident $($tail:ident)* ) => { 1 + count!($($tail)*) };
+}
+impl_component_value_for_tuple! {
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+}
+
+impl Val {
+    /// Returns the payload of a record field `name` in the record `fields`.
+    ///
+    /// This is a helper for the bindings generated via [`bindgen`](crate::bindgen).
+    ///
+    /// # Errors
+    ///
+    /// If the field `name` does not exist in `fields`.
+    #[doc(hidden)]
+    pub fn take_field(
+        fields: &mut Vec<(String, Val)>,
+        name: &str,
+        ty: &Type,
+    ) -> Result<Val, Error> {
+        let index = fields
+            .iter()
+            .position(|(field, _)| field == name)
+            .ok_or_else(|| Error::type_mismatch(ty))?;
+        Ok(fields.remove(index).1)
+    }
+
+    /// Returns `true` if the [`Val`] is an instance of the component type `ty`.
+    pub fn is_instance_of(&self, ty: &Type) -> bool {
+        let payload_matches = |payload: &Option<Box<Val>>, ty: Option<&Type>| match (payload, ty) {
+            (None, None) => true,
+            (Some(payload), Some(ty)) => payload.is_instance_of(ty),
+            _ => false,
+        };
+        match (self, ty) {
+            (Self::Bool(_), Type::Bool)
+            | (Self::S8(_), Type::S8)
+            | (Self::U8(_), Type::U8)
+            | (Self::S16(_), Type::S16)
+            | (Self::U16(_), Type::U16)
+            | (Self::S32(_), Type::S32)
+            | (Self::U32(_), Type::U32)
+            | (Self::S64(_), Type::S64)
+            | (Self::U64(_), Type::U64)
+            | (Self::Float32(_), Type::Float32)
+            | (Self::Float64(_), Type::Float64)
+            | (Self::Char(_), Type::Char)
+            | (Self::String(_), Type::String) => true,
+            (Self::List(values), Type::List(ty)) => values.iter().all(|val| val.is_instance_of(ty)),
+            (Self::Record(values), Type::Record(fields)) => {
+                values.len() == fields.len()
+                    && values.iter().zip(fields).all(|((name, val), field)| {
+                        let Field { name: expected, ty } = field;
+                        name == expected && val.is_instance_of(ty)
+                    })
+            }
+            (Self::Tuple(values), Type::Tuple(types)) => {
+                values.len() == types.len()
+                    && values
+                        .iter()
+                        .zip(types)
+                        .all(|(val, ty)| val.is_instance_of(ty))
+            }
+            (Self::Variant(name, payload), Type::Variant(cases)) => cases
+                .iter()
+                .find(|case| &case.name == name)
+                .map_or(false, |case| payload_matches(payload, case.ty.as_ref())),
+            (Self::Enum(name), Type::Enum(names)) => names.contains(name),
+            (Self::Option(value), Type::Option(ty)) => value
+                .as_ref()
+                .map_or(true, |value| value.is_instance_of(ty)),
+            (Self::Result(Ok(payload)), Type::Result { ok, .. }) => {
+                payload_matches(payload, ok.as_deref())
+            }
+            (Self::Result(Err(payload)), Type::Result { err, .. }) => {
+                payload_matches(payload, err.as_deref())
+            }
+            (Self::Flags(set), Type::Flags(names)) => set.iter().all(|flag| names.contains(flag)),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,757 @@
+//! A parser for a subset of the WIT interface definition language.
+//!
+//! Supported are `package` declarations, `interface` and `world` items with
+//! `record`, `variant`, `enum`, `flags` and `type` definitions as well as
+//! function declarations. Worlds may import and export functions and
+//! interfaces of the same document by name.
+//!
+//! Resources, `use` statements, inline interfaces and external packages
+//! are not supported.
+
+use std::fmt::{self, Display};
+
+/// An error that may occur upon parsing a WIT document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitError {
+    /// The 1-based line of the error.
+    line: usize,
+    /// The 1-based column of the error.
+    column: usize,
+    /// The message describing the error.
+    message: String,
+}
+
+impl WitError {
+    /// Creates a new [`WitError`] at `line` and `column`.
+    pub(crate) fn new(line: usize, column: usize, message: impl Display) -> Self {
+        Self {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates a new [`WitError`] for an item that cannot be resolved.
+    ///
+    /// Such errors are not tied to a position within the document.
+    pub(crate) fn unresolved(message: impl Display) -> Self {
+        Self::new(0, 0, message)
+    }
+
+    /// Returns the 1-based line of the error or 0 if it has no position.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the 1-based column of the error or 0 if it has no position.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::error::Error for WitError {}
+
+impl Display for WitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line == 0 {
+            return write!(f, "{}", self.message);
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// A parsed WIT document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// The name of the package if declared.
+    pub package: Option<String>,
+    /// The interfaces of the document.
+    pub interfaces: Vec<Interface>,
+    /// The worlds of the document.
+    pub worlds: Vec<World>,
+}
+
+/// A WIT `interface`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+    /// The name of the interface.
+    pub name: String,
+    /// The named types defined by the interface.
+    pub types: Vec<TypeDef>,
+    /// The functions of the interface.
+    pub funcs: Vec<Function>,
+}
+
+/// A WIT `world`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    /// The name of the world.
+    pub name: String,
+    /// The named types defined by the world.
+    pub types: Vec<TypeDef>,
+    /// The imports of the world.
+    pub imports: Vec<WorldItem>,
+    /// The exports of the world.
+    pub exports: Vec<WorldItem>,
+}
+
+/// An import or export of a [`World`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorldItem {
+    /// A function.
+    Func(Function),
+    /// An interface of the same document by name.
+    Interface(String),
+}
+
+/// A named WIT type definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDef {
+    /// The name of the type.
+    pub name: String,
+    /// The definition of the type.
+    pub kind: TypeDefKind,
+}
+
+/// The definition of a [`TypeDef`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeDefKind {
+    /// A `record` with named fields.
+    Record(Vec<(String, TypeRef)>),
+    /// A `variant` with named cases and optional payloads.
+    Variant(Vec<(String, Option<TypeRef>)>),
+    /// An `enum` with named cases.
+    Enum(Vec<String>),
+    /// A set of named `flags`.
+    Flags(Vec<String>),
+    /// A `type` alias.
+    Alias(TypeRef),
+}
+
+/// A reference to a WIT type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeRef {
+    /// A primitive type such as `u32` or `string`.
+    Primitive(crate::Type),
+    /// A named type defined in the enclosing interface or world.
+    Named(String),
+    /// A `list<T>`.
+    List(Box<TypeRef>),
+    /// An `option<T>`.
+    Option(Box<TypeRef>),
+    /// A `result<T, E>` with optional `ok` and `err` types.
+    Result {
+        /// The `ok` type if any.
+        ok: Option<Box<TypeRef>>,
+        /// The `err` type if any.
+        err: Option<Box<TypeRef>>,
+    },
+    /// A `tuple<...>`.
+    Tuple(Vec<TypeRef>),
+}
+
+/// A WIT function declaration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    /// The name of the function.
+    pub name: String,
+    /// The named parameters of the function.
+    pub params: Vec<(String, TypeRef)>,
+    /// The optionally named results of the function.
+    pub results: Vec<(Option<String>, TypeRef)>,
+}
+
+impl Document {
+    /// Returns the interface `name` if any.
+    pub fn interface(&self, name: &str) -> Option<&Interface> {
+        self.interfaces
+            .iter()
+            .find(|interface| interface.name == name)
+    }
+
+    /// Returns the world `name` if any.
+    pub fn world(&self, name: &str) -> Option<&World> {
+        self.worlds.iter().find(|world| world.name == name)
+    }
+}
+
+/// Parses the WIT `source` into a [`Document`].
+///
+/// # Errors
+///
+/// If `source` is not a valid WIT document or uses unsupported features.
+pub fn parse(source: &str) -> Result<Document, WitError> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    parser.document()
+}
+
+/// A lexical token of a WIT document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// An identifier or keyword.
+    Ident(String),
+    /// A punctuation character.
+    Punct(char),
+    /// The `->` arrow.
+    Arrow,
+    /// The end of the document.
+    Eof,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Ident(ident) => write!(f, "`{ident}`"),
+            Self::Punct(punct) => write!(f, "`{punct}`"),
+            Self::Arrow => write!(f, "`->`"),
+            Self::Eof => write!(f, "end of document"),
+        }
+    }
+}
+
+/// A [`Token`] with its 1-based line and column.
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+/// Splits the WIT `source` into tokens, skipping whitespace and comments.
+fn tokenize(source: &str) -> Result<Vec<Spanned>, WitError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let (mut line, mut column) = (1, 1);
+    let bump = |c: char, line: &mut usize, column: &mut usize| {
+        if c == '\n' {
+            *line += 1;
+            *column = 1;
+        } else {
+            *column += 1;
+        }
+    };
+    while let Some(&c) = chars.peek() {
+        let (start_line, start_column) = (line, column);
+        if c.is_whitespace() {
+            chars.next();
+            bump(c, &mut line, &mut column);
+            continue;
+        }
+        if c == '/' {
+            chars.next();
+            bump(c, &mut line, &mut column);
+            match chars.next() {
+                Some('/') => {
+                    bump('/', &mut line, &mut column);
+                    for c in chars.by_ref() {
+                        bump(c, &mut line, &mut column);
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some('*') => {
+                    bump('*', &mut line, &mut column);
+                    let mut previous = ' ';
+                    loop {
+                        match chars.next() {
+                            Some(c) => {
+                                bump(c, &mut line, &mut column);
+                                if previous == '*' && c == '/' {
+                                    break;
+                                }
+                                previous = c;
+                            }
+                            None => {
+                                return Err(WitError::new(
+                                    start_line,
+                                    start_column,
+                                    "unterminated block comment",
+                                ))
+                            }
+                        }
+                    }
+                }
+                _ => return Err(WitError::new(start_line, start_column, "unexpected `/`")),
+            }
+            continue;
+        }
+        let token = if c == '-' {
+            chars.next();
+            bump(c, &mut line, &mut column);
+            match chars.next() {
+                Some('>') => {
+                    bump('>', &mut line, &mut column);
+                    Token::Arrow
+                }
+                _ => return Err(WitError::new(start_line, start_column, "unexpected `-`")),
+            }
+        } else if c.is_ascii_alphanumeric() || c == '%' {
+            let mut ident = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || matches!(c, '-' | '%' | '.' | '@')) {
+                    break;
+                }
+                chars.next();
+                bump(c, &mut line, &mut column);
+                ident.push(c);
+            }
+            Token::Ident(ident)
+        } else if "{}()<>,:;=*_".contains(c) {
+            chars.next();
+            bump(c, &mut line, &mut column);
+            Token::Punct(c)
+        } else {
+            return Err(WitError::new(
+                start_line,
+                start_column,
+                format!("unexpected `{c}`"),
+            ));
+        };
+        tokens.push(Spanned {
+            token,
+            line: start_line,
+            column: start_column,
+        });
+    }
+    tokens.push(Spanned {
+        token: Token::Eof,
+        line,
+        column,
+    });
+    Ok(tokens)
+}
+
+/// A recursive descent parser for WIT documents.
+struct Parser {
+    /// The tokens of the document ending with [`Token::Eof`].
+    tokens: Vec<Spanned>,
+    /// The position of the next token.
+    position: usize,
+}
+
+impl Parser {
+    /// Returns the next token without consuming it.
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].token
+    }
+
+    /// Consumes and returns the next token.
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].token.clone();
+        if token != Token::Eof {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// Returns an error at the next token.
+    fn error(&self, message: impl Display) -> WitError {
+        let Spanned { line, column, .. } = self.tokens[self.position];
+        WitError::new(line, column, message)
+    }
+
+    /// Returns an error for an unexpected next token.
+    fn unexpected(&self, expected: &str) -> WitError {
+        self.error(format!("expected {expected} but found {}", self.peek()))
+    }
+
+    /// Consumes the next token if it is the punctuation `punct`.
+    fn eat(&mut self, punct: char) -> bool {
+        if *self.peek() == Token::Punct(punct) {
+            self.next();
+            return true;
+        }
+        false
+    }
+
+    /// Consumes the next token which must be the punctuation `punct`.
+    fn expect(&mut self, punct: char) -> Result<(), WitError> {
+        if !self.eat(punct) {
+            return Err(self.unexpected(&format!("`{punct}`")));
+        }
+        Ok(())
+    }
+
+    /// Consumes the next token which must be an identifier.
+    ///
+    /// A leading `%` used to escape keywords is stripped.
+    fn ident(&mut self) -> Result<String, WitError> {
+        match self.peek().clone() {
+            Token::Ident(ident) => {
+                self.next();
+                Ok(ident.strip_prefix('%').unwrap_or(&ident).to_string())
+            }
+            _ => Err(self.unexpected("an identifier")),
+        }
+    }
+
+    /// Consumes the next token which must be a kebab-case name.
+    fn name(&mut self) -> Result<String, WitError> {
+        let position = self.position;
+        let name = self.ident()?;
+        // Each word of a kebab-case name is either all lowercase or all uppercase.
+        let is_kebab = name.split('-').all(|word| {
+            let is_lower = word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+            let is_upper = word
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+            word.starts_with(|c: char| c.is_ascii_alphabetic()) && (is_lower || is_upper)
+        });
+        if !is_kebab {
+            self.position = position;
+            return Err(self.error(format!("`{name}` is not a valid kebab-case name")));
+        }
+        Ok(name)
+    }
+
+    /// Parses a comma separated list of items enclosed by `open` and `close`.
+    fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, WitError>,
+    ) -> Result<Vec<T>, WitError> {
+        self.expect(open)?;
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(item(self)?);
+            if !self.eat(',') {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    /// Parses a whole document.
+    fn document(&mut self) -> Result<Document, WitError> {
+        let mut document = Document {
+            package: None,
+            interfaces: Vec::new(),
+            worlds: Vec::new(),
+        };
+        if *self.peek() == Token::Ident("package".into()) {
+            self.next();
+            let mut package = self.ident()?;
+            while self.eat(':') {
+                package.push(':');
+                package.push_str(&self.ident()?);
+            }
+            document.package = Some(package);
+            self.expect(';')?;
+        }
+        loop {
+            match self.next() {
+                Token::Eof => return Ok(document),
+                Token::Ident(keyword) if keyword == "interface" => {
+                    let interface = self.interface()?;
+                    if document.interface(&interface.name).is_some() {
+                        return Err(self.error(format!("duplicate interface `{}`", interface.name)));
+                    }
+                    document.interfaces.push(interface);
+                }
+                Token::Ident(keyword) if keyword == "world" => {
+                    let world = self.world()?;
+                    if document.world(&world.name).is_some() {
+                        return Err(self.error(format!("duplicate world `{}`", world.name)));
+                    }
+                    document.worlds.push(world);
+                }
+                _ => {
+                    self.position -= 1;
+                    return Err(self.unexpected("`interface` or `world`"));
+                }
+            }
+        }
+    }
+
+    /// Parses an `interface` after its keyword.
+    fn interface(&mut self) -> Result<Interface, WitError> {
+        let mut interface = Interface {
+            name: self.name()?,
+            types: Vec::new(),
+            funcs: Vec::new(),
+        };
+        self.expect('{')?;
+        while !self.eat('}') {
+            if let Some(def) = self.type_def()? {
+                interface.types.push(def);
+                continue;
+            }
+            let name = self.name()?;
+            self.expect(':')?;
+            interface.funcs.push(self.func(name)?);
+        }
+        Ok(interface)
+    }
+
+    /// Parses a `world` after its keyword.
+    fn world(&mut self) -> Result<World, WitError> {
+        let mut world = World {
+            name: self.name()?,
+            types: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+        };
+        self.expect('{')?;
+        while !self.eat('}') {
+            if let Some(def) = self.type_def()? {
+                world.types.push(def);
+                continue;
+            }
+            let items = match self.ident()?.as_str() {
+                "import" => &mut world.imports,
+                "export" => &mut world.exports,
+                keyword => {
+                    self.position -= 1;
+                    return Err(self.error(format!("unsupported world item `{keyword}`")));
+                }
+            };
+            let name = self.name()?;
+            if self.eat(';') {
+                items.push(WorldItem::Interface(name));
+                continue;
+            }
+            self.expect(':')?;
+            if *self.peek() != Token::Ident("func".into()) {
+                return Err(self.error("only functions and interfaces by name are supported"));
+            }
+            items.push(WorldItem::Func(self.func(name)?));
+        }
+        Ok(world)
+    }
+
+    /// Parses a type definition if the next token starts one.
+    fn type_def(&mut self) -> Result<Option<TypeDef>, WitError> {
+        let keyword = match self.peek() {
+            Token::Ident(keyword) => keyword.clone(),
+            _ => return Ok(None),
+        };
+        let kind = match keyword.as_str() {
+            "record" | "variant" | "enum" | "flags" | "type" => {
+                self.next();
+                keyword
+            }
+            "use" | "resource" | "union" => {
+                return Err(self.error(format!("unsupported item `{keyword}`")))
+            }
+            _ => return Ok(None),
+        };
+        let name = self.name()?;
+        let kind = match kind.as_str() {
+            "record" => TypeDefKind::Record(self.list('{', '}', |this| {
+                let name = this.name()?;
+                this.expect(':')?;
+                Ok((name, this.ty()?))
+            })?),
+            "variant" => TypeDefKind::Variant(self.list('{', '}', |this| {
+                let name = this.name()?;
+                let ty = match this.eat('(') {
+                    true => {
+                        let ty = this.ty()?;
+                        this.expect(')')?;
+                        Some(ty)
+                    }
+                    false => None,
+                };
+                Ok((name, ty))
+            })?),
+            "enum" => TypeDefKind::Enum(self.list('{', '}', Self::name)?),
+            "flags" => TypeDefKind::Flags(self.list('{', '}', Self::name)?),
+            _ => {
+                self.expect('=')?;
+                let ty = self.ty()?;
+                self.expect(';')?;
+                TypeDefKind::Alias(ty)
+            }
+        };
+        Ok(Some(TypeDef { name, kind }))
+    }
+
+    /// Parses a function type after its name and colon.
+    fn func(&mut self, name: String) -> Result<Function, WitError> {
+        match self.ident()?.as_str() {
+            "func" => {}
+            _ => {
+                self.position -= 1;
+                return Err(self.unexpected("`func`"));
+            }
+        }
+        let params = self.list('(', ')', |this| {
+            let name = this.name()?;
+            this.expect(':')?;
+            Ok((name, this.ty()?))
+        })?;
+        let results = match self.peek() {
+            Token::Arrow => {
+                self.next();
+                if *self.peek() == Token::Punct('(') {
+                    self.list('(', ')', |this| {
+                        let name = this.name()?;
+                        this.expect(':')?;
+                        Ok((Some(name), this.ty()?))
+                    })?
+                } else {
+                    vec![(None, self.ty()?)]
+                }
+            }
+            _ => Vec::new(),
+        };
+        self.expect(';')?;
+        Ok(Function {
+            name,
+            params,
+            results,
+        })
+    }
+
+    /// Parses a type reference.
+    fn ty(&mut self) -> Result<TypeRef, WitError> {
+        use crate::Type;
+        let position = self.position;
+        let name = self.ident()?;
+        let primitive = match name.as_str() {
+            "bool" => Type::Bool,
+            "s8" => Type::S8,
+            "u8" => Type::U8,
+            "s16" => Type::S16,
+            "u16" => Type::U16,
+            "s32" => Type::S32,
+            "u32" => Type::U32,
+            "s64" => Type::S64,
+            "u64" => Type::U64,
+            "float32" => Type::Float32,
+            "float64" => Type::Float64,
+            "char" => Type::Char,
+            "string" => Type::String,
+            "list" | "option" => {
+                self.expect('<')?;
+                let ty = Box::new(self.ty()?);
+                self.expect('>')?;
+                return Ok(match name.as_str() {
+                    "list" => TypeRef::List(ty),
+                    _ => TypeRef::Option(ty),
+                });
+            }
+            "result" => {
+                if !self.eat('<') {
+                    return Ok(TypeRef::Result {
+                        ok: None,
+                        err: None,
+                    });
+                }
+                let ok = match self.eat('_') {
+                    true => None,
+                    false => Some(Box::new(self.ty()?)),
+                };
+                let err = match self.eat(',') {
+                    true => Some(Box::new(self.ty()?)),
+                    false => None,
+                };
+                self.expect('>')?;
+                return Ok(TypeRef::Result { ok, err });
+            }
+            "tuple" => return Ok(TypeRef::Tuple(self.list('<', '>', Self::ty)?)),
+            "borrow" | "own" | "future" | "stream" => {
+                self.position = position;
+                return Err(self.error(format!("unsupported type `{name}`")));
+            }
+            _ => {
+                self.position = position;
+                return self.name().map(TypeRef::Named);
+            }
+        };
+        Ok(TypeRef::Primitive(primitive))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Type;
+
+    const WIT: &str = r#"
+        package example:host@0.1.0;
+
+        /// Logging facilities of the host.
+        interface logging {
+            enum level { debug, info, error }
+            log: func(level: level, msg: string);
+        }
+
+        interface math {
+            record stats { count: u32, sum: u64, }
+            /* Computes statistics of all `values`. */
+            stats: func(values: list<u32>) -> stats;
+            div: func(a: s32, b: s32) -> result<s32, string>;
+            minmax: func(values: list<s32>) -> (min: s32, max: s32);
+        }
+
+        world example {
+            variant shape { circle(float32), rect(tuple<u32, u32>), empty }
+            import logging;
+            import lookup: func(id: u32) -> option<string>;
+            export math;
+            export area: func(shape: shape) -> float64;
+        }
+    "#;
+
+    #[test]
+    fn parse_works() {
+        let document = parse(WIT).unwrap();
+        assert_eq!(document.package.as_deref(), Some("example:host@0.1.0"));
+        let logging = document.interface("logging").unwrap();
+        assert_eq!(
+            logging.types[0].kind,
+            TypeDefKind::Enum(vec!["debug".into(), "info".into(), "error".into()])
+        );
+        assert_eq!(
+            logging.funcs[0].params,
+            [
+                ("level".into(), TypeRef::Named("level".into())),
+                ("msg".into(), TypeRef::Primitive(Type::String)),
+            ]
+        );
+        let math = document.interface("math").unwrap();
+        assert_eq!(math.funcs.len(), 3);
+        assert_eq!(
+            math.funcs[1].results,
+            [(
+                None,
+                TypeRef::Result {
+                    ok: Some(Box::new(TypeRef::Primitive(Type::S32))),
+                    err: Some(Box::new(TypeRef::Primitive(Type::String))),
+                }
+            )]
+        );
+        assert_eq!(math.funcs[2].results[1].0.as_deref(), Some("max"));
+        let world = document.world("example").unwrap();
+        assert_eq!(world.types[0].name, "shape");
+        assert_eq!(world.imports[0], WorldItem::Interface("logging".into()));
+        assert!(matches!(&world.imports[1], WorldItem::Func(func) if func.name == "lookup"));
+        assert_eq!(world.exports[0], WorldItem::Interface("math".into()));
+    }
+
+    #[test]
+    fn parse_errors_have_positions() {
+        let error = parse("interface a {\n  f: func(x: u32) -> ;\n}").unwrap_err();
+        assert_eq!((error.line(), error.column()), (2, 22));
+        let error = parse("interface a {\n  use b.{c};\n}").unwrap_err();
+        assert_eq!(error.message(), "unsupported item `use`");
+        let error = parse("interface Foo {}").unwrap_err();
+        assert_eq!((error.line(), error.column()), (1, 11));
+        assert!(parse("world w { import x: interface { } }").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+// Generated by `wasmi_component::bindgen` from the WIT world `example`.
+
+#[allow(unused_imports)]
+use wasmi_component::{
+    wasmi::{core::Trap, AsContextMut},
+    Case, Component, ComponentValue, Error, Field, Func, Instance, Linker, Type, Val,
+};
+
+pub mod logging {
+    #[allow(unused_imports)]
+    use super::*;
+
+    /// The host functions of the imported `logging` interface.
+    pub trait Host {
+        fn log(&mut self, msg: String) -> Result<(), Trap>;
+    }
+
+    /// Adds the `logging` interface to the `linker` using `get` to access its [`Host`].
+    ///
+    /// # Errors
+    ///
+    /// If the `linker` already defines any of the functions.
+    pub fn add_to_linker<T: 'static, U: Host + 'static>(
+        linker: &mut Linker<T>,
+        get: fn(&mut T) -> &mut U,
+    ) -> Result<(), Error> {
+        let mut instance = linker.instance("logging");
+        instance.func_new("log", move |mut store, params, _results| {
+            let host = get(store.data_mut());
+            host.log(
+                ComponentValue::from_val(params[0].clone())?,
+            )?;
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+pub mod math {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Stats {
+        pub count: u32,
+        pub sum: u64,
+    }
+
+    impl ComponentValue for Stats {
+        fn ty() -> Type {
+            Type::Record(vec![
+                Field { name: "count".into(), ty: <u32 as ComponentValue>::ty() },
+                Field { name: "sum".into(), ty: <u64 as ComponentValue>::ty() },
+            ])
+        }
+
+        fn into_val(self) -> Val {
+            Val::Record(vec![
+                ("count".into(), self.count.into_val()),
+                ("sum".into(), self.sum.into_val()),
+            ])
+        }
+
+        fn from_val(val: Val) -> Result<Self, Error> {
+            match val {
+                Val::Record(mut fields) => Ok(Self {
+                    count: ComponentValue::from_val(Val::take_field(&mut fields, "count", &Self::ty())?)?,
+                    sum: ComponentValue::from_val(Val::take_field(&mut fields, "sum", &Self::ty())?)?,
+                }),
+                _ => Err(Error::TypeMismatch { expected: Self::ty() }),
+            }
+        }
+    }
+}
+
+/// The host functions directly imported by the `example` world.
+pub trait ExampleImports {
+    fn lookup(&mut self, id: u32) -> Result<Option<String>, Trap>;
+}
+
+/// Adds all imports of the `example` world to the `linker` using `get` to access their hosts.
+///
+/// # Errors
+///
+/// If the `linker` already defines any of the functions.
+pub fn add_to_linker<T: 'static, U>(
+    linker: &mut Linker<T>,
+    get: fn(&mut T) -> &mut U,
+) -> Result<(), Error>
+where
+    U: ExampleImports + logging::Host + 'static,
+{
+    linker.func_new("lookup", move |mut store, params, results| {
+        let host = get(store.data_mut());
+        let result = host.lookup(
+            ComponentValue::from_val(params[0].clone())?,
+        )?;
+        results[0] = result.into_val();
+        Ok(())
+    })?;
+    logging::add_to_linker(linker, get)?;
+    Ok(())
+}
+
+/// The exports of an instantiated component of the `example` world.
+#[derive(Debug, Clone)]
+pub struct Example {
+    greet: Func,
+    describe: Func,
+    math_stats: Func,
+}
+
+impl Example {
+    /// Looks up the exports of the `instance`.
+    ///
+    /// # Errors
+    ///
+    /// If the `instance` lacks any of the exports.
+    pub fn new(instance: &Instance) -> Result<Self, Error> {
+        Ok(Self {
+            greet: instance
+                .get_func("greet")
+                .ok_or_else(|| Error::MissingExport("greet".into()))?,
+            describe: instance
+                .get_func("describe")
+                .ok_or_else(|| Error::MissingExport("describe".into()))?,
+            math_stats: instance
+                .get_instance_func("math", "stats")
+                .ok_or_else(|| Error::MissingExport("math/stats".into()))?,
+        })
+    }
+
+    /// Instantiates the `component` with the `linker` and looks up its exports.
+    ///
+    /// # Errors
+    ///
+    /// If instantiation fails or the component lacks any of the exports.
+    pub fn instantiate<T: 'static>(
+        store: impl AsContextMut<UserState = T>,
+        component: &Component,
+        linker: &Linker<T>,
+    ) -> Result<Self, Error> {
+        Self::new(&linker.instantiate(store, component)?)
+    }
+
+    /// Calls the exported `greet` function.
+    ///
+    /// # Errors
+    ///
+    /// If the execution traps or lifting or lowering values fails.
+    pub fn call_greet(&self, mut store: impl AsContextMut, name: String) -> Result<String, Error> {
+        let mut results = [Val::Bool(false)];
+        self.greet.call(&mut store, &[name.into_val()], &mut results)?;
+        let [r0] = results;
+        ComponentValue::from_val(r0)
+    }
+
+    /// Calls the exported `describe` function.
+    ///
+    /// # Errors
+    ///
+    /// If the execution traps or lifting or lowering values fails.
+    pub fn call_describe(&self, mut store: impl AsContextMut, id: u32) -> Result<String, Error> {
+        let mut results = [Val::Bool(false)];
+        self.describe.call(&mut store, &[id.into_val()], &mut results)?;
+        let [r0] = results;
+        ComponentValue::from_val(r0)
+    }
+
+    /// Calls the exported `stats` function.
+    ///
+    /// # Errors
+    ///
+    /// If the execution traps or lifting or lowering values fails.
+    pub fn call_math_stats(&self, mut store: impl AsContextMut, values: Vec<u32>) -> Result<math::Stats, Error> {
+        let mut results = [Val::Bool(false)];
+        self.math_stats.call(&mut store, &[values.into_val()], &mut results)?;
+        let [r0] = results;
+        ComponentValue::from_val(r0)
+    }
+}
@@ -0,0 +1,519 @@
+//! Tests for instantiating and calling Wasm components.
+//!
+//! The test component is encoded by hand since the text format of the
+//! Component Model differs between tool versions.
+
+use wasmi::{core::Trap, Engine, Store};
+use wasmi_component::{bindgen, Component, Error, Linker, Type, Val};
+
+mod bindings {
+    include!("bindings/example.rs");
+}
+
+/// The WIT world implemented by the component of [`example_component`].
+const WIT: &str = include_str!("wit/example.wit");
+
+/// A core module with a linear memory and a bump allocating `cabi_realloc`.
+const LIBC: &str = r#"
+    (module
+        (memory (export "memory") 1)
+        (global $heap (mut i32) (i32.const 1024))
+        (func (export "cabi_realloc") (param i32 i32 i32 i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr
+                (i32.and
+                    (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+                    (i32.sub (i32.const 0) (local.get 2))
+                )
+            )
+            (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+            (local.get $ptr)
+        )
+    )
+"#;
+
+/// The core module implementing the exports of the component.
+///
+/// Return areas are located at fixed addresses below the heap of [`LIBC`].
+const MAIN: &str = r#"
+    (module
+        (import "libc" "memory" (memory 1))
+        (import "libc" "cabi_realloc" (func $realloc (param i32 i32 i32 i32) (result i32)))
+        (import "host" "log" (func $log (param i32 i32)))
+        (import "host" "lookup" (func $lookup (param i32 i32)))
+        (data (i32.const 16) "hello, unknown")
+        ;; Logs the `name` and returns "hello, " followed by the `name`.
+        (func (export "greet") (param $ptr i32) (param $len i32) (result i32)
+            (local $out i32)
+            (call $log (local.get $ptr) (local.get $len))
+            (local.set $out
+                (call $realloc
+                    (i32.const 0) (i32.const 0) (i32.const 1)
+                    (i32.add (local.get $len) (i32.const 7))
+                )
+            )
+            (memory.copy (local.get $out) (i32.const 16) (i32.const 7))
+            (memory.copy
+                (i32.add (local.get $out) (i32.const 7))
+                (local.get $ptr)
+                (local.get $len)
+            )
+            (i32.store (i32.const 0) (local.get $out))
+            (i32.store (i32.const 4) (i32.add (local.get $len) (i32.const 7)))
+            (i32.const 0)
+        )
+        ;; Returns the name the host looks up for `id` or "unknown".
+        (func (export "describe") (param $id i32) (result i32)
+            (call $lookup (local.get $id) (i32.const 32))
+            (if (i32.load8_u (i32.const 32))
+                (then
+                    (i32.store (i32.const 0) (i32.load (i32.const 36)))
+                    (i32.store (i32.const 4) (i32.load (i32.const 40)))
+                )
+                (else
+                    (i32.store (i32.const 0) (i32.const 23))
+                    (i32.store (i32.const 4) (i32.const 7))
+                )
+            )
+            (i32.const 0)
+        )
+        ;; Returns the number and sum of all `values`.
+        (func (export "stats") (param $ptr i32) (param $len i32) (result i32)
+            (local $i i32)
+            (local $sum i64)
+            (block $done
+                (loop $next
+                    (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+                    (local.set $sum
+                        (i64.add
+                            (local.get $sum)
+                            (i64.extend_i32_u
+                                (i32.load
+                                    (i32.add
+                                        (local.get $ptr)
+                                        (i32.shl (local.get $i) (i32.const 2))
+                                    )
+                                )
+                            )
+                        )
+                    )
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br $next)
+                )
+            )
+            (i32.store (i32.const 48) (local.get $len))
+            (i64.store (i32.const 56) (local.get $sum))
+            (i32.const 48)
+        )
+    )
+"#;
+
+/// Encodes `value` as unsigned LEB128.
+fn leb(mut value: u32) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Encodes the `name` prefixed by its length.
+fn name(name: &str) -> Vec<u8> {
+    let mut bytes = leb(name.len() as u32);
+    bytes.extend_from_slice(name.as_bytes());
+    bytes
+}
+
+/// Concatenates all `parts`.
+fn concat(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+/// Encodes a section with `id` and raw `contents`.
+fn section(id: u8, contents: &[u8]) -> Vec<u8> {
+    concat(&[&[id], &leb(contents.len() as u32), contents])
+}
+
+/// Encodes a section with `id` containing a vector of `items`.
+fn vec_section(id: u8, items: &[Vec<u8>]) -> Vec<u8> {
+    let mut contents = leb(items.len() as u32);
+    for item in items {
+        contents.extend_from_slice(item);
+    }
+    section(id, &contents)
+}
+
+/// The header of a Wasm component binary.
+const HEADER: &[u8] = &[0x00, 0x61, 0x73, 0x6d, 0x0c, 0x00, 0x01, 0x00];
+
+/// Returns the binary of the example component.
+///
+/// ```text
+/// (component
+///     (import "logging" (instance (export "log" (func (param "msg" string)))))
+///     (import "lookup" (func (param "id" u32) (result (option string))))
+///     (core module $libc ..) (core module $main ..)
+///     (core instance $libc (instantiate $libc))
+///     (core instance $host (export "log" ..) (export "lookup" ..))
+///     (core instance $main (instantiate $main (with "libc" ..) (with "host" ..)))
+///     (export "greet" (func (param "name" string) (result string)))
+///     (export "describe" (func (param "id" u32) (result string)))
+///     (export "math" (instance (export "stats" (func (param "values" (list u32))
+///         (result (record (field "count" u32) (field "sum" u64)))))))
+/// )
+/// ```
+fn example_component() -> Vec<u8> {
+    let libc = wat::parse_str(LIBC).unwrap();
+    let main = wat::parse_str(MAIN).unwrap();
+    let types = vec_section(
+        0x07,
+        &[
+            // 0: instance type of `logging`
+            concat(&[
+                &[0x42, 0x02, 0x01, 0x40, 0x01],
+                &name("msg"),
+                &[0x73, 0x01, 0x00, 0x04],
+                &name("log"),
+                &[0x00, 0x01, 0x00],
+            ]),
+            // 1: option<string>
+            vec![0x6b, 0x73],
+            // 2: func(id: u32) -> option<string>
+            concat(&[&[0x40, 0x01], &name("id"), &[0x79, 0x00, 0x01]]),
+            // 3: func(name: string) -> string
+            concat(&[&[0x40, 0x01], &name("name"), &[0x73, 0x00, 0x73]]),
+            // 4: record { count: u32, sum: u64 }
+            concat(&[
+                &[0x72, 0x02],
+                &name("count"),
+                &[0x79],
+                &name("sum"),
+                &[0x77],
+            ]),
+            // 5: list<u32>
+            vec![0x70, 0x79],
+            // 6: func(values: list<u32>) -> stats
+            concat(&[&[0x40, 0x01], &name("values"), &[0x05, 0x00, 0x04]]),
+            // 7: func(id: u32) -> string
+            concat(&[&[0x40, 0x01], &name("id"), &[0x79, 0x00, 0x73]]),
+        ],
+    );
+    let imports = vec_section(
+        0x0a,
+        &[
+            concat(&[&name("logging"), &[0x00, 0x05, 0x00]]),
+            concat(&[&name("lookup"), &[0x00, 0x01, 0x02]]),
+        ],
+    );
+    let options = [0x02, 0x03, 0x00, 0x04, 0x00];
+    let alias_core_func =
+        |instance: u8, export: &str| concat(&[&[0x00, 0x00, 0x01, instance], &name(export)]);
+    concat(&[
+        HEADER,
+        &types,
+        &imports,
+        &section(0x01, &libc),
+        &section(0x01, &main),
+        // core instance 0: libc
+        &vec_section(0x02, &[vec![0x00, 0x00, 0x00]]),
+        &vec_section(
+            0x06,
+            &[
+                // core memory 0
+                concat(&[&[0x00, 0x02, 0x01, 0x00], &name("memory")]),
+                // core func 0
+                alias_core_func(0, "cabi_realloc"),
+                // func 1: logging/log
+                concat(&[&[0x01, 0x00, 0x00], &name("log")]),
+            ],
+        ),
+        &vec_section(
+            0x08,
+            &[
+                // core func 1: lowered `log`
+                concat(&[&[0x01, 0x00, 0x01], &options]),
+                // core func 2: lowered `lookup`
+                concat(&[&[0x01, 0x00, 0x00], &options]),
+            ],
+        ),
+        &vec_section(
+            0x02,
+            &[
+                // core instance 1: host
+                concat(&[
+                    &[0x01, 0x02],
+                    &name("log"),
+                    &[0x00, 0x01],
+                    &name("lookup"),
+                    &[0x00, 0x02],
+                ]),
+                // core instance 2: main
+                concat(&[
+                    &[0x00, 0x01, 0x02],
+                    &name("libc"),
+                    &[0x12, 0x00],
+                    &name("host"),
+                    &[0x12, 0x01],
+                ]),
+            ],
+        ),
+        // core funcs 3, 4 and 5
+        &vec_section(
+            0x06,
+            &[
+                alias_core_func(2, "greet"),
+                alias_core_func(2, "describe"),
+                alias_core_func(2, "stats"),
+            ],
+        ),
+        // funcs 2, 3 and 4
+        &vec_section(
+            0x08,
+            &[
+                concat(&[&[0x00, 0x00, 0x03], &options, &[0x03]]),
+                concat(&[&[0x00, 0x00, 0x04], &options, &[0x07]]),
+                concat(&[&[0x00, 0x00, 0x05], &options, &[0x06]]),
+            ],
+        ),
+        // instance 1: math
+        &vec_section(
+            0x05,
+            &[concat(&[&[0x01, 0x01], &name("stats"), &[0x01, 0x04]])],
+        ),
+        &vec_section(
+            0x0b,
+            &[
+                concat(&[&name("greet"), &[0x00, 0x01, 0x02, 0x00]]),
+                concat(&[&name("describe"), &[0x00, 0x01, 0x03, 0x00]]),
+                concat(&[&name("math"), &[0x00, 0x05, 0x01, 0x00]]),
+            ],
+        ),
+    ])
+}
+
+/// The host state of the tests recording all logged messages.
+#[derive(Debug, Default)]
+struct Host {
+    logs: Vec<String>,
+}
+
+/// Returns a [`Linker`] defining all imports of the example component.
+fn linker(engine: &Engine) -> Linker<Host> {
+    let mut linker = <Linker<Host>>::new(engine);
+    linker
+        .instance("logging")
+        .func_new("log", |mut store, params, _results| {
+            match &params[0] {
+                Val::String(msg) => store.data_mut().logs.push(msg.clone()),
+                _ => unreachable!("validated parameter type"),
+            }
+            Ok(())
+        })
+        .unwrap();
+    linker
+        .func_new("lookup", |_store, params, results| {
+            results[0] = match params[0] {
+                Val::U32(0) => return Err(Trap::new("invalid id")),
+                Val::U32(1) => Val::Option(Some(Box::new(Val::String("wasmi".into())))),
+                _ => Val::Option(None),
+            };
+            Ok(())
+        })
+        .unwrap();
+    linker
+}
+
+#[test]
+fn dynamic_calls_work() {
+    let engine = Engine::default();
+    let component = Component::new(&engine, &example_component()).unwrap();
+    let mut store = Store::new(&engine, Host::default());
+    let instance = linker(&engine).instantiate(&mut store, &component).unwrap();
+
+    let greet = instance.get_func("greet").unwrap();
+    assert_eq!(greet.ty().to_string(), "func(name: string) -> string");
+    let mut results = [Val::Bool(false)];
+    greet
+        .call(&mut store, &[Val::String("wörld".into())], &mut results)
+        .unwrap();
+    assert_eq!(results[0], Val::String("hello, wörld".into()));
+    assert_eq!(store.data().logs, ["wörld"]);
+
+    let describe = instance.get_func("describe").unwrap();
+    describe
+        .call(&mut store, &[Val::U32(1)], &mut results)
+        .unwrap();
+    assert_eq!(results[0], Val::String("wasmi".into()));
+    describe
+        .call(&mut store, &[Val::U32(2)], &mut results)
+        .unwrap();
+    assert_eq!(results[0], Val::String("unknown".into()));
+
+    let stats = instance.get_instance_func("math", "stats").unwrap();
+    let values = Val::List(vec![Val::U32(1), Val::U32(u32::MAX), Val::U32(3)]);
+    stats.call(&mut store, &[values], &mut results).unwrap();
+    assert_eq!(
+        results[0],
+        Val::Record(vec![
+            ("count".into(), Val::U32(3)),
+            ("sum".into(), Val::U64(u64::from(u32::MAX) + 4)),
+        ])
+    );
+}
+
+#[test]
+fn invalid_calls_are_rejected() {
+    let engine = Engine::default();
+    let component = Component::new(&engine, &example_component()).unwrap();
+    let mut store = Store::new(&engine, Host::default());
+    let instance = linker(&engine).instantiate(&mut store, &component).unwrap();
+    let greet = instance.get_func("greet").unwrap();
+    let mut results = [Val::Bool(false)];
+    assert!(matches!(
+        greet.call(&mut store, &[], &mut results),
+        Err(Error::ArityMismatch {
+            expected: 1,
+            found: 0
+        })
+    ));
+    assert!(matches!(
+        greet.call(&mut store, &[Val::U32(0)], &mut results),
+        Err(Error::TypeMismatch {
+            expected: Type::String
+        })
+    ));
+    let describe = instance.get_func("describe").unwrap();
+    let error = describe
+        .call(&mut store, &[Val::U32(0)], &mut results)
+        .unwrap_err();
+    assert!(error.to_string().contains("invalid id"), "{error}");
+}
+
+#[test]
+fn missing_imports_are_rejected() {
+    let engine = Engine::default();
+    let component = Component::new(&engine, &example_component()).unwrap();
+    let imports = component
+        .imports()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(imports, ["lookup", "logging/log"]);
+    let mut store = Store::new(&engine, Host::default());
+    let mut linker = <Linker<Host>>::new(&engine);
+    linker
+        .func_new("lookup", |_store, _params, _results| Ok(()))
+        .unwrap();
+    assert!(matches!(
+        linker.instantiate(&mut store, &component),
+        Err(Error::MissingImport(name)) if name == "logging/log"
+    ));
+    assert!(matches!(
+        linker.func_new("lookup", |_store, _params, _results| Ok(())),
+        Err(Error::DuplicateDefinition(name)) if name == "lookup"
+    ));
+}
+
+#[test]
+fn invalid_components_are_rejected() {
+    let engine = Engine::default();
+    assert!(matches!(
+        Component::new(&engine, &HEADER[..6]),
+        Err(Error::Parser(_))
+    ));
+    let module = wat::parse_str(LIBC).unwrap();
+    assert!(matches!(
+        Component::new(&engine, &module),
+        Err(Error::Unsupported(_))
+    ));
+    let nested = concat(&[HEADER, &section(0x04, HEADER)]);
+    assert!(matches!(
+        Component::new(&engine, &nested),
+        Err(Error::Unsupported(_))
+    ));
+}
+
+struct BindingsHost {
+    logs: Vec<String>,
+}
+
+impl bindings::logging::Host for BindingsHost {
+    fn log(&mut self, msg: String) -> Result<(), Trap> {
+        self.logs.push(msg);
+        Ok(())
+    }
+}
+
+impl bindings::ExampleImports for BindingsHost {
+    fn lookup(&mut self, id: u32) -> Result<Option<String>, Trap> {
+        Ok((id == 1).then(|| "bindgen".into()))
+    }
+}
+
+#[test]
+fn bindgen_output_is_up_to_date() {
+    let generated = bindgen::generate(WIT, "example").unwrap();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/bindings/example.rs");
+    if std::env::var_os("BLESS").is_some() {
+        std::fs::write(path, &generated).unwrap();
+    }
+    assert_eq!(
+        generated,
+        include_str!("bindings/example.rs"),
+        "rerun with `BLESS=1` to update the generated bindings"
+    );
+}
+
+#[test]
+fn generated_bindings_work() {
+    let engine = Engine::default();
+    let component = Component::new(&engine, &example_component()).unwrap();
+    let mut store = Store::new(&engine, BindingsHost { logs: Vec::new() });
+    let mut linker = <Linker<BindingsHost>>::new(&engine);
+    bindings::add_to_linker(&mut linker, |host| host).unwrap();
+    let example = bindings::Example::instantiate(&mut store, &component, &linker).unwrap();
+    let greeting = example.call_greet(&mut store, "bindgen".into()).unwrap();
+    assert_eq!(greeting, "hello, bindgen");
+    assert_eq!(store.data().logs, ["bindgen"]);
+    assert_eq!(example.call_describe(&mut store, 1).unwrap(), "bindgen");
+    assert_eq!(example.call_describe(&mut store, 7).unwrap(), "unknown");
+    let stats = example.call_math_stats(&mut store, vec![2, 4, 6]).unwrap();
+    assert_eq!(stats, bindings::math::Stats { count: 3, sum: 12 });
+}
+
+#[test]
+fn bindgen_reports_unresolved_items() {
+    let error = bindgen::generate(WIT, "missing").unwrap_err();
+    assert_eq!(error.to_string(), "cannot find world `missing`");
+    let wit = "interface a { f: func() -> b; } world w { export a; }";
+    let error = bindgen::generate(wit, "w").unwrap_err();
+    assert_eq!(error.to_string(), "cannot find type `b`");
+}
+
+#[test]
+fn linker_is_reusable_across_stores() {
+    // Host functions only access the store they are called with.
+    let engine = Engine::default();
+    let component = Component::new(&engine, &example_component()).unwrap();
+    let linker = linker(&engine);
+    let mut first = Store::new(&engine, Host::default());
+    let mut second = Store::new(&engine, Host::default());
+    let first_instance = linker.instantiate(&mut first, &component).unwrap();
+    let second_instance = linker.instantiate(&mut second, &component).unwrap();
+    let mut results = [Val::Bool(false)];
+    for (store, instance, name) in [
+        (&mut first, &first_instance, "a"),
+        (&mut second, &second_instance, "b"),
+    ] {
+        let greet = instance.get_func("greet").unwrap();
+        greet
+            .call(&mut *store, &[Val::String(name.into())], &mut results)
+            .unwrap();
+    }
+    assert_eq!(first.data().logs, ["a"]);
+    assert_eq!(second.data().logs, ["b"]);
+}
@@ -38,7 +38,7 @@ use self::value::{
 pub use self::{
     host_error::HostError,
     nan_preserving_float::{F32, F64},
-    trap::{Trap, TrapCode},
+    trap::{Trap, TrapCode, WasmBacktrace, WasmFrame},
     units::Pages,
     untyped::{DecodeUntypedSlice, EncodeUntypedSlice, UntypedError, UntypedValue},
     value::ValueType,
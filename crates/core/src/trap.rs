@@ -1,5 +1,5 @@
 use crate::HostError;
-use alloc::{boxed::Box, string::String};
+use alloc::{boxed::Box, string::String, vec::Vec};
 use core::fmt::{self, Display};
 
 #[cfg(feature = "std")]
@@ -13,8 +13,17 @@ use std::error::Error as StdError;
 /// host embedder.
 #[derive(Debug)]
 pub struct Trap {
-    /// The cloneable reason of a [`Trap`].
-    reason: Box<TrapReason>,
+    /// The reason of a [`Trap`] and its optional [`WasmBacktrace`].
+    inner: Box<TrapInner>,
+}
+
+/// The boxed contents of a [`Trap`].
+#[derive(Debug)]
+struct TrapInner {
+    /// The reason of the [`Trap`].
+    reason: TrapReason,
+    /// The Wasm call frames that were active when the [`Trap`] occurred if any.
+    backtrace: Option<WasmBacktrace>,
 }
 
 #[test]
@@ -94,7 +103,10 @@ impl Trap {
     /// Create a new [`Trap`] from the [`TrapReason`].
     fn with_reason(reason: TrapReason) -> Self {
         Self {
-            reason: Box::new(reason),
+            inner: Box::new(TrapInner {
+                reason,
+                backtrace: None,
+            }),
         }
     }
 
@@ -115,7 +127,8 @@ impl Trap {
     where
         T: HostError,
     {
-        self.reason
+        self.inner
+            .reason
             .as_host()
             .and_then(<(dyn HostError + 'static)>::downcast_ref)
     }
@@ -128,7 +141,8 @@ impl Trap {
     where
         T: HostError,
     {
-        self.reason
+        self.inner
+            .reason
             .as_host_mut()
             .and_then(<(dyn HostError + 'static)>::downcast_mut)
     }
@@ -141,7 +155,8 @@ impl Trap {
     where
         T: HostError,
     {
-        self.reason
+        self.inner
+            .reason
             .into_host()
             .and_then(|error| error.downcast().ok())
             .map(|boxed| *boxed)
@@ -159,13 +174,33 @@ impl Trap {
     /// Otherwise returns `None`.
     #[inline]
     pub fn i32_exit_status(&self) -> Option<i32> {
-        self.reason.i32_exit_status()
+        self.inner.reason.i32_exit_status()
     }
 
     /// Returns the [`TrapCode`] traps originating from Wasm execution.
     #[inline]
     pub fn trap_code(&self) -> Option<TrapCode> {
-        self.reason.trap_code()
+        self.inner.reason.trap_code()
+    }
+
+    /// Returns the [`WasmBacktrace`] captured when the [`Trap`] occurred if any.
+    ///
+    /// # Note
+    ///
+    /// Only traps raised while executing Wasm carry a backtrace. This includes
+    /// traps of host functions called by Wasm and uncaught Wasm exceptions.
+    #[inline]
+    pub fn backtrace(&self) -> Option<&WasmBacktrace> {
+        self.inner.backtrace.as_ref()
+    }
+
+    /// Attaches the [`WasmBacktrace`] to the [`Trap`].
+    ///
+    /// Replaces any previously attached [`WasmBacktrace`].
+    #[cold] // see Trap::new
+    pub fn with_backtrace(mut self, backtrace: WasmBacktrace) -> Self {
+        self.inner.backtrace = Some(backtrace);
+        self
     }
 }
 
@@ -200,7 +235,87 @@ impl Display for TrapReason {
 
 impl Display for Trap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        <TrapReason as Display>::fmt(&self.reason, f)
+        <TrapReason as Display>::fmt(&self.inner.reason, f)
+    }
+}
+
+/// The Wasm call frames that were active when a [`Trap`] occurred.
+///
+/// Frames are ordered from the innermost frame, which caused the [`Trap`],
+/// to the outermost frame, which was called by the host.
+#[derive(Debug, Default, Clone)]
+pub struct WasmBacktrace {
+    frames: Vec<WasmFrame>,
+}
+
+impl WasmBacktrace {
+    /// Creates a new [`WasmBacktrace`] from the innermost-first `frames`.
+    pub fn new(frames: Vec<WasmFrame>) -> Self {
+        Self { frames }
+    }
+
+    /// Returns the frames of the [`WasmBacktrace`], innermost frame first.
+    pub fn frames(&self) -> &[WasmFrame] {
+        &self.frames
+    }
+}
+
+impl Display for WasmBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, frame) in self.frames.iter().enumerate() {
+            writeln!(f, "{n:>4}: {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single Wasm call frame of a [`WasmBacktrace`].
+#[derive(Debug, Clone)]
+pub struct WasmFrame {
+    /// The index of the function within its module, including imported functions.
+    func_index: u32,
+    /// The name of the function as found in the `name` custom section if any.
+    func_name: Option<Box<str>>,
+    /// The byte offset of the executing Wasm operator within the Wasm binary.
+    source_pc: u32,
+}
+
+impl WasmFrame {
+    /// Creates a new [`WasmFrame`].
+    pub fn new(func_index: u32, func_name: Option<&str>, source_pc: u32) -> Self {
+        Self {
+            func_index,
+            func_name: func_name.map(Into::into),
+            source_pc,
+        }
+    }
+
+    /// Returns the index of the function within its module, including imported functions.
+    pub fn func_index(&self) -> u32 {
+        self.func_index
+    }
+
+    /// Returns the name of the function if any.
+    pub fn func_name(&self) -> Option<&str> {
+        self.func_name.as_deref()
+    }
+
+    /// Returns the byte offset of the executing Wasm operator within the Wasm binary.
+    ///
+    /// For the innermost frame this is the operator that caused the [`Trap`],
+    /// for all other frames this is the call operator that is still in progress.
+    pub fn source_pc(&self) -> u32 {
+        self.source_pc
+    }
+}
+
+impl Display for WasmFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:#08x} - ", self.source_pc)?;
+        match self.func_name() {
+            Some(name) => write!(f, "{name}"),
+            None => write!(f, "<wasm function {}>", self.func_index),
+        }
     }
 }
 
@@ -284,6 +399,31 @@ pub enum TrapCode {
     /// internal bytecode so that fuel is consumed for each executed instruction.
     /// This is useful to deterministically halt or yield a WebAssembly execution.
     OutOfFuel,
+
+    /// A Wasm exception has been thrown but was not caught by any handler.
+    ///
+    /// This is used internally by the `wasmi` executor and usually converted
+    /// into a [`Trap`] carrying the uncaught exception before it is returned
+    /// to the host.
+    UncaughtException,
+
+    /// An atomic memory access was not naturally aligned.
+    ///
+    /// Atomic memory accesses of the Wasm `threads` proposal require their
+    /// effective address to be a multiple of their access width.
+    UnalignedAtomic,
+
+    /// A `memory.atomic.wait` instruction was executed on a non-shared linear memory.
+    ExpectedSharedMemory,
+
+    /// A `memory.atomic.wait` instruction had to block without a host provided thread parker.
+    AtomicWaitUnsupported,
+
+    /// A `memory.grow` or `table.grow` instruction was denied by the resource limiter of the store.
+    GrowthOperationLimited,
+
+    /// The execution was interrupted since the epoch of the engine reached the deadline of the store.
+    Interrupted,
 }
 
 impl TrapCode {
@@ -305,6 +445,12 @@ impl TrapCode {
             Self::StackOverflow => "call stack exhausted",
             Self::BadSignature => "indirect call type mismatch",
             Self::OutOfFuel => "all fuel consumed by WebAssembly",
+            Self::UncaughtException => "uncaught exception",
+            Self::UnalignedAtomic => "unaligned atomic",
+            Self::ExpectedSharedMemory => "expected shared memory",
+            Self::AtomicWaitUnsupported => "atomic wait is not supported by the host",
+            Self::GrowthOperationLimited => "growth operation limited",
+            Self::Interrupted => "interrupted",
         }
     }
 }
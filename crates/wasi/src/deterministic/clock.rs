@@ -0,0 +1,203 @@
+use super::lock;
+use cap_std::time::{Instant, SystemTime};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, UNIX_EPOCH},
+};
+use wasi_common::{
+    clocks::{WasiMonotonicClock, WasiSystemClock},
+    sched::{
+        subscription::{RwEventFlags, Subscription},
+        Poll,
+        WasiSched,
+    },
+    Error,
+};
+
+/// A virtual clock serving the WASI system and monotonic clocks.
+///
+/// Both clocks share the same virtual timeline measured since the Unix epoch.
+/// The timeline only moves when the clock is read, when the guest sleeps or
+/// when the host explicitly advances it via [`VirtualClock::advance`].
+///
+/// Clones of a [`VirtualClock`] share the same timeline.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    inner: Arc<ClockInner>,
+}
+
+#[derive(Debug)]
+struct ClockInner {
+    /// The real instant that represents the start of the virtual timeline.
+    ///
+    /// # Note
+    ///
+    /// Monotonic clocks cannot be constructed from arbitrary values, so virtual
+    /// monotonic instants are offsets to this instant. Guests only observe the
+    /// offsets since WASI monotonic time is relative to the context creation.
+    base: std::time::Instant,
+    /// The time since the Unix epoch at the start of the virtual timeline.
+    start: Duration,
+    /// The mutable state of the virtual timeline.
+    state: Mutex<ClockState>,
+}
+
+#[derive(Debug)]
+struct ClockState {
+    /// The current time since the Unix epoch.
+    now: Duration,
+    /// The duration the clock advances after each reading.
+    tick: Duration,
+    /// The remaining scripted readings since the Unix epoch.
+    script: VecDeque<Duration>,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::fixed(Duration::ZERO)
+    }
+}
+
+impl VirtualClock {
+    /// Creates a new [`VirtualClock`] starting at `now` since the Unix epoch
+    /// advancing by `tick` after each reading.
+    fn new(now: Duration, tick: Duration, script: VecDeque<Duration>) -> Self {
+        Self {
+            inner: Arc::new(ClockInner {
+                base: std::time::Instant::now(),
+                start: now,
+                state: Mutex::new(ClockState { now, tick, script }),
+            }),
+        }
+    }
+
+    /// Creates a [`VirtualClock`] that is fixed at `now` since the Unix epoch.
+    pub fn fixed(now: Duration) -> Self {
+        Self::new(now, Duration::ZERO, VecDeque::new())
+    }
+
+    /// Creates a [`VirtualClock`] starting at `start` since the Unix epoch
+    /// that advances by `tick` after each reading.
+    pub fn ticking(start: Duration, tick: Duration) -> Self {
+        Self::new(start, tick, VecDeque::new())
+    }
+
+    /// Creates a [`VirtualClock`] that yields the scripted `readings` since the Unix epoch.
+    ///
+    /// Once all `readings` have been consumed the clock stays fixed at the last reading.
+    /// Readings that are earlier than the current time are clamped to keep the clock monotonic.
+    pub fn scripted(readings: impl IntoIterator<Item = Duration>) -> Self {
+        let script = readings.into_iter().collect::<VecDeque<_>>();
+        let start = script.front().copied().unwrap_or_default();
+        Self::new(start, Duration::ZERO, script)
+    }
+
+    /// Returns the current time since the Unix epoch without advancing the clock.
+    pub fn now(&self) -> Duration {
+        self.state().now
+    }
+
+    /// Advances the clock by `delta`.
+    pub fn advance(&self, delta: Duration) {
+        let mut state = self.state();
+        state.now = state.now.saturating_add(delta);
+    }
+
+    /// Reads the clock and advances it according to its configuration.
+    fn read(&self) -> Duration {
+        let mut state = self.state();
+        if let Some(reading) = state.script.pop_front() {
+            state.now = state.now.max(reading);
+            return state.now;
+        }
+        let now = state.now;
+        state.now = now.saturating_add(state.tick);
+        now
+    }
+
+    /// Returns the monotonic instant at the start of the virtual timeline.
+    pub(crate) fn creation_time(&self) -> Instant {
+        Instant::from_std(self.inner.base)
+    }
+
+    /// Converts the time since the Unix epoch `now` into a monotonic instant.
+    fn instant(&self, now: Duration) -> Instant {
+        let offset = now.saturating_sub(self.inner.start);
+        Instant::from_std(self.inner.base + offset)
+    }
+
+    /// Returns exclusive access to the state of the virtual timeline.
+    fn state(&self) -> MutexGuard<ClockState> {
+        lock(&self.inner.state)
+    }
+}
+
+impl WasiSystemClock for VirtualClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self, _precision: Duration) -> SystemTime {
+        SystemTime::from_std(UNIX_EPOCH + self.read())
+    }
+}
+
+impl WasiMonotonicClock for VirtualClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self, _precision: Duration) -> Instant {
+        self.instant(self.read())
+    }
+}
+
+/// A WASI scheduler that never blocks and instead advances a [`VirtualClock`].
+///
+/// - Reads and writes of virtual files are always ready.
+/// - Sleeping and waiting for clock subscriptions advance the [`VirtualClock`].
+#[derive(Debug, Clone)]
+pub struct VirtualSched {
+    clock: VirtualClock,
+}
+
+impl VirtualSched {
+    /// Creates a new [`VirtualSched`] advancing the `clock`.
+    pub fn new(clock: VirtualClock) -> Self {
+        Self { clock }
+    }
+}
+
+#[wiggle::async_trait]
+impl WasiSched for VirtualSched {
+    async fn poll_oneoff<'a>(&self, poll: &mut Poll<'a>) -> Result<(), Error> {
+        let mut ready = false;
+        for subscription in poll.rw_subscriptions() {
+            match subscription {
+                Subscription::Read(read) | Subscription::Write(read) => {
+                    read.complete(0, RwEventFlags::empty());
+                    ready = true;
+                }
+                Subscription::MonotonicClock(_) => {}
+            }
+        }
+        if !ready {
+            if let Some(subscription) = poll.earliest_clock_deadline() {
+                let now = self.clock.instant(self.clock.now());
+                self.clock
+                    .advance(subscription.deadline.saturating_duration_since(now));
+            }
+        }
+        Ok(())
+    }
+
+    async fn sched_yield(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn sleep(&self, duration: Duration) -> Result<(), Error> {
+        self.clock.advance(duration);
+        Ok(())
+    }
+}
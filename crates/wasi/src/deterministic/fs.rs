@@ -0,0 +1,660 @@
+use super::lock;
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    io::{IoSlice, IoSliceMut, SeekFrom},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+        Mutex,
+    },
+};
+use wasi_common::{
+    dir::{ReaddirCursor, ReaddirEntity, WasiDir},
+    file::{FdFlags, FileType, Filestat, OFlags, WasiFile},
+    Error,
+    ErrorExt,
+    SystemTimeSpec,
+};
+
+/// An entry of a [`VirtualDir`].
+#[derive(Debug, Clone)]
+enum Node {
+    File(FileNode),
+    Dir(VirtualDir),
+}
+
+impl Node {
+    /// Returns the [`Filestat`] of the [`Node`].
+    fn filestat(&self) -> Filestat {
+        match self {
+            Self::File(file) => file.filestat(),
+            Self::Dir(dir) => dir.filestat(),
+        }
+    }
+}
+
+/// The shared contents of a virtual file.
+#[derive(Debug, Clone)]
+struct FileNode {
+    inode: u64,
+    data: Arc<Mutex<Vec<u8>>>,
+}
+
+impl FileNode {
+    /// Returns the [`Filestat`] of the virtual file.
+    fn filestat(&self) -> Filestat {
+        Filestat {
+            device_id: 0,
+            inode: self.inode,
+            filetype: FileType::RegularFile,
+            nlink: 1,
+            size: lock(&self.data).len() as u64,
+            atim: None,
+            mtim: None,
+            ctim: None,
+        }
+    }
+}
+
+/// A directory of the in-memory virtual filesystem.
+///
+/// Clones of a [`VirtualDir`] share the same contents so that the host can
+/// inspect all changes made by the guest after the execution.
+///
+/// # Note
+///
+/// - Inodes are assigned in creation order and timestamps are never set
+///   so that all observable metadata is deterministic.
+/// - Guests cannot escape a preopened [`VirtualDir`] via `..`.
+/// - Symbolic and hard links are not supported.
+#[derive(Debug, Clone)]
+pub struct VirtualDir {
+    inner: Arc<DirInner>,
+}
+
+#[derive(Debug)]
+struct DirInner {
+    /// The inode of the directory.
+    inode: u64,
+    /// The next free inode of the virtual filesystem.
+    next_inode: Arc<AtomicU64>,
+    /// The entries of the directory sorted by their names.
+    entries: Mutex<BTreeMap<String, Node>>,
+}
+
+impl Default for VirtualDir {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualDir {
+    /// Creates a new empty [`VirtualDir`] as the root of a virtual filesystem.
+    pub fn new() -> Self {
+        Self::with_inode(Arc::new(AtomicU64::new(1)))
+    }
+
+    /// Creates a new empty [`VirtualDir`] with the next free inode of `next_inode`.
+    fn with_inode(next_inode: Arc<AtomicU64>) -> Self {
+        let inode = next_inode.fetch_add(1, Ordering::Relaxed);
+        Self {
+            inner: Arc::new(DirInner {
+                inode,
+                next_inode,
+                entries: Mutex::new(BTreeMap::new()),
+            }),
+        }
+    }
+
+    /// Returns the next free inode of the virtual filesystem.
+    fn next_inode(&self) -> u64 {
+        self.inner.next_inode.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Inserts a file with the `contents` at the `path` relative to the [`VirtualDir`].
+    ///
+    /// Creates all missing parent directories and replaces existing files.
+    ///
+    /// # Errors
+    ///
+    /// - If the `path` is invalid or escapes the [`VirtualDir`].
+    /// - If the `path` or any of its parents refers to an existing file or directory respectively.
+    pub fn insert_file(&self, path: &str, contents: impl Into<Vec<u8>>) -> Result<(), Error> {
+        let (parent, name) = self.create_parents(path)?;
+        let mut entries = lock(&parent.inner.entries);
+        if let Some(Node::Dir(_)) = entries.get(&name) {
+            return Err(Error::exist());
+        }
+        let file = FileNode {
+            inode: self.next_inode(),
+            data: Arc::new(Mutex::new(contents.into())),
+        };
+        entries.insert(name, Node::File(file));
+        Ok(())
+    }
+
+    /// Creates the directory at the `path` relative to the [`VirtualDir`] including all its parents.
+    ///
+    /// Returns the created or already existing directory.
+    ///
+    /// # Errors
+    ///
+    /// - If the `path` is invalid or escapes the [`VirtualDir`].
+    /// - If the `path` or any of its parents refers to an existing file.
+    pub fn create_dir_all(&self, path: &str) -> Result<VirtualDir, Error> {
+        let mut dir = self.clone();
+        for name in normalize(path)? {
+            let child = lock(&dir.inner.entries)
+                .entry(name)
+                .or_insert_with(|| Node::Dir(Self::with_inode(self.inner.next_inode.clone())))
+                .clone();
+            dir = match child {
+                Node::Dir(child) => child,
+                Node::File(_) => return Err(Error::not_dir()),
+            };
+        }
+        Ok(dir)
+    }
+
+    /// Returns the contents of the file at the `path` relative to the [`VirtualDir`] if any.
+    pub fn read_file(&self, path: &str) -> Option<Vec<u8>> {
+        match self.lookup(path).ok()? {
+            Node::File(file) => Some(lock(&file.data).clone()),
+            Node::Dir(_) => None,
+        }
+    }
+
+    /// Returns the names of all entries of the [`VirtualDir`] in sorted order.
+    pub fn entries(&self) -> Vec<String> {
+        lock(&self.inner.entries).keys().cloned().collect()
+    }
+
+    /// Returns the [`Filestat`] of the [`VirtualDir`].
+    fn filestat(&self) -> Filestat {
+        Filestat {
+            device_id: 0,
+            inode: self.inner.inode,
+            filetype: FileType::Directory,
+            nlink: 1,
+            size: 0,
+            atim: None,
+            mtim: None,
+            ctim: None,
+        }
+    }
+
+    /// Returns the directory at the normalized `names` relative to the [`VirtualDir`].
+    fn dir_at(&self, names: &[String]) -> Result<VirtualDir, Error> {
+        let mut dir = self.clone();
+        for name in names {
+            let child = lock(&dir.inner.entries).get(name).cloned();
+            dir = match child {
+                Some(Node::Dir(child)) => child,
+                Some(Node::File(_)) => return Err(Error::not_dir()),
+                None => return Err(Error::not_found()),
+            };
+        }
+        Ok(dir)
+    }
+
+    /// Returns the parent directory and the name of the entry at the `path`.
+    fn parent_and_name(&self, path: &str) -> Result<(VirtualDir, String), Error> {
+        let mut names = normalize(path)?;
+        let name = names.pop().ok_or_else(Error::invalid_argument)?;
+        Ok((self.dir_at(&names)?, name))
+    }
+
+    /// Returns the parent directory and the name of the entry at the `path`.
+    ///
+    /// Creates all missing parent directories.
+    fn create_parents(&self, path: &str) -> Result<(VirtualDir, String), Error> {
+        let mut names = normalize(path)?;
+        let name = names.pop().ok_or_else(Error::invalid_argument)?;
+        Ok((self.create_dir_all(&names.join("/"))?, name))
+    }
+
+    /// Returns the entry at the `path` relative to the [`VirtualDir`].
+    fn lookup(&self, path: &str) -> Result<Node, Error> {
+        let mut names = normalize(path)?;
+        let Some(name) = names.pop() else {
+            return Ok(Node::Dir(self.clone()));
+        };
+        let parent = self.dir_at(&names)?;
+        let node = lock(&parent.inner.entries).get(&name).cloned();
+        node.ok_or_else(Error::not_found)
+    }
+}
+
+/// Normalizes the relative `path` into the names of its components.
+///
+/// # Errors
+///
+/// If the `path` is absolute or escapes its base directory via `..`.
+fn normalize(path: &str) -> Result<Vec<String>, Error> {
+    if path.starts_with('/') {
+        return Err(Error::perm());
+    }
+    let mut names = Vec::new();
+    for name in path.split('/') {
+        match name {
+            "" | "." => {}
+            ".." => {
+                names.pop().ok_or_else(Error::perm)?;
+            }
+            name => names.push(name.to_string()),
+        }
+    }
+    Ok(names)
+}
+
+#[wiggle::async_trait]
+impl WasiDir for VirtualDir {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn open_file(
+        &self,
+        _symlink_follow: bool,
+        path: &str,
+        oflags: OFlags,
+        read: bool,
+        write: bool,
+        fdflags: FdFlags,
+    ) -> Result<Box<dyn WasiFile>, Error> {
+        let (parent, name) = self.parent_and_name(path)?;
+        let mut entries = lock(&parent.inner.entries);
+        let file = match entries.get(&name) {
+            Some(Node::Dir(_)) => return Err(Error::badf()),
+            Some(Node::File(_)) if oflags.contains(OFlags::CREATE | OFlags::EXCLUSIVE) => {
+                return Err(Error::exist())
+            }
+            Some(Node::File(file)) => {
+                if oflags.contains(OFlags::TRUNCATE) {
+                    lock(&file.data).clear();
+                }
+                file.clone()
+            }
+            None if oflags.contains(OFlags::CREATE) => {
+                let file = FileNode {
+                    inode: self.next_inode(),
+                    data: Arc::default(),
+                };
+                entries.insert(name, Node::File(file.clone()));
+                file
+            }
+            None => return Err(Error::not_found()),
+        };
+        Ok(Box::new(VirtualFile {
+            inode: file.inode,
+            data: file.data,
+            position: 0,
+            read,
+            write,
+            append: fdflags.contains(FdFlags::APPEND),
+        }))
+    }
+
+    async fn open_dir(&self, _symlink_follow: bool, path: &str) -> Result<Box<dyn WasiDir>, Error> {
+        match self.lookup(path)? {
+            Node::Dir(dir) => Ok(Box::new(dir)),
+            Node::File(_) => Err(Error::not_dir()),
+        }
+    }
+
+    async fn create_dir(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = self.parent_and_name(path)?;
+        let mut entries = lock(&parent.inner.entries);
+        if entries.contains_key(&name) {
+            return Err(Error::exist());
+        }
+        let dir = Self::with_inode(self.inner.next_inode.clone());
+        entries.insert(name, Node::Dir(dir));
+        Ok(())
+    }
+
+    async fn readdir(
+        &self,
+        cursor: ReaddirCursor,
+    ) -> Result<Box<dyn Iterator<Item = Result<ReaddirEntity, Error>> + Send>, Error> {
+        let inode = self.inner.inode;
+        let dots = [".", ".."]
+            .into_iter()
+            .map(|name| (name.to_string(), inode, FileType::Directory));
+        let children = lock(&self.inner.entries)
+            .iter()
+            .map(|(name, node)| {
+                let stat = node.filestat();
+                (name.clone(), stat.inode, stat.filetype)
+            })
+            .collect::<Vec<_>>();
+        let entries = dots
+            .chain(children)
+            .enumerate()
+            .map(|(index, (name, inode, filetype))| {
+                Ok(ReaddirEntity {
+                    next: ReaddirCursor::from(index as u64 + 1),
+                    inode,
+                    name,
+                    filetype,
+                })
+            })
+            .skip(u64::from(cursor) as usize)
+            .collect::<Vec<_>>();
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    async fn symlink(&self, _old_path: &str, _new_path: &str) -> Result<(), Error> {
+        Err(Error::not_supported())
+    }
+
+    async fn remove_dir(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = self.parent_and_name(path)?;
+        let mut entries = lock(&parent.inner.entries);
+        match entries.get(&name) {
+            Some(Node::Dir(dir)) if lock(&dir.inner.entries).is_empty() => {
+                entries.remove(&name);
+                Ok(())
+            }
+            Some(Node::Dir(_)) => Err(Error::exist()),
+            Some(Node::File(_)) => Err(Error::not_dir()),
+            None => Err(Error::not_found()),
+        }
+    }
+
+    async fn unlink_file(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = self.parent_and_name(path)?;
+        let mut entries = lock(&parent.inner.entries);
+        match entries.get(&name) {
+            Some(Node::File(_)) => {
+                entries.remove(&name);
+                Ok(())
+            }
+            Some(Node::Dir(_)) => Err(Error::perm()),
+            None => Err(Error::not_found()),
+        }
+    }
+
+    async fn read_link(&self, _path: &str) -> Result<PathBuf, Error> {
+        Err(Error::not_supported())
+    }
+
+    async fn get_filestat(&self) -> Result<Filestat, Error> {
+        Ok(self.filestat())
+    }
+
+    async fn get_path_filestat(
+        &self,
+        path: &str,
+        _follow_symlinks: bool,
+    ) -> Result<Filestat, Error> {
+        Ok(self.lookup(path)?.filestat())
+    }
+
+    async fn rename(
+        &self,
+        path: &str,
+        dest_dir: &dyn WasiDir,
+        dest_path: &str,
+    ) -> Result<(), Error> {
+        let dest_dir = dest_dir
+            .as_any()
+            .downcast_ref::<VirtualDir>()
+            .ok_or_else(Error::not_supported)?;
+        let (src_parent, src_name) = self.parent_and_name(path)?;
+        let (dest_parent, dest_name) = dest_dir.parent_and_name(dest_path)?;
+        let node = lock(&src_parent.inner.entries)
+            .remove(&src_name)
+            .ok_or_else(Error::not_found)?;
+        lock(&dest_parent.inner.entries).insert(dest_name, node);
+        Ok(())
+    }
+
+    async fn hard_link(
+        &self,
+        _path: &str,
+        _target_dir: &dyn WasiDir,
+        _target_path: &str,
+    ) -> Result<(), Error> {
+        Err(Error::not_supported())
+    }
+
+    async fn set_times(
+        &self,
+        _path: &str,
+        _atime: Option<SystemTimeSpec>,
+        _mtime: Option<SystemTimeSpec>,
+        _follow_symlinks: bool,
+    ) -> Result<(), Error> {
+        Err(Error::not_supported())
+    }
+}
+
+/// An open file of the in-memory virtual filesystem.
+#[derive(Debug)]
+pub struct VirtualFile {
+    inode: u64,
+    data: Arc<Mutex<Vec<u8>>>,
+    position: u64,
+    read: bool,
+    write: bool,
+    append: bool,
+}
+
+impl VirtualFile {
+    /// Creates a new read-only [`VirtualFile`] with the `contents`.
+    ///
+    /// This is used to serve stdin.
+    pub fn read_only(contents: impl Into<Vec<u8>>) -> Self {
+        Self {
+            inode: 0,
+            data: Arc::new(Mutex::new(contents.into())),
+            position: 0,
+            read: true,
+            write: false,
+            append: false,
+        }
+    }
+
+    /// Reads from the file at `offset` into the `bufs` and returns the number of read bytes.
+    fn read_at(&self, bufs: &mut [IoSliceMut<'_>], offset: u64) -> Result<u64, Error> {
+        if !self.read {
+            return Err(Error::badf());
+        }
+        let data = lock(&self.data);
+        let mut offset = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(data.len());
+        let start = offset;
+        for buf in bufs {
+            let len = buf.len().min(data.len() - offset);
+            buf[..len].copy_from_slice(&data[offset..offset + len]);
+            offset += len;
+        }
+        Ok((offset - start) as u64)
+    }
+
+    /// Writes the `bufs` to the file at `offset` and returns the number of written bytes.
+    fn write_at(&self, bufs: &[IoSlice<'_>], offset: u64) -> Result<u64, Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        let mut data = lock(&self.data);
+        let mut offset = usize::try_from(offset).map_err(|_| Error::too_big())?;
+        let start = offset;
+        for buf in bufs {
+            let end = offset.checked_add(buf.len()).ok_or_else(Error::too_big)?;
+            if data.len() < end {
+                data.resize(end, 0);
+            }
+            data[offset..end].copy_from_slice(buf);
+            offset = end;
+        }
+        Ok((offset - start) as u64)
+    }
+
+    /// Returns the current length of the file.
+    fn len(&self) -> u64 {
+        lock(&self.data).len() as u64
+    }
+}
+
+#[wiggle::async_trait]
+impl WasiFile for VirtualFile {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn get_filetype(&mut self) -> Result<FileType, Error> {
+        Ok(FileType::RegularFile)
+    }
+
+    async fn get_fdflags(&mut self) -> Result<FdFlags, Error> {
+        if self.append {
+            return Ok(FdFlags::APPEND);
+        }
+        Ok(FdFlags::empty())
+    }
+
+    async fn get_filestat(&mut self) -> Result<Filestat, Error> {
+        let file = FileNode {
+            inode: self.inode,
+            data: self.data.clone(),
+        };
+        Ok(file.filestat())
+    }
+
+    async fn set_filestat_size(&mut self, size: u64) -> Result<(), Error> {
+        if !self.write {
+            return Err(Error::badf());
+        }
+        let size = usize::try_from(size).map_err(|_| Error::too_big())?;
+        lock(&self.data).resize(size, 0);
+        Ok(())
+    }
+
+    async fn read_vectored<'a>(&mut self, bufs: &mut [IoSliceMut<'a>]) -> Result<u64, Error> {
+        let read = self.read_at(bufs, self.position)?;
+        self.position += read;
+        Ok(read)
+    }
+
+    async fn read_vectored_at<'a>(
+        &mut self,
+        bufs: &mut [IoSliceMut<'a>],
+        offset: u64,
+    ) -> Result<u64, Error> {
+        self.read_at(bufs, offset)
+    }
+
+    async fn write_vectored<'a>(&mut self, bufs: &[IoSlice<'a>]) -> Result<u64, Error> {
+        if self.append {
+            self.position = self.len();
+        }
+        let written = self.write_at(bufs, self.position)?;
+        self.position += written;
+        Ok(written)
+    }
+
+    async fn write_vectored_at<'a>(
+        &mut self,
+        bufs: &[IoSlice<'a>],
+        offset: u64,
+    ) -> Result<u64, Error> {
+        self.write_at(bufs, offset)
+    }
+
+    async fn seek(&mut self, pos: SeekFrom) -> Result<u64, Error> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+            SeekFrom::End(delta) => self.len().checked_add_signed(delta),
+        };
+        self.position = position.ok_or_else(Error::invalid_argument)?;
+        Ok(self.position)
+    }
+
+    async fn peek(&mut self, buf: &mut [u8]) -> Result<u64, Error> {
+        self.read_at(&mut [IoSliceMut::new(buf)], self.position)
+    }
+
+    async fn num_ready_bytes(&self) -> Result<u64, Error> {
+        Ok(self.len().saturating_sub(self.position))
+    }
+
+    async fn readable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn writable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// A captured output stream such as stdout or stderr.
+///
+/// Clones of an [`OutputBuffer`] share the same contents so that the host can
+/// inspect the output after the execution.
+#[derive(Debug, Clone, Default)]
+pub struct OutputBuffer {
+    data: Arc<Mutex<Vec<u8>>>,
+}
+
+impl OutputBuffer {
+    /// Returns a copy of all bytes written so far.
+    pub fn contents(&self) -> Vec<u8> {
+        lock(&self.data).clone()
+    }
+
+    /// Returns all bytes written so far and clears the [`OutputBuffer`].
+    pub fn take(&self) -> Vec<u8> {
+        core::mem::take(&mut *lock(&self.data))
+    }
+}
+
+#[wiggle::async_trait]
+impl WasiFile for OutputBuffer {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn get_filetype(&mut self) -> Result<FileType, Error> {
+        Ok(FileType::Pipe)
+    }
+
+    async fn get_fdflags(&mut self) -> Result<FdFlags, Error> {
+        Ok(FdFlags::APPEND)
+    }
+
+    async fn write_vectored<'a>(&mut self, bufs: &[IoSlice<'a>]) -> Result<u64, Error> {
+        let mut data = lock(&self.data);
+        let mut written = 0;
+        for buf in bufs {
+            data.extend_from_slice(buf);
+            written += buf.len() as u64;
+        }
+        Ok(written)
+    }
+
+    async fn write_vectored_at<'a>(
+        &mut self,
+        _bufs: &[IoSlice<'a>],
+        _offset: u64,
+    ) -> Result<u64, Error> {
+        Err(Error::seek_pipe())
+    }
+
+    async fn seek(&mut self, _pos: SeekFrom) -> Result<u64, Error> {
+        Err(Error::seek_pipe())
+    }
+
+    async fn readable(&self) -> Result<(), Error> {
+        Err(Error::badf())
+    }
+
+    async fn writable(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+//! A deterministic and fully virtualized WASI `preview1` backend.
+//!
+//! Unlike the default backend which forwards clocks, randomness and the
+//! filesystem to the real host, the [`DeterministicWasi`] backend serves all
+//! of them from virtual state:
+//!
+//! - an in-memory filesystem of [`VirtualDir`]s,
+//! - a fixed, ticking or scripted [`VirtualClock`],
+//! - a [`SeededRng`] random number generator,
+//! - captured stdout and stderr [`OutputBuffer`]s.
+//!
+//! Therefore two executions of the same Wasm module with the same
+//! [`DeterministicWasi`] configuration always observe the same WASI results
+//! which makes their execution traces reproducible.
+
+mod clock;
+mod fs;
+mod random;
+
+pub use self::{
+    clock::{VirtualClock, VirtualSched},
+    fs::{OutputBuffer, VirtualDir, VirtualFile},
+    random::SeededRng,
+};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use wasi_common::{clocks::WasiClocks, Error, Table, WasiCtx};
+
+/// Locks the `mutex` of some virtual state.
+///
+/// Poisoning is ignored since all virtual state is updated atomically.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The configuration of a deterministic and virtualized WASI context.
+///
+/// Use [`DeterministicWasi::build`] or `WasiCtxBuilderExt::build_deterministic`
+/// to build a [`WasiCtx`] from it.
+///
+/// Clones share the same [`VirtualClock`], [`OutputBuffer`]s and [`VirtualDir`]s.
+#[derive(Debug, Clone)]
+pub struct DeterministicWasi {
+    /// The clock serving `clock_time_get`, `poll_oneoff` and `sched_yield`.
+    clock: VirtualClock,
+    /// The seed of the random number generator serving `random_get`.
+    seed: u64,
+    /// The contents of stdin.
+    stdin: Vec<u8>,
+    /// The captured stdout.
+    stdout: OutputBuffer,
+    /// The captured stderr.
+    stderr: OutputBuffer,
+    /// The virtual directories preopened under their guest paths.
+    preopens: Vec<(String, VirtualDir)>,
+}
+
+impl Default for DeterministicWasi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeterministicWasi {
+    /// Creates a new [`DeterministicWasi`] configuration.
+    ///
+    /// By default the clock is fixed at the Unix epoch, the random seed is zero,
+    /// stdin is empty and no directories are preopened.
+    pub fn new() -> Self {
+        Self {
+            clock: VirtualClock::default(),
+            seed: 0,
+            stdin: Vec::new(),
+            stdout: OutputBuffer::default(),
+            stderr: OutputBuffer::default(),
+            preopens: Vec::new(),
+        }
+    }
+
+    /// Sets the [`VirtualClock`] used for all WASI clocks.
+    pub fn clock(mut self, clock: VirtualClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the `seed` of the random number generator.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the contents of stdin.
+    pub fn stdin(mut self, contents: impl Into<Vec<u8>>) -> Self {
+        self.stdin = contents.into();
+        self
+    }
+
+    /// Preopens the virtual `dir` under the `guest_path`.
+    pub fn preopened_dir(mut self, dir: VirtualDir, guest_path: impl Into<String>) -> Self {
+        self.preopens.push((guest_path.into(), dir));
+        self
+    }
+
+    /// Returns the captured stdout.
+    pub fn stdout(&self) -> &OutputBuffer {
+        &self.stdout
+    }
+
+    /// Returns the captured stderr.
+    pub fn stderr(&self) -> &OutputBuffer {
+        &self.stderr
+    }
+
+    /// Returns the [`VirtualClock`] used for all WASI clocks.
+    pub fn virtual_clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Builds a new [`WasiCtx`] that is entirely served by the virtual state.
+    ///
+    /// The [`WasiCtx`] has no arguments, environment variables or host resources.
+    ///
+    /// # Errors
+    ///
+    /// If preopening any of the virtual directories fails.
+    pub fn build(&self) -> Result<WasiCtx, Error> {
+        let clocks = WasiClocks {
+            system: Box::new(self.clock.clone()),
+            monotonic: Box::new(self.clock.clone()),
+            creation_time: self.clock.creation_time(),
+        };
+        let mut ctx = WasiCtx::new(
+            Box::new(SeededRng::new(self.seed)),
+            clocks,
+            Box::new(VirtualSched::new(self.clock.clone())),
+            Table::new(),
+        );
+        ctx.set_stdin(Box::new(VirtualFile::read_only(self.stdin.clone())));
+        ctx.set_stdout(Box::new(self.stdout.clone()));
+        ctx.set_stderr(Box::new(self.stderr.clone()));
+        for (guest_path, dir) in &self.preopens {
+            ctx.push_preopened_dir(Box::new(dir.clone()), guest_path)?;
+        }
+        Ok(ctx)
+    }
+}
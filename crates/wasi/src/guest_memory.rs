@@ -2,15 +2,28 @@ use wiggle::{borrow::BorrowChecker, BorrowHandle, GuestError, GuestMemory, Regio
 
 /// Lightweight `wasmi::Memory` wrapper so we can implement the
 /// `wiggle::GuestMemory` trait on it.
+///
+/// # Note
+///
+/// Only exclusively borrowed linear memories can be wrapped. The bytes of
+/// shared linear memories may be accessed by other threads concurrently.
 pub struct WasmiGuestMemory<'a> {
     mem: &'a mut [u8],
+    len: u32,
     bc: BorrowChecker,
 }
 
 impl<'a> WasmiGuestMemory<'a> {
-    pub fn new(mem: &'a mut [u8]) -> Self {
-        Self {
+    /// Creates a [`WasmiGuestMemory`] for the bytes of a linear memory.
+    ///
+    /// # Errors
+    ///
+    /// If the length of `mem` does not fit into a `u32` as required by `wiggle`.
+    pub fn new(mem: &'a mut [u8]) -> Result<Self, GuestError> {
+        let len = u32::try_from(mem.len()).map_err(|_| GuestError::PtrOverflow)?;
+        Ok(Self {
             mem,
+            len,
             // Wiggle does not expose any methods for functions to re-enter
             // the WebAssembly instance, or expose the memory via non-wiggle
             // mechanisms. However, the user-defined code may end up
@@ -18,13 +31,13 @@ impl<'a> WasmiGuestMemory<'a> {
             // implementation - we require exactly one BorrowChecker exist per
             // instance.
             bc: BorrowChecker::new(),
-        }
+        })
     }
 }
 
 unsafe impl GuestMemory for WasmiGuestMemory<'_> {
     fn base(&self) -> (*mut u8, u32) {
-        (self.mem.as_ptr() as *mut u8, self.mem.len() as u32)
+        (self.mem.as_ptr() as *mut u8, self.len)
     }
     fn has_outstanding_borrows(&self) -> bool {
         self.bc.has_outstanding_borrows()
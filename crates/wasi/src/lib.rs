@@ -1,13 +1,16 @@
 //! This crate provides support for WASI `preview1` for the `wasmi` interpreter.
 //!
 //! Use [`add_to_linker`] to add all supported WASI definitions to the `wasmi` linker.
+//!
+//! The [`deterministic`] module provides a virtualized WASI backend for reproducible executions.
 
+pub mod deterministic;
 mod guest_memory;
 
 #[cfg(feature = "sync")]
 pub mod sync;
 
-pub use self::guest_memory::WasmiGuestMemory;
+pub use self::{deterministic::DeterministicWasi, guest_memory::WasmiGuestMemory};
 pub use wasi_common::{Error, WasiCtx, WasiDir, WasiFile};
 
 /// Sync mode is the "default" of this crate, so we also export it at the top level.
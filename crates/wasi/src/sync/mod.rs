@@ -8,3 +8,35 @@ pub use wasi_cap_std_sync::*;
 
 #[doc(inline)]
 pub use self::snapshots::preview_1::add_wasi_snapshot_preview1_to_linker as add_to_linker;
+use crate::DeterministicWasi;
+use std::mem;
+use wasi_common::{Error, WasiCtx};
+
+/// Extension methods to select the WASI backend of a [`WasiCtxBuilder`].
+pub trait WasiCtxBuilderExt {
+    /// Builds a [`WasiCtx`] served by the deterministic and virtualized `wasi` backend.
+    ///
+    /// Only the arguments and environment variables of the builder are kept.
+    /// All other state of the builder, such as stdio and preopened directories,
+    /// is dropped and served by the virtual state of `wasi` instead.
+    ///
+    /// # Note
+    ///
+    /// Environment variables inherited from the host via
+    /// [`WasiCtxBuilder::inherit_env`] are kept as well.
+    ///
+    /// # Errors
+    ///
+    /// If preopening any of the virtual directories fails.
+    fn build_deterministic(self, wasi: &DeterministicWasi) -> Result<WasiCtx, Error>;
+}
+
+impl WasiCtxBuilderExt for WasiCtxBuilder {
+    fn build_deterministic(self, wasi: &DeterministicWasi) -> Result<WasiCtx, Error> {
+        let mut host = self.build();
+        let mut ctx = wasi.build()?;
+        mem::swap(&mut ctx.args, &mut host.args);
+        mem::swap(&mut ctx.env, &mut host.env);
+        Ok(ctx)
+    }
+}
@@ -1,33 +1,27 @@
 use crate::WasmiGuestMemory;
-use std::{
-    pin::Pin,
-    task::{Context, RawWaker, RawWakerVTable, Waker},
-};
 use wasi_common::Error;
-use wasmi::{core::Trap, Caller, Extern, Linker};
-
-// Creates a dummy `RawWaker`. We can only create Wakers from `RawWaker`s
-fn dummy_raw_waker() -> RawWaker {
-    fn no_op(_: *const ()) {}
-    //returns a new RawWaker by calling dummy_raw_waker again
-    fn clone(_: *const ()) -> RawWaker {
-        dummy_raw_waker()
-    }
-    // RawWakerVTable specifies the functions that should be called when the RawWaker is cloned, woken, or dropped.
-    let vtable = &RawWakerVTable::new(clone, no_op, no_op, no_op);
+use wasmi::{
+    core::{Trap, UntypedValue},
+    Caller,
+    Extern,
+    Linker,
+};
 
-    RawWaker::new(std::ptr::null::<()>(), vtable)
+/// Results of WASI functions that are recorded as raw bits in the execution trace.
+trait SyscallResult {
+    /// Returns the raw bits of the result values.
+    fn to_bits(&self) -> Vec<u64>;
 }
 
-// Creates a dummy waker which does *nothing*, as the future itsef polls to ready at first poll
-// A waker is needed to do any polling at all, as it is the primary constituent of the `Context` for polling
-fn run_in_dummy_executor<F: std::future::Future>(f: F) -> Result<F::Output, Trap> {
-    let mut f = Pin::from(Box::new(f));
-    let waker = unsafe { Waker::from_raw(dummy_raw_waker()) };
-    let mut cx = Context::from_waker(&waker);
-    match f.as_mut().poll(&mut cx) {
-        std::task::Poll::Ready(val) => Ok(val),
-        std::task::Poll::Pending => Err(Trap::new("Cannot wait on pending future")),
+impl SyscallResult for i32 {
+    fn to_bits(&self) -> Vec<u64> {
+        vec![UntypedValue::from(*self).to_bits()]
+    }
+}
+
+impl SyscallResult for () {
+    fn to_bits(&self) -> Vec<u64> {
+        Vec::new()
     }
 }
 
@@ -53,34 +47,61 @@ macro_rules! impl_add_to_linker_for_funcs {
         ///
         /// `WASI` is versioned in snapshots. It's still a WIP. Currently, this crate supports `preview_1`
         /// Look [here](https://github.com/WebAssembly/WASI/blob/main/phases/snapshot/docs.md) for more details.
+        ///
+        /// Every served WASI call is recorded as a system call in the execution trace of the `Store`.
+        ///
+        /// The WASI functions are asynchronous host functions. Use `Func::call_async` to
+        /// await WASI calls that are pending, e.g. because of an asynchronous `WasiSched`.
+        /// Other call APIs return an error for pending WASI calls.
         pub fn add_wasi_snapshot_preview1_to_linker<T, U>(
             linker: &mut Linker<T>,
             wasi_ctx: impl Fn(&mut T) -> &mut U + Send + Sync + Copy + 'static,
         ) -> Result<(), Error>
-        where U: wasi_common::snapshots::preview_1::wasi_snapshot_preview1::WasiSnapshotPreview1 +
+        where T: 'static,
+              U: wasi_common::snapshots::preview_1::wasi_snapshot_preview1::WasiSnapshotPreview1 +
                  wasi_common::snapshots::preview_1::wasi_snapshot_preview1::UserErrorConversion
         {
             $(
                 // $(#[$docs])* // TODO: find place for docs
-                linker.func_wrap(
+                linker.func_wrap_async(
                     "wasi_snapshot_preview1",
                     stringify!($fname),
-                    move |mut caller: Caller<'_, T>, $($arg : $typ,)*| {
-                        let result = async {
+                    move |mut caller: Caller<'_, T>, ($($arg,)*): ($($typ,)*)| {
+                        Box::new(async move {
                             let memory = match caller.get_export("memory") {
                                 Some(Extern::Memory(m)) => m,
                                 _ => return Err(Trap::new(String::from("missing required WASI memory export"))),
                             };
-                            let(memory, ctx) = memory.data_and_store_mut(&mut caller);
-                            let ctx = wasi_ctx(ctx);
-                            let memory = WasmiGuestMemory::new(memory);
-                            match wasi_common::snapshots::preview_1::wasi_snapshot_preview1::$fname(ctx, &memory, $($arg,)*).await {
-                                Ok(r) => Ok(<$ret>::from(r)),
+                            let result = {
+                                let (bytes, ctx) = memory.try_data_and_store_mut(&mut caller);
+                                let ctx = wasi_ctx(ctx);
+                                let memory = match bytes {
+                                    Some(bytes) => WasmiGuestMemory::new(bytes)
+                                        .map_err(|error| Trap::new(error.to_string()))?,
+                                    None => {
+                                        // Shared linear memories may be accessed by other
+                                        // threads concurrently which WASI functions cannot handle.
+                                        return Err(Trap::new(String::from(
+                                            "WASI functions cannot access shared linear memories",
+                                        )));
+                                    }
+                                };
+                                wasi_common::snapshots::preview_1::wasi_snapshot_preview1::$fname(ctx, &memory, $($arg,)*).await
+                            };
+                            let params: &[u64] = &[$(UntypedValue::from($arg).to_bits(),)*];
+                            match result {
+                                Ok(r) => {
+                                    let r = <$ret>::from(r);
+                                    caller.record_syscall(stringify!($fname), params, &r.to_bits());
+                                    Ok(r)
+                                }
                                 Err(wiggle::Trap::String(err)) => Err(Trap::new(err)),
-                                Err(wiggle::Trap::I32Exit(i)) => Err(Trap::i32_exit(i)),
+                                Err(wiggle::Trap::I32Exit(i)) => {
+                                    caller.record_syscall(stringify!($fname), params, &[]);
+                                    Err(Trap::i32_exit(i))
+                                }
                             }
-                        };
-                        run_in_dummy_executor(result)?
+                        })
                     }
                 )?;
             )*
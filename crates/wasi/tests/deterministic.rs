@@ -0,0 +1,178 @@
+use std::time::Duration;
+use wasi_cap_std_sync::{ambient_authority, Dir, WasiCtxBuilder};
+use wasmi::{Engine, Extern, Linker, Module, Store};
+use wasmi_wasi::{
+    add_to_linker,
+    deterministic::{VirtualClock, VirtualDir},
+    DeterministicWasi,
+    WasiCtx,
+    WasiCtxBuilderExt,
+};
+
+/// Runs the `_start` function of `deterministic.wat` with the `wasi` backend.
+///
+/// Returns the store and the contents of the linear memory afterwards.
+fn run(wasi: &DeterministicWasi) -> (Store<WasiCtx>, Vec<u8>) {
+    let wasm = wat::parse_bytes(include_bytes!("wat/deterministic.wat")).unwrap();
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<WasiCtx>>::new(&engine);
+    add_to_linker(&mut linker, |ctx| ctx).unwrap();
+    let ctx = WasiCtxBuilder::new().build_deterministic(wasi).unwrap();
+    let mut store = Store::new(&engine, ctx);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    instance
+        .get_export(&store, "_start")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .call(&mut store, &[], &mut [])
+        .unwrap();
+    let memory = instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .unwrap();
+    let data = memory.data(&store).to_vec();
+    (store, data)
+}
+
+/// Returns a [`DeterministicWasi`] with an `input.txt` file and a scripted clock.
+fn setup(seed: u64) -> (DeterministicWasi, VirtualDir) {
+    let dir = VirtualDir::new();
+    dir.insert_file("input.txt", "file contents").unwrap();
+    let clock = VirtualClock::scripted([Duration::from_secs(10), Duration::from_secs(20)]);
+    let wasi = DeterministicWasi::new()
+        .clock(clock)
+        .seed(seed)
+        .preopened_dir(dir.clone(), "/");
+    (wasi, dir)
+}
+
+fn read_u64(memory: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(memory[offset..offset + 8].try_into().unwrap())
+}
+
+#[test]
+fn deterministic_backend_is_virtualized() {
+    let (wasi, dir) = setup(42);
+    let (_store, memory) = run(&wasi);
+    assert_eq!(wasi.stdout().contents(), b"hello\n");
+    assert_eq!(wasi.stderr().contents(), b"file contents");
+    assert_eq!(read_u64(&memory, 304), 10_000_000_000);
+    assert_eq!(read_u64(&memory, 312), 20_000_000_000);
+    assert_eq!(dir.read_file("output.txt").unwrap(), b"hello\n");
+    assert_eq!(dir.entries(), ["input.txt", "output.txt"]);
+}
+
+#[test]
+fn deterministic_backend_is_reproducible() {
+    let (first, _) = setup(42);
+    let (second, _) = setup(42);
+    let (third, _) = setup(43);
+    let (first_store, first_memory) = run(&first);
+    let (second_store, second_memory) = run(&second);
+    let (_, third_memory) = run(&third);
+    assert_eq!(first_memory, second_memory);
+    assert_ne!(read_u64(&first_memory, 320), read_u64(&third_memory, 320));
+    assert_eq!(first_store.tracer.to_json(), second_store.tracer.to_json());
+}
+
+#[test]
+fn deterministic_backend_records_syscalls() {
+    let (wasi, _) = setup(0);
+    let (store, _) = run(&wasi);
+    let names = store
+        .tracer
+        .syscalls()
+        .iter()
+        .map(|syscall| syscall.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "fd_write",
+            "clock_time_get",
+            "clock_time_get",
+            "random_get",
+            "path_open",
+            "fd_read",
+            "fd_write",
+            "path_open",
+            "fd_write",
+        ]
+    );
+    // All served calls succeed with `errno::success`.
+    assert!(store
+        .tracer
+        .syscalls()
+        .iter()
+        .all(|syscall| syscall.results == [0]));
+}
+
+#[test]
+fn virtual_dirs_cannot_be_escaped() {
+    let dir = VirtualDir::new();
+    dir.insert_file("a/b.txt", "b").unwrap();
+    assert_eq!(dir.read_file("a/../a/./b.txt").unwrap(), b"b");
+    assert!(dir.insert_file("../c.txt", "c").is_err());
+    assert!(dir.insert_file("/c.txt", "c").is_err());
+    assert!(dir.insert_file("a/b.txt/c.txt", "c").is_err());
+    assert_eq!(dir.entries(), ["a"]);
+}
+
+#[test]
+fn host_state_of_builder_is_not_visible() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "wasi_snapshot_preview1" "fd_prestat_get"
+                (func $fd_prestat_get (param i32 i32) (result i32)))
+            (import "wasi_snapshot_preview1" "environ_sizes_get"
+                (func $environ_sizes_get (param i32 i32) (result i32)))
+            (memory (export "memory") 1)
+            ;; Returns the `errno` of querying the first preopened directory.
+            (func (export "prestat") (result i32)
+                (call $fd_prestat_get (i32.const 3) (i32.const 0))
+            )
+            ;; Returns the number of environment variables.
+            (func (export "environ_count") (result i32)
+                (drop (call $environ_sizes_get (i32.const 0) (i32.const 4)))
+                (i32.load (i32.const 0))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<WasiCtx>>::new(&engine);
+    add_to_linker(&mut linker, |ctx| ctx).unwrap();
+    let host_dir = Dir::open_ambient_dir(std::env::temp_dir(), ambient_authority()).unwrap();
+    let ctx = WasiCtxBuilder::new()
+        .preopened_dir(host_dir, "/host")
+        .unwrap()
+        .env("KEY", "VALUE")
+        .unwrap()
+        .build_deterministic(&DeterministicWasi::new())
+        .unwrap();
+    let mut store = Store::new(&engine, ctx);
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let mut call = |name: &str| {
+        instance
+            .get_typed_func::<(), i32>(&store, name)
+            .unwrap()
+            .call(&mut store, ())
+            .unwrap()
+    };
+    // The host directory is not preopened: `errno::badf`.
+    assert_eq!(call("prestat"), 8);
+    // Environment variables of the builder are kept.
+    assert_eq!(call("environ_count"), 1);
+}
@@ -1,5 +1,14 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+    thread::{self, Thread},
+    time::Duration,
+};
 use wasi_cap_std_sync::WasiCtxBuilder;
-use wasmi::{Config, Engine, Extern, Instance, Linker, Module, Store};
+use wasi_common::{sched::WasiSched, Error};
+use wasmi::{Config, Engine, Extern, Instance, Linker, Module, Store, Value};
 use wasmi_wasi::{add_to_linker, WasiCtx};
 
 pub fn load_instance_from_wat(wat_bytes: &[u8]) -> (Store<WasiCtx>, wasmi::Instance) {
@@ -45,3 +54,133 @@ fn test_hello_world() {
     let mut result = [];
     f.call(&mut store, &[], &mut result).unwrap();
 }
+
+#[test]
+fn test_shared_memory_traps() {
+    let wasm = wat2wasm(
+        br#"
+        (module
+            (import "wasi_snapshot_preview1" "proc_exit" (func $proc_exit (param i32)))
+            (memory (export "memory") 1 1 shared)
+            (func (export "_start")
+                (call $proc_exit (i32.const 0))
+            )
+        )
+        "#,
+    );
+    let mut config = Config::default();
+    config.wasm_threads(true).deterministic_tracing(false);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<WasiCtx>>::new(&engine);
+    let mut store = Store::new(&engine, WasiCtxBuilder::new().build());
+    add_to_linker(&mut linker, |ctx| ctx).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let f = instance
+        .get_export(&store, "_start")
+        .and_then(Extern::into_func)
+        .unwrap();
+    let error = f.call(&mut store, &[], &mut []).unwrap_err();
+    assert!(error.to_string().contains("shared linear memories"));
+}
+
+/// Wakes up the blocked thread of [`block_on`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the `future` to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// A future that is pending exactly once before it resolves.
+#[derive(Default)]
+struct YieldOnce {
+    yielded: bool,
+}
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.yielded {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        Poll::Ready(())
+    }
+}
+
+/// A [`WasiSched`] whose `sched_yield` is pending once before it completes.
+struct YieldingSched;
+
+#[wiggle::async_trait]
+impl WasiSched for YieldingSched {
+    async fn poll_oneoff<'a>(&self, _poll: &mut wasi_common::sched::Poll<'a>) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn sched_yield(&self) -> Result<(), Error> {
+        YieldOnce::default().await;
+        Ok(())
+    }
+
+    async fn sleep(&self, _duration: Duration) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_pending_wasi_call() {
+    let wasm = wat2wasm(
+        br#"
+        (module
+            (import "wasi_snapshot_preview1" "sched_yield" (func $sched_yield (result i32)))
+            (memory (export "memory") 1)
+            (func (export "run") (result i32)
+                (call $sched_yield)
+            )
+        )
+        "#,
+    );
+    let engine = Engine::default();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut linker = <Linker<WasiCtx>>::new(&engine);
+    let mut wasi = WasiCtxBuilder::new().build();
+    wasi.sched = Box::new(YieldingSched);
+    let mut store = Store::new(&engine, wasi);
+    add_to_linker(&mut linker, |ctx| ctx).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let f = instance
+        .get_export(&store, "run")
+        .and_then(Extern::into_func)
+        .unwrap();
+    let mut result = [Value::I32(-1)];
+    // Synchronous calls cannot wait on the pending WASI call.
+    let error = f.call(&mut store, &[], &mut result).unwrap_err();
+    assert!(error.to_string().contains("Func::call_async"));
+    block_on(f.call_async(&mut store, &[], &mut result)).unwrap();
+    assert_eq!(result[0].i32(), Some(0));
+}
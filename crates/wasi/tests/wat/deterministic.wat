@@ -0,0 +1,63 @@
+;; Exercises the WASI functions served by the deterministic backend.
+(module
+    (import "wasi_snapshot_preview1" "fd_read" (func $fd_read (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "fd_write" (func $fd_write (param i32 i32 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "path_open"
+        (func $path_open (param i32 i32 i32 i32 i32 i64 i64 i32 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "clock_time_get" (func $clock_time_get (param i32 i64 i32) (result i32)))
+    (import "wasi_snapshot_preview1" "random_get" (func $random_get (param i32 i32) (result i32)))
+
+    (memory (export "memory") 1)
+
+    (data (i32.const 100) "hello\n")
+    (data (i32.const 200) "input.txt")
+    (data (i32.const 220) "output.txt")
+
+    ;; Traps if the WASI `errno` is not success.
+    (func $check (param $errno i32)
+        (if (local.get $errno) (then unreachable))
+    )
+
+    ;; Writes `len` bytes at `ptr` to the file descriptor `fd`.
+    (func $write (param $fd i32) (param $ptr i32) (param $len i32)
+        (i32.store (i32.const 0) (local.get $ptr))
+        (i32.store (i32.const 4) (local.get $len))
+        (call $check (call $fd_write (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 400)))
+    )
+
+    ;; Opens the file at `ptr` and `len` in the preopened directory with `oflags`.
+    (func $open (param $ptr i32) (param $len i32) (param $oflags i32) (result i32)
+        (call $check
+            (call $path_open
+                (i32.const 3) ;; preopened directory
+                (i32.const 0) ;; lookup flags
+                (local.get $ptr)
+                (local.get $len)
+                (local.get $oflags)
+                (i64.const 0x42) ;; fd_read | fd_write
+                (i64.const 0)
+                (i32.const 0) ;; fd flags
+                (i32.const 404)
+            )
+        )
+        (i32.load (i32.const 404))
+    )
+
+    (func (export "_start")
+        (local $fd i32)
+        (call $write (i32.const 1) (i32.const 100) (i32.const 6))
+        ;; Read the realtime clock twice and 8 random bytes.
+        (call $check (call $clock_time_get (i32.const 0) (i64.const 1) (i32.const 304)))
+        (call $check (call $clock_time_get (i32.const 0) (i64.const 1) (i32.const 312)))
+        (call $check (call $random_get (i32.const 320) (i32.const 8)))
+        ;; Copy the contents of `input.txt` to stderr.
+        (local.set $fd (call $open (i32.const 200) (i32.const 9) (i32.const 0)))
+        (i32.store (i32.const 0) (i32.const 1024))
+        (i32.store (i32.const 4) (i32.const 64))
+        (call $check (call $fd_read (local.get $fd) (i32.const 0) (i32.const 1) (i32.const 400)))
+        (call $write (i32.const 2) (i32.const 1024) (i32.load (i32.const 400)))
+        ;; Create `output.txt` and write the greeting to it.
+        (local.set $fd (call $open (i32.const 220) (i32.const 10) (i32.const 1)))
+        (call $write (local.get $fd) (i32.const 100) (i32.const 6))
+    )
+)
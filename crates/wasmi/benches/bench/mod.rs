@@ -21,13 +21,24 @@ pub fn load_wasm_from_file(file_name: &str) -> Vec<u8> {
 }
 
 /// Returns a [`Config`] useful for benchmarking.
-fn bench_config() -> Config {
+pub fn bench_config() -> Config {
     let mut config = Config::default();
     config.wasm_tail_call(true);
     config.set_stack_limits(StackLimits::new(1024, 1024 * 1024, 64 * 1024).unwrap());
     config
 }
 
+/// Returns a [`Config`] useful for benchmarking without execution tracing.
+///
+/// Enables the superinstruction fusion pass if `superinstructions` is `true`.
+pub fn bench_config_untraced(superinstructions: bool) -> Config {
+    let mut config = bench_config();
+    config
+        .deterministic_tracing(false)
+        .superinstructions(superinstructions);
+    config
+}
+
 /// Parses the Wasm binary at the given `file_name` into a `wasmi` module.
 ///
 /// # Note
@@ -38,8 +49,21 @@ fn bench_config() -> Config {
 ///
 /// If the benchmark Wasm file could not be opened, read or parsed.
 pub fn load_module_from_file(file_name: &str) -> wasmi::Module {
+    load_module_from_file_with_config(file_name, &bench_config())
+}
+
+/// Parses the Wasm binary at the given `file_name` into a `wasmi` module using `config`.
+///
+/// # Note
+///
+/// This includes validation and compilation to `wasmi` bytecode.
+///
+/// # Panics
+///
+/// If the benchmark Wasm file could not be opened, read or parsed.
+pub fn load_module_from_file_with_config(file_name: &str, config: &Config) -> wasmi::Module {
     let wasm = load_wasm_from_file(file_name);
-    let engine = wasmi::Engine::new(&bench_config());
+    let engine = wasmi::Engine::new(config);
     wasmi::Module::new(&engine, &wasm[..]).unwrap_or_else(|error| {
         panic!(
             "could not parse Wasm module from file {}: {}",
@@ -58,7 +82,23 @@ pub fn load_module_from_file(file_name: &str) -> wasmi::Module {
 ///
 /// If the benchmark Wasm file could not be opened, read or parsed.
 pub fn load_instance_from_file(file_name: &str) -> (wasmi::Store<()>, wasmi::Instance) {
-    let module = load_module_from_file(file_name);
+    load_instance_from_file_with_config(file_name, &bench_config())
+}
+
+/// Parses the Wasm binary from the given `file_name` into a `wasmi` module using `config`.
+///
+/// # Note
+///
+/// This includes validation and compilation to `wasmi` bytecode.
+///
+/// # Panics
+///
+/// If the benchmark Wasm file could not be opened, read or parsed.
+pub fn load_instance_from_file_with_config(
+    file_name: &str,
+    config: &Config,
+) -> (wasmi::Store<()>, wasmi::Instance) {
+    let module = load_module_from_file_with_config(file_name, config);
     let linker = <wasmi::Linker<()>>::new(module.engine());
     let mut store = wasmi::Store::new(module.engine(), ());
     let instance = linker
@@ -84,8 +124,24 @@ pub fn wat2wasm(bytes: &[u8]) -> Vec<u8> {
 ///
 /// If the benchmark Wasm file could not be opened, read or parsed.
 pub fn load_instance_from_wat(wat_bytes: &[u8]) -> (wasmi::Store<()>, wasmi::Instance) {
+    load_instance_from_wat_with_config(wat_bytes, &bench_config())
+}
+
+/// Parses the Wasm source from the given `.wat` bytes into a `wasmi` module using `config`.
+///
+/// # Note
+///
+/// This includes validation and compilation to `wasmi` bytecode.
+///
+/// # Panics
+///
+/// If the benchmark Wasm file could not be opened, read or parsed.
+pub fn load_instance_from_wat_with_config(
+    wat_bytes: &[u8],
+    config: &Config,
+) -> (wasmi::Store<()>, wasmi::Instance) {
     let wasm = wat2wasm(wat_bytes);
-    let engine = wasmi::Engine::new(&bench_config());
+    let engine = wasmi::Engine::new(config);
     let module = wasmi::Module::new(&engine, &wasm[..]).unwrap();
     let linker = <wasmi::Linker<()>>::new(&engine);
     let mut store = wasmi::Store::new(&engine, ());
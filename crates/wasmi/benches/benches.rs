@@ -1,15 +1,19 @@
 mod bench;
 
 use self::bench::{
+    bench_config,
+    bench_config_untraced,
     load_instance_from_file,
+    load_instance_from_file_with_config,
     load_instance_from_wat,
+    load_instance_from_wat_with_config,
     load_module_from_file,
     load_wasm_from_file,
     wat2wasm,
 };
 use core::{slice, time::Duration};
 use criterion::{criterion_group, criterion_main, Bencher, Criterion};
-use wasmi::{core::TrapCode, Engine, Extern, Func, Linker, Memory, Module, Store, Value};
+use wasmi::{core::TrapCode, Config, Engine, Extern, Func, Linker, Memory, Module, Store, Value};
 use wasmi_core::{Pages, ValueType, F32, F64};
 
 criterion_group!(
@@ -257,8 +261,22 @@ fn bench_instantiate_erc1155(c: &mut Criterion) {
 }
 
 fn bench_execute_tiny_keccak(c: &mut Criterion) {
-    c.bench_function("execute/tiny_keccak", |b| {
-        let (mut store, instance) = load_instance_from_file(WASM_KERNEL);
+    bench_execute_tiny_keccak_with(c, "execute/tiny_keccak", &bench_config());
+    bench_execute_tiny_keccak_with(
+        c,
+        "execute/tiny_keccak/untraced",
+        &bench_config_untraced(false),
+    );
+    bench_execute_tiny_keccak_with(
+        c,
+        "execute/tiny_keccak/superinstructions",
+        &bench_config_untraced(true),
+    );
+}
+
+fn bench_execute_tiny_keccak_with(c: &mut Criterion, bench_id: &str, config: &Config) {
+    c.bench_function(bench_id, |b| {
+        let (mut store, instance) = load_instance_from_file_with_config(WASM_KERNEL, config);
         let prepare = instance
             .get_export(&store, "prepare_tiny_keccak")
             .and_then(Extern::into_func)
@@ -922,6 +940,26 @@ fn bench_execute_fibonacci(c: &mut Criterion) {
     bench_fib("execute/fibonacci_rec", "fibonacci_rec", FIBONACCI_REC_N);
     bench_fib("execute/fibonacci_tail", "fibonacci_tail", FIBONACCI_TAIL_N);
     bench_fib("execute/fibonacci_iter", "fibonacci_iter", FIBONACCI_INC_N);
+    for (bench_id, superinstructions) in [
+        ("execute/fibonacci_iter/untraced", false),
+        ("execute/fibonacci_iter/superinstructions", true),
+    ] {
+        let config = bench_config_untraced(superinstructions);
+        let (mut store, instance) =
+            load_instance_from_wat_with_config(include_bytes!("wat/fibonacci.wat"), &config);
+        c.bench_function(bench_id, |b| {
+            let expected = fib(FIBONACCI_INC_N);
+            let fib = instance
+                .get_export(&store, "fibonacci_iter")
+                .and_then(Extern::into_func)
+                .unwrap()
+                .typed::<i64, i64>(&store)
+                .unwrap();
+            b.iter(|| {
+                assert_eq!(fib.call(&mut store, FIBONACCI_INC_N).unwrap(), expected);
+            });
+        });
+    }
 }
 
 fn bench_execute_memory_sum(c: &mut Criterion) {
@@ -7,22 +7,40 @@ mod tests;
 
 pub use self::utils::{
     BranchOffset, BranchParams, DataSegmentIdx, DropKeep, DropKeepError, ElementSegmentIdx,
-    FuncIdx, GlobalIdx, LocalDepth, Offset, SignatureIdx, TableIdx,
+    FuncIdx, GlobalIdx, LocalDepth, Offset, SignatureIdx, TableIdx, TagIdx,
 };
-use core::fmt::Debug;
+use core::{fmt::Debug, ops::RangeInclusive};
 use wasmi_core::UntypedValue;
 
+/// The source position and opcode of the Wasm operator an [`Instruction`] was translated from.
+///
+/// The third field holds the source position of the first Wasm operator
+/// that has been fused into the [`Instruction`] and equals the source
+/// position of the [`Instruction`] itself if nothing has been fused.
+///
+/// The last field holds the height of the operand stack, excluding parameters
+/// and local variables, before the [`Instruction`] is executed.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
-pub struct InstrMeta(pub usize, pub u16);
+pub struct InstrMeta(pub usize, pub u16, pub usize, pub u32);
 
 impl InstrMeta {
     pub fn source_pc(&self) -> u32 {
         self.0 as u32
     }
 
+    /// Returns the range of source positions of all Wasm operators the [`Instruction`] covers.
+    pub fn source_pc_range(&self) -> RangeInclusive<u32> {
+        (self.2 as u32)..=(self.0 as u32)
+    }
+
     pub fn opcode(&self) -> u16 {
         self.1
     }
+
+    /// Returns the height of the operand stack before the [`Instruction`] is executed.
+    pub fn stack_height(&self) -> u32 {
+        self.3
+    }
 }
 
 /// The internal `wasmi` bytecode that is stored for Wasm functions.
@@ -70,6 +88,35 @@ pub enum Instruction {
         table: TableIdx,
         func_type: SignatureIdx,
     },
+    /// Registers an exception handler for the instructions up to `landing`.
+    ///
+    /// # Note
+    ///
+    /// - `landing` points to the first `catch` clause of the `try` block.
+    /// - `end` points to the end of the `try` block and its `catch` clauses.
+    /// - `len_params` is the number of parameters of the `try` block.
+    Try {
+        landing: BranchOffset,
+        end: BranchOffset,
+        len_params: u32,
+    },
+    /// Continues execution if the caught exception matches `tag`.
+    ///
+    /// Otherwise branches to `next` which is the next `catch` clause.
+    CatchTag {
+        tag: TagIdx,
+        next: BranchOffset,
+    },
+    /// Throws a new exception with the given `tag`.
+    Throw(TagIdx),
+    /// Rethrows the exception caught by the `try` block with the given `landing`.
+    Rethrow {
+        landing: BranchOffset,
+    },
+    /// Rethrows the caught exception skipping the `skip` innermost exception handlers.
+    Delegate {
+        skip: u32,
+    },
     Drop,
     Select,
     GlobalGet(GlobalIdx),
@@ -103,6 +150,76 @@ pub enum Instruction {
     MemoryCopy,
     MemoryInit(DataSegmentIdx),
     DataDrop(DataSegmentIdx),
+    I32AtomicLoad(Offset),
+    I64AtomicLoad(Offset),
+    I32AtomicLoad8U(Offset),
+    I32AtomicLoad16U(Offset),
+    I64AtomicLoad8U(Offset),
+    I64AtomicLoad16U(Offset),
+    I64AtomicLoad32U(Offset),
+    I32AtomicStore(Offset),
+    I64AtomicStore(Offset),
+    I32AtomicStore8(Offset),
+    I32AtomicStore16(Offset),
+    I64AtomicStore8(Offset),
+    I64AtomicStore16(Offset),
+    I64AtomicStore32(Offset),
+    I32AtomicRmwAdd(Offset),
+    I64AtomicRmwAdd(Offset),
+    I32AtomicRmw8AddU(Offset),
+    I32AtomicRmw16AddU(Offset),
+    I64AtomicRmw8AddU(Offset),
+    I64AtomicRmw16AddU(Offset),
+    I64AtomicRmw32AddU(Offset),
+    I32AtomicRmwSub(Offset),
+    I64AtomicRmwSub(Offset),
+    I32AtomicRmw8SubU(Offset),
+    I32AtomicRmw16SubU(Offset),
+    I64AtomicRmw8SubU(Offset),
+    I64AtomicRmw16SubU(Offset),
+    I64AtomicRmw32SubU(Offset),
+    I32AtomicRmwAnd(Offset),
+    I64AtomicRmwAnd(Offset),
+    I32AtomicRmw8AndU(Offset),
+    I32AtomicRmw16AndU(Offset),
+    I64AtomicRmw8AndU(Offset),
+    I64AtomicRmw16AndU(Offset),
+    I64AtomicRmw32AndU(Offset),
+    I32AtomicRmwOr(Offset),
+    I64AtomicRmwOr(Offset),
+    I32AtomicRmw8OrU(Offset),
+    I32AtomicRmw16OrU(Offset),
+    I64AtomicRmw8OrU(Offset),
+    I64AtomicRmw16OrU(Offset),
+    I64AtomicRmw32OrU(Offset),
+    I32AtomicRmwXor(Offset),
+    I64AtomicRmwXor(Offset),
+    I32AtomicRmw8XorU(Offset),
+    I32AtomicRmw16XorU(Offset),
+    I64AtomicRmw8XorU(Offset),
+    I64AtomicRmw16XorU(Offset),
+    I64AtomicRmw32XorU(Offset),
+    I32AtomicRmwXchg(Offset),
+    I64AtomicRmwXchg(Offset),
+    I32AtomicRmw8XchgU(Offset),
+    I32AtomicRmw16XchgU(Offset),
+    I64AtomicRmw8XchgU(Offset),
+    I64AtomicRmw16XchgU(Offset),
+    I64AtomicRmw32XchgU(Offset),
+    I32AtomicRmwCmpxchg(Offset),
+    I64AtomicRmwCmpxchg(Offset),
+    I32AtomicRmw8CmpxchgU(Offset),
+    I32AtomicRmw16CmpxchgU(Offset),
+    I64AtomicRmw8CmpxchgU(Offset),
+    I64AtomicRmw16CmpxchgU(Offset),
+    I64AtomicRmw32CmpxchgU(Offset),
+    /// Wakes up threads waiting on the effective address of a shared linear memory.
+    MemoryAtomicNotify(Offset),
+    /// Blocks the executing thread while the `i32` at the effective address equals the operand.
+    MemoryAtomicWait32(Offset),
+    /// Blocks the executing thread while the `i64` at the effective address equals the operand.
+    MemoryAtomicWait64(Offset),
+    AtomicFence,
     TableSize {
         table: TableIdx,
     },
@@ -263,6 +380,385 @@ pub enum Instruction {
     I64TruncSatF32U,
     I64TruncSatF64S,
     I64TruncSatF64U,
+    /// Copies the value of the local variable at `input` to the local variable at `result`.
+    ///
+    /// # Note
+    ///
+    /// This is a superinstruction that replaces a `local.get`
+    /// followed by a `local.set` instruction.
+    LocalCopy {
+        result: LocalDepth,
+        input: LocalDepth,
+    },
+    // The local operand forms of `i32` and `i64` binary instructions.
+    //
+    // - `<op>LocalLocal` reads both `lhs` and `rhs` from local variables.
+    // - `<op>Local` pops `lhs` from the value stack and reads `rhs` from a local variable.
+    //
+    // In both cases the result is pushed onto the value stack.
+    I32EqLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32EqLocal {
+        rhs: LocalDepth,
+    },
+    I32NeLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32NeLocal {
+        rhs: LocalDepth,
+    },
+    I32LtSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32LtSLocal {
+        rhs: LocalDepth,
+    },
+    I32LtULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32LtULocal {
+        rhs: LocalDepth,
+    },
+    I32GtSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32GtSLocal {
+        rhs: LocalDepth,
+    },
+    I32GtULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32GtULocal {
+        rhs: LocalDepth,
+    },
+    I32LeSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32LeSLocal {
+        rhs: LocalDepth,
+    },
+    I32LeULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32LeULocal {
+        rhs: LocalDepth,
+    },
+    I32GeSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32GeSLocal {
+        rhs: LocalDepth,
+    },
+    I32GeULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32GeULocal {
+        rhs: LocalDepth,
+    },
+    I32AddLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32AddLocal {
+        rhs: LocalDepth,
+    },
+    I32SubLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32SubLocal {
+        rhs: LocalDepth,
+    },
+    I32MulLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32MulLocal {
+        rhs: LocalDepth,
+    },
+    I32AndLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32AndLocal {
+        rhs: LocalDepth,
+    },
+    I32OrLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32OrLocal {
+        rhs: LocalDepth,
+    },
+    I32XorLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32XorLocal {
+        rhs: LocalDepth,
+    },
+    I32ShlLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32ShlLocal {
+        rhs: LocalDepth,
+    },
+    I32ShrSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32ShrSLocal {
+        rhs: LocalDepth,
+    },
+    I32ShrULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32ShrULocal {
+        rhs: LocalDepth,
+    },
+    I32RotlLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32RotlLocal {
+        rhs: LocalDepth,
+    },
+    I32RotrLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I32RotrLocal {
+        rhs: LocalDepth,
+    },
+    I64EqLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64EqLocal {
+        rhs: LocalDepth,
+    },
+    I64NeLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64NeLocal {
+        rhs: LocalDepth,
+    },
+    I64LtSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64LtSLocal {
+        rhs: LocalDepth,
+    },
+    I64LtULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64LtULocal {
+        rhs: LocalDepth,
+    },
+    I64GtSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64GtSLocal {
+        rhs: LocalDepth,
+    },
+    I64GtULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64GtULocal {
+        rhs: LocalDepth,
+    },
+    I64LeSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64LeSLocal {
+        rhs: LocalDepth,
+    },
+    I64LeULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64LeULocal {
+        rhs: LocalDepth,
+    },
+    I64GeSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64GeSLocal {
+        rhs: LocalDepth,
+    },
+    I64GeULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64GeULocal {
+        rhs: LocalDepth,
+    },
+    I64AddLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64AddLocal {
+        rhs: LocalDepth,
+    },
+    I64SubLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64SubLocal {
+        rhs: LocalDepth,
+    },
+    I64MulLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64MulLocal {
+        rhs: LocalDepth,
+    },
+    I64AndLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64AndLocal {
+        rhs: LocalDepth,
+    },
+    I64OrLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64OrLocal {
+        rhs: LocalDepth,
+    },
+    I64XorLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64XorLocal {
+        rhs: LocalDepth,
+    },
+    I64ShlLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64ShlLocal {
+        rhs: LocalDepth,
+    },
+    I64ShrSLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64ShrSLocal {
+        rhs: LocalDepth,
+    },
+    I64ShrULocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64ShrULocal {
+        rhs: LocalDepth,
+    },
+    I64RotlLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64RotlLocal {
+        rhs: LocalDepth,
+    },
+    I64RotrLocalLocal {
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+    },
+    I64RotrLocal {
+        rhs: LocalDepth,
+    },
+    // The superinstruction forms of `i32` and `i64` instructions.
+    //
+    // - `<op>Imm` pops `lhs` from the value stack and uses the immediate `rhs` operand.
+    // - `BrIf<op>` pops both operands and branches if the comparison holds.
+    //
+    // They replace a `Const` followed by a binary instruction and
+    // a comparison followed by a `BrIfNez` instruction respectively.
+    I32EqImm(UntypedValue),
+    I32NeImm(UntypedValue),
+    I32LtSImm(UntypedValue),
+    I32LtUImm(UntypedValue),
+    I32GtSImm(UntypedValue),
+    I32GtUImm(UntypedValue),
+    I32LeSImm(UntypedValue),
+    I32LeUImm(UntypedValue),
+    I32GeSImm(UntypedValue),
+    I32GeUImm(UntypedValue),
+    I32AddImm(UntypedValue),
+    I32SubImm(UntypedValue),
+    I32MulImm(UntypedValue),
+    I32AndImm(UntypedValue),
+    I32OrImm(UntypedValue),
+    I32XorImm(UntypedValue),
+    I32ShlImm(UntypedValue),
+    I32ShrSImm(UntypedValue),
+    I32ShrUImm(UntypedValue),
+    I32RotlImm(UntypedValue),
+    I32RotrImm(UntypedValue),
+    I64EqImm(UntypedValue),
+    I64NeImm(UntypedValue),
+    I64LtSImm(UntypedValue),
+    I64LtUImm(UntypedValue),
+    I64GtSImm(UntypedValue),
+    I64GtUImm(UntypedValue),
+    I64LeSImm(UntypedValue),
+    I64LeUImm(UntypedValue),
+    I64GeSImm(UntypedValue),
+    I64GeUImm(UntypedValue),
+    I64AddImm(UntypedValue),
+    I64SubImm(UntypedValue),
+    I64MulImm(UntypedValue),
+    I64AndImm(UntypedValue),
+    I64OrImm(UntypedValue),
+    I64XorImm(UntypedValue),
+    I64ShlImm(UntypedValue),
+    I64ShrSImm(UntypedValue),
+    I64ShrUImm(UntypedValue),
+    I64RotlImm(UntypedValue),
+    I64RotrImm(UntypedValue),
+    BrIfI32Eq(BranchParams),
+    BrIfI32Ne(BranchParams),
+    BrIfI32LtS(BranchParams),
+    BrIfI32LtU(BranchParams),
+    BrIfI32GtS(BranchParams),
+    BrIfI32GtU(BranchParams),
+    BrIfI32LeS(BranchParams),
+    BrIfI32LeU(BranchParams),
+    BrIfI32GeS(BranchParams),
+    BrIfI32GeU(BranchParams),
+    BrIfI64Eq(BranchParams),
+    BrIfI64Ne(BranchParams),
+    BrIfI64LtS(BranchParams),
+    BrIfI64LtU(BranchParams),
+    BrIfI64GtS(BranchParams),
+    BrIfI64GtU(BranchParams),
+    BrIfI64LeS(BranchParams),
+    BrIfI64LeU(BranchParams),
+    BrIfI64GeS(BranchParams),
+    BrIfI64GeU(BranchParams),
 }
 
 impl Instruction {
@@ -295,6 +791,192 @@ impl Instruction {
         }
     }
 
+    /// Returns the local operand form of the binary instruction reading both operands from locals.
+    ///
+    /// Returns `None` if `self` has no local operand form.
+    pub fn with_local_operands(self, lhs: LocalDepth, rhs: LocalDepth) -> Option<Self> {
+        let instr = match self {
+            Self::I32Eq => Self::I32EqLocalLocal { lhs, rhs },
+            Self::I32Ne => Self::I32NeLocalLocal { lhs, rhs },
+            Self::I32LtS => Self::I32LtSLocalLocal { lhs, rhs },
+            Self::I32LtU => Self::I32LtULocalLocal { lhs, rhs },
+            Self::I32GtS => Self::I32GtSLocalLocal { lhs, rhs },
+            Self::I32GtU => Self::I32GtULocalLocal { lhs, rhs },
+            Self::I32LeS => Self::I32LeSLocalLocal { lhs, rhs },
+            Self::I32LeU => Self::I32LeULocalLocal { lhs, rhs },
+            Self::I32GeS => Self::I32GeSLocalLocal { lhs, rhs },
+            Self::I32GeU => Self::I32GeULocalLocal { lhs, rhs },
+            Self::I32Add => Self::I32AddLocalLocal { lhs, rhs },
+            Self::I32Sub => Self::I32SubLocalLocal { lhs, rhs },
+            Self::I32Mul => Self::I32MulLocalLocal { lhs, rhs },
+            Self::I32And => Self::I32AndLocalLocal { lhs, rhs },
+            Self::I32Or => Self::I32OrLocalLocal { lhs, rhs },
+            Self::I32Xor => Self::I32XorLocalLocal { lhs, rhs },
+            Self::I32Shl => Self::I32ShlLocalLocal { lhs, rhs },
+            Self::I32ShrS => Self::I32ShrSLocalLocal { lhs, rhs },
+            Self::I32ShrU => Self::I32ShrULocalLocal { lhs, rhs },
+            Self::I32Rotl => Self::I32RotlLocalLocal { lhs, rhs },
+            Self::I32Rotr => Self::I32RotrLocalLocal { lhs, rhs },
+            Self::I64Eq => Self::I64EqLocalLocal { lhs, rhs },
+            Self::I64Ne => Self::I64NeLocalLocal { lhs, rhs },
+            Self::I64LtS => Self::I64LtSLocalLocal { lhs, rhs },
+            Self::I64LtU => Self::I64LtULocalLocal { lhs, rhs },
+            Self::I64GtS => Self::I64GtSLocalLocal { lhs, rhs },
+            Self::I64GtU => Self::I64GtULocalLocal { lhs, rhs },
+            Self::I64LeS => Self::I64LeSLocalLocal { lhs, rhs },
+            Self::I64LeU => Self::I64LeULocalLocal { lhs, rhs },
+            Self::I64GeS => Self::I64GeSLocalLocal { lhs, rhs },
+            Self::I64GeU => Self::I64GeULocalLocal { lhs, rhs },
+            Self::I64Add => Self::I64AddLocalLocal { lhs, rhs },
+            Self::I64Sub => Self::I64SubLocalLocal { lhs, rhs },
+            Self::I64Mul => Self::I64MulLocalLocal { lhs, rhs },
+            Self::I64And => Self::I64AndLocalLocal { lhs, rhs },
+            Self::I64Or => Self::I64OrLocalLocal { lhs, rhs },
+            Self::I64Xor => Self::I64XorLocalLocal { lhs, rhs },
+            Self::I64Shl => Self::I64ShlLocalLocal { lhs, rhs },
+            Self::I64ShrS => Self::I64ShrSLocalLocal { lhs, rhs },
+            Self::I64ShrU => Self::I64ShrULocalLocal { lhs, rhs },
+            Self::I64Rotl => Self::I64RotlLocalLocal { lhs, rhs },
+            Self::I64Rotr => Self::I64RotrLocalLocal { lhs, rhs },
+            _ => return None,
+        };
+        Some(instr)
+    }
+
+    /// Returns the local operand form of the binary instruction reading its `rhs` from a local.
+    ///
+    /// Returns `None` if `self` has no local operand form.
+    pub fn with_local_rhs(self, rhs: LocalDepth) -> Option<Self> {
+        let instr = match self {
+            Self::I32Eq => Self::I32EqLocal { rhs },
+            Self::I32Ne => Self::I32NeLocal { rhs },
+            Self::I32LtS => Self::I32LtSLocal { rhs },
+            Self::I32LtU => Self::I32LtULocal { rhs },
+            Self::I32GtS => Self::I32GtSLocal { rhs },
+            Self::I32GtU => Self::I32GtULocal { rhs },
+            Self::I32LeS => Self::I32LeSLocal { rhs },
+            Self::I32LeU => Self::I32LeULocal { rhs },
+            Self::I32GeS => Self::I32GeSLocal { rhs },
+            Self::I32GeU => Self::I32GeULocal { rhs },
+            Self::I32Add => Self::I32AddLocal { rhs },
+            Self::I32Sub => Self::I32SubLocal { rhs },
+            Self::I32Mul => Self::I32MulLocal { rhs },
+            Self::I32And => Self::I32AndLocal { rhs },
+            Self::I32Or => Self::I32OrLocal { rhs },
+            Self::I32Xor => Self::I32XorLocal { rhs },
+            Self::I32Shl => Self::I32ShlLocal { rhs },
+            Self::I32ShrS => Self::I32ShrSLocal { rhs },
+            Self::I32ShrU => Self::I32ShrULocal { rhs },
+            Self::I32Rotl => Self::I32RotlLocal { rhs },
+            Self::I32Rotr => Self::I32RotrLocal { rhs },
+            Self::I64Eq => Self::I64EqLocal { rhs },
+            Self::I64Ne => Self::I64NeLocal { rhs },
+            Self::I64LtS => Self::I64LtSLocal { rhs },
+            Self::I64LtU => Self::I64LtULocal { rhs },
+            Self::I64GtS => Self::I64GtSLocal { rhs },
+            Self::I64GtU => Self::I64GtULocal { rhs },
+            Self::I64LeS => Self::I64LeSLocal { rhs },
+            Self::I64LeU => Self::I64LeULocal { rhs },
+            Self::I64GeS => Self::I64GeSLocal { rhs },
+            Self::I64GeU => Self::I64GeULocal { rhs },
+            Self::I64Add => Self::I64AddLocal { rhs },
+            Self::I64Sub => Self::I64SubLocal { rhs },
+            Self::I64Mul => Self::I64MulLocal { rhs },
+            Self::I64And => Self::I64AndLocal { rhs },
+            Self::I64Or => Self::I64OrLocal { rhs },
+            Self::I64Xor => Self::I64XorLocal { rhs },
+            Self::I64Shl => Self::I64ShlLocal { rhs },
+            Self::I64ShrS => Self::I64ShrSLocal { rhs },
+            Self::I64ShrU => Self::I64ShrULocal { rhs },
+            Self::I64Rotl => Self::I64RotlLocal { rhs },
+            Self::I64Rotr => Self::I64RotrLocal { rhs },
+            _ => return None,
+        };
+        Some(instr)
+    }
+
+    /// Returns the superinstruction form of the binary instruction using the immediate `rhs`.
+    ///
+    /// Returns `None` if `self` has no immediate operand form.
+    pub fn with_imm_rhs(self, rhs: UntypedValue) -> Option<Self> {
+        let instr = match self {
+            Self::I32Eq => Self::I32EqImm(rhs),
+            Self::I32Ne => Self::I32NeImm(rhs),
+            Self::I32LtS => Self::I32LtSImm(rhs),
+            Self::I32LtU => Self::I32LtUImm(rhs),
+            Self::I32GtS => Self::I32GtSImm(rhs),
+            Self::I32GtU => Self::I32GtUImm(rhs),
+            Self::I32LeS => Self::I32LeSImm(rhs),
+            Self::I32LeU => Self::I32LeUImm(rhs),
+            Self::I32GeS => Self::I32GeSImm(rhs),
+            Self::I32GeU => Self::I32GeUImm(rhs),
+            Self::I32Add => Self::I32AddImm(rhs),
+            Self::I32Sub => Self::I32SubImm(rhs),
+            Self::I32Mul => Self::I32MulImm(rhs),
+            Self::I32And => Self::I32AndImm(rhs),
+            Self::I32Or => Self::I32OrImm(rhs),
+            Self::I32Xor => Self::I32XorImm(rhs),
+            Self::I32Shl => Self::I32ShlImm(rhs),
+            Self::I32ShrS => Self::I32ShrSImm(rhs),
+            Self::I32ShrU => Self::I32ShrUImm(rhs),
+            Self::I32Rotl => Self::I32RotlImm(rhs),
+            Self::I32Rotr => Self::I32RotrImm(rhs),
+            Self::I64Eq => Self::I64EqImm(rhs),
+            Self::I64Ne => Self::I64NeImm(rhs),
+            Self::I64LtS => Self::I64LtSImm(rhs),
+            Self::I64LtU => Self::I64LtUImm(rhs),
+            Self::I64GtS => Self::I64GtSImm(rhs),
+            Self::I64GtU => Self::I64GtUImm(rhs),
+            Self::I64LeS => Self::I64LeSImm(rhs),
+            Self::I64LeU => Self::I64LeUImm(rhs),
+            Self::I64GeS => Self::I64GeSImm(rhs),
+            Self::I64GeU => Self::I64GeUImm(rhs),
+            Self::I64Add => Self::I64AddImm(rhs),
+            Self::I64Sub => Self::I64SubImm(rhs),
+            Self::I64Mul => Self::I64MulImm(rhs),
+            Self::I64And => Self::I64AndImm(rhs),
+            Self::I64Or => Self::I64OrImm(rhs),
+            Self::I64Xor => Self::I64XorImm(rhs),
+            Self::I64Shl => Self::I64ShlImm(rhs),
+            Self::I64ShrS => Self::I64ShrSImm(rhs),
+            Self::I64ShrU => Self::I64ShrUImm(rhs),
+            Self::I64Rotl => Self::I64RotlImm(rhs),
+            Self::I64Rotr => Self::I64RotrImm(rhs),
+            _ => return None,
+        };
+        Some(instr)
+    }
+
+    /// Returns the constructor of the conditional branch fused with the comparison `self`.
+    ///
+    /// Returns `None` if `self` is not a comparison that can be fused with a `BrIfNez`.
+    pub fn br_if_compare(self) -> Option<fn(BranchParams) -> Self> {
+        let make: fn(BranchParams) -> Self = match self {
+            Self::I32Eq => Self::BrIfI32Eq,
+            Self::I32Ne => Self::BrIfI32Ne,
+            Self::I32LtS => Self::BrIfI32LtS,
+            Self::I32LtU => Self::BrIfI32LtU,
+            Self::I32GtS => Self::BrIfI32GtS,
+            Self::I32GtU => Self::BrIfI32GtU,
+            Self::I32LeS => Self::BrIfI32LeS,
+            Self::I32LeU => Self::BrIfI32LeU,
+            Self::I32GeS => Self::BrIfI32GeS,
+            Self::I32GeU => Self::BrIfI32GeU,
+            Self::I64Eq => Self::BrIfI64Eq,
+            Self::I64Ne => Self::BrIfI64Ne,
+            Self::I64LtS => Self::BrIfI64LtS,
+            Self::I64LtU => Self::BrIfI64LtU,
+            Self::I64GtS => Self::BrIfI64GtS,
+            Self::I64GtU => Self::BrIfI64GtU,
+            Self::I64LeS => Self::BrIfI64LeS,
+            Self::I64LeU => Self::BrIfI64LeU,
+            Self::I64GeS => Self::BrIfI64GeS,
+            Self::I64GeU => Self::BrIfI64GeU,
+            _ => return None,
+        };
+        Some(make)
+    }
+
     /// Convenience method to create a new `ConsumeFuel` instruction.
     pub fn consume_fuel(amount: u64) -> Self {
         Self::ConsumeFuel { amount }
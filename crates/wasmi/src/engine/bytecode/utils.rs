@@ -100,6 +100,24 @@ impl TableIdx {
     }
 }
 
+/// An exception tag index.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct TagIdx(u32);
+
+impl From<u32> for TagIdx {
+    fn from(index: u32) -> Self {
+        Self(index)
+    }
+}
+
+impl TagIdx {
+    /// Returns the inner `u32` index.
+    pub fn into_inner(self) -> u32 {
+        self.0
+    }
+}
+
 /// An index of a unique function signature.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
@@ -125,12 +143,19 @@ impl SignatureIdx {
 /// The depth refers to the relative position of a local
 /// variable on the value stack with respect to the height
 /// of the value stack at the time of access.
+///
+/// The depth is stored as `u32` so that instructions referring to
+/// multiple local variables still fit into an [`Instruction`].
+///
+/// [`Instruction`]: super::Instruction
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(transparent)]
-pub struct LocalDepth(usize);
+pub struct LocalDepth(u32);
 
 impl From<usize> for LocalDepth {
     fn from(index: usize) -> Self {
+        let index = u32::try_from(index)
+            .unwrap_or_else(|_| panic!("local depth is out of bounds: {index}"));
         Self(index)
     }
 }
@@ -138,7 +163,7 @@ impl From<usize> for LocalDepth {
 impl LocalDepth {
     /// Returns the depth as `usize` index.
     pub fn into_inner(self) -> usize {
-        self.0
+        self.0 as usize
     }
 }
 
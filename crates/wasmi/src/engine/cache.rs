@@ -21,6 +21,10 @@ use wasmi_core::UntypedValue;
 pub struct InstanceCache {
     /// The bytes of a default linear memory of the currently used [`Instance`].
     default_memory_bytes: Option<NonNull<[u8]>>,
+    /// Is `true` if the default linear memory of the currently used [`Instance`] is shared.
+    ///
+    /// Only valid while `default_memory_bytes` is cached.
+    default_memory_shared: bool,
     /// The last accessed global variable value of the currently used [`Instance`].
     last_global: Option<(GlobalIdx, NonNull<UntypedValue>)>,
     /// The current instance in use.
@@ -42,6 +46,7 @@ impl From<&'_ Instance> for InstanceCache {
             last_func: None,
             last_global: None,
             default_memory_bytes: None,
+            default_memory_shared: false,
         }
     }
 }
@@ -118,11 +123,11 @@ impl InstanceCache {
         &mut self,
         ctx: &'a mut StoreInner,
         segment: DataSegmentIdx,
-    ) -> (&'a mut [u8], &'a [u8]) {
+    ) -> (NonNull<[u8]>, &'a [u8]) {
         let seg = self.get_data_segment(ctx, segment.into_inner());
         let mem = self.default_memory(ctx);
         let (memory, segment) = ctx.resolve_memory_mut_and_data_segment(mem, &seg);
-        (memory.data_mut(), segment.bytes())
+        (memory.data_ptr(), segment.bytes())
     }
 
     /// Loads the [`ElementSegment`] at `index` of the currently used [`Instance`].
@@ -182,25 +187,49 @@ impl InstanceCache {
     ///
     /// # Note
     ///
-    /// This avoids one indirection compared to using the `default_memory`.
+    /// - This avoids one indirection compared to using the `default_memory`.
+    /// - Must not be used if the default linear memory is shared since other
+    ///   threads might concurrently access its bytes. Use
+    ///   [`InstanceCache::default_memory_ptr`] in this case instead.
     #[inline]
     pub fn default_memory_bytes<'ctx>(&mut self, ctx: &'ctx mut StoreInner) -> &'ctx mut [u8] {
-        let bytes = match self.default_memory_bytes {
-            Some(ref mut cached) => cached,
-            None => self.load_default_memory_bytes(ctx),
-        };
+        let mut bytes = self.default_memory_ptr(ctx);
+        debug_assert!(!self.default_memory_shared);
         unsafe { bytes.as_mut() }
     }
 
+    /// Returns a raw pointer to the bytes of the cached default linear memory.
+    ///
+    /// # Note
+    ///
+    /// The length of a shared default linear memory is loaded again upon every
+    /// call since other threads might concurrently grow the shared linear memory.
+    #[inline]
+    pub fn default_memory_ptr(&mut self, ctx: &mut StoreInner) -> NonNull<[u8]> {
+        match self.default_memory_bytes {
+            Some(cached) if !self.default_memory_shared => cached,
+            _ => self.load_default_memory_bytes(ctx),
+        }
+    }
+
+    /// Returns `true` if the default linear memory is shared.
+    #[inline]
+    pub fn default_memory_is_shared(&mut self, ctx: &mut StoreInner) -> bool {
+        if self.default_memory_bytes.is_none() {
+            self.load_default_memory_bytes(ctx);
+        }
+        self.default_memory_shared
+    }
+
     /// Loads and populates the cached default memory instance.
     ///
-    /// Returns an exclusive reference to the cached default memory.
-    #[cold]
+    /// Returns a raw pointer to the bytes of the cached default memory.
     #[inline]
-    fn load_default_memory_bytes(&mut self, ctx: &mut StoreInner) -> &mut NonNull<[u8]> {
+    fn load_default_memory_bytes(&mut self, ctx: &mut StoreInner) -> NonNull<[u8]> {
         let memory = *self.default_memory(ctx);
-        self.default_memory_bytes
-            .insert(ctx.resolve_memory_mut(&memory).data().into())
+        let memory = ctx.resolve_memory_mut(&memory);
+        self.default_memory_shared = memory.shared().is_some();
+        *self.default_memory_bytes.insert(memory.data_ptr())
     }
 
     /// Clears the cached default memory instance.
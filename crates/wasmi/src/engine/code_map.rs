@@ -1,7 +1,7 @@
 //! Datastructure to efficiently store function bodies and their instructions.
 
 use super::Instruction;
-use alloc::vec::Vec;
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
 use wasmi_arena::ArenaIndex;
 use crate::engine::bytecode::InstrMeta;
 
@@ -31,6 +31,8 @@ pub struct InstructionsRef {
 pub struct FuncHeader {
     /// A reference to the instructions of the function.
     iref: InstructionsRef,
+    /// The number of parameters of the function.
+    len_params: usize,
     /// The number of local variables of the function.
     len_locals: usize,
     /// The maximum stack height usage of the function during execution.
@@ -43,6 +45,11 @@ impl FuncHeader {
         self.iref
     }
 
+    /// Returns the amount of parameters of the function.
+    pub fn len_params(&self) -> usize {
+        self.len_params
+    }
+
     /// Returns the amount of local variable of the function.
     pub fn len_locals(&self) -> usize {
         self.len_locals
@@ -59,6 +66,20 @@ impl FuncHeader {
     }
 }
 
+/// The addresses of the instructions of a [`CodeMap`] at some point in time.
+///
+/// # Note
+///
+/// The instructions of a [`CodeMap`] move when new Wasm functions are compiled.
+/// This is used to relocate [`InstructionPtr`] that point into moved instructions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CodeAddresses {
+    /// The address of the first instruction.
+    insts: usize,
+    /// The address of the first [`InstrMeta`].
+    metas: usize,
+}
+
 /// Datastructure to efficiently store Wasm function bodies.
 #[derive(Debug, Default)]
 pub struct CodeMap {
@@ -75,6 +96,22 @@ pub struct CodeMap {
     /// and generally improves data locality.
     insts: Vec<Instruction>,
     metas: Vec<InstrMeta>,
+    /// The module level debug information of function bodies by their header index.
+    ///
+    /// # Note
+    ///
+    /// Names are decoded from the Wasm `name` custom section which comes
+    /// after the code section and thus are registered after allocation.
+    infos: BTreeMap<usize, FuncInfo>,
+}
+
+/// Module level debug information about a compiled function.
+#[derive(Debug)]
+struct FuncInfo {
+    /// The index of the function within its module, including imported functions.
+    index: u32,
+    /// The name of the function as found in the `name` custom section if any.
+    name: Option<Box<str>>,
 }
 
 impl CodeMap {
@@ -83,7 +120,7 @@ impl CodeMap {
     /// Returns a reference to the allocated function body that can
     /// be used with [`CodeMap::header`] in order to resolve its
     /// instructions.
-    pub fn alloc<I>(&mut self, len_locals: usize, max_stack_height: usize, insts: I, metas: Vec<InstrMeta>) -> FuncBody
+    pub fn alloc<I>(&mut self, len_params: usize, len_locals: usize, max_stack_height: usize, insts: I, metas: Vec<InstrMeta>) -> FuncBody
     where
         I: IntoIterator<Item = Instruction>,
     {
@@ -93,6 +130,7 @@ impl CodeMap {
         let iref = InstructionsRef { start };
         let header = FuncHeader {
             iref,
+            len_params,
             len_locals,
             max_stack_height: len_locals + max_stack_height,
         };
@@ -107,6 +145,62 @@ impl CodeMap {
         InstructionPtr::new(self.insts[iref.start..].as_ptr(), self.metas[iref.start..].as_ptr())
     }
 
+    /// Returns the current [`CodeAddresses`] of the instructions of the [`CodeMap`].
+    pub fn addresses(&self) -> CodeAddresses {
+        CodeAddresses {
+            insts: self.insts.as_ptr() as usize,
+            metas: self.metas.as_ptr() as usize,
+        }
+    }
+
+    /// Relocates the `ip` that pointed into the instructions at the `old` [`CodeAddresses`].
+    ///
+    /// # Panics
+    ///
+    /// If `ip` did not point into the instructions at the `old` [`CodeAddresses`].
+    pub fn relocate(&self, old: CodeAddresses, ip: InstructionPtr) -> InstructionPtr {
+        let index = |ptr: *const Instruction| {
+            (ptr as usize - old.insts) / core::mem::size_of::<Instruction>()
+        };
+        // Note: the instructions and their metas are stored at the same indices.
+        let (pc, source) = (index(ip.ptr), index(ip.source));
+        InstructionPtr {
+            ptr: self.insts[pc..].as_ptr(),
+            source: self.insts[source..].as_ptr(),
+            meta: self.metas[pc..].as_ptr(),
+        }
+    }
+
+    /// Sets the module level `index` and optional `name` of the function body referred to by [`FuncBody`].
+    pub fn set_info(&mut self, func_body: FuncBody, index: u32, name: Option<&str>) {
+        let name = name.map(Into::into);
+        self.infos.insert(func_body.0, FuncInfo { index, name });
+    }
+
+    /// Returns the name of the function body referred to by [`FuncBody`] if any.
+    pub fn name(&self, func_body: FuncBody) -> Option<&str> {
+        self.infos.get(&func_body.0)?.name.as_deref()
+    }
+
+    /// Returns the module level function index of the function body referred to by [`FuncBody`] if any.
+    pub fn index(&self, func_body: FuncBody) -> Option<u32> {
+        self.infos.get(&func_body.0).map(|info| info.index)
+    }
+
+    /// Returns the [`FuncBody`] that contains the instruction at `ip` if any.
+    pub fn func_body_at(&self, ip: InstructionPtr) -> Option<FuncBody> {
+        let offset = (ip.ptr as usize).checked_sub(self.insts.as_ptr() as usize)?;
+        let pc = offset / core::mem::size_of::<Instruction>();
+        if pc >= self.insts.len() {
+            return None;
+        }
+        let index = self
+            .headers
+            .partition_point(|header| header.iref.start <= pc)
+            .checked_sub(1)?;
+        Some(FuncBody(index))
+    }
+
     /// Returns the [`FuncHeader`] of the [`FuncBody`].
     pub fn header(&self, func_body: FuncBody) -> &FuncHeader {
         &self.headers[func_body.0]
@@ -122,6 +216,15 @@ impl CodeMap {
         instrs.get(index)
     }
 
+    /// Resolves the [`InstrMeta`] of the instruction at `index` of the compiled [`FuncBody`].
+    #[cfg(test)]
+    pub fn get_meta(&self, func_body: FuncBody, index: usize) -> Option<&InstrMeta> {
+        let header = self.header(func_body);
+        let start = header.iref.start;
+        let end = self.instr_end(func_body);
+        self.metas[start..end].get(index)
+    }
+
     pub fn instr_vec(&self, func_body: FuncBody) -> Vec<Instruction> {
         let header = self.header(func_body);
         let start = header.iref.start;
@@ -129,6 +232,14 @@ impl CodeMap {
         self.insts[start..end].to_vec()
     }
 
+    /// Returns the [`InstrMeta`] of all instructions of the compiled [`FuncBody`].
+    pub fn instr_metas(&self, func_body: FuncBody) -> Vec<InstrMeta> {
+        let header = self.header(func_body);
+        let start = header.iref.start;
+        let end = self.instr_end(func_body);
+        self.metas[start..end].to_vec()
+    }
+
     /// Returns the `end` index of the instructions of [`FuncBody`].
     ///
     /// This is important to synthesize how many instructions there are in
@@ -1,5 +1,7 @@
-use super::{stack::StackLimits, DropKeep};
+use super::{bytecode::Instruction, opcode::OpCode, stack::StackLimits, DropKeep};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, vec};
 use core::{mem::size_of, num::NonZeroU64};
+use serde::{Deserialize, Deserializer, Serialize};
 use wasmi_core::UntypedValue;
 use wasmparser::WasmFeatures;
 
@@ -8,8 +10,13 @@ const DEFAULT_CACHED_STACKS: usize = 2;
 
 /// Configuration for an [`Engine`].
 ///
+/// # Note
+///
+/// [`Config`] does not implement [`Copy`] since its [`FuelCosts`] own the
+/// schedule of individually priced instructions.
+///
 /// [`Engine`]: [`crate::Engine`]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Config {
     /// The limits set on the value stack and call stack.
     stack_limits: StackLimits,
@@ -31,10 +38,20 @@ pub struct Config {
     tail_call: bool,
     /// Is `true` if the [`extended-const`] Wasm proposal is enabled.
     extended_const: bool,
+    /// Is `true` if the [`exception-handling`] Wasm proposal is enabled.
+    exceptions: bool,
+    /// Is `true` if the [`threads`] Wasm proposal is enabled.
+    threads: bool,
+    /// Is `true` if executions shall be traced deterministically.
+    deterministic_tracing: bool,
     /// Is `true` if Wasm instructions on `f32` and `f64` types are allowed.
     floats: bool,
     /// Is `true` if `wasmi` executions shall consume fuel.
     consume_fuel: bool,
+    /// Is `true` if `wasmi` executions can be interrupted via the epoch of the [`Engine`](crate::Engine).
+    epoch_interruption: bool,
+    /// Is `true` if the superinstruction fusion pass is enabled.
+    superinstructions: bool,
     /// The fuel consumption mode of the `wasmi` [`Engine`](crate::Engine).
     fuel_consumption_mode: FuelConsumptionMode,
     /// The configured fuel costs of all `wasmi` bytecode instructions.
@@ -90,7 +107,35 @@ pub enum FuelConsumptionMode {
 }
 
 /// Type storing all kinds of fuel costs of instructions.
-#[derive(Debug, Copy, Clone)]
+///
+/// # Schedule Format
+///
+/// [`FuelCosts`] can be loaded from any `serde` data format.
+/// Missing fields fall back to their default values.
+/// Individual instructions are priced through the `instrs` table
+/// which is keyed by the instruction names that are also used by
+/// the execution tracer and the profiler. Unknown instruction names
+/// are rejected. For example:
+///
+/// ```json
+/// {
+///     "base": 1,
+///     "memory_bytes_per_fuel": 64,
+///     "instrs": {
+///         "i64_div_u": { "base": 8 },
+///         "call_indirect": { "base": 12 },
+///         "memory_copy": { "base": 4, "items_per_fuel": 16 }
+///     }
+/// }
+/// ```
+///
+/// # Note
+///
+/// [`FuelCosts`] does not implement [`Copy`] since it owns the `instrs` table.
+/// An [`Engine`](crate::Engine) resolves the `instrs` table into a dense table
+/// upon its creation so that instruction costs are not looked up by name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct FuelCosts {
     /// The base fuel costs for all instructions.
     pub base: u64,
@@ -134,6 +179,49 @@ pub struct FuelCosts {
     ///
     /// If this is zero then processing table elements costs nothing.
     table_elements_per_fuel: u64,
+    /// The fuel costs of individually priced instructions.
+    ///
+    /// # Note
+    ///
+    /// Instructions without an entry are priced by the coarse costs above.
+    #[serde(
+        skip_serializing_if = "BTreeMap::is_empty",
+        deserialize_with = "deserialize_instrs"
+    )]
+    instrs: BTreeMap<String, InstrCost>,
+}
+
+/// Deserializes the `instrs` table of [`FuelCosts`] rejecting unknown instruction names.
+fn deserialize_instrs<'de, D>(deserializer: D) -> Result<BTreeMap<String, InstrCost>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let instrs = BTreeMap::<String, InstrCost>::deserialize(deserializer)?;
+    if let Some(name) = instrs.keys().find(|name| OpCode::index_of(name).is_none()) {
+        return Err(serde::de::Error::custom(format_args!(
+            "unknown instruction name `{name}` in fuel costs"
+        )));
+    }
+    Ok(instrs)
+}
+
+/// The fuel costs of an individually priced instruction of [`FuelCosts`].
+///
+/// Unset costs fall back to the coarse costs of the [`FuelCosts`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstrCost {
+    /// The fuel cost charged for every execution of the instruction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<u64>,
+    /// How many memory bytes or table elements consume one fuel.
+    ///
+    /// # Note
+    ///
+    /// This only applies to bulk-operations such as `memory.copy` or `table.grow`.
+    /// If this is zero then processing bytes or elements costs nothing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items_per_fuel: Option<u64>,
 }
 
 impl FuelCosts {
@@ -170,6 +258,87 @@ impl FuelCosts {
     pub fn fuel_for_elements(&self, elements: u64) -> u64 {
         Self::costs_per(elements, self.table_elements_per_fuel)
     }
+
+    /// Returns the [`InstrCost`] of the instruction with the given `name` if any.
+    pub fn instr_cost(&self, name: &str) -> Option<&InstrCost> {
+        self.instrs.get(name)
+    }
+
+    /// Prices the instruction with the given `name` individually.
+    ///
+    /// The `name` is the instruction name used by the execution tracer,
+    /// for example `i64_div_u`, `call_indirect` or `memory_copy`.
+    ///
+    /// # Panics
+    ///
+    /// If `name` is not the name of a `wasmi` instruction.
+    pub fn set_instr_cost(&mut self, name: impl Into<String>, cost: InstrCost) -> &mut Self {
+        let name = name.into();
+        assert!(
+            OpCode::index_of(&name).is_some(),
+            "unknown instruction name `{name}` in fuel costs"
+        );
+        self.instrs.insert(name, cost);
+        self
+    }
+}
+
+/// The [`FuelCosts`] of an [`Engine`] with the individually priced instructions
+/// resolved into a dense table indexed by [`OpCode::index`].
+///
+/// [`Engine`]: crate::Engine
+#[derive(Debug)]
+pub(crate) struct ResolvedFuelCosts {
+    /// The resolved [`FuelCosts`].
+    costs: FuelCosts,
+    /// The [`InstrCost`] of every `wasmi` instruction.
+    instrs: Box<[InstrCost]>,
+}
+
+impl ResolvedFuelCosts {
+    /// Resolves the individually priced instructions of the [`FuelCosts`].
+    pub fn new(costs: &FuelCosts) -> Self {
+        let mut instrs = vec![InstrCost::default(); OpCode::NAMES.len()].into_boxed_slice();
+        for (name, cost) in &costs.instrs {
+            let index = OpCode::index_of(name)
+                .unwrap_or_else(|| panic!("unknown instruction name `{name}` in fuel costs"));
+            instrs[index] = *cost;
+        }
+        Self {
+            costs: costs.clone(),
+            instrs,
+        }
+    }
+
+    /// Returns the [`InstrCost`] of the `instr`.
+    fn cost_of(&self, instr: Instruction) -> &InstrCost {
+        &self.instrs[OpCode(instr).index()]
+    }
+
+    /// Returns the fuel consumption for executing the `instr`.
+    ///
+    /// Returns `default` if the `instr` is not priced individually.
+    pub fn fuel_for_instr(&self, instr: Instruction, default: u64) -> u64 {
+        self.cost_of(instr).base.unwrap_or(default)
+    }
+
+    /// Returns the fuel consumption for processing the amount of memory bytes by `instr`.
+    pub fn fuel_for_instr_bytes(&self, instr: Instruction, bytes: u64) -> u64 {
+        let bytes_per_fuel = self
+            .cost_of(instr)
+            .items_per_fuel
+            .unwrap_or(self.costs.memory_bytes_per_fuel);
+        FuelCosts::costs_per(bytes, bytes_per_fuel)
+    }
+
+    /// Returns the fuel consumption for processing the amount of table elements by `instr`.
+    pub fn fuel_for_instr_elements(&self, instr: Instruction, elements: u64) -> u64 {
+        let elements_per_fuel = self
+            .cost_of(instr)
+            .items_per_fuel
+            .unwrap_or(self.costs.table_elements_per_fuel);
+        FuelCosts::costs_per(elements, elements_per_fuel)
+    }
 }
 
 impl Default for FuelCosts {
@@ -187,6 +356,7 @@ impl Default for FuelCosts {
             branch_kept_per_fuel: registers_per_fuel,
             memory_bytes_per_fuel,
             table_elements_per_fuel: registers_per_fuel,
+            instrs: BTreeMap::new(),
         }
     }
 }
@@ -204,10 +374,15 @@ impl Default for Config {
             reference_types: true,
             tail_call: false,
             extended_const: false,
+            exceptions: false,
+            threads: false,
+            deterministic_tracing: true,
             floats: true,
             consume_fuel: false,
+            epoch_interruption: false,
             fuel_costs: FuelCosts::default(),
             fuel_consumption_mode: FuelConsumptionMode::default(),
+            superinstructions: false,
         }
     }
 }
@@ -324,6 +499,58 @@ impl Config {
         self
     }
 
+    /// Enable or disable the [`exception-handling`] Wasm proposal for the [`Config`].
+    ///
+    /// # Note
+    ///
+    /// Disabled by default.
+    ///
+    /// [`exception-handling`]: https://github.com/WebAssembly/exception-handling
+    pub fn wasm_exceptions(&mut self, enable: bool) -> &mut Self {
+        self.exceptions = enable;
+        self
+    }
+
+    /// Enable or disable the [`threads`] Wasm proposal for the [`Config`].
+    ///
+    /// # Note
+    ///
+    /// - Disabled by default.
+    /// - Shared linear memories additionally require to disable
+    ///   deterministic tracing via [`Config::deterministic_tracing`].
+    /// - The bytes of shared linear memories are allocated up to their maximum size
+    ///   upon creation since they must never move while other threads access them.
+    ///   Therefore the [`ResourceLimiter`] of a [`Store`] is charged for the maximum
+    ///   size of a shared linear memory instead of its initial size.
+    /// - Shared linear memories are required to have a maximum size.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
+    /// [`threads`]: https://github.com/WebAssembly/threads
+    pub fn wasm_threads(&mut self, enable: bool) -> &mut Self {
+        self.threads = enable;
+        self
+    }
+
+    /// Enable or disable deterministic tracing for the [`Config`].
+    ///
+    /// # Note
+    ///
+    /// In deterministic tracing mode all traced executions are reproducible
+    /// which is why shared linear memories are rejected since they can be
+    /// concurrently modified by other threads.
+    ///
+    /// Enabled by default.
+    pub fn deterministic_tracing(&mut self, enable: bool) -> &mut Self {
+        self.deterministic_tracing = enable;
+        self
+    }
+
+    /// Returns `true` if the [`Config`] enables deterministic tracing.
+    pub(crate) fn get_deterministic_tracing(&self) -> bool {
+        self.deterministic_tracing
+    }
+
     /// Enable or disable the [`extended-const`] Wasm proposal for the [`Config`].
     ///
     /// # Note
@@ -373,6 +600,48 @@ impl Config {
         self.consume_fuel
     }
 
+    /// Configures whether Wasm executions can be interrupted via the epoch of the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// - The executor checks the epoch of the [`Engine`] at every loop back-edge
+    ///   and function call and traps with [`TrapCode::Interrupted`] once it
+    ///   reached the deadline of the [`Store`].
+    /// - Use [`Engine::increment_epoch`] to advance the epoch, e.g. from another thread.
+    /// - Use [`Store::set_epoch_deadline`] to configure the deadline of a [`Store`].
+    ///   By default a [`Store`] has no deadline.
+    /// - In contrast to fuel metering this does not add any instructions to the
+    ///   translated Wasm functions.
+    ///
+    /// Disabled by default.
+    ///
+    /// [`Engine`]: crate::Engine
+    /// [`Engine::increment_epoch`]: crate::Engine::increment_epoch
+    /// [`Store`]: crate::Store
+    /// [`Store::set_epoch_deadline`]: crate::Store::set_epoch_deadline
+    /// [`TrapCode::Interrupted`]: crate::core::TrapCode::Interrupted
+    pub fn epoch_interruption(&mut self, enable: bool) -> &mut Self {
+        self.epoch_interruption = enable;
+        self
+    }
+
+    /// Returns `true` if the [`Config`] enables epoch based interruption.
+    pub(crate) fn get_epoch_interruption(&self) -> bool {
+        self.epoch_interruption
+    }
+
+    /// Sets the [`FuelCosts`] used for fuel metering of the [`Engine`].
+    ///
+    /// # Note
+    ///
+    /// This has no effect if fuel metering is disabled for the [`Engine`].
+    ///
+    /// [`Engine`]: crate::Engine
+    pub fn set_fuel_costs(&mut self, fuel_costs: FuelCosts) -> &mut Self {
+        self.fuel_costs = fuel_costs;
+        self
+    }
+
     /// Returns the configured [`FuelCosts`].
     pub(crate) fn fuel_costs(&self) -> &FuelCosts {
         &self.fuel_costs
@@ -400,6 +669,35 @@ impl Config {
             .then_some(self.fuel_consumption_mode)
     }
 
+    /// Enables or disables the superinstruction fusion pass of the [`Engine`].
+    ///
+    /// If enabled the function translator fuses common instruction sequences,
+    /// such as a constant followed by a binary instruction or a comparison
+    /// followed by a conditional branch, into single superinstructions.
+    /// Infallible `i32` and `i64` binary instructions also read `local.get`
+    /// operands directly from their local variables and a `local.get`
+    /// followed by a `local.set` is fused into a single local copy.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Note
+    ///
+    /// Superinstructions are never used in deterministic tracing mode
+    /// since the execution tracer records every Wasm operator separately.
+    ///
+    /// [`Engine`]: crate::Engine
+    pub fn superinstructions(&mut self, enable: bool) -> &mut Self {
+        self.superinstructions = enable;
+        self
+    }
+
+    /// Returns `true` if the superinstruction fusion pass is enabled for the [`Engine`].
+    ///
+    /// [`Engine`]: crate::Engine
+    pub(crate) fn get_superinstructions(&self) -> bool {
+        self.superinstructions && !self.deterministic_tracing
+    }
+
     /// Returns the [`WasmFeatures`] represented by the [`Config`].
     pub(crate) fn wasm_features(&self) -> WasmFeatures {
         WasmFeatures {
@@ -415,9 +713,9 @@ impl Config {
             component_model: false,
             simd: false,
             relaxed_simd: false,
-            threads: false,
+            threads: self.threads,
             multi_memory: false,
-            exceptions: false,
+            exceptions: self.exceptions,
             memory64: false,
             memory_control: false,
         }
@@ -0,0 +1,297 @@
+//! Interactive step debugging of Wasm executions.
+
+use super::bytecode::Instruction;
+use crate::{Instance, StoreInner, Value};
+use alloc::{boxed::Box, collections::BTreeSet, vec, vec::Vec};
+use core::fmt::{self, Debug};
+use wasmi_core::UntypedValue;
+
+/// A location at which the [`Debugger`] pauses the execution.
+///
+/// Functions are identified by their index within their module, including imported functions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Breakpoint {
+    /// Pauses before the first instruction of the function.
+    Func(u32),
+    /// Pauses before the instruction of the function that covers the Wasm operator at `source_pc`.
+    ///
+    /// The `source_pc` is the byte offset of the Wasm operator within the Wasm binary.
+    Instr {
+        /// The index of the function.
+        func_index: u32,
+        /// The byte offset of the Wasm operator.
+        source_pc: u32,
+    },
+}
+
+/// The reason why the [`Debugger`] paused the execution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PauseReason {
+    /// The execution hit a [`Breakpoint`].
+    Breakpoint(Breakpoint),
+    /// The execution finished a single step.
+    Step,
+}
+
+/// How to proceed with the execution after a pause.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DebugAction {
+    /// Resume the execution until the next [`Breakpoint`] is hit.
+    Continue,
+    /// Execute the paused instruction and pause again before the next one.
+    Step,
+}
+
+/// The handler that is called whenever the [`Debugger`] pauses the execution.
+type PauseHandler = Box<dyn FnMut(&mut DebugContext) -> DebugAction + Send + Sync>;
+
+/// A step debugger that pauses Wasm executions of a [`Store`].
+///
+/// Install it via [`Store::set_debugger`]. Whenever the execution pauses the
+/// handler is called with a [`DebugContext`] to inspect the paused state.
+/// The execution resumes as soon as the handler returns.
+///
+/// [`Store`]: crate::Store
+/// [`Store::set_debugger`]: crate::Store::set_debugger
+pub struct Debugger {
+    /// The breakpoints at which the execution pauses.
+    breakpoints: BTreeSet<Breakpoint>,
+    /// Whether to pause before the next executed instruction.
+    stepping: bool,
+    /// The handler called upon pauses.
+    handler: PauseHandler,
+}
+
+impl Debug for Debugger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Debugger")
+            .field("breakpoints", &self.breakpoints)
+            .field("stepping", &self.stepping)
+            .finish()
+    }
+}
+
+impl Debugger {
+    /// Creates a new [`Debugger`] calling `handler` whenever the execution pauses.
+    pub fn new<F>(handler: F) -> Self
+    where
+        F: FnMut(&mut DebugContext) -> DebugAction + Send + Sync + 'static,
+    {
+        Self {
+            breakpoints: BTreeSet::new(),
+            stepping: false,
+            handler: Box::new(handler),
+        }
+    }
+
+    /// Adds the [`Breakpoint`] and returns `false` if it already existed.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.breakpoints.insert(breakpoint)
+    }
+
+    /// Removes the [`Breakpoint`] and returns `false` if it did not exist.
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.breakpoints.remove(&breakpoint)
+    }
+
+    /// Returns an iterator over all [`Breakpoint`] of the [`Debugger`].
+    pub fn breakpoints(&self) -> impl Iterator<Item = Breakpoint> + '_ {
+        self.breakpoints.iter().copied()
+    }
+
+    /// Pauses before the next executed instruction if `stepping` is `true`.
+    ///
+    /// This is useful to pause at the very first instruction of an execution.
+    pub fn set_stepping(&mut self, stepping: bool) {
+        self.stepping = stepping;
+    }
+
+    /// Returns `true` if the [`Debugger`] may pause the execution in the function at `func_pc`.
+    ///
+    /// This is a cheap pre-check that avoids resolving the function index for every instruction.
+    #[inline]
+    pub(crate) fn may_pause(&self, func_pc: u32, source_pcs: (u32, u32)) -> bool {
+        if self.stepping {
+            return true;
+        }
+        if self.breakpoints.is_empty() {
+            return false;
+        }
+        let (first, last) = source_pcs;
+        self.breakpoints.iter().any(|breakpoint| match *breakpoint {
+            Breakpoint::Func(_) => func_pc == 0,
+            Breakpoint::Instr { source_pc, .. } => first <= source_pc && source_pc <= last,
+        })
+    }
+
+    /// Returns the [`PauseReason`] if the execution must pause at the instruction.
+    pub(crate) fn pause_reason(
+        &self,
+        func_index: u32,
+        func_pc: u32,
+        source_pcs: (u32, u32),
+    ) -> Option<PauseReason> {
+        let (first, last) = source_pcs;
+        let breakpoint = self
+            .breakpoints
+            .iter()
+            .find(|breakpoint| match **breakpoint {
+                Breakpoint::Func(index) => index == func_index && func_pc == 0,
+                Breakpoint::Instr {
+                    func_index: index,
+                    source_pc,
+                } => index == func_index && first <= source_pc && source_pc <= last,
+            });
+        match breakpoint {
+            Some(breakpoint) => Some(PauseReason::Breakpoint(*breakpoint)),
+            None if self.stepping => Some(PauseReason::Step),
+            None => None,
+        }
+    }
+
+    /// Calls the pause handler with the paused state and applies the returned [`DebugAction`].
+    pub(crate) fn pause(
+        &mut self,
+        state: PausedState,
+        store: &StoreInner,
+        values: &[UntypedValue],
+    ) {
+        let mut ctx = DebugContext {
+            state,
+            store,
+            values,
+            breakpoints: &mut self.breakpoints,
+        };
+        let action = (self.handler)(&mut ctx);
+        self.stepping = matches!(action, DebugAction::Step);
+    }
+}
+
+/// The executor state at a pause point.
+#[derive(Debug)]
+pub(crate) struct PausedState {
+    /// The reason of the pause.
+    pub reason: PauseReason,
+    /// The index of the paused function within its module.
+    pub func_index: u32,
+    /// The byte offset of the paused Wasm operator.
+    pub source_pc: u32,
+    /// The paused instruction.
+    pub instr: Instruction,
+    /// The number of Wasm callers of the paused function.
+    pub call_depth: usize,
+    /// The number of parameters and local variables of the paused function.
+    pub len_locals: usize,
+    /// The height of the operand stack of the paused function.
+    pub len_operands: usize,
+    /// The instance of the paused function.
+    pub instance: Instance,
+}
+
+/// The paused state of a Wasm execution as seen by the [`Debugger`] pause handler.
+pub struct DebugContext<'a> {
+    state: PausedState,
+    store: &'a StoreInner,
+    values: &'a [UntypedValue],
+    breakpoints: &'a mut BTreeSet<Breakpoint>,
+}
+
+impl Debug for DebugContext<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DebugContext")
+            .field("state", &self.state)
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+impl<'a> DebugContext<'a> {
+    /// Returns the reason why the execution paused.
+    pub fn reason(&self) -> PauseReason {
+        self.state.reason
+    }
+
+    /// Returns the index of the paused function within its module, including imported functions.
+    pub fn func_index(&self) -> u32 {
+        self.state.func_index
+    }
+
+    /// Returns the byte offset of the paused Wasm operator within the Wasm binary.
+    pub fn source_pc(&self) -> u32 {
+        self.state.source_pc
+    }
+
+    /// Returns the paused `wasmi` bytecode [`Instruction`].
+    pub fn instr(&self) -> Instruction {
+        self.state.instr
+    }
+
+    /// Returns the number of Wasm callers of the paused function.
+    pub fn call_depth(&self) -> usize {
+        self.state.call_depth
+    }
+
+    /// Returns the parameters followed by the local variables of the paused function.
+    pub fn locals(&self) -> &[UntypedValue] {
+        let end = self.values.len() - self.state.len_operands;
+        &self.values[end - self.state.len_locals..end]
+    }
+
+    /// Returns the operand stack of the paused function, top-most value last.
+    pub fn operands(&self) -> &[UntypedValue] {
+        &self.values[self.values.len() - self.state.len_operands..]
+    }
+
+    /// Returns the entire value stack of the execution, including all caller frames.
+    pub fn value_stack(&self) -> &[UntypedValue] {
+        self.values
+    }
+
+    /// Returns the value of the global variable at `index` of the paused instance if any.
+    pub fn global(&self, index: u32) -> Option<Value> {
+        let global = self
+            .store
+            .resolve_instance(&self.state.instance)
+            .get_global(index)?;
+        Some(self.store.resolve_global(&global).get())
+    }
+
+    /// Returns a copy of up to `len` bytes of the linear memory at `index` of the
+    /// paused instance starting at `offset` if any.
+    ///
+    /// # Note
+    ///
+    /// - The returned bytes end at the current size of the linear memory.
+    /// - The bytes are copied since other threads might concurrently
+    ///   modify the bytes of a shared linear memory.
+    pub fn read_memory(&self, index: u32, offset: usize, len: usize) -> Option<Vec<u8>> {
+        let memory = self
+            .store
+            .resolve_instance(&self.state.instance)
+            .get_memory(index)?;
+        let memory = self.store.resolve_memory(&memory);
+        let size = memory.data_len();
+        let start = offset.min(size);
+        let end = offset.saturating_add(len).min(size);
+        let mut bytes = vec![0x00; end - start];
+        memory
+            .read(start, &mut bytes)
+            .unwrap_or_else(|error| panic!("failed to read in bounds linear memory: {error}"));
+        Some(bytes)
+    }
+
+    /// Adds the [`Breakpoint`] and returns `false` if it already existed.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.breakpoints.insert(breakpoint)
+    }
+
+    /// Removes the [`Breakpoint`] and returns `false` if it did not exist.
+    pub fn remove_breakpoint(&mut self, breakpoint: Breakpoint) -> bool {
+        self.breakpoints.remove(&breakpoint)
+    }
+
+    /// Returns an iterator over all [`Breakpoint`] of the [`Debugger`].
+    pub fn breakpoints(&self) -> impl Iterator<Item = Breakpoint> + '_ {
+        self.breakpoints.iter().copied()
+    }
+}
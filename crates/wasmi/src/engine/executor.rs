@@ -1,38 +1,63 @@
-use core::cmp::{self};
+use alloc::vec;
+use core::{
+    cmp::{self},
+    mem::size_of,
+    ptr::NonNull,
+    sync::atomic,
+    time::Duration,
+};
 
+use wasmi_arena::ArenaIndex;
 use wasmi_core::{Pages, UntypedValue};
 
 use crate::{
     core::TrapCode,
     engine::{
         bytecode::{
+            BranchOffset,
             BranchParams,
             DataSegmentIdx,
             ElementSegmentIdx,
             FuncIdx,
             GlobalIdx,
+            InstrMeta,
             Instruction,
             LocalDepth,
             Offset,
             SignatureIdx,
             TableIdx,
+            TagIdx,
         },
         cache::InstanceCache,
         code_map::{CodeMap, InstructionPtr},
-        config::FuelCosts,
+        config::ResolvedFuelCosts,
+        debugger::{Debugger, PausedState},
+        profiler::Profiler,
         DropKeep,
         FuncFrame,
-        stack::{CallStack, ValueStackPtr},
+        stack::{CallStack, CaughtException, TryHandler, UntypedException, ValueStackPtr},
         ValueStack,
     },
+    errors::{MemoryError, TableError},
     FuelConsumptionMode,
     Func,
     func::FuncEntity,
+    limits::ResourceLimiterRef,
+    memory::{
+        atomics::{self, AtomicRmwOp, AtomicWord},
+        copy_raw,
+        fill_raw,
+        read_raw,
+        write_raw,
+        WaitResult,
+        Waiting,
+    },
     FuncRef,
     Instance,
     StoreInner,
     Table,
     table::TableEntity,
+    Tag,
 };
 use crate::engine::tracer::Tracer;
 
@@ -42,12 +67,17 @@ use crate::engine::tracer::Tracer;
 ///
 /// A Wasm execution includes everything but host calls.
 /// In other words: Everything in between host calls is a Wasm execution.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug)]
 pub enum WasmOutcome {
     /// The Wasm execution has ended and returns to the host side.
     Return,
     /// The Wasm execution calls a host function.
     Call { host_func: Func, instance: Instance },
+    /// The Wasm execution blocks in a `memory.atomic.wait` instruction.
+    ///
+    /// The result of the instruction has to be pushed onto the value stack
+    /// before the execution is resumed.
+    Wait(Waiting),
 }
 
 /// The outcome of a Wasm execution.
@@ -93,15 +123,30 @@ pub enum ReturnOutcome {
 ///
 /// If the Wasm execution traps.
 #[inline(never)]
-pub fn execute_wasm<'engine>(
-    ctx: &mut StoreInner,
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn execute_wasm<'engine>(
+    ctx: &'engine mut StoreInner,
     cache: &'engine mut InstanceCache,
     value_stack: &'engine mut ValueStack,
     call_stack: &'engine mut CallStack,
     code_map: &'engine CodeMap,
     tracer: &'engine mut Tracer,
+    debugger: &'engine mut Option<Debugger>,
+    profiler: &'engine mut Option<Profiler>,
+    limiter: ResourceLimiterRef<'engine>,
 ) -> Result<WasmOutcome, TrapCode> {
-    Executor::new(ctx, cache, value_stack, call_stack, code_map, tracer).execute()
+    Executor::new(
+        ctx,
+        cache,
+        value_stack,
+        call_stack,
+        code_map,
+        tracer,
+        debugger,
+        profiler,
+        limiter,
+    )
+    .execute()
 }
 
 /// The function signature of Wasm load operations.
@@ -116,6 +161,57 @@ type WasmStoreOp = fn(
     value: UntypedValue,
 ) -> Result<(), TrapCode>;
 
+/// Executes the `load_extend` operation on the bytes of a shared linear `memory`.
+///
+/// # Note
+///
+/// Other threads might concurrently access the bytes of a shared linear memory.
+/// Therefore the loaded bytes are first copied via raw pointer reads.
+fn shared_load_extend(
+    memory: NonNull<[u8]>,
+    address: UntypedValue,
+    offset: u32,
+    load_extend: WasmLoadOp,
+) -> Result<UntypedValue, TrapCode> {
+    let address = u32::from(address)
+        .checked_add(offset)
+        .ok_or(TrapCode::MemoryOutOfBounds)? as usize;
+    let mut buffer = [0x00_u8; 8];
+    let len_buffer = memory.len().saturating_sub(address).min(buffer.len());
+    let buffer = &mut buffer[..len_buffer];
+    // Safety: the bytes of shared linear memories are valid for reads.
+    unsafe { read_raw(memory, address, buffer) }.map_err(|_| TrapCode::MemoryOutOfBounds)?;
+    // The load traps if `buffer` is smaller than the loaded value.
+    load_extend(buffer, UntypedValue::from(0_u32), 0)
+}
+
+/// Executes the `store_wrap` operation of `len` bytes on the bytes of a shared linear `memory`.
+///
+/// Returns the effective address and the stored bytes.
+///
+/// # Note
+///
+/// Other threads might concurrently access the bytes of a shared linear memory.
+/// Therefore the stored bytes are copied via raw pointer writes.
+fn shared_store_wrap(
+    memory: NonNull<[u8]>,
+    address: UntypedValue,
+    offset: u32,
+    value: UntypedValue,
+    store_wrap: WasmStoreOp,
+    len: u32,
+) -> Result<(usize, [u8; 8]), TrapCode> {
+    let address = u32::from(address)
+        .checked_add(offset)
+        .ok_or(TrapCode::MemoryOutOfBounds)? as usize;
+    let mut buffer = [0x00_u8; 8];
+    let stored = &mut buffer[..len as usize];
+    store_wrap(stored, UntypedValue::from(0_u32), 0, value)?;
+    // Safety: the bytes of shared linear memories are valid for writes.
+    unsafe { write_raw(memory, address, stored) }.map_err(|_| TrapCode::MemoryOutOfBounds)?;
+    Ok((address, buffer))
+}
+
 /// An error that can occur upon `memory.grow` or `table.grow`.
 #[derive(Copy, Clone)]
 pub enum EntityGrowError {
@@ -169,6 +265,16 @@ struct Executor<'ctx, 'engine> {
     code_map: &'engine CodeMap,
     /// A tracer.
     tracer: &'ctx mut Tracer,
+    /// The installed step debugger if any.
+    debugger: &'ctx mut Option<Debugger>,
+    /// The installed profiler if any.
+    profiler: &'ctx mut Option<Profiler>,
+    /// The epoch deadline of the [`Store`] if epoch based interruption is enabled.
+    ///
+    /// [`Store`]: crate::Store
+    epoch_deadline: Option<u64>,
+    /// The installed resource limiter if any.
+    limiter: ResourceLimiterRef<'ctx>,
 }
 
 macro_rules! forward_call {
@@ -186,9 +292,18 @@ macro_rules! forward_call {
     }};
 }
 
+macro_rules! forward_wait {
+    ($expr:expr) => {{
+        if let Some(waiting) = $expr? {
+            return Ok(WasmOutcome::Wait(waiting));
+        }
+    }};
+}
+
 impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     /// Creates a new [`Executor`] for executing a `wasmi` function frame.
     #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         ctx: &'ctx mut StoreInner,
         cache: &'engine mut InstanceCache,
@@ -196,10 +311,18 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         call_stack: &'engine mut CallStack,
         code_map: &'engine CodeMap,
         tracer: &'ctx mut Tracer,
+        debugger: &'ctx mut Option<Debugger>,
+        profiler: &'ctx mut Option<Profiler>,
+        limiter: ResourceLimiterRef<'ctx>,
     ) -> Self {
         let frame = call_stack.pop().expect("must have frame on the call stack");
         let sp = value_stack.stack_ptr();
         let ip = frame.ip();
+        let epoch_deadline = ctx
+            .engine()
+            .config()
+            .get_epoch_interruption()
+            .then(|| ctx.epoch_deadline());
         Self {
             sp,
             ip,
@@ -209,32 +332,65 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
             call_stack,
             code_map,
             tracer,
+            debugger,
+            profiler,
+            epoch_deadline,
+            limiter,
         }
     }
 
     /// Executes the function frame until it returns or traps.
+    ///
+    /// Upon a trap the frame of the trapping function is pushed onto the
+    /// [`CallStack`] so that a [`WasmBacktrace`] can be captured from it.
+    ///
+    /// [`WasmBacktrace`]: crate::core::WasmBacktrace
     #[inline(always)]
     fn execute(mut self) -> Result<WasmOutcome, TrapCode> {
+        let result = self.execute_instrs();
+        if result.is_err() {
+            self.call_stack
+                .push_trapped(FuncFrame::new(self.ip, self.cache.instance()));
+        }
+        result
+    }
+
+    /// Executes instructions of the function frame until it returns or traps.
+    #[inline(always)]
+    fn execute_instrs(&mut self) -> Result<WasmOutcome, TrapCode> {
         use Instruction as Instr;
+        if let Some(exception) = self.call_stack.exceptions_mut().take_pending() {
+            // Case: a host function called by the current frame threw an exception.
+            self.throw(exception, 0)?;
+        }
         loop {
             let instr = *self.ip.get();
             let meta = *self.ip.meta();
 
             // handle pre-instruction state
+            let func_index = self
+                .code_map
+                .func_body_at(self.ip)
+                .and_then(|func_body| self.code_map.index(func_body));
             self.tracer.pre_opcode_state(
                 self.ip.pc(),
                 instr,
                 self.value_stack.dump_stack(self.sp),
                 &meta,
+                func_index,
             );
+            if self.debugger.is_some() {
+                self.debug_hook(instr, &meta);
+            }
+            self.profile(|profiler, _, fuel_consumed| profiler.instr(instr, fuel_consumed));
 
             match instr {
                 Instr::LocalGet { local_depth } => self.visit_local_get(local_depth),
                 Instr::LocalSet { local_depth } => self.visit_local_set(local_depth),
                 Instr::LocalTee { local_depth } => self.visit_local_tee(local_depth),
-                Instr::Br(params) => self.visit_br(params),
-                Instr::BrIfEqz(params) => self.visit_br_if_eqz(params),
-                Instr::BrIfNez(params) => self.visit_br_if_nez(params),
+                Instr::Br(params) => self.visit_br(params)?,
+                Instr::BrIfEqz(params) => self.visit_br_if_eqz(params)?,
+                Instr::BrIfNez(params) => self.visit_br_if_nez(params)?,
                 Instr::BrTable { len_targets } => self.visit_br_table(len_targets),
                 Instr::Unreachable => self.visit_unreachable()?,
                 Instr::ConsumeFuel { amount } => self.visit_consume_fuel(amount)?,
@@ -262,6 +418,15 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                 Instr::CallIndirect { table, func_type } => {
                     forward_call!(self.visit_call_indirect(table, func_type))
                 }
+                Instr::Try {
+                    landing,
+                    end,
+                    len_params,
+                } => self.visit_try(landing, end, len_params),
+                Instr::CatchTag { tag, next } => self.visit_catch_tag(tag, next),
+                Instr::Throw(tag) => self.visit_throw(tag)?,
+                Instr::Rethrow { landing } => self.visit_rethrow(landing)?,
+                Instr::Delegate { skip } => self.visit_delegate(skip)?,
                 Instr::Drop => self.visit_drop(),
                 Instr::Select => self.visit_select(),
                 Instr::GlobalGet(global_idx) => self.visit_global_get(global_idx),
@@ -295,6 +460,77 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                 Instr::MemoryCopy => self.visit_memory_copy()?,
                 Instr::MemoryInit(segment) => self.visit_memory_init(segment)?,
                 Instr::DataDrop(segment) => self.visit_data_drop(segment),
+                Instr::I32AtomicLoad(offset) => self.visit_i32_atomic_load(offset)?,
+                Instr::I64AtomicLoad(offset) => self.visit_i64_atomic_load(offset)?,
+                Instr::I32AtomicLoad8U(offset) => self.visit_i32_atomic_load8_u(offset)?,
+                Instr::I32AtomicLoad16U(offset) => self.visit_i32_atomic_load16_u(offset)?,
+                Instr::I64AtomicLoad8U(offset) => self.visit_i64_atomic_load8_u(offset)?,
+                Instr::I64AtomicLoad16U(offset) => self.visit_i64_atomic_load16_u(offset)?,
+                Instr::I64AtomicLoad32U(offset) => self.visit_i64_atomic_load32_u(offset)?,
+                Instr::I32AtomicStore(offset) => self.visit_i32_atomic_store(offset)?,
+                Instr::I64AtomicStore(offset) => self.visit_i64_atomic_store(offset)?,
+                Instr::I32AtomicStore8(offset) => self.visit_i32_atomic_store8(offset)?,
+                Instr::I32AtomicStore16(offset) => self.visit_i32_atomic_store16(offset)?,
+                Instr::I64AtomicStore8(offset) => self.visit_i64_atomic_store8(offset)?,
+                Instr::I64AtomicStore16(offset) => self.visit_i64_atomic_store16(offset)?,
+                Instr::I64AtomicStore32(offset) => self.visit_i64_atomic_store32(offset)?,
+                Instr::I32AtomicRmwAdd(offset) => self.visit_i32_atomic_rmw_add(offset)?,
+                Instr::I64AtomicRmwAdd(offset) => self.visit_i64_atomic_rmw_add(offset)?,
+                Instr::I32AtomicRmw8AddU(offset) => self.visit_i32_atomic_rmw8_add_u(offset)?,
+                Instr::I32AtomicRmw16AddU(offset) => self.visit_i32_atomic_rmw16_add_u(offset)?,
+                Instr::I64AtomicRmw8AddU(offset) => self.visit_i64_atomic_rmw8_add_u(offset)?,
+                Instr::I64AtomicRmw16AddU(offset) => self.visit_i64_atomic_rmw16_add_u(offset)?,
+                Instr::I64AtomicRmw32AddU(offset) => self.visit_i64_atomic_rmw32_add_u(offset)?,
+                Instr::I32AtomicRmwSub(offset) => self.visit_i32_atomic_rmw_sub(offset)?,
+                Instr::I64AtomicRmwSub(offset) => self.visit_i64_atomic_rmw_sub(offset)?,
+                Instr::I32AtomicRmw8SubU(offset) => self.visit_i32_atomic_rmw8_sub_u(offset)?,
+                Instr::I32AtomicRmw16SubU(offset) => self.visit_i32_atomic_rmw16_sub_u(offset)?,
+                Instr::I64AtomicRmw8SubU(offset) => self.visit_i64_atomic_rmw8_sub_u(offset)?,
+                Instr::I64AtomicRmw16SubU(offset) => self.visit_i64_atomic_rmw16_sub_u(offset)?,
+                Instr::I64AtomicRmw32SubU(offset) => self.visit_i64_atomic_rmw32_sub_u(offset)?,
+                Instr::I32AtomicRmwAnd(offset) => self.visit_i32_atomic_rmw_and(offset)?,
+                Instr::I64AtomicRmwAnd(offset) => self.visit_i64_atomic_rmw_and(offset)?,
+                Instr::I32AtomicRmw8AndU(offset) => self.visit_i32_atomic_rmw8_and_u(offset)?,
+                Instr::I32AtomicRmw16AndU(offset) => self.visit_i32_atomic_rmw16_and_u(offset)?,
+                Instr::I64AtomicRmw8AndU(offset) => self.visit_i64_atomic_rmw8_and_u(offset)?,
+                Instr::I64AtomicRmw16AndU(offset) => self.visit_i64_atomic_rmw16_and_u(offset)?,
+                Instr::I64AtomicRmw32AndU(offset) => self.visit_i64_atomic_rmw32_and_u(offset)?,
+                Instr::I32AtomicRmwOr(offset) => self.visit_i32_atomic_rmw_or(offset)?,
+                Instr::I64AtomicRmwOr(offset) => self.visit_i64_atomic_rmw_or(offset)?,
+                Instr::I32AtomicRmw8OrU(offset) => self.visit_i32_atomic_rmw8_or_u(offset)?,
+                Instr::I32AtomicRmw16OrU(offset) => self.visit_i32_atomic_rmw16_or_u(offset)?,
+                Instr::I64AtomicRmw8OrU(offset) => self.visit_i64_atomic_rmw8_or_u(offset)?,
+                Instr::I64AtomicRmw16OrU(offset) => self.visit_i64_atomic_rmw16_or_u(offset)?,
+                Instr::I64AtomicRmw32OrU(offset) => self.visit_i64_atomic_rmw32_or_u(offset)?,
+                Instr::I32AtomicRmwXor(offset) => self.visit_i32_atomic_rmw_xor(offset)?,
+                Instr::I64AtomicRmwXor(offset) => self.visit_i64_atomic_rmw_xor(offset)?,
+                Instr::I32AtomicRmw8XorU(offset) => self.visit_i32_atomic_rmw8_xor_u(offset)?,
+                Instr::I32AtomicRmw16XorU(offset) => self.visit_i32_atomic_rmw16_xor_u(offset)?,
+                Instr::I64AtomicRmw8XorU(offset) => self.visit_i64_atomic_rmw8_xor_u(offset)?,
+                Instr::I64AtomicRmw16XorU(offset) => self.visit_i64_atomic_rmw16_xor_u(offset)?,
+                Instr::I64AtomicRmw32XorU(offset) => self.visit_i64_atomic_rmw32_xor_u(offset)?,
+                Instr::I32AtomicRmwXchg(offset) => self.visit_i32_atomic_rmw_xchg(offset)?,
+                Instr::I64AtomicRmwXchg(offset) => self.visit_i64_atomic_rmw_xchg(offset)?,
+                Instr::I32AtomicRmw8XchgU(offset) => self.visit_i32_atomic_rmw8_xchg_u(offset)?,
+                Instr::I32AtomicRmw16XchgU(offset) => self.visit_i32_atomic_rmw16_xchg_u(offset)?,
+                Instr::I64AtomicRmw8XchgU(offset) => self.visit_i64_atomic_rmw8_xchg_u(offset)?,
+                Instr::I64AtomicRmw16XchgU(offset) => self.visit_i64_atomic_rmw16_xchg_u(offset)?,
+                Instr::I64AtomicRmw32XchgU(offset) => self.visit_i64_atomic_rmw32_xchg_u(offset)?,
+                Instr::I32AtomicRmwCmpxchg(offset) => self.visit_i32_atomic_rmw_cmpxchg(offset)?,
+                Instr::I64AtomicRmwCmpxchg(offset) => self.visit_i64_atomic_rmw_cmpxchg(offset)?,
+                Instr::I32AtomicRmw8CmpxchgU(offset) => self.visit_i32_atomic_rmw8_cmpxchg_u(offset)?,
+                Instr::I32AtomicRmw16CmpxchgU(offset) => self.visit_i32_atomic_rmw16_cmpxchg_u(offset)?,
+                Instr::I64AtomicRmw8CmpxchgU(offset) => self.visit_i64_atomic_rmw8_cmpxchg_u(offset)?,
+                Instr::I64AtomicRmw16CmpxchgU(offset) => self.visit_i64_atomic_rmw16_cmpxchg_u(offset)?,
+                Instr::I64AtomicRmw32CmpxchgU(offset) => self.visit_i64_atomic_rmw32_cmpxchg_u(offset)?,
+                Instr::MemoryAtomicNotify(offset) => self.visit_memory_atomic_notify(offset)?,
+                Instr::MemoryAtomicWait32(offset) => {
+                    forward_wait!(self.visit_memory_atomic_wait32(offset))
+                }
+                Instr::MemoryAtomicWait64(offset) => {
+                    forward_wait!(self.visit_memory_atomic_wait64(offset))
+                }
+                Instr::AtomicFence => self.visit_atomic_fence(),
                 Instr::TableSize { table } => self.visit_table_size(table),
                 Instr::TableGrow { table } => self.visit_table_grow(table)?,
                 Instr::TableFill { table } => self.visit_table_fill(table)?,
@@ -432,6 +668,153 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                 Instr::I64TruncSatF32U => self.visit_i64_trunc_sat_f32_u(),
                 Instr::I64TruncSatF64S => self.visit_i64_trunc_sat_f64_s(),
                 Instr::I64TruncSatF64U => self.visit_i64_trunc_sat_f64_u(),
+                Instr::LocalCopy { result, input } => self.visit_local_copy(result, input),
+                Instr::I32EqLocalLocal { lhs, rhs } => self.visit_i32_eq_local_local(lhs, rhs),
+                Instr::I32EqLocal { rhs } => self.visit_i32_eq_local(rhs),
+                Instr::I32NeLocalLocal { lhs, rhs } => self.visit_i32_ne_local_local(lhs, rhs),
+                Instr::I32NeLocal { rhs } => self.visit_i32_ne_local(rhs),
+                Instr::I32LtSLocalLocal { lhs, rhs } => self.visit_i32_lt_s_local_local(lhs, rhs),
+                Instr::I32LtSLocal { rhs } => self.visit_i32_lt_s_local(rhs),
+                Instr::I32LtULocalLocal { lhs, rhs } => self.visit_i32_lt_u_local_local(lhs, rhs),
+                Instr::I32LtULocal { rhs } => self.visit_i32_lt_u_local(rhs),
+                Instr::I32GtSLocalLocal { lhs, rhs } => self.visit_i32_gt_s_local_local(lhs, rhs),
+                Instr::I32GtSLocal { rhs } => self.visit_i32_gt_s_local(rhs),
+                Instr::I32GtULocalLocal { lhs, rhs } => self.visit_i32_gt_u_local_local(lhs, rhs),
+                Instr::I32GtULocal { rhs } => self.visit_i32_gt_u_local(rhs),
+                Instr::I32LeSLocalLocal { lhs, rhs } => self.visit_i32_le_s_local_local(lhs, rhs),
+                Instr::I32LeSLocal { rhs } => self.visit_i32_le_s_local(rhs),
+                Instr::I32LeULocalLocal { lhs, rhs } => self.visit_i32_le_u_local_local(lhs, rhs),
+                Instr::I32LeULocal { rhs } => self.visit_i32_le_u_local(rhs),
+                Instr::I32GeSLocalLocal { lhs, rhs } => self.visit_i32_ge_s_local_local(lhs, rhs),
+                Instr::I32GeSLocal { rhs } => self.visit_i32_ge_s_local(rhs),
+                Instr::I32GeULocalLocal { lhs, rhs } => self.visit_i32_ge_u_local_local(lhs, rhs),
+                Instr::I32GeULocal { rhs } => self.visit_i32_ge_u_local(rhs),
+                Instr::I32AddLocalLocal { lhs, rhs } => self.visit_i32_add_local_local(lhs, rhs),
+                Instr::I32AddLocal { rhs } => self.visit_i32_add_local(rhs),
+                Instr::I32SubLocalLocal { lhs, rhs } => self.visit_i32_sub_local_local(lhs, rhs),
+                Instr::I32SubLocal { rhs } => self.visit_i32_sub_local(rhs),
+                Instr::I32MulLocalLocal { lhs, rhs } => self.visit_i32_mul_local_local(lhs, rhs),
+                Instr::I32MulLocal { rhs } => self.visit_i32_mul_local(rhs),
+                Instr::I32AndLocalLocal { lhs, rhs } => self.visit_i32_and_local_local(lhs, rhs),
+                Instr::I32AndLocal { rhs } => self.visit_i32_and_local(rhs),
+                Instr::I32OrLocalLocal { lhs, rhs } => self.visit_i32_or_local_local(lhs, rhs),
+                Instr::I32OrLocal { rhs } => self.visit_i32_or_local(rhs),
+                Instr::I32XorLocalLocal { lhs, rhs } => self.visit_i32_xor_local_local(lhs, rhs),
+                Instr::I32XorLocal { rhs } => self.visit_i32_xor_local(rhs),
+                Instr::I32ShlLocalLocal { lhs, rhs } => self.visit_i32_shl_local_local(lhs, rhs),
+                Instr::I32ShlLocal { rhs } => self.visit_i32_shl_local(rhs),
+                Instr::I32ShrSLocalLocal { lhs, rhs } => self.visit_i32_shr_s_local_local(lhs, rhs),
+                Instr::I32ShrSLocal { rhs } => self.visit_i32_shr_s_local(rhs),
+                Instr::I32ShrULocalLocal { lhs, rhs } => self.visit_i32_shr_u_local_local(lhs, rhs),
+                Instr::I32ShrULocal { rhs } => self.visit_i32_shr_u_local(rhs),
+                Instr::I32RotlLocalLocal { lhs, rhs } => self.visit_i32_rotl_local_local(lhs, rhs),
+                Instr::I32RotlLocal { rhs } => self.visit_i32_rotl_local(rhs),
+                Instr::I32RotrLocalLocal { lhs, rhs } => self.visit_i32_rotr_local_local(lhs, rhs),
+                Instr::I32RotrLocal { rhs } => self.visit_i32_rotr_local(rhs),
+                Instr::I64EqLocalLocal { lhs, rhs } => self.visit_i64_eq_local_local(lhs, rhs),
+                Instr::I64EqLocal { rhs } => self.visit_i64_eq_local(rhs),
+                Instr::I64NeLocalLocal { lhs, rhs } => self.visit_i64_ne_local_local(lhs, rhs),
+                Instr::I64NeLocal { rhs } => self.visit_i64_ne_local(rhs),
+                Instr::I64LtSLocalLocal { lhs, rhs } => self.visit_i64_lt_s_local_local(lhs, rhs),
+                Instr::I64LtSLocal { rhs } => self.visit_i64_lt_s_local(rhs),
+                Instr::I64LtULocalLocal { lhs, rhs } => self.visit_i64_lt_u_local_local(lhs, rhs),
+                Instr::I64LtULocal { rhs } => self.visit_i64_lt_u_local(rhs),
+                Instr::I64GtSLocalLocal { lhs, rhs } => self.visit_i64_gt_s_local_local(lhs, rhs),
+                Instr::I64GtSLocal { rhs } => self.visit_i64_gt_s_local(rhs),
+                Instr::I64GtULocalLocal { lhs, rhs } => self.visit_i64_gt_u_local_local(lhs, rhs),
+                Instr::I64GtULocal { rhs } => self.visit_i64_gt_u_local(rhs),
+                Instr::I64LeSLocalLocal { lhs, rhs } => self.visit_i64_le_s_local_local(lhs, rhs),
+                Instr::I64LeSLocal { rhs } => self.visit_i64_le_s_local(rhs),
+                Instr::I64LeULocalLocal { lhs, rhs } => self.visit_i64_le_u_local_local(lhs, rhs),
+                Instr::I64LeULocal { rhs } => self.visit_i64_le_u_local(rhs),
+                Instr::I64GeSLocalLocal { lhs, rhs } => self.visit_i64_ge_s_local_local(lhs, rhs),
+                Instr::I64GeSLocal { rhs } => self.visit_i64_ge_s_local(rhs),
+                Instr::I64GeULocalLocal { lhs, rhs } => self.visit_i64_ge_u_local_local(lhs, rhs),
+                Instr::I64GeULocal { rhs } => self.visit_i64_ge_u_local(rhs),
+                Instr::I64AddLocalLocal { lhs, rhs } => self.visit_i64_add_local_local(lhs, rhs),
+                Instr::I64AddLocal { rhs } => self.visit_i64_add_local(rhs),
+                Instr::I64SubLocalLocal { lhs, rhs } => self.visit_i64_sub_local_local(lhs, rhs),
+                Instr::I64SubLocal { rhs } => self.visit_i64_sub_local(rhs),
+                Instr::I64MulLocalLocal { lhs, rhs } => self.visit_i64_mul_local_local(lhs, rhs),
+                Instr::I64MulLocal { rhs } => self.visit_i64_mul_local(rhs),
+                Instr::I64AndLocalLocal { lhs, rhs } => self.visit_i64_and_local_local(lhs, rhs),
+                Instr::I64AndLocal { rhs } => self.visit_i64_and_local(rhs),
+                Instr::I64OrLocalLocal { lhs, rhs } => self.visit_i64_or_local_local(lhs, rhs),
+                Instr::I64OrLocal { rhs } => self.visit_i64_or_local(rhs),
+                Instr::I64XorLocalLocal { lhs, rhs } => self.visit_i64_xor_local_local(lhs, rhs),
+                Instr::I64XorLocal { rhs } => self.visit_i64_xor_local(rhs),
+                Instr::I64ShlLocalLocal { lhs, rhs } => self.visit_i64_shl_local_local(lhs, rhs),
+                Instr::I64ShlLocal { rhs } => self.visit_i64_shl_local(rhs),
+                Instr::I64ShrSLocalLocal { lhs, rhs } => self.visit_i64_shr_s_local_local(lhs, rhs),
+                Instr::I64ShrSLocal { rhs } => self.visit_i64_shr_s_local(rhs),
+                Instr::I64ShrULocalLocal { lhs, rhs } => self.visit_i64_shr_u_local_local(lhs, rhs),
+                Instr::I64ShrULocal { rhs } => self.visit_i64_shr_u_local(rhs),
+                Instr::I64RotlLocalLocal { lhs, rhs } => self.visit_i64_rotl_local_local(lhs, rhs),
+                Instr::I64RotlLocal { rhs } => self.visit_i64_rotl_local(rhs),
+                Instr::I64RotrLocalLocal { lhs, rhs } => self.visit_i64_rotr_local_local(lhs, rhs),
+                Instr::I64RotrLocal { rhs } => self.visit_i64_rotr_local(rhs),
+                Instr::I32EqImm(rhs) => self.visit_i32_eq_imm(rhs),
+                Instr::I32NeImm(rhs) => self.visit_i32_ne_imm(rhs),
+                Instr::I32LtSImm(rhs) => self.visit_i32_lt_s_imm(rhs),
+                Instr::I32LtUImm(rhs) => self.visit_i32_lt_u_imm(rhs),
+                Instr::I32GtSImm(rhs) => self.visit_i32_gt_s_imm(rhs),
+                Instr::I32GtUImm(rhs) => self.visit_i32_gt_u_imm(rhs),
+                Instr::I32LeSImm(rhs) => self.visit_i32_le_s_imm(rhs),
+                Instr::I32LeUImm(rhs) => self.visit_i32_le_u_imm(rhs),
+                Instr::I32GeSImm(rhs) => self.visit_i32_ge_s_imm(rhs),
+                Instr::I32GeUImm(rhs) => self.visit_i32_ge_u_imm(rhs),
+                Instr::I32AddImm(rhs) => self.visit_i32_add_imm(rhs),
+                Instr::I32SubImm(rhs) => self.visit_i32_sub_imm(rhs),
+                Instr::I32MulImm(rhs) => self.visit_i32_mul_imm(rhs),
+                Instr::I32AndImm(rhs) => self.visit_i32_and_imm(rhs),
+                Instr::I32OrImm(rhs) => self.visit_i32_or_imm(rhs),
+                Instr::I32XorImm(rhs) => self.visit_i32_xor_imm(rhs),
+                Instr::I32ShlImm(rhs) => self.visit_i32_shl_imm(rhs),
+                Instr::I32ShrSImm(rhs) => self.visit_i32_shr_s_imm(rhs),
+                Instr::I32ShrUImm(rhs) => self.visit_i32_shr_u_imm(rhs),
+                Instr::I32RotlImm(rhs) => self.visit_i32_rotl_imm(rhs),
+                Instr::I32RotrImm(rhs) => self.visit_i32_rotr_imm(rhs),
+                Instr::I64EqImm(rhs) => self.visit_i64_eq_imm(rhs),
+                Instr::I64NeImm(rhs) => self.visit_i64_ne_imm(rhs),
+                Instr::I64LtSImm(rhs) => self.visit_i64_lt_s_imm(rhs),
+                Instr::I64LtUImm(rhs) => self.visit_i64_lt_u_imm(rhs),
+                Instr::I64GtSImm(rhs) => self.visit_i64_gt_s_imm(rhs),
+                Instr::I64GtUImm(rhs) => self.visit_i64_gt_u_imm(rhs),
+                Instr::I64LeSImm(rhs) => self.visit_i64_le_s_imm(rhs),
+                Instr::I64LeUImm(rhs) => self.visit_i64_le_u_imm(rhs),
+                Instr::I64GeSImm(rhs) => self.visit_i64_ge_s_imm(rhs),
+                Instr::I64GeUImm(rhs) => self.visit_i64_ge_u_imm(rhs),
+                Instr::I64AddImm(rhs) => self.visit_i64_add_imm(rhs),
+                Instr::I64SubImm(rhs) => self.visit_i64_sub_imm(rhs),
+                Instr::I64MulImm(rhs) => self.visit_i64_mul_imm(rhs),
+                Instr::I64AndImm(rhs) => self.visit_i64_and_imm(rhs),
+                Instr::I64OrImm(rhs) => self.visit_i64_or_imm(rhs),
+                Instr::I64XorImm(rhs) => self.visit_i64_xor_imm(rhs),
+                Instr::I64ShlImm(rhs) => self.visit_i64_shl_imm(rhs),
+                Instr::I64ShrSImm(rhs) => self.visit_i64_shr_s_imm(rhs),
+                Instr::I64ShrUImm(rhs) => self.visit_i64_shr_u_imm(rhs),
+                Instr::I64RotlImm(rhs) => self.visit_i64_rotl_imm(rhs),
+                Instr::I64RotrImm(rhs) => self.visit_i64_rotr_imm(rhs),
+                Instr::BrIfI32Eq(params) => self.visit_br_if_i32_eq(params)?,
+                Instr::BrIfI32Ne(params) => self.visit_br_if_i32_ne(params)?,
+                Instr::BrIfI32LtS(params) => self.visit_br_if_i32_lt_s(params)?,
+                Instr::BrIfI32LtU(params) => self.visit_br_if_i32_lt_u(params)?,
+                Instr::BrIfI32GtS(params) => self.visit_br_if_i32_gt_s(params)?,
+                Instr::BrIfI32GtU(params) => self.visit_br_if_i32_gt_u(params)?,
+                Instr::BrIfI32LeS(params) => self.visit_br_if_i32_le_s(params)?,
+                Instr::BrIfI32LeU(params) => self.visit_br_if_i32_le_u(params)?,
+                Instr::BrIfI32GeS(params) => self.visit_br_if_i32_ge_s(params)?,
+                Instr::BrIfI32GeU(params) => self.visit_br_if_i32_ge_u(params)?,
+                Instr::BrIfI64Eq(params) => self.visit_br_if_i64_eq(params)?,
+                Instr::BrIfI64Ne(params) => self.visit_br_if_i64_ne(params)?,
+                Instr::BrIfI64LtS(params) => self.visit_br_if_i64_lt_s(params)?,
+                Instr::BrIfI64LtU(params) => self.visit_br_if_i64_lt_u(params)?,
+                Instr::BrIfI64GtS(params) => self.visit_br_if_i64_gt_s(params)?,
+                Instr::BrIfI64GtU(params) => self.visit_br_if_i64_gt_u(params)?,
+                Instr::BrIfI64LeS(params) => self.visit_br_if_i64_le_s(params)?,
+                Instr::BrIfI64LeU(params) => self.visit_br_if_i64_le_u(params)?,
+                Instr::BrIfI64GeS(params) => self.visit_br_if_i64_ge_s(params)?,
+                Instr::BrIfI64GeU(params) => self.visit_br_if_i64_ge_u(params)?,
                 Instr::I32Extend8S => self.visit_i32_extend8_s(),
                 Instr::I32Extend16S => self.visit_i32_extend16_s(),
                 Instr::I64Extend8S => self.visit_i64_extend8_s(),
@@ -461,6 +844,10 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         load_extend: WasmLoadOp,
     ) -> Result<(), TrapCode> {
         self.sp.try_eval_top(|address| {
+            if self.cache.default_memory_is_shared(self.ctx) {
+                let memory = self.cache.default_memory_ptr(self.ctx);
+                return shared_load_extend(memory, address, offset.into_inner(), load_extend);
+            }
             let memory = self.cache.default_memory_bytes(self.ctx);
             let value = load_extend(memory, address, offset.into_inner())?;
             Ok(value)
@@ -486,6 +873,14 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         len: u32,
     ) -> Result<(), TrapCode> {
         let (address, value) = self.sp.pop2();
+        if self.cache.default_memory_is_shared(self.ctx) {
+            let memory = self.cache.default_memory_ptr(self.ctx);
+            let (address, buffer) =
+                shared_store_wrap(memory, address, offset.into_inner(), value, store_wrap, len)?;
+            self.tracer
+                .memory_change(address as u32, len, &buffer[..len as usize]);
+            return self.try_next_instr();
+        }
         let memory = self.cache.default_memory_bytes(self.ctx);
         store_wrap(memory, address, offset.into_inner(), value)?;
         self.ip.offset(0);
@@ -494,6 +889,137 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         self.try_next_instr()
     }
 
+    /// Executes a generic Wasm `atomic.load[N]_u` operation.
+    ///
+    /// # Note
+    ///
+    /// This can be used to emulate the following Wasm operands:
+    ///
+    /// - `{i32, i64}.atomic.load`
+    /// - `{i32, i64}.atomic.load8_u`
+    /// - `{i32, i64}.atomic.load16_u`
+    /// - `i64.atomic.load32_u`
+    #[inline(always)]
+    fn execute_atomic_load<T: AtomicWord>(&mut self, offset: Offset) -> Result<(), TrapCode> {
+        self.sp.try_eval_top(|address| {
+            let memory = self.cache.default_memory_ptr(self.ctx);
+            atomics::load::<T>(memory, address, offset.into_inner())
+        })?;
+        self.try_next_instr()
+    }
+
+    /// Executes a generic Wasm `atomic.store[N]` operation.
+    ///
+    /// # Note
+    ///
+    /// This can be used to emulate the following Wasm operands:
+    ///
+    /// - `{i32, i64}.atomic.store`
+    /// - `{i32, i64}.atomic.store8`
+    /// - `{i32, i64}.atomic.store16`
+    /// - `i64.atomic.store32`
+    #[inline(always)]
+    fn execute_atomic_store<T: AtomicWord>(&mut self, offset: Offset) -> Result<(), TrapCode> {
+        let (address, value) = self.sp.pop2();
+        let memory = self.cache.default_memory_ptr(self.ctx);
+        let address = atomics::store::<T>(memory, address, offset.into_inner(), value)?;
+        self.trace_atomic_change::<T>(memory, address);
+        self.try_next_instr()
+    }
+
+    /// Executes a generic Wasm `atomic.rmw[N].<op>[_u]` operation.
+    ///
+    /// # Note
+    ///
+    /// This can be used to emulate all Wasm atomic read-modify-write operands
+    /// except for `cmpxchg` which is handled by [`Executor::execute_atomic_cmpxchg`].
+    #[inline(always)]
+    fn execute_atomic_rmw<T: AtomicWord>(
+        &mut self,
+        offset: Offset,
+        op: AtomicRmwOp,
+    ) -> Result<(), TrapCode> {
+        let (address, value) = self.sp.pop2();
+        let memory = self.cache.default_memory_ptr(self.ctx);
+        let (address, previous) =
+            atomics::rmw::<T>(memory, address, offset.into_inner(), op, value)?;
+        self.trace_atomic_change::<T>(memory, address);
+        self.sp.push(previous);
+        self.try_next_instr()
+    }
+
+    /// Executes a generic Wasm `atomic.rmw[N].cmpxchg[_u]` operation.
+    #[inline(always)]
+    fn execute_atomic_cmpxchg<T: AtomicWord>(&mut self, offset: Offset) -> Result<(), TrapCode> {
+        let (address, expected, replacement) = self.sp.pop3();
+        let memory = self.cache.default_memory_ptr(self.ctx);
+        let (address, previous) =
+            atomics::cmpxchg::<T>(memory, address, offset.into_inner(), expected, replacement)?;
+        self.trace_atomic_change::<T>(memory, address);
+        self.sp.push(previous);
+        self.try_next_instr()
+    }
+
+    /// Records the bytes of `T` at the effective `address` of an atomic access in the [`Tracer`].
+    fn trace_atomic_change<T: AtomicWord>(&mut self, memory: NonNull<[u8]>, address: usize) {
+        let mut buffer = [0x00_u8; 8];
+        let buffer = &mut buffer[..size_of::<T>()];
+        // Safety: the effective address of the atomic access has already been checked.
+        if unsafe { read_raw(memory, address, buffer) }.is_ok() {
+            self.tracer
+                .memory_change(address as u32, buffer.len() as u32, buffer);
+        }
+    }
+
+    /// Executes a generic Wasm `memory.atomic.wait{32,64}` operation.
+    ///
+    /// Returns the [`Waiting`] thread if the execution has to block. In this case the
+    /// function frame is pushed onto the [`CallStack`] so that the execution can be
+    /// resumed after the executing thread has been woken up.
+    ///
+    /// # Errors
+    ///
+    /// - If the effective address is out of bounds or unaligned.
+    /// - If the default linear memory is not shared.
+    /// - If the executing thread has to block but there is no [`ThreadParker`].
+    ///
+    /// [`ThreadParker`]: crate::ThreadParker
+    #[inline(always)]
+    fn execute_atomic_wait<T: AtomicWord>(
+        &mut self,
+        offset: Offset,
+    ) -> Result<Option<Waiting>, TrapCode> {
+        let (address, expected, timeout) = self.sp.pop3();
+        let memory = *self.cache.default_memory(self.ctx);
+        let memory = self.ctx.resolve_memory(&memory);
+        let address =
+            atomics::effective_address::<T>(memory.data_len(), address, offset.into_inner())?;
+        let shared = memory.shared().ok_or(TrapCode::ExpectedSharedMemory)?;
+        // Negative timeouts represent an infinite timeout.
+        let timeout = u64::try_from(i64::from(timeout))
+            .ok()
+            .map(Duration::from_nanos);
+        let waiting = shared.wait(
+            address,
+            T::wrap(expected),
+            timeout,
+            self.ctx.thread_parker(),
+        )?;
+        self.next_instr();
+        match waiting {
+            Some(waiting) => {
+                self.sync_stack_ptr();
+                self.call_stack
+                    .push(FuncFrame::new(self.ip, self.cache.instance()))?;
+                Ok(Some(waiting))
+            }
+            None => {
+                self.sp.push_as(u32::from(WaitResult::Mismatch));
+                Ok(None)
+            }
+        }
+    }
+
     /// Executes an infallible unary `wasmi` instruction.
     #[inline(always)]
     fn execute_unary(&mut self, f: fn(UntypedValue) -> UntypedValue) {
@@ -518,6 +1044,58 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         self.next_instr()
     }
 
+    /// Executes an infallible binary `wasmi` instruction reading both operands from locals.
+    #[inline(always)]
+    fn execute_binary_local_local(
+        &mut self,
+        lhs: LocalDepth,
+        rhs: LocalDepth,
+        f: fn(UntypedValue, UntypedValue) -> UntypedValue,
+    ) {
+        let lhs = self.sp.nth_back(lhs.into_inner());
+        let rhs = self.sp.nth_back(rhs.into_inner());
+        self.sp.push(f(lhs, rhs));
+        self.next_instr()
+    }
+
+    /// Executes an infallible binary `wasmi` instruction reading its `rhs` operand from a local.
+    #[inline(always)]
+    fn execute_binary_local(
+        &mut self,
+        rhs: LocalDepth,
+        f: fn(UntypedValue, UntypedValue) -> UntypedValue,
+    ) {
+        let rhs = self.sp.nth_back(rhs.into_inner());
+        self.sp.eval_top(|lhs| f(lhs, rhs));
+        self.next_instr()
+    }
+
+    /// Executes an infallible binary `wasmi` instruction with an immediate `rhs` operand.
+    #[inline(always)]
+    fn execute_binary_imm(
+        &mut self,
+        rhs: UntypedValue,
+        f: fn(UntypedValue, UntypedValue) -> UntypedValue,
+    ) {
+        self.sp.eval_top(|lhs| f(lhs, rhs));
+        self.next_instr()
+    }
+
+    /// Executes a conditional branch taken if the comparison `f` of the top two values holds.
+    #[inline(always)]
+    fn execute_br_if_compare(
+        &mut self,
+        params: BranchParams,
+        f: fn(UntypedValue, UntypedValue) -> UntypedValue,
+    ) -> Result<(), TrapCode> {
+        let (lhs, rhs) = self.sp.pop2();
+        if bool::from(f(lhs, rhs)) {
+            self.branch_to(params)
+        } else {
+            self.try_next_instr()
+        }
+    }
+
     /// Executes a fallible binary `wasmi` instruction.
     #[inline(always)]
     fn try_execute_binary(
@@ -546,10 +1124,36 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     }
 
     /// Offsets the instruction pointer using the given [`BranchParams`].
+    ///
+    /// # Errors
+    ///
+    /// If the branch is a loop back-edge and the execution has been interrupted.
     #[inline(always)]
-    fn branch_to(&mut self, params: BranchParams) {
+    fn branch_to(&mut self, params: BranchParams) -> Result<(), TrapCode> {
+        let offset = params.offset().into_i32();
+        if offset <= 0 {
+            self.check_epoch()?;
+        }
         self.sp.drop_keep(params.drop_keep());
-        self.ip.offset(params.offset().into_i32() as isize)
+        self.ip.offset(offset as isize);
+        Ok(())
+    }
+
+    /// Traps with [`TrapCode::Interrupted`] if the epoch of the [`Engine`]
+    /// reached the deadline of the [`Store`].
+    ///
+    /// Does nothing if epoch based interruption is disabled.
+    ///
+    /// [`Engine`]: crate::Engine
+    /// [`Store`]: crate::Store
+    #[inline(always)]
+    fn check_epoch(&self) -> Result<(), TrapCode> {
+        if let Some(deadline) = self.epoch_deadline {
+            if self.ctx.engine().current_epoch() >= deadline {
+                return Err(TrapCode::Interrupted);
+            }
+        }
+        Ok(())
     }
 
     /// Synchronizes the current stack pointer with the [`ValueStack`].
@@ -570,21 +1174,46 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     /// the function call so that the stack and execution state is synchronized
     /// with the outer structures.
     #[inline(always)]
-    fn call_func(&mut self, func: &Func, kind: CallKind, func_index: u32) -> Result<CallOutcome, TrapCode> {
+    fn call_func(&mut self, func: &Func, kind: CallKind) -> Result<CallOutcome, TrapCode> {
+        self.check_epoch()?;
+        if matches!(kind, CallKind::Nested) {
+            // The callee considers all exception handlers of the caller to be live
+            // so we have to drop the ones the caller has already branched out of.
+            let depth = self.call_stack.len();
+            self.call_stack.exceptions_mut().prune(depth, self.ip);
+        }
         self.next_instr();
         self.sync_stack_ptr();
-        if matches!(kind, CallKind::Nested) {
-            self.call_stack
-                .push(FuncFrame::new(self.ip, self.cache.instance()))?;
+        match kind {
+            CallKind::Nested => {
+                self.call_stack
+                    .push(FuncFrame::new(self.ip, self.cache.instance()))?;
+            }
+            CallKind::Tail => {
+                // The callee replaces the current function frame and
+                // therefore must not see its exception handlers.
+                let depth = self.call_stack.len();
+                self.call_stack.exceptions_mut().drop_frames(depth);
+            }
         }
         match self.ctx.resolve_func(func) {
             FuncEntity::Wasm(wasm_func) => {
+                if let Some(profiler) = self.profiler.as_mut() {
+                    let fuel_consumed = self.ctx.fuel().fuel_consumed();
+                    if matches!(kind, CallKind::Tail) {
+                        profiler.leave(1, fuel_consumed);
+                    }
+                    profiler.enter_wasm(wasm_func.func_body(), self.code_map, fuel_consumed);
+                }
                 let header = self.code_map.header(wasm_func.func_body());
+                let fn_name = self.code_map.name(wasm_func.func_body()).unwrap_or_default();
+                // Functions are traced by their store wide index just like in `Engine::execute_func`.
+                let func_index = self.ctx.unwrap_stored(func.as_inner()).into_usize() as u32;
                 self.tracer.function_call(
                     func_index,
                     header.max_stack_height(),
                     header.len_locals(),
-                    String::new(),
+                    fn_name.into(),
                 );
                 self.value_stack.prepare_wasm_call(header)?;
                 self.sp = self.value_stack.stack_ptr();
@@ -592,7 +1221,14 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
                 self.ip = self.code_map.instr_ptr(header.iref());
                 Ok(CallOutcome::Continue)
             }
-            FuncEntity::Host(_host_func) => {
+            FuncEntity::Host(host_func) => {
+                if let Some(profiler) = self.profiler.as_mut() {
+                    let fuel_consumed = self.ctx.fuel().fuel_consumed();
+                    if matches!(kind, CallKind::Tail) {
+                        profiler.leave(1, fuel_consumed);
+                    }
+                    profiler.call_host(host_func.name(), fuel_consumed);
+                }
                 self.cache.reset();
                 Ok(CallOutcome::Call {
                     host_func: *func,
@@ -602,14 +1238,63 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         }
     }
 
+    /// Pauses the execution before `instr` if the installed [`Debugger`] requests it.
+    #[cold]
+    #[inline(never)]
+    fn debug_hook(&mut self, instr: Instruction, meta: &InstrMeta) {
+        let Some(debugger) = self.debugger.as_mut() else {
+            return;
+        };
+        let func_pc = self.ip.pc();
+        let source_pcs = meta.source_pc_range().into_inner();
+        if !debugger.may_pause(func_pc, source_pcs) {
+            return;
+        }
+        let Some(func_body) = self.code_map.func_body_at(self.ip) else {
+            return;
+        };
+        let Some(func_index) = self.code_map.index(func_body) else {
+            return;
+        };
+        let Some(reason) = debugger.pause_reason(func_index, func_pc, source_pcs) else {
+            return;
+        };
+        let header = self.code_map.header(func_body);
+        let state = PausedState {
+            reason,
+            func_index,
+            source_pc: meta.source_pc(),
+            instr,
+            call_depth: self.call_stack.len(),
+            len_locals: header.len_params() + header.len_locals(),
+            len_operands: meta.stack_height() as usize,
+            instance: *self.cache.instance(),
+        };
+        let values = self.value_stack.dump_stack(self.sp);
+        debugger.pause(state, self.ctx, &values);
+    }
+
+    /// Records an event with the installed [`Profiler`] if any.
+    ///
+    /// The closure receives the [`Profiler`], the [`CodeMap`] and the amount of consumed fuel.
+    #[inline(always)]
+    fn profile(&mut self, f: impl FnOnce(&mut Profiler, &CodeMap, u64)) {
+        if let Some(profiler) = self.profiler.as_mut() {
+            f(profiler, self.code_map, self.ctx.fuel().fuel_consumed())
+        }
+    }
+
     /// Returns to the caller.
     ///
     /// This also modifies the stack as the caller would expect it
     /// and synchronizes the execution state with the outer structures.
     #[inline(always)]
     fn ret(&mut self, drop_keep: DropKeep) -> ReturnOutcome {
+        self.profile(|profiler, _, fuel_consumed| profiler.leave(1, fuel_consumed));
         self.sp.drop_keep(drop_keep);
         self.sync_stack_ptr();
+        let depth = self.call_stack.len();
+        self.call_stack.exceptions_mut().drop_frames(depth);
         match self.call_stack.pop() {
             Some(caller) => {
                 self.ip = caller.ip();
@@ -620,6 +1305,57 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         }
     }
 
+    /// Throws the `exception` at the current instruction pointer.
+    ///
+    /// This unwinds the call stack and value stack to the innermost live exception
+    /// handler and continues execution at its `catch` clauses.
+    /// Skips the `skip` innermost live exception handlers of the current function frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TrapCode::UncaughtException`] if there is no live exception handler.
+    /// In this case the `exception` is stored as pending exception of the [`CallStack`].
+    fn throw(&mut self, exception: UntypedException, skip: u32) -> Result<(), TrapCode> {
+        let depth = self.call_stack.len();
+        let handler = match self
+            .call_stack
+            .exceptions_mut()
+            .pop_handler(depth, self.ip, skip)
+        {
+            Some(handler) => handler,
+            None => {
+                self.call_stack.exceptions_mut().set_pending(exception);
+                return Err(TrapCode::UncaughtException);
+            }
+        };
+        if handler.depth() < depth {
+            self.profile(|profiler, _, fuel_consumed| {
+                profiler.leave(depth - handler.depth(), fuel_consumed)
+            });
+            let frame = self.call_stack.unwind_to(handler.depth());
+            self.call_stack
+                .exceptions_mut()
+                .drop_frames(handler.depth() + 1);
+            self.cache.update_instance(frame.instance());
+        }
+        self.sp = self.value_stack.stack_ptr_at(handler.height());
+        self.sync_stack_ptr();
+        self.ip = handler.landing();
+        self.call_stack
+            .exceptions_mut()
+            .push_caught(CaughtException::new(&handler, exception));
+        Ok(())
+    }
+
+    /// Returns the [`Tag`] at `index` of the currently used instance.
+    #[inline]
+    fn resolve_tag(&self, index: TagIdx) -> Tag {
+        self.ctx
+            .resolve_instance(self.cache.instance())
+            .get_tag(index.into_inner())
+            .unwrap_or_else(|| panic!("missing tag at index: {index:?}"))
+    }
+
     /// Consume an amount of fuel specified by `delta` if `exec` succeeds.
     ///
     /// # Note
@@ -636,7 +1372,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     #[inline(always)]
     fn consume_fuel_with<T, E>(
         &mut self,
-        delta: impl FnOnce(&FuelCosts) -> u64,
+        delta: impl FnOnce(&ResolvedFuelCosts) -> u64,
         exec: impl FnOnce(&mut Self) -> Result<T, E>,
     ) -> Result<T, E>
         where
@@ -660,7 +1396,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     fn consume_fuel_with_mode<T, E>(
         &mut self,
         mode: FuelConsumptionMode,
-        delta: impl FnOnce(&FuelCosts) -> u64,
+        delta: impl FnOnce(&ResolvedFuelCosts) -> u64,
         exec: impl FnOnce(&mut Self) -> Result<T, E>,
     ) -> Result<T, E>
         where
@@ -720,12 +1456,12 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         exec(self)
     }
 
-    /// Returns a shared reference to the [`FuelCosts`] of the [`Engine`].
+    /// Returns a shared reference to the [`ResolvedFuelCosts`] of the [`Engine`].
     ///
     /// [`Engine`]: crate::Engine
     #[inline]
-    fn fuel_costs(&self) -> &FuelCosts {
-        self.ctx.engine().config().fuel_costs()
+    fn fuel_costs(&self) -> &ResolvedFuelCosts {
+        self.ctx.engine().resolved_fuel_costs()
     }
 
     /// Returns the [`FuelConsumptionMode`] of the [`Engine`].
@@ -744,7 +1480,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         kind: CallKind,
     ) -> Result<CallOutcome, TrapCode> {
         let callee = self.cache.get_func(self.ctx, func_index);
-        self.call_func(&callee, kind, func_index.into_inner())
+        self.call_func(&callee, kind)
     }
 
     /// Executes a `call_indirect` or `return_call_indirect` instruction.
@@ -775,7 +1511,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         if actual_signature != expected_signature {
             return Err(TrapCode::BadSignature).map_err(Into::into);
         }
-        self.call_func(func, kind, func_index)
+        self.call_func(func, kind)
     }
 }
 
@@ -795,27 +1531,27 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     }
 
     #[inline(always)]
-    fn visit_br(&mut self, params: BranchParams) {
+    fn visit_br(&mut self, params: BranchParams) -> Result<(), TrapCode> {
         self.branch_to(params)
     }
 
     #[inline(always)]
-    fn visit_br_if_eqz(&mut self, params: BranchParams) {
+    fn visit_br_if_eqz(&mut self, params: BranchParams) -> Result<(), TrapCode> {
         let condition = self.sp.pop_as();
         if condition {
-            self.next_instr()
+            self.try_next_instr()
         } else {
             self.branch_to(params)
         }
     }
 
     #[inline(always)]
-    fn visit_br_if_nez(&mut self, params: BranchParams) {
+    fn visit_br_if_nez(&mut self, params: BranchParams) -> Result<(), TrapCode> {
         let condition = self.sp.pop_as();
         if condition {
             self.branch_to(params)
         } else {
-            self.next_instr()
+            self.try_next_instr()
         }
     }
 
@@ -867,6 +1603,13 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         self.next_instr()
     }
 
+    #[inline(always)]
+    fn visit_local_copy(&mut self, result: LocalDepth, input: LocalDepth) {
+        let value = self.sp.nth_back(input.into_inner());
+        self.sp.set_nth_back(result.into_inner(), value);
+        self.next_instr()
+    }
+
     #[inline(always)]
     fn visit_global_get(&mut self, global_index: GlobalIdx) {
         let global_value = self.cache.get_global(self.ctx, global_index);
@@ -906,7 +1649,7 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     #[inline(always)]
     fn visit_call(&mut self, func_index: FuncIdx) -> Result<CallOutcome, TrapCode> {
         let callee = self.cache.get_func(self.ctx, func_index);
-        self.call_func(&callee, CallKind::Nested, func_index.into_inner())
+        self.call_func(&callee, CallKind::Nested)
     }
 
     #[inline(always)]
@@ -919,6 +1662,66 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         self.execute_call_indirect(table, func_index, func_type, CallKind::Nested)
     }
 
+    #[inline(always)]
+    fn visit_try(&mut self, landing: BranchOffset, end: BranchOffset, len_params: u32) {
+        let height = self.value_stack.stack_len(self.sp) - len_params as usize;
+        let handler = TryHandler::new(self.call_stack.len(), self.ip, landing, end, height);
+        self.call_stack.exceptions_mut().push_handler(handler);
+        self.next_instr()
+    }
+
+    #[inline(always)]
+    fn visit_catch_tag(&mut self, tag: TagIdx, next: BranchOffset) {
+        let tag = self.resolve_tag(tag);
+        let exception = self.call_stack.exceptions().last_caught().exception();
+        if exception.tag() != tag {
+            self.ip.offset(next.into_i32() as isize);
+            return;
+        }
+        for value in exception.values() {
+            self.sp.push(*value);
+        }
+        self.next_instr()
+    }
+
+    #[inline(always)]
+    fn visit_throw(&mut self, tag: TagIdx) -> Result<(), TrapCode> {
+        let tag = self.resolve_tag(tag);
+        let len_values = self
+            .ctx
+            .resolve_func_type_with(self.ctx.resolve_tag(&tag).ty_dedup(), |func_type| {
+                func_type.params().len()
+            });
+        let values = (0..len_values)
+            .rev()
+            .map(|n| self.sp.nth_back(n + 1))
+            .collect();
+        self.throw(UntypedException::new(tag, values), 0)
+    }
+
+    #[inline(always)]
+    fn visit_rethrow(&mut self, landing: BranchOffset) -> Result<(), TrapCode> {
+        let mut landing_ip = self.ip;
+        landing_ip.offset(landing.into_i32() as isize);
+        let exception = self
+            .call_stack
+            .exceptions()
+            .get_caught(self.call_stack.len(), landing_ip)
+            .exception()
+            .clone();
+        self.throw(exception, 0)
+    }
+
+    #[inline(always)]
+    fn visit_delegate(&mut self, skip: u32) -> Result<(), TrapCode> {
+        let exception = self
+            .call_stack
+            .exceptions_mut()
+            .pop_caught()
+            .into_exception();
+        self.throw(exception, skip)
+    }
+
     #[inline(always)]
     fn visit_const(&mut self, bytes: UntypedValue) {
         self.sp.push(bytes);
@@ -966,16 +1769,21 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let result = self.consume_fuel_with(
             |costs| {
                 let delta_in_bytes = delta.to_bytes().unwrap_or(0) as u64;
-                costs.fuel_for_bytes(delta_in_bytes)
+                costs.fuel_for_instr_bytes(Instruction::MemoryGrow, delta_in_bytes)
             },
             |this| {
                 let memory = this.cache.default_memory(this.ctx);
                 let new_pages = this
                     .ctx
                     .resolve_memory_mut(memory)
-                    .grow(delta)
+                    .grow(delta, &mut this.limiter)
                     .map(u32::from)
-                    .map_err(|_| EntityGrowError::InvalidGrow)?;
+                    .map_err(|error| match error {
+                        MemoryError::ResourceLimiterDeniedAllocation => {
+                            EntityGrowError::TrapCode(TrapCode::GrowthOperationLimited)
+                        }
+                        _ => EntityGrowError::InvalidGrow,
+                    })?;
                 // The `memory.grow` operation might have invalidated the cached
                 // linear memory so we need to reset it in order for the cache to
                 // reload in case it is used again.
@@ -992,6 +1800,38 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         self.try_next_instr()
     }
 
+    #[inline(always)]
+    fn visit_memory_atomic_notify(&mut self, offset: Offset) -> Result<(), TrapCode> {
+        let (address, count) = self.sp.pop2();
+        let memory = *self.cache.default_memory(self.ctx);
+        let memory = self.ctx.resolve_memory(&memory);
+        let address =
+            atomics::effective_address::<u32>(memory.data_len(), address, offset.into_inner())?;
+        // Non-shared linear memories cannot have waiters.
+        let woken = match memory.shared() {
+            Some(shared) => shared.notify(address, u32::from(count)),
+            None => 0,
+        };
+        self.sp.push_as(woken);
+        self.try_next_instr()
+    }
+
+    #[inline(always)]
+    fn visit_memory_atomic_wait32(&mut self, offset: Offset) -> Result<Option<Waiting>, TrapCode> {
+        self.execute_atomic_wait::<u32>(offset)
+    }
+
+    #[inline(always)]
+    fn visit_memory_atomic_wait64(&mut self, offset: Offset) -> Result<Option<Waiting>, TrapCode> {
+        self.execute_atomic_wait::<u64>(offset)
+    }
+
+    #[inline(always)]
+    fn visit_atomic_fence(&mut self) {
+        atomic::fence(atomic::Ordering::SeqCst);
+        self.next_instr()
+    }
+
     #[inline(always)]
     fn visit_memory_fill(&mut self) -> Result<(), TrapCode> {
         // The `n`, `val` and `d` variable bindings are extracted from the Wasm specification.
@@ -1000,8 +1840,17 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let offset = i32::from(d) as usize;
         let byte = u8::from(val);
         self.consume_fuel_with(
-            |costs| costs.fuel_for_bytes(n as u64),
+            |costs| costs.fuel_for_instr_bytes(Instruction::MemoryFill, n as u64),
             |this| {
+                if this.cache.default_memory_is_shared(this.ctx) {
+                    let memory = this.cache.default_memory_ptr(this.ctx);
+                    // Safety: the bytes of shared linear memories are valid for writes.
+                    unsafe { fill_raw(memory, offset, byte, n) }
+                        .map_err(|_| TrapCode::MemoryOutOfBounds)?;
+                    this.tracer
+                        .memory_change(offset as u32, n as u32, &vec![byte; n]);
+                    return Ok(());
+                }
                 let memory = this
                     .cache
                     .default_memory_bytes(this.ctx)
@@ -1024,8 +1873,21 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let src_offset = i32::from(s) as usize;
         let dst_offset = i32::from(d) as usize;
         self.consume_fuel_with(
-            |costs| costs.fuel_for_bytes(n as u64),
+            |costs| costs.fuel_for_instr_bytes(Instruction::MemoryCopy, n as u64),
             |this| {
+                if this.cache.default_memory_is_shared(this.ctx) {
+                    let memory = this.cache.default_memory_ptr(this.ctx);
+                    let mut copied = vec![0x00_u8; n];
+                    // Safety: the bytes of shared linear memories are valid for reads and writes.
+                    unsafe {
+                        copy_raw(memory, src_offset, dst_offset, n)
+                            .and_then(|_| read_raw(memory, dst_offset, &mut copied))
+                    }
+                    .map_err(|_| TrapCode::MemoryOutOfBounds)?;
+                    this.tracer
+                        .memory_change(dst_offset as u32, n as u32, &copied);
+                    return Ok(());
+                }
                 let data = this.cache.default_memory_bytes(this.ctx);
                 // These accesses just perform the bounds checks required by the Wasm spec.
                 data.get(src_offset..)
@@ -1050,11 +1912,25 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let src_offset = i32::from(s) as usize;
         let dst_offset = i32::from(d) as usize;
         self.consume_fuel_with(
-            |costs| costs.fuel_for_bytes(n as u64),
+            |costs| costs.fuel_for_instr_bytes(Instruction::MemoryInit(segment), n as u64),
             |this| {
+                let is_shared = this.cache.default_memory_is_shared(this.ctx);
                 let (memory, data) = this
                     .cache
                     .get_default_memory_and_data_segment(this.ctx, segment);
+                if is_shared {
+                    let data = data
+                        .get(src_offset..)
+                        .and_then(|data| data.get(..n))
+                        .ok_or(TrapCode::MemoryOutOfBounds)?;
+                    // Safety: the bytes of shared linear memories are valid for writes.
+                    unsafe { write_raw(memory, dst_offset, data) }
+                        .map_err(|_| TrapCode::MemoryOutOfBounds)?;
+                    this.tracer.global_memory(dst_offset as u32, n as u32, data);
+                    return Ok(());
+                }
+                // Safety: the bytes of non-shared linear memories are exclusively owned by the store.
+                let memory = unsafe { &mut *memory.as_ptr() };
                 let memory = memory
                     .get_mut(dst_offset..)
                     .and_then(|memory| memory.get_mut(..n))
@@ -1093,13 +1969,23 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let (init, delta) = self.sp.pop2();
         let delta: u32 = delta.into();
         let result = self.consume_fuel_with(
-            |costs| costs.fuel_for_elements(u64::from(delta)),
+            |costs| {
+                costs.fuel_for_instr_elements(
+                    Instruction::TableGrow { table: table_index },
+                    u64::from(delta),
+                )
+            },
             |this| {
                 let table = this.cache.get_table(this.ctx, table_index);
                 this.ctx
                     .resolve_table_mut(&table)
-                    .grow_untyped(delta, init)
-                    .map_err(|_| EntityGrowError::InvalidGrow)
+                    .grow_untyped(delta, init, &mut this.limiter)
+                    .map_err(|error| match error {
+                        TableError::ResourceLimiterDeniedAllocation => {
+                            EntityGrowError::TrapCode(TrapCode::GrowthOperationLimited)
+                        }
+                        _ => EntityGrowError::InvalidGrow,
+                    })
             },
         );
         let result = match result {
@@ -1118,7 +2004,12 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let dst: u32 = i.into();
         let len: u32 = n.into();
         self.consume_fuel_with(
-            |costs| costs.fuel_for_elements(u64::from(len)),
+            |costs| {
+                costs.fuel_for_instr_elements(
+                    Instruction::TableFill { table: table_index },
+                    u64::from(len),
+                )
+            },
             |this| {
                 let table = this.cache.get_table(this.ctx, table_index);
                 this.ctx
@@ -1163,7 +2054,9 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let src_index = u32::from(s);
         let dst_index = u32::from(d);
         self.consume_fuel_with(
-            |costs| costs.fuel_for_elements(u64::from(len)),
+            |costs| {
+                costs.fuel_for_instr_elements(Instruction::TableCopy { dst, src }, u64::from(len))
+            },
             |this| {
                 // Query both tables and check if they are the same:
                 let dst = this.cache.get_table(this.ctx, dst);
@@ -1195,7 +2088,10 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
         let src_index = u32::from(s);
         let dst_index = u32::from(d);
         self.consume_fuel_with(
-            |costs| costs.fuel_for_elements(u64::from(len)),
+            |costs| {
+                costs
+                    .fuel_for_instr_elements(Instruction::TableInit { table, elem }, u64::from(len))
+            },
             |this| {
                 let (instance, table, element) = this
                     .cache
@@ -1290,6 +2186,335 @@ impl<'ctx, 'engine> Executor<'ctx, 'engine> {
     }
 }
 
+macro_rules! impl_visit_atomic_load {
+    ( $( fn $visit_ident:ident($word:ty); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, offset: Offset) -> Result<(), TrapCode> {
+                self.execute_atomic_load::<$word>(offset)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_atomic_load! {
+        fn visit_i32_atomic_load(u32);
+        fn visit_i64_atomic_load(u64);
+        fn visit_i32_atomic_load8_u(u8);
+        fn visit_i32_atomic_load16_u(u16);
+        fn visit_i64_atomic_load8_u(u8);
+        fn visit_i64_atomic_load16_u(u16);
+        fn visit_i64_atomic_load32_u(u32);
+    }
+}
+
+macro_rules! impl_visit_atomic_store {
+    ( $( fn $visit_ident:ident($word:ty); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, offset: Offset) -> Result<(), TrapCode> {
+                self.execute_atomic_store::<$word>(offset)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_atomic_store! {
+        fn visit_i32_atomic_store(u32);
+        fn visit_i64_atomic_store(u64);
+        fn visit_i32_atomic_store8(u8);
+        fn visit_i32_atomic_store16(u16);
+        fn visit_i64_atomic_store8(u8);
+        fn visit_i64_atomic_store16(u16);
+        fn visit_i64_atomic_store32(u32);
+    }
+}
+
+macro_rules! impl_visit_atomic_rmw {
+    ( $( fn $visit_ident:ident($word:ty, $op:ident); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, offset: Offset) -> Result<(), TrapCode> {
+                self.execute_atomic_rmw::<$word>(offset, AtomicRmwOp::$op)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_atomic_rmw! {
+        fn visit_i32_atomic_rmw_add(u32, Add);
+        fn visit_i64_atomic_rmw_add(u64, Add);
+        fn visit_i32_atomic_rmw8_add_u(u8, Add);
+        fn visit_i32_atomic_rmw16_add_u(u16, Add);
+        fn visit_i64_atomic_rmw8_add_u(u8, Add);
+        fn visit_i64_atomic_rmw16_add_u(u16, Add);
+        fn visit_i64_atomic_rmw32_add_u(u32, Add);
+        fn visit_i32_atomic_rmw_sub(u32, Sub);
+        fn visit_i64_atomic_rmw_sub(u64, Sub);
+        fn visit_i32_atomic_rmw8_sub_u(u8, Sub);
+        fn visit_i32_atomic_rmw16_sub_u(u16, Sub);
+        fn visit_i64_atomic_rmw8_sub_u(u8, Sub);
+        fn visit_i64_atomic_rmw16_sub_u(u16, Sub);
+        fn visit_i64_atomic_rmw32_sub_u(u32, Sub);
+        fn visit_i32_atomic_rmw_and(u32, And);
+        fn visit_i64_atomic_rmw_and(u64, And);
+        fn visit_i32_atomic_rmw8_and_u(u8, And);
+        fn visit_i32_atomic_rmw16_and_u(u16, And);
+        fn visit_i64_atomic_rmw8_and_u(u8, And);
+        fn visit_i64_atomic_rmw16_and_u(u16, And);
+        fn visit_i64_atomic_rmw32_and_u(u32, And);
+        fn visit_i32_atomic_rmw_or(u32, Or);
+        fn visit_i64_atomic_rmw_or(u64, Or);
+        fn visit_i32_atomic_rmw8_or_u(u8, Or);
+        fn visit_i32_atomic_rmw16_or_u(u16, Or);
+        fn visit_i64_atomic_rmw8_or_u(u8, Or);
+        fn visit_i64_atomic_rmw16_or_u(u16, Or);
+        fn visit_i64_atomic_rmw32_or_u(u32, Or);
+        fn visit_i32_atomic_rmw_xor(u32, Xor);
+        fn visit_i64_atomic_rmw_xor(u64, Xor);
+        fn visit_i32_atomic_rmw8_xor_u(u8, Xor);
+        fn visit_i32_atomic_rmw16_xor_u(u16, Xor);
+        fn visit_i64_atomic_rmw8_xor_u(u8, Xor);
+        fn visit_i64_atomic_rmw16_xor_u(u16, Xor);
+        fn visit_i64_atomic_rmw32_xor_u(u32, Xor);
+        fn visit_i32_atomic_rmw_xchg(u32, Xchg);
+        fn visit_i64_atomic_rmw_xchg(u64, Xchg);
+        fn visit_i32_atomic_rmw8_xchg_u(u8, Xchg);
+        fn visit_i32_atomic_rmw16_xchg_u(u16, Xchg);
+        fn visit_i64_atomic_rmw8_xchg_u(u8, Xchg);
+        fn visit_i64_atomic_rmw16_xchg_u(u16, Xchg);
+        fn visit_i64_atomic_rmw32_xchg_u(u32, Xchg);
+    }
+}
+
+macro_rules! impl_visit_atomic_cmpxchg {
+    ( $( fn $visit_ident:ident($word:ty); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, offset: Offset) -> Result<(), TrapCode> {
+                self.execute_atomic_cmpxchg::<$word>(offset)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_atomic_cmpxchg! {
+        fn visit_i32_atomic_rmw_cmpxchg(u32);
+        fn visit_i64_atomic_rmw_cmpxchg(u64);
+        fn visit_i32_atomic_rmw8_cmpxchg_u(u8);
+        fn visit_i32_atomic_rmw16_cmpxchg_u(u16);
+        fn visit_i64_atomic_rmw8_cmpxchg_u(u8);
+        fn visit_i64_atomic_rmw16_cmpxchg_u(u16);
+        fn visit_i64_atomic_rmw32_cmpxchg_u(u32);
+    }
+}
+
+macro_rules! impl_visit_binary_local_local {
+    ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, lhs: LocalDepth, rhs: LocalDepth) {
+                self.execute_binary_local_local(lhs, rhs, UntypedValue::$untyped_ident)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_binary_local_local! {
+        fn visit_i32_eq_local_local(i32_eq);
+        fn visit_i32_ne_local_local(i32_ne);
+        fn visit_i32_lt_s_local_local(i32_lt_s);
+        fn visit_i32_lt_u_local_local(i32_lt_u);
+        fn visit_i32_gt_s_local_local(i32_gt_s);
+        fn visit_i32_gt_u_local_local(i32_gt_u);
+        fn visit_i32_le_s_local_local(i32_le_s);
+        fn visit_i32_le_u_local_local(i32_le_u);
+        fn visit_i32_ge_s_local_local(i32_ge_s);
+        fn visit_i32_ge_u_local_local(i32_ge_u);
+        fn visit_i32_add_local_local(i32_add);
+        fn visit_i32_sub_local_local(i32_sub);
+        fn visit_i32_mul_local_local(i32_mul);
+        fn visit_i32_and_local_local(i32_and);
+        fn visit_i32_or_local_local(i32_or);
+        fn visit_i32_xor_local_local(i32_xor);
+        fn visit_i32_shl_local_local(i32_shl);
+        fn visit_i32_shr_s_local_local(i32_shr_s);
+        fn visit_i32_shr_u_local_local(i32_shr_u);
+        fn visit_i32_rotl_local_local(i32_rotl);
+        fn visit_i32_rotr_local_local(i32_rotr);
+        fn visit_i64_eq_local_local(i64_eq);
+        fn visit_i64_ne_local_local(i64_ne);
+        fn visit_i64_lt_s_local_local(i64_lt_s);
+        fn visit_i64_lt_u_local_local(i64_lt_u);
+        fn visit_i64_gt_s_local_local(i64_gt_s);
+        fn visit_i64_gt_u_local_local(i64_gt_u);
+        fn visit_i64_le_s_local_local(i64_le_s);
+        fn visit_i64_le_u_local_local(i64_le_u);
+        fn visit_i64_ge_s_local_local(i64_ge_s);
+        fn visit_i64_ge_u_local_local(i64_ge_u);
+        fn visit_i64_add_local_local(i64_add);
+        fn visit_i64_sub_local_local(i64_sub);
+        fn visit_i64_mul_local_local(i64_mul);
+        fn visit_i64_and_local_local(i64_and);
+        fn visit_i64_or_local_local(i64_or);
+        fn visit_i64_xor_local_local(i64_xor);
+        fn visit_i64_shl_local_local(i64_shl);
+        fn visit_i64_shr_s_local_local(i64_shr_s);
+        fn visit_i64_shr_u_local_local(i64_shr_u);
+        fn visit_i64_rotl_local_local(i64_rotl);
+        fn visit_i64_rotr_local_local(i64_rotr);
+    }
+}
+
+macro_rules! impl_visit_binary_local {
+    ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, rhs: LocalDepth) {
+                self.execute_binary_local(rhs, UntypedValue::$untyped_ident)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_binary_local! {
+        fn visit_i32_eq_local(i32_eq);
+        fn visit_i32_ne_local(i32_ne);
+        fn visit_i32_lt_s_local(i32_lt_s);
+        fn visit_i32_lt_u_local(i32_lt_u);
+        fn visit_i32_gt_s_local(i32_gt_s);
+        fn visit_i32_gt_u_local(i32_gt_u);
+        fn visit_i32_le_s_local(i32_le_s);
+        fn visit_i32_le_u_local(i32_le_u);
+        fn visit_i32_ge_s_local(i32_ge_s);
+        fn visit_i32_ge_u_local(i32_ge_u);
+        fn visit_i32_add_local(i32_add);
+        fn visit_i32_sub_local(i32_sub);
+        fn visit_i32_mul_local(i32_mul);
+        fn visit_i32_and_local(i32_and);
+        fn visit_i32_or_local(i32_or);
+        fn visit_i32_xor_local(i32_xor);
+        fn visit_i32_shl_local(i32_shl);
+        fn visit_i32_shr_s_local(i32_shr_s);
+        fn visit_i32_shr_u_local(i32_shr_u);
+        fn visit_i32_rotl_local(i32_rotl);
+        fn visit_i32_rotr_local(i32_rotr);
+        fn visit_i64_eq_local(i64_eq);
+        fn visit_i64_ne_local(i64_ne);
+        fn visit_i64_lt_s_local(i64_lt_s);
+        fn visit_i64_lt_u_local(i64_lt_u);
+        fn visit_i64_gt_s_local(i64_gt_s);
+        fn visit_i64_gt_u_local(i64_gt_u);
+        fn visit_i64_le_s_local(i64_le_s);
+        fn visit_i64_le_u_local(i64_le_u);
+        fn visit_i64_ge_s_local(i64_ge_s);
+        fn visit_i64_ge_u_local(i64_ge_u);
+        fn visit_i64_add_local(i64_add);
+        fn visit_i64_sub_local(i64_sub);
+        fn visit_i64_mul_local(i64_mul);
+        fn visit_i64_and_local(i64_and);
+        fn visit_i64_or_local(i64_or);
+        fn visit_i64_xor_local(i64_xor);
+        fn visit_i64_shl_local(i64_shl);
+        fn visit_i64_shr_s_local(i64_shr_s);
+        fn visit_i64_shr_u_local(i64_shr_u);
+        fn visit_i64_rotl_local(i64_rotl);
+        fn visit_i64_rotr_local(i64_rotr);
+    }
+}
+
+macro_rules! impl_visit_binary_imm {
+    ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, rhs: UntypedValue) {
+                self.execute_binary_imm(rhs, UntypedValue::$untyped_ident)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_binary_imm! {
+        fn visit_i32_eq_imm(i32_eq);
+        fn visit_i32_ne_imm(i32_ne);
+        fn visit_i32_lt_s_imm(i32_lt_s);
+        fn visit_i32_lt_u_imm(i32_lt_u);
+        fn visit_i32_gt_s_imm(i32_gt_s);
+        fn visit_i32_gt_u_imm(i32_gt_u);
+        fn visit_i32_le_s_imm(i32_le_s);
+        fn visit_i32_le_u_imm(i32_le_u);
+        fn visit_i32_ge_s_imm(i32_ge_s);
+        fn visit_i32_ge_u_imm(i32_ge_u);
+        fn visit_i32_add_imm(i32_add);
+        fn visit_i32_sub_imm(i32_sub);
+        fn visit_i32_mul_imm(i32_mul);
+        fn visit_i32_and_imm(i32_and);
+        fn visit_i32_or_imm(i32_or);
+        fn visit_i32_xor_imm(i32_xor);
+        fn visit_i32_shl_imm(i32_shl);
+        fn visit_i32_shr_s_imm(i32_shr_s);
+        fn visit_i32_shr_u_imm(i32_shr_u);
+        fn visit_i32_rotl_imm(i32_rotl);
+        fn visit_i32_rotr_imm(i32_rotr);
+        fn visit_i64_eq_imm(i64_eq);
+        fn visit_i64_ne_imm(i64_ne);
+        fn visit_i64_lt_s_imm(i64_lt_s);
+        fn visit_i64_lt_u_imm(i64_lt_u);
+        fn visit_i64_gt_s_imm(i64_gt_s);
+        fn visit_i64_gt_u_imm(i64_gt_u);
+        fn visit_i64_le_s_imm(i64_le_s);
+        fn visit_i64_le_u_imm(i64_le_u);
+        fn visit_i64_ge_s_imm(i64_ge_s);
+        fn visit_i64_ge_u_imm(i64_ge_u);
+        fn visit_i64_add_imm(i64_add);
+        fn visit_i64_sub_imm(i64_sub);
+        fn visit_i64_mul_imm(i64_mul);
+        fn visit_i64_and_imm(i64_and);
+        fn visit_i64_or_imm(i64_or);
+        fn visit_i64_xor_imm(i64_xor);
+        fn visit_i64_shl_imm(i64_shl);
+        fn visit_i64_shr_s_imm(i64_shr_s);
+        fn visit_i64_shr_u_imm(i64_shr_u);
+        fn visit_i64_rotl_imm(i64_rotl);
+        fn visit_i64_rotr_imm(i64_rotr);
+    }
+}
+
+macro_rules! impl_visit_br_if_compare {
+    ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
+        $(
+            #[inline(always)]
+            fn $visit_ident(&mut self, params: BranchParams) -> Result<(), TrapCode> {
+                self.execute_br_if_compare(params, UntypedValue::$untyped_ident)
+            }
+        )*
+    }
+}
+impl<'ctx, 'engine> Executor<'ctx, 'engine> {
+    impl_visit_br_if_compare! {
+        fn visit_br_if_i32_eq(i32_eq);
+        fn visit_br_if_i32_ne(i32_ne);
+        fn visit_br_if_i32_lt_s(i32_lt_s);
+        fn visit_br_if_i32_lt_u(i32_lt_u);
+        fn visit_br_if_i32_gt_s(i32_gt_s);
+        fn visit_br_if_i32_gt_u(i32_gt_u);
+        fn visit_br_if_i32_le_s(i32_le_s);
+        fn visit_br_if_i32_le_u(i32_le_u);
+        fn visit_br_if_i32_ge_s(i32_ge_s);
+        fn visit_br_if_i32_ge_u(i32_ge_u);
+        fn visit_br_if_i64_eq(i64_eq);
+        fn visit_br_if_i64_ne(i64_ne);
+        fn visit_br_if_i64_lt_s(i64_lt_s);
+        fn visit_br_if_i64_lt_u(i64_lt_u);
+        fn visit_br_if_i64_gt_s(i64_gt_s);
+        fn visit_br_if_i64_gt_u(i64_gt_u);
+        fn visit_br_if_i64_le_s(i64_le_s);
+        fn visit_br_if_i64_le_u(i64_le_u);
+        fn visit_br_if_i64_ge_s(i64_ge_s);
+        fn visit_br_if_i64_ge_u(i64_ge_u);
+    }
+}
+
 macro_rules! impl_visit_unary {
     ( $( fn $visit_ident:ident($untyped_ident:ident); )* ) => {
         $(
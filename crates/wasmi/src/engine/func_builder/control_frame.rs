@@ -269,6 +269,171 @@ impl IfControlFrame {
     }
 }
 
+/// A Wasm `try` control flow frame.
+///
+/// # Note
+///
+/// The instructions of a `try` control flow frame are laid out as follows:
+///
+/// ```text
+///         Try { landing, end }
+///         <try body>
+///         Br end
+/// landing:
+///         CatchTag { tag, next } | <catch_all>
+///         <catch body>
+///         Br end
+/// next:
+///         ...
+///         Rethrow { landing }     // only if there is no `catch_all` clause
+/// end:
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct TryControlFrame {
+    /// The type of the [`TryControlFrame`].
+    block_type: BlockType,
+    /// The value stack height upon entering the [`TryControlFrame`].
+    stack_height: u32,
+    /// Label representing the end of the [`TryControlFrame`].
+    end_label: LabelRef,
+    /// Label representing the start of the `catch` clauses of the [`TryControlFrame`].
+    landing_label: LabelRef,
+    /// Label representing the next `catch` clause if the current `catch` clause does not match.
+    ///
+    /// # Note
+    ///
+    /// This is `None` while translating the `try` body and for `catch_all` clauses.
+    next_label: Option<LabelRef>,
+    /// The state of the [`TryControlFrame`] translation.
+    state: TryState,
+    /// Instruction to consume fuel upon entering the basic block if fuel metering is enabled.
+    ///
+    /// # Note
+    ///
+    /// For the `try` body this might be a reference to the consume fuel instruction
+    /// of the parent [`ControlFrame`]. Upon entering a `catch` clause this is updated
+    /// to represent the [`ConsumeFuel`] instruction of the `catch` clause.
+    ///
+    /// [`ConsumeFuel`]: enum.Instruction.html#variant.ConsumeFuel
+    consume_fuel: Option<Instr>,
+}
+
+/// The translation state of a [`TryControlFrame`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TryState {
+    /// The `try` body is translated.
+    Body,
+    /// A `catch` clause is translated.
+    Catch,
+    /// The `catch_all` clause is translated.
+    CatchAll,
+}
+
+impl TryControlFrame {
+    /// Creates a new [`TryControlFrame`].
+    pub fn new(
+        block_type: BlockType,
+        end_label: LabelRef,
+        landing_label: LabelRef,
+        stack_height: u32,
+        consume_fuel: Option<Instr>,
+    ) -> Self {
+        assert_ne!(
+            end_label, landing_label,
+            "end and landing labels must be different"
+        );
+        Self {
+            block_type,
+            stack_height,
+            end_label,
+            landing_label,
+            next_label: None,
+            state: TryState::Body,
+            consume_fuel,
+        }
+    }
+
+    /// Returns the label for the branch destination of the [`TryControlFrame`].
+    ///
+    /// # Note
+    ///
+    /// Branches to [`TryControlFrame`] jump to the end of the frame.
+    pub fn branch_destination(&self) -> LabelRef {
+        self.end_label
+    }
+
+    /// Returns the label to the end of the [`TryControlFrame`].
+    pub fn end_label(&self) -> LabelRef {
+        self.end_label
+    }
+
+    /// Returns the label to the `catch` clauses of the [`TryControlFrame`].
+    pub fn landing_label(&self) -> LabelRef {
+        self.landing_label
+    }
+
+    /// Returns the value stack height upon entering the [`TryControlFrame`].
+    pub fn stack_height(&self) -> u32 {
+        self.stack_height
+    }
+
+    /// Returns the [`BlockType`] of the [`TryControlFrame`].
+    pub fn block_type(&self) -> BlockType {
+        self.block_type
+    }
+
+    /// Returns the [`TryState`] of the [`TryControlFrame`].
+    pub fn state(&self) -> TryState {
+        self.state
+    }
+
+    /// Enters a new `catch` or `catch_all` clause.
+    ///
+    /// Returns the label to the next `catch` clause of the previous `catch` clause if any.
+    ///
+    /// # Panics
+    ///
+    /// If a `catch_all` clause has already been entered.
+    pub fn enter_clause(
+        &mut self,
+        state: TryState,
+        next_label: Option<LabelRef>,
+    ) -> Option<LabelRef> {
+        assert_ne!(
+            self.state,
+            TryState::CatchAll,
+            "tried to enter a clause after the `catch_all` clause"
+        );
+        self.state = state;
+        core::mem::replace(&mut self.next_label, next_label)
+    }
+
+    /// Returns the label to the next `catch` clause of the current `catch` clause if any.
+    pub fn take_next_label(&mut self) -> Option<LabelRef> {
+        self.next_label.take()
+    }
+
+    /// Returns a reference to the [`ConsumeFuel`] instruction of the [`TryControlFrame`] if any.
+    ///
+    /// Returns `None` if fuel metering is disabled.
+    ///
+    /// [`ConsumeFuel`]: enum.Instruction.html#variant.ConsumeFuel
+    pub fn consume_fuel_instr(&self) -> Option<Instr> {
+        self.consume_fuel
+    }
+
+    /// Updates the [`ConsumeFuel`] instruction for when a `catch` clause is entered.
+    ///
+    /// [`ConsumeFuel`]: enum.Instruction.html#variant.ConsumeFuel
+    pub fn update_consume_fuel_instr(&mut self, instr: Instr) {
+        assert!(
+            self.consume_fuel.is_some(),
+            "can only update the consume fuel instruction if it existed before"
+        );
+        self.consume_fuel = Some(instr);
+    }
+}
+
 /// An unreachable control flow frame of any kind.
 #[derive(Debug, Copy, Clone)]
 pub struct UnreachableControlFrame {
@@ -287,6 +452,8 @@ pub enum ControlFrameKind {
     Loop,
     /// An `if` and `else` block control flow frame.
     If,
+    /// A `try` block control flow frame with its `catch` clauses.
+    Try,
 }
 
 impl UnreachableControlFrame {
@@ -315,6 +482,8 @@ pub enum ControlFrame {
     Loop(LoopControlFrame),
     /// If and else control frame.
     If(IfControlFrame),
+    /// Try and catch control frame.
+    Try(TryControlFrame),
     /// An unreachable control frame.
     Unreachable(UnreachableControlFrame),
 }
@@ -337,6 +506,12 @@ impl From<IfControlFrame> for ControlFrame {
     }
 }
 
+impl From<TryControlFrame> for ControlFrame {
+    fn from(frame: TryControlFrame) -> Self {
+        Self::Try(frame)
+    }
+}
+
 impl From<UnreachableControlFrame> for ControlFrame {
     fn from(frame: UnreachableControlFrame) -> Self {
         Self::Unreachable(frame)
@@ -350,6 +525,7 @@ impl ControlFrame {
             ControlFrame::Block(_) => ControlFrameKind::Block,
             ControlFrame::Loop(_) => ControlFrameKind::Loop,
             ControlFrame::If(_) => ControlFrameKind::If,
+            ControlFrame::Try(_) => ControlFrameKind::Try,
             ControlFrame::Unreachable(frame) => frame.kind(),
         }
     }
@@ -360,6 +536,7 @@ impl ControlFrame {
             Self::Block(frame) => frame.branch_destination(),
             Self::Loop(frame) => frame.branch_destination(),
             Self::If(frame) => frame.branch_destination(),
+            Self::Try(frame) => frame.branch_destination(),
             Self::Unreachable(frame) => panic!(
                 "tried to get `branch_destination` for an unreachable control frame: {frame:?}"
             ),
@@ -375,6 +552,7 @@ impl ControlFrame {
         match self {
             Self::Block(frame) => frame.end_label(),
             Self::If(frame) => frame.end_label(),
+            Self::Try(frame) => frame.end_label(),
             Self::Loop(frame) => {
                 panic!("tried to get `end_label` for a loop control frame: {frame:?}")
             }
@@ -390,6 +568,7 @@ impl ControlFrame {
             Self::Block(frame) => Some(frame.stack_height()),
             Self::Loop(frame) => Some(frame.stack_height()),
             Self::If(frame) => Some(frame.stack_height()),
+            Self::Try(frame) => Some(frame.stack_height()),
             Self::Unreachable(_frame) => None,
         }
     }
@@ -400,6 +579,7 @@ impl ControlFrame {
             Self::Block(frame) => frame.block_type(),
             Self::Loop(frame) => frame.block_type(),
             Self::If(frame) => frame.block_type(),
+            Self::Try(frame) => frame.block_type(),
             Self::Unreachable(frame) => frame.block_type(),
         }
     }
@@ -419,6 +599,7 @@ impl ControlFrame {
             ControlFrame::Block(frame) => frame.consume_fuel_instr(),
             ControlFrame::Loop(frame) => frame.consume_fuel_instr(),
             ControlFrame::If(frame) => frame.consume_fuel_instr(),
+            ControlFrame::Try(frame) => frame.consume_fuel_instr(),
             ControlFrame::Unreachable(_) => None,
         }
     }
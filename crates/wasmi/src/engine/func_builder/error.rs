@@ -17,6 +17,13 @@ impl TranslationError {
         }
     }
 
+    /// Creates a new error indicating a shared linear memory in deterministic tracing mode.
+    pub fn shared_memory_in_deterministic_tracing() -> Self {
+        Self {
+            inner: Box::new(TranslationErrorInner::SharedMemoryInDeterministicTracing),
+        }
+    }
+
     /// Creates a new error indicating an unsupported Wasm value type.
     pub fn unsupported_value_type(value_type: wasmparser::ValType) -> Self {
         Self {
@@ -52,6 +59,12 @@ impl Display for TranslationError {
                 write!(f, "encountered unsupported Wasm value type: {error:?}")
             }
             TranslationErrorInner::DropKeep(error) => error.fmt(f),
+            TranslationErrorInner::SharedMemoryInDeterministicTracing => {
+                write!(
+                    f,
+                    "shared linear memories are not allowed in deterministic tracing mode"
+                )
+            }
         }
     }
 }
@@ -67,4 +80,6 @@ enum TranslationErrorInner {
     UnsupportedValueType(wasmparser::ValType),
     /// An error with limitations of `DropKeep`.
     DropKeep(DropKeepError),
+    /// Encountered a shared linear memory in deterministic tracing mode.
+    SharedMemoryInDeterministicTracing,
 }
@@ -3,7 +3,7 @@
 use super::labels::{LabelRef, LabelRegistry};
 use crate::engine::bytecode::InstrMeta;
 use crate::engine::{
-    bytecode::{BranchOffset, Instruction},
+    bytecode::{BranchOffset, BranchParams, Instruction, LocalDepth},
     Engine, FuncBody,
 };
 use alloc::vec::Vec;
@@ -77,6 +77,13 @@ pub struct InstructionsBuilder {
     labels: LabelRegistry,
     /// Instruction meta state (pc and opcode number)
     temp_meta: InstrMeta,
+    /// The index of the first instruction that may be fused with its successors.
+    ///
+    /// # Note
+    ///
+    /// Instructions before a pinned label must not be fused with instructions
+    /// after it since branches to the label would skip parts of the fused instruction.
+    fusion_barrier: usize,
 }
 
 impl InstructionsBuilder {
@@ -84,6 +91,7 @@ impl InstructionsBuilder {
     pub fn reset(&mut self) {
         self.insts.clear();
         self.labels.reset();
+        self.fusion_barrier = 0;
     }
 
     /// Returns the current instruction pointer as index.
@@ -106,6 +114,7 @@ impl InstructionsBuilder {
     /// the given label can be resolved properly.
     /// This usually takes place when encountering the Wasm `End` operand for example.
     pub fn pin_label_if_unpinned(&mut self, label: LabelRef) {
+        self.fusion_barrier = self.insts.len();
         self.labels.try_pin_label(label, self.current_pc())
     }
 
@@ -121,6 +130,7 @@ impl InstructionsBuilder {
     ///
     /// If the label has already been resolved.
     pub fn pin_label(&mut self, label: LabelRef) {
+        self.fusion_barrier = self.insts.len();
         self.labels
             .pin_label(label, self.current_pc())
             .unwrap_or_else(|err| panic!("failed to pin label: {err}"));
@@ -136,8 +146,107 @@ impl InstructionsBuilder {
         idx
     }
 
-    pub fn register_meta(&mut self, pc: usize, opcode: u16) {
-        self.temp_meta = InstrMeta(pc, opcode);
+    /// Removes the last `len` instructions so that the next pushed instruction replaces them.
+    ///
+    /// The [`InstrMeta`] of the next pushed instruction covers the source
+    /// positions of all removed instructions.
+    fn remove_last_for_fusion(&mut self, len: usize) {
+        let new_len = self.insts.len() - len;
+        let metas = self.metas.get_mut();
+        self.temp_meta.2 = metas[new_len].2;
+        self.temp_meta.3 = metas[new_len].3;
+        self.insts.truncate(new_len);
+        metas.truncate(new_len);
+    }
+
+    /// Replaces the last `len` instructions with `inst`.
+    ///
+    /// Returns an [`Instr`] to refer to the pushed instruction.
+    fn replace_last(&mut self, len: usize, inst: Instruction) -> Instr {
+        self.remove_last_for_fusion(len);
+        self.push_inst(inst)
+    }
+
+    /// Pushes the binary `inst` fusing a preceding `Const` instruction into its `rhs` operand.
+    ///
+    /// Returns `None` and pushes nothing if `inst` has no immediate operand
+    /// form or if there is no `Const` instruction that can be fused.
+    pub fn push_binary_with_imm_operand(&mut self, inst: Instruction) -> Option<Instr> {
+        match self.insts[self.fusion_barrier..] {
+            [.., Instruction::Const(rhs)] => {
+                let fused = inst.with_imm_rhs(rhs)?;
+                Some(self.replace_last(1, fused))
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes a preceding comparison that can be fused with a `BrIfNez` instruction.
+    ///
+    /// Returns the constructor of the fused conditional branch instruction
+    /// that must be pushed next or `None` if there is nothing to fuse.
+    ///
+    /// # Note
+    ///
+    /// This must be called before the label of the branch is resolved
+    /// since label users are registered by their instruction index.
+    pub fn take_br_if_compare(&mut self) -> Option<fn(BranchParams) -> Instruction> {
+        let make = self.insts[self.fusion_barrier..].last()?.br_if_compare()?;
+        self.remove_last_for_fusion(1);
+        Some(make)
+    }
+
+    /// Pushes the binary `inst` fusing preceding `local.get` instructions into its operands.
+    ///
+    /// Returns an [`Instr`] to refer to the pushed instruction.
+    ///
+    /// # Note
+    ///
+    /// Pushes `inst` as is if it has no local operand form or if
+    /// there are no `local.get` instructions that can be fused.
+    pub fn push_binary_with_local_operands(&mut self, inst: Instruction) -> Instr {
+        let fusable = &self.insts[self.fusion_barrier..];
+        let fused = match fusable {
+            [
+                ..,
+                Instruction::LocalGet { local_depth: lhs },
+                Instruction::LocalGet { local_depth: rhs },
+            ] => {
+                // The `rhs` has been read while `lhs` was on top of the value stack.
+                let rhs = LocalDepth::from(rhs.into_inner() - 1);
+                inst.with_local_operands(*lhs, rhs).map(|fused| (2, fused))
+            }
+            [.., Instruction::LocalGet { local_depth: rhs }] => {
+                inst.with_local_rhs(*rhs).map(|fused| (1, fused))
+            }
+            _ => None,
+        };
+        match fused {
+            Some((len, fused)) => self.replace_last(len, fused),
+            None => self.push_inst(inst),
+        }
+    }
+
+    /// Pushes a `local.set` to `local_depth` fusing a preceding `local.get`.
+    ///
+    /// The fused instruction is an [`Instruction::LocalCopy`].
+    ///
+    /// Returns an [`Instr`] to refer to the pushed instruction.
+    pub fn push_local_set_with_local_operand(&mut self, local_depth: LocalDepth) -> Instr {
+        match self.insts[self.fusion_barrier..] {
+            [.., Instruction::LocalGet { local_depth: input }] => self.replace_last(
+                1,
+                Instruction::LocalCopy {
+                    result: local_depth,
+                    input,
+                },
+            ),
+            _ => self.push_inst(Instruction::LocalSet { local_depth }),
+        }
+    }
+
+    pub fn register_meta(&mut self, pc: usize, opcode: u16, stack_height: u32) {
+        self.temp_meta = InstrMeta(pc, opcode, pc, stack_height);
     }
 
     /// Try resolving the `label` for the currently constructed instruction.
@@ -169,13 +278,20 @@ impl InstructionsBuilder {
     pub fn finish(
         &mut self,
         engine: &Engine,
+        len_params: usize,
         len_locals: usize,
         max_stack_height: usize,
     ) -> FuncBody {
         self.update_branch_offsets();
         let metas = self.metas.replace(Vec::new());
         assert_eq!(self.insts.len(), metas.len());
-        engine.alloc_func_body(len_locals, max_stack_height, self.insts.drain(..), metas)
+        engine.alloc_func_body(
+            len_params,
+            len_locals,
+            max_stack_height,
+            self.insts.drain(..),
+            metas,
+        )
     }
 
     /// Updates the branch offsets of all branch instructions inplace.
@@ -212,7 +328,42 @@ impl Instruction {
         match self {
             Instruction::Br(params)
             | Instruction::BrIfEqz(params)
-            | Instruction::BrIfNez(params) => params.init(offset),
+            | Instruction::BrIfNez(params)
+            | Instruction::BrIfI32Eq(params)
+            | Instruction::BrIfI32Ne(params)
+            | Instruction::BrIfI32LtS(params)
+            | Instruction::BrIfI32LtU(params)
+            | Instruction::BrIfI32GtS(params)
+            | Instruction::BrIfI32GtU(params)
+            | Instruction::BrIfI32LeS(params)
+            | Instruction::BrIfI32LeU(params)
+            | Instruction::BrIfI32GeS(params)
+            | Instruction::BrIfI32GeU(params)
+            | Instruction::BrIfI64Eq(params)
+            | Instruction::BrIfI64Ne(params)
+            | Instruction::BrIfI64LtS(params)
+            | Instruction::BrIfI64LtU(params)
+            | Instruction::BrIfI64GtS(params)
+            | Instruction::BrIfI64GtU(params)
+            | Instruction::BrIfI64LeS(params)
+            | Instruction::BrIfI64LeU(params)
+            | Instruction::BrIfI64GeS(params)
+            | Instruction::BrIfI64GeU(params) => params.init(offset),
+            Instruction::CatchTag { next, .. } => {
+                assert!(!next.is_init());
+                *next = offset;
+            }
+            Instruction::Try { landing, end, .. } => {
+                // Note: The `landing` label is always resolved before the `end` label
+                //       since it is registered before it for the same `Try` instruction.
+                assert!(offset.is_init());
+                if !landing.is_init() {
+                    *landing = offset;
+                } else {
+                    assert!(!end.is_init());
+                    *end = offset;
+                }
+            }
             _ => panic!("tried to update branch offset of a non-branch instruction: {self:?}"),
         }
     }
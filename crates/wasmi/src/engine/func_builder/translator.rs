@@ -6,8 +6,8 @@ use wasmparser::VisitOperator;
 use crate::{
     engine::{
         bytecode::{
-            self, BranchParams, DataSegmentIdx, ElementSegmentIdx, Instruction, Offset,
-            SignatureIdx, TableIdx,
+            self, BranchParams, DataSegmentIdx, ElementSegmentIdx, Instruction, LocalDepth,
+            Offset, SignatureIdx, TableIdx,
         },
         config::FuelCosts,
         func_builder::control_frame::ControlFrameKind,
@@ -15,14 +15,15 @@ use crate::{
     },
     module::{
         BlockType, ConstExpr, FuncIdx, FuncTypeIdx, GlobalIdx, MemoryIdx, ModuleResources,
-        DEFAULT_MEMORY_INDEX,
+        TagIdx, DEFAULT_MEMORY_INDEX,
     },
     Engine, FuncType, GlobalType, Mutability, Value,
 };
 
 use super::{
     control_frame::{
-        BlockControlFrame, ControlFrame, IfControlFrame, LoopControlFrame, UnreachableControlFrame,
+        BlockControlFrame, ControlFrame, IfControlFrame, LoopControlFrame, TryControlFrame,
+        TryState, UnreachableControlFrame,
     },
     labels::LabelRef,
     locals_registry::LocalsRegistry,
@@ -145,7 +146,10 @@ impl<'parser> FuncTranslator<'parser> {
     }
 
     pub fn register_opcode_metadata(&mut self, pos: usize, opcode: u16) {
-        self.alloc.inst_builder.register_meta(pos, opcode);
+        let stack_height = self.stack_height.height();
+        self.alloc
+            .inst_builder
+            .register_meta(pos, opcode, stack_height);
     }
 
     /// This informs the [`FuncTranslator`] that the function header translation is finished.
@@ -166,6 +170,7 @@ impl<'parser> FuncTranslator<'parser> {
     pub fn finish(&mut self) -> Result<FuncBody, TranslationError> {
         let func_body = self.alloc.inst_builder.finish(
             self.res.engine(),
+            self.func_type().params().len(),
             self.len_locals(),
             self.stack_height.max_stack_height() as usize,
         );
@@ -190,6 +195,31 @@ impl<'parser> FuncTranslator<'parser> {
         self.engine().config().get_consume_fuel()
     }
 
+    /// Returns `true` if the superinstruction fusion pass is enabled.
+    fn is_superinstructions(&self) -> bool {
+        self.engine().config().get_superinstructions()
+    }
+
+    /// Pushes the binary `inst` fusing its operands if superinstructions are enabled.
+    ///
+    /// Fuses a preceding `Const` instruction into `inst` first and
+    /// preceding `local.get` instructions otherwise.
+    fn push_binary_inst(&mut self, inst: Instruction) {
+        if self.is_superinstructions()
+            && self
+                .alloc
+                .inst_builder
+                .push_binary_with_imm_operand(inst)
+                .is_some()
+        {
+            return;
+        }
+        match self.is_superinstructions() {
+            true => self.alloc.inst_builder.push_binary_with_local_operands(inst),
+            false => self.alloc.inst_builder.push_inst(inst),
+        };
+    }
+
     /// Returns the configured [`FuelCosts`] of the [`Engine`].
     fn fuel_costs(&self) -> &FuelCosts {
         self.engine().config().fuel_costs()
@@ -215,6 +245,21 @@ impl<'parser> FuncTranslator<'parser> {
         }
     }
 
+    /// Adds the fuel costs of `inst` to the most recent [`ConsumeFuel`] instruction.
+    ///
+    /// Charges `default` fuel unless `inst` is priced individually by the [`FuelCosts`].
+    ///
+    /// [`ConsumeFuel`]: enum.Instruction.html#variant.ConsumeFuel
+    fn bump_fuel_consumption_for(&mut self, inst: Instruction, default: u64) {
+        if self.consume_fuel_instr().is_some() {
+            let delta = self
+                .engine()
+                .resolved_fuel_costs()
+                .fuel_for_instr(inst, default);
+            self.bump_fuel_consumption(delta);
+        }
+    }
+
     /// Returns the [`FuncType`] of the function that is currently translated.
     fn func_type(&self) -> FuncType {
         let dedup_func_type = self.res.get_type_of_func(self.func);
@@ -295,7 +340,7 @@ impl<'parser> FuncTranslator<'parser> {
         let frame = self.alloc.control_frames.nth_back(depth);
         // Find out how many values we need to keep (copy to the new stack location after the drop).
         let keep = match frame.kind() {
-            ControlFrameKind::Block | ControlFrameKind::If => {
+            ControlFrameKind::Block | ControlFrameKind::If | ControlFrameKind::Try => {
                 frame.block_type().len_results(self.res.engine())
             }
             ControlFrameKind::Loop => frame.block_type().len_params(self.res.engine()),
@@ -383,6 +428,132 @@ impl<'parser> FuncTranslator<'parser> {
         })
     }
 
+    /// Returns the number of values carried by exceptions of the tag at `tag_index`.
+    fn len_tag_params(&self, tag_index: u32) -> u32 {
+        let func_type = self.res.get_type_of_tag(TagIdx::from(tag_index));
+        self.engine()
+            .resolve_func_type(func_type, |func_type| func_type.params().len() as u32)
+    }
+
+    /// Translates the end of the current control flow frame.
+    fn translate_end(&mut self) -> Result<(), TranslationError> {
+        let frame = self.alloc.control_frames.last();
+        if let ControlFrame::If(if_frame) = &frame {
+            // At this point we can resolve the `Else` label.
+            //
+            // Note: The `Else` label might have already been resolved
+            //       in case there was an `Else` block.
+            self.alloc
+                .inst_builder
+                .pin_label_if_unpinned(if_frame.else_label());
+        }
+        if frame.is_reachable() && !matches!(frame.kind(), ControlFrameKind::Loop) {
+            // At this point we can resolve the `End` labels.
+            // Note that `loop` control frames do not have an `End` label.
+            self.alloc.inst_builder.pin_label(frame.end_label());
+        }
+        // These bindings are required because of borrowing issues.
+        let frame_reachable = frame.is_reachable();
+        let frame_stack_height = frame.stack_height();
+        if self.alloc.control_frames.len() == 1 {
+            // If the control flow frames stack is empty after this point
+            // we know that we are ending the function body `block`
+            // frame and therefore we have to return from the function.
+            self.visit_return()?;
+        } else {
+            // The following code is only reachable if the ended control flow
+            // frame was reachable upon entering to begin with.
+            self.reachable = frame_reachable;
+        }
+        if let Some(frame_stack_height) = frame_stack_height {
+            self.stack_height.shrink_to(frame_stack_height);
+        }
+        let frame = self.alloc.control_frames.pop_frame();
+        frame
+            .block_type()
+            .foreach_result(self.res.engine(), |_result| self.stack_height.push());
+        Ok(())
+    }
+
+    /// Finishes the `try` body or the current `catch` clause of the `try_frame`.
+    ///
+    /// This jumps to the end of the `try` block if the current code is reachable
+    /// and afterwards pins the label of the code that follows.
+    ///
+    /// - For the `try` body this is the landing label of the `catch` clauses.
+    /// - For `catch` clauses this is the label of the next `catch` clause.
+    fn finish_try_section(&mut self, try_frame: &mut TryControlFrame) {
+        if self.is_reachable() {
+            let params = self.branch_params(try_frame.end_label(), DropKeep::none());
+            self.bump_fuel_consumption_for(Instruction::Br(params), self.fuel_costs().base);
+            self.alloc.inst_builder.push_inst(Instruction::Br(params));
+        }
+        match try_frame.state() {
+            TryState::Body => {
+                self.alloc.inst_builder.pin_label(try_frame.landing_label());
+            }
+            TryState::Catch | TryState::CatchAll => {
+                if let Some(next_label) = try_frame.take_next_label() {
+                    self.alloc.inst_builder.pin_label(next_label);
+                }
+            }
+        }
+    }
+
+    /// Translates a Wasm `catch` or `catch_all` clause.
+    ///
+    /// Translates a `catch_all` clause if `tag_index` is `None`.
+    fn translate_catch_clause(&mut self, tag_index: Option<u32>) -> Result<(), TranslationError> {
+        let mut try_frame = match self.alloc.control_frames.pop_frame() {
+            ControlFrame::Try(try_frame) => try_frame,
+            ControlFrame::Unreachable(frame) if matches!(frame.kind(), ControlFrameKind::Try) => {
+                // Encountered `catch` clause for unreachable `try` block.
+                //
+                // In this case we can simply ignore the entire `catch` clause
+                // since it is unreachable anyways.
+                self.alloc.control_frames.push_frame(frame);
+                return Ok(());
+            }
+            unexpected => panic!(
+                "expected `try` control flow frame on top \
+                for `catch` but found: {unexpected:?}",
+            ),
+        };
+        self.finish_try_section(&mut try_frame);
+        match tag_index {
+            Some(tag_index) => {
+                let next_label = self.alloc.inst_builder.new_label();
+                try_frame.enter_clause(TryState::Catch, Some(next_label));
+                let next = self.alloc.inst_builder.try_resolve_label(next_label);
+                self.alloc.inst_builder.push_inst(Instruction::CatchTag {
+                    tag: bytecode::TagIdx::from(tag_index),
+                    next,
+                });
+            }
+            None => {
+                try_frame.enter_clause(TryState::CatchAll, None);
+            }
+        }
+        self.is_fuel_metering_enabled().then(|| {
+            let consume_fuel = self
+                .alloc
+                .inst_builder
+                .push_inst(Instruction::consume_fuel(self.fuel_costs().base));
+            try_frame.update_consume_fuel_instr(consume_fuel);
+        });
+        // We need to reset the value stack to exactly how it has been
+        // when entering the `try` so that the `catch` clause starts with
+        // the values carried by the caught exception on top of the stack.
+        self.stack_height.shrink_to(try_frame.stack_height());
+        if let Some(tag_index) = tag_index {
+            self.stack_height.push_n(self.len_tag_params(tag_index));
+        }
+        self.alloc.control_frames.push_frame(try_frame);
+        // We can reset reachability now since the parent `try` block was reachable.
+        self.reachable = true;
+        Ok(())
+    }
+
     /// Adjusts the emulated value stack given the [`FuncType`] of the call.
     fn adjust_value_stack_for_call(&mut self, func_type: &FuncType) {
         let (params, results) = func_type.params_results();
@@ -450,11 +621,11 @@ impl<'parser> FuncTranslator<'parser> {
         self.translate_if_reachable(|builder| {
             let (memory_idx, offset) = Self::decompose_memarg(memarg);
             debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(builder.fuel_costs().load);
+            let inst = make_inst(Offset::from(offset));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().load);
             builder.stack_height.pop1();
             builder.stack_height.push();
-            let offset = Offset::from(offset);
-            builder.alloc.inst_builder.push_inst(make_inst(offset));
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -483,10 +654,87 @@ impl<'parser> FuncTranslator<'parser> {
         self.translate_if_reachable(|builder| {
             let (memory_idx, offset) = Self::decompose_memarg(memarg);
             debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(builder.fuel_costs().store);
+            let inst = make_inst(Offset::from(offset));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().store);
+            builder.stack_height.pop2();
+            builder.alloc.inst_builder.push_inst(inst);
+            Ok(())
+        })
+    }
+
+    /// Translate a Wasm atomic read-modify-write instruction.
+    ///
+    /// # Note
+    ///
+    /// This is used as the translation backend of the following Wasm instructions:
+    ///
+    /// - `{i32, i64}.atomic.rmw.{add, sub, and, or, xor, xchg}`
+    /// - `{i32, i64}.atomic.rmw8.{add, sub, and, or, xor, xchg}_u`
+    /// - `{i32, i64}.atomic.rmw16.{add, sub, and, or, xor, xchg}_u`
+    /// - `i64.atomic.rmw32.{add, sub, and, or, xor, xchg}_u`
+    fn translate_atomic_rmw(
+        &mut self,
+        memarg: wasmparser::MemArg,
+        make_inst: fn(Offset) -> Instruction,
+    ) -> Result<(), TranslationError> {
+        self.translate_if_reachable(|builder| {
+            let (memory_idx, offset) = Self::decompose_memarg(memarg);
+            debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
+            let inst = make_inst(Offset::from(offset));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().store);
             builder.stack_height.pop2();
-            let offset = Offset::from(offset);
-            builder.alloc.inst_builder.push_inst(make_inst(offset));
+            builder.stack_height.push();
+            builder.alloc.inst_builder.push_inst(inst);
+            Ok(())
+        })
+    }
+
+    /// Translate a Wasm atomic compare-exchange instruction.
+    ///
+    /// # Note
+    ///
+    /// This is used as the translation backend of the following Wasm instructions:
+    ///
+    /// - `{i32, i64}.atomic.rmw.cmpxchg`
+    /// - `{i32, i64}.atomic.rmw8.cmpxchg_u`
+    /// - `{i32, i64}.atomic.rmw16.cmpxchg_u`
+    /// - `i64.atomic.rmw32.cmpxchg_u`
+    fn translate_atomic_cmpxchg(
+        &mut self,
+        memarg: wasmparser::MemArg,
+        make_inst: fn(Offset) -> Instruction,
+    ) -> Result<(), TranslationError> {
+        self.translate_if_reachable(|builder| {
+            let (memory_idx, offset) = Self::decompose_memarg(memarg);
+            debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
+            let inst = make_inst(Offset::from(offset));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().store);
+            builder.stack_height.pop3();
+            builder.stack_height.push();
+            builder.alloc.inst_builder.push_inst(inst);
+            Ok(())
+        })
+    }
+
+    /// Translate a Wasm `memory.atomic.{notify, wait32, wait64}` instruction.
+    ///
+    /// The `notify` instruction pops 2 operands whereas `wait` pops 3.
+    fn translate_atomic_wait_notify(
+        &mut self,
+        memarg: wasmparser::MemArg,
+        make_inst: fn(Offset) -> Instruction,
+    ) -> Result<(), TranslationError> {
+        self.translate_if_reachable(|builder| {
+            let (memory_idx, offset) = Self::decompose_memarg(memarg);
+            debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
+            let inst = make_inst(Offset::from(offset));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            match inst {
+                Instruction::MemoryAtomicNotify(_) => builder.stack_height.pop2(),
+                _ => builder.stack_height.pop3(),
+            }
+            builder.stack_height.push();
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -506,13 +754,10 @@ impl<'parser> FuncTranslator<'parser> {
         T: Into<Value>,
     {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
-            let value = value.into();
+            let inst = Instruction::constant(value.into());
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.stack_height.push();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::constant(value));
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -531,7 +776,7 @@ impl<'parser> FuncTranslator<'parser> {
         inst: Instruction,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
@@ -555,10 +800,10 @@ impl<'parser> FuncTranslator<'parser> {
         inst: Instruction,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.stack_height.pop2();
             builder.stack_height.push();
-            builder.alloc.inst_builder.push_inst(inst);
+            builder.push_binary_inst(inst);
             Ok(())
         })
     }
@@ -585,7 +830,7 @@ impl<'parser> FuncTranslator<'parser> {
         inst: Instruction,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
@@ -618,10 +863,10 @@ impl<'parser> FuncTranslator<'parser> {
         inst: Instruction,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.stack_height.pop2();
             builder.stack_height.push();
-            builder.alloc.inst_builder.push_inst(inst);
+            builder.push_binary_inst(inst);
             Ok(())
         })
     }
@@ -649,7 +894,7 @@ impl<'parser> FuncTranslator<'parser> {
         inst: Instruction,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
@@ -763,6 +1008,12 @@ macro_rules! impl_visit_operator {
     ( @tail_call $($rest:tt)* ) => {
         impl_visit_operator!(@@skipped $($rest)*);
     };
+    ( @exceptions $($rest:tt)* ) => {
+        impl_visit_operator!(@@skipped $($rest)*);
+    };
+    ( @threads $($rest:tt)* ) => {
+        impl_visit_operator!(@@skipped $($rest)*);
+    };
     ( @@skipped $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident $($rest:tt)* ) => {
         // We skip Wasm operators that we already implement manually.
         impl_visit_operator!($($rest)*);
@@ -788,7 +1039,7 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_unreachable(&mut self) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(Instruction::Unreachable, builder.fuel_costs().base);
             builder
                 .alloc
                 .inst_builder
@@ -859,11 +1110,10 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
             let stack_height = self.frame_stack_height(block_type);
             let else_label = self.alloc.inst_builder.new_label();
             let end_label = self.alloc.inst_builder.new_label();
-            self.bump_fuel_consumption(self.fuel_costs().base);
             let branch_params = self.branch_params(else_label, DropKeep::none());
-            self.alloc
-                .inst_builder
-                .push_inst(Instruction::BrIfEqz(branch_params));
+            let inst = Instruction::BrIfEqz(branch_params);
+            self.bump_fuel_consumption_for(inst, self.fuel_costs().base);
+            self.alloc.inst_builder.push_inst(inst);
             let consume_fuel = self.is_fuel_metering_enabled().then(|| {
                 self.alloc
                     .inst_builder
@@ -913,8 +1163,8 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         // Create the jump from the end of the `then` block to the `if`
         // block's end label in case the end of `then` is reachable.
         if reachable {
-            self.bump_fuel_consumption(self.fuel_costs().base);
             let params = self.branch_params(if_frame.end_label(), DropKeep::none());
+            self.bump_fuel_consumption_for(Instruction::Br(params), self.fuel_costs().base);
             self.alloc.inst_builder.push_inst(Instruction::Br(params));
         }
         // Now resolve labels for the instructions of the `else` block
@@ -946,52 +1196,135 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     }
 
     fn visit_end(&mut self) -> Result<(), TranslationError> {
-        let frame = self.alloc.control_frames.last();
-        if let ControlFrame::If(if_frame) = &frame {
-            // At this point we can resolve the `Else` label.
-            //
-            // Note: The `Else` label might have already been resolved
-            //       in case there was an `Else` block.
-            self.alloc
-                .inst_builder
-                .pin_label_if_unpinned(if_frame.else_label());
-        }
-        if frame.is_reachable() && !matches!(frame.kind(), ControlFrameKind::Loop) {
-            // At this point we can resolve the `End` labels.
-            // Note that `loop` control frames do not have an `End` label.
-            self.alloc.inst_builder.pin_label(frame.end_label());
+        if let ControlFrame::Try(try_frame) = self.alloc.control_frames.last() {
+            let mut try_frame = *try_frame;
+            if !matches!(try_frame.state(), TryState::CatchAll) {
+                // Without a `catch_all` clause exceptions that are not caught
+                // by any of the `catch` clauses are rethrown at the end.
+                self.finish_try_section(&mut try_frame);
+                let landing = self
+                    .alloc
+                    .inst_builder
+                    .try_resolve_label(try_frame.landing_label());
+                self.alloc
+                    .inst_builder
+                    .push_inst(Instruction::Rethrow { landing });
+                self.reachable = false;
+            }
         }
-        // These bindings are required because of borrowing issues.
-        let frame_reachable = frame.is_reachable();
-        let frame_stack_height = frame.stack_height();
-        if self.alloc.control_frames.len() == 1 {
-            // If the control flow frames stack is empty after this point
-            // we know that we are ending the function body `block`
-            // frame and therefore we have to return from the function.
-            self.visit_return()?;
+        self.translate_end()
+    }
+
+    fn visit_try(&mut self, block_type: wasmparser::BlockType) -> Result<(), TranslationError> {
+        let block_type = BlockType::new(block_type, self.res);
+        if self.is_reachable() {
+            // Inherit `ConsumeFuel` instruction from parent control frame.
+            // This is applicable since the Wasm `try` body unconditionally executes
+            // all its instructions unless an exception is thrown.
+            let consume_fuel = self.alloc.control_frames.last().consume_fuel_instr();
+            let stack_height = self.frame_stack_height(block_type);
+            let landing_label = self.alloc.inst_builder.new_label();
+            let end_label = self.alloc.inst_builder.new_label();
+            let landing = self.alloc.inst_builder.try_resolve_label(landing_label);
+            let end = self.alloc.inst_builder.try_resolve_label(end_label);
+            let len_params = block_type.len_params(self.engine());
+            let inst = Instruction::Try {
+                landing,
+                end,
+                len_params,
+            };
+            self.bump_fuel_consumption_for(inst, self.fuel_costs().base);
+            self.alloc.inst_builder.push_inst(inst);
+            self.alloc.control_frames.push_frame(TryControlFrame::new(
+                block_type,
+                end_label,
+                landing_label,
+                stack_height,
+                consume_fuel,
+            ));
         } else {
-            // The following code is only reachable if the ended control flow
-            // frame was reachable upon entering to begin with.
-            self.reachable = frame_reachable;
-        }
-        if let Some(frame_stack_height) = frame_stack_height {
-            self.stack_height.shrink_to(frame_stack_height);
+            self.alloc
+                .control_frames
+                .push_frame(UnreachableControlFrame::new(
+                    ControlFrameKind::Try,
+                    block_type,
+                ));
         }
-        let frame = self.alloc.control_frames.pop_frame();
-        frame
-            .block_type()
-            .foreach_result(self.res.engine(), |_result| self.stack_height.push());
         Ok(())
     }
 
+    fn visit_catch(&mut self, tag_index: u32) -> Result<(), TranslationError> {
+        self.translate_catch_clause(Some(tag_index))
+    }
+
+    fn visit_catch_all(&mut self) -> Result<(), TranslationError> {
+        self.translate_catch_clause(None)
+    }
+
+    fn visit_throw(&mut self, tag_index: u32) -> Result<(), TranslationError> {
+        self.translate_if_reachable(|builder| {
+            let inst = Instruction::Throw(bytecode::TagIdx::from(tag_index));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
+            let len_params = builder.len_tag_params(tag_index);
+            builder.stack_height.pop_n(len_params);
+            builder.alloc.inst_builder.push_inst(inst);
+            builder.reachable = false;
+            Ok(())
+        })
+    }
+
+    fn visit_rethrow(&mut self, relative_depth: u32) -> Result<(), TranslationError> {
+        self.translate_if_reachable(|builder| {
+            let landing_label = match builder.alloc.control_frames.nth_back(relative_depth) {
+                ControlFrame::Try(try_frame) => try_frame.landing_label(),
+                unexpected => panic!(
+                    "expected `try` control flow frame at depth {relative_depth} \
+                    for `rethrow` but found: {unexpected:?}",
+                ),
+            };
+            let landing = builder.alloc.inst_builder.try_resolve_label(landing_label);
+            let inst = Instruction::Rethrow { landing };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
+            builder.alloc.inst_builder.push_inst(inst);
+            builder.reachable = false;
+            Ok(())
+        })
+    }
+
+    fn visit_delegate(&mut self, relative_depth: u32) -> Result<(), TranslationError> {
+        if let ControlFrame::Try(try_frame) = self.alloc.control_frames.last() {
+            let mut try_frame = *try_frame;
+            self.finish_try_section(&mut try_frame);
+            // Exceptions are delegated to the exception handlers outside of the
+            // targeted label. Therefore we skip all exception handlers of the
+            // `try` blocks in between whose bodies enclose the `delegate`.
+            let skip = (1..=relative_depth)
+                .filter(|&depth| {
+                    matches!(
+                        self.alloc.control_frames.nth_back(depth),
+                        ControlFrame::Try(frame) if matches!(frame.state(), TryState::Body)
+                    )
+                })
+                .count() as u32;
+            self.alloc
+                .inst_builder
+                .push_inst(Instruction::Delegate { skip });
+            self.reachable = false;
+        }
+        self.translate_end()
+    }
+
     fn visit_br(&mut self, relative_depth: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
             match builder.acquire_target(relative_depth)? {
                 AcquiredTarget::Branch(end_label, drop_keep) => {
-                    builder.bump_fuel_consumption(builder.fuel_costs().base);
                     builder
                         .bump_fuel_consumption(builder.fuel_costs().fuel_for_drop_keep(drop_keep));
                     let params = builder.branch_params(end_label, drop_keep);
+                    builder.bump_fuel_consumption_for(
+                        Instruction::Br(params),
+                        builder.fuel_costs().base,
+                    );
                     builder
                         .alloc
                         .inst_builder
@@ -1012,14 +1345,25 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
             builder.stack_height.pop1();
             match builder.acquire_target(relative_depth)? {
                 AcquiredTarget::Branch(end_label, drop_keep) => {
-                    builder.bump_fuel_consumption(builder.fuel_costs().base);
                     builder
                         .bump_fuel_consumption(builder.fuel_costs().fuel_for_drop_keep(drop_keep));
+                    // Note: The fused comparison must be removed before the label is resolved.
+                    let fused = match builder.is_superinstructions() {
+                        true => builder.alloc.inst_builder.take_br_if_compare(),
+                        false => None,
+                    };
                     let params = builder.branch_params(end_label, drop_keep);
-                    builder
-                        .alloc
-                        .inst_builder
-                        .push_inst(Instruction::BrIfNez(params));
+                    // Note: Fuel is charged for `br_if_nez` even if the branch is fused
+                    //       so that fuel consumption does not depend on the fusion pass.
+                    builder.bump_fuel_consumption_for(
+                        Instruction::BrIfNez(params),
+                        builder.fuel_costs().base,
+                    );
+                    let inst = match fused {
+                        Some(make) => make(params),
+                        None => Instruction::BrIfNez(params),
+                    };
+                    builder.alloc.inst_builder.push_inst(inst);
                 }
                 AcquiredTarget::Return(drop_keep) => {
                     builder
@@ -1079,7 +1423,10 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
                 })
                 .map(RelativeDepth::from_u32);
 
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(
+                Instruction::BrTable { len_targets: 0 },
+                builder.fuel_costs().base,
+            );
             // The maximum fuel costs among all `br_table` arms.
             // We use this to charge fuel once at the entry of a `br_table`
             // for the most expensive arm of all of its arms.
@@ -1112,12 +1459,10 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     fn visit_return(&mut self) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
             let drop_keep = builder.drop_keep_return()?;
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            let inst = Instruction::Return(drop_keep);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
             builder.bump_fuel_consumption(builder.fuel_costs().fuel_for_drop_keep(drop_keep));
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::Return(drop_keep));
+            builder.alloc.inst_builder.push_inst(inst);
             builder.reachable = false;
             Ok(())
         })
@@ -1128,12 +1473,10 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
             let func = bytecode::FuncIdx::from(func_idx);
             let func_type = builder.func_type_of(func_idx.into());
             let drop_keep = builder.drop_keep_return_call(&func_type)?;
-            builder.bump_fuel_consumption(builder.fuel_costs().call);
+            let inst = Instruction::ReturnCall { drop_keep, func };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().call);
             builder.bump_fuel_consumption(builder.fuel_costs().fuel_for_drop_keep(drop_keep));
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::ReturnCall { drop_keep, func });
+            builder.alloc.inst_builder.push_inst(inst);
             builder.reachable = false;
             Ok(())
         })
@@ -1150,16 +1493,14 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
             let table = TableIdx::from(table_index);
             builder.stack_height.pop1();
             let drop_keep = builder.drop_keep_return_call(&func_type)?;
-            builder.bump_fuel_consumption(builder.fuel_costs().call);
+            let inst = Instruction::ReturnCallIndirect {
+                drop_keep,
+                table,
+                func_type: signature,
+            };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().call);
             builder.bump_fuel_consumption(builder.fuel_costs().fuel_for_drop_keep(drop_keep));
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::ReturnCallIndirect {
-                    drop_keep,
-                    table,
-                    func_type: signature,
-                });
+            builder.alloc.inst_builder.push_inst(inst);
             builder.reachable = false;
             Ok(())
         })
@@ -1167,15 +1508,12 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_call(&mut self, func_idx: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().call);
             let func_idx = FuncIdx::from(func_idx);
             let func_type = builder.func_type_of(func_idx);
+            let inst = Instruction::Call(func_idx.into_u32().into());
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().call);
             builder.adjust_value_stack_for_call(&func_type);
-            let func_idx = func_idx.into_u32().into();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::Call(func_idx));
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1187,22 +1525,20 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         _table_byte: u8,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().call);
             let func_type = SignatureIdx::from(func_type_index);
             let table = TableIdx::from(table_index);
+            let inst = Instruction::CallIndirect { table, func_type };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().call);
             builder.stack_height.pop1();
             builder.adjust_value_stack_for_call(&builder.func_type_at(func_type));
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::CallIndirect { table, func_type });
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_drop(&mut self) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(Instruction::Drop, builder.fuel_costs().base);
             builder.stack_height.pop1();
             builder.alloc.inst_builder.push_inst(Instruction::Drop);
             Ok(())
@@ -1211,7 +1547,7 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_select(&mut self) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
+            builder.bump_fuel_consumption_for(Instruction::Select, builder.fuel_costs().base);
             builder.stack_height.pop3();
             builder.stack_height.push();
             builder.alloc.inst_builder.push_inst(Instruction::Select);
@@ -1241,12 +1577,11 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_ref_func(&mut self, func_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
-            let func_index = bytecode::FuncIdx::from(func_index);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::RefFunc { func_index });
+            let inst = Instruction::RefFunc {
+                func_index: bytecode::FuncIdx::from(func_index),
+            };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
+            builder.alloc.inst_builder.push_inst(inst);
             builder.stack_height.push();
             Ok(())
         })
@@ -1254,12 +1589,9 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_local_get(&mut self, local_idx: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
-            let local_depth = builder.relative_local_depth(local_idx);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::local_get(local_depth));
+            let inst = Instruction::local_get(builder.relative_local_depth(local_idx));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
+            builder.alloc.inst_builder.push_inst(inst);
             builder.stack_height.push();
             Ok(())
         })
@@ -1267,32 +1599,37 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_local_set(&mut self, local_idx: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
             builder.stack_height.pop1();
             let local_depth = builder.relative_local_depth(local_idx);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::local_set(local_depth));
+            builder.bump_fuel_consumption_for(
+                Instruction::local_set(local_depth),
+                builder.fuel_costs().base,
+            );
+            match builder.is_superinstructions() {
+                true => builder
+                    .alloc
+                    .inst_builder
+                    .push_local_set_with_local_operand(LocalDepth::from(local_depth)),
+                false => builder
+                    .alloc
+                    .inst_builder
+                    .push_inst(Instruction::local_set(local_depth)),
+            };
             Ok(())
         })
     }
 
     fn visit_local_tee(&mut self, local_idx: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().base);
-            let local_depth = builder.relative_local_depth(local_idx);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::local_tee(local_depth));
+            let inst = Instruction::local_tee(builder.relative_local_depth(local_idx));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_global_get(&mut self, global_idx: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let global_idx = GlobalIdx::from(global_idx);
             builder.stack_height.push();
             let (global_type, init_value) = builder.res.get_global(global_idx);
@@ -1300,6 +1637,7 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
                 // No optimization took place in this case.
                 Instruction::GlobalGet(global_idx.into_u32().into())
             });
+            builder.bump_fuel_consumption_for(instr, builder.fuel_costs().entity);
             builder.alloc.inst_builder.push_inst(instr);
             Ok(())
         })
@@ -1307,16 +1645,13 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
 
     fn visit_global_set(&mut self, global_idx: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let global_idx = GlobalIdx::from(global_idx);
             let global_type = builder.res.get_type_of_global(global_idx);
             debug_assert_eq!(global_type.mutability(), Mutability::Var);
             builder.stack_height.pop1();
-            let global_idx = global_idx.into_u32().into();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::GlobalSet(global_idx));
+            let inst = Instruction::GlobalSet(global_idx.into_u32().into());
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1419,14 +1754,12 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         _mem_byte: u8,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let memory_idx = MemoryIdx::from(memory_idx);
             debug_assert_eq!(memory_idx.into_u32(), DEFAULT_MEMORY_INDEX);
             builder.stack_height.push();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::MemorySize);
+            let inst = Instruction::MemorySize;
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1438,11 +1771,9 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::MemoryGrow);
+            let inst = Instruction::MemoryGrow;
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1454,12 +1785,10 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             builder.stack_height.pop3();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::MemoryInit(DataSegmentIdx::from(segment_index)));
+            let inst = Instruction::MemoryInit(DataSegmentIdx::from(segment_index));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1467,12 +1796,10 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     fn visit_memory_fill(&mut self, memory_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(memory_index, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             builder.stack_height.pop3();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::MemoryFill);
+            let inst = Instruction::MemoryFill;
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1481,102 +1808,86 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         self.translate_if_reachable(|builder| {
             debug_assert_eq!(dst_mem, DEFAULT_MEMORY_INDEX);
             debug_assert_eq!(src_mem, DEFAULT_MEMORY_INDEX);
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             builder.stack_height.pop3();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::MemoryCopy);
+            let inst = Instruction::MemoryCopy;
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_data_drop(&mut self, segment_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let segment_index = DataSegmentIdx::from(segment_index);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::DataDrop(segment_index));
+            let inst = Instruction::DataDrop(segment_index);
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_table_size(&mut self, table_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let table = TableIdx::from(table_index);
             builder.stack_height.push();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableSize { table });
+            let inst = Instruction::TableSize { table };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_table_grow(&mut self, table_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let table = TableIdx::from(table_index);
             builder.stack_height.pop1();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableGrow { table });
+            let inst = Instruction::TableGrow { table };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_table_copy(&mut self, dst_table: u32, src_table: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let dst = TableIdx::from(dst_table);
             let src = TableIdx::from(src_table);
             builder.stack_height.pop3();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableCopy { dst, src });
+            let inst = Instruction::TableCopy { dst, src };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_table_fill(&mut self, table_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let table = TableIdx::from(table_index);
             builder.stack_height.pop3();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableFill { table });
+            let inst = Instruction::TableFill { table };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_table_get(&mut self, table_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let table = TableIdx::from(table_index);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableGet { table });
+            let inst = Instruction::TableGet { table };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_table_set(&mut self, table_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             let table = TableIdx::from(table_index);
             builder.stack_height.pop2();
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableSet { table });
+            let inst = Instruction::TableSet { table };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -1587,27 +1898,21 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
         table_index: u32,
     ) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
             builder.stack_height.pop3();
             let table = TableIdx::from(table_index);
             let elem = ElementSegmentIdx::from(segment_index);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::TableInit { table, elem });
+            let inst = Instruction::TableInit { table, elem };
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
 
     fn visit_elem_drop(&mut self, segment_index: u32) -> Result<(), TranslationError> {
         self.translate_if_reachable(|builder| {
-            builder.bump_fuel_consumption(builder.fuel_costs().entity);
-            builder
-                .alloc
-                .inst_builder
-                .push_inst(Instruction::ElemDrop(ElementSegmentIdx::from(
-                    segment_index,
-                )));
+            let inst = Instruction::ElemDrop(ElementSegmentIdx::from(segment_index));
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().entity);
+            builder.alloc.inst_builder.push_inst(inst);
             Ok(())
         })
     }
@@ -2171,4 +2476,475 @@ impl<'a> VisitOperator<'a> for FuncTranslator<'a> {
     fn visit_i64_trunc_sat_f64_u(&mut self) -> Result<(), TranslationError> {
         self.translate_conversion(ValueType::F64, ValueType::I64, Instruction::I64TruncSatF64U)
     }
+
+    fn visit_memory_atomic_notify(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_wait_notify(memarg, Instruction::MemoryAtomicNotify)
+    }
+
+    fn visit_memory_atomic_wait32(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_wait_notify(memarg, Instruction::MemoryAtomicWait32)
+    }
+
+    fn visit_memory_atomic_wait64(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_wait_notify(memarg, Instruction::MemoryAtomicWait64)
+    }
+
+    fn visit_atomic_fence(&mut self) -> Result<(), TranslationError> {
+        self.translate_if_reachable(|builder| {
+            let inst = Instruction::AtomicFence;
+            builder.bump_fuel_consumption_for(inst, builder.fuel_costs().base);
+            builder.alloc.inst_builder.push_inst(inst);
+            Ok(())
+        })
+    }
+
+    fn visit_i32_atomic_load(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I32, Instruction::I32AtomicLoad)
+    }
+
+    fn visit_i64_atomic_load(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I64, Instruction::I64AtomicLoad)
+    }
+
+    fn visit_i32_atomic_load8_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I32, Instruction::I32AtomicLoad8U)
+    }
+
+    fn visit_i32_atomic_load16_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I32, Instruction::I32AtomicLoad16U)
+    }
+
+    fn visit_i64_atomic_load8_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I64, Instruction::I64AtomicLoad8U)
+    }
+
+    fn visit_i64_atomic_load16_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I64, Instruction::I64AtomicLoad16U)
+    }
+
+    fn visit_i64_atomic_load32_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_load(memarg, ValueType::I64, Instruction::I64AtomicLoad32U)
+    }
+
+    fn visit_i32_atomic_store(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I32, Instruction::I32AtomicStore)
+    }
+
+    fn visit_i64_atomic_store(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I64, Instruction::I64AtomicStore)
+    }
+
+    fn visit_i32_atomic_store8(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I32, Instruction::I32AtomicStore8)
+    }
+
+    fn visit_i32_atomic_store16(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I32, Instruction::I32AtomicStore16)
+    }
+
+    fn visit_i64_atomic_store8(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I64, Instruction::I64AtomicStore8)
+    }
+
+    fn visit_i64_atomic_store16(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I64, Instruction::I64AtomicStore16)
+    }
+
+    fn visit_i64_atomic_store32(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_store(memarg, ValueType::I64, Instruction::I64AtomicStore32)
+    }
+
+    fn visit_i32_atomic_rmw_add(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmwAdd)
+    }
+
+    fn visit_i64_atomic_rmw_add(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmwAdd)
+    }
+
+    fn visit_i32_atomic_rmw8_add_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw8AddU)
+    }
+
+    fn visit_i32_atomic_rmw16_add_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw16AddU)
+    }
+
+    fn visit_i64_atomic_rmw8_add_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw8AddU)
+    }
+
+    fn visit_i64_atomic_rmw16_add_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw16AddU)
+    }
+
+    fn visit_i64_atomic_rmw32_add_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw32AddU)
+    }
+
+    fn visit_i32_atomic_rmw_sub(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmwSub)
+    }
+
+    fn visit_i64_atomic_rmw_sub(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmwSub)
+    }
+
+    fn visit_i32_atomic_rmw8_sub_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw8SubU)
+    }
+
+    fn visit_i32_atomic_rmw16_sub_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw16SubU)
+    }
+
+    fn visit_i64_atomic_rmw8_sub_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw8SubU)
+    }
+
+    fn visit_i64_atomic_rmw16_sub_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw16SubU)
+    }
+
+    fn visit_i64_atomic_rmw32_sub_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw32SubU)
+    }
+
+    fn visit_i32_atomic_rmw_and(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmwAnd)
+    }
+
+    fn visit_i64_atomic_rmw_and(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmwAnd)
+    }
+
+    fn visit_i32_atomic_rmw8_and_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw8AndU)
+    }
+
+    fn visit_i32_atomic_rmw16_and_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw16AndU)
+    }
+
+    fn visit_i64_atomic_rmw8_and_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw8AndU)
+    }
+
+    fn visit_i64_atomic_rmw16_and_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw16AndU)
+    }
+
+    fn visit_i64_atomic_rmw32_and_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw32AndU)
+    }
+
+    fn visit_i32_atomic_rmw_or(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmwOr)
+    }
+
+    fn visit_i64_atomic_rmw_or(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmwOr)
+    }
+
+    fn visit_i32_atomic_rmw8_or_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw8OrU)
+    }
+
+    fn visit_i32_atomic_rmw16_or_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw16OrU)
+    }
+
+    fn visit_i64_atomic_rmw8_or_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw8OrU)
+    }
+
+    fn visit_i64_atomic_rmw16_or_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw16OrU)
+    }
+
+    fn visit_i64_atomic_rmw32_or_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw32OrU)
+    }
+
+    fn visit_i32_atomic_rmw_xor(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmwXor)
+    }
+
+    fn visit_i64_atomic_rmw_xor(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmwXor)
+    }
+
+    fn visit_i32_atomic_rmw8_xor_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw8XorU)
+    }
+
+    fn visit_i32_atomic_rmw16_xor_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw16XorU)
+    }
+
+    fn visit_i64_atomic_rmw8_xor_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw8XorU)
+    }
+
+    fn visit_i64_atomic_rmw16_xor_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw16XorU)
+    }
+
+    fn visit_i64_atomic_rmw32_xor_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw32XorU)
+    }
+
+    fn visit_i32_atomic_rmw_xchg(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmwXchg)
+    }
+
+    fn visit_i64_atomic_rmw_xchg(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmwXchg)
+    }
+
+    fn visit_i32_atomic_rmw8_xchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw8XchgU)
+    }
+
+    fn visit_i32_atomic_rmw16_xchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I32AtomicRmw16XchgU)
+    }
+
+    fn visit_i64_atomic_rmw8_xchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw8XchgU)
+    }
+
+    fn visit_i64_atomic_rmw16_xchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw16XchgU)
+    }
+
+    fn visit_i64_atomic_rmw32_xchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_rmw(memarg, Instruction::I64AtomicRmw32XchgU)
+    }
+
+    fn visit_i32_atomic_rmw_cmpxchg(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I32AtomicRmwCmpxchg)
+    }
+
+    fn visit_i64_atomic_rmw_cmpxchg(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I64AtomicRmwCmpxchg)
+    }
+
+    fn visit_i32_atomic_rmw8_cmpxchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I32AtomicRmw8CmpxchgU)
+    }
+
+    fn visit_i32_atomic_rmw16_cmpxchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I32AtomicRmw16CmpxchgU)
+    }
+
+    fn visit_i64_atomic_rmw8_cmpxchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I64AtomicRmw8CmpxchgU)
+    }
+
+    fn visit_i64_atomic_rmw16_cmpxchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I64AtomicRmw16CmpxchgU)
+    }
+
+    fn visit_i64_atomic_rmw32_cmpxchg_u(
+        &mut self,
+        memarg: wasmparser::MemArg,
+    ) -> Result<(), TranslationError> {
+        self.translate_atomic_cmpxchg(memarg, Instruction::I64AtomicRmw32CmpxchgU)
+    }
 }
@@ -0,0 +1,458 @@
+//! Recording and replaying of host function calls for reproducible executions.
+
+use crate::{
+    core::UntypedValue,
+    limits::ResourceLimiterRef,
+    memory::MemoryJournal,
+    store::StoreInner,
+};
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Display};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use wasmi_core::{HostError, Pages, Trap};
+
+/// The pages a host function call grew a linear memory by.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryGrow {
+    /// The index of the linear memory within its [`Store`](crate::Store).
+    pub memory: u32,
+    /// The amount of pages the linear memory grew by.
+    pub pages: u32,
+}
+
+/// The bytes a host function call wrote to a linear memory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryWrite {
+    /// The index of the linear memory within its [`Store`](crate::Store).
+    pub memory: u32,
+    /// The offset of the first written byte.
+    pub offset: u32,
+    /// The written bytes, encoded as hex in JSON.
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    pub data: Vec<u8>,
+}
+
+fn serialize_hex<S>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&hex::encode(data))
+}
+
+fn deserialize_hex<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let encoded = String::deserialize(deserializer)?;
+    hex::decode(encoded).map_err(serde::de::Error::custom)
+}
+
+/// A single recorded host function call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCall {
+    /// The name of the called host function.
+    ///
+    /// This is the import name if the host function was imported by name
+    /// and otherwise the name the host function was defined with.
+    pub name: String,
+    /// The raw bits of the parameters of the call.
+    pub params: Vec<u64>,
+    /// The raw bits of the results of the call.
+    ///
+    /// Empty if the call trapped.
+    pub results: Vec<u64>,
+    /// The growth of linear memories during the call in growth order.
+    ///
+    /// Replayed before the `memory_writes` since those might write to grown pages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_grows: Vec<MemoryGrow>,
+    /// The bytes the call wrote to linear memories in replay order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_writes: Vec<MemoryWrite>,
+    /// The message of the trap the call returned if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trap: Option<String>,
+    /// The exit status if the call exited via [`Trap::i32_exit`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_status: Option<i32>,
+}
+
+/// A log of recorded host function calls in call order.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostCallLog {
+    /// The recorded host function calls.
+    pub calls: Vec<HostCall>,
+}
+
+impl HostCallLog {
+    /// Creates a new empty [`HostCallLog`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the recorded host function calls.
+    pub fn calls(&self) -> &[HostCall] {
+        &self.calls
+    }
+
+    /// Serializes the [`HostCallLog`] to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Deserializes a [`HostCallLog`] from JSON.
+    ///
+    /// # Errors
+    ///
+    /// If `json` is not a valid serialized [`HostCallLog`].
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// How a [`Store`](crate::Store) treats host function calls.
+///
+/// Install it via [`Store::set_host_call_mode`](crate::Store::set_host_call_mode).
+#[derive(Debug, Clone)]
+pub enum HostCallMode {
+    /// Calls the real host functions and records each call into the log.
+    ///
+    /// # Note
+    ///
+    /// Writes of host functions through exclusive borrows of the bytes of a linear
+    /// memory, such as via [`Memory::try_data_mut`] or by WASI functions, cannot be
+    /// tracked. Therefore the first such borrow during a recorded call copies the
+    /// entire linear memory and the copy is compared to the linear memory after the
+    /// call. Shared linear memories are copied before every recorded call.
+    /// This costs time and space linear in the size of the linear memories for
+    /// every recorded call that borrows them.
+    ///
+    /// [`Memory::try_data_mut`]: crate::Memory::try_data_mut
+    Record(HostCallLog),
+    /// Serves host function calls from the log without calling the real host functions.
+    ///
+    /// The `usize` is the index of the next call to be replayed.
+    Replay(HostCallLog, usize),
+}
+
+impl HostCallMode {
+    /// Creates a [`HostCallMode`] recording into an empty [`HostCallLog`].
+    pub fn record() -> Self {
+        Self::Record(HostCallLog::new())
+    }
+
+    /// Creates a [`HostCallMode`] replaying the `log` from its start.
+    pub fn replay(log: HostCallLog) -> Self {
+        Self::Replay(log, 0)
+    }
+
+    /// Returns the recorded or replayed [`HostCallLog`].
+    pub fn log(&self) -> &HostCallLog {
+        match self {
+            Self::Record(log) | Self::Replay(log, _) => log,
+        }
+    }
+
+    /// Consumes `self` and returns the recorded or replayed [`HostCallLog`].
+    pub fn into_log(self) -> HostCallLog {
+        match self {
+            Self::Record(log) | Self::Replay(log, _) => log,
+        }
+    }
+
+    /// Returns the number of calls replayed so far or `None` if recording.
+    pub fn replayed(&self) -> Option<usize> {
+        match self {
+            Self::Record(_) => None,
+            Self::Replay(_, position) => Some(*position),
+        }
+    }
+}
+
+/// Error returned when a replayed execution diverges from its [`HostCallLog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostCallDivergence {
+    /// A host function was called that does not match the next recorded call.
+    Call {
+        /// The index of the diverging call within the [`HostCallLog`].
+        index: usize,
+        /// The call that was expected according to the [`HostCallLog`] if any.
+        expected: Option<HostCall>,
+        /// The name of the host function that was actually called.
+        name: String,
+        /// The raw bits of the parameters the host function was actually called with.
+        params: Vec<u64>,
+    },
+    /// A recorded memory growth cannot be applied to the linear memories of the [`Store`](crate::Store).
+    MemoryGrow {
+        /// The index of the diverging call within the [`HostCallLog`].
+        index: usize,
+        /// The memory growth that could not be applied.
+        grow: MemoryGrow,
+    },
+    /// A recorded memory write does not fit into the linear memories of the [`Store`](crate::Store).
+    MemoryWrite {
+        /// The index of the diverging call within the [`HostCallLog`].
+        index: usize,
+        /// The memory write that could not be applied.
+        write: MemoryWrite,
+    },
+}
+
+impl Display for HostCallDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Call {
+                index,
+                expected: None,
+                name,
+                params,
+            } => write!(
+                f,
+                "host call replay diverged at call {index}: \
+                 unexpected call to `{name}` {params:?} after the end of the log",
+            ),
+            Self::Call {
+                index,
+                expected: Some(expected),
+                name,
+                params,
+            } => write!(
+                f,
+                "host call replay diverged at call {index}: \
+                 expected `{}` {:?} but found `{name}` {params:?}",
+                expected.name, expected.params,
+            ),
+            Self::MemoryGrow { index, grow } => write!(
+                f,
+                "host call replay diverged at call {index}: \
+                 cannot grow memory {} by {} pages",
+                grow.memory, grow.pages,
+            ),
+            Self::MemoryWrite { index, write } => write!(
+                f,
+                "host call replay diverged at call {index}: \
+                 cannot write {} bytes at offset {} of memory {}",
+                write.data.len(),
+                write.offset,
+                write.memory,
+            ),
+        }
+    }
+}
+
+impl HostError for HostCallDivergence {}
+
+/// A host function call in the process of being recorded.
+#[derive(Debug)]
+pub(crate) struct PendingHostCall {
+    /// The name of the called host function.
+    name: String,
+    /// The raw bits of the parameters of the call.
+    params: Vec<u64>,
+    /// The number of linear memories before the call.
+    ///
+    /// Only these linear memories journal their modifications during the call.
+    len_memories: usize,
+}
+
+impl PendingHostCall {
+    /// Starts recording the call of `name` with `params`.
+    ///
+    /// Starts a [`MemoryJournal`] for every linear memory of the `store`.
+    pub fn new(store: &mut StoreInner, name: String, params: &[UntypedValue]) -> Self {
+        let mut len_memories = 0;
+        for memory in store.memory_entities_mut() {
+            memory.start_journal();
+            len_memories += 1;
+        }
+        Self {
+            name,
+            params: to_bits(params),
+            len_memories,
+        }
+    }
+
+    /// Finishes recording the call given its `outcome` and appends it to the `log` if any.
+    ///
+    /// Always stops the [`MemoryJournal`]s started for the call.
+    /// The `results` are only used if the call succeeded.
+    pub fn finish(
+        self,
+        store: &mut StoreInner,
+        outcome: Result<&[UntypedValue], &Trap>,
+        log: Option<&mut HostCallLog>,
+    ) {
+        let mut memory_grows = Vec::new();
+        let mut memory_writes = Vec::new();
+        for (index, memory) in store.memory_entities_mut().enumerate() {
+            let journal = match index < self.len_memories {
+                true => memory.finish_journal(),
+                false => None,
+            };
+            if log.is_none() {
+                continue;
+            }
+            let index = index as u32;
+            // Note: Only shared linear memories need to be copied for the comparison.
+            let bytes = match memory.try_data() {
+                Some(bytes) => Cow::Borrowed(bytes),
+                None => Cow::Owned(memory.to_bytes()),
+            };
+            let Some(journal) = journal else {
+                // Note: Linear memories created during the call have no journal.
+                diff_memory(index, &[], &bytes, &mut memory_writes);
+                continue;
+            };
+            record_journal(index, &journal, &mut memory_grows, &mut memory_writes);
+            if let Some(snapshot) = journal.snapshot_bytes() {
+                diff_memory(index, snapshot, &bytes, &mut memory_writes);
+            }
+        }
+        let Some(log) = log else {
+            return;
+        };
+        let (results, trap, exit_status) = match outcome {
+            Ok(results) => (to_bits(results), None, None),
+            Err(trap) => match trap.i32_exit_status() {
+                Some(status) => (Vec::new(), None, Some(status)),
+                None => (Vec::new(), Some(trap.to_string()), None),
+            },
+        };
+        log.calls.push(HostCall {
+            name: self.name,
+            params: self.params,
+            results,
+            memory_grows,
+            memory_writes,
+            trap,
+            exit_status,
+        });
+    }
+}
+
+/// Appends the growth and writes recorded by the `journal` of the `memory`.
+fn record_journal(
+    memory: u32,
+    journal: &MemoryJournal,
+    grows: &mut Vec<MemoryGrow>,
+    writes: &mut Vec<MemoryWrite>,
+) {
+    grows.extend(journal.grows().iter().map(|&pages| MemoryGrow {
+        memory,
+        pages: u32::from(pages),
+    }));
+    writes.extend(journal.writes().iter().map(|(offset, data)| MemoryWrite {
+        memory,
+        offset: *offset as u32,
+        data: data.clone(),
+    }));
+}
+
+/// Replays the next call of the `log` at `position` for the call of `name` with `params`.
+///
+/// On success applies the recorded memory growth and writes to the `store` and
+/// writes the recorded results into `params_results`.
+///
+/// # Errors
+///
+/// - If the call does not match the next recorded call.
+/// - If the recorded memory growth cannot be applied to the linear memories of the `store`.
+/// - If the recorded memory writes do not fit into the linear memories of the `store`.
+/// - If the recorded call trapped or exited.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn replay_host_call(
+    log: &HostCallLog,
+    position: &mut usize,
+    store: &mut StoreInner,
+    limiter: &mut ResourceLimiterRef<'_>,
+    name: String,
+    params_results: &mut [UntypedValue],
+    len_params: usize,
+    len_results: usize,
+) -> Result<(), Trap> {
+    let index = *position;
+    let params = to_bits(&params_results[..len_params]);
+    let expected = log.calls.get(index);
+    let matches = expected.map_or(false, |call| {
+        let failed = call.trap.is_some() || call.exit_status.is_some();
+        call.name == name && call.params == params && (failed || call.results.len() == len_results)
+    });
+    if !matches {
+        return Err(Trap::from(HostCallDivergence::Call {
+            index,
+            expected: expected.cloned(),
+            name,
+            params,
+        }));
+    }
+    let call = &log.calls[index];
+    *position += 1;
+    for grow in &call.memory_grows {
+        let grown = store
+            .memory_entity_mut(grow.memory)
+            .zip(Pages::new(grow.pages))
+            .map_or(false, |(memory, pages)| memory.grow(pages, limiter).is_ok());
+        if !grown {
+            return Err(Trap::from(HostCallDivergence::MemoryGrow {
+                index,
+                grow: grow.clone(),
+            }));
+        }
+    }
+    for write in &call.memory_writes {
+        let offset = write.offset as usize;
+        let written = store
+            .memory_entity_mut(write.memory)
+            .map_or(false, |memory| memory.write(offset, &write.data).is_ok());
+        if !written {
+            return Err(Trap::from(HostCallDivergence::MemoryWrite {
+                index,
+                write: write.clone(),
+            }));
+        }
+    }
+    if let Some(status) = call.exit_status {
+        return Err(Trap::i32_exit(status));
+    }
+    if let Some(message) = &call.trap {
+        return Err(Trap::new(message.clone()));
+    }
+    for (dst, bits) in params_results.iter_mut().zip(&call.results) {
+        *dst = UntypedValue::from(*bits);
+    }
+    Ok(())
+}
+
+/// Returns the raw bits of the `values`.
+fn to_bits(values: &[UntypedValue]) -> Vec<u64> {
+    values.iter().map(|value| value.to_bits()).collect()
+}
+
+/// Appends the ranges of `after` that differ from `before` to `writes`.
+///
+/// Bytes of `after` beyond the length of `before` are compared against zero
+/// since linear memories are zero initialized when they grow.
+fn diff_memory(memory: u32, before: &[u8], after: &[u8], writes: &mut Vec<MemoryWrite>) {
+    let old = |offset: usize| before.get(offset).copied().unwrap_or(0);
+    let mut offset = 0;
+    while offset < after.len() {
+        if after[offset] == old(offset) {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        while offset < after.len() && after[offset] != old(offset) {
+            offset += 1;
+        }
+        writes.push(MemoryWrite {
+            memory,
+            offset: start as u32,
+            data: after[start..offset].to_vec(),
+        });
+    }
+}
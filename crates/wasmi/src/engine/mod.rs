@@ -1,28 +1,40 @@
 //! The `wasmi` interpreter.
 
 use alloc::{sync::Arc, vec::Vec};
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
-use spin::{Mutex, RwLock};
+use spin::{Mutex, RwLock, RwLockReadGuard};
 
 use wasmi_arena::{ArenaIndex, GuardedEntity};
+use wasmi_core::UntypedValue;
 
 use crate::{
     AsContext,
     AsContextMut,
-    core::{Trap, TrapCode},
+    core::{Trap, TrapCode, WasmBacktrace, WasmFrame},
     Func,
     func::FuncEntity,
+    Exception,
     FuncType,
     StoreContextMut,
 };
-use crate::engine::bytecode::InstrMeta;
+use crate::limits::ResourceLimiterRef;
 use crate::engine::code_map::{InstructionPtr, InstructionsRef};
 
 pub use self::{
-    bytecode::{BranchParams, BranchOffset, DropKeep, Instruction},
-    code_map::FuncBody,
-    config::{Config, FuelConsumptionMode},
+    bytecode::{BranchParams, BranchOffset, DropKeep, InstrMeta, Instruction},
+    code_map::{FuncBody, FuncHeader},
+    config::{Config, FuelConsumptionMode, FuelCosts, InstrCost},
+    debugger::{Breakpoint, DebugAction, DebugContext, Debugger, PauseReason},
+    host_calls::{
+        HostCall,
+        HostCallDivergence,
+        HostCallLog,
+        HostCallMode,
+        MemoryGrow,
+        MemoryWrite,
+    },
+    profiler::{FuncProfile, ProfileWeight, Profiler},
     func_builder::{
         FuncBuilder,
         FuncTranslatorAllocations,
@@ -37,10 +49,11 @@ pub use self::{
 use self::{
     cache::InstanceCache,
     code_map::CodeMap,
+    config::ResolvedFuelCosts,
     executor::{execute_wasm, WasmOutcome},
     func_types::FuncTypeRegistry,
     resumable::ResumableCallBase,
-    stack::{FuncFrame, Stack, ValueStack},
+    stack::{CallStack, FuncFrame, Stack, ValueStack},
 };
 pub(crate) use self::{
     func_args::{FuncFinished, FuncParams, FuncResults},
@@ -51,10 +64,13 @@ pub mod bytecode;
 mod cache;
 pub mod code_map;
 mod config;
+mod debugger;
 pub mod executor;
 mod func_args;
 mod func_builder;
 mod func_types;
+mod host_calls;
+mod profiler;
 mod resumable;
 pub mod stack;
 mod traits;
@@ -133,11 +149,36 @@ impl Engine {
         self.inner.config()
     }
 
+    /// Returns the [`FuelCosts`] of the [`Config`] resolved upon [`Engine`] creation.
+    pub(crate) fn resolved_fuel_costs(&self) -> &ResolvedFuelCosts {
+        &self.inner.fuel_costs
+    }
+
     /// Returns `true` if both [`Engine`] references `a` and `b` refer to the same [`Engine`].
     pub fn same(a: &Engine, b: &Engine) -> bool {
         Arc::ptr_eq(&a.inner, &b.inner)
     }
 
+    /// Increments the epoch of the [`Engine`] by one.
+    ///
+    /// # Note
+    ///
+    /// - Wasm executions trap with [`TrapCode::Interrupted`] once the epoch
+    ///   reaches the deadline of their [`Store`]. This requires epoch based
+    ///   interruption to be enabled via [`Config::epoch_interruption`].
+    /// - This is safe to call concurrently with running Wasm executions,
+    ///   e.g. from another thread holding a clone of the [`Engine`].
+    ///
+    /// [`Store`]: crate::Store
+    pub fn increment_epoch(&self) {
+        self.inner.epoch.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current epoch of the [`Engine`].
+    pub(crate) fn current_epoch(&self) -> u64 {
+        self.inner.epoch.load(Ordering::Relaxed)
+    }
+
     /// Allocates a new function type to the engine.
     pub(super) fn alloc_func_type(&self, func_type: FuncType) -> DedupFuncType {
         self.inner.alloc_func_type(func_type)
@@ -161,6 +202,7 @@ impl Engine {
     /// Returns a [`FuncBody`] reference to the allocated function body.
     pub(super) fn alloc_func_body<I>(
         &self,
+        len_params: usize,
         len_locals: usize,
         max_stack_height: usize,
         insts: I,
@@ -171,7 +213,7 @@ impl Engine {
             I::IntoIter: ExactSizeIterator,
     {
         self.inner
-            .alloc_func_body(len_locals, max_stack_height, insts, metas)
+            .alloc_func_body(len_params, len_locals, max_stack_height, insts, metas)
     }
 
     /// Resolves the [`FuncBody`] to the underlying `wasmi` bytecode instructions.
@@ -190,10 +232,47 @@ impl Engine {
         self.inner.resolve_inst(func_body, index)
     }
 
+    /// Sets the module level `index` and optional `name` of the compiled [`FuncBody`].
+    ///
+    /// These are used by the execution tracer and to capture [`WasmBacktrace`] upon traps.
+    pub(crate) fn set_func_info(&self, func_body: FuncBody, index: u32, name: Option<&str>) {
+        self.inner.set_func_info(func_body, index, name)
+    }
+
+    /// Resolves the [`InstrMeta`] of the instruction at `index` of the compiled [`FuncBody`].
+    ///
+    /// # Panics
+    ///
+    /// If the [`FuncBody`] is invalid for the [`Engine`].
+    #[cfg(test)]
+    pub(crate) fn resolve_meta(&self, func_body: FuncBody, index: usize) -> Option<InstrMeta> {
+        self.inner.resolve_meta(func_body, index)
+    }
+
     pub fn instr_vec(&self, func_body: FuncBody) -> Vec<Instruction> {
         self.inner.instr_vec(func_body)
     }
 
+    /// Returns the [`InstrMeta`] of all instructions of the compiled [`FuncBody`].
+    ///
+    /// The returned [`InstrMeta`] are in the same order as the instructions of [`Engine::instr_vec`].
+    ///
+    /// # Panics
+    ///
+    /// If the [`FuncBody`] is invalid for the [`Engine`].
+    pub fn instr_metas(&self, func_body: FuncBody) -> Vec<InstrMeta> {
+        self.inner.instr_metas(func_body)
+    }
+
+    /// Returns the [`FuncHeader`] of the compiled [`FuncBody`].
+    ///
+    /// # Panics
+    ///
+    /// If the [`FuncBody`] is invalid for the [`Engine`].
+    pub fn func_header(&self, func_body: FuncBody) -> FuncHeader {
+        self.inner.func_header(func_body)
+    }
+
     /// Executes the given [`Func`] with parameters `params`.
     ///
     /// Stores the execution result into `results` upon a successful execution.
@@ -311,6 +390,8 @@ impl Engine {
 pub struct EngineInner {
     /// The [`Config`] of the engine.
     config: Config,
+    /// The [`FuelCosts`] of the [`Config`] resolved for fast lookups.
+    fuel_costs: ResolvedFuelCosts,
     /// Engine resources shared across multiple engine executors.
     res: RwLock<EngineResources>,
     /// Reusable engine stacks for Wasm execution.
@@ -319,6 +400,8 @@ pub struct EngineInner {
     /// operate on. Therefore a Wasm engine is required to provide stacks and
     /// ideally recycles old ones since creation of a new stack is rather expensive.
     stacks: Mutex<EngineStacks>,
+    /// The current epoch used to interrupt Wasm executions.
+    epoch: AtomicU64,
 }
 
 /// The engine's stacks for reuse.
@@ -364,9 +447,11 @@ impl EngineInner {
     /// Creates a new [`EngineInner`] with the given [`Config`].
     fn new(config: &Config) -> Self {
         Self {
-            config: *config,
+            config: config.clone(),
+            fuel_costs: ResolvedFuelCosts::new(config.fuel_costs()),
             res: RwLock::new(EngineResources::new()),
             stacks: Mutex::new(EngineStacks::new(config)),
+            epoch: AtomicU64::new(0),
         }
     }
 
@@ -378,7 +463,7 @@ impl EngineInner {
         self.res.write().func_types.alloc_func_type(func_type)
     }
 
-    fn alloc_func_body<I>(&self, len_locals: usize, max_stack_height: usize, insts: I, metas: Vec<InstrMeta>) -> FuncBody
+    fn alloc_func_body<I>(&self, len_params: usize, len_locals: usize, max_stack_height: usize, insts: I, metas: Vec<InstrMeta>) -> FuncBody
         where
             I: IntoIterator<Item=Instruction>,
             I::IntoIter: ExactSizeIterator,
@@ -386,7 +471,11 @@ impl EngineInner {
         self.res
             .write()
             .code_map
-            .alloc(len_locals, max_stack_height, insts, metas)
+            .alloc(len_params, len_locals, max_stack_height, insts, metas)
+    }
+
+    fn set_func_info(&self, func_body: FuncBody, index: u32, name: Option<&str>) {
+        self.res.write().code_map.set_info(func_body, index, name)
     }
 
     fn resolve_func_type<F, R>(&self, func_type: &DedupFuncType, f: F) -> R
@@ -405,6 +494,15 @@ impl EngineInner {
             .copied()
     }
 
+    #[cfg(test)]
+    fn resolve_meta(&self, func_body: FuncBody, index: usize) -> Option<InstrMeta> {
+        self.res
+            .read()
+            .code_map
+            .get_meta(func_body, index)
+            .copied()
+    }
+
     fn instr_vec(&self, func_body: FuncBody) -> Vec<Instruction> {
         self.res
             .read()
@@ -412,6 +510,20 @@ impl EngineInner {
             .instr_vec(func_body)
     }
 
+    fn instr_metas(&self, func_body: FuncBody) -> Vec<InstrMeta> {
+        self.res
+            .read()
+            .code_map
+            .instr_metas(func_body)
+    }
+
+    fn func_header(&self, func_body: FuncBody) -> FuncHeader {
+        *self.res
+            .read()
+            .code_map
+            .header(func_body)
+    }
+
     fn execute_func<T, Results>(
         &self,
         ctx: StoreContextMut<T>,
@@ -422,9 +534,8 @@ impl EngineInner {
         where
             Results: CallResults,
     {
-        let res = self.res.read();
         let mut stack = self.stacks.lock().reuse_or_new();
-        let results = EngineExecutor::new(&res, &mut stack)
+        let results = EngineExecutor::new(&self.res, &mut stack)
             .execute_func(ctx, func, params, results)
             .map_err(TaggedTrap::into_trap);
         self.stacks.lock().recycle(stack);
@@ -441,9 +552,8 @@ impl EngineInner {
         where
             Results: CallResults,
     {
-        let res = self.res.read();
         let mut stack = self.stacks.lock().reuse_or_new();
-        let results = EngineExecutor::new(&res, &mut stack).execute_func(
+        let results = EngineExecutor::new(&self.res, &mut stack).execute_func(
             ctx.as_context_mut(),
             func,
             params,
@@ -481,9 +591,8 @@ impl EngineInner {
         where
             Results: CallResults,
     {
-        let res = self.res.read();
         let host_func = invocation.host_func();
-        let results = EngineExecutor::new(&res, &mut invocation.stack)
+        let results = EngineExecutor::new(&self.res, &mut invocation.stack)
             .resume_func(ctx, host_func, params, results);
         match results {
             Ok(results) => {
@@ -579,14 +688,19 @@ impl From<TrapCode> for TaggedTrap {
 #[derive(Debug)]
 pub struct EngineExecutor<'engine> {
     /// Shared and reusable generic engine resources.
-    res: &'engine EngineResources,
+    ///
+    /// # Note
+    ///
+    /// The resources are locked for reads during the entire execution
+    /// except while the execution is blocked in `memory.atomic.wait`.
+    res: &'engine RwLock<EngineResources>,
     /// The value and call stacks.
     stack: &'engine mut Stack,
 }
 
 impl<'engine> EngineExecutor<'engine> {
     /// Creates a new [`EngineExecutor`] with the given [`StackLimits`].
-    fn new(res: &'engine EngineResources, stack: &'engine mut Stack) -> Self {
+    fn new(res: &'engine RwLock<EngineResources>, stack: &'engine mut Stack) -> Self {
         Self { res, stack }
     }
 
@@ -609,21 +723,37 @@ impl<'engine> EngineExecutor<'engine> {
         where
             Results: CallResults,
     {
+        let res = self.res.read();
         self.stack.reset();
         self.stack.values.extend(params.call_params());
         let fn_index = ctx.as_context().store.inner.unwrap_stored(func.as_inner()).into_usize();
         match ctx.as_context().store.inner.resolve_func(func) {
             FuncEntity::Wasm(wasm_func) => {
                 self.stack
-                    .prepare_wasm_call(wasm_func, &self.res.code_map)?;
-                let header = self.res.code_map.header(wasm_func.func_body());
+                    .prepare_wasm_call(wasm_func, &res.code_map)?;
+                let func_body = wasm_func.func_body();
+                let header = res.code_map.header(func_body);
+                let fn_name = res.code_map.name(func_body).unwrap_or_default();
                 ctx.as_context_mut().store.tracer.function_call(
                     fn_index as u32,
                     header.max_stack_height(),
                     header.len_locals(),
-                    String::new(),
+                    fn_name.into(),
                 );
-                self.execute_wasm_func(ctx.as_context_mut())?;
+                let store = &mut ctx.as_context_mut().store;
+                let fuel_consumed = store.inner.fuel().fuel_consumed();
+                let depth = store.profiler.as_mut().map(|profiler| {
+                    let depth = profiler.depth();
+                    profiler.enter_wasm(func_body, &res.code_map, fuel_consumed);
+                    depth
+                });
+                let result = self.execute_wasm_func(ctx.as_context_mut(), res);
+                let store = &mut ctx.as_context_mut().store;
+                let fuel_consumed = store.inner.fuel().fuel_consumed();
+                if let (Some(profiler), Some(depth)) = (store.profiler.as_mut(), depth) {
+                    profiler.restore(depth, fuel_consumed);
+                }
+                result?;
             }
             FuncEntity::Host(host_func) => {
                 let host_func = host_func.clone();
@@ -633,10 +763,16 @@ impl<'engine> EngineExecutor<'engine> {
                     host_func.num_params() as usize,
                     host_func.name().clone(),
                 );
+                let store = &mut ctx.as_context_mut().store;
+                let fuel_consumed = store.inner.fuel().fuel_consumed();
+                if let Some(profiler) = store.profiler.as_mut() {
+                    profiler.call_host(host_func.name(), fuel_consumed);
+                }
                 self.stack.call_host_as_root(
                     ctx.as_context_mut(),
+                    func,
                     host_func,
-                    &self.res.func_types,
+                    &res.func_types,
                 )?;
             }
         };
@@ -671,7 +807,8 @@ impl<'engine> EngineExecutor<'engine> {
             self.stack.frames.peek().is_some(),
             "a frame must be on the call stack upon resumption"
         );
-        self.execute_wasm_func(ctx.as_context_mut())?;
+        let res = self.res.read();
+        self.execute_wasm_func(ctx.as_context_mut(), res)?;
         let results = self.write_results_back(results);
         Ok(results)
     }
@@ -695,11 +832,19 @@ impl<'engine> EngineExecutor<'engine> {
 
     /// Executes the top most Wasm function on the [`Stack`] until the [`Stack`] is empty.
     ///
+    /// The engine resources `res` are locked for the entire execution except while the
+    /// execution is blocked in `memory.atomic.wait` so that other threads can still compile
+    /// Wasm modules using the same [`Engine`], for example in order to wake up the execution.
+    ///
     /// # Errors
     ///
     /// When encountering a Wasm or host trap during the execution of `func`.
     #[inline(never)]
-    fn execute_wasm_func<T>(&mut self, mut ctx: StoreContextMut<T>) -> Result<(), TaggedTrap> {
+    fn execute_wasm_func<T>(
+        &mut self,
+        mut ctx: StoreContextMut<T>,
+        mut res: RwLockReadGuard<'engine, EngineResources>,
+    ) -> Result<(), TaggedTrap> {
         let mut cache = self
             .stack
             .frames
@@ -708,7 +853,7 @@ impl<'engine> EngineExecutor<'engine> {
             .map(InstanceCache::from)
             .expect("must have frame on the call stack");
         loop {
-            match self.execute_wasm(ctx.as_context_mut(), &mut cache)? {
+            match self.execute_wasm(ctx.as_context_mut(), &mut cache, &res.code_map)? {
                 WasmOutcome::Return => return Ok(()),
                 WasmOutcome::Call {
                     ref host_func,
@@ -721,16 +866,37 @@ impl<'engine> EngineExecutor<'engine> {
                     };
                     let result = self.stack.call_host_impl(
                         ctx.as_context_mut(),
+                        func,
                         host_func,
                         Some(&instance),
-                        &self.res.func_types,
+                        &res.func_types,
                     );
                     if self.stack.frames.peek().is_some() {
                         // Case: There is a frame on the call stack.
                         //
                         // This is the default case and we can easily make host function
                         // errors return a resumable call handle.
-                        result.map_err(|trap| TaggedTrap::host(*func, trap))?;
+                        //
+                        // Exceptions thrown by the host function are instead propagated
+                        // to the exception handlers of the Wasm caller.
+                        if let Err(trap) = result {
+                            match trap.downcast_ref::<Exception>() {
+                                Some(exception) => self
+                                    .stack
+                                    .frames
+                                    .exceptions_mut()
+                                    .set_pending(exception.clone().into_untyped()),
+                                None => {
+                                    let backtrace = capture_backtrace(
+                                        &self.stack.frames,
+                                        &res.code_map,
+                                        false,
+                                    );
+                                    let trap = trap.with_backtrace(backtrace);
+                                    return Err(TaggedTrap::host(*func, trap));
+                                }
+                            }
+                        }
                     } else {
                         // Case: No frame is on the call stack. (edge case)
                         //
@@ -740,6 +906,21 @@ impl<'engine> EngineExecutor<'engine> {
                         result.map_err(TaggedTrap::Wasm)?;
                     }
                 }
+                WasmOutcome::Wait(waiting) => {
+                    // The instructions of the `CodeMap` move if other threads compile
+                    // Wasm modules while the engine resources are released.
+                    let addresses = res.code_map.addresses();
+                    drop(res);
+                    let result = waiting.park();
+                    res = self.res.read();
+                    if res.code_map.addresses() != addresses {
+                        let code_map = &res.code_map;
+                        self.stack
+                            .frames
+                            .relocate(|ip| code_map.relocate(addresses, ip));
+                    }
+                    self.stack.values.push(UntypedValue::from(u32::from(result)));
+                }
             }
         }
     }
@@ -759,6 +940,7 @@ impl<'engine> EngineExecutor<'engine> {
         &mut self,
         ctx: StoreContextMut<T>,
         cache: &mut InstanceCache,
+        code_map: &CodeMap,
     ) -> Result<WasmOutcome, Trap> {
         /// Converts a [`TrapCode`] into a [`Trap`].
         ///
@@ -766,14 +948,60 @@ impl<'engine> EngineExecutor<'engine> {
         /// annotation has severe effects on performance.
         #[inline]
         #[cold]
-        fn make_trap(code: TrapCode) -> Trap {
-            code.into()
+        fn make_trap(code: TrapCode, call_stack: &CallStack, code_map: &CodeMap) -> Trap {
+            Trap::from(code).with_backtrace(capture_backtrace(call_stack, code_map, true))
         }
 
         let store_inner = &mut ctx.store.inner;
         let value_stack = &mut self.stack.values;
         let call_stack = &mut self.stack.frames;
-        let code_map = &self.res.code_map;
-        execute_wasm(store_inner, cache, value_stack, call_stack, code_map, &mut ctx.store.tracer).map_err(make_trap)
+        execute_wasm(
+            store_inner,
+            cache,
+            value_stack,
+            call_stack,
+            code_map,
+            &mut ctx.store.tracer,
+            &mut ctx.store.debugger,
+            &mut ctx.store.profiler,
+            ResourceLimiterRef::new(&mut ctx.store.limiter, &mut ctx.store.data),
+        )
+        .map_err(|code| match code {
+            TrapCode::UncaughtException => {
+                let exception = call_stack
+                    .exceptions_mut()
+                    .take_pending()
+                    .expect("missing pending exception for uncaught exception trap");
+                let exception = Exception::from_untyped(store_inner, &exception);
+                Trap::from(exception).with_backtrace(capture_backtrace(call_stack, code_map, true))
+            }
+            code => make_trap(code, call_stack, code_map),
+        })
     }
 }
+
+/// Captures the [`WasmBacktrace`] of the function frames on the [`CallStack`].
+///
+/// # Note
+///
+/// If `trapped` is `true` the innermost frame is expected to be the trapping frame
+/// pushed by the executor. All other frames are callers that point past their call
+/// instruction, e.g. the Wasm callers of a trapping host function.
+fn capture_backtrace(call_stack: &CallStack, code_map: &CodeMap, trapped: bool) -> WasmBacktrace {
+    let frames = call_stack
+        .frames()
+        .rev()
+        .enumerate()
+        .filter_map(|(depth, frame)| {
+            let mut ip = frame.ip();
+            if depth != 0 || !trapped {
+                ip.offset(-1);
+            }
+            let func_body = code_map.func_body_at(ip)?;
+            let func_index = code_map.index(func_body)?;
+            let func_name = code_map.name(func_body);
+            Some(WasmFrame::new(func_index, func_name, ip.meta().source_pc()))
+        })
+        .collect();
+    WasmBacktrace::new(frames)
+}
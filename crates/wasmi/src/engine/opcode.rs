@@ -4,201 +4,474 @@ use crate::engine::DropKeep;
 #[derive(Debug, Clone)]
 pub struct OpCode(pub Instruction);
 
+/// Defines [`OpCode::name`], [`OpCode::index`] and [`OpCode::NAMES`] from the name of every instruction.
+macro_rules! define_opcode_names {
+    ( $( $pat:pat => $name:literal, )* ) => {
+        impl OpCode {
+            /// The names of all `wasmi` instructions indexed by [`OpCode::index`].
+            pub const NAMES: &'static [&'static str] = &[$($name),*];
+
+            pub fn name(&self) -> &'static str {
+                use Instruction::*;
+                match self.0 {
+                    $( $pat => $name, )*
+                }
+            }
+
+            /// Returns the index of the instruction name within [`OpCode::NAMES`].
+            pub fn index(&self) -> usize {
+                use Instruction::*;
+                match self.0 {
+                    $( $pat => {
+                        const INDEX: usize = OpCode::const_index_of($name);
+                        INDEX
+                    } )*
+                }
+            }
+        }
+    };
+}
+
+define_opcode_names! {
+    LocalGet { .. } => "local_get",
+    LocalSet { .. } => "local_set",
+    LocalTee { .. } => "local_tee",
+    Br(_) => "br",
+    BrIfEqz(_) => "br_if_eqz",
+    BrIfNez(_) => "br_if_nez",
+    BrTable { .. } => "br_table",
+    Unreachable => "unreachable",
+    ConsumeFuel { .. } => "consume_fuel",
+    Return(_) => "return",
+    ReturnIfNez(_) => "return_if_nez",
+    ReturnCall { .. } => "return_call",
+    ReturnCallIndirect { .. } => "return_call_indirect",
+    Call(_) => "call",
+    CallIndirect { .. } => "call_indirect",
+    Try { .. } => "try",
+    CatchTag { .. } => "catch",
+    Throw(_) => "throw",
+    Rethrow { .. } => "rethrow",
+    Delegate { .. } => "delegate",
+    Drop => "drop",
+    Select => "select",
+    GlobalGet(_) => "global_get",
+    GlobalSet(_) => "global_set",
+    I32Load(_) => "i32_load",
+    I64Load(_) => "i64_load",
+    F32Load(_) => "f32_load",
+    F64Load(_) => "f64_load",
+    I32Load8S(_) => "i32_load8_s",
+    I32Load8U(_) => "i32_load8_u",
+    I32Load16S(_) => "i32_load16_s",
+    I32Load16U(_) => "i32_load16_u",
+    I64Load8S(_) => "i64_load8_s",
+    I64Load8U(_) => "i64_load8_u",
+    I64Load16S(_) => "i64_load16_s",
+    I64Load16U(_) => "i64_load16_u",
+    I64Load32S(_) => "i64_load32_s",
+    I64Load32U(_) => "i64_load32_u",
+    I32Store(_) => "i32_store",
+    I64Store(_) => "i64_store",
+    F32Store(_) => "f32_store",
+    F64Store(_) => "f64_store",
+    I32Store8(_) => "i32_store8",
+    I32Store16(_) => "i32_store16",
+    I64Store8(_) => "i64_store8",
+    I64Store16(_) => "i64_store16",
+    I64Store32(_) => "i64_store32",
+    MemorySize => "memory_size",
+    MemoryGrow => "memory_grow",
+    MemoryFill => "memory_fill",
+    MemoryCopy => "memory_copy",
+    MemoryInit(_) => "memory_init",
+    DataDrop(_) => "data_drop",
+    I32AtomicLoad(_) => "i32_atomic_load",
+    I64AtomicLoad(_) => "i64_atomic_load",
+    I32AtomicLoad8U(_) => "i32_atomic_load8_u",
+    I32AtomicLoad16U(_) => "i32_atomic_load16_u",
+    I64AtomicLoad8U(_) => "i64_atomic_load8_u",
+    I64AtomicLoad16U(_) => "i64_atomic_load16_u",
+    I64AtomicLoad32U(_) => "i64_atomic_load32_u",
+    I32AtomicStore(_) => "i32_atomic_store",
+    I64AtomicStore(_) => "i64_atomic_store",
+    I32AtomicStore8(_) => "i32_atomic_store8",
+    I32AtomicStore16(_) => "i32_atomic_store16",
+    I64AtomicStore8(_) => "i64_atomic_store8",
+    I64AtomicStore16(_) => "i64_atomic_store16",
+    I64AtomicStore32(_) => "i64_atomic_store32",
+    I32AtomicRmwAdd(_) => "i32_atomic_rmw_add",
+    I64AtomicRmwAdd(_) => "i64_atomic_rmw_add",
+    I32AtomicRmw8AddU(_) => "i32_atomic_rmw8_add_u",
+    I32AtomicRmw16AddU(_) => "i32_atomic_rmw16_add_u",
+    I64AtomicRmw8AddU(_) => "i64_atomic_rmw8_add_u",
+    I64AtomicRmw16AddU(_) => "i64_atomic_rmw16_add_u",
+    I64AtomicRmw32AddU(_) => "i64_atomic_rmw32_add_u",
+    I32AtomicRmwSub(_) => "i32_atomic_rmw_sub",
+    I64AtomicRmwSub(_) => "i64_atomic_rmw_sub",
+    I32AtomicRmw8SubU(_) => "i32_atomic_rmw8_sub_u",
+    I32AtomicRmw16SubU(_) => "i32_atomic_rmw16_sub_u",
+    I64AtomicRmw8SubU(_) => "i64_atomic_rmw8_sub_u",
+    I64AtomicRmw16SubU(_) => "i64_atomic_rmw16_sub_u",
+    I64AtomicRmw32SubU(_) => "i64_atomic_rmw32_sub_u",
+    I32AtomicRmwAnd(_) => "i32_atomic_rmw_and",
+    I64AtomicRmwAnd(_) => "i64_atomic_rmw_and",
+    I32AtomicRmw8AndU(_) => "i32_atomic_rmw8_and_u",
+    I32AtomicRmw16AndU(_) => "i32_atomic_rmw16_and_u",
+    I64AtomicRmw8AndU(_) => "i64_atomic_rmw8_and_u",
+    I64AtomicRmw16AndU(_) => "i64_atomic_rmw16_and_u",
+    I64AtomicRmw32AndU(_) => "i64_atomic_rmw32_and_u",
+    I32AtomicRmwOr(_) => "i32_atomic_rmw_or",
+    I64AtomicRmwOr(_) => "i64_atomic_rmw_or",
+    I32AtomicRmw8OrU(_) => "i32_atomic_rmw8_or_u",
+    I32AtomicRmw16OrU(_) => "i32_atomic_rmw16_or_u",
+    I64AtomicRmw8OrU(_) => "i64_atomic_rmw8_or_u",
+    I64AtomicRmw16OrU(_) => "i64_atomic_rmw16_or_u",
+    I64AtomicRmw32OrU(_) => "i64_atomic_rmw32_or_u",
+    I32AtomicRmwXor(_) => "i32_atomic_rmw_xor",
+    I64AtomicRmwXor(_) => "i64_atomic_rmw_xor",
+    I32AtomicRmw8XorU(_) => "i32_atomic_rmw8_xor_u",
+    I32AtomicRmw16XorU(_) => "i32_atomic_rmw16_xor_u",
+    I64AtomicRmw8XorU(_) => "i64_atomic_rmw8_xor_u",
+    I64AtomicRmw16XorU(_) => "i64_atomic_rmw16_xor_u",
+    I64AtomicRmw32XorU(_) => "i64_atomic_rmw32_xor_u",
+    I32AtomicRmwXchg(_) => "i32_atomic_rmw_xchg",
+    I64AtomicRmwXchg(_) => "i64_atomic_rmw_xchg",
+    I32AtomicRmw8XchgU(_) => "i32_atomic_rmw8_xchg_u",
+    I32AtomicRmw16XchgU(_) => "i32_atomic_rmw16_xchg_u",
+    I64AtomicRmw8XchgU(_) => "i64_atomic_rmw8_xchg_u",
+    I64AtomicRmw16XchgU(_) => "i64_atomic_rmw16_xchg_u",
+    I64AtomicRmw32XchgU(_) => "i64_atomic_rmw32_xchg_u",
+    I32AtomicRmwCmpxchg(_) => "i32_atomic_rmw_cmpxchg",
+    I64AtomicRmwCmpxchg(_) => "i64_atomic_rmw_cmpxchg",
+    I32AtomicRmw8CmpxchgU(_) => "i32_atomic_rmw8_cmpxchg_u",
+    I32AtomicRmw16CmpxchgU(_) => "i32_atomic_rmw16_cmpxchg_u",
+    I64AtomicRmw8CmpxchgU(_) => "i64_atomic_rmw8_cmpxchg_u",
+    I64AtomicRmw16CmpxchgU(_) => "i64_atomic_rmw16_cmpxchg_u",
+    I64AtomicRmw32CmpxchgU(_) => "i64_atomic_rmw32_cmpxchg_u",
+    MemoryAtomicNotify(_) => "memory_atomic_notify",
+    MemoryAtomicWait32(_) => "memory_atomic_wait32",
+    MemoryAtomicWait64(_) => "memory_atomic_wait64",
+    AtomicFence => "atomic_fence",
+    TableSize { .. } => "table_size",
+    TableGrow { .. } => "table_grow",
+    TableFill { .. } => "table_fill",
+    TableGet { .. } => "table_get",
+    TableSet { .. } => "table_set",
+    TableCopy { .. } => "table_copy",
+    TableInit { .. } => "table_init",
+    ElemDrop(_) => "elem_drop",
+    RefFunc { .. } => "ref_func",
+    Const(_) => "const",
+    I32Eqz => "i32_eqz",
+    I32Eq => "i32_eq",
+    I32Ne => "i32_ne",
+    I32LtS => "i32_lt_s",
+    I32LtU => "i32_lt_u",
+    I32GtS => "i32_gt_s",
+    I32GtU => "i32_gt_u",
+    I32LeS => "i32_le_s",
+    I32LeU => "i32_le_u",
+    I32GeS => "i32_ge_s",
+    I32GeU => "i32_ge_u",
+    I64Eqz => "i64_eqz",
+    I64Eq => "i64_eq",
+    I64Ne => "i64_ne",
+    I64LtS => "i64_lt_s",
+    I64LtU => "i64_lt_u",
+    I64GtS => "i64_gt_s",
+    I64GtU => "i64_gt_u",
+    I64LeS => "i64_le_s",
+    I64LeU => "i64_le_u",
+    I64GeS => "i64_ge_s",
+    I64GeU => "i64_ge_u",
+    F32Eq => "f32_eq",
+    F32Ne => "f32_ne",
+    F32Lt => "f32_lt",
+    F32Gt => "f32_gt",
+    F32Le => "f32_le",
+    F32Ge => "f32_ge",
+    F64Eq => "f64_eq",
+    F64Ne => "f64_ne",
+    F64Lt => "f64_lt",
+    F64Gt => "f64_gt",
+    F64Le => "f64_le",
+    F64Ge => "f64_ge",
+    I32Clz => "i32_clz",
+    I32Ctz => "i32_ctz",
+    I32Popcnt => "i32_popcnt",
+    I32Add => "i32_add",
+    I32Sub => "i32_sub",
+    I32Mul => "i32_mul",
+    I32DivS => "i32_div_s",
+    I32DivU => "i32_div_u",
+    I32RemS => "i32_rem_s",
+    I32RemU => "i32_rem_u",
+    I32And => "i32_and",
+    I32Or => "i32_or",
+    I32Xor => "i32_xor",
+    I32Shl => "i32_shl",
+    I32ShrS => "i32_shr_s",
+    I32ShrU => "i32_shr_u",
+    I32Rotl => "i32_rotl",
+    I32Rotr => "i32_rotr",
+    I64Clz => "i64_clz",
+    I64Ctz => "i64_ctz",
+    I64Popcnt => "i64_popcnt",
+    I64Add => "i64_add",
+    I64Sub => "i64_sub",
+    I64Mul => "i64_mul",
+    I64DivS => "i64_div_s",
+    I64DivU => "i64_div_u",
+    I64RemS => "i64_rem_s",
+    I64RemU => "i64_rem_u",
+    I64And => "i64_and",
+    I64Or => "i64_or",
+    I64Xor => "i64_xor",
+    I64Shl => "i64_shl",
+    I64ShrS => "i64_shr_s",
+    I64ShrU => "i64_shr_u",
+    I64Rotl => "i64_rotl",
+    I64Rotr => "i64_rotr",
+    F32Abs => "f32_abs",
+    F32Neg => "f32_neg",
+    F32Ceil => "f32_ceil",
+    F32Floor => "f32_floor",
+    F32Trunc => "f32_trunc",
+    F32Nearest => "f32_nearest",
+    F32Sqrt => "f32_sqrt",
+    F32Add => "f32_add",
+    F32Sub => "f32_sub",
+    F32Mul => "f32_mul",
+    F32Div => "f32_div",
+    F32Min => "f32_min",
+    F32Max => "f32_max",
+    F32Copysign => "f32_copysign",
+    F64Abs => "f64_abs",
+    F64Neg => "f64_neg",
+    F64Ceil => "f64_ceil",
+    F64Floor => "f64_floor",
+    F64Trunc => "f64_trunc",
+    F64Nearest => "f64_nearest",
+    F64Sqrt => "f64_sqrt",
+    F64Add => "f64_add",
+    F64Sub => "f64_sub",
+    F64Mul => "f64_mul",
+    F64Div => "f64_div",
+    F64Min => "f64_min",
+    F64Max => "f64_max",
+    F64Copysign => "f64_copysign",
+    I32WrapI64 => "i32_wrap_i64",
+    I32TruncF32S => "i32_trunc_f32_s",
+    I32TruncF32U => "i32_trunc_f32_u",
+    I32TruncF64S => "i32_trunc_f64_s",
+    I32TruncF64U => "i32_trunc_f64_u",
+    I64ExtendI32S => "i64_extend_i32_s",
+    I64ExtendI32U => "i64_extend_i32_u",
+    I64TruncF32S => "i64_trunc_f32_s",
+    I64TruncF32U => "i64_trunc_f32_u",
+    I64TruncF64S => "i64_trunc_f64_s",
+    I64TruncF64U => "i64_trunc_f64_u",
+    F32ConvertI32S => "f32_convert_i32_s",
+    F32ConvertI32U => "f32_convert_i32_u",
+    F32ConvertI64S => "f32_convert_i64_s",
+    F32ConvertI64U => "f32_convert_i64_u",
+    F32DemoteF64 => "f32_demote_f64",
+    F64ConvertI32S => "f64_convert_i32_s",
+    F64ConvertI32U => "f64_convert_i32_u",
+    F64ConvertI64S => "f64_convert_i64_s",
+    F64ConvertI64U => "f64_convert_i64_u",
+    F64PromoteF32 => "f64_promote_f32",
+    I32Extend8S => "i32_extend8_s",
+    I32Extend16S => "i32_extend16_s",
+    I64Extend8S => "i64_extend8_s",
+    I64Extend16S => "i64_extend16_s",
+    I64Extend32S => "i64_extend32_s",
+    I32TruncSatF32S => "i32_trunc_sat_f32_s",
+    I32TruncSatF32U => "i32_trunc_sat_f32_u",
+    I32TruncSatF64S => "i32_trunc_sat_f64_s",
+    I32TruncSatF64U => "i32_trunc_sat_f64_u",
+    I64TruncSatF32S => "i64_trunc_sat_f32_s",
+    I64TruncSatF32U => "i64_trunc_sat_f32_u",
+    I64TruncSatF64S => "i64_trunc_sat_f64_s",
+    I64TruncSatF64U => "i64_trunc_sat_f64_u",
+    LocalCopy { .. } => "local_copy",
+    I32EqLocalLocal { .. } => "i32_eq_local_local",
+    I32EqLocal { .. } => "i32_eq_local",
+    I32NeLocalLocal { .. } => "i32_ne_local_local",
+    I32NeLocal { .. } => "i32_ne_local",
+    I32LtSLocalLocal { .. } => "i32_lt_s_local_local",
+    I32LtSLocal { .. } => "i32_lt_s_local",
+    I32LtULocalLocal { .. } => "i32_lt_u_local_local",
+    I32LtULocal { .. } => "i32_lt_u_local",
+    I32GtSLocalLocal { .. } => "i32_gt_s_local_local",
+    I32GtSLocal { .. } => "i32_gt_s_local",
+    I32GtULocalLocal { .. } => "i32_gt_u_local_local",
+    I32GtULocal { .. } => "i32_gt_u_local",
+    I32LeSLocalLocal { .. } => "i32_le_s_local_local",
+    I32LeSLocal { .. } => "i32_le_s_local",
+    I32LeULocalLocal { .. } => "i32_le_u_local_local",
+    I32LeULocal { .. } => "i32_le_u_local",
+    I32GeSLocalLocal { .. } => "i32_ge_s_local_local",
+    I32GeSLocal { .. } => "i32_ge_s_local",
+    I32GeULocalLocal { .. } => "i32_ge_u_local_local",
+    I32GeULocal { .. } => "i32_ge_u_local",
+    I32AddLocalLocal { .. } => "i32_add_local_local",
+    I32AddLocal { .. } => "i32_add_local",
+    I32SubLocalLocal { .. } => "i32_sub_local_local",
+    I32SubLocal { .. } => "i32_sub_local",
+    I32MulLocalLocal { .. } => "i32_mul_local_local",
+    I32MulLocal { .. } => "i32_mul_local",
+    I32AndLocalLocal { .. } => "i32_and_local_local",
+    I32AndLocal { .. } => "i32_and_local",
+    I32OrLocalLocal { .. } => "i32_or_local_local",
+    I32OrLocal { .. } => "i32_or_local",
+    I32XorLocalLocal { .. } => "i32_xor_local_local",
+    I32XorLocal { .. } => "i32_xor_local",
+    I32ShlLocalLocal { .. } => "i32_shl_local_local",
+    I32ShlLocal { .. } => "i32_shl_local",
+    I32ShrSLocalLocal { .. } => "i32_shr_s_local_local",
+    I32ShrSLocal { .. } => "i32_shr_s_local",
+    I32ShrULocalLocal { .. } => "i32_shr_u_local_local",
+    I32ShrULocal { .. } => "i32_shr_u_local",
+    I32RotlLocalLocal { .. } => "i32_rotl_local_local",
+    I32RotlLocal { .. } => "i32_rotl_local",
+    I32RotrLocalLocal { .. } => "i32_rotr_local_local",
+    I32RotrLocal { .. } => "i32_rotr_local",
+    I64EqLocalLocal { .. } => "i64_eq_local_local",
+    I64EqLocal { .. } => "i64_eq_local",
+    I64NeLocalLocal { .. } => "i64_ne_local_local",
+    I64NeLocal { .. } => "i64_ne_local",
+    I64LtSLocalLocal { .. } => "i64_lt_s_local_local",
+    I64LtSLocal { .. } => "i64_lt_s_local",
+    I64LtULocalLocal { .. } => "i64_lt_u_local_local",
+    I64LtULocal { .. } => "i64_lt_u_local",
+    I64GtSLocalLocal { .. } => "i64_gt_s_local_local",
+    I64GtSLocal { .. } => "i64_gt_s_local",
+    I64GtULocalLocal { .. } => "i64_gt_u_local_local",
+    I64GtULocal { .. } => "i64_gt_u_local",
+    I64LeSLocalLocal { .. } => "i64_le_s_local_local",
+    I64LeSLocal { .. } => "i64_le_s_local",
+    I64LeULocalLocal { .. } => "i64_le_u_local_local",
+    I64LeULocal { .. } => "i64_le_u_local",
+    I64GeSLocalLocal { .. } => "i64_ge_s_local_local",
+    I64GeSLocal { .. } => "i64_ge_s_local",
+    I64GeULocalLocal { .. } => "i64_ge_u_local_local",
+    I64GeULocal { .. } => "i64_ge_u_local",
+    I64AddLocalLocal { .. } => "i64_add_local_local",
+    I64AddLocal { .. } => "i64_add_local",
+    I64SubLocalLocal { .. } => "i64_sub_local_local",
+    I64SubLocal { .. } => "i64_sub_local",
+    I64MulLocalLocal { .. } => "i64_mul_local_local",
+    I64MulLocal { .. } => "i64_mul_local",
+    I64AndLocalLocal { .. } => "i64_and_local_local",
+    I64AndLocal { .. } => "i64_and_local",
+    I64OrLocalLocal { .. } => "i64_or_local_local",
+    I64OrLocal { .. } => "i64_or_local",
+    I64XorLocalLocal { .. } => "i64_xor_local_local",
+    I64XorLocal { .. } => "i64_xor_local",
+    I64ShlLocalLocal { .. } => "i64_shl_local_local",
+    I64ShlLocal { .. } => "i64_shl_local",
+    I64ShrSLocalLocal { .. } => "i64_shr_s_local_local",
+    I64ShrSLocal { .. } => "i64_shr_s_local",
+    I64ShrULocalLocal { .. } => "i64_shr_u_local_local",
+    I64ShrULocal { .. } => "i64_shr_u_local",
+    I64RotlLocalLocal { .. } => "i64_rotl_local_local",
+    I64RotlLocal { .. } => "i64_rotl_local",
+    I64RotrLocalLocal { .. } => "i64_rotr_local_local",
+    I64RotrLocal { .. } => "i64_rotr_local",
+    I32EqImm(_) => "i32_eq_imm",
+    I32NeImm(_) => "i32_ne_imm",
+    I32LtSImm(_) => "i32_lt_s_imm",
+    I32LtUImm(_) => "i32_lt_u_imm",
+    I32GtSImm(_) => "i32_gt_s_imm",
+    I32GtUImm(_) => "i32_gt_u_imm",
+    I32LeSImm(_) => "i32_le_s_imm",
+    I32LeUImm(_) => "i32_le_u_imm",
+    I32GeSImm(_) => "i32_ge_s_imm",
+    I32GeUImm(_) => "i32_ge_u_imm",
+    I32AddImm(_) => "i32_add_imm",
+    I32SubImm(_) => "i32_sub_imm",
+    I32MulImm(_) => "i32_mul_imm",
+    I32AndImm(_) => "i32_and_imm",
+    I32OrImm(_) => "i32_or_imm",
+    I32XorImm(_) => "i32_xor_imm",
+    I32ShlImm(_) => "i32_shl_imm",
+    I32ShrSImm(_) => "i32_shr_s_imm",
+    I32ShrUImm(_) => "i32_shr_u_imm",
+    I32RotlImm(_) => "i32_rotl_imm",
+    I32RotrImm(_) => "i32_rotr_imm",
+    I64EqImm(_) => "i64_eq_imm",
+    I64NeImm(_) => "i64_ne_imm",
+    I64LtSImm(_) => "i64_lt_s_imm",
+    I64LtUImm(_) => "i64_lt_u_imm",
+    I64GtSImm(_) => "i64_gt_s_imm",
+    I64GtUImm(_) => "i64_gt_u_imm",
+    I64LeSImm(_) => "i64_le_s_imm",
+    I64LeUImm(_) => "i64_le_u_imm",
+    I64GeSImm(_) => "i64_ge_s_imm",
+    I64GeUImm(_) => "i64_ge_u_imm",
+    I64AddImm(_) => "i64_add_imm",
+    I64SubImm(_) => "i64_sub_imm",
+    I64MulImm(_) => "i64_mul_imm",
+    I64AndImm(_) => "i64_and_imm",
+    I64OrImm(_) => "i64_or_imm",
+    I64XorImm(_) => "i64_xor_imm",
+    I64ShlImm(_) => "i64_shl_imm",
+    I64ShrSImm(_) => "i64_shr_s_imm",
+    I64ShrUImm(_) => "i64_shr_u_imm",
+    I64RotlImm(_) => "i64_rotl_imm",
+    I64RotrImm(_) => "i64_rotr_imm",
+    BrIfI32Eq(_) => "br_if_i32_eq",
+    BrIfI32Ne(_) => "br_if_i32_ne",
+    BrIfI32LtS(_) => "br_if_i32_lt_s",
+    BrIfI32LtU(_) => "br_if_i32_lt_u",
+    BrIfI32GtS(_) => "br_if_i32_gt_s",
+    BrIfI32GtU(_) => "br_if_i32_gt_u",
+    BrIfI32LeS(_) => "br_if_i32_le_s",
+    BrIfI32LeU(_) => "br_if_i32_le_u",
+    BrIfI32GeS(_) => "br_if_i32_ge_s",
+    BrIfI32GeU(_) => "br_if_i32_ge_u",
+    BrIfI64Eq(_) => "br_if_i64_eq",
+    BrIfI64Ne(_) => "br_if_i64_ne",
+    BrIfI64LtS(_) => "br_if_i64_lt_s",
+    BrIfI64LtU(_) => "br_if_i64_lt_u",
+    BrIfI64GtS(_) => "br_if_i64_gt_s",
+    BrIfI64GtU(_) => "br_if_i64_gt_u",
+    BrIfI64LeS(_) => "br_if_i64_le_s",
+    BrIfI64LeU(_) => "br_if_i64_le_u",
+    BrIfI64GeS(_) => "br_if_i64_ge_s",
+    BrIfI64GeU(_) => "br_if_i64_ge_u",
+}
+
 impl OpCode {
-    pub fn name(&self) -> &'static str {
-        use Instruction::*;
-        match self.0 {
-            LocalGet { .. } => "local_get",
-            LocalSet { .. } => "local_set",
-            LocalTee { .. } => "local_tee",
-            Br(_) => "br",
-            BrIfEqz(_) => "br_if_eqz",
-            BrIfNez(_) => "br_if_nez",
-            BrTable { .. } => "br_table",
-            Unreachable => "unreachable",
-            ConsumeFuel { .. } => "consume_fuel",
-            Return(_) => "return",
-            ReturnIfNez(_) => "return_if_nez",
-            ReturnCall { .. } => "return_call",
-            ReturnCallIndirect { .. } => "return_call_indirect",
-            Call(_) => "call",
-            CallIndirect { .. } => "call_indirect",
-            Drop => "drop",
-            Select => "select",
-            GlobalGet(_) => "global_get",
-            GlobalSet(_) => "global_set",
-            I32Load(_) => "i32_load",
-            I64Load(_) => "i64_load",
-            F32Load(_) => "f32_load",
-            F64Load(_) => "f64_load",
-            I32Load8S(_) => "i32_load8_s",
-            I32Load8U(_) => "i32_load8_u",
-            I32Load16S(_) => "i32_load16_s",
-            I32Load16U(_) => "i32_load16_u",
-            I64Load8S(_) => "i64_load8_s",
-            I64Load8U(_) => "i64_load8_u",
-            I64Load16S(_) => "i64_load16_s",
-            I64Load16U(_) => "i64_load16_u",
-            I64Load32S(_) => "i64_load32_s",
-            I64Load32U(_) => "i64_load32_u",
-            I32Store(_) => "i32_store",
-            I64Store(_) => "i64_store",
-            F32Store(_) => "f32_store",
-            F64Store(_) => "f64_store",
-            I32Store8(_) => "i32_store8",
-            I32Store16(_) => "i32_store16",
-            I64Store8(_) => "i64_store8",
-            I64Store16(_) => "i64_store16",
-            I64Store32(_) => "i64_store32",
-            MemorySize => "memory_size",
-            MemoryGrow => "memory_grow",
-            MemoryFill => "memory_fill",
-            MemoryCopy => "memory_copy",
-            MemoryInit(_) => "memory_init",
-            DataDrop(_) => "data_drop",
-            TableSize { .. } => "table_size",
-            TableGrow { .. } => "table_grow",
-            TableFill { .. } => "table_fill",
-            TableGet { .. } => "table_get",
-            TableSet { .. } => "table_set",
-            TableCopy { .. } => "table_copy",
-            TableInit { .. } => "table_init",
-            ElemDrop(_) => "elem_drop",
-            RefFunc { .. } => "ref_func",
-            Const(_) => "const",
-            I32Eqz => "i32_eqz",
-            I32Eq => "i32_eq",
-            I32Ne => "i32_ne",
-            I32LtS => "i32_lt_s",
-            I32LtU => "i32_lt_u",
-            I32GtS => "i32_gt_s",
-            I32GtU => "i32_gt_u",
-            I32LeS => "i32_le_s",
-            I32LeU => "i32_le_u",
-            I32GeS => "i32_ge_s",
-            I32GeU => "i32_ge_u",
-            I64Eqz => "i64_eqz",
-            I64Eq => "i64_eq",
-            I64Ne => "i64_ne",
-            I64LtS => "i64_lt_s",
-            I64LtU => "i64_lt_u",
-            I64GtS => "i64_gt_s",
-            I64GtU => "i64_gt_u",
-            I64LeS => "i64_le_s",
-            I64LeU => "i64_le_u",
-            I64GeS => "i64_ge_s",
-            I64GeU => "i64_ge_u",
-            F32Eq => "f32_eq",
-            F32Ne => "f32_ne",
-            F32Lt => "f32_lt",
-            F32Gt => "f32_gt",
-            F32Le => "f32_le",
-            F32Ge => "f32_ge",
-            F64Eq => "f64_eq",
-            F64Ne => "f64_ne",
-            F64Lt => "f64_lt",
-            F64Gt => "f64_gt",
-            F64Le => "f64_le",
-            F64Ge => "f64_ge",
-            I32Clz => "i32_clz",
-            I32Ctz => "i32_ctz",
-            I32Popcnt => "i32_popcnt",
-            I32Add => "i32_add",
-            I32Sub => "i32_sub",
-            I32Mul => "i32_mul",
-            I32DivS => "i32_div_s",
-            I32DivU => "i32_div_u",
-            I32RemS => "i32_rem_s",
-            I32RemU => "i32_rem_u",
-            I32And => "i32_and",
-            I32Or => "i32_or",
-            I32Xor => "i32_xor",
-            I32Shl => "i32_shl",
-            I32ShrS => "i32_shr_s",
-            I32ShrU => "i32_shr_u",
-            I32Rotl => "i32_rotl",
-            I32Rotr => "i32_rotr",
-            I64Clz => "i64_clz",
-            I64Ctz => "i64_ctz",
-            I64Popcnt => "i64_popcnt",
-            I64Add => "i64_add",
-            I64Sub => "i64_sub",
-            I64Mul => "i64_mul",
-            I64DivS => "i64_div_s",
-            I64DivU => "i64_div_u",
-            I64RemS => "i64_rem_s",
-            I64RemU => "i64_rem_u",
-            I64And => "i64_and",
-            I64Or => "i64_or",
-            I64Xor => "i64_xor",
-            I64Shl => "i64_shl",
-            I64ShrS => "i64_shr_s",
-            I64ShrU => "i64_shr_u",
-            I64Rotl => "i64_rotl",
-            I64Rotr => "i64_rotr",
-            F32Abs => "f32_abs",
-            F32Neg => "f32_neg",
-            F32Ceil => "f32_ceil",
-            F32Floor => "f32_floor",
-            F32Trunc => "f32_trunc",
-            F32Nearest => "f32_nearest",
-            F32Sqrt => "f32_sqrt",
-            F32Add => "f32_add",
-            F32Sub => "f32_sub",
-            F32Mul => "f32_mul",
-            F32Div => "f32_div",
-            F32Min => "f32_min",
-            F32Max => "f32_max",
-            F32Copysign => "f32_copysign",
-            F64Abs => "f64_abs",
-            F64Neg => "f64_neg",
-            F64Ceil => "f64_ceil",
-            F64Floor => "f64_floor",
-            F64Trunc => "f64_trunc",
-            F64Nearest => "f64_nearest",
-            F64Sqrt => "f64_sqrt",
-            F64Add => "f64_add",
-            F64Sub => "f64_sub",
-            F64Mul => "f64_mul",
-            F64Div => "f64_div",
-            F64Min => "f64_min",
-            F64Max => "f64_max",
-            F64Copysign => "f64_copysign",
-            I32WrapI64 => "i32_wrap_i64",
-            I32TruncF32S => "i32_trunc_f32_s",
-            I32TruncF32U => "i32_trunc_f32_u",
-            I32TruncF64S => "i32_trunc_f64_s",
-            I32TruncF64U => "i32_trunc_f64_u",
-            I64ExtendI32S => "i64_extend_i32_s",
-            I64ExtendI32U => "i64_extend_i32_u",
-            I64TruncF32S => "i64_trunc_f32_s",
-            I64TruncF32U => "i64_trunc_f32_u",
-            I64TruncF64S => "i64_trunc_f64_s",
-            I64TruncF64U => "i64_trunc_f64_u",
-            F32ConvertI32S => "f32_convert_i32_s",
-            F32ConvertI32U => "f32_convert_i32_u",
-            F32ConvertI64S => "f32_convert_i64_s",
-            F32ConvertI64U => "f32_convert_i64_u",
-            F32DemoteF64 => "f32_demote_f64",
-            F64ConvertI32S => "f64_convert_i32_s",
-            F64ConvertI32U => "f64_convert_i32_u",
-            F64ConvertI64S => "f64_convert_i64_s",
-            F64ConvertI64U => "f64_convert_i64_u",
-            F64PromoteF32 => "f64_promote_f32",
-            I32Extend8S => "i32_extend8_s",
-            I32Extend16S => "i32_extend16_s",
-            I64Extend8S => "i64_extend8_s",
-            I64Extend16S => "i64_extend16_s",
-            I64Extend32S => "i64_extend32_s",
-            I32TruncSatF32S => "i32_trunc_sat_f32_s",
-            I32TruncSatF32U => "i32_trunc_sat_f32_u",
-            I32TruncSatF64S => "i32_trunc_sat_f64_s",
-            I32TruncSatF64U => "i32_trunc_sat_f64_u",
-            I64TruncSatF32S => "i64_trunc_sat_f32_s",
-            I64TruncSatF32U => "i64_trunc_sat_f32_u",
-            I64TruncSatF64S => "i64_trunc_sat_f64_s",
-            I64TruncSatF64U => "i64_trunc_sat_f64_u",
+    /// Returns the index of the instruction `name` within [`OpCode::NAMES`] if any.
+    pub fn index_of(name: &str) -> Option<usize> {
+        Self::NAMES.iter().position(|known| *known == name)
+    }
+
+    /// Returns the index of the instruction `name` within [`OpCode::NAMES`].
+    ///
+    /// # Panics
+    ///
+    /// If `name` is not the name of an instruction.
+    const fn const_index_of(name: &str) -> usize {
+        let name = name.as_bytes();
+        let mut index = 0;
+        while index < Self::NAMES.len() {
+            let known = Self::NAMES[index].as_bytes();
+            if known.len() == name.len() {
+                let mut n = 0;
+                while n < name.len() && known[n] == name[n] {
+                    n += 1;
+                }
+                if n == name.len() {
+                    return index;
+                }
+            }
+            index += 1;
         }
+        panic!("unknown instruction name")
     }
 
     pub fn params(&self) -> Option<Vec<u64>> {
@@ -206,15 +479,167 @@ impl OpCode {
             Instruction::LocalGet { local_depth } |
             Instruction::LocalSet { local_depth } |
             Instruction::LocalTee { local_depth } => vec![local_depth.into_inner() as u64],
+            Instruction::LocalCopy { result, input } => vec![result.into_inner() as u64, input.into_inner() as u64],
+            Instruction::I32EqLocalLocal { lhs, rhs } |
+            Instruction::I32NeLocalLocal { lhs, rhs } |
+            Instruction::I32LtSLocalLocal { lhs, rhs } |
+            Instruction::I32LtULocalLocal { lhs, rhs } |
+            Instruction::I32GtSLocalLocal { lhs, rhs } |
+            Instruction::I32GtULocalLocal { lhs, rhs } |
+            Instruction::I32LeSLocalLocal { lhs, rhs } |
+            Instruction::I32LeULocalLocal { lhs, rhs } |
+            Instruction::I32GeSLocalLocal { lhs, rhs } |
+            Instruction::I32GeULocalLocal { lhs, rhs } |
+            Instruction::I32AddLocalLocal { lhs, rhs } |
+            Instruction::I32SubLocalLocal { lhs, rhs } |
+            Instruction::I32MulLocalLocal { lhs, rhs } |
+            Instruction::I32AndLocalLocal { lhs, rhs } |
+            Instruction::I32OrLocalLocal { lhs, rhs } |
+            Instruction::I32XorLocalLocal { lhs, rhs } |
+            Instruction::I32ShlLocalLocal { lhs, rhs } |
+            Instruction::I32ShrSLocalLocal { lhs, rhs } |
+            Instruction::I32ShrULocalLocal { lhs, rhs } |
+            Instruction::I32RotlLocalLocal { lhs, rhs } |
+            Instruction::I32RotrLocalLocal { lhs, rhs } |
+            Instruction::I64EqLocalLocal { lhs, rhs } |
+            Instruction::I64NeLocalLocal { lhs, rhs } |
+            Instruction::I64LtSLocalLocal { lhs, rhs } |
+            Instruction::I64LtULocalLocal { lhs, rhs } |
+            Instruction::I64GtSLocalLocal { lhs, rhs } |
+            Instruction::I64GtULocalLocal { lhs, rhs } |
+            Instruction::I64LeSLocalLocal { lhs, rhs } |
+            Instruction::I64LeULocalLocal { lhs, rhs } |
+            Instruction::I64GeSLocalLocal { lhs, rhs } |
+            Instruction::I64GeULocalLocal { lhs, rhs } |
+            Instruction::I64AddLocalLocal { lhs, rhs } |
+            Instruction::I64SubLocalLocal { lhs, rhs } |
+            Instruction::I64MulLocalLocal { lhs, rhs } |
+            Instruction::I64AndLocalLocal { lhs, rhs } |
+            Instruction::I64OrLocalLocal { lhs, rhs } |
+            Instruction::I64XorLocalLocal { lhs, rhs } |
+            Instruction::I64ShlLocalLocal { lhs, rhs } |
+            Instruction::I64ShrSLocalLocal { lhs, rhs } |
+            Instruction::I64ShrULocalLocal { lhs, rhs } |
+            Instruction::I64RotlLocalLocal { lhs, rhs } |
+            Instruction::I64RotrLocalLocal { lhs, rhs } => vec![lhs.into_inner() as u64, rhs.into_inner() as u64],
+            Instruction::I32EqLocal { rhs } |
+            Instruction::I32NeLocal { rhs } |
+            Instruction::I32LtSLocal { rhs } |
+            Instruction::I32LtULocal { rhs } |
+            Instruction::I32GtSLocal { rhs } |
+            Instruction::I32GtULocal { rhs } |
+            Instruction::I32LeSLocal { rhs } |
+            Instruction::I32LeULocal { rhs } |
+            Instruction::I32GeSLocal { rhs } |
+            Instruction::I32GeULocal { rhs } |
+            Instruction::I32AddLocal { rhs } |
+            Instruction::I32SubLocal { rhs } |
+            Instruction::I32MulLocal { rhs } |
+            Instruction::I32AndLocal { rhs } |
+            Instruction::I32OrLocal { rhs } |
+            Instruction::I32XorLocal { rhs } |
+            Instruction::I32ShlLocal { rhs } |
+            Instruction::I32ShrSLocal { rhs } |
+            Instruction::I32ShrULocal { rhs } |
+            Instruction::I32RotlLocal { rhs } |
+            Instruction::I32RotrLocal { rhs } |
+            Instruction::I64EqLocal { rhs } |
+            Instruction::I64NeLocal { rhs } |
+            Instruction::I64LtSLocal { rhs } |
+            Instruction::I64LtULocal { rhs } |
+            Instruction::I64GtSLocal { rhs } |
+            Instruction::I64GtULocal { rhs } |
+            Instruction::I64LeSLocal { rhs } |
+            Instruction::I64LeULocal { rhs } |
+            Instruction::I64GeSLocal { rhs } |
+            Instruction::I64GeULocal { rhs } |
+            Instruction::I64AddLocal { rhs } |
+            Instruction::I64SubLocal { rhs } |
+            Instruction::I64MulLocal { rhs } |
+            Instruction::I64AndLocal { rhs } |
+            Instruction::I64OrLocal { rhs } |
+            Instruction::I64XorLocal { rhs } |
+            Instruction::I64ShlLocal { rhs } |
+            Instruction::I64ShrSLocal { rhs } |
+            Instruction::I64ShrULocal { rhs } |
+            Instruction::I64RotlLocal { rhs } |
+            Instruction::I64RotrLocal { rhs } => vec![rhs.into_inner() as u64],
+            Instruction::I32EqImm(rhs) |
+            Instruction::I32NeImm(rhs) |
+            Instruction::I32LtSImm(rhs) |
+            Instruction::I32LtUImm(rhs) |
+            Instruction::I32GtSImm(rhs) |
+            Instruction::I32GtUImm(rhs) |
+            Instruction::I32LeSImm(rhs) |
+            Instruction::I32LeUImm(rhs) |
+            Instruction::I32GeSImm(rhs) |
+            Instruction::I32GeUImm(rhs) |
+            Instruction::I32AddImm(rhs) |
+            Instruction::I32SubImm(rhs) |
+            Instruction::I32MulImm(rhs) |
+            Instruction::I32AndImm(rhs) |
+            Instruction::I32OrImm(rhs) |
+            Instruction::I32XorImm(rhs) |
+            Instruction::I32ShlImm(rhs) |
+            Instruction::I32ShrSImm(rhs) |
+            Instruction::I32ShrUImm(rhs) |
+            Instruction::I32RotlImm(rhs) |
+            Instruction::I32RotrImm(rhs) |
+            Instruction::I64EqImm(rhs) |
+            Instruction::I64NeImm(rhs) |
+            Instruction::I64LtSImm(rhs) |
+            Instruction::I64LtUImm(rhs) |
+            Instruction::I64GtSImm(rhs) |
+            Instruction::I64GtUImm(rhs) |
+            Instruction::I64LeSImm(rhs) |
+            Instruction::I64LeUImm(rhs) |
+            Instruction::I64GeSImm(rhs) |
+            Instruction::I64GeUImm(rhs) |
+            Instruction::I64AddImm(rhs) |
+            Instruction::I64SubImm(rhs) |
+            Instruction::I64MulImm(rhs) |
+            Instruction::I64AndImm(rhs) |
+            Instruction::I64OrImm(rhs) |
+            Instruction::I64XorImm(rhs) |
+            Instruction::I64ShlImm(rhs) |
+            Instruction::I64ShrSImm(rhs) |
+            Instruction::I64ShrUImm(rhs) |
+            Instruction::I64RotlImm(rhs) |
+            Instruction::I64RotrImm(rhs) => vec![rhs.to_bits()],
             Instruction::Br(bp) |
             Instruction::BrIfEqz(bp) |
-            Instruction::BrIfNez(bp) => vec![bp.offset().into_i32() as u64],
+            Instruction::BrIfNez(bp) |
+            Instruction::BrIfI32Eq(bp) |
+            Instruction::BrIfI32Ne(bp) |
+            Instruction::BrIfI32LtS(bp) |
+            Instruction::BrIfI32LtU(bp) |
+            Instruction::BrIfI32GtS(bp) |
+            Instruction::BrIfI32GtU(bp) |
+            Instruction::BrIfI32LeS(bp) |
+            Instruction::BrIfI32LeU(bp) |
+            Instruction::BrIfI32GeS(bp) |
+            Instruction::BrIfI32GeU(bp) |
+            Instruction::BrIfI64Eq(bp) |
+            Instruction::BrIfI64Ne(bp) |
+            Instruction::BrIfI64LtS(bp) |
+            Instruction::BrIfI64LtU(bp) |
+            Instruction::BrIfI64GtS(bp) |
+            Instruction::BrIfI64GtU(bp) |
+            Instruction::BrIfI64LeS(bp) |
+            Instruction::BrIfI64LeU(bp) |
+            Instruction::BrIfI64GeS(bp) |
+            Instruction::BrIfI64GeU(bp) => vec![bp.offset().into_i32() as u64],
             Instruction::BrTable { len_targets } => vec![len_targets as u64],
             Instruction::ConsumeFuel { amount } => vec![amount as u64],
             Instruction::ReturnCall { func, .. } => vec![func.into_inner() as u64],
             Instruction::ReturnCallIndirect { table, func_type, .. } => vec![table.into_inner() as u64, func_type.into_inner() as u64],
             Instruction::Call(func) => vec![func.into_inner() as u64],
             Instruction::CallIndirect { table, func_type } => vec![table.into_inner() as u64, func_type.into_inner() as u64],
+            Instruction::Try { landing, end, len_params } => vec![landing.into_i32() as u64, end.into_i32() as u64, len_params as u64],
+            Instruction::CatchTag { tag, next } => vec![tag.into_inner() as u64, next.into_i32() as u64],
+            Instruction::Throw(tag) => vec![tag.into_inner() as u64],
+            Instruction::Rethrow { landing } => vec![landing.into_i32() as u64],
+            Instruction::Delegate { skip } => vec![skip as u64],
             Instruction::GlobalGet(global_index) |
             Instruction::GlobalSet(global_index) => vec![global_index.into_inner() as u64],
             Instruction::I32Load(offset) |
@@ -239,7 +664,73 @@ impl OpCode {
             Instruction::I32Store16(offset) |
             Instruction::I64Store8(offset) |
             Instruction::I64Store16(offset) |
-            Instruction::I64Store32(offset) => vec![offset.into_inner() as u64],
+            Instruction::I64Store32(offset) |
+            Instruction::I32AtomicLoad(offset) |
+            Instruction::I64AtomicLoad(offset) |
+            Instruction::I32AtomicLoad8U(offset) |
+            Instruction::I32AtomicLoad16U(offset) |
+            Instruction::I64AtomicLoad8U(offset) |
+            Instruction::I64AtomicLoad16U(offset) |
+            Instruction::I64AtomicLoad32U(offset) |
+            Instruction::I32AtomicStore(offset) |
+            Instruction::I64AtomicStore(offset) |
+            Instruction::I32AtomicStore8(offset) |
+            Instruction::I32AtomicStore16(offset) |
+            Instruction::I64AtomicStore8(offset) |
+            Instruction::I64AtomicStore16(offset) |
+            Instruction::I64AtomicStore32(offset) |
+            Instruction::I32AtomicRmwAdd(offset) |
+            Instruction::I64AtomicRmwAdd(offset) |
+            Instruction::I32AtomicRmw8AddU(offset) |
+            Instruction::I32AtomicRmw16AddU(offset) |
+            Instruction::I64AtomicRmw8AddU(offset) |
+            Instruction::I64AtomicRmw16AddU(offset) |
+            Instruction::I64AtomicRmw32AddU(offset) |
+            Instruction::I32AtomicRmwSub(offset) |
+            Instruction::I64AtomicRmwSub(offset) |
+            Instruction::I32AtomicRmw8SubU(offset) |
+            Instruction::I32AtomicRmw16SubU(offset) |
+            Instruction::I64AtomicRmw8SubU(offset) |
+            Instruction::I64AtomicRmw16SubU(offset) |
+            Instruction::I64AtomicRmw32SubU(offset) |
+            Instruction::I32AtomicRmwAnd(offset) |
+            Instruction::I64AtomicRmwAnd(offset) |
+            Instruction::I32AtomicRmw8AndU(offset) |
+            Instruction::I32AtomicRmw16AndU(offset) |
+            Instruction::I64AtomicRmw8AndU(offset) |
+            Instruction::I64AtomicRmw16AndU(offset) |
+            Instruction::I64AtomicRmw32AndU(offset) |
+            Instruction::I32AtomicRmwOr(offset) |
+            Instruction::I64AtomicRmwOr(offset) |
+            Instruction::I32AtomicRmw8OrU(offset) |
+            Instruction::I32AtomicRmw16OrU(offset) |
+            Instruction::I64AtomicRmw8OrU(offset) |
+            Instruction::I64AtomicRmw16OrU(offset) |
+            Instruction::I64AtomicRmw32OrU(offset) |
+            Instruction::I32AtomicRmwXor(offset) |
+            Instruction::I64AtomicRmwXor(offset) |
+            Instruction::I32AtomicRmw8XorU(offset) |
+            Instruction::I32AtomicRmw16XorU(offset) |
+            Instruction::I64AtomicRmw8XorU(offset) |
+            Instruction::I64AtomicRmw16XorU(offset) |
+            Instruction::I64AtomicRmw32XorU(offset) |
+            Instruction::I32AtomicRmwXchg(offset) |
+            Instruction::I64AtomicRmwXchg(offset) |
+            Instruction::I32AtomicRmw8XchgU(offset) |
+            Instruction::I32AtomicRmw16XchgU(offset) |
+            Instruction::I64AtomicRmw8XchgU(offset) |
+            Instruction::I64AtomicRmw16XchgU(offset) |
+            Instruction::I64AtomicRmw32XchgU(offset) |
+            Instruction::I32AtomicRmwCmpxchg(offset) |
+            Instruction::I64AtomicRmwCmpxchg(offset) |
+            Instruction::I32AtomicRmw8CmpxchgU(offset) |
+            Instruction::I32AtomicRmw16CmpxchgU(offset) |
+            Instruction::I64AtomicRmw8CmpxchgU(offset) |
+            Instruction::I64AtomicRmw16CmpxchgU(offset) |
+            Instruction::I64AtomicRmw32CmpxchgU(offset) |
+            Instruction::MemoryAtomicNotify(offset) |
+            Instruction::MemoryAtomicWait32(offset) |
+            Instruction::MemoryAtomicWait64(offset) => vec![offset.into_inner() as u64],
             Instruction::MemoryInit(data_segment) |
             Instruction::DataDrop(data_segment) => vec![data_segment.into_inner() as u64],
             Instruction::TableSize { table } |
@@ -262,7 +753,27 @@ impl OpCode {
             // branch param
             Instruction::Br(bp) |
             Instruction::BrIfEqz(bp) |
-            Instruction::BrIfNez(bp) => Some(bp.drop_keep()),
+            Instruction::BrIfNez(bp) |
+            Instruction::BrIfI32Eq(bp) |
+            Instruction::BrIfI32Ne(bp) |
+            Instruction::BrIfI32LtS(bp) |
+            Instruction::BrIfI32LtU(bp) |
+            Instruction::BrIfI32GtS(bp) |
+            Instruction::BrIfI32GtU(bp) |
+            Instruction::BrIfI32LeS(bp) |
+            Instruction::BrIfI32LeU(bp) |
+            Instruction::BrIfI32GeS(bp) |
+            Instruction::BrIfI32GeU(bp) |
+            Instruction::BrIfI64Eq(bp) |
+            Instruction::BrIfI64Ne(bp) |
+            Instruction::BrIfI64LtS(bp) |
+            Instruction::BrIfI64LtU(bp) |
+            Instruction::BrIfI64GtS(bp) |
+            Instruction::BrIfI64GtU(bp) |
+            Instruction::BrIfI64LeS(bp) |
+            Instruction::BrIfI64LeU(bp) |
+            Instruction::BrIfI64GeS(bp) |
+            Instruction::BrIfI64GeU(bp) => Some(bp.drop_keep()),
             // drop keep
             Instruction::Return(drop_keep) |
             Instruction::ReturnIfNez(drop_keep) => Some(drop_keep),
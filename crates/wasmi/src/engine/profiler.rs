@@ -0,0 +1,426 @@
+//! Instruction count and fuel profiling of Wasm executions.
+
+use super::{
+    bytecode::Instruction,
+    code_map::{CodeMap, FuncBody},
+    opcode::OpCode,
+};
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{cmp::Reverse, fmt::Write};
+use wasmi_arena::ArenaIndex;
+
+/// The weight of call paths in the folded stack output of the [`Profiler`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ProfileWeight {
+    /// Weight call paths by their number of executed instructions.
+    Instructions,
+    /// Weight call paths by their amount of consumed fuel.
+    Fuel,
+}
+
+/// The aggregated profile of a single function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuncProfile {
+    /// The name of the function.
+    name: String,
+    /// The index of the function within its module or `None` for host functions.
+    index: Option<u32>,
+    /// The number of calls to the function.
+    calls: u64,
+    /// The number of instructions executed by the function itself.
+    self_instrs: u64,
+    /// The number of instructions executed by the function and all its callees.
+    total_instrs: u64,
+    /// The amount of fuel consumed by the function itself.
+    self_fuel: u64,
+    /// The amount of fuel consumed by the function and all its callees.
+    total_fuel: u64,
+}
+
+impl FuncProfile {
+    /// Returns the name of the function.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the index of the function within its module or `None` for host functions.
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    /// Returns the number of calls to the function.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Returns the number of instructions executed by the function itself.
+    pub fn self_instrs(&self) -> u64 {
+        self.self_instrs
+    }
+
+    /// Returns the number of instructions executed by the function and all its callees.
+    pub fn total_instrs(&self) -> u64 {
+        self.total_instrs
+    }
+
+    /// Returns the amount of fuel consumed by the function itself.
+    pub fn self_fuel(&self) -> u64 {
+        self.self_fuel
+    }
+
+    /// Returns the amount of fuel consumed by the function and all its callees.
+    pub fn total_fuel(&self) -> u64 {
+        self.total_fuel
+    }
+}
+
+/// A function known to the [`Profiler`].
+#[derive(Debug)]
+struct ProfiledFunc {
+    /// The name of the function.
+    name: String,
+    /// The index of the function within its module or `None` for host functions.
+    index: Option<u32>,
+}
+
+/// A node of the call tree of the [`Profiler`].
+#[derive(Debug)]
+struct CallNode {
+    /// The profiled function of the node or `None` for the root node.
+    func: Option<usize>,
+    /// The parent node or `None` for the root node.
+    parent: Option<usize>,
+    /// The child nodes indexed by their profiled function.
+    children: BTreeMap<usize, usize>,
+    /// The number of calls along the call path of the node.
+    calls: u64,
+    /// The number of instructions executed by the node itself.
+    instrs: u64,
+    /// The amount of fuel consumed by the node itself.
+    fuel: u64,
+}
+
+impl CallNode {
+    /// Creates a new [`CallNode`] for `func` called by `parent`.
+    fn new(func: Option<usize>, parent: Option<usize>) -> Self {
+        Self {
+            func,
+            parent,
+            children: BTreeMap::new(),
+            calls: 0,
+            instrs: 0,
+            fuel: 0,
+        }
+    }
+}
+
+/// The index of the root node of the call tree.
+const ROOT: usize = 0;
+
+/// A profiler that aggregates executed instructions and consumed fuel
+/// of Wasm executions of a [`Store`] per function and per call path.
+///
+/// Install it via [`Store::set_profiler`]. The call paths are tracked via the
+/// function call and return events of the executor so that the profile can be
+/// emitted as folded stacks for flamegraph tooling via [`Profiler::folded`].
+///
+/// # Sampling
+///
+/// By default every executed instruction is counted. With a sample interval of `n`
+/// only every `n`-th instruction is recorded with a weight of `n` which makes
+/// instruction counts and the opcode histogram estimates. Call counts are always exact.
+///
+/// [`Store`]: crate::Store
+/// [`Store::set_profiler`]: crate::Store::set_profiler
+#[derive(Debug)]
+pub struct Profiler {
+    /// The number of instructions between two recorded samples.
+    interval: u64,
+    /// The number of instructions until the next recorded sample.
+    countdown: u64,
+    /// All functions seen by the profiler.
+    funcs: Vec<ProfiledFunc>,
+    /// The profiled functions of Wasm function bodies.
+    wasm_funcs: BTreeMap<usize, usize>,
+    /// The profiled functions of host functions by name.
+    host_funcs: BTreeMap<String, usize>,
+    /// The nodes of the call tree with the root node at index 0.
+    nodes: Vec<CallNode>,
+    /// The call path of the current execution.
+    stack: Vec<usize>,
+    /// The number of recorded instructions per opcode.
+    opcodes: BTreeMap<&'static str, u64>,
+    /// The amount of consumed fuel of the store seen at the last event if any.
+    fuel_consumed: Option<u64>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Profiler {
+    /// Creates a new [`Profiler`] that counts every executed instruction.
+    pub fn new() -> Self {
+        Self {
+            interval: 1,
+            countdown: 1,
+            funcs: Vec::new(),
+            wasm_funcs: BTreeMap::new(),
+            host_funcs: BTreeMap::new(),
+            nodes: Vec::from([CallNode::new(None, None)]),
+            stack: Vec::from([ROOT]),
+            opcodes: BTreeMap::new(),
+            fuel_consumed: None,
+        }
+    }
+
+    /// Creates a new [`Profiler`] that records only every `interval`-th executed instruction.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero.
+    pub fn sampling(interval: u64) -> Self {
+        assert!(interval != 0, "profiler sample interval must not be zero");
+        Self {
+            interval,
+            countdown: interval,
+            ..Self::new()
+        }
+    }
+
+    /// Returns the number of instructions between two recorded samples.
+    pub fn sample_interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Discards the recorded profile.
+    pub fn reset(&mut self) {
+        *self = Self {
+            interval: self.interval,
+            countdown: self.interval,
+            fuel_consumed: self.fuel_consumed,
+            ..Self::new()
+        };
+    }
+
+    /// Returns the current depth of the call path.
+    pub(crate) fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns to the call path at `depth` after a root execution finished or trapped.
+    pub(crate) fn restore(&mut self, depth: usize, fuel_consumed: u64) {
+        self.flush_fuel(fuel_consumed);
+        self.stack.truncate(depth.max(1));
+    }
+
+    /// Records a call to the Wasm function of `func_body` and enters it.
+    pub(crate) fn enter_wasm(
+        &mut self,
+        func_body: FuncBody,
+        code_map: &CodeMap,
+        fuel_consumed: u64,
+    ) {
+        self.flush_fuel(fuel_consumed);
+        let func = match self.wasm_funcs.get(&func_body.into_usize()) {
+            Some(func) => *func,
+            None => {
+                let index = code_map.index(func_body);
+                let name = match (code_map.name(func_body), index) {
+                    (Some(name), _) => name.to_string(),
+                    (None, Some(index)) => format!("<wasm function {index}>"),
+                    (None, None) => String::from("<wasm function>"),
+                };
+                let func = self.push_func(name, index);
+                self.wasm_funcs.insert(func_body.into_usize(), func);
+                func
+            }
+        };
+        let node = self.call(func);
+        self.stack.push(node);
+    }
+
+    /// Records a call to the host function with `name`.
+    ///
+    /// Host functions do not execute instructions and therefore are not entered.
+    pub(crate) fn call_host(&mut self, name: &str, fuel_consumed: u64) {
+        self.flush_fuel(fuel_consumed);
+        let name = if name.is_empty() {
+            "<host function>"
+        } else {
+            name
+        };
+        let func = match self.host_funcs.get(name) {
+            Some(func) => *func,
+            None => {
+                let func = self.push_func(name.to_string(), None);
+                self.host_funcs.insert(name.to_string(), func);
+                func
+            }
+        };
+        self.call(func);
+    }
+
+    /// Leaves the `n` innermost functions of the call path upon returns or unwinding.
+    pub(crate) fn leave(&mut self, n: usize, fuel_consumed: u64) {
+        self.flush_fuel(fuel_consumed);
+        let len = self.stack.len().saturating_sub(n).max(1);
+        self.stack.truncate(len);
+    }
+
+    /// Records the execution of `instr` by the innermost function of the call path.
+    #[inline]
+    pub(crate) fn instr(&mut self, instr: Instruction, fuel_consumed: u64) {
+        self.countdown -= 1;
+        if self.countdown != 0 {
+            return;
+        }
+        self.countdown = self.interval;
+        self.flush_fuel(fuel_consumed);
+        let node = self.current();
+        self.nodes[node].instrs += self.interval;
+        *self.opcodes.entry(OpCode(instr).name()).or_default() += self.interval;
+    }
+
+    /// Attributes the fuel consumed since the last event to the innermost function of the call path.
+    fn flush_fuel(&mut self, fuel_consumed: u64) {
+        let Some(last) = self.fuel_consumed.replace(fuel_consumed) else {
+            return;
+        };
+        let delta = fuel_consumed.wrapping_sub(last);
+        let node = self.current();
+        self.nodes[node].fuel += delta;
+    }
+
+    /// Returns the node of the innermost function of the call path.
+    fn current(&self) -> usize {
+        self.stack.last().copied().unwrap_or(ROOT)
+    }
+
+    /// Adds a new profiled function and returns its index.
+    fn push_func(&mut self, name: String, index: Option<u32>) -> usize {
+        let func = self.funcs.len();
+        self.funcs.push(ProfiledFunc { name, index });
+        func
+    }
+
+    /// Records a call to `func` by the innermost function of the call path and returns its node.
+    fn call(&mut self, func: usize) -> usize {
+        let parent = self.current();
+        let node = match self.nodes[parent].children.get(&func) {
+            Some(node) => *node,
+            None => {
+                let node = self.nodes.len();
+                self.nodes.push(CallNode::new(Some(func), Some(parent)));
+                self.nodes[parent].children.insert(func, node);
+                node
+            }
+        };
+        self.nodes[node].calls += 1;
+        node
+    }
+
+    /// Returns the self weight of the `node`.
+    fn weight(&self, node: &CallNode, weight: ProfileWeight) -> u64 {
+        match weight {
+            ProfileWeight::Instructions => node.instrs,
+            ProfileWeight::Fuel => node.fuel,
+        }
+    }
+
+    /// Returns the profile as folded stacks compatible with flamegraph tooling.
+    ///
+    /// Every line holds a `;` separated call path from the outermost to the innermost
+    /// function followed by the self `weight` of the innermost function on that path.
+    /// Call paths with a self weight of zero are omitted.
+    pub fn folded(&self, weight: ProfileWeight) -> String {
+        let mut folded = String::new();
+        for node in &self.nodes {
+            let Some(func) = node.func else {
+                continue;
+            };
+            let value = self.weight(node, weight);
+            if value == 0 {
+                continue;
+            }
+            let mut path = Vec::from([func]);
+            let mut parent = node.parent;
+            while let Some(node) = parent.map(|parent| &self.nodes[parent]) {
+                path.extend(node.func);
+                parent = node.parent;
+            }
+            for (n, func) in path.iter().rev().enumerate() {
+                if n != 0 {
+                    folded.push(';');
+                }
+                // Semicolons separate frames and must not appear within function names.
+                folded.push_str(&self.funcs[*func].name.replace(';', ":"));
+            }
+            writeln!(folded, " {value}").expect("writing to a String cannot fail");
+        }
+        folded
+    }
+
+    /// Returns the number of recorded instructions per opcode in descending order.
+    pub fn opcode_histogram(&self) -> Vec<(&'static str, u64)> {
+        let mut histogram = self
+            .opcodes
+            .iter()
+            .map(|(name, count)| (*name, *count))
+            .collect::<Vec<_>>();
+        histogram.sort_by_key(|(name, count)| (Reverse(*count), *name));
+        histogram
+    }
+
+    /// Returns the aggregated [`FuncProfile`] of all called functions sorted by their
+    /// total number of executed instructions in descending order.
+    ///
+    /// The total weight of a function includes all its callees
+    /// but counts recursive calls of the function only once.
+    pub fn funcs(&self) -> Vec<FuncProfile> {
+        let mut profiles = self
+            .funcs
+            .iter()
+            .map(|func| FuncProfile {
+                name: func.name.clone(),
+                index: func.index,
+                calls: 0,
+                self_instrs: 0,
+                total_instrs: 0,
+                self_fuel: 0,
+                total_fuel: 0,
+            })
+            .collect::<Vec<_>>();
+        for node in &self.nodes {
+            let Some(func) = node.func else {
+                continue;
+            };
+            let profile = &mut profiles[func];
+            profile.calls += node.calls;
+            profile.self_instrs += node.instrs;
+            profile.self_fuel += node.fuel;
+            // Attribute the self weight to every distinct function of the call path.
+            let mut seen = Vec::new();
+            let mut current = Some(node);
+            while let Some(ancestor) = current {
+                if let Some(func) = ancestor.func {
+                    if !seen.contains(&func) {
+                        seen.push(func);
+                        profiles[func].total_instrs += node.instrs;
+                        profiles[func].total_fuel += node.fuel;
+                    }
+                }
+                current = ancestor.parent.map(|parent| &self.nodes[parent]);
+            }
+        }
+        profiles.sort_by_key(|profile| Reverse(profile.total_instrs));
+        profiles
+    }
+}
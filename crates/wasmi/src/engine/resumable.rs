@@ -119,6 +119,11 @@ impl ResumableInvocation {
         replace(&mut self.stack, Stack::empty())
     }
 
+    /// Consumes the [`ResumableInvocation`] and returns the encountered host error.
+    pub(crate) fn into_host_error(mut self) -> Trap {
+        replace(&mut self.host_error, Trap::new(""))
+    }
+
     /// Updates the [`ResumableInvocation`] with the new `host_func` and a `host_error`.
     pub(super) fn update(&mut self, host_func: Func, host_error: Trap) {
         self.host_func = host_func;
@@ -0,0 +1,334 @@
+//! Data structures to represent live Wasm exception handlers during execution.
+
+use crate::{
+    core::UntypedValue,
+    engine::{bytecode::BranchOffset, code_map::InstructionPtr},
+    Tag,
+};
+use alloc::{boxed::Box, vec::Vec};
+
+/// An exception that is propagated through the Wasm call stack.
+///
+/// # Note
+///
+/// In contrast to [`Exception`] the values of an [`UntypedException`]
+/// are untyped which is the representation used by the `wasmi` executor.
+///
+/// [`Exception`]: crate::Exception
+#[derive(Debug, Clone)]
+pub struct UntypedException {
+    /// The tag of the exception.
+    tag: Tag,
+    /// The values carried by the exception.
+    values: Box<[UntypedValue]>,
+}
+
+impl UntypedException {
+    /// Creates a new [`UntypedException`] for the `tag` carrying the given `values`.
+    pub fn new(tag: Tag, values: Box<[UntypedValue]>) -> Self {
+        Self { tag, values }
+    }
+
+    /// Returns the [`Tag`] of the [`UntypedException`].
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns the values carried by the [`UntypedException`].
+    pub fn values(&self) -> &[UntypedValue] {
+        &self.values
+    }
+}
+
+/// An exception handler registered upon entering a Wasm `try` block.
+///
+/// # Note
+///
+/// Exception handlers are not unregistered when control flow branches out
+/// of their `try` block. Instead they are lazily recognized as dead once the
+/// instruction pointer of their function frame is no longer within the bounds
+/// of their `try` block. Calls drop the dead exception handlers of the calling
+/// function frame before the callee is entered since the callee treats all
+/// exception handlers of its callers as live. Returns and tail calls unregister
+/// the exception handlers of the returning function frame eagerly.
+#[derive(Debug, Copy, Clone)]
+pub struct TryHandler {
+    /// The depth of the function frame on the [`CallStack`] that registered the handler.
+    ///
+    /// [`CallStack`]: super::CallStack
+    depth: usize,
+    /// The `Try` instruction that registered the handler.
+    start: InstructionPtr,
+    /// The first `catch` clause of the `try` block.
+    landing: InstructionPtr,
+    /// The end of the `try` block and its `catch` clauses.
+    end: InstructionPtr,
+    /// The value stack height to restore upon entering the `catch` clauses.
+    height: usize,
+}
+
+impl TryHandler {
+    /// Creates a new [`TryHandler`] for the `Try` instruction at `start`.
+    pub fn new(
+        depth: usize,
+        start: InstructionPtr,
+        landing: BranchOffset,
+        end: BranchOffset,
+        height: usize,
+    ) -> Self {
+        let offset_by = |offset: BranchOffset| {
+            let mut ip = start;
+            ip.offset(offset.into_i32() as isize);
+            ip
+        };
+        Self {
+            depth,
+            start,
+            landing: offset_by(landing),
+            end: offset_by(end),
+            height,
+        }
+    }
+
+    /// Returns the depth of the function frame that registered the [`TryHandler`].
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the instruction pointer to the first `catch` clause of the [`TryHandler`].
+    pub fn landing(&self) -> InstructionPtr {
+        self.landing
+    }
+
+    /// Returns the value stack height of the [`TryHandler`].
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if the [`TryHandler`] still guards execution at `ip` of the `depth` frame.
+    fn is_live(&self, depth: usize, ip: InstructionPtr) -> bool {
+        self.depth < depth
+            || (self.depth == depth && self.start.ptr < ip.ptr && ip.ptr < self.landing.ptr)
+    }
+}
+
+/// An exception that has been caught by the `catch` clauses of a `try` block.
+#[derive(Debug, Clone)]
+pub struct CaughtException {
+    /// The depth of the function frame on the [`CallStack`] that caught the exception.
+    ///
+    /// [`CallStack`]: super::CallStack
+    depth: usize,
+    /// The first `catch` clause of the `try` block that caught the exception.
+    landing: InstructionPtr,
+    /// The end of the `try` block and its `catch` clauses.
+    end: InstructionPtr,
+    /// The caught exception.
+    exception: UntypedException,
+}
+
+impl CaughtException {
+    /// Creates a new [`CaughtException`] for the `exception` caught by `handler`.
+    pub fn new(handler: &TryHandler, exception: UntypedException) -> Self {
+        Self {
+            depth: handler.depth,
+            landing: handler.landing,
+            end: handler.end,
+            exception,
+        }
+    }
+
+    /// Returns the caught [`UntypedException`].
+    pub fn exception(&self) -> &UntypedException {
+        &self.exception
+    }
+
+    /// Consumes `self` and returns the caught [`UntypedException`].
+    pub fn into_exception(self) -> UntypedException {
+        self.exception
+    }
+
+    /// Returns `true` if the `catch` clause at `landing` of the `depth` frame caught `self`.
+    fn is_caught_by(&self, depth: usize, landing: InstructionPtr) -> bool {
+        self.depth == depth && self.landing.ptr == landing.ptr
+    }
+
+    /// Returns `true` if the [`CaughtException`] is still accessible at `ip` of the `depth` frame.
+    fn is_live(&self, depth: usize, ip: InstructionPtr) -> bool {
+        self.depth < depth
+            || (self.depth == depth && self.landing.ptr <= ip.ptr && ip.ptr < self.end.ptr)
+    }
+}
+
+/// The live exception handlers and caught exceptions of a [`CallStack`].
+///
+/// [`CallStack`]: super::CallStack
+#[derive(Debug, Default)]
+pub struct ExceptionStack {
+    /// The registered exception handlers in order.
+    handlers: Vec<TryHandler>,
+    /// The caught exceptions of all `catch` clauses in order.
+    caught: Vec<CaughtException>,
+    /// An exception that is about to be thrown or that was not caught.
+    pending: Option<UntypedException>,
+}
+
+impl ExceptionStack {
+    /// Registers the exception `handler`.
+    ///
+    /// This also drops all dead exception handlers.
+    pub fn push_handler(&mut self, handler: TryHandler) {
+        self.prune_handlers(handler.depth, handler.start);
+        self.handlers.push(handler);
+    }
+
+    /// Pops the innermost live exception handler guarding `ip` of the `depth` frame.
+    ///
+    /// Skips the `skip` innermost live exception handlers registered by the `depth` frame.
+    /// All dead and skipped exception handlers are dropped.
+    pub fn pop_handler(
+        &mut self,
+        depth: usize,
+        ip: InstructionPtr,
+        mut skip: u32,
+    ) -> Option<TryHandler> {
+        while let Some(handler) = self.handlers.pop() {
+            if !handler.is_live(depth, ip) {
+                continue;
+            }
+            if handler.depth == depth && skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            return Some(handler);
+        }
+        None
+    }
+
+    /// Drops all dead exception handlers at `ip` of the `depth` frame.
+    ///
+    /// # Note
+    ///
+    /// Dead exception handlers are always on top of the live ones
+    /// since `try` blocks are properly nested.
+    fn prune_handlers(&mut self, depth: usize, ip: InstructionPtr) {
+        while let Some(handler) = self.handlers.last() {
+            if handler.is_live(depth, ip) {
+                break;
+            }
+            self.handlers.pop();
+        }
+    }
+
+    /// Drops all dead exception handlers and caught exceptions at `ip` of the `depth` frame.
+    ///
+    /// # Note
+    ///
+    /// This must be called before the `depth` frame calls another function
+    /// since the callee considers all exception handlers and caught exceptions
+    /// of its callers to be live.
+    pub fn prune(&mut self, depth: usize, ip: InstructionPtr) {
+        self.prune_handlers(depth, ip);
+        while let Some(caught) = self.caught.last() {
+            if caught.is_live(depth, ip) {
+                break;
+            }
+            self.caught.pop();
+        }
+    }
+
+    /// Applies `relocate` to all instruction pointers of the [`ExceptionStack`].
+    pub fn relocate(&mut self, relocate: impl Fn(InstructionPtr) -> InstructionPtr) {
+        for handler in &mut self.handlers {
+            handler.start = relocate(handler.start);
+            handler.landing = relocate(handler.landing);
+            handler.end = relocate(handler.end);
+        }
+        for caught in &mut self.caught {
+            caught.landing = relocate(caught.landing);
+            caught.end = relocate(caught.end);
+        }
+    }
+
+    /// Drops all exception handlers and caught exceptions of the `depth` frame and above.
+    ///
+    /// # Note
+    ///
+    /// This must be called whenever function frames are left via return or tail call
+    /// since a later function frame at the same depth must not see them.
+    pub fn drop_frames(&mut self, depth: usize) {
+        while matches!(self.handlers.last(), Some(handler) if handler.depth >= depth) {
+            self.handlers.pop();
+        }
+        while matches!(self.caught.last(), Some(caught) if caught.depth >= depth) {
+            self.caught.pop();
+        }
+    }
+
+    /// Pushes the `caught` exception.
+    ///
+    /// This also drops all dead caught exceptions.
+    pub fn push_caught(&mut self, caught: CaughtException) {
+        while let Some(last) = self.caught.last() {
+            if last.is_live(caught.depth, caught.landing)
+                && !last.is_caught_by(caught.depth, caught.landing)
+            {
+                break;
+            }
+            self.caught.pop();
+        }
+        self.caught.push(caught);
+    }
+
+    /// Returns the most recently caught exception.
+    ///
+    /// # Panics
+    ///
+    /// If there is no caught exception.
+    pub fn last_caught(&self) -> &CaughtException {
+        self.caught
+            .last()
+            .unwrap_or_else(|| panic!("missing caught exception"))
+    }
+
+    /// Pops the most recently caught exception.
+    ///
+    /// # Panics
+    ///
+    /// If there is no caught exception.
+    pub fn pop_caught(&mut self) -> CaughtException {
+        self.caught
+            .pop()
+            .unwrap_or_else(|| panic!("missing caught exception"))
+    }
+
+    /// Returns the exception caught by the `catch` clauses at `landing` of the `depth` frame.
+    ///
+    /// # Panics
+    ///
+    /// If there is no such caught exception.
+    pub fn get_caught(&self, depth: usize, landing: InstructionPtr) -> &CaughtException {
+        self.caught
+            .iter()
+            .rev()
+            .find(|caught| caught.is_caught_by(depth, landing))
+            .unwrap_or_else(|| panic!("missing caught exception for rethrow at depth {depth}"))
+    }
+
+    /// Sets the pending `exception`.
+    pub fn set_pending(&mut self, exception: UntypedException) {
+        self.pending = Some(exception);
+    }
+
+    /// Takes the pending exception if any.
+    pub fn take_pending(&mut self) -> Option<UntypedException> {
+        self.pending.take()
+    }
+
+    /// Clears the [`ExceptionStack`] entirely.
+    pub fn reset(&mut self) {
+        self.handlers.clear();
+        self.caught.clear();
+        self.pending = None;
+    }
+}
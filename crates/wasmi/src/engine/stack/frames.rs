@@ -1,6 +1,6 @@
 //! Data structures to represent the Wasm call stack during execution.
 
-use super::{err_stack_overflow, DEFAULT_MAX_RECURSION_DEPTH};
+use super::{err_stack_overflow, ExceptionStack, DEFAULT_MAX_RECURSION_DEPTH};
 use crate::{core::TrapCode, engine::code_map::InstructionPtr, Instance};
 use alloc::vec::Vec;
 
@@ -46,6 +46,8 @@ pub struct CallStack {
     frames: Vec<FuncFrame>,
     /// The maximum allowed depth of the `frames` stack.
     recursion_limit: usize,
+    /// The live exception handlers and caught exceptions of the function frames.
+    exceptions: ExceptionStack,
 }
 
 impl Default for CallStack {
@@ -60,6 +62,7 @@ impl CallStack {
         Self {
             frames: Vec::new(),
             recursion_limit,
+            exceptions: ExceptionStack::default(),
         }
     }
 
@@ -79,6 +82,24 @@ impl CallStack {
         Ok(())
     }
 
+    /// Pushes the [`FuncFrame`] of a trapping Wasm function onto the [`CallStack`].
+    ///
+    /// # Note
+    ///
+    /// Unlike [`CallStack::push`] this does not check the recursion limit
+    /// since the frame is only kept to capture a [`WasmBacktrace`].
+    ///
+    /// [`WasmBacktrace`]: crate::core::WasmBacktrace
+    #[cold]
+    pub fn push_trapped(&mut self, frame: FuncFrame) {
+        self.frames.push(frame);
+    }
+
+    /// Returns an iterator over the [`FuncFrame`] of the [`CallStack`], outermost first.
+    pub fn frames(&self) -> core::slice::Iter<'_, FuncFrame> {
+        self.frames.iter()
+    }
+
     /// Pops the last [`FuncFrame`] from the [`CallStack`] if any.
     #[inline]
     pub fn pop(&mut self) -> Option<FuncFrame> {
@@ -93,10 +114,45 @@ impl CallStack {
 
     /// Returns the amount of function frames on the [`CallStack`].
     #[inline]
-    fn len(&self) -> usize {
+    pub fn len(&self) -> usize {
         self.frames.len()
     }
 
+    /// Pops all [`FuncFrame`] down to the given `depth` and returns the one at `depth`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no [`FuncFrame`] at `depth`.
+    pub fn unwind_to(&mut self, depth: usize) -> FuncFrame {
+        let frame = self.frames[depth];
+        self.frames.truncate(depth);
+        frame
+    }
+
+    /// Returns a shared reference to the [`ExceptionStack`] of the [`CallStack`].
+    pub fn exceptions(&self) -> &ExceptionStack {
+        &self.exceptions
+    }
+
+    /// Returns an exclusive reference to the [`ExceptionStack`] of the [`CallStack`].
+    pub fn exceptions_mut(&mut self) -> &mut ExceptionStack {
+        &mut self.exceptions
+    }
+
+    /// Applies `relocate` to all instruction pointers of the [`CallStack`].
+    ///
+    /// # Note
+    ///
+    /// This is required after the instructions of the [`CodeMap`] have moved.
+    ///
+    /// [`CodeMap`]: crate::engine::code_map::CodeMap
+    pub fn relocate(&mut self, relocate: impl Fn(InstructionPtr) -> InstructionPtr) {
+        for frame in &mut self.frames {
+            frame.ip = relocate(frame.ip);
+        }
+        self.exceptions.relocate(relocate);
+    }
+
     /// Clears the [`CallStack`] entirely.
     ///
     /// # Note
@@ -107,5 +163,6 @@ impl CallStack {
     /// function execution happens.
     pub fn reset(&mut self) {
         self.frames.clear();
+        self.exceptions.reset();
     }
 }
@@ -1,15 +1,26 @@
+mod exceptions;
 mod frames;
 mod values;
 
 pub use self::{
+    exceptions::{CaughtException, ExceptionStack, TryHandler, UntypedException},
     frames::{CallStack, FuncFrame},
     values::{ValueStack, ValueStackPtr},
 };
 use crate::{
     core::UntypedValue,
-    engine::{code_map::CodeMap, func_types::FuncTypeRegistry, FuncParams},
+    engine::{
+        code_map::CodeMap,
+        func_types::FuncTypeRegistry,
+        host_calls::{replay_host_call, PendingHostCall},
+        FuncParams,
+        HostCallMode,
+    },
     func::{HostFuncEntity, WasmFuncEntity},
+    limits::ResourceLimiterRef,
     AsContext,
+    AsContextMut,
+    Func,
     Instance,
     StoreContextMut,
 };
@@ -158,22 +169,27 @@ impl Stack {
     pub fn call_host_as_root<T>(
         &mut self,
         ctx: StoreContextMut<T>,
+        func: &Func,
         host_func: HostFuncEntity,
         func_types: &FuncTypeRegistry,
     ) -> Result<(), Trap> {
-        self.call_host_impl(ctx, host_func, None, func_types)
+        self.call_host_impl(ctx, func, host_func, None, func_types)
     }
 
     /// Executes the given host function.
     ///
+    /// Records or replays the call if the [`Store`](crate::Store) has a [`HostCallMode`] installed.
+    ///
     /// # Errors
     ///
     /// - If the host function returns a host side error or trap.
     /// - If the value stack overflowed upon pushing parameters or results.
+    /// - If a replayed call diverges from its [`HostCallLog`](crate::HostCallLog).
     #[inline(always)]
     pub fn call_host_impl<T>(
         &mut self,
-        ctx: StoreContextMut<T>,
+        mut ctx: StoreContextMut<T>,
+        func: &Func,
         host_func: HostFuncEntity,
         instance: Option<&Instance>,
         func_types: &FuncTypeRegistry,
@@ -199,30 +215,60 @@ impl Stack {
         } else {
             0
         };
-        let params_results = FuncParams::new(
-            self.values.peek_as_slice_mut(max_inout),
-            len_inputs,
-            len_outputs,
-        );
-        // Now we are ready to perform the host function call.
-        // Note: We need to clone the host function due to some borrowing issues.
-        //       This should not be a big deal since host functions usually are cheap to clone.
-        let trampoline = ctx
-            .as_context()
+        let params_results = self.values.peek_as_slice_mut(max_inout);
+        // Note: The host function name is only resolved when host calls
+        //       are recorded or replayed since this is rather costly.
+        let name = ctx
             .store
-            .resolve_trampoline(host_func.trampoline())
-            .clone();
-        trampoline
-            .call(ctx, instance, params_results)
-            .map_err(|error| {
-                // Note: We drop the values that have been temporarily added to
-                //       the stack to act as parameter and result buffer for the
-                //       called host function. Since the host function failed we
-                //       need to clean up the temporary buffer values here.
-                //       This is required for resumable calls to work properly.
-                self.values.drop(delta);
-                error
-            })?;
+            .host_calls
+            .is_some()
+            .then(|| ctx.store.host_call_name(func, &host_func));
+        let result = match (name, ctx.store.host_calls.as_mut()) {
+            (Some(name), Some(HostCallMode::Replay(log, position))) => replay_host_call(
+                log,
+                position,
+                &mut ctx.store.inner,
+                &mut ResourceLimiterRef::new(&mut ctx.store.limiter, &mut ctx.store.data),
+                name,
+                params_results,
+                len_inputs,
+                len_outputs,
+            ),
+            (Some(name), Some(HostCallMode::Record(_))) => {
+                let pending =
+                    PendingHostCall::new(&mut ctx.store.inner, name, &params_results[..len_inputs]);
+                let result = Self::call_trampoline(
+                    ctx.as_context_mut(),
+                    &host_func,
+                    instance,
+                    FuncParams::new(params_results, len_inputs, len_outputs),
+                );
+                // Note: The host function might have replaced the host call mode.
+                let store = &mut *ctx.store;
+                let log = match store.host_calls.as_mut() {
+                    Some(HostCallMode::Record(log)) => Some(log),
+                    _ => None,
+                };
+                let outcome = result.as_ref().map(|_| &params_results[..len_outputs]);
+                pending.finish(&mut store.inner, outcome, log);
+                result
+            }
+            _ => Self::call_trampoline(
+                ctx,
+                &host_func,
+                instance,
+                FuncParams::new(params_results, len_inputs, len_outputs),
+            ),
+        };
+        result.map_err(|error| {
+            // Note: We drop the values that have been temporarily added to
+            //       the stack to act as parameter and result buffer for the
+            //       called host function. Since the host function failed we
+            //       need to clean up the temporary buffer values here.
+            //       This is required for resumable calls to work properly.
+            self.values.drop(delta);
+            error
+        })?;
         // If the host functions returns fewer results than it receives parameters
         // the value stack needs to be shrinked for the delta.
         if len_outputs < len_inputs {
@@ -235,6 +281,24 @@ impl Stack {
         Ok(())
     }
 
+    /// Calls the trampoline of the `host_func` with `params_results`.
+    fn call_trampoline<T>(
+        ctx: StoreContextMut<T>,
+        host_func: &HostFuncEntity,
+        instance: Option<&Instance>,
+        params_results: FuncParams,
+    ) -> Result<(), Trap> {
+        // Note: We need to clone the host function due to some borrowing issues.
+        //       This should not be a big deal since host functions usually are cheap to clone.
+        let trampoline = ctx
+            .as_context()
+            .store
+            .resolve_trampoline(host_func.trampoline())
+            .clone();
+        trampoline.call(ctx, instance, params_results)?;
+        Ok(())
+    }
+
     /// Clears both value and call stacks.
     pub fn reset(&mut self) {
         self.values.reset();
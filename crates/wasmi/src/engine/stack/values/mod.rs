@@ -95,6 +95,12 @@ impl ValueStack {
         self.base_ptr().into_add(self.stack_ptr)
     }
 
+    /// Returns the [`ValueStackPtr`] of `self` for a value stack with the given `height`.
+    #[inline]
+    pub fn stack_ptr_at(&mut self, height: usize) -> ValueStackPtr {
+        self.base_ptr().into_add(height)
+    }
+
     pub fn stack_len(&mut self, sp: ValueStackPtr) -> usize {
         let base = self.base_ptr();
         sp.offset_from(base) as usize
@@ -4,7 +4,7 @@ use super::{
 };
 use crate::{
     engine::{
-        bytecode::{BranchOffset, BranchParams, Instruction},
+        bytecode::{BranchOffset, BranchParams, Instruction, LocalDepth},
         config::FuelCosts,
         DropKeep,
     },
@@ -128,6 +128,30 @@ where
     assert_func_bodies_with_config(&config, wasm_bytes, expected)
 }
 
+/// Returns a [`Config`] with the superinstruction fusion pass enabled.
+fn superinstructions_config() -> Config {
+    let mut config = Config::default();
+    config.deterministic_tracing(false).superinstructions(true);
+    config
+}
+
+/// Asserts that the given `wasm` bytes yield functions with expected instructions.
+///
+/// Uses a [`Config`] for the tests where the superinstruction fusion pass is enabled.
+///
+/// # Panics
+///
+/// If any of the yielded functions consists of instruction different from the
+/// expected instructions for that function.
+fn assert_func_bodies_superinstructions<E, T>(wasm_bytes: impl AsRef<[u8]>, expected: E)
+where
+    E: IntoIterator<Item = T>,
+    T: IntoIterator<Item = Instruction>,
+    <T as IntoIterator>::IntoIter: ExactSizeIterator,
+{
+    assert_func_bodies_with_config(&superinstructions_config(), wasm_bytes, expected)
+}
+
 fn drop_keep(drop: usize, keep: usize) -> DropKeep {
     DropKeep::new(drop, keep).unwrap()
 }
@@ -766,7 +790,7 @@ fn br_table_return() {
 
 /// Returns the default [`FuelCosts`].
 pub fn fuel_costs() -> FuelCosts {
-    *Config::default().fuel_costs()
+    Config::default().fuel_costs().clone()
 }
 
 #[test]
@@ -1330,3 +1354,291 @@ fn metered_store_01() {
     ];
     assert_func_bodies_metered(wasm, [expected]);
 }
+
+#[test]
+fn metered_instr_costs() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (memory 1)
+            (func (param $ptr i32) (result i64)
+                (i64.div_u
+                    (i64.load (local.get $ptr))
+                    (i64.const 3)
+                )
+            )
+        )
+    "#,
+    );
+    let mut costs = fuel_costs();
+    costs
+        .set_instr_cost(
+            "i64_div_u",
+            config::InstrCost {
+                base: Some(10),
+                items_per_fuel: None,
+            },
+        )
+        .set_instr_cost(
+            "i64_load",
+            config::InstrCost {
+                base: Some(7),
+                items_per_fuel: None,
+            },
+        );
+    let expected_fuel = 4 * costs.base
+        + 10
+        + 7
+        + costs.fuel_for_locals(1)
+        + costs.fuel_for_drop_keep(drop_keep(1, 1));
+    let expected = [
+        Instruction::consume_fuel(expected_fuel),
+        Instruction::local_get(1),
+        Instruction::I64Load(Offset::from(0)),
+        Instruction::constant(3_i64),
+        Instruction::I64DivU,
+        Instruction::Return(drop_keep(1, 1)),
+    ];
+    let mut config = Config::default();
+    config.consume_fuel(true).set_fuel_costs(costs);
+    assert_func_bodies_with_config(&config, wasm, [expected]);
+}
+
+fn local_depth(depth: usize) -> LocalDepth {
+    LocalDepth::from(depth)
+}
+
+#[test]
+fn superinstructions_binary_local_operands() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (param i32) (result i32)
+                (i32.add
+                    (i32.mul (local.get 0) (local.get 1))
+                    (local.get 1)
+                )
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::I32MulLocalLocal {
+            lhs: local_depth(2),
+            rhs: local_depth(1),
+        },
+        Instruction::I32AddLocal {
+            rhs: local_depth(2),
+        },
+        Instruction::Return(drop_keep(2, 1)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_local_copy() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i64) (result i64) (local i64)
+                (local.set 1 (local.get 0))
+                (i64.lt_u (local.get 1) (local.get 0))
+                (drop)
+                (local.get 1)
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::LocalCopy {
+            result: local_depth(1),
+            input: local_depth(2),
+        },
+        Instruction::I64LtULocalLocal {
+            lhs: local_depth(1),
+            rhs: local_depth(2),
+        },
+        Instruction::Drop,
+        Instruction::local_get(1),
+        Instruction::Return(drop_keep(2, 1)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_no_local_fusion_across_labels() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (param i32) (result i32)
+                (local.get 0)
+                (block (result i32) (local.get 1))
+                (i32.add)
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::local_get(2),
+        Instruction::local_get(2),
+        Instruction::I32Add,
+        Instruction::Return(drop_keep(2, 1)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_fallible_and_float_operands_are_not_fused() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (param f32) (result i32)
+                (drop (f32.add (local.get 1) (local.get 1)))
+                (i32.div_u (local.get 0) (local.get 0))
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::local_get(1),
+        Instruction::local_get(2),
+        Instruction::F32Add,
+        Instruction::Drop,
+        Instruction::local_get(2),
+        Instruction::local_get(3),
+        Instruction::I32DivU,
+        Instruction::Return(drop_keep(2, 1)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_binary_imm() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (param i64) (result i64)
+                (drop (i32.shl (local.get 0) (i32.const 5)))
+                (i64.sub (local.get 1) (i64.const -1))
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::local_get(2),
+        Instruction::I32ShlImm(5_i32.into()),
+        Instruction::Drop,
+        Instruction::local_get(1),
+        Instruction::I64SubImm((-1_i64).into()),
+        Instruction::Return(drop_keep(2, 1)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_br_if_compare() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (param i32)
+                (block
+                    (br_if 0 (i32.lt_s (local.get 0) (local.get 1)))
+                    (br_if 0 (i32.ge_u (i32.eqz (local.get 0)) (i32.eqz (local.get 1))))
+                    (br_if 0 (i32.eqz (local.get 0)))
+                )
+            )
+        )
+    "#,
+    );
+    // Note: Comparisons with local operands are fused with their operands instead.
+    let expected = [
+        /*  0 */
+        Instruction::I32LtSLocalLocal {
+            lhs: local_depth(2),
+            rhs: local_depth(1),
+        },
+        /*  1 */ Instruction::BrIfNez(params!(1 => 10, drop: 0, keep: 0)),
+        /*  2 */ Instruction::local_get(2),
+        /*  3 */ Instruction::I32Eqz,
+        /*  4 */ Instruction::local_get(2),
+        /*  5 */ Instruction::I32Eqz,
+        /*  6 */ Instruction::BrIfI32GeU(params!(6 => 10, drop: 0, keep: 0)),
+        /*  7 */ Instruction::local_get(2),
+        /*  8 */ Instruction::I32Eqz,
+        /*  9 */ Instruction::BrIfNez(params!(9 => 10, drop: 0, keep: 0)),
+        /* 10 */ Instruction::Return(drop_keep(2, 0)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_no_fusion_across_labels() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (result i32)
+                (local.get 0)
+                (block (result i32) (i32.const 1))
+                (i32.add)
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::local_get(1),
+        Instruction::constant(1_i32),
+        Instruction::I32Add,
+        Instruction::Return(drop_keep(1, 1)),
+    ];
+    assert_func_bodies_superinstructions(wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_disabled_in_deterministic_tracing() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (result i32)
+                (i32.add (local.get 0) (i32.const 1))
+            )
+        )
+    "#,
+    );
+    let expected = [
+        Instruction::local_get(1),
+        Instruction::constant(1_i32),
+        Instruction::I32Add,
+        Instruction::Return(drop_keep(1, 1)),
+    ];
+    let mut config = Config::default();
+    config.superinstructions(true);
+    assert_func_bodies_with_config(&config, wasm, [expected]);
+}
+
+#[test]
+fn superinstructions_keep_source_pc_range() {
+    let wasm = wat2wasm(
+        r#"
+        (module
+            (func (param i32) (result i32)
+                (i32.add (local.get 0) (i32.const 1))
+            )
+        )
+    "#,
+    );
+    let module = create_module(&superinstructions_config(), &wasm);
+    let engine = module.engine();
+    let (_, func_body) = module.internal_funcs().next().unwrap();
+    assert!(matches!(
+        engine.resolve_inst(func_body, 1),
+        Some(Instruction::I32AddImm(_))
+    ));
+    let local_get = engine.resolve_meta(func_body, 0).unwrap();
+    let fused = engine.resolve_meta(func_body, 1).unwrap();
+    // `local.get` and `i32.const` are each encoded using 2 bytes.
+    assert_eq!(local_get.source_pc_range(), local_get.source_pc()..=local_get.source_pc());
+    assert_eq!(
+        fused.source_pc_range(),
+        (local_get.source_pc() + 2)..=(local_get.source_pc() + 4)
+    );
+}
@@ -10,6 +10,8 @@ use wasmi_core::UntypedValue;
 use crate::engine::bytecode::{InstrMeta, Instruction};
 use crate::engine::opcode::OpCode;
 use crate::Extern;
+#[cfg(feature = "dwarf")]
+use crate::DebugInfo;
 
 #[derive(Debug, Clone)]
 pub struct MemoryState {
@@ -39,6 +41,10 @@ pub struct OpCodeState {
     pub stack: Vec<u64>,
     pub source_pc: u32,
     pub code: u16,
+    /// The index of the executing function within its module, including imported functions.
+    ///
+    /// This is `None` if the function is unknown and is not part of the serialized trace.
+    pub func_index: Option<u32>,
 }
 
 impl Serialize for OpCodeState {
@@ -108,6 +114,33 @@ impl Serialize for GlobalVariable {
     }
 }
 
+/// A system call served by the host, such as a WASI function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyscallState {
+    /// The number of log entries recorded before the system call.
+    pub step: u32,
+    /// The name of the system call.
+    pub name: String,
+    /// The parameters of the system call as raw bits.
+    pub params: Vec<u64>,
+    /// The results of the system call as raw bits.
+    pub results: Vec<u64>,
+}
+
+impl Serialize for SyscallState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("SyscallState", 4)?;
+        s.serialize_field("step", &self.step)?;
+        s.serialize_field("name", &self.name)?;
+        s.serialize_field("params", &self.params)?;
+        s.serialize_field("results", &self.results)?;
+        s.end()
+    }
+}
+
 #[derive(Default)]
 pub struct Tracer {
     global_memory: Vec<MemoryState>,
@@ -117,6 +150,7 @@ pub struct Tracer {
     fns_meta: Vec<FunctionMeta>,
     global_variables: Vec<GlobalVariable>,
     extern_names: BTreeMap<u32, String>,
+    syscalls: Vec<SyscallState>,
 }
 
 impl Debug for Tracer {
@@ -134,12 +168,7 @@ impl Serialize for Tracer {
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_struct("Tracer", 3)?;
-        s.serialize_field("global_memory", &self.global_memory)?;
-        s.serialize_field("logs", &self.logs)?;
-        s.serialize_field("global_variables", &self.global_variables)?;
-        s.serialize_field("fn_metas", &self.fns_meta)?;
-        s.end()
+        self.serialize_with_logs(&self.logs, serializer)
     }
 }
 
@@ -156,11 +185,22 @@ impl Tracer {
         self.logs.last().map(|opcode| opcode.source_pc)
     }
 
+    /// Returns the states of all traced instructions in execution order.
+    pub fn logs(&self) -> &[OpCodeState] {
+        &self.logs
+    }
+
+    /// Returns the metadata of all traced function calls in execution order.
+    pub fn fn_metas(&self) -> &[FunctionMeta] {
+        &self.fns_meta
+    }
+
     pub fn register_extern(&mut self, ex: Extern, name: &Box<str>, entity_index: u32) {
         match ex {
             Extern::Global(_) => {}
             Extern::Table(_) => {}
             Extern::Memory(_) => {}
+            Extern::Tag(_) => {}
             Extern::Func(_) => {
                 self.extern_names.insert(entity_index, name.to_string());
             }
@@ -173,6 +213,7 @@ impl Tracer {
         opcode: Instruction,
         stack: Vec<UntypedValue>,
         meta: &InstrMeta,
+        func_index: Option<u32>,
     ) {
         let memory_changes = self.memory_changes.replace(Vec::new());
         let stack = stack.iter().map(|v| v.to_bits()).collect();
@@ -183,6 +224,7 @@ impl Tracer {
             stack,
             source_pc: meta.source_pc(),
             code: meta.opcode(),
+            func_index,
         };
         self.logs.push(opcode_state.clone());
         if let Some(cb) = &self.cb_on_after_item_added_to_logs {
@@ -229,7 +271,68 @@ impl Tracer {
         });
     }
 
+    /// Records a system call served by the host with its raw `params` and `results`.
+    pub fn syscall(&mut self, name: impl Into<String>, params: &[u64], results: &[u64]) {
+        self.syscalls.push(SyscallState {
+            step: self.logs.len() as u32,
+            name: name.into(),
+            params: params.to_vec(),
+            results: results.to_vec(),
+        })
+    }
+
+    /// Returns all recorded system calls in the order they were served.
+    pub fn syscalls(&self) -> &[SyscallState] {
+        &self.syscalls
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string(self).unwrap()
     }
+
+    /// Serializes the trace with `logs` in place of the traced logs.
+    fn serialize_with_logs<L, S>(&self, logs: &L, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: Serialize + ?Sized,
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("Tracer", 5)?;
+        s.serialize_field("global_memory", &self.global_memory)?;
+        s.serialize_field("logs", logs)?;
+        s.serialize_field("global_variables", &self.global_variables)?;
+        s.serialize_field("fn_metas", &self.fns_meta)?;
+        s.serialize_field("syscalls", &self.syscalls)?;
+        s.end()
+    }
+
+    /// Serializes the trace like [`Tracer::to_json`] with `logs` in place of the traced logs.
+    ///
+    /// This allows to serialize a filtered or annotated selection of [`Tracer::logs`].
+    pub fn to_json_with_logs<L>(&self, logs: &L) -> String
+    where
+        L: Serialize + ?Sized,
+    {
+        let mut json = Vec::new();
+        self.serialize_with_logs(logs, &mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+        String::from_utf8(json).unwrap()
+    }
+
+    /// Serializes the trace like [`Tracer::to_json`] with every log entry
+    /// annotated by the source `location` of its `source_pc` if known.
+    #[cfg(feature = "dwarf")]
+    pub fn to_json_with_debug_info(&self, debug_info: &DebugInfo) -> String {
+        let mut json = serde_json::to_value(self).unwrap();
+        if let Some(logs) = json
+            .get_mut("logs")
+            .and_then(serde_json::Value::as_array_mut)
+        {
+            for (log, state) in logs.iter_mut().zip(&self.logs) {
+                if let Some(location) = debug_info.lookup(state.source_pc) {
+                    log["location"] = serde_json::to_value(location).unwrap();
+                }
+            }
+        }
+        json.to_string()
+    }
 }
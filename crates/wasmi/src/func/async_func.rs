@@ -0,0 +1,204 @@
+use super::{
+    super::engine::{FuncFinished, FuncParams, FuncResults},
+    into_func::{WasmRet, WasmTypeList},
+    Caller,
+    TrampolineEntity,
+};
+use crate::{
+    core::{HostError, Trap},
+    value::WithType,
+    AsContext,
+    AsContextMut,
+    FuncType,
+    Instance,
+    StoreContextMut,
+    Value,
+};
+use alloc::{boxed::Box, sync::Arc, task::Wake, vec::Vec};
+use core::{
+    fmt::{self, Display},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use spin::Mutex;
+
+/// The boxed future of an asynchronous host function call.
+///
+/// Resolves to the results of the host function.
+pub(crate) type HostFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Value>, Trap>> + 'a>>;
+
+/// A deferred asynchronous host function call awaiting its [`Caller`].
+type DeferredFn<T> = dyn for<'a> FnOnce(Caller<'a, T>) -> HostFuture<'a> + Send;
+
+/// The host error returned by the trampoline of an asynchronous host function.
+///
+/// # Note
+///
+/// This suspends the Wasm execution at the host function call so that
+/// [`Func::call_async`] can call the host function with a new [`Caller`],
+/// await its [`HostFuture`] and resume the execution with its results afterwards.
+///
+/// [`Func::call_async`]: crate::Func::call_async
+pub(crate) struct AsyncHostCall<T> {
+    /// The deferred host function call.
+    ///
+    /// This is `None` after the call has been taken.
+    call: Mutex<Option<DeferredCall<T>>>,
+}
+
+/// A deferred asynchronous host function call.
+pub(crate) struct DeferredCall<T> {
+    /// Calls the host function with its parameters.
+    func: Box<DeferredFn<T>>,
+    /// The module instance associated to the call if any.
+    instance: Option<Instance>,
+}
+
+impl<T: 'static> DeferredCall<T> {
+    /// Calls the host function and returns the [`HostFuture`] borrowing the `ctx`.
+    pub fn call<C>(self, ctx: &mut C) -> HostFuture<'_>
+    where
+        C: AsContextMut<UserState = T>,
+    {
+        (self.func)(Caller::new(ctx, self.instance.as_ref()))
+    }
+}
+
+impl<T> fmt::Debug for AsyncHostCall<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncHostCall").finish_non_exhaustive()
+    }
+}
+
+impl<T> Display for AsyncHostCall<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "called an asynchronous host function outside of `Func::call_async`"
+        )
+    }
+}
+
+impl<T: 'static> HostError for AsyncHostCall<T> {}
+
+impl<T: 'static> AsyncHostCall<T> {
+    /// Creates a new [`AsyncHostCall`] for the deferred host function `call`.
+    fn new(call: DeferredCall<T>) -> Self {
+        Self {
+            call: Mutex::new(Some(call)),
+        }
+    }
+
+    /// Takes the [`DeferredCall`] out of the `trap` if it suspended an asynchronous host function call.
+    pub fn take(trap: &Trap) -> Option<DeferredCall<T>> {
+        trap.downcast_ref::<Self>()?.call.lock().take()
+    }
+}
+
+/// Marks the [`Store`] as executing [`Func::call_async`] while it is alive.
+///
+/// # Note
+///
+/// The mark is reset once the guard is dropped so that it never outlives the
+/// execution, even if the execution unwinds.
+///
+/// [`Store`]: crate::Store
+/// [`Func::call_async`]: crate::Func::call_async
+pub(crate) struct AsyncCallGuard<'a, T> {
+    /// The context of the marked [`Store`](crate::Store).
+    pub ctx: StoreContextMut<'a, T>,
+}
+
+impl<'a, T> AsyncCallGuard<'a, T> {
+    /// Marks the [`Store`](crate::Store) of `ctx` as executing [`Func::call_async`].
+    ///
+    /// [`Func::call_async`]: crate::Func::call_async
+    pub fn new(ctx: StoreContextMut<'a, T>) -> Self {
+        ctx.store.set_async_call(true);
+        Self { ctx }
+    }
+}
+
+impl<T> Drop for AsyncCallGuard<'_, T> {
+    fn drop(&mut self) {
+        self.ctx.store.set_async_call(false);
+    }
+}
+
+/// A [`Waker`] that does nothing when woken up.
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls the `future` once and returns its output if it is ready.
+fn poll_once<F: Future + ?Sized>(future: Pin<&mut F>) -> Option<F::Output> {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    match future.poll(&mut cx) {
+        Poll::Ready(output) => Some(output),
+        Poll::Pending => None,
+    }
+}
+
+/// Creates the signature and trampoline of an asynchronous host function.
+///
+/// Within [`Func::call_async`] the trampoline decodes the parameters and
+/// suspends the execution with the deferred call of `func` wrapped in an
+/// [`AsyncHostCall`]. Otherwise the [`Future`] returned by `func` is polled
+/// once and must be ready.
+///
+/// [`Func::call_async`]: crate::Func::call_async
+pub(crate) fn into_async_func<T, Params, Results>(
+    func: impl for<'a> Fn(Caller<'a, T>, Params) -> Box<dyn Future<Output = Results> + 'a>
+        + Send
+        + Sync
+        + 'static,
+) -> (FuncType, TrampolineEntity<T>)
+where
+    T: 'static,
+    Params: WasmTypeList + Send + 'static,
+    Results: WasmRet,
+{
+    let signature = FuncType::new(
+        <Params as WasmTypeList>::types(),
+        <Results::Ok as WasmTypeList>::types(),
+    );
+    let func = Arc::new(func);
+    let trampoline = TrampolineEntity::new(
+        move |caller: Caller<T>, params_results: FuncParams| -> Result<FuncFinished, Trap> {
+            let (params, func_results): (Params, FuncResults) = params_results.decode_params();
+            if !caller.as_context().store.is_async_call() {
+                let mut future = Box::into_pin(func(caller, params));
+                let results = poll_once(future.as_mut()).ok_or_else(|| {
+                    Trap::new(
+                        "cannot wait on a pending asynchronous host function outside of `Func::call_async`",
+                    )
+                })?;
+                return Ok(func_results.encode_results(results.into_fallible()?));
+            }
+            let instance = caller.instance();
+            let func = func.clone();
+            let deferred: Box<DeferredFn<T>> = Box::new(move |caller| {
+                Box::pin(async move {
+                    let results = Box::into_pin(func(caller, params)).await.into_fallible()?;
+                    let types = <Results::Ok as WasmTypeList>::types();
+                    let values = results
+                        .values()
+                        .into_iter()
+                        .zip(types)
+                        .map(|(value, ty)| value.with_type(ty))
+                        .collect();
+                    Ok(values)
+                })
+            });
+            Err(Trap::from(AsyncHostCall::new(DeferredCall {
+                func: deferred,
+                instance,
+            })))
+        },
+    );
+    (signature, trampoline)
+}
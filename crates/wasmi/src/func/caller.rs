@@ -1,5 +1,10 @@
 use super::super::{AsContext, AsContextMut, StoreContext, StoreContextMut};
-use crate::{store::FuelError, Engine, Extern, Instance};
+use crate::{
+    store::{FuelError, FuelFrame},
+    Engine,
+    Extern,
+    Instance,
+};
 
 /// Represents the caller’s context when creating a host function via [`Func::wrap`].
 ///
@@ -25,6 +30,11 @@ impl<'a, T> Caller<'a, T> {
         }
     }
 
+    /// Returns the module instance associated to the call if any.
+    pub(crate) fn instance(&self) -> Option<Instance> {
+        self.instance
+    }
+
     /// Queries the caller for an exported definition identifier by `name`.
     ///
     /// Returns `None` if there is no associated [`Instance`] of the caller
@@ -84,6 +94,49 @@ impl<'a, T> Caller<'a, T> {
     pub fn consume_fuel(&mut self, delta: u64) -> Result<u64, FuelError> {
         self.ctx.store.consume_fuel(delta)
     }
+
+    /// Pushes a nested fuel frame that may consume at most `limit` fuel.
+    ///
+    /// See [`Store::push_fuel_frame`](crate::Store::push_fuel_frame) for details.
+    ///
+    /// # Errors
+    ///
+    /// - If fuel metering is disabled.
+    /// - If less than `limit` fuel is remaining in the current fuel frame.
+    pub fn push_fuel_frame(&mut self, limit: u64) -> Result<(), FuelError> {
+        self.ctx.store.push_fuel_frame(limit)
+    }
+
+    /// Pops the innermost fuel frame and refunds its unused fuel to its parent frame.
+    ///
+    /// See [`Store::pop_fuel_frame`](crate::Store::pop_fuel_frame) for details.
+    ///
+    /// # Errors
+    ///
+    /// - If fuel metering is disabled.
+    /// - If no fuel frame has been pushed.
+    pub fn pop_fuel_frame(&mut self) -> Result<FuelFrame, FuelError> {
+        self.ctx.store.pop_fuel_frame()
+    }
+
+    /// Refunds `delta` consumed fuel to the innermost fuel frame.
+    ///
+    /// Returns the amount of refunded fuel.
+    ///
+    /// # Errors
+    ///
+    /// If fuel metering is disabled.
+    pub fn refund_fuel(&mut self, delta: u64) -> Result<u64, FuelError> {
+        self.ctx.store.refund_fuel(delta)
+    }
+
+    /// Records a system call served by the host function in the execution trace.
+    ///
+    /// The `params` and `results` are recorded as raw bits.
+    /// Recorded system calls are listed under `syscalls` in the serialized trace.
+    pub fn record_syscall(&mut self, name: &str, params: &[u64], results: &[u64]) {
+        self.ctx.store.tracer.syscall(name, params, results)
+    }
 }
 
 impl<T> AsContext for Caller<'_, T> {
@@ -1,5 +1,5 @@
 use alloc::{boxed::Box, sync::Arc};
-use core::{fmt, fmt::Debug, num::NonZeroU32};
+use core::{fmt, fmt::Debug, future::Future, num::NonZeroU32};
 
 use wasmi_arena::ArenaIndex;
 
@@ -23,7 +23,9 @@ pub use self::{
     typed_func::{TypedFunc, WasmParams, WasmResults},
 };
 pub(crate) use self::typed_func::CallResultsTuple;
+use self::async_func::{into_async_func, AsyncCallGuard, AsyncHostCall};
 
+mod async_func;
 mod caller;
 mod error;
 mod func_type;
@@ -254,6 +256,24 @@ impl<T> HostFuncTrampolineEntity<T> {
         Self { ty, trampoline }
     }
 
+    /// Creates a new asynchronous host function trampoline from the given statically typed closure.
+    pub fn wrap_async<Params, Results>(
+        engine: &Engine,
+        func: impl for<'a> Fn(Caller<'a, T>, Params) -> Box<dyn Future<Output = Results> + 'a>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self
+    where
+        T: 'static,
+        Params: WasmTypeList + Send + 'static,
+        Results: WasmRet,
+    {
+        let (signature, trampoline) = into_async_func(func);
+        let ty = engine.alloc_func_type(signature);
+        Self { ty, trampoline }
+    }
+
     /// Returns the signature of the host function.
     pub fn ty_dedup(&self) -> &DedupFuncType {
         &self.ty
@@ -391,6 +411,40 @@ impl Func {
             .alloc_func(HostFuncEntity::new_with_meta(ty_dedup, func, 0, name).into())
     }
 
+    /// Creates a new asynchronous host function from the given closure.
+    ///
+    /// The closure receives the [`Caller`] and the parameters of the host function
+    /// as tuple and returns a boxed [`Future`] resolving to the results of the host function.
+    /// The [`Future`] may keep using the [`Caller`] across its suspension points.
+    ///
+    /// # Note
+    ///
+    /// - Within [`Func::call_async`] asynchronous host functions suspend the
+    ///   Wasm execution until their [`Future`] completes.
+    /// - Other call APIs poll the [`Future`] once and return an error if it is still pending.
+    pub fn wrap_async<T, Params, Results>(
+        mut ctx: impl AsContextMut<UserState = T>,
+        func: impl for<'a> Fn(Caller<'a, T>, Params) -> Box<dyn Future<Output = Results> + 'a>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self
+    where
+        T: 'static,
+        Params: WasmTypeList + Send + 'static,
+        Results: WasmRet,
+    {
+        let engine = ctx.as_context().store.engine();
+        let host_func = HostFuncTrampolineEntity::wrap_async(engine, func);
+        let ty_dedup = *host_func.ty_dedup();
+        let trampoline = host_func.trampoline().clone();
+        let func = ctx.as_context_mut().store.alloc_trampoline(trampoline);
+        ctx.as_context_mut()
+            .store
+            .inner
+            .alloc_func(HostFuncEntity::new(ty_dedup, func).into())
+    }
+
     /// Returns the signature of the function.
     pub(crate) fn ty_dedup<'a, T: 'a>(
         &self,
@@ -477,6 +531,66 @@ impl Func {
             .map(ResumableCall::new)
     }
 
+    /// Calls the Wasm or host function with the given inputs asynchronously.
+    ///
+    /// The result is written back into the `outputs` buffer.
+    ///
+    /// Whenever the execution calls an asynchronous host function created via
+    /// [`Func::wrap_async`] or [`Linker::func_wrap_async`] the execution is suspended
+    /// until the [`Future`] of the host function completes and then resumed with its results.
+    ///
+    /// # Errors
+    ///
+    /// - If the function returned a [`Trap`].
+    /// - If the types of the `inputs` do not match the expected types for the
+    ///   function signature of `self`.
+    /// - If the number of input values does not match the expected number of
+    ///   inputs required by the function signature of `self`.
+    /// - If the number of output values does not match the expected number of
+    ///   outputs required by the function signature of `self`.
+    ///
+    /// [`Linker::func_wrap_async`]: crate::Linker::func_wrap_async
+    pub async fn call_async<T: 'static>(
+        &self,
+        mut ctx: impl AsContextMut<UserState = T>,
+        inputs: &[Value],
+        outputs: &mut [Value],
+    ) -> Result<(), Error> {
+        let result = {
+            let mut guard = AsyncCallGuard::new(ctx.as_context_mut());
+            self.call_resumable(&mut guard.ctx, inputs, outputs)
+        };
+        let mut invocation = match result {
+            Ok(ResumableCall::Finished) => return Ok(()),
+            Ok(ResumableCall::Resumable(invocation)) => invocation,
+            Err(Error::Trap(trap)) => {
+                // Asynchronous host functions that are called without a Wasm caller,
+                // e.g. as root function, directly yield the results of the call.
+                let Some(call) = AsyncHostCall::<T>::take(&trap) else {
+                    return Err(Error::Trap(trap));
+                };
+                let results = call.call(&mut ctx).await?;
+                outputs.clone_from_slice(&results);
+                return Ok(());
+            }
+            Err(error) => return Err(error),
+        };
+        loop {
+            let Some(call) = AsyncHostCall::<T>::take(invocation.host_error()) else {
+                return Err(Error::Trap(invocation.into_host_error()));
+            };
+            let results = call.call(&mut ctx).await?;
+            let result = {
+                let mut guard = AsyncCallGuard::new(ctx.as_context_mut());
+                invocation.resume(&mut guard.ctx, &results, outputs)
+            };
+            invocation = match result? {
+                ResumableCall::Finished => return Ok(()),
+                ResumableCall::Resumable(invocation) => invocation,
+            };
+        }
+    }
+
     /// Verify that the `inputs` and `outputs` value types match the function signature.
     ///
     /// Since [`Func`] is a dynamically typed function instance there is
@@ -11,6 +11,7 @@ use crate::{
     Memory,
     Module,
     Table,
+    Tag,
 };
 use alloc::{boxed::Box, collections::BTreeMap, sync::Arc, vec::Vec};
 
@@ -22,6 +23,7 @@ pub struct InstanceEntityBuilder {
     funcs: Vec<Func>,
     memories: Vec<Memory>,
     globals: Vec<Global>,
+    tags: Vec<Tag>,
     start_fn: Option<FuncIdx>,
     exports: BTreeMap<Box<str>, Extern>,
     data_segments: Vec<DataSegment>,
@@ -40,6 +42,7 @@ impl InstanceEntityBuilder {
         let mut len_globals = module.len_globals();
         let mut len_tables = module.len_tables();
         let mut len_memories = module.len_memories();
+        let mut len_tags = module.len_tags();
         for import in module.imports() {
             match import.ty() {
                 ExternType::Func(_) => {
@@ -54,6 +57,9 @@ impl InstanceEntityBuilder {
                 ExternType::Global(_) => {
                     len_globals += 1;
                 }
+                ExternType::Tag(_) => {
+                    len_tags += 1;
+                }
             }
         }
         Self {
@@ -62,6 +68,7 @@ impl InstanceEntityBuilder {
             funcs: vec_with_capacity_exact(len_funcs),
             memories: vec_with_capacity_exact(len_memories),
             globals: vec_with_capacity_exact(len_globals),
+            tags: vec_with_capacity_exact(len_tags),
             start_fn: None,
             exports: BTreeMap::default(),
             data_segments: Vec::new(),
@@ -124,6 +131,18 @@ impl InstanceEntityBuilder {
             .unwrap_or_else(|| panic!("missing `Global` at index: {index}"))
     }
 
+    /// Returns the [`Tag`] at the `index`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no [`Tag`] at the given `index.
+    pub fn get_tag(&self, index: u32) -> Tag {
+        self.tags
+            .get(index as usize)
+            .copied()
+            .unwrap_or_else(|| panic!("missing `Tag` at index: {index}"))
+    }
+
     /// Returns the function at the `index`.
     ///
     /// # Panics
@@ -151,6 +170,11 @@ impl InstanceEntityBuilder {
         self.globals.push(global);
     }
 
+    /// Pushes a new [`Tag`] to the [`InstanceEntity`] under construction.
+    pub fn push_tag(&mut self, tag: Tag) {
+        self.tags.push(tag);
+    }
+
     /// Pushes a new [`Func`] to the [`InstanceEntity`] under construction.
     pub fn push_func(&mut self, func: Func) {
         self.funcs.push(func);
@@ -190,6 +214,7 @@ impl InstanceEntityBuilder {
             funcs: self.funcs.into(),
             memories: self.memories.into(),
             globals: self.globals.into(),
+            tags: self.tags.into(),
             exports: self.exports,
             data_segments: self.data_segments.into(),
             elem_segments: self.elem_segments.into(),
@@ -1,4 +1,16 @@
-use crate::{AsContext, Func, FuncType, Global, GlobalType, Memory, MemoryType, Table, TableType};
+use crate::{
+    AsContext,
+    Func,
+    FuncType,
+    Global,
+    GlobalType,
+    Memory,
+    MemoryType,
+    Table,
+    TableType,
+    Tag,
+    TagType,
+};
 use alloc::{boxed::Box, collections::btree_map};
 use core::iter::FusedIterator;
 
@@ -18,6 +30,8 @@ pub enum Extern {
     Memory(Memory),
     /// A WebAssembly function which can be called.
     Func(Func),
+    /// A WebAssembly exception tag.
+    Tag(Tag),
 }
 
 impl From<Global> for Extern {
@@ -44,6 +58,12 @@ impl From<Func> for Extern {
     }
 }
 
+impl From<Tag> for Extern {
+    fn from(tag: Tag) -> Self {
+        Self::Tag(tag)
+    }
+}
+
 impl Extern {
     /// Returns the underlying global variable if `self` is a global variable.
     ///
@@ -85,6 +105,16 @@ impl Extern {
         None
     }
 
+    /// Returns the underlying exception tag if `self` is a tag.
+    ///
+    /// Returns `None` otherwise.
+    pub fn into_tag(self) -> Option<Tag> {
+        if let Self::Tag(tag) = self {
+            return Some(tag);
+        }
+        None
+    }
+
     /// Returns the type associated with this [`Extern`].
     ///
     /// # Panics
@@ -96,6 +126,7 @@ impl Extern {
             Extern::Table(table) => table.ty(ctx).into(),
             Extern::Memory(memory) => memory.ty(ctx).into(),
             Extern::Func(func) => func.ty(ctx).into(),
+            Extern::Tag(tag) => tag.ty(ctx).into(),
         }
     }
 }
@@ -113,6 +144,8 @@ pub enum ExternType {
     Memory(MemoryType),
     /// The type of an [`Extern::Func`].
     Func(FuncType),
+    /// The type of an [`Extern::Tag`].
+    Tag(TagType),
 }
 
 impl From<GlobalType> for ExternType {
@@ -139,6 +172,12 @@ impl From<FuncType> for ExternType {
     }
 }
 
+impl From<TagType> for ExternType {
+    fn from(tag: TagType) -> Self {
+        Self::Tag(tag)
+    }
+}
+
 impl ExternType {
     /// Returns the underlying [`GlobalType`] or `None` if it is of a different type.
     pub fn global(&self) -> Option<&GlobalType> {
@@ -171,6 +210,14 @@ impl ExternType {
             _ => None,
         }
     }
+
+    /// Returns the underlying [`TagType`] or `None` if it is of a different type.
+    pub fn tag(&self) -> Option<&TagType> {
+        match self {
+            Self::Tag(ty) => Some(ty),
+            _ => None,
+        }
+    }
 }
 
 /// An exported WebAssembly value.
@@ -229,6 +276,11 @@ impl<'instance> Export<'instance> {
     pub fn into_global(self) -> Option<Global> {
         self.definition.into_global()
     }
+
+    /// Returns the underlying [`Tag`], if the [`Export`] is an exception tag or `None` otherwise.
+    pub fn into_tag(self) -> Option<Tag> {
+        self.definition.into_tag()
+    }
 }
 
 /// An iterator over the [`Extern`] declarations of an [`Instance`](crate::Instance).
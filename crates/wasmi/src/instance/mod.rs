@@ -10,6 +10,7 @@ use super::{
     StoreContext,
     Stored,
     Table,
+    Tag,
 };
 use crate::{
     func::FuncError,
@@ -52,6 +53,7 @@ pub struct InstanceEntity {
     funcs: Box<[Func]>,
     memories: Box<[Memory]>,
     globals: Box<[Global]>,
+    tags: Box<[Tag]>,
     exports: BTreeMap<Box<str>, Extern>,
     data_segments: Box<[DataSegment]>,
     elem_segments: Box<[ElementSegment]>,
@@ -67,6 +69,7 @@ impl InstanceEntity {
             funcs: [].into(),
             memories: [].into(),
             globals: [].into(),
+            tags: [].into(),
             exports: BTreeMap::new(),
             data_segments: [].into(),
             elem_segments: [].into(),
@@ -103,6 +106,11 @@ impl InstanceEntity {
         self.funcs.get(index as usize).copied()
     }
 
+    /// Returns the exception tag at the `index` if any.
+    pub fn get_tag(&self, index: u32) -> Option<Tag> {
+        self.tags.get(index as usize).copied()
+    }
+
     /// Returns the signature at the `index` if any.
     pub fn get_signature(&self, index: u32) -> Option<&DedupFuncType> {
         self.func_types.get(index as usize)
@@ -244,6 +252,18 @@ impl Instance {
         self.get_export(store, name)?.into_global()
     }
 
+    /// Looks up an exported [`Tag`] value by `name`.
+    ///
+    /// Returns `None` if there was no export named `name`,
+    /// or if there was but it wasn’t an exception tag.
+    ///
+    /// # Panics
+    ///
+    /// If `store` does not own this [`Instance`].
+    pub fn get_tag(&self, store: impl AsContext, name: &str) -> Option<Tag> {
+        self.get_export(store, name)?.into_tag()
+    }
+
     /// Looks up an exported [`Table`] value by `name`.
     ///
     /// Returns `None` if there was no export named `name`,
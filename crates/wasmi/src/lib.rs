@@ -76,7 +76,7 @@
     clippy::default_trait_access,
     clippy::items_after_statements
 )]
-#![recursion_limit = "750"]
+#![recursion_limit = "1000"]
 
 #[cfg(not(feature = "std"))]
 #[macro_use]
@@ -93,11 +93,13 @@ mod externref;
 mod func;
 mod global;
 mod instance;
+mod limits;
 mod linker;
 mod memory;
 mod module;
 mod store;
 mod table;
+mod tag;
 mod value;
 
 /// Definitions from the `wasmi_core` crate.
@@ -119,16 +121,35 @@ pub mod errors {
 
 pub use self::{
     engine::{
-        tracer::OpCodeState,
+        tracer::{FunctionMeta, OpCodeState, SyscallState, Tracer},
+        Breakpoint,
         Config,
+        DebugAction,
+        DebugContext,
+        Debugger,
         Engine,
         FuelConsumptionMode,
+        FuelCosts,
+        FuncProfile,
+        HostCall,
+        HostCallDivergence,
+        HostCallLog,
+        HostCallMode,
+        InstrCost,
+        MemoryGrow,
+        MemoryWrite,
+        PauseReason,
+        ProfileWeight,
+        Profiler,
         ResumableCall,
         ResumableInvocation,
         StackLimits,
         TypedResumableCall,
         TypedResumableInvocation,
         Instruction,
+        InstrMeta,
+        FuncBody,
+        FuncHeader,
         DropKeep,
         BranchParams,
         BranchOffset,
@@ -150,13 +171,24 @@ pub use self::{
     },
     global::{Global, GlobalType, Mutability},
     instance::{Export, ExportsIter, Extern, ExternType, Instance},
+    limits::{
+        ResourceLimiter,
+        StoreLimits,
+        StoreLimitsBuilder,
+        DEFAULT_INSTANCE_LIMIT,
+        DEFAULT_MEMORY_LIMIT,
+        DEFAULT_TABLE_LIMIT,
+    },
     linker::Linker,
-    memory::{Memory, MemoryType},
+    memory::{Memory, MemoryType, SharedMemory, ThreadParker, WaitResult},
     module::*,
-    store::{AsContext, AsContextMut, Store, StoreContext, StoreContextMut},
+    store::{AsContext, AsContextMut, FuelFrame, Store, StoreContext, StoreContextMut},
     table::{Table, TableType},
+    tag::{Exception, Tag, TagType},
     value::Value,
 };
+#[cfg(feature = "std")]
+pub use self::memory::StdThreadParker;
 use self::{
     func::{FuncEntity, FuncIdx},
     global::{GlobalEntity, GlobalIdx},
@@ -164,4 +196,5 @@ use self::{
     memory::{DataSegmentEntity, DataSegmentIdx, MemoryEntity, MemoryIdx},
     store::{StoreInner, Stored},
     table::{ElementSegment, ElementSegmentEntity, ElementSegmentIdx, TableEntity, TableIdx},
+    tag::{TagEntity, TagIdx},
 };
@@ -0,0 +1,348 @@
+//! Resource limiting of the linear memories, tables and instances of a [`Store`].
+//!
+//! [`Store`]: crate::Store
+
+use crate::errors::{MemoryError, TableError};
+use alloc::boxed::Box;
+
+/// The default limit for the number of instances of a [`Store`].
+///
+/// [`Store`]: crate::Store
+pub const DEFAULT_INSTANCE_LIMIT: usize = 10_000;
+
+/// The default limit for the number of tables of a [`Store`].
+///
+/// [`Store`]: crate::Store
+pub const DEFAULT_TABLE_LIMIT: usize = 10_000;
+
+/// The default limit for the number of linear memories of a [`Store`].
+///
+/// [`Store`]: crate::Store
+pub const DEFAULT_MEMORY_LIMIT: usize = 10_000;
+
+/// Used by hosts to limit the resource consumption of the Wasm instances of a [`Store`].
+///
+/// Install a [`ResourceLimiter`] via [`Store::limiter`]. It is consulted whenever a linear memory
+/// or a table is created or grown as well as upon every module instantiation.
+///
+/// # Denial
+///
+/// - Returning `Ok(false)` from a `*_growing` method denies the allocation.
+///   The `memory.grow` and `table.grow` Wasm instructions then return `-1`
+///   whereas the creation of a linear memory or table fails with an error.
+/// - Returning `Err` from a `*_growing` method denies the allocation and
+///   traps the `memory.grow` and `table.grow` Wasm instructions with
+///   [`TrapCode::GrowthOperationLimited`].
+///
+/// [`Store`]: crate::Store
+/// [`Store::limiter`]: crate::Store::limiter
+/// [`TrapCode::GrowthOperationLimited`]: crate::core::TrapCode::GrowthOperationLimited
+pub trait ResourceLimiter {
+    /// Called when a linear memory is created or grows from `current` to `desired` bytes.
+    ///
+    /// The `maximum` is the number of bytes declared by the linear memory type if any.
+    /// Upon creation the `current` size is zero and `desired` is the initial size.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation shall be denied with a trap.
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool, MemoryError>;
+
+    /// Called when a table is created or grows from `current` to `desired` elements.
+    ///
+    /// The `maximum` is the number of elements declared by the table type if any.
+    /// Upon creation the `current` size is zero and `desired` is the initial size.
+    ///
+    /// # Errors
+    ///
+    /// If the allocation shall be denied with a trap.
+    fn table_growing(
+        &mut self,
+        current: u32,
+        desired: u32,
+        maximum: Option<u32>,
+    ) -> Result<bool, TableError>;
+
+    /// Called after a linear memory has been created or has grown from `current` to `desired` bytes.
+    ///
+    /// Unlike [`ResourceLimiter::memory_growing`] this is only called once the allocation
+    /// succeeded and therefore is the place to account for the consumed memory.
+    fn memory_grown(&mut self, _current: usize, _desired: usize) {}
+
+    /// Called after a table has been created or has grown from `current` to `desired` elements.
+    ///
+    /// Unlike [`ResourceLimiter::table_growing`] this is only called once the allocation
+    /// succeeded and therefore is the place to account for the consumed elements.
+    fn table_grown(&mut self, _current: u32, _desired: u32) {}
+
+    /// Called when growing a linear memory failed for any reason.
+    fn memory_grow_failed(&mut self, _error: &MemoryError) {}
+
+    /// Called when growing a table failed for any reason.
+    fn table_grow_failed(&mut self, _error: &TableError) {}
+
+    /// Returns the maximum number of instances of the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    fn instances(&self) -> usize;
+
+    /// Returns the maximum number of tables defined by Wasm modules of the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    fn tables(&self) -> usize;
+
+    /// Returns the maximum number of linear memories defined by Wasm modules of the [`Store`].
+    ///
+    /// [`Store`]: crate::Store
+    fn memories(&self) -> usize;
+}
+
+/// The [`ResourceLimiter`] of a [`Store`] borrowed for a single operation if any.
+///
+/// [`Store`]: crate::Store
+pub(crate) struct ResourceLimiterRef<'a>(Option<&'a mut dyn ResourceLimiter>);
+
+impl core::fmt::Debug for ResourceLimiterRef<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ResourceLimiterRef")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+impl<'a> ResourceLimiterRef<'a> {
+    /// Queries the [`ResourceLimiter`] from the host `data` if a `query` is installed.
+    pub fn new<T>(query: &'a mut Option<ResourceLimiterQuery<T>>, data: &'a mut T) -> Self {
+        match query {
+            Some(query) => Self(Some(query.query(data))),
+            None => Self(None),
+        }
+    }
+
+    /// Returns the [`ResourceLimiter`] if any.
+    pub fn as_resource_limiter(&mut self) -> Option<&mut dyn ResourceLimiter> {
+        match &mut self.0 {
+            Some(limiter) => Some(&mut **limiter),
+            None => None,
+        }
+    }
+}
+
+/// A [`ResourceLimiter`] with static limits for linear memories, tables and instances.
+///
+/// Create it via [`StoreLimitsBuilder`].
+#[derive(Debug, Clone)]
+pub struct StoreLimits {
+    /// The maximum number of bytes of a single linear memory if any.
+    memory_size: Option<usize>,
+    /// The maximum number of bytes of all linear memories together if any.
+    total_memory_size: Option<usize>,
+    /// The number of bytes allocated by all linear memories so far.
+    total_memory_consumed: usize,
+    /// The maximum number of elements of a single table if any.
+    table_elements: Option<u32>,
+    /// The maximum number of elements of all tables together if any.
+    total_table_elements: Option<usize>,
+    /// The number of elements allocated by all tables so far.
+    total_table_elements_consumed: usize,
+    /// The maximum number of instances.
+    instances: usize,
+    /// The maximum number of tables.
+    tables: usize,
+    /// The maximum number of linear memories.
+    memories: usize,
+    /// Whether denied `memory.grow` and `table.grow` instructions trap instead of returning `-1`.
+    trap_on_grow_failure: bool,
+}
+
+impl Default for StoreLimits {
+    fn default() -> Self {
+        Self {
+            memory_size: None,
+            total_memory_size: None,
+            total_memory_consumed: 0,
+            table_elements: None,
+            total_table_elements: None,
+            total_table_elements_consumed: 0,
+            instances: DEFAULT_INSTANCE_LIMIT,
+            tables: DEFAULT_TABLE_LIMIT,
+            memories: DEFAULT_MEMORY_LIMIT,
+            trap_on_grow_failure: false,
+        }
+    }
+}
+
+impl StoreLimits {
+    /// Returns the number of bytes allocated by all linear memories so far.
+    pub fn total_memory_consumed(&self) -> usize {
+        self.total_memory_consumed
+    }
+
+    /// Returns the number of elements allocated by all tables so far.
+    pub fn total_table_elements_consumed(&self) -> usize {
+        self.total_table_elements_consumed
+    }
+}
+
+impl ResourceLimiter for StoreLimits {
+    fn memory_growing(
+        &mut self,
+        current: usize,
+        desired: usize,
+        maximum: Option<usize>,
+    ) -> Result<bool, MemoryError> {
+        let delta = desired.saturating_sub(current);
+        let exceeds =
+            |limit: Option<usize>, size: usize| matches!(limit, Some(limit) if size > limit);
+        let allow = !exceeds(self.memory_size, desired)
+            && !exceeds(maximum, desired)
+            && !exceeds(
+                self.total_memory_size,
+                self.total_memory_consumed.saturating_add(delta),
+            );
+        if !allow && self.trap_on_grow_failure {
+            return Err(MemoryError::ResourceLimiterDeniedAllocation);
+        }
+        Ok(allow)
+    }
+
+    fn memory_grown(&mut self, current: usize, desired: usize) {
+        self.total_memory_consumed += desired.saturating_sub(current);
+    }
+
+    fn table_growing(
+        &mut self,
+        current: u32,
+        desired: u32,
+        maximum: Option<u32>,
+    ) -> Result<bool, TableError> {
+        let delta = desired.saturating_sub(current) as usize;
+        let exceeds = |limit: Option<u32>| matches!(limit, Some(limit) if desired > limit);
+        let exceeds_total = matches!(
+            self.total_table_elements,
+            Some(limit) if self.total_table_elements_consumed.saturating_add(delta) > limit
+        );
+        let allow = !exceeds(self.table_elements) && !exceeds(maximum) && !exceeds_total;
+        if !allow && self.trap_on_grow_failure {
+            return Err(TableError::ResourceLimiterDeniedAllocation);
+        }
+        Ok(allow)
+    }
+
+    fn table_grown(&mut self, current: u32, desired: u32) {
+        self.total_table_elements_consumed += desired.saturating_sub(current) as usize;
+    }
+
+    fn instances(&self) -> usize {
+        self.instances
+    }
+
+    fn tables(&self) -> usize {
+        self.tables
+    }
+
+    fn memories(&self) -> usize {
+        self.memories
+    }
+}
+
+/// A builder for [`StoreLimits`].
+#[derive(Debug, Default, Clone)]
+pub struct StoreLimitsBuilder(StoreLimits);
+
+impl StoreLimitsBuilder {
+    /// Creates a new [`StoreLimitsBuilder`] with the default limits.
+    ///
+    /// By default the sizes of linear memories and tables are unlimited and at most
+    /// [`DEFAULT_INSTANCE_LIMIT`] instances, [`DEFAULT_TABLE_LIMIT`] tables and
+    /// [`DEFAULT_MEMORY_LIMIT`] linear memories are allowed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits every single linear memory to at most `limit` bytes.
+    pub fn memory_size(mut self, limit: usize) -> Self {
+        self.0.memory_size = Some(limit);
+        self
+    }
+
+    /// Limits all linear memories together to at most `limit` bytes.
+    pub fn total_memory_size(mut self, limit: usize) -> Self {
+        self.0.total_memory_size = Some(limit);
+        self
+    }
+
+    /// Limits every single table to at most `limit` elements.
+    pub fn table_elements(mut self, limit: u32) -> Self {
+        self.0.table_elements = Some(limit);
+        self
+    }
+
+    /// Limits all tables together to at most `limit` elements.
+    pub fn total_table_elements(mut self, limit: usize) -> Self {
+        self.0.total_table_elements = Some(limit);
+        self
+    }
+
+    /// Limits the number of instances to at most `limit`.
+    pub fn instances(mut self, limit: usize) -> Self {
+        self.0.instances = limit;
+        self
+    }
+
+    /// Limits the number of tables defined by Wasm modules to at most `limit`.
+    pub fn tables(mut self, limit: usize) -> Self {
+        self.0.tables = limit;
+        self
+    }
+
+    /// Limits the number of linear memories defined by Wasm modules to at most `limit`.
+    pub fn memories(mut self, limit: usize) -> Self {
+        self.0.memories = limit;
+        self
+    }
+
+    /// Traps denied `memory.grow` and `table.grow` instructions instead of returning `-1`.
+    pub fn trap_on_grow_failure(mut self, trap: bool) -> Self {
+        self.0.trap_on_grow_failure = trap;
+        self
+    }
+
+    /// Finishes building the [`StoreLimits`].
+    pub fn build(self) -> StoreLimits {
+        self.0
+    }
+}
+
+/// The closure installed via [`Store::limiter`] to query the [`ResourceLimiter`] from the host state.
+///
+/// [`Store::limiter`]: crate::Store::limiter
+pub(crate) struct ResourceLimiterQuery<T>(
+    Box<dyn FnMut(&mut T) -> &mut (dyn ResourceLimiter) + Send + Sync>,
+);
+
+impl<T> core::fmt::Debug for ResourceLimiterQuery<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ResourceLimiterQuery")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> ResourceLimiterQuery<T> {
+    /// Creates a new [`ResourceLimiterQuery`] from the closure `query`.
+    pub fn new(
+        query: impl FnMut(&mut T) -> &mut (dyn ResourceLimiter) + Send + Sync + 'static,
+    ) -> Self {
+        Self(Box::new(query))
+    }
+
+    /// Queries the [`ResourceLimiter`] from the host `data`.
+    pub fn query<'a>(&mut self, data: &'a mut T) -> &'a mut dyn ResourceLimiter {
+        (self.0)(data)
+    }
+}
@@ -12,21 +12,29 @@ use crate::{
     Func,
     FuncType,
     GlobalType,
+    Instance,
     InstancePre,
     IntoFunc,
     MemoryType,
     Module,
+    StoreContextMut,
     TableType,
+    TagType,
     Value,
+    WasmRet,
+    WasmTypeList,
 };
 use alloc::{
+    boxed::Box,
     collections::{btree_map::Entry, BTreeMap},
+    format,
     sync::Arc,
     vec::Vec,
 };
 use core::{
     fmt,
     fmt::{Debug, Display},
+    future::Future,
     num::NonZeroUsize,
     ops::Deref,
 };
@@ -91,6 +99,15 @@ pub enum LinkerError {
         /// The mismatching [`GlobalType`] found.
         found: GlobalType,
     },
+    /// Encountered when a [`TagType`] does not match the expected [`TagType`].
+    TagTypeMismatch {
+        /// The name of the import with the mismatched type.
+        name: ImportName,
+        /// The expected [`TagType`].
+        expected: TagType,
+        /// The mismatching [`TagType`] found.
+        found: TagType,
+    },
 }
 
 impl LinkerError {
@@ -146,6 +163,15 @@ impl LinkerError {
             found: *found,
         }
     }
+
+    /// Create a new [`LinkerError`] for when a [`TagType`] mismatched.
+    fn tag_type_mismatch(name: &ImportName, expected: &TagType, found: &TagType) -> Self {
+        Self::TagTypeMismatch {
+            name: name.clone(),
+            expected: expected.clone(),
+            found: found.clone(),
+        }
+    }
 }
 
 #[cfg(feature = "std")]
@@ -207,6 +233,17 @@ impl Display for LinkerError {
                     expected {expected:?} but found {found:?}",
                 )
             }
+            Self::TagTypeMismatch {
+                name,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "exception tag type mismatch for import {name}: \
+                    expected {expected:?} but found {found:?}",
+                )
+            }
         }
     }
 }
@@ -429,6 +466,11 @@ impl<'a, T> Debug for DebugHostFuncEntity<'a, T> {
     }
 }
 
+/// A resolver for imports that have no definition in a [`Linker`].
+///
+/// See [`Linker::set_fallback_resolver`].
+type FallbackResolver<T> = dyn Fn(StoreContextMut<T>, &ImportType) -> Option<Extern> + Send + Sync;
+
 /// A linker used to define module imports and instantiate module instances.
 pub struct Linker<T> {
     /// The underlying [`Engine`] for the [`Linker`].
@@ -442,6 +484,10 @@ pub struct Linker<T> {
     strings: StringInterner,
     /// Stores the definitions given their names.
     definitions: BTreeMap<ImportKey, Definition<T>>,
+    /// Is `true` if later definitions may replace earlier definitions of the same name.
+    allow_shadowing: bool,
+    /// Resolves imports that have no definition in the [`Linker`] if any.
+    fallback: Option<Arc<FallbackResolver<T>>>,
 }
 
 impl<T> Debug for Linker<T> {
@@ -449,6 +495,8 @@ impl<T> Debug for Linker<T> {
         f.debug_struct("Linker")
             .field("strings", &self.strings)
             .field("definitions", &DebugDefinitions::new(self))
+            .field("allow_shadowing", &self.allow_shadowing)
+            .field("fallback", &self.fallback.is_some())
             .finish()
     }
 }
@@ -459,6 +507,8 @@ impl<T> Clone for Linker<T> {
             engine: self.engine.clone(),
             strings: self.strings.clone(),
             definitions: self.definitions.clone(),
+            allow_shadowing: self.allow_shadowing,
+            fallback: self.fallback.clone(),
         }
     }
 }
@@ -476,6 +526,8 @@ impl<T> Linker<T> {
             engine: engine.clone(),
             strings: StringInterner::default(),
             definitions: BTreeMap::default(),
+            allow_shadowing: false,
+            fallback: None,
         }
     }
 
@@ -484,6 +536,65 @@ impl<T> Linker<T> {
         &self.engine
     }
 
+    /// Configures whether later definitions may replace earlier definitions of the same name.
+    ///
+    /// By default shadowing is disabled and redefinitions are reported as
+    /// [`LinkerError::DuplicateDefinition`].
+    pub fn allow_shadowing(&mut self, allow: bool) -> &mut Self {
+        self.allow_shadowing = allow;
+        self
+    }
+
+    /// Sets the `resolver` that is queried for imports without a definition in this [`Linker`].
+    ///
+    /// The `resolver` is given the store of the instantiation so that it can
+    /// create the resolved items, such as [`Func`]s, on demand.
+    /// Items returned by the `resolver` are type checked just like regular definitions.
+    /// If the `resolver` returns `None` the import is reported as [`LinkerError::MissingDefinition`].
+    pub fn set_fallback_resolver(
+        &mut self,
+        resolver: impl Fn(StoreContextMut<T>, &ImportType) -> Option<Extern> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.fallback = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Defines all function imports of the `module` without a definition as trapping stubs.
+    ///
+    /// This allows to instantiate modules with unused function imports, for example to inspect
+    /// or partially execute them. Calling a stub traps with a message naming the import.
+    ///
+    /// # Note
+    ///
+    /// - Imports of other kinds, such as memories or globals, are left undefined.
+    /// - The stubs take precedence over the resolver set via [`Linker::set_fallback_resolver`].
+    pub fn define_unknown_imports_as_traps(&mut self, module: &Module) -> &mut Self {
+        for import in module.imports() {
+            let func_type = match import.ty() {
+                ExternType::Func(func_type) => func_type.clone(),
+                _ => continue,
+            };
+            let key = self.import_key(import.module(), import.name());
+            if self.definitions.contains_key(&key) {
+                continue;
+            }
+            let message = format!(
+                "unknown import `{}::{}` has not been defined",
+                import.module(),
+                import.name()
+            );
+            let func = HostFuncTrampolineEntity::new(
+                &self.engine,
+                func_type,
+                move |_caller: Caller<'_, T>, _params: &[Value], _results: &mut [Value]| {
+                    Err(Trap::new(message.clone()))
+                },
+            );
+            self.definitions.insert(key, Definition::HostFunc(func));
+        }
+        self
+    }
+
     /// Define a new item in this [`Linker`].
     ///
     /// # Errors
@@ -553,6 +664,119 @@ impl<T> Linker<T> {
         Ok(self)
     }
 
+    /// Creates a new named asynchronous host [`Func`] for this [`Linker`].
+    ///
+    /// The closure receives the [`Caller`] and the parameters of the host function
+    /// as tuple and returns a boxed [`Future`] resolving to the results of the host function.
+    ///
+    /// For more information see [`Func::wrap_async`] and [`Func::call_async`].
+    ///
+    /// # Errors
+    ///
+    /// If there already is a definition under the same name for this [`Linker`].
+    pub fn func_wrap_async<Params, Results>(
+        &mut self,
+        module: &str,
+        name: &str,
+        func: impl for<'a> Fn(Caller<'a, T>, Params) -> Box<dyn Future<Output = Results> + 'a>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Result<&mut Self, LinkerError>
+    where
+        T: 'static,
+        Params: WasmTypeList + Send + 'static,
+        Results: WasmRet,
+    {
+        let func = HostFuncTrampolineEntity::wrap_async(&self.engine, func);
+        let key = self.import_key(module, name);
+        self.insert(key, Definition::HostFunc(func))?;
+        Ok(self)
+    }
+
+    /// Defines all exports of the `instance` in this [`Linker`] under `module_name`.
+    ///
+    /// This allows other modules to import the exports of an already instantiated
+    /// [`Instance`] using `module_name` as their import module name.
+    ///
+    /// # Panics
+    ///
+    /// If the [`Engine`] of this [`Linker`] and the [`Engine`] of `context` are not the same.
+    ///
+    /// # Errors
+    ///
+    /// If there already is a definition for any of the exports under `module_name`.
+    /// In this case none of the exports are defined.
+    pub fn instance(
+        &mut self,
+        context: impl AsContext<UserState = T>,
+        module_name: &str,
+        instance: Instance,
+    ) -> Result<&mut Self, LinkerError> {
+        assert!(Engine::same(self.engine(), context.as_context().engine()));
+        let exports = instance
+            .exports(&context)
+            .map(|export| (Box::<str>::from(export.name()), export.into_extern()))
+            .collect::<Vec<_>>();
+        self.ensure_undefined(module_name, exports.iter().map(|(name, _)| &**name))?;
+        for (name, item) in exports {
+            self.define(module_name, &name, item)?;
+        }
+        Ok(self)
+    }
+
+    /// Instantiates the `module` and defines all of its exports under `module_name`.
+    ///
+    /// The start function of the `module` is executed before its exports are defined.
+    /// This is a convenience method for [`Linker::instantiate`] followed by
+    /// [`Linker::instance`] with the started [`Instance`].
+    ///
+    /// # Panics
+    ///
+    /// If the [`Engine`] of this [`Linker`] and the [`Engine`] of `context` are not the same.
+    ///
+    /// # Errors
+    ///
+    /// - If there already is a definition for any of the exports under `module_name`.
+    ///   This is checked before the `module` is instantiated.
+    /// - If the instantiation of the `module` or the execution of its start function fails.
+    pub fn module(
+        &mut self,
+        mut context: impl AsContextMut<UserState = T>,
+        module_name: &str,
+        module: &Module,
+    ) -> Result<&mut Self, Error> {
+        self.ensure_undefined(module_name, module.exports().map(|export| export.name()))?;
+        let instance = self
+            .instantiate(&mut context, module)?
+            .start(&mut context)?;
+        self.instance(&context, module_name, instance)?;
+        Ok(self)
+    }
+
+    /// Ensures that none of the `names` is defined under `module_name` in this [`Linker`].
+    ///
+    /// Always succeeds if shadowing is allowed.
+    ///
+    /// # Errors
+    ///
+    /// If there already is a definition for any of the `names` under `module_name`.
+    fn ensure_undefined<'a>(
+        &self,
+        module_name: &str,
+        mut names: impl Iterator<Item = &'a str>,
+    ) -> Result<(), LinkerError> {
+        if self.allow_shadowing {
+            return Ok(());
+        }
+        match names.find(|name| self.has_definition(module_name, name)) {
+            Some(name) => Err(LinkerError::DuplicateDefinition {
+                import_name: ImportName::new(module_name, name),
+            }),
+            None => Ok(()),
+        }
+    }
+
     /// Returns the import key for the module name and item name.
     fn import_key(&mut self, module: &str, name: &str) -> ImportKey {
         ImportKey {
@@ -572,9 +796,13 @@ impl<T> Linker<T> {
     ///
     /// # Errors
     ///
-    /// If there already is a definition for the import key for this [`Linker`].
+    /// If there already is a definition for the import key for this [`Linker`]
+    /// and shadowing is not allowed.
     fn insert(&mut self, key: ImportKey, item: Definition<T>) -> Result<(), LinkerError> {
         match self.definitions.entry(key) {
+            Entry::Occupied(mut entry) if self.allow_shadowing => {
+                entry.insert(item);
+            }
             Entry::Occupied(_) => {
                 let (module_name, field_name) = self
                     .resolve_import_key(key)
@@ -609,6 +837,21 @@ impl<T> Linker<T> {
         }
     }
 
+    /// Returns `true` if there is a definition for `module` and `name` in this [`Linker`].
+    ///
+    /// # Note
+    ///
+    /// Items that are only provided by the resolver set via
+    /// [`Linker::set_fallback_resolver`] are not taken into account.
+    pub fn has_definition(&self, module: &str, name: &str) -> bool {
+        match (self.strings.get(module), self.strings.get(name)) {
+            (Some(module), Some(name)) => {
+                self.definitions.contains_key(&ImportKey { module, name })
+            }
+            _ => false,
+        }
+    }
+
     /// Looks up a [`Definition`] by name in this [`Linker`].
     ///
     /// Returns `None` if this name was not previously defined in this [`Linker`].
@@ -674,9 +917,19 @@ impl<T> Linker<T> {
         let import_name = import.import_name();
         let module_name = import.module();
         let field_name = import.name();
-        let resolved = self
-            .get_definition(context.as_context(), module_name, field_name)
-            .ok_or_else(|| LinkerError::missing_definition(&import))?;
+        let fallback;
+        let resolved = match self.get_definition(context.as_context(), module_name, field_name) {
+            Some(definition) => definition,
+            None => {
+                let item = self
+                    .fallback
+                    .as_ref()
+                    .and_then(|resolver| resolver(context.as_context_mut(), &import))
+                    .ok_or_else(|| LinkerError::missing_definition(&import))?;
+                fallback = Definition::Extern(item);
+                &fallback
+            }
+        };
         let invalid_type = || LinkerError::invalid_type_definition(&import, &resolved.ty(&context));
         match import.ty() {
             ExternType::Func(expected_type) => {
@@ -739,6 +992,23 @@ impl<T> Linker<T> {
                 }
                 Ok(Extern::Global(global))
             }
+            ExternType::Tag(expected_type) => {
+                let tag = resolved
+                    .as_extern()
+                    .copied()
+                    .and_then(Extern::into_tag)
+                    .ok_or_else(invalid_type)?;
+                let found_type = tag.ty(context);
+                if &found_type != expected_type {
+                    return Err(LinkerError::tag_type_mismatch(
+                        import_name,
+                        expected_type,
+                        &found_type,
+                    ))
+                    .map_err(Into::into);
+                }
+                Ok(Extern::Tag(tag))
+            }
         }
     }
 }
@@ -849,4 +1119,304 @@ mod tests {
         wasm_set_b.call(&mut store, 200).unwrap();
         assert_eq!(wasm_get_b.call(&mut store, ()).unwrap(), 200);
     }
+
+    /// Compiles the `wat` into a [`Module`] for the `engine`.
+    fn module(engine: &Engine, wat: &str) -> Module {
+        let wasm = wat::parse_str(wat).unwrap();
+        Module::new(engine, &mut &wasm[..]).unwrap()
+    }
+
+    const LIB: &str = r#"
+        (module
+            (global $counter (export "counter") (mut i32) (i32.const 0))
+            (func (export "add") (param i32 i32) (result i32)
+                (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+                (i32.add (local.get 0) (local.get 1))
+            )
+        )
+    "#;
+
+    const APP: &str = r#"
+        (module
+            (import "lib" "add" (func $add (param i32 i32) (result i32)))
+            (import "lib" "counter" (global $counter (mut i32)))
+            (func (export "add_twice") (param i32) (result i32)
+                (call $add (call $add (local.get 0) (local.get 0)) (local.get 0))
+            )
+            (func (export "calls") (result i32)
+                (global.get $counter)
+            )
+        )
+    "#;
+
+    #[test]
+    fn linker_instance_works() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        let lib = linker
+            .instantiate(&mut store, &module(&engine, LIB))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        linker.instance(&store, "lib", lib).unwrap();
+        assert!(linker.get(&store, "lib", "add").is_some());
+        assert!(linker.get(&store, "lib", "counter").is_some());
+        let app = linker
+            .instantiate(&mut store, &module(&engine, APP))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let add_twice = app.get_typed_func::<i32, i32>(&store, "add_twice").unwrap();
+        let calls = app.get_typed_func::<(), i32>(&store, "calls").unwrap();
+        assert_eq!(add_twice.call(&mut store, 5).unwrap(), 15);
+        assert_eq!(calls.call(&mut store, ()).unwrap(), 2);
+        // Both instances share the same global.
+        let counter = lib.get_global(&store, "counter").unwrap();
+        assert_eq!(counter.get(&store).i32(), Some(2));
+        // Registering the same instance twice under the same name is an error.
+        assert!(matches!(
+            linker.instance(&store, "lib", lib),
+            Err(LinkerError::DuplicateDefinition { .. })
+        ));
+    }
+
+    #[test]
+    fn linker_duplicate_exports_define_nothing() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        // Only the `counter` export of `LIB` conflicts.
+        linker.func_wrap("lib", "counter", || ()).unwrap();
+        let lib = module(&engine, LIB);
+        assert!(matches!(
+            linker.module(&mut store, "lib", &lib),
+            Err(Error::Linker(LinkerError::DuplicateDefinition { .. }))
+        ));
+        assert!(!linker.has_definition("lib", "add"));
+        let lib = linker
+            .instantiate(&mut store, &lib)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        assert!(matches!(
+            linker.instance(&store, "lib", lib),
+            Err(LinkerError::DuplicateDefinition { .. })
+        ));
+        assert!(!linker.has_definition("lib", "add"));
+    }
+
+    #[test]
+    fn linker_module_works() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        linker
+            .module(&mut store, "lib", &module(&engine, LIB))
+            .unwrap();
+        let app = linker
+            .instantiate(&mut store, &module(&engine, APP))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let add_twice = app.get_typed_func::<i32, i32>(&store, "add_twice").unwrap();
+        assert_eq!(add_twice.call(&mut store, 2).unwrap(), 6);
+        // Missing module definitions are still reported.
+        let mut empty = <Linker<()>>::new(&engine);
+        assert!(empty
+            .instantiate(&mut store, &module(&engine, APP))
+            .is_err());
+        assert!(empty
+            .module(&mut store, "app", &module(&engine, APP))
+            .is_err());
+    }
+
+    #[test]
+    fn tracer_names_cross_module_calls() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        linker
+            .module(&mut store, "lib", &module(&engine, LIB))
+            .unwrap();
+        let app = linker
+            .instantiate(&mut store, &module(&engine, APP))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let add = linker
+            .get(&store, "lib", "add")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(i32, i32), i32>(&store)
+            .unwrap();
+        let add_twice = app.get_typed_func::<i32, i32>(&store, "add_twice").unwrap();
+        add.call(&mut store, (1, 2)).unwrap();
+        add_twice.call(&mut store, 1).unwrap();
+        let trace: serde_json::Value = serde_json::from_str(&store.tracer.to_json()).unwrap();
+        let names = trace["fn_metas"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|meta| meta["fn_name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        // The calls from `add_twice` into the `lib` instance are named after its exports.
+        assert_eq!(names, ["add", "add_twice", "add", "add"]);
+    }
+
+    #[test]
+    fn tracer_names_calls_by_store_index() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        let lib = r#"
+            (module
+                (func $one (export "one") (result i32) (i32.const 1))
+                (func $two (export "two") (result i32) (i32.const 2))
+                (func (export "sum") (result i32)
+                    (i32.add (call $one) (call $two))
+                )
+            )
+        "#;
+        // The local function indices of `app` differ from the store wide indices.
+        let app = r#"
+            (module
+                (import "lib" "sum" (func $sum (result i32)))
+                (table funcref (elem $local))
+                (type $ty (func (result i32)))
+                (func $local (result i32)
+                    (call $sum)
+                )
+                (func (export "run") (result i32)
+                    (i32.add (call $local) (call_indirect (type $ty) (i32.const 0)))
+                )
+            )
+        "#;
+        linker.module(&mut store, "lib", &module(&engine, lib)).unwrap();
+        let app = linker
+            .instantiate(&mut store, &module(&engine, app))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let run = app.get_typed_func::<(), i32>(&store, "run").unwrap();
+        assert_eq!(run.call(&mut store, ()).unwrap(), 6);
+        let trace: serde_json::Value = serde_json::from_str(&store.tracer.to_json()).unwrap();
+        let names = trace["fn_metas"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|meta| meta["fn_name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["run", "local", "sum", "one", "two", "local", "sum", "one", "two"]
+        );
+    }
+
+    const STUBBED: &str = r#"
+        (module
+            (import "env" "used" (func $used (result i32)))
+            (import "env" "unused" (func $unused))
+            (func (export "run") (result i32)
+                (call $used)
+            )
+            (func (export "run_unused")
+                (call $unused)
+            )
+        )
+    "#;
+
+    #[test]
+    fn linker_shadowing_works() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        linker.func_wrap("env", "used", || 1_i32).unwrap();
+        assert!(matches!(
+            linker.func_wrap("env", "used", || 2_i32),
+            Err(LinkerError::DuplicateDefinition { .. })
+        ));
+        linker.allow_shadowing(true);
+        linker.func_wrap("env", "used", || 2_i32).unwrap();
+        linker.define_unknown_imports_as_traps(&module(&engine, STUBBED));
+        let instance = linker
+            .instantiate(&mut store, &module(&engine, STUBBED))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let run = instance.get_typed_func::<(), i32>(&store, "run").unwrap();
+        assert_eq!(run.call(&mut store, ()).unwrap(), 2);
+    }
+
+    #[test]
+    fn linker_unknown_imports_as_traps_works() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let mut linker = <Linker<()>>::new(&engine);
+        let module = module(&engine, STUBBED);
+        assert!(linker.instantiate(&mut store, &module).is_err());
+        linker.func_wrap("env", "used", || 1_i32).unwrap();
+        linker.define_unknown_imports_as_traps(&module);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let run = instance.get_typed_func::<(), i32>(&store, "run").unwrap();
+        let run_unused = instance
+            .get_typed_func::<(), ()>(&store, "run_unused")
+            .unwrap();
+        // The defined import is not replaced by a stub.
+        assert_eq!(run.call(&mut store, ()).unwrap(), 1);
+        let error = run_unused.call(&mut store, ()).unwrap_err();
+        assert!(error.to_string().contains("env::unused"));
+    }
+
+    #[test]
+    fn linker_has_definition_works() {
+        let engine = Engine::default();
+        let mut linker = <Linker<()>>::new(&engine);
+        assert!(!linker.has_definition("env", "used"));
+        linker.func_wrap("env", "used", || 1_i32).unwrap();
+        assert!(linker.has_definition("env", "used"));
+        assert!(!linker.has_definition("env", "unused"));
+        assert!(!linker.has_definition("other", "used"));
+        linker.define_unknown_imports_as_traps(&module(&engine, STUBBED));
+        assert!(linker.has_definition("env", "unused"));
+        // Items provided by the fallback resolver are not taken into account.
+        linker.set_fallback_resolver(|store, _import| Some(Extern::Func(Func::wrap(store, || ()))));
+        assert!(!linker.has_definition("env", "other"));
+    }
+
+    #[test]
+    fn linker_fallback_resolver_works() {
+        let engine = Engine::default();
+        let mut store = <Store<()>>::new(&engine, ());
+        let unused = Func::wrap(&mut store, || ());
+        let mut linker = <Linker<()>>::new(&engine);
+        // Resolved functions can be created on demand using the store.
+        linker.set_fallback_resolver(|store, import| match (import.module(), import.name()) {
+            ("env", "used") => Some(Extern::Func(Func::wrap(store, || 3_i32))),
+            ("env", "unused") => Some(Extern::Func(Func::wrap(store, || ()))),
+            _ => None,
+        });
+        let instance = linker
+            .instantiate(&mut store, &module(&engine, STUBBED))
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+        let run = instance.get_typed_func::<(), i32>(&store, "run").unwrap();
+        assert_eq!(run.call(&mut store, ()).unwrap(), 3);
+        // Resolved items are type checked like regular definitions.
+        linker.set_fallback_resolver(move |_store, _import| Some(Extern::Func(unused)));
+        assert!(matches!(
+            linker.instantiate(&mut store, &module(&engine, STUBBED)),
+            Err(Error::Linker(LinkerError::FuncTypeMismatch { .. }))
+        ));
+        linker.set_fallback_resolver(|_store, _import| None);
+        assert!(matches!(
+            linker.instantiate(&mut store, &module(&engine, STUBBED)),
+            Err(Error::Linker(LinkerError::MissingDefinition { .. }))
+        ));
+    }
 }
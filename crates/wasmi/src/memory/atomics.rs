@@ -0,0 +1,235 @@
+//! Atomic accesses to the bytes of a linear memory.
+//!
+//! These implement the atomic memory instructions of the Wasm `threads` proposal.
+
+use core::{
+    mem::size_of,
+    ptr::NonNull,
+    sync::atomic::{AtomicU16, AtomicU32, AtomicU64, AtomicU8, Ordering},
+};
+use wasmi_core::{TrapCode, UntypedValue};
+
+/// The read-modify-write operations of the Wasm `threads` proposal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AtomicRmwOp {
+    /// Wrapping addition of the operand.
+    Add,
+    /// Wrapping subtraction of the operand.
+    Sub,
+    /// Bitwise and with the operand.
+    And,
+    /// Bitwise or with the operand.
+    Or,
+    /// Bitwise xor with the operand.
+    Xor,
+    /// Exchanges the stored value with the operand.
+    Xchg,
+}
+
+/// An unsigned integer that can be atomically accessed in linear memory.
+///
+/// # Note
+///
+/// Values narrower than the Wasm operand type are zero extended upon loading
+/// and wrapped upon storing which is in line with the Wasm `threads` proposal.
+pub trait AtomicWord: Copy + Eq {
+    /// Wraps the `value` to the width of `Self`.
+    fn wrap(value: UntypedValue) -> Self;
+
+    /// Zero extends `self` to an [`UntypedValue`].
+    fn extend(self) -> UntypedValue;
+
+    /// Atomically loads the value at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// The `ptr` must be valid and naturally aligned for `Self`.
+    unsafe fn load(ptr: *mut u8) -> Self;
+
+    /// Atomically stores `value` at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// The `ptr` must be valid and naturally aligned for `Self`.
+    unsafe fn store(ptr: *mut u8, value: Self);
+
+    /// Atomically applies `op` with `value` at `ptr` and returns the previous value.
+    ///
+    /// # Safety
+    ///
+    /// The `ptr` must be valid and naturally aligned for `Self`.
+    unsafe fn rmw(ptr: *mut u8, op: AtomicRmwOp, value: Self) -> Self;
+
+    /// Atomically replaces the value at `ptr` with `replacement` if it equals `expected`.
+    ///
+    /// Returns the previous value.
+    ///
+    /// # Safety
+    ///
+    /// The `ptr` must be valid and naturally aligned for `Self`.
+    unsafe fn cmpxchg(ptr: *mut u8, expected: Self, replacement: Self) -> Self;
+}
+
+macro_rules! impl_atomic_word {
+    ( $( impl AtomicWord for $ty:ty as $atomic:ty; )* ) => {
+        $(
+            impl AtomicWord for $ty {
+                fn wrap(value: UntypedValue) -> Self {
+                    u64::from(value) as $ty
+                }
+
+                fn extend(self) -> UntypedValue {
+                    UntypedValue::from(u64::from(self))
+                }
+
+                unsafe fn load(ptr: *mut u8) -> Self {
+                    (*ptr.cast::<$atomic>()).load(Ordering::SeqCst)
+                }
+
+                unsafe fn store(ptr: *mut u8, value: Self) {
+                    (*ptr.cast::<$atomic>()).store(value, Ordering::SeqCst)
+                }
+
+                unsafe fn rmw(ptr: *mut u8, op: AtomicRmwOp, value: Self) -> Self {
+                    let atomic = &*ptr.cast::<$atomic>();
+                    match op {
+                        AtomicRmwOp::Add => atomic.fetch_add(value, Ordering::SeqCst),
+                        AtomicRmwOp::Sub => atomic.fetch_sub(value, Ordering::SeqCst),
+                        AtomicRmwOp::And => atomic.fetch_and(value, Ordering::SeqCst),
+                        AtomicRmwOp::Or => atomic.fetch_or(value, Ordering::SeqCst),
+                        AtomicRmwOp::Xor => atomic.fetch_xor(value, Ordering::SeqCst),
+                        AtomicRmwOp::Xchg => atomic.swap(value, Ordering::SeqCst),
+                    }
+                }
+
+                unsafe fn cmpxchg(ptr: *mut u8, expected: Self, replacement: Self) -> Self {
+                    let atomic = &*ptr.cast::<$atomic>();
+                    match atomic.compare_exchange(expected, replacement, Ordering::SeqCst, Ordering::SeqCst) {
+                        Ok(previous) | Err(previous) => previous,
+                    }
+                }
+            }
+        )*
+    };
+}
+impl_atomic_word! {
+    impl AtomicWord for u8 as AtomicU8;
+    impl AtomicWord for u16 as AtomicU16;
+    impl AtomicWord for u32 as AtomicU32;
+    impl AtomicWord for u64 as AtomicU64;
+}
+
+/// Calculates the effective address of an atomic access of `T` into `len_memory` bytes.
+///
+/// # Errors
+///
+/// - If `address + offset` overflows or accesses out of bounds of the linear memory.
+/// - If `address + offset` is not naturally aligned for `T`.
+pub fn effective_address<T: AtomicWord>(
+    len_memory: usize,
+    address: UntypedValue,
+    offset: u32,
+) -> Result<usize, TrapCode> {
+    let width = size_of::<T>();
+    let address = u32::from(address)
+        .checked_add(offset)
+        .ok_or(TrapCode::MemoryOutOfBounds)? as usize;
+    match address.checked_add(width) {
+        Some(end) if end <= len_memory => {}
+        _ => return Err(TrapCode::MemoryOutOfBounds),
+    }
+    if address % width != 0 {
+        return Err(TrapCode::UnalignedAtomic);
+    }
+    Ok(address)
+}
+
+/// Returns a pointer to the naturally aligned `T` at `address` in `memory`.
+///
+/// # Note
+///
+/// Linear memory bytes are allocated with at least the alignment of `u64`
+/// and therefore naturally aligned effective addresses are also naturally
+/// aligned in host memory.
+fn atomic_ptr<T: AtomicWord>(memory: NonNull<[u8]>, address: usize) -> *mut u8 {
+    debug_assert!(address + size_of::<T>() <= memory.len());
+    let ptr = memory.cast::<u8>().as_ptr().wrapping_add(address);
+    debug_assert_eq!(ptr as usize % size_of::<T>(), 0);
+    ptr
+}
+
+/// Executes a Wasm `atomic.load` instruction of `T` on `memory`.
+///
+/// # Errors
+///
+/// If the effective address is out of bounds or unaligned.
+pub fn load<T: AtomicWord>(
+    memory: NonNull<[u8]>,
+    address: UntypedValue,
+    offset: u32,
+) -> Result<UntypedValue, TrapCode> {
+    let address = effective_address::<T>(memory.len(), address, offset)?;
+    // Safety: the effective address has been checked to be in bounds and aligned.
+    let value = unsafe { T::load(atomic_ptr::<T>(memory, address)) };
+    Ok(value.extend())
+}
+
+/// Executes a Wasm `atomic.store` instruction of `T` on `memory`.
+///
+/// Returns the effective address of the store.
+///
+/// # Errors
+///
+/// If the effective address is out of bounds or unaligned.
+pub fn store<T: AtomicWord>(
+    memory: NonNull<[u8]>,
+    address: UntypedValue,
+    offset: u32,
+    value: UntypedValue,
+) -> Result<usize, TrapCode> {
+    let address = effective_address::<T>(memory.len(), address, offset)?;
+    // Safety: the effective address has been checked to be in bounds and aligned.
+    unsafe { T::store(atomic_ptr::<T>(memory, address), T::wrap(value)) };
+    Ok(address)
+}
+
+/// Executes a Wasm `atomic.rmw` instruction of `T` applying `op` on `memory`.
+///
+/// Returns the effective address and the previously stored value.
+///
+/// # Errors
+///
+/// If the effective address is out of bounds or unaligned.
+pub fn rmw<T: AtomicWord>(
+    memory: NonNull<[u8]>,
+    address: UntypedValue,
+    offset: u32,
+    op: AtomicRmwOp,
+    value: UntypedValue,
+) -> Result<(usize, UntypedValue), TrapCode> {
+    let address = effective_address::<T>(memory.len(), address, offset)?;
+    // Safety: the effective address has been checked to be in bounds and aligned.
+    let previous = unsafe { T::rmw(atomic_ptr::<T>(memory, address), op, T::wrap(value)) };
+    Ok((address, previous.extend()))
+}
+
+/// Executes a Wasm `atomic.rmw.cmpxchg` instruction of `T` on `memory`.
+///
+/// Returns the effective address and the previously stored value.
+///
+/// # Errors
+///
+/// If the effective address is out of bounds or unaligned.
+pub fn cmpxchg<T: AtomicWord>(
+    memory: NonNull<[u8]>,
+    address: UntypedValue,
+    offset: u32,
+    expected: UntypedValue,
+    replacement: UntypedValue,
+) -> Result<(usize, UntypedValue), TrapCode> {
+    let address = effective_address::<T>(memory.len(), address, offset)?;
+    let ptr = atomic_ptr::<T>(memory, address);
+    // Safety: the effective address has been checked to be in bounds and aligned.
+    let previous = unsafe { T::cmpxchg(ptr, T::wrap(expected), T::wrap(replacement)) };
+    Ok((address, previous.extend()))
+}
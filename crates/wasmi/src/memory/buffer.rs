@@ -1,4 +1,5 @@
 use alloc::{vec, vec::Vec};
+use core::{mem::size_of, slice};
 
 /// A `Vec`-based byte buffer implementation.
 ///
@@ -7,9 +8,18 @@ use alloc::{vec, vec::Vec};
 /// This is less efficient than the byte buffer implementation that is
 /// based on actual OS provided virtual memory but it is a safe fallback
 /// solution fitting any platform.
+///
+/// The bytes are backed by `u64` words so that the buffer is always aligned
+/// to 8 bytes as required by the atomic instructions of the Wasm `threads` proposal.
 #[derive(Debug)]
 pub struct ByteBuffer {
-    bytes: Vec<u8>,
+    words: Vec<u64>,
+    len: usize,
+}
+
+/// Returns the amount of `u64` words required to store `len` bytes.
+fn len_words(len: usize) -> usize {
+    (len + size_of::<u64>() - 1) / size_of::<u64>()
 }
 
 impl ByteBuffer {
@@ -21,7 +31,8 @@ impl ByteBuffer {
     /// - If the initial length exceeds the maximum supported limit.
     pub fn new(initial_len: usize) -> Self {
         Self {
-            bytes: vec![0x00_u8; initial_len],
+            words: vec![0x00_u64; len_words(initial_len)],
+            len: initial_len,
         }
     }
 
@@ -32,21 +43,24 @@ impl ByteBuffer {
     /// If the current size of the [`ByteBuffer`] is larger than `new_size`.
     pub fn grow(&mut self, new_size: usize) {
         assert!(new_size >= self.len());
-        self.bytes.resize(new_size, 0x00_u8);
+        self.words.resize(len_words(new_size), 0x00_u64);
+        self.len = new_size;
     }
 
     /// Returns the length of the byte buffer in bytes.
     pub fn len(&self) -> usize {
-        self.bytes.len()
+        self.len
     }
 
     /// Returns a shared slice to the bytes underlying to the byte buffer.
     pub fn data(&self) -> &[u8] {
-        &self.bytes[..]
+        // Safety: the `words` buffer owns at least `len` initialized bytes.
+        unsafe { slice::from_raw_parts(self.words.as_ptr().cast::<u8>(), self.len) }
     }
 
     /// Returns an exclusive slice to the bytes underlying to the byte buffer.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        &mut self.bytes[..]
+        // Safety: the `words` buffer owns at least `len` initialized bytes.
+        unsafe { slice::from_raw_parts_mut(self.words.as_mut_ptr().cast::<u8>(), self.len) }
     }
 }
@@ -20,6 +20,12 @@ pub enum MemoryError {
         /// The [`MemoryType`] which is supposed to be a supertype of `ty`.
         other: MemoryType,
     },
+    /// Tried to create a shared linear memory in deterministic tracing mode.
+    SharedMemoryInDeterministicTracing,
+    /// The [`ResourceLimiter`] denied the allocation of linear memory.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    ResourceLimiterDeniedAllocation,
 }
 
 impl Display for MemoryError {
@@ -40,6 +46,15 @@ impl Display for MemoryError {
             Self::InvalidSubtype { ty, other } => {
                 write!(f, "memory type {ty:?} is not a subtype of {other:?}",)
             }
+            Self::SharedMemoryInDeterministicTracing => {
+                write!(
+                    f,
+                    "shared linear memories are not allowed in deterministic tracing mode"
+                )
+            }
+            Self::ResourceLimiterDeniedAllocation => {
+                write!(f, "a resource limiter denied to allocate linear memory")
+            }
         }
     }
 }
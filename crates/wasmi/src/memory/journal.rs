@@ -0,0 +1,61 @@
+use alloc::vec::Vec;
+use wasmi_core::Pages;
+
+/// Records the modifications of a linear memory while a host function call is recorded.
+///
+/// # Note
+///
+/// Writes through [`MemoryEntity::write`] and growth are recorded as they happen.
+/// Exclusive borrows of the bytes of the linear memory cannot be tracked and therefore
+/// make the [`MemoryJournal`] take a snapshot of the bytes upon the first such borrow.
+/// All modifications after the snapshot are found by comparing against the snapshot.
+///
+/// [`MemoryEntity::write`]: super::MemoryEntity::write
+#[derive(Debug, Default)]
+pub struct MemoryJournal {
+    /// The amounts of pages the linear memory grew by in growth order.
+    grows: Vec<Pages>,
+    /// The offsets and bytes written before the `snapshot` was taken in write order.
+    writes: Vec<(usize, Vec<u8>)>,
+    /// The bytes of the linear memory upon their first exclusive borrow if any.
+    snapshot: Option<Vec<u8>>,
+}
+
+impl MemoryJournal {
+    /// Records that the linear memory grew by `additional` pages.
+    pub fn grow(&mut self, additional: Pages) {
+        self.grows.push(additional);
+    }
+
+    /// Records that `buffer` has been written at `offset` to the linear memory.
+    pub fn write(&mut self, offset: usize, buffer: &[u8]) {
+        if self.snapshot.is_none() {
+            self.writes.push((offset, buffer.to_vec()));
+        }
+    }
+
+    /// Returns `true` if the [`MemoryJournal`] has already taken a snapshot.
+    pub fn has_snapshot(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    /// Records the `bytes` of the linear memory before they are exclusively borrowed.
+    pub fn snapshot(&mut self, bytes: Vec<u8>) {
+        self.snapshot = Some(bytes);
+    }
+
+    /// Returns the amounts of pages the linear memory grew by in growth order.
+    pub fn grows(&self) -> &[Pages] {
+        &self.grows
+    }
+
+    /// Returns the recorded writes in write order.
+    pub fn writes(&self) -> &[(usize, Vec<u8>)] {
+        &self.writes
+    }
+
+    /// Returns the snapshot of the linear memory if any.
+    pub fn snapshot_bytes(&self) -> Option<&[u8]> {
+        self.snapshot.as_deref()
+    }
+}
@@ -1,6 +1,9 @@
+pub(crate) mod atomics;
 mod buffer;
 mod data;
 mod error;
+mod journal;
+mod shared;
 
 #[cfg(test)]
 mod tests;
@@ -9,8 +12,16 @@ use self::buffer::ByteBuffer;
 pub use self::{
     data::{DataSegment, DataSegmentEntity, DataSegmentIdx},
     error::MemoryError,
+    shared::{SharedMemory, ThreadParker, WaitResult},
 };
+#[cfg(feature = "std")]
+pub use self::shared::StdThreadParker;
+pub(crate) use self::journal::MemoryJournal;
+pub(crate) use self::shared::{copy_raw, fill_raw, read_raw, write_raw, Waiting};
 use super::{AsContext, AsContextMut, StoreContext, StoreContextMut, Stored};
+use crate::{limits::ResourceLimiterRef, Engine};
+use alloc::{vec, vec::Vec};
+use core::ptr::NonNull;
 use wasmi_arena::ArenaIndex;
 use wasmi_core::Pages;
 
@@ -36,6 +47,7 @@ impl ArenaIndex for MemoryIdx {
 pub struct MemoryType {
     initial_pages: Pages,
     maximum_pages: Option<Pages>,
+    shared: bool,
 }
 
 impl MemoryType {
@@ -56,9 +68,34 @@ impl MemoryType {
         Ok(Self {
             initial_pages,
             maximum_pages,
+            shared: false,
         })
     }
 
+    /// Creates a new shared memory type with initial and maximum pages.
+    ///
+    /// # Note
+    ///
+    /// Shared linear memories are part of the Wasm `threads` proposal
+    /// and are required to have a maximum size.
+    ///
+    /// # Errors
+    ///
+    /// If the linear memory type initial or maximum size exceeds the
+    /// maximum limits of 2^16 pages.
+    pub fn new_shared(initial: u32, maximum: u32) -> Result<Self, MemoryError> {
+        let memory_type = Self::new(initial, Some(maximum))?;
+        Ok(Self {
+            shared: true,
+            ..memory_type
+        })
+    }
+
+    /// Returns `true` if the memory type describes a shared linear memory.
+    pub fn is_shared(self) -> bool {
+        self.shared
+    }
+
     /// Returns the initial pages of the memory type.
     pub fn initial_pages(self) -> Pages {
         self.initial_pages
@@ -106,6 +143,9 @@ impl MemoryType {
     /// [import subtyping]:
     /// https://webassembly.github.io/spec/core/valid/types.html#import-subtyping
     pub(crate) fn is_subtype_of(&self, other: &MemoryType) -> bool {
+        if self.is_shared() != other.is_shared() {
+            return false;
+        }
         if self.initial_pages() < other.initial_pages() {
             return false;
         }
@@ -120,31 +160,147 @@ impl MemoryType {
 /// A linear memory entity.
 #[derive(Debug)]
 pub struct MemoryEntity {
-    bytes: ByteBuffer,
     memory_type: MemoryType,
-    current_pages: Pages,
+    storage: MemoryStorage,
+    /// The started [`MemoryJournal`]s recording modifications of the linear memory.
+    ///
+    /// Journals are started for every recorded host function call and thus nest.
+    journals: Vec<MemoryJournal>,
+}
+
+/// The storage of the bytes of a [`MemoryEntity`].
+#[derive(Debug)]
+enum MemoryStorage {
+    /// The bytes are owned by the [`MemoryEntity`].
+    Owned {
+        bytes: ByteBuffer,
+        current_pages: Pages,
+    },
+    /// The bytes are shared with other [`MemoryEntity`] instances.
+    Shared(SharedMemory),
 }
 
 impl MemoryEntity {
     /// Creates a new memory entity with the given memory type.
-    pub fn new(memory_type: MemoryType) -> Result<Self, MemoryError> {
+    ///
+    /// # Errors
+    ///
+    /// - If the initial size of the linear memory cannot be allocated.
+    /// - If the [`ResourceLimiter`] denied the allocation of the initial size.
+    /// - If `memory_type` is a shared [`MemoryType`].
+    ///   Use [`MemoryEntity::from_shared`] for shared linear memories instead.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    pub(crate) fn new(
+        memory_type: MemoryType,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<Self, MemoryError> {
+        if memory_type.is_shared() {
+            return Err(MemoryError::InvalidMemoryType);
+        }
         let initial_pages = memory_type.initial_pages();
         let initial_len = initial_pages
             .to_bytes()
             .ok_or(MemoryError::OutOfBoundsAllocation)?;
+        Self::limit_allocation(memory_type, 0, initial_len, limiter)?;
         let memory = Self {
-            bytes: ByteBuffer::new(initial_len),
             memory_type,
-            current_pages: initial_pages,
+            storage: MemoryStorage::Owned {
+                bytes: ByteBuffer::new(initial_len),
+                current_pages: initial_pages,
+            },
+            journals: Vec::new(),
         };
+        Self::notify_grown(0, initial_len, limiter);
         Ok(memory)
     }
 
+    /// Consults the [`ResourceLimiter`] about the allocation of a new linear memory
+    /// of `memory_type` with `initial_len` bytes.
+    ///
+    /// # Errors
+    ///
+    /// If the [`ResourceLimiter`] denied the allocation.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn limit_allocation(
+        memory_type: MemoryType,
+        current: usize,
+        initial_len: usize,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<(), MemoryError> {
+        let Some(limiter) = limiter.as_resource_limiter() else {
+            return Ok(());
+        };
+        let maximum = memory_type.maximum_pages().and_then(Pages::to_bytes);
+        match limiter
+            .memory_growing(current, initial_len, maximum)
+            .map_err(|_| MemoryError::ResourceLimiterDeniedAllocation)?
+        {
+            true => Ok(()),
+            false => Err(MemoryError::ResourceLimiterDeniedAllocation),
+        }
+    }
+
+    /// Informs the [`ResourceLimiter`] that a linear memory successfully grew
+    /// from `current` to `desired` bytes.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn notify_grown(current: usize, desired: usize, limiter: &mut ResourceLimiterRef<'_>) {
+        if let Some(limiter) = limiter.as_resource_limiter() {
+            limiter.memory_grown(current, desired);
+        }
+    }
+
+    /// Creates a new memory entity backed by a new [`SharedMemory`] of `memory_type`.
+    ///
+    /// # Note
+    ///
+    /// The [`ResourceLimiter`] is consulted about the maximum size of the [`SharedMemory`]
+    /// before it is allocated since its bytes are allocated up to its maximum size.
+    ///
+    /// # Errors
+    ///
+    /// - If `memory_type` is not a shared [`MemoryType`].
+    /// - If the `engine` is in deterministic tracing mode.
+    /// - If the [`ResourceLimiter`] denied the allocation of the maximum size.
+    /// - If the maximum size of the [`SharedMemory`] cannot be allocated.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    pub(crate) fn new_shared(
+        engine: &Engine,
+        memory_type: MemoryType,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<Self, MemoryError> {
+        let capacity = SharedMemory::capacity(engine, memory_type)?;
+        Self::limit_allocation(memory_type, 0, capacity, limiter)?;
+        let memory = SharedMemory::new(engine, memory_type)?;
+        Self::notify_grown(0, capacity, limiter);
+        Ok(Self::from_shared(memory))
+    }
+
+    /// Creates a new memory entity backed by the [`SharedMemory`].
+    pub fn from_shared(memory: SharedMemory) -> Self {
+        Self {
+            memory_type: memory.ty(),
+            storage: MemoryStorage::Shared(memory),
+            journals: Vec::new(),
+        }
+    }
+
     /// Returns the memory type of the linear memory.
     pub fn ty(&self) -> MemoryType {
         self.memory_type
     }
 
+    /// Returns the [`SharedMemory`] if the linear memory is shared.
+    pub fn shared(&self) -> Option<&SharedMemory> {
+        match &self.storage {
+            MemoryStorage::Owned { .. } => None,
+            MemoryStorage::Shared(memory) => Some(memory),
+        }
+    }
+
     /// Returns the dynamic [`MemoryType`] of the [`MemoryEntity`].
     ///
     /// # Note
@@ -154,13 +310,21 @@ impl MemoryEntity {
     pub fn dynamic_ty(&self) -> MemoryType {
         let current_pages = self.current_pages().into();
         let maximum_pages = self.ty().maximum_pages().map(Into::into);
-        MemoryType::new(current_pages, maximum_pages)
-            .unwrap_or_else(|_| panic!("must result in valid memory type due to invariants"))
+        let memory_type = match maximum_pages {
+            Some(maximum_pages) if self.ty().is_shared() => {
+                MemoryType::new_shared(current_pages, maximum_pages)
+            }
+            _ => MemoryType::new(current_pages, maximum_pages),
+        };
+        memory_type.unwrap_or_else(|_| panic!("must result in valid memory type due to invariants"))
     }
 
     /// Returns the amount of pages in use by the linear memory.
     pub fn current_pages(&self) -> Pages {
-        self.current_pages
+        match &self.storage {
+            MemoryStorage::Owned { current_pages, .. } => *current_pages,
+            MemoryStorage::Shared(memory) => memory.current_pages(),
+        }
     }
 
     /// Grows the linear memory by the given amount of new pages.
@@ -169,37 +333,151 @@ impl MemoryEntity {
     ///
     /// # Errors
     ///
-    /// If the linear memory would grow beyond its maximum limit after
-    /// the grow operation.
-    pub fn grow(&mut self, additional: Pages) -> Result<Pages, MemoryError> {
-        let current_pages = self.current_pages();
+    /// - If the linear memory would grow beyond its maximum limit after
+    ///   the grow operation.
+    /// - If the [`ResourceLimiter`] denied the growth. In this case
+    ///   [`MemoryError::ResourceLimiterDeniedAllocation`] is returned
+    ///   if the [`ResourceLimiter`] returned an error and
+    ///   [`MemoryError::OutOfBoundsGrowth`] otherwise.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    pub(crate) fn grow(
+        &mut self,
+        additional: Pages,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<Pages, MemoryError> {
+        let result = self.grow_limited(additional, limiter);
+        match &result {
+            Ok(_) if additional != Pages::from(0) => {
+                for journal in &mut self.journals {
+                    journal.grow(additional);
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                if let Some(limiter) = limiter.as_resource_limiter() {
+                    limiter.memory_grow_failed(error);
+                }
+            }
+        }
+        result
+    }
+
+    /// Grows the linear memory by `additional` pages if the [`ResourceLimiter`] allows it.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn grow_limited(
+        &mut self,
+        additional: Pages,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<Pages, MemoryError> {
+        if let MemoryStorage::Shared(memory) = &self.storage {
+            // The bytes of a shared linear memory have already been
+            // accounted for up to its maximum size upon its creation.
+            return memory.grow(additional);
+        }
+        let maximum_pages = self.ty().maximum_pages().unwrap_or_else(Pages::max);
+        let old_pages = self.current_pages();
         if additional == Pages::from(0) {
             // Nothing to do in this case. Bail out early.
-            return Ok(current_pages);
+            return Ok(old_pages);
         }
-        let maximum_pages = self.ty().maximum_pages().unwrap_or_else(Pages::max);
-        let new_pages = current_pages
+        let new_pages = old_pages
             .checked_add(additional)
             .filter(|&new_pages| new_pages <= maximum_pages)
             .ok_or(MemoryError::OutOfBoundsGrowth)?;
         let new_size = new_pages
             .to_bytes()
             .ok_or(MemoryError::OutOfBoundsAllocation)?;
+        let current_size = old_pages
+            .to_bytes()
+            .ok_or(MemoryError::OutOfBoundsAllocation)?;
+        if let Some(limiter) = limiter.as_resource_limiter() {
+            let maximum_size = self.ty().maximum_pages().and_then(Pages::to_bytes);
+            if !limiter
+                .memory_growing(current_size, new_size, maximum_size)
+                .map_err(|_| MemoryError::ResourceLimiterDeniedAllocation)?
+            {
+                return Err(MemoryError::OutOfBoundsGrowth);
+            }
+        }
+        let (bytes, current_pages) = match &mut self.storage {
+            MemoryStorage::Owned {
+                bytes,
+                current_pages,
+            } => (bytes, current_pages),
+            MemoryStorage::Shared(_) => unreachable!("shared linear memories grew above"),
+        };
         // At this point it is okay to grow the underlying virtual memory
         // by the given amount of additional pages.
-        self.bytes.grow(new_size);
-        self.current_pages = new_pages;
-        Ok(current_pages)
+        bytes.grow(new_size);
+        *current_pages = new_pages;
+        Self::notify_grown(current_size, new_size, limiter);
+        Ok(old_pages)
     }
 
     /// Returns a shared slice to the bytes underlying to the byte buffer.
+    ///
+    /// # Panics
+    ///
+    /// If the linear memory is shared since other threads might concurrently modify its bytes.
     pub fn data(&self) -> &[u8] {
-        self.bytes.data()
+        self.try_data()
+            .unwrap_or_else(|| panic!("cannot borrow the bytes of a shared linear memory"))
     }
 
     /// Returns an exclusive slice to the bytes underlying to the byte buffer.
+    ///
+    /// # Panics
+    ///
+    /// If the linear memory is shared since other threads might concurrently access its bytes.
     pub fn data_mut(&mut self) -> &mut [u8] {
-        self.bytes.data_mut()
+        self.try_data_mut()
+            .unwrap_or_else(|| panic!("cannot borrow the bytes of a shared linear memory"))
+    }
+
+    /// Returns a shared slice to the bytes underlying to the byte buffer.
+    ///
+    /// Returns `None` if the linear memory is shared since other threads
+    /// might concurrently modify its bytes.
+    pub fn try_data(&self) -> Option<&[u8]> {
+        match &self.storage {
+            MemoryStorage::Owned { bytes, .. } => Some(bytes.data()),
+            MemoryStorage::Shared(_) => None,
+        }
+    }
+
+    /// Returns an exclusive slice to the bytes underlying to the byte buffer.
+    ///
+    /// Returns `None` if the linear memory is shared since other threads
+    /// might concurrently access its bytes.
+    pub fn try_data_mut(&mut self) -> Option<&mut [u8]> {
+        self.snapshot_journals();
+        match &mut self.storage {
+            MemoryStorage::Owned { bytes, .. } => Some(bytes.data_mut()),
+            MemoryStorage::Shared(_) => None,
+        }
+    }
+
+    /// Returns a raw pointer to the bytes underlying to the byte buffer.
+    ///
+    /// # Note
+    ///
+    /// Use [`read_raw`] and [`write_raw`] to access the bytes of shared linear memories.
+    pub(crate) fn data_ptr(&mut self) -> NonNull<[u8]> {
+        self.snapshot_journals();
+        match &mut self.storage {
+            MemoryStorage::Owned { bytes, .. } => NonNull::from(bytes.data_mut()),
+            MemoryStorage::Shared(memory) => memory.data_ptr(),
+        }
+    }
+
+    /// Returns the current size of the linear memory in bytes.
+    pub(crate) fn data_len(&self) -> usize {
+        match &self.storage {
+            MemoryStorage::Owned { bytes, .. } => bytes.data().len(),
+            MemoryStorage::Shared(memory) => memory.data().len(),
+        }
     }
 
     /// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
@@ -209,8 +487,12 @@ impl MemoryEntity {
     ///
     /// If this operation accesses out of bounds linear memory.
     pub fn read(&self, offset: usize, buffer: &mut [u8]) -> Result<(), MemoryError> {
+        let bytes = match &self.storage {
+            MemoryStorage::Owned { bytes, .. } => bytes,
+            MemoryStorage::Shared(memory) => return memory.read(offset, buffer),
+        };
         let len_buffer = buffer.len();
-        let slice = self
+        let slice = bytes
             .data()
             .get(offset..(offset + len_buffer))
             .ok_or(MemoryError::OutOfBoundsAccess)?;
@@ -225,14 +507,65 @@ impl MemoryEntity {
     ///
     /// If this operation accesses out of bounds linear memory.
     pub fn write(&mut self, offset: usize, buffer: &[u8]) -> Result<(), MemoryError> {
-        let len_buffer = buffer.len();
-        let slice = self
-            .data_mut()
-            .get_mut(offset..(offset + len_buffer))
-            .ok_or(MemoryError::OutOfBoundsAccess)?;
-        slice.copy_from_slice(buffer);
+        match &mut self.storage {
+            MemoryStorage::Owned { bytes, .. } => {
+                let len_buffer = buffer.len();
+                let slice = bytes
+                    .data_mut()
+                    .get_mut(offset..(offset + len_buffer))
+                    .ok_or(MemoryError::OutOfBoundsAccess)?;
+                slice.copy_from_slice(buffer);
+            }
+            MemoryStorage::Shared(memory) => memory.write(offset, buffer)?,
+        }
+        for journal in &mut self.journals {
+            journal.write(offset, buffer);
+        }
         Ok(())
     }
+
+    /// Returns a copy of the bytes of the linear memory.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0x00_u8; self.data_len()];
+        self.read(0, &mut bytes)
+            .unwrap_or_else(|error| panic!("failed to read linear memory: {error}"));
+        bytes
+    }
+
+    /// Starts a new [`MemoryJournal`] recording all modifications of the linear memory.
+    ///
+    /// # Note
+    ///
+    /// The bytes of a shared linear memory are recorded up front
+    /// since other threads might modify them at any time.
+    pub(crate) fn start_journal(&mut self) {
+        let mut journal = MemoryJournal::default();
+        if let MemoryStorage::Shared(_) = &self.storage {
+            journal.snapshot(self.to_bytes());
+        }
+        self.journals.push(journal);
+    }
+
+    /// Stops and returns the most recently started [`MemoryJournal`] if any.
+    pub(crate) fn finish_journal(&mut self) -> Option<MemoryJournal> {
+        self.journals.pop()
+    }
+
+    /// Makes all started [`MemoryJournal`]s without a snapshot take one.
+    ///
+    /// This is required before the bytes of the linear memory are exclusively
+    /// borrowed since writes through the borrow cannot be recorded.
+    fn snapshot_journals(&mut self) {
+        if self.journals.iter().all(MemoryJournal::has_snapshot) {
+            return;
+        }
+        let bytes = self.to_bytes();
+        for journal in &mut self.journals {
+            if !journal.has_snapshot() {
+                journal.snapshot(bytes.clone());
+            }
+        }
+    }
 }
 
 /// A Wasm linear memory reference.
@@ -255,13 +588,63 @@ impl Memory {
     ///
     /// # Errors
     ///
-    /// If more than [`u32::MAX`] much linear memory is allocated.
+    /// - If more than [`u32::MAX`] much linear memory is allocated.
+    /// - If `ty` is shared and the [`Engine`] is in deterministic tracing mode.
+    /// - If the [`ResourceLimiter`] of the [`Store`] denied the allocation.
+    ///
+    /// [`Engine`]: crate::Engine
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
     pub fn new(mut ctx: impl AsContextMut, ty: MemoryType) -> Result<Self, MemoryError> {
-        let entity = MemoryEntity::new(ty)?;
-        let memory = ctx.as_context_mut().store.inner.alloc_memory(entity);
+        let ctx = ctx.as_context_mut();
+        let engine = ctx.store.engine().clone();
+        let (inner, mut limiter) = ctx.store.store_inner_and_resource_limiter_ref();
+        let entity = match ty.is_shared() {
+            true => MemoryEntity::new_shared(&engine, ty, &mut limiter)?,
+            false => MemoryEntity::new(ty, &mut limiter)?,
+        };
+        let memory = inner.alloc_memory(entity);
         Ok(memory)
     }
 
+    /// Creates a new linear memory to the store that is backed by the [`SharedMemory`].
+    ///
+    /// # Note
+    ///
+    /// This allows to use the same [`SharedMemory`] in multiple [`Store`]s on different threads.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Engine`] of the [`Store`] is in deterministic tracing mode.
+    ///
+    /// [`Store`]: crate::Store
+    /// [`Engine`]: crate::Engine
+    pub fn new_shared(
+        mut ctx: impl AsContextMut,
+        memory: SharedMemory,
+    ) -> Result<Self, MemoryError> {
+        let ctx = ctx.as_context_mut();
+        if ctx.store.engine().config().get_deterministic_tracing() {
+            return Err(MemoryError::SharedMemoryInDeterministicTracing);
+        }
+        let entity = MemoryEntity::from_shared(memory);
+        Ok(ctx.store.inner.alloc_memory(entity))
+    }
+
+    /// Returns the [`SharedMemory`] backing the linear memory if it is shared.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn shared(&self, ctx: impl AsContext) -> Option<SharedMemory> {
+        ctx.as_context()
+            .store
+            .inner
+            .resolve_memory(self)
+            .shared()
+            .cloned()
+    }
+
     /// Returns the memory type of the linear memory.
     ///
     /// # Panics
@@ -308,29 +691,46 @@ impl Memory {
     ///
     /// # Errors
     ///
-    /// If the linear memory would grow beyond its maximum limit after
-    /// the grow operation.
+    /// - If the linear memory would grow beyond its maximum limit after
+    ///   the grow operation.
+    /// - If the [`ResourceLimiter`] of the [`Store`] denied the growth.
     ///
     /// # Panics
     ///
     /// Panics if `ctx` does not own this [`Memory`].
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
     pub fn grow(
         &self,
         mut ctx: impl AsContextMut,
         additional: Pages,
     ) -> Result<Pages, MemoryError> {
-        ctx.as_context_mut()
+        let (inner, mut limiter) = ctx
+            .as_context_mut()
             .store
-            .inner
+            .store_inner_and_resource_limiter_ref();
+        inner
             .resolve_memory_mut(self)
-            .grow(additional)
+            .grow(additional, &mut limiter)
     }
 
-    /// Returns a shared slice to the bytes underlying the [`Memory`].
+    /// Returns the current size of the [`Memory`] in bytes.
     ///
     /// # Panics
     ///
     /// Panics if `ctx` does not own this [`Memory`].
+    pub fn data_size(&self, ctx: impl AsContext) -> usize {
+        ctx.as_context().store.inner.resolve_memory(self).data_len()
+    }
+
+    /// Returns a shared slice to the bytes underlying the [`Memory`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `ctx` does not own this [`Memory`].
+    /// - Panics if the [`Memory`] is shared.
+    ///   Use [`Memory::try_data`] or [`SharedMemory::data`] instead.
     pub fn data<'a, T: 'a>(&self, ctx: impl Into<StoreContext<'a, T>>) -> &'a [u8] {
         ctx.into().store.inner.resolve_memory(self).data()
     }
@@ -339,7 +739,9 @@ impl Memory {
     ///
     /// # Panics
     ///
-    /// Panics if `ctx` does not own this [`Memory`].
+    /// - Panics if `ctx` does not own this [`Memory`].
+    /// - Panics if the [`Memory`] is shared.
+    ///   Use [`Memory::try_data_mut`] or [`SharedMemory::data`] instead.
     pub fn data_mut<'a, T: 'a>(&self, ctx: impl Into<StoreContextMut<'a, T>>) -> &'a mut [u8] {
         ctx.into().store.inner.resolve_memory_mut(self).data_mut()
     }
@@ -349,7 +751,8 @@ impl Memory {
     ///
     /// # Panics
     ///
-    /// Panics if `ctx` does not own this [`Memory`].
+    /// - Panics if `ctx` does not own this [`Memory`].
+    /// - Panics if the [`Memory`] is shared. Use [`Memory::try_data_and_store_mut`] instead.
     pub fn data_and_store_mut<'a, T: 'a>(
         &self,
         ctx: impl Into<StoreContextMut<'a, T>>,
@@ -358,6 +761,56 @@ impl Memory {
         (memory.data_mut(), store)
     }
 
+    /// Returns a shared slice to the bytes underlying the [`Memory`].
+    ///
+    /// Returns `None` if the [`Memory`] is shared since other threads might
+    /// concurrently modify its bytes. Use [`Memory::read`] or the [`SharedMemory`]
+    /// returned by [`Memory::shared`] to access the bytes of a shared [`Memory`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn try_data<'a, T: 'a>(&self, ctx: impl Into<StoreContext<'a, T>>) -> Option<&'a [u8]> {
+        ctx.into().store.inner.resolve_memory(self).try_data()
+    }
+
+    /// Returns an exclusive slice to the bytes underlying the [`Memory`].
+    ///
+    /// Returns `None` if the [`Memory`] is shared since other threads might
+    /// concurrently access its bytes. Use [`Memory::write`] or the [`SharedMemory`]
+    /// returned by [`Memory::shared`] to access the bytes of a shared [`Memory`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn try_data_mut<'a, T: 'a>(
+        &self,
+        ctx: impl Into<StoreContextMut<'a, T>>,
+    ) -> Option<&'a mut [u8]> {
+        ctx.into()
+            .store
+            .inner
+            .resolve_memory_mut(self)
+            .try_data_mut()
+    }
+
+    /// Returns an exclusive slice to the bytes underlying the [`Memory`], and an exclusive
+    /// reference to the user provided state.
+    ///
+    /// The slice is `None` if the [`Memory`] is shared since other threads might
+    /// concurrently access its bytes. The user provided state is always returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Memory`].
+    pub fn try_data_and_store_mut<'a, T: 'a>(
+        &self,
+        ctx: impl Into<StoreContextMut<'a, T>>,
+    ) -> (Option<&'a mut [u8]>, &'a mut T) {
+        let (memory, store) = ctx.into().store.resolve_memory_and_state_mut(self);
+        (memory.try_data_mut(), store)
+    }
+
     /// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
     /// where `n` is the length of `buffer`.
     ///
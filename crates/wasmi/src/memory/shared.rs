@@ -0,0 +1,650 @@
+use super::{
+    atomics::{self, AtomicWord},
+    MemoryError,
+    MemoryType,
+};
+use crate::Engine;
+use alloc::{
+    alloc::{alloc_zeroed, dealloc, handle_alloc_error, Layout},
+    boxed::Box,
+    sync::Arc,
+    vec::Vec,
+};
+use core::{
+    cell::UnsafeCell,
+    fmt::{self, Debug},
+    mem::size_of,
+    ptr::{self, NonNull},
+    slice,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::Duration,
+};
+use spin::Mutex;
+use wasmi_core::{Pages, TrapCode};
+
+/// Host provided hooks to block and wake up threads.
+///
+/// These are used to implement the `memory.atomic.wait` and `memory.atomic.notify`
+/// instructions of the Wasm `threads` proposal.
+///
+/// # Note
+///
+/// - Use [`Store::set_thread_parker`] to install a [`ThreadParker`] for a [`Store`].
+/// - With the `std` crate feature [`StdThreadParker`] is installed by default.
+///
+/// [`Store`]: crate::Store
+/// [`Store::set_thread_parker`]: crate::Store::set_thread_parker
+pub trait ThreadParker: Send + Sync {
+    /// Returns a closure that unparks the calling thread.
+    ///
+    /// # Note
+    ///
+    /// An unpark that happens before the calling thread parks must not get lost.
+    /// Instead the next call to [`ThreadParker::park`] must return immediately.
+    fn unparker(&self) -> Box<dyn Fn() + Send + Sync>;
+
+    /// Blocks the calling thread until it is unparked or the optional `timeout` elapsed.
+    ///
+    /// # Note
+    ///
+    /// Spurious wake-ups are allowed.
+    fn park(&self, timeout: Option<Duration>);
+
+    /// Returns the current time of a monotonic clock.
+    ///
+    /// # Note
+    ///
+    /// Only the difference between two returned times is meaningful.
+    /// This is used to compute the deadline of a `memory.atomic.wait` timeout
+    /// that has to span over spurious wake-ups.
+    fn now(&self) -> Duration;
+}
+
+impl Debug for dyn ThreadParker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ThreadParker")
+    }
+}
+
+/// The [`ThreadParker`] based on the thread parking facilities of the Rust standard library.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct StdThreadParker;
+
+#[cfg(feature = "std")]
+impl ThreadParker for StdThreadParker {
+    fn unparker(&self) -> Box<dyn Fn() + Send + Sync> {
+        let thread = std::thread::current();
+        Box::new(move || thread.unpark())
+    }
+
+    fn park(&self, timeout: Option<Duration>) {
+        match timeout {
+            Some(timeout) => std::thread::park_timeout(timeout),
+            None => std::thread::park(),
+        }
+    }
+
+    fn now(&self) -> Duration {
+        static EPOCH: spin::Once<std::time::Instant> = spin::Once::new();
+        EPOCH.call_once(std::time::Instant::now).elapsed()
+    }
+}
+
+/// A thread that is blocked in a `memory.atomic.wait` instruction.
+struct Waiter {
+    /// The effective address the thread waits on.
+    address: usize,
+    /// Is `true` once the thread has been notified.
+    notified: Arc<AtomicBool>,
+    /// Unparks the waiting thread.
+    unparker: Box<dyn Fn() + Send + Sync>,
+}
+
+impl Debug for Waiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waiter")
+            .field("address", &self.address)
+            .field("notified", &self.notified)
+            .finish()
+    }
+}
+
+/// The result of a `memory.atomic.wait` on a [`SharedMemory`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WaitResult {
+    /// The waiting thread has been woken up by a `memory.atomic.notify`.
+    Ok = 0,
+    /// The value at the address did not equal the expected value.
+    Mismatch = 1,
+    /// The timeout of the wait elapsed.
+    TimedOut = 2,
+}
+
+impl From<WaitResult> for u32 {
+    fn from(result: WaitResult) -> Self {
+        result as u32
+    }
+}
+
+/// The bytes of a [`SharedMemory`].
+///
+/// # Note
+///
+/// - The bytes are allocated for the maximum size of the [`SharedMemory`]
+///   up front so that they never move when the [`SharedMemory`] grows.
+/// - Multiple threads may concurrently access the bytes. Therefore no
+///   references to them are ever handed out. Instead all non-atomic accesses
+///   are raw pointer reads and writes and all atomic accesses use atomic types.
+#[derive(Debug)]
+struct SharedBuffer {
+    ptr: NonNull<UnsafeCell<u8>>,
+    layout: Layout,
+}
+
+// Safety: the bytes of a `SharedBuffer` are meant to be accessed by multiple threads.
+//         They are only ever accessed through raw pointers or atomic types.
+unsafe impl Send for SharedBuffer {}
+unsafe impl Sync for SharedBuffer {}
+
+impl SharedBuffer {
+    /// Allocates a new zeroed [`SharedBuffer`] with a capacity of `len` bytes.
+    fn new(len: usize) -> Result<Self, MemoryError> {
+        if len == 0 {
+            return Ok(Self {
+                ptr: NonNull::<u64>::dangling().cast(),
+                layout: Layout::new::<()>(),
+            });
+        }
+        let layout = Layout::from_size_align(len, size_of::<u64>())
+            .map_err(|_| MemoryError::OutOfBoundsAllocation)?;
+        // Safety: `layout` has a non-zero size.
+        let ptr = unsafe { alloc_zeroed(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        Ok(Self {
+            ptr: ptr.cast(),
+            layout,
+        })
+    }
+
+    /// Returns a raw pointer to the first byte of the [`SharedBuffer`].
+    fn as_ptr(&self) -> *mut u8 {
+        UnsafeCell::raw_get(self.ptr.as_ptr())
+    }
+}
+
+impl Drop for SharedBuffer {
+    fn drop(&mut self) {
+        if self.layout.size() != 0 {
+            // Safety: `ptr` has been allocated with `layout` in `SharedBuffer::new`.
+            unsafe { dealloc(self.as_ptr(), self.layout) }
+        }
+    }
+}
+
+/// The shared state of a [`SharedMemory`].
+#[derive(Debug)]
+struct SharedMemoryEntity {
+    /// The type of the shared linear memory.
+    memory_type: MemoryType,
+    /// The bytes of the shared linear memory.
+    bytes: SharedBuffer,
+    /// The current amount of pages of the shared linear memory.
+    current_pages: AtomicU32,
+    /// Serializes concurrent `memory.grow` operations.
+    grow_lock: Mutex<()>,
+    /// The threads blocked in `memory.atomic.wait` in the order they started waiting.
+    waiters: Mutex<Vec<Waiter>>,
+}
+
+/// A linear memory that can be shared between multiple [`Store`]s on different threads.
+///
+/// # Note
+///
+/// - Shared linear memories are part of the Wasm `threads` proposal.
+/// - Use [`Memory::new_shared`] to use a [`SharedMemory`] within a [`Store`].
+/// - Use [`Memory::shared`] to access the [`SharedMemory`] of a shared [`Memory`].
+///
+/// [`Store`]: crate::Store
+/// [`Memory`]: crate::Memory
+/// [`Memory::new_shared`]: crate::Memory::new_shared
+/// [`Memory::shared`]: crate::Memory::shared
+#[derive(Debug, Clone)]
+pub struct SharedMemory {
+    inner: Arc<SharedMemoryEntity>,
+}
+
+impl SharedMemory {
+    /// Creates a new [`SharedMemory`] with the given shared [`MemoryType`].
+    ///
+    /// # Note
+    ///
+    /// The bytes of the [`SharedMemory`] are allocated up to its maximum size
+    /// since they must never move while other threads access them.
+    /// Use [`Memory::new`] with a shared [`MemoryType`] instead in order to
+    /// have the [`ResourceLimiter`] of a [`Store`] reject large maximum sizes
+    /// before anything is allocated.
+    ///
+    /// # Errors
+    ///
+    /// - If `ty` is not a shared [`MemoryType`] with a maximum size.
+    /// - If the `engine` is in deterministic tracing mode.
+    /// - If the maximum size of the [`SharedMemory`] cannot be allocated.
+    ///
+    /// [`Memory::new`]: crate::Memory::new
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
+    pub fn new(engine: &Engine, ty: MemoryType) -> Result<Self, MemoryError> {
+        let capacity = Self::capacity(engine, ty)?;
+        let bytes = SharedBuffer::new(capacity)?;
+        Ok(Self {
+            inner: Arc::new(SharedMemoryEntity {
+                memory_type: ty,
+                bytes,
+                current_pages: AtomicU32::new(ty.initial_pages().into()),
+                grow_lock: Mutex::new(()),
+                waiters: Mutex::new(Vec::new()),
+            }),
+        })
+    }
+
+    /// Returns the amount of bytes allocated by [`SharedMemory::new`] for `ty`.
+    ///
+    /// # Errors
+    ///
+    /// - If `ty` is not a shared [`MemoryType`] with a maximum size.
+    /// - If the `engine` is in deterministic tracing mode.
+    /// - If the maximum size of `ty` is not addressable.
+    pub(crate) fn capacity(engine: &Engine, ty: MemoryType) -> Result<usize, MemoryError> {
+        // Shared linear memories without a maximum size would have to allocate 4 GiB up front.
+        let maximum_pages = ty
+            .maximum_pages()
+            .filter(|_| ty.is_shared())
+            .ok_or(MemoryError::InvalidMemoryType)?;
+        if engine.config().get_deterministic_tracing() {
+            return Err(MemoryError::SharedMemoryInDeterministicTracing);
+        }
+        maximum_pages
+            .to_bytes()
+            .ok_or(MemoryError::OutOfBoundsAllocation)
+    }
+
+    /// Returns the memory type of the [`SharedMemory`].
+    pub fn ty(&self) -> MemoryType {
+        self.inner.memory_type
+    }
+
+    /// Returns the amount of pages in use by the [`SharedMemory`].
+    pub fn current_pages(&self) -> Pages {
+        let pages = self.inner.current_pages.load(Ordering::SeqCst);
+        Pages::new(pages)
+            .unwrap_or_else(|| panic!("invalid amount of shared memory pages: {pages}"))
+    }
+
+    /// Returns the current size of the [`SharedMemory`] in bytes.
+    fn len(&self) -> usize {
+        self.current_pages().to_bytes().unwrap_or(0)
+    }
+
+    /// Grows the [`SharedMemory`] by the given amount of new pages.
+    ///
+    /// Returns the amount of pages before the operation upon success.
+    ///
+    /// # Errors
+    ///
+    /// If the [`SharedMemory`] would grow beyond its maximum limit after
+    /// the grow operation.
+    pub fn grow(&self, additional: Pages) -> Result<Pages, MemoryError> {
+        let _guard = self.inner.grow_lock.lock();
+        let current_pages = self.current_pages();
+        if additional == Pages::from(0) {
+            return Ok(current_pages);
+        }
+        let maximum_pages = self
+            .ty()
+            .maximum_pages()
+            .expect("shared linear memories always have a maximum size");
+        let new_pages = current_pages
+            .checked_add(additional)
+            .filter(|&new_pages| new_pages <= maximum_pages)
+            .ok_or(MemoryError::OutOfBoundsGrowth)?;
+        // The bytes have already been allocated up to the maximum size
+        // so growing only requires to publish the new size.
+        self.inner
+            .current_pages
+            .store(new_pages.into(), Ordering::SeqCst);
+        Ok(current_pages)
+    }
+
+    /// Returns the bytes underlying the [`SharedMemory`].
+    ///
+    /// # Note
+    ///
+    /// Other threads might concurrently access the returned bytes.
+    /// Therefore they are only accessible through raw pointers.
+    /// Use [`SharedMemory::read`] and [`SharedMemory::write`] for
+    /// safe non-atomic accesses instead.
+    pub fn data(&self) -> &[UnsafeCell<u8>] {
+        // Safety: the bytes are allocated up to the maximum size and never move.
+        unsafe { slice::from_raw_parts(self.inner.bytes.ptr.as_ptr(), self.len()) }
+    }
+
+    /// Returns a raw pointer to the bytes underlying the [`SharedMemory`].
+    ///
+    /// # Note
+    ///
+    /// The pointer stays valid for as long as `self` is alive even if
+    /// the [`SharedMemory`] grows since its bytes never move. Only its
+    /// length might become stale.
+    pub(crate) fn data_ptr(&self) -> NonNull<[u8]> {
+        let data = ptr::slice_from_raw_parts_mut(self.inner.bytes.as_ptr(), self.len());
+        // Safety: the bytes of a `SharedBuffer` are never null.
+        unsafe { NonNull::new_unchecked(data) }
+    }
+
+    /// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
+    /// where `n` is the length of `buffer`.
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    pub fn read(&self, offset: usize, buffer: &mut [u8]) -> Result<(), MemoryError> {
+        // Safety: `data_ptr` points to valid bytes of the `SharedMemory`.
+        unsafe { read_raw(self.data_ptr(), offset, buffer) }
+    }
+
+    /// Writes `n` bytes to `memory[offset..offset+n]` from `buffer`
+    /// where `n` is the length of `buffer`.
+    ///
+    /// # Errors
+    ///
+    /// If this operation accesses out of bounds linear memory.
+    pub fn write(&self, offset: usize, buffer: &[u8]) -> Result<(), MemoryError> {
+        // Safety: `data_ptr` points to valid bytes of the `SharedMemory`.
+        unsafe { write_raw(self.data_ptr(), offset, buffer) }
+    }
+
+    /// Returns `true` if `self` and `other` refer to the same [`SharedMemory`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    /// Wakes up at most `count` threads waiting on the `u32` at `address` of the [`SharedMemory`].
+    ///
+    /// Returns the amount of threads that have been woken up.
+    ///
+    /// # Note
+    ///
+    /// This is the host side equivalent of the Wasm `memory.atomic.notify` instruction.
+    ///
+    /// # Errors
+    ///
+    /// If the `u32` at `address` is out of bounds or not naturally aligned.
+    pub fn atomic_notify(&self, address: u32, count: u32) -> Result<u32, TrapCode> {
+        let address = atomics::effective_address::<u32>(self.len(), address.into(), 0)?;
+        Ok(self.notify(address, count))
+    }
+
+    /// Blocks the calling thread if the `u32` at `address` equals `expected`.
+    ///
+    /// The calling thread is blocked via the `parker` until it is woken up by a
+    /// `memory.atomic.notify` on `address` or until the optional `timeout` elapsed.
+    ///
+    /// # Note
+    ///
+    /// This is the host side equivalent of the Wasm `memory.atomic.wait32` instruction.
+    ///
+    /// # Errors
+    ///
+    /// If the `u32` at `address` is out of bounds or not naturally aligned.
+    pub fn atomic_wait32(
+        &self,
+        address: u32,
+        expected: u32,
+        timeout: Option<Duration>,
+        parker: &Arc<dyn ThreadParker>,
+    ) -> Result<WaitResult, TrapCode> {
+        self.atomic_wait(address, expected, timeout, parker)
+    }
+
+    /// Blocks the calling thread if the `u64` at `address` equals `expected`.
+    ///
+    /// The calling thread is blocked via the `parker` until it is woken up by a
+    /// `memory.atomic.notify` on `address` or until the optional `timeout` elapsed.
+    ///
+    /// # Note
+    ///
+    /// This is the host side equivalent of the Wasm `memory.atomic.wait64` instruction.
+    ///
+    /// # Errors
+    ///
+    /// If the `u64` at `address` is out of bounds or not naturally aligned.
+    pub fn atomic_wait64(
+        &self,
+        address: u32,
+        expected: u64,
+        timeout: Option<Duration>,
+        parker: &Arc<dyn ThreadParker>,
+    ) -> Result<WaitResult, TrapCode> {
+        self.atomic_wait(address, expected, timeout, parker)
+    }
+
+    /// Blocks the calling thread if the `T` at `address` of the [`SharedMemory`] equals `expected`.
+    fn atomic_wait<T: AtomicWord>(
+        &self,
+        address: u32,
+        expected: T,
+        timeout: Option<Duration>,
+        parker: &Arc<dyn ThreadParker>,
+    ) -> Result<WaitResult, TrapCode> {
+        let address = atomics::effective_address::<T>(self.len(), address.into(), 0)?;
+        match self.wait(address, expected, timeout, Some(parker))? {
+            Some(waiting) => Ok(waiting.park()),
+            None => Ok(WaitResult::Mismatch),
+        }
+    }
+
+    /// Executes a `memory.atomic.wait` on the effective `address` of the [`SharedMemory`].
+    ///
+    /// Registers the calling thread as waiter on `address` if the value at `address`
+    /// equals `expected`. The registered thread then has to block via [`Waiting::park`].
+    ///
+    /// Returns `None` if the value at `address` did not equal `expected`.
+    ///
+    /// # Note
+    ///
+    /// The `address` must be in bounds and naturally aligned for `T`.
+    ///
+    /// # Errors
+    ///
+    /// If the calling thread has to block but there is no `parker`.
+    pub(crate) fn wait<T: AtomicWord>(
+        &self,
+        address: usize,
+        expected: T,
+        timeout: Option<Duration>,
+        parker: Option<&Arc<dyn ThreadParker>>,
+    ) -> Result<Option<Waiting>, TrapCode> {
+        let notified = Arc::new(AtomicBool::new(false));
+        let mut waiters = self.inner.waiters.lock();
+        // Safety: the `address` is in bounds and naturally aligned.
+        let value = unsafe { T::load(self.inner.bytes.as_ptr().add(address)) };
+        if value != expected {
+            return Ok(None);
+        }
+        let parker = parker.ok_or(TrapCode::AtomicWaitUnsupported)?;
+        waiters.push(Waiter {
+            address,
+            notified: notified.clone(),
+            unparker: parker.unparker(),
+        });
+        // The deadline is computed once so that spurious wake-ups do not extend the timeout.
+        let deadline = timeout.map(|timeout| parker.now().saturating_add(timeout));
+        Ok(Some(Waiting {
+            memory: self.clone(),
+            notified,
+            parker: parker.clone(),
+            deadline,
+        }))
+    }
+
+    /// Executes a `memory.atomic.notify` on the effective `address` of the [`SharedMemory`].
+    ///
+    /// Wakes up at most `count` threads waiting on `address` in the order they started waiting.
+    ///
+    /// Returns the amount of threads that have been woken up.
+    pub(crate) fn notify(&self, address: usize, count: u32) -> u32 {
+        let mut waiters = self.inner.waiters.lock();
+        let mut woken = 0;
+        waiters.retain(|waiter| {
+            if woken == count || waiter.address != address {
+                return true;
+            }
+            woken += 1;
+            waiter.notified.store(true, Ordering::SeqCst);
+            (waiter.unparker)();
+            false
+        });
+        woken
+    }
+}
+
+/// A thread that has been registered as waiter by a `memory.atomic.wait`.
+///
+/// # Note
+///
+/// The thread is not blocked until [`Waiting::park`] is called. This allows the
+/// executor to release resources that other threads might need before blocking.
+#[derive(Debug)]
+pub struct Waiting {
+    /// The [`SharedMemory`] the thread waits on.
+    memory: SharedMemory,
+    /// Is `true` once the thread has been notified.
+    notified: Arc<AtomicBool>,
+    /// Blocks the waiting thread.
+    parker: Arc<dyn ThreadParker>,
+    /// The time of the [`ThreadParker`] clock at which the wait times out if any.
+    deadline: Option<Duration>,
+}
+
+impl Waiting {
+    /// Blocks the calling thread until it has been notified or the wait timed out.
+    pub(crate) fn park(self) -> WaitResult {
+        let parker = &*self.parker;
+        loop {
+            let remaining = self
+                .deadline
+                .map(|deadline| deadline.saturating_sub(parker.now()));
+            if !matches!(remaining, Some(remaining) if remaining.is_zero()) {
+                parker.park(remaining);
+            }
+            if self.notified.load(Ordering::SeqCst) {
+                return WaitResult::Ok;
+            }
+            let timed_out = matches!(self.deadline, Some(deadline) if parker.now() >= deadline);
+            if timed_out {
+                let mut waiters = self.memory.inner.waiters.lock();
+                if self.notified.load(Ordering::SeqCst) {
+                    return WaitResult::Ok;
+                }
+                waiters.retain(|waiter| !Arc::ptr_eq(&waiter.notified, &self.notified));
+                return WaitResult::TimedOut;
+            }
+        }
+    }
+}
+
+/// Returns a raw pointer to `memory[offset..offset+len]`.
+///
+/// # Errors
+///
+/// If the range is out of bounds of `memory`.
+fn raw_range(memory: NonNull<[u8]>, offset: usize, len: usize) -> Result<*mut u8, MemoryError> {
+    match offset.checked_add(len) {
+        Some(end) if end <= memory.len() => {}
+        _ => return Err(MemoryError::OutOfBoundsAccess),
+    }
+    // Safety: `offset` is in bounds of `memory`.
+    Ok(unsafe { memory.cast::<u8>().as_ptr().add(offset) })
+}
+
+/// Reads `n` bytes from `memory[offset..offset+n]` into `buffer`
+/// where `n` is the length of `buffer`.
+///
+/// # Safety
+///
+/// The `memory` must be valid for reads but may be concurrently accessed by other threads.
+///
+/// # Errors
+///
+/// If this operation accesses out of bounds of `memory`.
+pub(crate) unsafe fn read_raw(
+    memory: NonNull<[u8]>,
+    offset: usize,
+    buffer: &mut [u8],
+) -> Result<(), MemoryError> {
+    let src = raw_range(memory, offset, buffer.len())?;
+    ptr::copy_nonoverlapping(src, buffer.as_mut_ptr(), buffer.len());
+    Ok(())
+}
+
+/// Writes `n` bytes to `memory[offset..offset+n]` from `buffer`
+/// where `n` is the length of `buffer`.
+///
+/// # Safety
+///
+/// The `memory` must be valid for writes but may be concurrently accessed by other threads.
+///
+/// # Errors
+///
+/// If this operation accesses out of bounds of `memory`.
+pub(crate) unsafe fn write_raw(
+    memory: NonNull<[u8]>,
+    offset: usize,
+    buffer: &[u8],
+) -> Result<(), MemoryError> {
+    let dst = raw_range(memory, offset, buffer.len())?;
+    ptr::copy_nonoverlapping(buffer.as_ptr(), dst, buffer.len());
+    Ok(())
+}
+
+/// Fills `memory[offset..offset+len]` with `byte`.
+///
+/// # Safety
+///
+/// The `memory` must be valid for writes but may be concurrently accessed by other threads.
+///
+/// # Errors
+///
+/// If this operation accesses out of bounds of `memory`.
+pub(crate) unsafe fn fill_raw(
+    memory: NonNull<[u8]>,
+    offset: usize,
+    byte: u8,
+    len: usize,
+) -> Result<(), MemoryError> {
+    let dst = raw_range(memory, offset, len)?;
+    ptr::write_bytes(dst, byte, len);
+    Ok(())
+}
+
+/// Copies `memory[src..src+len]` to `memory[dst..dst+len]`.
+///
+/// # Safety
+///
+/// The `memory` must be valid for reads and writes but may be concurrently accessed by other threads.
+///
+/// # Errors
+///
+/// If this operation accesses out of bounds of `memory`.
+pub(crate) unsafe fn copy_raw(
+    memory: NonNull<[u8]>,
+    src: usize,
+    dst: usize,
+    len: usize,
+) -> Result<(), MemoryError> {
+    let src = raw_range(memory, src, len)?;
+    let dst = raw_range(memory, dst, len)?;
+    ptr::copy(src, dst, len);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::Config;
 
 fn memory_type(minimum: u32, maximum: impl Into<Option<u32>>) -> MemoryType {
     MemoryType::new(minimum, maximum.into()).unwrap()
@@ -14,3 +15,28 @@ fn subtyping_works() {
     assert!(memory_type(0, 1).is_subtype_of(&memory_type(0, None)));
     assert!(!memory_type(0, None).is_subtype_of(&memory_type(0, 1)));
 }
+
+#[test]
+fn shared_subtyping_works() {
+    let shared = |minimum, maximum| MemoryType::new_shared(minimum, maximum).unwrap();
+    assert!(shared(0, 1).is_subtype_of(&shared(0, 1)));
+    assert!(shared(1, 1).is_subtype_of(&shared(0, 2)));
+    assert!(!shared(0, 1).is_subtype_of(&memory_type(0, 1)));
+    assert!(!memory_type(0, 1).is_subtype_of(&shared(0, 1)));
+}
+
+#[test]
+fn shared_memory_requires_maximum() {
+    let mut config = Config::default();
+    config.deterministic_tracing(false).wasm_threads(true);
+    let engine = Engine::new(&config);
+    let unbounded = MemoryType {
+        shared: true,
+        ..memory_type(1, None)
+    };
+    assert!(matches!(
+        SharedMemory::new(&engine, unbounded),
+        Err(MemoryError::InvalidMemoryType)
+    ));
+    assert!(SharedMemory::new(&engine, MemoryType::new_shared(1, 2).unwrap()).is_ok());
+}
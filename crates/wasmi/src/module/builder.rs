@@ -2,6 +2,7 @@ use super::{
     export::ExternIdx,
     import::FuncTypeIdx,
     ConstExpr,
+    CustomSection,
     DataSegment,
     ElementSegment,
     ExternTypeIdx,
@@ -11,9 +12,11 @@ use super::{
     Import,
     ImportName,
     Module,
+    ModuleNames,
+    TagIdx,
 };
 use crate::{
-    engine::{DedupFuncType, FuncBody},
+    engine::{DedupFuncType, FuncBody, TranslationError},
     errors::ModuleError,
     Engine,
     FuncType,
@@ -34,11 +37,15 @@ pub struct ModuleBuilder<'engine> {
     pub memories: Vec<MemoryType>,
     pub globals: Vec<GlobalType>,
     pub globals_init: Vec<ConstExpr>,
+    pub tags: Vec<DedupFuncType>,
     pub exports: BTreeMap<Box<str>, ExternIdx>,
     pub start: Option<FuncIdx>,
     pub func_bodies: Vec<FuncBody>,
     pub element_segments: Vec<ElementSegment>,
     pub data_segments: Vec<DataSegment>,
+    pub custom_sections: Vec<CustomSection>,
+    pub names: ModuleNames,
+    pub code_section_offset: Option<u32>,
 }
 
 /// The import names of the [`Module`] imports.
@@ -48,6 +55,7 @@ pub struct ModuleImports {
     pub tables: Vec<ImportName>,
     pub memories: Vec<ImportName>,
     pub globals: Vec<ImportName>,
+    pub tags: Vec<ImportName>,
 }
 
 impl ModuleImports {
@@ -89,6 +97,11 @@ impl<'a> ModuleResources<'a> {
         self.res.globals[global_idx.into_u32() as usize]
     }
 
+    /// Returns the [`FuncType`] of the indexed exception tag.
+    pub fn get_type_of_tag(&self, tag_idx: TagIdx) -> &DedupFuncType {
+        &self.res.tags[tag_idx.into_u32() as usize]
+    }
+
     /// Returns the global variable type and optional initial value.
     pub fn get_global(&self, global_idx: GlobalIdx) -> (GlobalType, Option<&ConstExpr>) {
         let index = global_idx.into_u32() as usize;
@@ -117,11 +130,15 @@ impl<'engine> ModuleBuilder<'engine> {
             memories: Vec::new(),
             globals: Vec::new(),
             globals_init: Vec::new(),
+            tags: Vec::new(),
             exports: BTreeMap::new(),
             start: None,
             func_bodies: Vec::new(),
             element_segments: Vec::new(),
             data_segments: Vec::new(),
+            custom_sections: Vec::new(),
+            names: ModuleNames::default(),
+            code_section_offset: None,
         }
     }
 
@@ -182,6 +199,7 @@ impl<'engine> ModuleBuilder<'engine> {
                     self.tables.push(table_type);
                 }
                 ExternTypeIdx::Memory(memory_type) => {
+                    self.ensure_memory_supported(&memory_type)?;
                     self.imports.memories.push(name);
                     self.memories.push(memory_type);
                 }
@@ -189,6 +207,11 @@ impl<'engine> ModuleBuilder<'engine> {
                     self.imports.globals.push(name);
                     self.globals.push(global_type);
                 }
+                ExternTypeIdx::Tag(func_type_idx) => {
+                    self.imports.tags.push(name);
+                    let func_type = self.func_types[func_type_idx.into_u32() as usize];
+                    self.tags.push(func_type);
+                }
             }
         }
         Ok(())
@@ -265,11 +288,24 @@ impl<'engine> ModuleBuilder<'engine> {
         );
         for memory in memories {
             let memory = memory?;
+            self.ensure_memory_supported(&memory)?;
             self.memories.push(memory);
         }
         Ok(())
     }
 
+    /// Ensures that the linear `memory` type is supported by the [`Engine`].
+    ///
+    /// # Errors
+    ///
+    /// If `memory` is shared while the [`Engine`] is in deterministic tracing mode.
+    fn ensure_memory_supported(&self, memory: &MemoryType) -> Result<(), ModuleError> {
+        if memory.is_shared() && self.engine.config().get_deterministic_tracing() {
+            return Err(TranslationError::shared_memory_in_deterministic_tracing().into());
+        }
+        Ok(())
+    }
+
     /// Pushes the given global variables to the [`Module`] under construction.
     ///
     /// # Errors
@@ -297,6 +333,32 @@ impl<'engine> ModuleBuilder<'engine> {
         Ok(())
     }
 
+    /// Pushes the given exception tag declarations to the [`Module`] under construction.
+    ///
+    /// # Errors
+    ///
+    /// If an exception tag declaration fails to validate.
+    ///
+    /// # Panics
+    ///
+    /// If this function has already been called on the same [`ModuleBuilder`].
+    pub fn push_tags<T>(&mut self, tags: T) -> Result<(), ModuleError>
+    where
+        T: IntoIterator<Item = Result<FuncTypeIdx, ModuleError>>,
+    {
+        assert_eq!(
+            self.tags.len(),
+            self.imports.tags.len(),
+            "tried to initialize module exception tag declarations twice"
+        );
+        for tag in tags {
+            let func_type_idx = tag?;
+            let func_type = self.func_types[func_type_idx.into_u32() as usize];
+            self.tags.push(func_type);
+        }
+        Ok(())
+    }
+
     /// Pushes the given exports to the [`Module`] under construction.
     ///
     /// # Errors
@@ -372,6 +434,21 @@ impl<'engine> ModuleBuilder<'engine> {
         Ok(())
     }
 
+    /// Pushes the given custom section to the [`Module`] under construction.
+    ///
+    /// # Note
+    ///
+    /// The names of the `name` custom section are decoded and ignored if malformed
+    /// since malformed custom sections must not invalidate a Wasm module.
+    pub fn push_custom_section(&mut self, name: &str, data: &[u8], data_offset: usize) {
+        if name == "name" {
+            if let Ok(names) = ModuleNames::parse(data, data_offset) {
+                self.names = names;
+            }
+        }
+        self.custom_sections.push(CustomSection::new(name, data));
+    }
+
     /// Finishes construction of the WebAssembly [`Module`].
     pub fn finish(self) -> Module {
         Module::from_builder(self)
@@ -0,0 +1,68 @@
+use alloc::boxed::Box;
+use core::slice::Iter as SliceIter;
+
+/// A custom section of a Wasm [`Module`].
+///
+/// [`Module`]: [`super::Module`]
+#[derive(Debug)]
+pub struct CustomSection {
+    /// The name of the custom section.
+    name: Box<str>,
+    /// The payload of the custom section.
+    data: Box<[u8]>,
+}
+
+impl CustomSection {
+    /// Creates a new [`CustomSection`] from its `name` and `data` payload.
+    pub(crate) fn new(name: &str, data: &[u8]) -> Self {
+        Self {
+            name: name.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Returns the name of the [`CustomSection`].
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the payload of the [`CustomSection`].
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// An iterator over the custom sections of a Wasm [`Module`].
+///
+/// [`Module`]: [`super::Module`]
+#[derive(Debug)]
+pub struct CustomSectionsIter<'a> {
+    iter: SliceIter<'a, CustomSection>,
+}
+
+impl<'a> CustomSectionsIter<'a> {
+    /// Creates a new [`CustomSectionsIter`] over the given `sections`.
+    pub(super) fn new(sections: &'a [CustomSection]) -> Self {
+        Self {
+            iter: sections.iter(),
+        }
+    }
+}
+
+impl<'a> Iterator for CustomSectionsIter<'a> {
+    type Item = &'a CustomSection;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for CustomSectionsIter<'a> {
+    fn len(&self) -> usize {
+        ExactSizeIterator::len(&self.iter)
+    }
+}
@@ -0,0 +1,340 @@
+use super::CustomSection;
+use crate::core::WasmBacktrace;
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::fmt::{self, Display};
+use gimli::{
+    ColumnType,
+    Dwarf,
+    EndianSlice,
+    FileEntry,
+    LineProgramHeader,
+    LittleEndian,
+    SectionId,
+    Unit,
+};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+/// The `gimli` reader used to read DWARF sections from Wasm custom sections.
+type DwarfReader<'a> = EndianSlice<'a, LittleEndian>;
+
+/// A source code location resolved from the DWARF debug information of a Wasm [`Module`].
+///
+/// [`Module`]: [`super::Module`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The path of the source file if any.
+    file: Option<Arc<str>>,
+    /// The 1-based line within the source file if any.
+    line: Option<u32>,
+    /// The 1-based column within the source line if any.
+    column: Option<u32>,
+    /// The name of the enclosing source function if any.
+    function: Option<Arc<str>>,
+}
+
+impl SourceLocation {
+    /// Returns the path of the source file if any.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the 1-based line within the source file if any.
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// Returns the 1-based column within the source line if any.
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+
+    /// Returns the name of the enclosing source function if any.
+    pub fn function(&self) -> Option<&str> {
+        self.function.as_deref()
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file().unwrap_or("<unknown>"))?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for SourceLocation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct("SourceLocation", 4)?;
+        s.serialize_field("file", &self.file())?;
+        s.serialize_field("line", &self.line)?;
+        s.serialize_field("column", &self.column)?;
+        s.serialize_field("function", &self.function())?;
+        s.end()
+    }
+}
+
+/// A row of the DWARF line table.
+#[derive(Debug)]
+struct LineRow {
+    /// The code section relative address of the first instruction covered by the row.
+    address: u64,
+    /// The source file, line and column of the row or `None` if it ends a sequence.
+    location: Option<(Arc<str>, Option<u32>, Option<u32>)>,
+}
+
+/// The code section relative address range of a source function.
+#[derive(Debug)]
+struct FuncRange {
+    /// The first address of the function.
+    low: u64,
+    /// The address past the last instruction of the function.
+    high: u64,
+    /// The name of the source function.
+    name: Arc<str>,
+}
+
+/// The DWARF debug information of a Wasm [`Module`].
+///
+/// Maps the `source_pc` byte offsets of traces and backtraces back to source locations.
+///
+/// # Note
+///
+/// Only the `.debug_line` and `.debug_info` custom sections are used.
+/// Addresses in Wasm DWARF are relative to the start of the code section payload.
+///
+/// [`Module`]: [`super::Module`]
+#[derive(Debug)]
+pub struct DebugInfo {
+    /// The byte offset of the code section payload within the Wasm binary.
+    code_offset: u32,
+    /// The rows of all line tables sorted by address.
+    rows: Vec<LineRow>,
+    /// The address ranges of all named source functions sorted by address.
+    funcs: Vec<FuncRange>,
+}
+
+impl DebugInfo {
+    /// Parses the DWARF custom `sections` of a Wasm module whose code section payload starts at `code_offset`.
+    ///
+    /// Returns `None` if there is no DWARF debug information or if it is malformed
+    /// since malformed custom sections must not invalidate a Wasm module.
+    pub(crate) fn parse(sections: &[CustomSection], code_offset: u32) -> Option<Self> {
+        let section = |id: SectionId| {
+            sections
+                .iter()
+                .find(|section| section.name() == id.name())
+                .map(CustomSection::data)
+                .unwrap_or_default()
+        };
+        if section(SectionId::DebugLine).is_empty() && section(SectionId::DebugInfo).is_empty() {
+            return None;
+        }
+        let dwarf =
+            Dwarf::load(|id| Ok::<_, gimli::Error>(EndianSlice::new(section(id), LittleEndian)))
+                .ok()?;
+        let mut info = Self {
+            code_offset,
+            rows: Vec::new(),
+            funcs: Vec::new(),
+        };
+        info.load(&dwarf).ok()?;
+        // Rows that end a sequence must not shadow rows that start a sequence at the same address.
+        info.rows
+            .sort_by_key(|row| (row.address, row.location.is_some()));
+        info.funcs.sort_by_key(|func| func.low);
+        Some(info)
+    }
+
+    /// Loads the line tables and source functions of all compilation units of `dwarf`.
+    fn load(&mut self, dwarf: &Dwarf<DwarfReader>) -> gimli::Result<()> {
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            self.load_rows(dwarf, &unit)?;
+            self.load_funcs(dwarf, &unit)?;
+        }
+        Ok(())
+    }
+
+    /// Loads the rows of the line table of the `unit`.
+    ///
+    /// # Note
+    ///
+    /// Sequences starting at address 0 belong to functions that have been
+    /// removed by the linker and are skipped.
+    fn load_rows(
+        &mut self,
+        dwarf: &Dwarf<DwarfReader>,
+        unit: &Unit<DwarfReader>,
+    ) -> gimli::Result<()> {
+        let Some(program) = unit.line_program.clone() else {
+            return Ok(());
+        };
+        let mut files = BTreeMap::<u64, Arc<str>>::new();
+        let mut rows = program.rows();
+        let mut sequence_start = true;
+        let mut skip_sequence = false;
+        while let Some((header, row)) = rows.next_row()? {
+            if sequence_start {
+                skip_sequence = row.address() == 0;
+                sequence_start = false;
+            }
+            if row.end_sequence() {
+                sequence_start = true;
+                if !skip_sequence {
+                    self.rows.push(LineRow {
+                        address: row.address(),
+                        location: None,
+                    });
+                }
+                continue;
+            }
+            if skip_sequence {
+                continue;
+            }
+            let file = match files.get(&row.file_index()) {
+                Some(file) => file.clone(),
+                None => {
+                    let path = match row.file(header) {
+                        Some(file) => file_path(dwarf, unit, header, file)?,
+                        None => String::new(),
+                    };
+                    let path: Arc<str> = path.into();
+                    files.insert(row.file_index(), path.clone());
+                    path
+                }
+            };
+            let line = row.line().map(|line| line.get() as u32);
+            let column = match row.column() {
+                ColumnType::LeftEdge => None,
+                ColumnType::Column(column) => Some(column.get() as u32),
+            };
+            self.rows.push(LineRow {
+                address: row.address(),
+                location: Some((file, line, column)),
+            });
+        }
+        Ok(())
+    }
+
+    /// Loads the address ranges of all named subprograms of the `unit`.
+    fn load_funcs(
+        &mut self,
+        dwarf: &Dwarf<DwarfReader>,
+        unit: &Unit<DwarfReader>,
+    ) -> gimli::Result<()> {
+        let mut entries = unit.entries();
+        while let Some((_, entry)) = entries.next_dfs()? {
+            if entry.tag() != gimli::DW_TAG_subprogram {
+                continue;
+            }
+            let Some(name) = entry.attr_value(gimli::DW_AT_name)? else {
+                continue;
+            };
+            let name: Arc<str> = dwarf.attr_string(unit, name)?.to_string_lossy().into();
+            let mut ranges = dwarf.die_ranges(unit, entry)?;
+            while let Some(range) = ranges.next()? {
+                if range.begin == 0 || range.begin >= range.end {
+                    continue;
+                }
+                self.funcs.push(FuncRange {
+                    low: range.begin,
+                    high: range.end,
+                    name: name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the [`SourceLocation`] of the Wasm operator at the `source_pc` byte offset if any.
+    ///
+    /// The `source_pc` is the byte offset within the Wasm binary as found in
+    /// trace entries and [`WasmFrame`] of backtraces.
+    ///
+    /// [`WasmFrame`]: crate::core::WasmFrame
+    pub fn lookup(&self, source_pc: u32) -> Option<SourceLocation> {
+        let address = u64::from(source_pc.checked_sub(self.code_offset)?);
+        let row = self
+            .rows
+            .partition_point(|row| row.address <= address)
+            .checked_sub(1)
+            .and_then(|index| self.rows[index].location.as_ref());
+        let function = self
+            .funcs
+            .partition_point(|func| func.low <= address)
+            .checked_sub(1)
+            .map(|index| &self.funcs[index])
+            .filter(|func| address < func.high)
+            .map(|func| func.name.clone());
+        if row.is_none() && function.is_none() {
+            return None;
+        }
+        let (file, line, column) = match row {
+            Some((file, line, column)) => (Some(file.clone()), *line, *column),
+            None => (None, None, None),
+        };
+        Some(SourceLocation {
+            file: file.filter(|file| !file.is_empty()),
+            line,
+            column,
+            function,
+        })
+    }
+
+    /// Returns a [`Display`] wrapper that prints the `backtrace` annotated with source locations.
+    pub fn display_backtrace<'a>(&'a self, backtrace: &'a WasmBacktrace) -> DisplayBacktrace<'a> {
+        DisplayBacktrace {
+            debug_info: self,
+            backtrace,
+        }
+    }
+}
+
+/// Returns the path of the `file` of a line table joined with its directory.
+fn file_path(
+    dwarf: &Dwarf<DwarfReader>,
+    unit: &Unit<DwarfReader>,
+    header: &LineProgramHeader<DwarfReader>,
+    file: &FileEntry<DwarfReader>,
+) -> gimli::Result<String> {
+    let name = dwarf.attr_string(unit, file.path_name())?.to_string_lossy();
+    let directory = match file.directory(header) {
+        Some(directory) => dwarf.attr_string(unit, directory)?.to_string_lossy(),
+        None => return Ok(name.into_owned()),
+    };
+    if directory.is_empty() || name.starts_with('/') {
+        return Ok(name.into_owned());
+    }
+    Ok(format!("{}/{name}", directory.trim_end_matches('/')))
+}
+
+/// Displays a [`WasmBacktrace`] annotated with the [`SourceLocation`] of its frames.
+#[derive(Debug)]
+pub struct DisplayBacktrace<'a> {
+    debug_info: &'a DebugInfo,
+    backtrace: &'a WasmBacktrace,
+}
+
+impl Display for DisplayBacktrace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (n, frame) in self.backtrace.frames().iter().enumerate() {
+            writeln!(f, "{n:>4}: {frame}")?;
+            if let Some(location) = self.debug_info.lookup(frame.source_pc()) {
+                match location.function() {
+                    Some(function) => writeln!(f, "          at {function} ({location})")?,
+                    None => writeln!(f, "          at {location}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
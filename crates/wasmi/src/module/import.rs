@@ -66,9 +66,7 @@ impl From<wasmparser::Import<'_>> for Import {
             TypeRef::Table(ty) => ExternTypeIdx::Table(TableType::from_wasmparser(ty)),
             TypeRef::Memory(ty) => ExternTypeIdx::Memory(MemoryType::from_wasmparser(ty)),
             TypeRef::Global(ty) => ExternTypeIdx::Global(GlobalType::from_wasmparser(ty)),
-            TypeRef::Tag(tag) => panic!(
-                "wasmi does not support the `exception-handling` Wasm proposal but found: {tag:?}"
-            ),
+            TypeRef::Tag(tag) => ExternTypeIdx::Tag(tag.func_type_idx.into()),
         };
         Self::new(import.module, import.name, kind)
     }
@@ -106,6 +104,8 @@ pub enum ExternTypeIdx {
     Memory(MemoryType),
     /// An imported global variable.
     Global(GlobalType),
+    /// An imported exception tag.
+    Tag(FuncTypeIdx),
 }
 
 /// A [`FuncType`] index.
@@ -5,6 +5,7 @@ use crate::{
     ExternType,
     FuncType,
     Table,
+    TagType,
 };
 use core::{fmt, fmt::Display};
 
@@ -29,6 +30,13 @@ pub enum InstantiationError {
         /// The actual function signature for the function import.
         actual: FuncType,
     },
+    /// Caused when an imported exception tag has a mismatching type.
+    TagTypeMismatch {
+        /// The expected tag type for the tag import.
+        expected: TagType,
+        /// The actual tag type for the tag import.
+        actual: TagType,
+    },
     /// Occurs when an imported table does not satisfy the required table type.
     Table(TableError),
     /// Occurs when an imported memory does not satisfy the required memory type.
@@ -49,6 +57,18 @@ pub enum InstantiationError {
         /// The index of the found `start` function.
         index: u32,
     },
+    /// Caused when the instantiation exceeds the instance limit of the [`ResourceLimiter`].
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    TooManyInstances,
+    /// Caused when the instantiation exceeds the table limit of the [`ResourceLimiter`].
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    TooManyTables,
+    /// Caused when the instantiation exceeds the linear memory limit of the [`ResourceLimiter`].
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    TooManyMemories,
 }
 
 #[cfg(feature = "std")]
@@ -71,6 +91,12 @@ impl Display for InstantiationError {
                     "expected {expected:?} function signature but found {actual:?}",
                 )
             }
+            Self::TagTypeMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "expected {expected:?} exception tag type but found {actual:?}",
+                )
+            }
             Self::ElementSegmentDoesNotFit {
                 table,
                 offset,
@@ -82,6 +108,11 @@ impl Display for InstantiationError {
             Self::FoundStartFn { index } => {
                 write!(f, "found an unexpected start function with index {index}")
             }
+            Self::TooManyInstances => write!(f, "the resource limit for instances has been exceeded"),
+            Self::TooManyTables => write!(f, "the resource limit for tables has been exceeded"),
+            Self::TooManyMemories => {
+                write!(f, "the resource limit for linear memories has been exceeded")
+            }
             Self::Table(error) => Display::fmt(error, f),
             Self::Memory(error) => Display::fmt(error, f),
             Self::Global(error) => Display::fmt(error, f),
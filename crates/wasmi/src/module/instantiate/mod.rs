@@ -1,3 +1,4 @@
+use wasmi_arena::ArenaIndex;
 use wasmi_core::{Trap, UntypedValue};
 
 use crate::{
@@ -17,6 +18,8 @@ use crate::{
     Memory,
     memory::DataSegment,
     Table,
+    Tag,
+    TagEntity,
     Value,
     value::WithType,
 };
@@ -44,9 +47,12 @@ impl Module {
     ///
     /// # Errors
     ///
-    /// If the given `externals` do not satisfy the required imports, e.g. if an externally
-    /// provided [`Func`] has a different function signature than required by the module import.
+    /// - If the given `externals` do not satisfy the required imports, e.g. if an externally
+    ///   provided [`Func`] has a different function signature than required by the module import.
+    /// - If the [`ResourceLimiter`] of the [`Store`] denies the instance or its resources.
     ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
     /// [`Linker`]: struct.Linker.html
     /// [`Func`]: [`crate::Func`]
     pub(crate) fn instantiate<I>(
@@ -57,19 +63,30 @@ impl Module {
         where
             I: IntoIterator<Item=Extern>,
     {
+        let len_tables = self.internal_tables().len();
+        let len_memories = self.internal_memories().len();
+        context
+            .as_context_mut()
+            .store
+            .check_resource_counts(len_tables, len_memories)?;
         let handle = context.as_context_mut().store.inner.alloc_instance();
         let mut builder = InstanceEntity::build(self);
 
         self.extract_imports(&mut context, &mut builder, externals)?;
         self.extract_functions(&mut context, &mut builder, handle);
         self.extract_tables(&mut context, &mut builder)?;
-        self.extract_memories(&mut context, &mut builder);
+        self.extract_memories(&mut context, &mut builder)?;
         self.extract_globals(&mut context, &mut builder);
+        self.extract_tags(&mut context, &mut builder);
         self.extract_exports(&mut context, &mut builder);
         self.extract_start_fn(&mut builder);
 
         self.initialize_table_elements(&mut context, &mut builder)?;
         self.initialize_memory_data(&mut context, &mut builder)?;
+        context
+            .as_context_mut()
+            .store
+            .bump_resource_counts(len_tables, len_memories);
 
         // At this point the module instantiation is nearly done.
         // The only thing that is missing is to run the `start` function.
@@ -129,6 +146,10 @@ impl Module {
                             expected: expected_signature.clone(),
                         });
                     }
+                    context
+                        .as_context_mut()
+                        .store
+                        .register_import_name(&func, import.name());
                     builder.push_func(func);
                 }
                 (ExternType::Table(required), Extern::Table(table)) => {
@@ -146,6 +167,16 @@ impl Module {
                     required.satisfies(&imported)?;
                     builder.push_global(global);
                 }
+                (ExternType::Tag(required), Extern::Tag(tag)) => {
+                    let imported = tag.ty(context.as_context());
+                    if &imported != required {
+                        return Err(InstantiationError::TagTypeMismatch {
+                            actual: imported,
+                            expected: required.clone(),
+                        });
+                    }
+                    builder.push_tag(tag);
+                }
                 (expected_import, actual_extern_val) => {
                     return Err(InstantiationError::ImportsExternalsMismatch {
                         expected: expected_import.clone(),
@@ -202,22 +233,22 @@ impl Module {
     ///
     /// This also stores [`Memory`] references into the [`Instance`] under construction.
     ///
+    /// # Errors
+    ///
+    /// If a linear memory cannot be allocated, e.g. if the [`ResourceLimiter`] denied it.
+    ///
     /// [`Store`]: struct.Store.html
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
     fn extract_memories(
         &self,
         context: &mut impl AsContextMut,
         builder: &mut InstanceEntityBuilder,
-    ) {
+    ) -> Result<(), InstantiationError> {
         for memory_type in self.internal_memories().copied() {
-            let memory =
-                Memory::new(context.as_context_mut(), memory_type).unwrap_or_else(|error| {
-                    panic!(
-                        "encountered unexpected invalid memory type \
-                        {memory_type:?} after Wasm validation: {error}",
-                    )
-                });
+            let memory = Memory::new(context.as_context_mut(), memory_type)?;
             builder.push_memory(memory);
         }
+        Ok(())
     }
 
     /// Extracts the Wasm global variables from the module and stores them into the [`Store`].
@@ -249,6 +280,22 @@ impl Module {
         }
     }
 
+    /// Extracts the Wasm exception tags from the module and stores them into the [`Store`].
+    ///
+    /// This also stores [`Tag`] references into the [`Instance`] under construction.
+    ///
+    /// [`Store`]: struct.Store.html
+    fn extract_tags(&self, context: &mut impl AsContextMut, builder: &mut InstanceEntityBuilder) {
+        for tag_type in self.internal_tags().copied() {
+            let tag: Tag = context
+                .as_context_mut()
+                .store
+                .inner
+                .alloc_tag(TagEntity::new(tag_type));
+            builder.push_tag(tag);
+        }
+    }
+
     /// Evaluates the given initializer expression using the partially constructed [`Instance`].
     fn eval_init_expr(
         context: impl AsContext,
@@ -268,8 +315,15 @@ impl Module {
         for (field, idx) in &self.exports {
             let (external, entity_index) = match idx {
                 export::ExternIdx::Func(func_index) => {
-                    let func_index = func_index.into_u32();
-                    let func = builder.get_func(func_index);
+                    let func = builder.get_func(func_index.into_u32());
+                    // The tracer names functions by their store wide index so that
+                    // calls into functions of other linked instances resolve as well.
+                    let func_index = context
+                        .as_context()
+                        .store
+                        .inner
+                        .unwrap_stored(func.as_inner())
+                        .into_usize() as u32;
                     (Extern::Func(func), func_index)
                 }
                 export::ExternIdx::Table(table_index) => {
@@ -287,6 +341,11 @@ impl Module {
                     let global = builder.get_global(global_index);
                     (Extern::Global(global), global_index)
                 }
+                export::ExternIdx::Tag(tag_index) => {
+                    let tag_index = tag_index.into_u32();
+                    let tag = builder.get_tag(tag_index);
+                    (Extern::Tag(tag), tag_index)
+                }
             };
             context.as_context_mut().store.tracer.register_extern(external, field, entity_index);
             builder.push_export(field, external);
@@ -1,6 +1,9 @@
 mod builder;
 mod compile;
+mod custom;
 mod data;
+#[cfg(feature = "dwarf")]
+mod dwarf;
 mod element;
 mod error;
 mod export;
@@ -8,6 +11,7 @@ mod global;
 mod import;
 mod init_expr;
 mod instantiate;
+mod names;
 mod parser;
 mod read;
 mod utils;
@@ -24,11 +28,13 @@ pub use self::{
 pub use self::{
     builder::ModuleResources,
     compile::BlockType,
+    custom::{CustomSection, CustomSectionsIter},
     error::ModuleError,
-    export::{ExportType, FuncIdx, MemoryIdx, ModuleExportsIter, TableIdx},
+    export::{ExportType, FuncIdx, MemoryIdx, ModuleExportsIter, TableIdx, TagIdx},
     global::GlobalIdx,
     import::{FuncTypeIdx, ImportName},
     instantiate::{InstancePre, InstantiationError},
+    names::ModuleNames,
     parser::ReusableAllocations,
     read::Read,
 };
@@ -37,6 +43,8 @@ pub use self::{
     element::{ElementSegment, ElementSegmentItems, ElementSegmentKind},
     init_expr::ConstExpr,
 };
+#[cfg(feature = "dwarf")]
+pub use self::dwarf::{DebugInfo, DisplayBacktrace, SourceLocation};
 use crate::{
     engine::{DedupFuncType, FuncBody},
     Engine,
@@ -46,6 +54,7 @@ use crate::{
     GlobalType,
     MemoryType,
     TableType,
+    TagType,
 };
 use alloc::{boxed::Box, collections::BTreeMap, sync::Arc};
 use core::{iter, slice::Iter as SliceIter};
@@ -61,11 +70,18 @@ pub struct Module {
     pub memories: Box<[MemoryType]>,
     pub globals: Box<[GlobalType]>,
     pub globals_init: Box<[ConstExpr]>,
+    pub tags: Box<[DedupFuncType]>,
     pub exports: BTreeMap<Box<str>, ExternIdx>,
     pub start: Option<FuncIdx>,
     pub func_bodies: Box<[FuncBody]>,
     pub element_segments: Box<[ElementSegment]>,
     pub data_segments: Box<[DataSegment]>,
+    pub custom_sections: Box<[CustomSection]>,
+    pub names: ModuleNames,
+    #[cfg(feature = "dwarf")]
+    pub code_section_offset: Option<u32>,
+    #[cfg(feature = "dwarf")]
+    pub debug_info: spin::Once<Option<DebugInfo>>,
 }
 
 /// The index of the default Wasm linear memory.
@@ -88,6 +104,10 @@ pub enum Imported {
     Memory(ImportName),
     /// The name of an imported [`Global`].
     Global(ImportName),
+    /// The name of an imported [`Tag`].
+    ///
+    /// [`Tag`]: [`crate::Tag`]
+    Tag(ImportName),
 }
 
 /// The import names of the [`Module`] imports.
@@ -109,6 +129,10 @@ pub struct ModuleImports {
     ///
     /// [`Table`]: [`crate::Table`]
     pub len_tables: usize,
+    /// The amount of imported [`Tag`].
+    ///
+    /// [`Tag`]: [`crate::Tag`]
+    pub len_tags: usize,
 }
 
 impl ModuleImports {
@@ -118,14 +142,17 @@ impl ModuleImports {
         let len_globals = imports.globals.len();
         let len_memories = imports.memories.len();
         let len_tables = imports.tables.len();
+        let len_tags = imports.tags.len();
         let funcs = imports.funcs.into_iter().map(Imported::Func);
         let tables = imports.tables.into_iter().map(Imported::Table);
         let memories = imports.memories.into_iter().map(Imported::Memory);
         let globals = imports.globals.into_iter().map(Imported::Global);
+        let tags = imports.tags.into_iter().map(Imported::Tag);
         let items = funcs
             .chain(tables)
             .chain(memories)
             .chain(globals)
+            .chain(tags)
             .collect::<Box<[_]>>();
         Self {
             items,
@@ -133,6 +160,7 @@ impl ModuleImports {
             len_globals,
             len_memories,
             len_tables,
+            len_tags,
         }
     }
 }
@@ -155,6 +183,12 @@ impl Module {
 
     /// Creates a new [`Module`] from the [`ModuleBuilder`].
     fn from_builder(builder: ModuleBuilder) -> Self {
+        let len_imported_funcs = builder.imports.funcs.len();
+        for (index, func_body) in builder.func_bodies.iter().enumerate() {
+            let func_index = (len_imported_funcs + index) as u32;
+            let name = builder.names.func(func_index);
+            builder.engine().set_func_info(*func_body, func_index, name);
+        }
         Self {
             engine: builder.engine().clone(),
             func_types: builder.func_types.into(),
@@ -164,11 +198,18 @@ impl Module {
             memories: builder.memories.into(),
             globals: builder.globals.into(),
             globals_init: builder.globals_init.into(),
+            tags: builder.tags.into(),
             exports: builder.exports,
             start: builder.start,
             func_bodies: builder.func_bodies.into(),
             element_segments: builder.element_segments.into(),
             data_segments: builder.data_segments.into(),
+            custom_sections: builder.custom_sections.into(),
+            names: builder.names,
+            #[cfg(feature = "dwarf")]
+            code_section_offset: builder.code_section_offset,
+            #[cfg(feature = "dwarf")]
+            debug_info: spin::Once::new(),
         }
     }
 
@@ -188,6 +229,10 @@ impl Module {
     pub(crate) fn len_globals(&self) -> usize {
         self.memories.len()
     }
+    /// Returns the number of non-imported exception tags of the [`Module`].
+    pub(crate) fn len_tags(&self) -> usize {
+        self.tags.len() - self.imports.len_tags
+    }
 
     /// Returns a slice to the function types of the [`Module`].
     ///
@@ -202,6 +247,7 @@ impl Module {
     pub fn imports(&self) -> ModuleImportsIter {
         let len_imported_funcs = self.imports.len_funcs;
         let len_imported_globals = self.imports.len_globals;
+        let len_imported_tags = self.imports.len_tags;
         ModuleImportsIter {
             engine: &self.engine,
             names: self.imports.items.iter(),
@@ -209,6 +255,7 @@ impl Module {
             tables: self.tables.iter(),
             memories: self.memories.iter(),
             globals: self.globals[..len_imported_globals].iter(),
+            tags: self.tags[..len_imported_tags].iter(),
         }
     }
 
@@ -239,7 +286,7 @@ impl Module {
     }
 
     /// Returns an iterator over the [`TableType`] of internal tables.
-    fn internal_tables(&self) -> SliceIter<TableType> {
+    pub fn internal_tables(&self) -> SliceIter<TableType> {
         let len_imported = self.imports.len_tables;
         // We skip the first `len_imported` elements in `memories`
         // since they refer to imported and not internally defined
@@ -261,11 +308,76 @@ impl Module {
         }
     }
 
+    /// Returns an iterator over the compiled internally defined [`Func`].
+    ///
+    /// Yields the module level function index, the [`FuncType`] and the [`FuncBody`]
+    /// of each function. The [`FuncBody`] can be inspected via [`Engine::instr_vec`],
+    /// [`Engine::instr_metas`] and [`Engine::func_header`].
+    ///
+    /// [`Func`]: [`crate::Func`]
+    pub fn compiled_funcs(&self) -> impl Iterator<Item = (u32, FuncType, FuncBody)> + '_ {
+        let len_imported = self.imports.len_funcs;
+        self.internal_funcs()
+            .enumerate()
+            .map(move |(index, (dedup, func_body))| {
+                let func_type = self.engine.resolve_func_type(&dedup, Clone::clone);
+                ((len_imported + index) as u32, func_type, func_body)
+            })
+    }
+
+    /// Returns the data segments of the [`Module`] in the order they appear in the Wasm binary.
+    pub fn data_segments(&self) -> SliceIter<DataSegment> {
+        self.data_segments.iter()
+    }
+
+    /// Returns an iterator over the types of the internally defined exception tags.
+    pub(crate) fn internal_tags(&self) -> SliceIter<DedupFuncType> {
+        let len_imported = self.imports.len_tags;
+        // We skip the first `len_imported` elements in `tags`
+        // since they refer to imported and not internally defined
+        // exception tags.
+        self.tags[len_imported..].iter()
+    }
+
     /// Returns an iterator over the exports of the [`Module`].
     pub fn exports(&self) -> ModuleExportsIter {
         ModuleExportsIter::new(self)
     }
 
+    /// Returns an iterator over the custom sections of the [`Module`].
+    ///
+    /// The custom sections are yielded in the order they appear in the Wasm binary.
+    pub fn custom_sections(&self) -> CustomSectionsIter {
+        CustomSectionsIter::new(&self.custom_sections)
+    }
+
+    /// Returns the names of the [`Module`] and its entities.
+    ///
+    /// # Note
+    ///
+    /// The names are decoded from the Wasm `name` custom section.
+    /// They are empty if the Wasm binary has no or a malformed `name` custom section.
+    pub fn names(&self) -> &ModuleNames {
+        &self.names
+    }
+
+    /// Returns the DWARF [`DebugInfo`] of the [`Module`] if any.
+    ///
+    /// # Note
+    ///
+    /// The debug information is decoded from the `.debug_line` and `.debug_info` custom sections
+    /// upon the first call and then cached. It is `None` if the Wasm binary has no or malformed
+    /// DWARF custom sections.
+    #[cfg(feature = "dwarf")]
+    pub fn debug_info(&self) -> Option<&DebugInfo> {
+        self.debug_info
+            .call_once(|| {
+                self.code_section_offset
+                    .and_then(|offset| DebugInfo::parse(&self.custom_sections, offset))
+            })
+            .as_ref()
+    }
+
     /// Looks up an export in this [`Module`] by its `name`.
     ///
     /// Returns `None` if no export with the name was found.
@@ -303,6 +415,11 @@ impl Module {
                 let global_type = self.globals[index.into_u32() as usize];
                 ExternType::Global(global_type)
             }
+            ExternIdx::Tag(index) => {
+                let dedup = &self.tags[index.into_u32() as usize];
+                let func_type = self.engine.resolve_func_type(dedup, Clone::clone);
+                ExternType::Tag(TagType::from_func_type(func_type))
+            }
         }
     }
 }
@@ -316,6 +433,7 @@ pub struct ModuleImportsIter<'a> {
     tables: SliceIter<'a, TableType>,
     memories: SliceIter<'a, MemoryType>,
     globals: SliceIter<'a, GlobalType>,
+    tags: SliceIter<'a, DedupFuncType>,
 }
 
 impl<'a> Iterator for ModuleImportsIter<'a> {
@@ -350,6 +468,13 @@ impl<'a> Iterator for ModuleImportsIter<'a> {
                     });
                     ImportType::new(name, *global_type)
                 }
+                Imported::Tag(name) => {
+                    let tag_type = self.tags.next().unwrap_or_else(|| {
+                        panic!("unexpected missing imported exception tag for {name:?}")
+                    });
+                    let func_type = self.engine.resolve_func_type(tag_type, FuncType::clone);
+                    ImportType::new(name, TagType::from_func_type(func_type))
+                }
             },
         };
         Some(import)
@@ -0,0 +1,91 @@
+use alloc::{boxed::Box, collections::BTreeMap};
+use wasmparser::{BinaryReaderError, Name, NameMap, NameSectionReader};
+
+/// The names of a Wasm [`Module`] and its entities.
+///
+/// These are decoded from the Wasm `name` custom section if present.
+/// All function and global variable indices refer to the respective index
+/// spaces of the [`Module`] and therefore include imported entities.
+///
+/// [`Module`]: [`super::Module`]
+#[derive(Debug, Default)]
+pub struct ModuleNames {
+    /// The name of the module itself.
+    module: Option<Box<str>>,
+    /// The names of functions by function index.
+    funcs: BTreeMap<u32, Box<str>>,
+    /// The names of local variables by function and local index.
+    locals: BTreeMap<u32, BTreeMap<u32, Box<str>>>,
+    /// The names of global variables by global index.
+    globals: BTreeMap<u32, Box<str>>,
+}
+
+impl ModuleNames {
+    /// Decodes the [`ModuleNames`] from the payload of a Wasm `name` custom section.
+    ///
+    /// # Errors
+    ///
+    /// If the `name` custom section is malformed.
+    pub(crate) fn parse(data: &[u8], offset: usize) -> Result<Self, BinaryReaderError> {
+        let mut names = Self::default();
+        for subsection in NameSectionReader::new(data, offset) {
+            match subsection? {
+                Name::Module { name, .. } => {
+                    names.module = Some(name.into());
+                }
+                Name::Function(map) => {
+                    names.funcs = Self::parse_map(map)?;
+                }
+                Name::Local(indirect_map) => {
+                    for indirect in indirect_map {
+                        let indirect = indirect?;
+                        let map = Self::parse_map(indirect.names)?;
+                        names.locals.insert(indirect.index, map);
+                    }
+                }
+                Name::Global(map) => {
+                    names.globals = Self::parse_map(map)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(names)
+    }
+
+    /// Decodes all names of the name `map` by their index.
+    fn parse_map(map: NameMap) -> Result<BTreeMap<u32, Box<str>>, BinaryReaderError> {
+        map.into_iter()
+            .map(|naming| naming.map(|naming| (naming.index, naming.name.into())))
+            .collect()
+    }
+
+    /// Returns the name of the [`Module`] if any.
+    ///
+    /// [`Module`]: [`super::Module`]
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// Returns the name of the function at `func_index` if any.
+    pub fn func(&self, func_index: u32) -> Option<&str> {
+        self.funcs.get(&func_index).map(AsRef::as_ref)
+    }
+
+    /// Returns the name of the local variable at `local_index` of the function at `func_index` if any.
+    pub fn local(&self, func_index: u32, local_index: u32) -> Option<&str> {
+        self.locals
+            .get(&func_index)?
+            .get(&local_index)
+            .map(AsRef::as_ref)
+    }
+
+    /// Returns the name of the global variable at `global_index` if any.
+    pub fn global(&self, global_index: u32) -> Option<&str> {
+        self.globals.get(&global_index).map(AsRef::as_ref)
+    }
+
+    /// Returns an iterator over all named functions and their function indices.
+    pub fn funcs(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.funcs.iter().map(|(index, name)| (*index, &**name))
+    }
+}
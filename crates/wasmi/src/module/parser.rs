@@ -20,6 +20,7 @@ use core::{
 };
 use wasmparser::{
     Chunk,
+    CustomSectionReader,
     DataSectionReader,
     ElementSectionReader,
     Encoding,
@@ -194,7 +195,7 @@ impl<'engine> ModuleParser<'engine> {
             Payload::ElementSection(section) => self.process_element(section),
             Payload::DataCountSection { count, range } => self.process_data_count(count, range),
             Payload::DataSection(section) => self.process_data(section),
-            Payload::CustomSection { .. } => Ok(()),
+            Payload::CustomSection(section) => self.process_custom(section),
             Payload::CodeSectionStart { count, range, .. } => self.process_code_start(count, range),
             Payload::CodeSectionEntry(func_body) => self.process_code_entry(func_body),
             Payload::UnknownSection { id, range, .. } => self.process_unknown(id, range),
@@ -368,10 +369,20 @@ impl<'engine> ModuleParser<'engine> {
     ///
     /// # Note
     ///
-    /// This is part of the module linking Wasm proposal and not yet supported
-    /// by `wasmi`.
+    /// This extracts all tag declarations into the [`Module`] under construction.
+    /// Tags are part of the Wasm `exception-handling` proposal.
+    ///
+    /// # Errors
+    ///
+    /// If a tag declaration fails to validate.
     fn process_tags(&mut self, section: wasmparser::TagSectionReader) -> Result<(), ModuleError> {
-        self.validator.tag_section(&section).map_err(Into::into)
+        self.validator.tag_section(&section)?;
+        let tags = section.into_iter().map(|tag| {
+            tag.map(|tag| FuncTypeIdx::from(tag.func_type_idx))
+                .map_err(ModuleError::from)
+        });
+        self.builder.push_tags(tags)?;
+        Ok(())
     }
 
     /// Process module global variable declarations.
@@ -489,6 +500,7 @@ impl<'engine> ModuleParser<'engine> {
     /// If the code start section fails to validate.
     fn process_code_start(&mut self, count: u32, range: Range<usize>) -> Result<(), ModuleError> {
         self.validator.code_section_start(count, &range)?;
+        self.builder.code_section_offset = Some(range.start as u32);
         // We have to adjust the initial func reference to the first
         // internal function before we process any of the internal functions.
         let len_func_imports = self.builder.imports.funcs.len() as u32;
@@ -542,6 +554,17 @@ impl<'engine> ModuleParser<'engine> {
         )
     }
 
+    /// Processes a Wasm custom section.
+    ///
+    /// # Note
+    ///
+    /// Custom sections are not validated and stored on the [`Module`] as is.
+    fn process_custom(&mut self, section: CustomSectionReader) -> Result<(), ModuleError> {
+        self.builder
+            .push_custom_section(section.name(), section.data(), section.data_offset());
+        Ok(())
+    }
+
     /// Process an unknown Wasm module section.
     ///
     /// # Note
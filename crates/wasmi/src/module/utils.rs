@@ -28,10 +28,6 @@ impl MemoryType {
             !memory_type.memory64,
             "wasmi does not support the `memory64` Wasm proposal"
         );
-        assert!(
-            !memory_type.shared,
-            "wasmi does not support the `threads` Wasm proposal"
-        );
         let initial: u32 = memory_type
             .initial
             .try_into()
@@ -41,8 +37,12 @@ impl MemoryType {
             .map(TryInto::try_into)
             .transpose()
             .expect("wasm32 memories must have a valid u32 maximum size if any");
-        Self::new(initial, maximum)
-            .expect("encountered invalid wasmparser::MemoryType after validation")
+        let memory_type = match (memory_type.shared, maximum) {
+            (true, Some(maximum)) => Self::new_shared(initial, maximum),
+            (true, None) => panic!("shared linear memories must have a maximum size after validation"),
+            (false, maximum) => Self::new(initial, maximum),
+        };
+        memory_type.expect("encountered invalid wasmparser::MemoryType after validation")
     }
 }
 
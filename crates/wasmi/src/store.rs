@@ -1,7 +1,7 @@
 use crate::{
     engine::DedupFuncType,
     externref::{ExternObject, ExternObjectEntity, ExternObjectIdx},
-    func::{Trampoline, TrampolineEntity, TrampolineIdx},
+    func::{HostFuncEntity, Trampoline, TrampolineEntity, TrampolineIdx},
     memory::DataSegment,
     DataSegmentEntity,
     DataSegmentIdx,
@@ -25,6 +25,16 @@ use crate::{
     Table,
     TableEntity,
     TableIdx,
+    Tag,
+    TagEntity,
+    TagIdx,
+    ThreadParker,
+};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
 };
 use core::{
     fmt::{self, Debug},
@@ -33,6 +43,13 @@ use core::{
 use wasmi_arena::{Arena, ArenaIndex, GuardedEntity};
 use wasmi_core::TrapCode;
 use crate::engine::tracer::Tracer;
+use crate::{
+    errors::InstantiationError,
+    limits::{ResourceLimiter, ResourceLimiterQuery, ResourceLimiterRef},
+    Debugger,
+    HostCallMode,
+    Profiler,
+};
 
 /// A unique store index.
 ///
@@ -81,9 +98,27 @@ pub struct Store<T> {
     /// Stored host function trampolines.
     trampolines: Arena<TrampolineIdx, TrampolineEntity<T>>,
     /// User provided host data owned by the [`Store`].
-    data: T,
+    pub(crate) data: T,
     /// Tracer
     pub tracer: Tracer,
+    /// The installed step debugger if any.
+    pub(crate) debugger: Option<Debugger>,
+    /// The installed profiler if any.
+    pub(crate) profiler: Option<Profiler>,
+    /// The installed host call recording or replay mode if any.
+    pub(crate) host_calls: Option<HostCallMode>,
+    /// The names under which functions have been imported, keyed by their store index.
+    import_names: BTreeMap<usize, String>,
+    /// Queries the installed [`ResourceLimiter`] from the host data if any.
+    pub(crate) limiter: Option<ResourceLimiterQuery<T>>,
+    /// The number of instances instantiated within the [`Store`].
+    instance_count: usize,
+    /// The number of tables defined by Wasm modules within the [`Store`].
+    table_count: usize,
+    /// The number of linear memories defined by Wasm modules within the [`Store`].
+    memory_count: usize,
+    /// Is `true` while [`Func::call_async`] executes Wasm functions of the [`Store`].
+    async_call: bool,
 }
 
 /// The inner store that owns all data not associated to the host state.
@@ -101,6 +136,8 @@ pub struct StoreInner {
     tables: Arena<TableIdx, TableEntity>,
     /// Stored global variables.
     globals: Arena<GlobalIdx, GlobalEntity>,
+    /// Stored exception tags.
+    tags: Arena<TagIdx, TagEntity>,
     /// Stored module instances.
     instances: Arena<InstanceIdx, InstanceEntity>,
     /// Stored data segments.
@@ -117,6 +154,10 @@ pub struct StoreInner {
     engine: Engine,
     /// The fuel of the [`Store`].
     fuel: Fuel,
+    /// The [`Engine`] epoch at which Wasm executions of the [`Store`] are interrupted.
+    epoch_deadline: u64,
+    /// Blocks and wakes up threads for the atomic wait and notify instructions.
+    thread_parker: Option<Arc<dyn ThreadParker>>,
 }
 
 #[test]
@@ -138,6 +179,8 @@ pub enum FuelError {
     FuelMeteringDisabled,
     /// Raised when trying to consume more fuel than is available in the [`Store`].
     OutOfFuel,
+    /// Raised when trying to pop a fuel frame while no fuel frame has been pushed.
+    NoFuelFrame,
 }
 
 impl fmt::Display for FuelError {
@@ -145,6 +188,7 @@ impl fmt::Display for FuelError {
         match self {
             Self::FuelMeteringDisabled => write!(f, "fuel metering is disabled"),
             Self::OutOfFuel => write!(f, "all fuel consumed"),
+            Self::NoFuelFrame => write!(f, "no fuel frame has been pushed"),
         }
     }
 }
@@ -169,20 +213,90 @@ impl FuelError {
     pub fn out_of_fuel() -> Self {
         Self::OutOfFuel
     }
+
+    /// Returns an error indicating that there is no fuel frame to pop.
+    ///
+    /// # Note
+    ///
+    /// This method exists to indicate that this execution path is cold.
+    #[cold]
+    pub fn no_fuel_frame() -> Self {
+        Self::NoFuelFrame
+    }
 }
 
 /// The remaining and consumed fuel counters.
-#[derive(Debug, Default, Copy, Clone)]
+///
+/// # Note
+///
+/// The remaining fuel always belongs to the innermost fuel frame.
+/// This way executions charge fuel against the innermost fuel frame
+/// without knowing about fuel frames at all.
+#[derive(Debug, Default, Clone)]
 pub struct Fuel {
     /// The remaining fuel.
     remaining: u64,
     /// The total amount of fuel so far.
     total: u64,
+    /// The remaining fuel of all parent frames that is not reserved by their child frames.
+    reserved: u64,
+    /// The stack of nested fuel frames.
+    frames: Vec<FuelFrameEntity>,
+}
+
+/// A nested fuel frame on the [`Fuel`] frame stack.
+#[derive(Debug, Copy, Clone)]
+struct FuelFrameEntity {
+    /// The remaining fuel of the parent frame that is not reserved by this frame.
+    parent_remaining: u64,
+    /// The fuel limit of this frame.
+    limit: u64,
+    /// The amount of fuel refunded to this frame.
+    refunded: u64,
+}
+
+/// The fuel accounting of a popped fuel frame.
+///
+/// Returned by [`Store::pop_fuel_frame`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FuelFrame {
+    /// The fuel limit of the fuel frame.
+    limit: u64,
+    /// The fuel that has been consumed within the fuel frame.
+    consumed: u64,
+    /// The fuel that has been refunded within the fuel frame.
+    refunded: u64,
+}
+
+impl FuelFrame {
+    /// Returns the fuel limit the fuel frame has been pushed with.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns the fuel consumed within the fuel frame after refunds.
+    pub fn consumed(&self) -> u64 {
+        self.consumed
+    }
+
+    /// Returns the fuel refunded within the fuel frame.
+    pub fn refunded(&self) -> u64 {
+        self.refunded
+    }
+
+    /// Returns the unused fuel that has been returned to the parent frame.
+    pub fn unused(&self) -> u64 {
+        self.limit - self.consumed
+    }
 }
 
 impl Fuel {
     /// Adds `delta` quantity of fuel to the remaining [`Fuel`].
     ///
+    /// # Note
+    ///
+    /// The fuel is added to the innermost fuel frame.
+    ///
     /// # Panics
     ///
     /// If this overflows the [`Fuel`] counter.
@@ -195,11 +309,16 @@ impl Fuel {
         });
         // No need to check as well since `self.total >= self.remaining`.
         self.remaining = self.remaining.wrapping_add(delta);
+        if let Some(frame) = self.frames.last_mut() {
+            frame.limit = frame.limit.wrapping_add(delta);
+        }
     }
 
     /// Returns the amount of [`Fuel`] consumed by executions of the [`Store`] so far.
     pub fn fuel_consumed(&self) -> u64 {
-        self.total.wrapping_sub(self.remaining)
+        self.total
+            .wrapping_sub(self.remaining)
+            .wrapping_sub(self.reserved)
     }
 
     /// Returns `Ok` if enough fuel is remaining to satisfy `delta` fuel consumption.
@@ -222,6 +341,84 @@ impl Fuel {
             .ok_or(TrapCode::OutOfFuel)?;
         Ok(self.remaining)
     }
+
+    /// Returns the amount of fuel consumed within the innermost fuel frame.
+    fn frame_consumed(&self) -> u64 {
+        match self.frames.last() {
+            Some(frame) => frame.limit - self.remaining,
+            None => self.fuel_consumed(),
+        }
+    }
+
+    /// Pushes a new fuel frame that may consume at most `limit` fuel.
+    ///
+    /// The `limit` is reserved from the remaining fuel of the current frame.
+    ///
+    /// # Errors
+    ///
+    /// If the current frame has less than `limit` fuel remaining.
+    pub fn push_frame(&mut self, limit: u64) -> Result<(), TrapCode> {
+        let parent_remaining = self
+            .remaining
+            .checked_sub(limit)
+            .ok_or(TrapCode::OutOfFuel)?;
+        self.frames.push(FuelFrameEntity {
+            parent_remaining,
+            limit,
+            refunded: 0,
+        });
+        self.reserved += parent_remaining;
+        self.remaining = limit;
+        Ok(())
+    }
+
+    /// Pops the innermost fuel frame and returns its [`FuelFrame`] accounting.
+    ///
+    /// The unused fuel of the popped frame is returned to its parent frame.
+    ///
+    /// Returns `None` if no fuel frame has been pushed.
+    pub fn pop_frame(&mut self) -> Option<FuelFrame> {
+        let frame = self.frames.pop()?;
+        let unused = self.remaining;
+        self.reserved -= frame.parent_remaining;
+        self.remaining = frame.parent_remaining + unused;
+        Some(FuelFrame {
+            limit: frame.limit,
+            consumed: frame.limit - unused,
+            refunded: frame.refunded,
+        })
+    }
+
+    /// Returns the number of pushed fuel frames.
+    pub fn frame_depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Refunds `delta` consumed fuel to the innermost fuel frame.
+    ///
+    /// At most the fuel consumed within the innermost fuel frame is refunded.
+    ///
+    /// Returns the amount of refunded fuel.
+    pub fn refund_fuel(&mut self, delta: u64) -> u64 {
+        let refund = delta.min(self.frame_consumed());
+        self.remaining += refund;
+        if let Some(frame) = self.frames.last_mut() {
+            frame.refunded += refund;
+        }
+        refund
+    }
+}
+
+/// Returns the [`ThreadParker`] that is used by default.
+fn default_thread_parker() -> Option<Arc<dyn ThreadParker>> {
+    #[cfg(feature = "std")]
+    {
+        Some(Arc::new(crate::StdThreadParker))
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        None
+    }
 }
 
 impl StoreInner {
@@ -234,14 +431,22 @@ impl StoreInner {
             memories: Arena::new(),
             tables: Arena::new(),
             globals: Arena::new(),
+            tags: Arena::new(),
             instances: Arena::new(),
             datas: Arena::new(),
             elems: Arena::new(),
             extern_objects: Arena::new(),
             fuel: Fuel::default(),
+            epoch_deadline: u64::MAX,
+            thread_parker: default_thread_parker(),
         }
     }
 
+    /// Returns the [`ThreadParker`] of the [`StoreInner`] if any.
+    pub fn thread_parker(&self) -> Option<&Arc<dyn ThreadParker>> {
+        self.thread_parker.as_ref()
+    }
+
     /// Returns the [`Engine`] that this store is associated with.
     pub fn engine(&self) -> &Engine {
         &self.engine
@@ -257,6 +462,11 @@ impl StoreInner {
         &mut self.fuel
     }
 
+    /// Returns the [`Engine`] epoch at which Wasm executions are interrupted.
+    pub fn epoch_deadline(&self) -> u64 {
+        self.epoch_deadline
+    }
+
     /// Wraps an entitiy `Idx` (index type) as a [`Stored<Idx>`] type.
     ///
     /// # Note
@@ -290,6 +500,12 @@ impl StoreInner {
         Global::from_inner(self.wrap_stored(global))
     }
 
+    /// Allocates a new [`TagEntity`] and returns a [`Tag`] reference to it.
+    pub fn alloc_tag(&mut self, tag: TagEntity) -> Tag {
+        let tag = self.tags.alloc(tag);
+        Tag::from_inner(self.wrap_stored(tag))
+    }
+
     /// Allocates a new [`TableEntity`] and returns a [`Table`] reference to it.
     pub fn alloc_table(&mut self, table: TableEntity) -> Table {
         let table = self.tables.alloc(table);
@@ -450,6 +666,16 @@ impl StoreInner {
         Self::resolve_mut(idx, &mut self.globals)
     }
 
+    /// Returns a shared reference to the [`TagEntity`] associated to the given [`Tag`].
+    ///
+    /// # Panics
+    ///
+    /// - If the [`Tag`] does not originate from this [`Store`].
+    /// - If the [`Tag`] cannot be resolved to its entity.
+    pub fn resolve_tag(&self, tag: &Tag) -> &TagEntity {
+        self.resolve(tag.as_inner(), &self.tags)
+    }
+
     /// Returns a shared reference to the [`TableEntity`] associated to the given [`Table`].
     ///
     /// # Panics
@@ -586,6 +812,16 @@ impl StoreInner {
         self.resolve(memory.as_inner(), &self.memories)
     }
 
+    /// Returns an iterator over exclusive references to all [`MemoryEntity`]s of the [`StoreInner`] in allocation order.
+    pub fn memory_entities_mut(&mut self) -> impl Iterator<Item = &mut MemoryEntity> {
+        self.memories.iter_mut().map(|(_, memory)| memory)
+    }
+
+    /// Returns an exclusive reference to the `index`-th allocated [`MemoryEntity`] if any.
+    pub fn memory_entity_mut(&mut self, index: u32) -> Option<&mut MemoryEntity> {
+        self.memories.get_mut(MemoryIdx::from_usize(index as usize))
+    }
+
     /// Returns an exclusive reference to the [`MemoryEntity`] associated to the given [`Memory`].
     ///
     /// # Panics
@@ -695,6 +931,15 @@ impl<T> Store<T> {
             trampolines: Arena::new(),
             data,
             tracer: Tracer::default(),
+            debugger: None,
+            profiler: None,
+            host_calls: None,
+            import_names: BTreeMap::new(),
+            limiter: None,
+            instance_count: 0,
+            table_count: 0,
+            memory_count: 0,
+            async_call: false,
         }
     }
 
@@ -703,6 +948,162 @@ impl<T> Store<T> {
         self.inner.engine()
     }
 
+    /// Installs the [`Debugger`] that pauses all Wasm executions of this [`Store`].
+    ///
+    /// Replaces and returns the previously installed [`Debugger`] if any.
+    pub fn set_debugger(&mut self, debugger: Debugger) -> Option<Debugger> {
+        self.debugger.replace(debugger)
+    }
+
+    /// Removes and returns the installed [`Debugger`] if any.
+    pub fn take_debugger(&mut self) -> Option<Debugger> {
+        self.debugger.take()
+    }
+
+    /// Returns an exclusive reference to the installed [`Debugger`] if any.
+    pub fn debugger_mut(&mut self) -> Option<&mut Debugger> {
+        self.debugger.as_mut()
+    }
+
+    /// Installs the [`Profiler`] that profiles all Wasm executions of this [`Store`].
+    ///
+    /// Replaces and returns the previously installed [`Profiler`] if any.
+    pub fn set_profiler(&mut self, profiler: Profiler) -> Option<Profiler> {
+        self.profiler.replace(profiler)
+    }
+
+    /// Removes and returns the installed [`Profiler`] if any.
+    pub fn take_profiler(&mut self) -> Option<Profiler> {
+        self.profiler.take()
+    }
+
+    /// Returns a shared reference to the installed [`Profiler`] if any.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Returns an exclusive reference to the installed [`Profiler`] if any.
+    pub fn profiler_mut(&mut self) -> Option<&mut Profiler> {
+        self.profiler.as_mut()
+    }
+
+    /// Installs the [`HostCallMode`] that records or replays all host function calls of this [`Store`].
+    ///
+    /// Replaces and returns the previously installed [`HostCallMode`] if any.
+    pub fn set_host_call_mode(&mut self, mode: HostCallMode) -> Option<HostCallMode> {
+        self.host_calls.replace(mode)
+    }
+
+    /// Removes and returns the installed [`HostCallMode`] if any.
+    pub fn take_host_call_mode(&mut self) -> Option<HostCallMode> {
+        self.host_calls.take()
+    }
+
+    /// Returns a shared reference to the installed [`HostCallMode`] if any.
+    pub fn host_call_mode(&self) -> Option<&HostCallMode> {
+        self.host_calls.as_ref()
+    }
+
+    /// Returns `true` if [`Func::call_async`] currently executes Wasm functions of the [`Store`].
+    ///
+    /// Asynchronous host functions suspend the execution only in this case.
+    pub(crate) fn is_async_call(&self) -> bool {
+        self.async_call
+    }
+
+    /// Sets whether [`Func::call_async`] currently executes Wasm functions of the [`Store`].
+    pub(crate) fn set_async_call(&mut self, async_call: bool) {
+        self.async_call = async_call;
+    }
+
+    /// Registers that `func` has been imported under `name`.
+    pub(crate) fn register_import_name(&mut self, func: &Func, name: &str) {
+        let fn_index = self.inner.unwrap_stored(func.as_inner()).into_usize();
+        self.import_names.insert(fn_index, name.to_string());
+    }
+
+    /// Returns the name under which the host function `func` is recorded.
+    ///
+    /// This is the name `func` was last imported under and
+    /// otherwise the name `func` was defined with.
+    pub(crate) fn host_call_name(&self, func: &Func, host_func: &HostFuncEntity) -> String {
+        let fn_index = self.inner.unwrap_stored(func.as_inner()).into_usize();
+        self.import_names
+            .get(&fn_index)
+            .unwrap_or(host_func.name())
+            .clone()
+    }
+
+    /// Installs the [`ResourceLimiter`] returned by `limiter` for this [`Store`].
+    ///
+    /// The closure is called with the host data of the [`Store`] whenever a linear memory
+    /// or table is created or grown and whenever a module is instantiated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use wasmi::{Engine, Store, StoreLimits, StoreLimitsBuilder};
+    /// let engine = Engine::default();
+    /// let limits = StoreLimitsBuilder::new().memory_size(1 << 20).build();
+    /// let mut store = Store::new(&engine, limits);
+    /// store.limiter(|limits| limits);
+    /// ```
+    pub fn limiter(
+        &mut self,
+        limiter: impl FnMut(&mut T) -> &mut (dyn ResourceLimiter) + Send + Sync + 'static,
+    ) {
+        self.limiter = Some(ResourceLimiterQuery::new(limiter));
+    }
+
+    /// Returns an exclusive reference to the [`StoreInner`] and the installed [`ResourceLimiter`] if any.
+    pub(crate) fn store_inner_and_resource_limiter_ref(
+        &mut self,
+    ) -> (&mut StoreInner, ResourceLimiterRef<'_>) {
+        let limiter = ResourceLimiterRef::new(&mut self.limiter, &mut self.data);
+        (&mut self.inner, limiter)
+    }
+
+    /// Checks if an instance with `tables` tables and `memories` linear memories
+    /// defined by its Wasm module may be created.
+    ///
+    /// # Errors
+    ///
+    /// If the installed [`ResourceLimiter`] does not allow for more instances,
+    /// tables or linear memories.
+    pub(crate) fn check_resource_counts(
+        &mut self,
+        tables: usize,
+        memories: usize,
+    ) -> Result<(), InstantiationError> {
+        let Some(query) = &mut self.limiter else {
+            return Ok(());
+        };
+        let limiter = query.query(&mut self.data);
+        if self.instance_count.saturating_add(1) > limiter.instances() {
+            return Err(InstantiationError::TooManyInstances);
+        }
+        if self.table_count.saturating_add(tables) > limiter.tables() {
+            return Err(InstantiationError::TooManyTables);
+        }
+        if self.memory_count.saturating_add(memories) > limiter.memories() {
+            return Err(InstantiationError::TooManyMemories);
+        }
+        Ok(())
+    }
+
+    /// Accounts for a successfully created instance with `tables` tables and
+    /// `memories` linear memories defined by its Wasm module.
+    ///
+    /// # Note
+    ///
+    /// The counts are tracked regardless of an installed [`ResourceLimiter`]
+    /// so that a [`ResourceLimiter`] installed later sees the correct counts.
+    pub(crate) fn bump_resource_counts(&mut self, tables: usize, memories: usize) {
+        self.instance_count = self.instance_count.saturating_add(1);
+        self.table_count = self.table_count.saturating_add(tables);
+        self.memory_count = self.memory_count.saturating_add(memories);
+    }
+
     /// Returns a shared reference to the user provided data owned by this [`Store`].
     pub fn data(&self) -> &T {
         &self.data
@@ -733,6 +1134,18 @@ impl<T> Store<T> {
         Ok(())
     }
 
+    /// Sets the [`ThreadParker`] used by `memory.atomic.wait` and `memory.atomic.notify`.
+    ///
+    /// # Note
+    ///
+    /// With the `std` crate feature the [`StdThreadParker`] is used by default.
+    /// Otherwise `memory.atomic.wait` traps if it has to block the executing thread.
+    ///
+    /// [`StdThreadParker`]: crate::StdThreadParker
+    pub fn set_thread_parker(&mut self, parker: Arc<dyn ThreadParker>) {
+        self.inner.thread_parker = Some(parker);
+    }
+
     /// Adds `delta` quantity of fuel to the remaining fuel.
     ///
     /// # Panics
@@ -776,6 +1189,75 @@ impl<T> Store<T> {
             .map_err(|_error| FuelError::out_of_fuel())
     }
 
+    /// Pushes a nested fuel frame that may consume at most `limit` fuel.
+    ///
+    /// The `limit` is reserved from the remaining fuel of the current fuel frame
+    /// and all executions charge their fuel against the new fuel frame until it
+    /// is popped via [`Store::pop_fuel_frame`]. Running out of fuel within the
+    /// new fuel frame never consumes the remaining fuel of its parent frames.
+    ///
+    /// # Errors
+    ///
+    /// - If fuel metering is disabled.
+    /// - If less than `limit` fuel is remaining in the current fuel frame.
+    pub fn push_fuel_frame(&mut self, limit: u64) -> Result<(), FuelError> {
+        self.check_fuel_metering_enabled()?;
+        self.inner
+            .fuel
+            .push_frame(limit)
+            .map_err(|_error| FuelError::out_of_fuel())
+    }
+
+    /// Pops the innermost fuel frame pushed via [`Store::push_fuel_frame`].
+    ///
+    /// The unused fuel of the popped fuel frame is refunded to its parent frame.
+    ///
+    /// # Errors
+    ///
+    /// - If fuel metering is disabled.
+    /// - If no fuel frame has been pushed.
+    pub fn pop_fuel_frame(&mut self) -> Result<FuelFrame, FuelError> {
+        self.check_fuel_metering_enabled()?;
+        self.inner
+            .fuel
+            .pop_frame()
+            .ok_or_else(FuelError::no_fuel_frame)
+    }
+
+    /// Returns the number of fuel frames pushed via [`Store::push_fuel_frame`].
+    pub fn fuel_frame_depth(&self) -> usize {
+        self.inner.fuel.frame_depth()
+    }
+
+    /// Refunds `delta` consumed fuel to the innermost fuel frame.
+    ///
+    /// At most the fuel consumed within the innermost fuel frame is refunded.
+    ///
+    /// Returns the amount of refunded fuel.
+    ///
+    /// # Errors
+    ///
+    /// If fuel metering is disabled.
+    pub fn refund_fuel(&mut self, delta: u64) -> Result<u64, FuelError> {
+        self.check_fuel_metering_enabled()?;
+        Ok(self.inner.fuel.refund_fuel(delta))
+    }
+
+    /// Interrupts Wasm executions of the [`Store`] once the epoch of its [`Engine`]
+    /// advanced by `ticks_beyond_current` from its current epoch.
+    ///
+    /// # Note
+    ///
+    /// - This requires [`Config::epoch_interruption`] to be enabled.
+    /// - A deadline of zero interrupts executions at their next loop back-edge or call.
+    /// - By default a [`Store`] has no deadline.
+    ///
+    /// [`Config::epoch_interruption`]: crate::Config::epoch_interruption
+    pub fn set_epoch_deadline(&mut self, ticks_beyond_current: u64) {
+        let current = self.engine().current_epoch();
+        self.inner.epoch_deadline = current.saturating_add(ticks_beyond_current);
+    }
+
     /// Allocates a new [`TrampolineEntity`] and returns a [`Trampoline`] reference to it.
     pub(super) fn alloc_trampoline(&mut self, func: TrampolineEntity<T>) -> Trampoline {
         let idx = self.trampolines.alloc(func);
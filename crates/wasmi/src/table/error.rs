@@ -38,6 +38,10 @@ pub enum TableError {
         /// The [`TableType`] which is supposed to be a supertype of `ty`.
         other: TableType,
     },
+    /// The [`ResourceLimiter`] denied the allocation of table elements.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    ResourceLimiterDeniedAllocation,
 }
 
 impl Display for TableError {
@@ -70,6 +74,9 @@ impl Display for TableError {
             Self::InvalidSubtype { ty, other } => {
                 write!(f, "table type {ty:?} is not a subtype of {other:?}",)
             }
+            Self::ResourceLimiterDeniedAllocation => {
+                write!(f, "a resource limiter denied to allocate table elements")
+            }
         }
     }
 }
@@ -3,6 +3,7 @@ pub use self::{
     error::TableError,
 };
 use super::{AsContext, AsContextMut, Stored};
+use crate::limits::ResourceLimiterRef;
 use crate::{module::FuncIdx, value::WithType, Func, FuncRef, Value};
 use alloc::vec::Vec;
 use core::cmp::max;
@@ -144,10 +145,26 @@ impl TableEntity {
     ///
     /// # Errors
     ///
-    /// If `init` does not match the [`TableType`] element type.
-    pub fn new(ty: TableType, init: Value) -> Result<Self, TableError> {
+    /// - If `init` does not match the [`TableType`] element type.
+    /// - If the [`ResourceLimiter`] denied the allocation of the initial elements.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    pub(crate) fn new(
+        ty: TableType,
+        init: Value,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<Self, TableError> {
         ty.matches_element_type(init.ty())?;
+        if let Some(limiter) = limiter.as_resource_limiter() {
+            if !limiter
+                .table_growing(0, ty.minimum(), ty.maximum())
+                .map_err(|_| TableError::ResourceLimiterDeniedAllocation)?
+            {
+                return Err(TableError::ResourceLimiterDeniedAllocation);
+            }
+        }
         let elements = vec![init.into(); ty.minimum() as usize];
+        Self::notify_grown(0, ty.minimum(), limiter);
         Ok(Self { ty, elements })
     }
 
@@ -183,9 +200,17 @@ impl TableEntity {
     ///
     /// - If the table is grown beyond its maximum limits.
     /// - If `value` does not match the [`Table`] element type.
-    pub fn grow(&mut self, delta: u32, init: Value) -> Result<u32, TableError> {
+    /// - If the [`ResourceLimiter`] denied the growth.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    pub(crate) fn grow(
+        &mut self,
+        delta: u32,
+        init: Value,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<u32, TableError> {
         self.ty().matches_element_type(init.ty())?;
-        self.grow_untyped(delta, init.into())
+        self.grow_untyped(delta, init.into(), limiter)
     }
 
     /// Grows the table by the given amount of elements.
@@ -200,8 +225,35 @@ impl TableEntity {
     ///
     /// # Errors
     ///
-    /// If the table is grown beyond its maximum limits.
-    pub fn grow_untyped(&mut self, delta: u32, init: UntypedValue) -> Result<u32, TableError> {
+    /// - If the table is grown beyond its maximum limits.
+    /// - If the [`ResourceLimiter`] denied the growth. In this case
+    ///   [`TableError::ResourceLimiterDeniedAllocation`] is returned
+    ///   if the [`ResourceLimiter`] returned an error and
+    ///   [`TableError::GrowOutOfBounds`] otherwise.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    pub(crate) fn grow_untyped(
+        &mut self,
+        delta: u32,
+        init: UntypedValue,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<u32, TableError> {
+        let result = self.grow_limited(delta, init, limiter);
+        if let (Err(error), Some(limiter)) = (&result, limiter.as_resource_limiter()) {
+            limiter.table_grow_failed(error);
+        }
+        result
+    }
+
+    /// Grows the table by `delta` elements if the [`ResourceLimiter`] allows it.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn grow_limited(
+        &mut self,
+        delta: u32,
+        init: UntypedValue,
+        limiter: &mut ResourceLimiterRef<'_>,
+    ) -> Result<u32, TableError> {
         let maximum = self.ty.maximum().unwrap_or(u32::MAX);
         let current = self.size();
         let new_len = current
@@ -211,11 +263,38 @@ impl TableEntity {
                 maximum,
                 current,
                 delta,
-            })? as usize;
-        self.elements.resize(new_len, init);
+            })?;
+        if delta != 0 {
+            if let Some(limiter) = limiter.as_resource_limiter() {
+                if !limiter
+                    .table_growing(current, new_len, self.ty.maximum())
+                    .map_err(|_| TableError::ResourceLimiterDeniedAllocation)?
+                {
+                    return Err(TableError::GrowOutOfBounds {
+                        maximum,
+                        current,
+                        delta,
+                    });
+                }
+            }
+        }
+        self.elements.resize(new_len as usize, init);
+        if delta != 0 {
+            Self::notify_grown(current, new_len, limiter);
+        }
         Ok(current)
     }
 
+    /// Informs the [`ResourceLimiter`] that a table successfully grew
+    /// from `current` to `desired` elements.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    fn notify_grown(current: u32, desired: u32, limiter: &mut ResourceLimiterRef<'_>) {
+        if let Some(limiter) = limiter.as_resource_limiter() {
+            limiter.table_grown(current, desired);
+        }
+    }
+
     /// Converts the internal [`UntypedValue`] into a [`Value`] for this [`Table`] element type.
     fn make_typed(&self, untyped: UntypedValue) -> Value {
         untyped.with_type(self.ty().element())
@@ -471,10 +550,18 @@ impl Table {
     ///
     /// # Errors
     ///
-    /// If `init` does not match the [`TableType`] element type.
+    /// - If `init` does not match the [`TableType`] element type.
+    /// - If the [`ResourceLimiter`] of the [`Store`] denied the allocation.
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
     pub fn new(mut ctx: impl AsContextMut, ty: TableType, init: Value) -> Result<Self, TableError> {
-        let entity = TableEntity::new(ty, init)?;
-        let table = ctx.as_context_mut().store.inner.alloc_table(entity);
+        let (inner, mut limiter) = ctx
+            .as_context_mut()
+            .store
+            .store_inner_and_resource_limiter_ref();
+        let entity = TableEntity::new(ty, init, &mut limiter)?;
+        let table = inner.alloc_table(entity);
         Ok(table)
     }
 
@@ -526,21 +613,27 @@ impl Table {
     ///
     /// - If the table is grown beyond its maximum limits.
     /// - If `value` does not match the [`Table`] element type.
+    /// - If the [`ResourceLimiter`] of the [`Store`] denied the growth.
     ///
     /// # Panics
     ///
     /// Panics if `ctx` does not own this [`Table`].
+    ///
+    /// [`ResourceLimiter`]: crate::ResourceLimiter
+    /// [`Store`]: crate::Store
     pub fn grow(
         &self,
         mut ctx: impl AsContextMut,
         delta: u32,
         init: Value,
     ) -> Result<u32, TableError> {
-        ctx.as_context_mut()
+        let (inner, mut limiter) = ctx
+            .as_context_mut()
             .store
-            .inner
+            .store_inner_and_resource_limiter_ref();
+        inner
             .resolve_table_mut(self)
-            .grow(delta, init)
+            .grow(delta, init, &mut limiter)
     }
 
     /// Returns the [`Table`] element value at `index`.
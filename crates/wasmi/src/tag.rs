@@ -0,0 +1,217 @@
+use super::{AsContext, AsContextMut, StoreInner, Stored};
+use crate::{
+    core::ValueType,
+    engine::{stack::UntypedException, DedupFuncType},
+    value::WithType,
+    FuncType,
+    Value,
+};
+use alloc::boxed::Box;
+use core::{fmt, fmt::Display};
+use wasmi_arena::ArenaIndex;
+use wasmi_core::{HostError, UntypedValue};
+
+/// A raw index to a tag entity.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TagIdx(u32);
+
+impl ArenaIndex for TagIdx {
+    fn into_usize(self) -> usize {
+        self.0 as usize
+    }
+
+    fn from_usize(value: usize) -> Self {
+        let value = value.try_into().unwrap_or_else(|error| {
+            panic!("index {value} is out of bounds as tag index: {error}")
+        });
+        Self(value)
+    }
+}
+
+/// The type of a Wasm exception tag.
+///
+/// # Note
+///
+/// A tag type is described by a function type without results
+/// where the parameters are the values carried by the exception.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagType {
+    /// The underlying function type of the tag.
+    func_type: FuncType,
+}
+
+impl TagType {
+    /// Creates a new [`TagType`] from the given exception `params`.
+    pub fn new<P>(params: P) -> Self
+    where
+        P: IntoIterator<Item = ValueType>,
+    {
+        Self {
+            func_type: FuncType::new(params, []),
+        }
+    }
+
+    /// Creates a new [`TagType`] from the given [`FuncType`].
+    ///
+    /// # Panics
+    ///
+    /// If `func_type` has results.
+    pub(crate) fn from_func_type(func_type: FuncType) -> Self {
+        assert!(
+            func_type.results().is_empty(),
+            "tag types must not have results but found: {func_type:?}"
+        );
+        Self { func_type }
+    }
+
+    /// Returns the value types carried by exceptions of the [`TagType`].
+    pub fn params(&self) -> &[ValueType] {
+        self.func_type.params()
+    }
+
+    /// Returns the underlying [`FuncType`] of the [`TagType`].
+    pub fn func_type(&self) -> &FuncType {
+        &self.func_type
+    }
+}
+
+/// A Wasm exception tag entity.
+#[derive(Debug)]
+pub struct TagEntity {
+    /// The deduplicated function type of the tag.
+    ty: DedupFuncType,
+}
+
+impl TagEntity {
+    /// Creates a new [`TagEntity`] with the given deduplicated function type.
+    pub fn new(ty: DedupFuncType) -> Self {
+        Self { ty }
+    }
+
+    /// Returns the [`DedupFuncType`] of the tag.
+    pub fn ty_dedup(&self) -> &DedupFuncType {
+        &self.ty
+    }
+}
+
+/// A Wasm exception tag reference.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Tag(Stored<TagIdx>);
+
+impl Tag {
+    /// Creates a new stored tag reference.
+    ///
+    /// # Note
+    ///
+    /// This API is primarily used by the [`Store`] itself.
+    ///
+    /// [`Store`]: [`crate::Store`]
+    pub(super) fn from_inner(stored: Stored<TagIdx>) -> Self {
+        Self(stored)
+    }
+
+    /// Returns the underlying stored representation.
+    pub(super) fn as_inner(&self) -> &Stored<TagIdx> {
+        &self.0
+    }
+
+    /// Creates a new tag with the given [`TagType`] to the store.
+    pub fn new(mut ctx: impl AsContextMut, ty: TagType) -> Self {
+        let ctx = ctx.as_context_mut();
+        let dedup = ctx.store.engine().alloc_func_type(ty.func_type);
+        ctx.store.inner.alloc_tag(TagEntity::new(dedup))
+    }
+
+    /// Returns the [`TagType`] of the tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ctx` does not own this [`Tag`].
+    pub fn ty(&self, ctx: impl AsContext) -> TagType {
+        let store = &ctx.as_context().store.inner;
+        let dedup = store.resolve_tag(self).ty_dedup();
+        TagType::from_func_type(store.resolve_func_type(dedup))
+    }
+}
+
+/// A Wasm exception thrown by either Wasm or host code.
+///
+/// # Note
+///
+/// - Host functions may throw an [`Exception`] by returning it as a [`Trap`].
+///   The exception then unwinds the Wasm call stack until a matching
+///   `catch` or `catch_all` clause is found.
+/// - Exceptions that are not caught by any Wasm handler are returned to
+///   the host as a [`Trap`] from which the [`Exception`] can be extracted
+///   via [`Trap::downcast_ref`].
+///
+/// [`Trap`]: crate::core::Trap
+/// [`Trap::downcast_ref`]: crate::core::Trap::downcast_ref
+#[derive(Debug, Clone)]
+pub struct Exception {
+    /// The tag of the exception.
+    tag: Tag,
+    /// The values carried by the exception.
+    values: Box<[Value]>,
+}
+
+impl Exception {
+    /// Creates a new [`Exception`] for the `tag` carrying the given `values`.
+    pub fn new<V>(tag: Tag, values: V) -> Self
+    where
+        V: IntoIterator<Item = Value>,
+    {
+        Self {
+            tag,
+            values: values.into_iter().collect(),
+        }
+    }
+
+    /// Returns the [`Tag`] of the [`Exception`].
+    pub fn tag(&self) -> Tag {
+        self.tag
+    }
+
+    /// Returns the values carried by the [`Exception`].
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    /// Converts the [`Exception`] into its untyped representation used by the executor.
+    pub(crate) fn into_untyped(self) -> UntypedException {
+        let values = self
+            .values
+            .iter()
+            .cloned()
+            .map(UntypedValue::from)
+            .collect();
+        UntypedException::new(self.tag, values)
+    }
+
+    /// Creates an [`Exception`] from its untyped representation used by the executor.
+    ///
+    /// # Panics
+    ///
+    /// If the [`Tag`] of `exception` does not originate from `store`.
+    pub(crate) fn from_untyped(store: &StoreInner, exception: &UntypedException) -> Self {
+        let tag = exception.tag();
+        let func_type = store.resolve_tag(&tag).ty_dedup();
+        store.resolve_func_type_with(func_type, |func_type| {
+            let values = exception
+                .values()
+                .iter()
+                .zip(func_type.params())
+                .map(|(value, value_type)| value.with_type(*value_type));
+            Self::new(tag, values)
+        })
+    }
+}
+
+impl Display for Exception {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "uncaught wasm exception with values {:?}", self.values)
+    }
+}
+
+impl HostError for Exception {}
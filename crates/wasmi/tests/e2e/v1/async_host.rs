@@ -0,0 +1,218 @@
+//! Tests for asynchronous host functions and [`Func::call_async`].
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::Arc,
+    task::Wake,
+    thread::{self, Thread},
+};
+use wasmi::{core::Trap, Caller, Engine, Extern, Func, Instance, Linker, Module, Store, Value};
+
+/// Wakes up the blocked thread of [`block_on`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the `future` to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// A future that is pending exactly once before it resolves to its value.
+///
+/// This simulates a host function awaiting I/O.
+struct YieldOnce<T> {
+    value: Option<T>,
+    yielded: bool,
+}
+
+impl<T> YieldOnce<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value: Some(value),
+            yielded: false,
+        }
+    }
+}
+
+impl<T: Unpin> Future for YieldOnce<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        if !self.yielded {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        Poll::Ready(self.value.take().unwrap())
+    }
+}
+
+const WAT: &str = r#"
+    (module
+        (import "env" "fetch" (func $fetch (param i32) (result i64)))
+        (func (export "sum") (param i32) (result i64)
+            (i64.add
+                (call $fetch (local.get 0))
+                (call $fetch (i32.add (local.get 0) (i32.const 1)))
+            )
+        )
+    )
+"#;
+
+/// Instantiates [`WAT`] with an asynchronous `fetch` host function.
+///
+/// The `fetch` host function returns its parameter added to the host state
+/// and traps for negative parameters.
+fn setup() -> (Store<i64>, Instance) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, 100_i64);
+    let mut linker = <Linker<i64>>::new(&engine);
+    linker
+        .func_wrap_async("env", "fetch", |caller: Caller<i64>, (key,): (i32,)| {
+            Box::new(async move {
+                let value = YieldOnce::new(i64::from(key)).await;
+                if value < 0 {
+                    return Err(Trap::new("invalid key"));
+                }
+                // The `Caller` is still accessible after the future has been resumed.
+                Ok(value + *caller.data())
+            })
+        })
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+/// Returns the exported `sum` function of the `instance`.
+fn sum(store: &Store<i64>, instance: Instance) -> Func {
+    instance
+        .get_export(store, "sum")
+        .and_then(Extern::into_func)
+        .unwrap()
+}
+
+#[test]
+fn call_async_resumes_after_host_futures() {
+    let (mut store, instance) = setup();
+    let sum = sum(&store, instance);
+    let mut outputs = [Value::I64(0)];
+    block_on(sum.call_async(&mut store, &[Value::I32(1)], &mut outputs)).unwrap();
+    assert_eq!(outputs[0].i64(), Some(203));
+    // The host state can be changed in between calls.
+    *store.data_mut() = 0;
+    block_on(sum.call_async(&mut store, &[Value::I32(20)], &mut outputs)).unwrap();
+    assert_eq!(outputs[0].i64(), Some(41));
+}
+
+#[test]
+fn call_async_propagates_host_traps() {
+    let (mut store, instance) = setup();
+    let sum = sum(&store, instance);
+    let mut outputs = [Value::I64(0)];
+    let error = block_on(sum.call_async(&mut store, &[Value::I32(-1)], &mut outputs)).unwrap_err();
+    assert_eq!(error.to_string(), "invalid key");
+}
+
+#[test]
+fn call_async_calls_async_host_func_as_root() {
+    let (mut store, _) = setup();
+    let double = Func::wrap_async(&mut store, |_caller: Caller<i64>, (value,): (i32,)| {
+        Box::new(YieldOnce::new(value * 2))
+    });
+    let mut outputs = [Value::I32(0)];
+    block_on(double.call_async(&mut store, &[Value::I32(21)], &mut outputs)).unwrap();
+    assert_eq!(outputs[0].i32(), Some(42));
+}
+
+#[test]
+fn sync_call_of_async_host_func_fails() {
+    let (mut store, instance) = setup();
+    let sum = sum(&store, instance);
+    let mut outputs = [Value::I64(0)];
+    let error = sum
+        .call(&mut store, &[Value::I32(1)], &mut outputs)
+        .unwrap_err();
+    assert!(error.to_string().contains("Func::call_async"));
+}
+
+/// Asserts that a ready asynchronous host function can be called synchronously.
+///
+/// This fails if the `store` is still marked as executing [`Func::call_async`].
+fn assert_sync_call_of_ready_async_host_func_works(store: &mut Store<i64>) {
+    let double = Func::wrap_async(&mut *store, |_caller: Caller<i64>, (value,): (i32,)| {
+        Box::new(async move { value * 2 })
+    });
+    let mut outputs = [Value::I32(0)];
+    double.call(store, &[Value::I32(21)], &mut outputs).unwrap();
+    assert_eq!(outputs[0].i32(), Some(42));
+}
+
+#[test]
+fn dropped_call_async_resets_async_call() {
+    let (mut store, instance) = setup();
+    let sum = sum(&store, instance);
+    let mut outputs = [Value::I64(0)];
+    {
+        let mut future = Box::pin(sum.call_async(&mut store, &[Value::I32(1)], &mut outputs));
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        // The first `fetch` yields once so the future is dropped before it completes.
+        assert!(future.as_mut().poll(&mut cx).is_pending());
+    }
+    assert_sync_call_of_ready_async_host_func_works(&mut store);
+}
+
+#[test]
+fn unwinding_call_async_resets_async_call() {
+    let (mut store, _) = setup();
+    let panics = Func::wrap(&mut store, |_caller: Caller<i64>| -> i64 {
+        panic!("host function panicked")
+    });
+    let mut outputs = [Value::I64(0)];
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        block_on(panics.call_async(&mut store, &[], &mut outputs))
+    }));
+    assert!(result.is_err());
+    assert_sync_call_of_ready_async_host_func_works(&mut store);
+}
+
+#[test]
+fn sync_call_of_ready_async_host_func_works() {
+    let (mut store, _) = setup();
+    let double = Func::wrap_async(&mut store, |mut caller: Caller<i64>, (value,): (i32,)| {
+        Box::new(async move {
+            *caller.data_mut() += 1;
+            value * 2
+        })
+    });
+    let mut outputs = [Value::I32(0)];
+    double
+        .call(&mut store, &[Value::I32(21)], &mut outputs)
+        .unwrap();
+    assert_eq!(outputs[0].i32(), Some(42));
+    assert_eq!(*store.data(), 101);
+}
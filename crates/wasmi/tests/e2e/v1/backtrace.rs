@@ -0,0 +1,145 @@
+//! Tests for Wasm backtraces attached to traps.
+
+use wasmi::{core::Trap, Caller, Config, Engine, Exception, Extern, Func, Linker, Module, Store};
+
+/// Module with a chain of calls that ends in a trap.
+const WAT: &str = r#"
+    (module
+        (import "env" "fail" (func $fail))
+        (func $inner (param i32) (result i32)
+            (if (local.get 0)
+                (then (call $fail))
+            )
+            (unreachable)
+        )
+        (func $middle (param i32) (result i32)
+            (i32.add (call $inner (local.get 0)) (i32.const 1))
+        )
+        (func $outer (export "run") (param i32) (result i32)
+            (call $middle (local.get 0))
+        )
+        (func (export "div") (param i32) (result i32)
+            (i32.div_u (i32.const 1) (local.get 0))
+        )
+    )
+"#;
+
+/// Calls the exported function `name` with `input` and returns the resulting [`Trap`].
+fn run(wasm: &[u8], name: &str, input: i32) -> Trap {
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm).unwrap();
+    let mut store = Store::new(&engine, ());
+    let fail = Func::wrap(&mut store, |_caller: Caller<()>| -> Result<(), Trap> {
+        Err(Trap::new("host failure"))
+    });
+    let mut linker = <Linker<()>>::new(&engine);
+    linker.define("env", "fail", fail).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    instance
+        .get_export(&store, name)
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, input)
+        .unwrap_err()
+}
+
+#[test]
+fn backtrace_frames_are_innermost_first() {
+    let wasm = wat::parse_str(WAT).unwrap();
+    let trap = run(&wasm, "run", 0);
+    let backtrace = trap.backtrace().unwrap();
+    let frames = backtrace.frames();
+    let indices: Vec<u32> = frames.iter().map(|frame| frame.func_index()).collect();
+    assert_eq!(indices, [1, 2, 3]);
+    let names: Vec<Option<&str>> = frames.iter().map(|frame| frame.func_name()).collect();
+    assert_eq!(names, [Some("inner"), Some("middle"), Some("outer")]);
+    // The innermost frame points to the `unreachable` and all callers to their `call`.
+    assert_eq!(wasm[frames[0].source_pc() as usize], 0x00);
+    assert_eq!(wasm[frames[1].source_pc() as usize], 0x10);
+    assert_eq!(wasm[frames[2].source_pc() as usize], 0x10);
+    assert!(frames[0].source_pc() < frames[1].source_pc());
+    assert!(frames[1].source_pc() < frames[2].source_pc());
+}
+
+#[test]
+fn backtrace_points_to_trapping_operator() {
+    let wasm = wat::parse_str(WAT).unwrap();
+    let trap = run(&wasm, "div", 0);
+    let frames = trap.backtrace().unwrap().frames();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].func_index(), 4);
+    assert_eq!(frames[0].func_name(), None);
+    // The `i32.div_u` opcode.
+    assert_eq!(wasm[frames[0].source_pc() as usize], 0x6E);
+}
+
+#[test]
+fn backtrace_is_displayed() {
+    let wasm = wat::parse_str(WAT).unwrap();
+    let trap = run(&wasm, "div", 0);
+    let backtrace = trap.backtrace().unwrap().to_string();
+    assert!(backtrace.starts_with("   0: 0x"));
+    assert!(backtrace.trim_end().ends_with("<wasm function 4>"));
+    // The trap message itself is not affected by the backtrace.
+    assert_eq!(trap.to_string(), "integer divide by zero");
+}
+
+#[test]
+fn host_traps_have_backtrace() {
+    let wasm = wat::parse_str(WAT).unwrap();
+    let trap = run(&wasm, "run", 1);
+    assert_eq!(trap.to_string(), "host failure");
+    let frames = trap.backtrace().unwrap().frames();
+    let indices: Vec<u32> = frames.iter().map(|frame| frame.func_index()).collect();
+    assert_eq!(indices, [1, 2, 3]);
+    // All frames are callers pointing to their `call` including the caller of the host function.
+    for frame in frames {
+        assert_eq!(wasm[frame.source_pc() as usize], 0x10);
+    }
+}
+
+#[test]
+fn uncaught_exceptions_have_backtrace() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (tag $e (param i32))
+            (func $throw (param i32) (result i32)
+                (throw $e (local.get 0))
+            )
+            (func (export "run") (param i32) (result i32)
+                (call $throw (local.get 0))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let mut config = Config::default();
+    config.wasm_exceptions(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let trap = instance
+        .get_typed_func::<i32, i32>(&store, "run")
+        .unwrap()
+        .call(&mut store, 7)
+        .unwrap_err();
+    assert!(trap.downcast_ref::<Exception>().is_some());
+    let frames = trap.backtrace().unwrap().frames();
+    let indices: Vec<u32> = frames.iter().map(|frame| frame.func_index()).collect();
+    assert_eq!(indices, [0, 1]);
+    // The `throw` and `call` opcodes.
+    assert_eq!(wasm[frames[0].source_pc() as usize], 0x08);
+    assert_eq!(wasm[frames[1].source_pc() as usize], 0x10);
+}
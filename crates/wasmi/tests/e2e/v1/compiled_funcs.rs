@@ -0,0 +1,65 @@
+//! Tests for inspecting the compiled `wasmi` bytecode of a [`Module`].
+
+use wasmi::{core::ValueType, DataSegmentKind, Engine, Instruction, Module};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "log" (func $log (param i32)))
+        (memory 1)
+        (data (i32.const 8) "wasmi")
+        (func $run (param i32) (result i32)
+            (local i64)
+            (call $log (local.get 0))
+            (i32.add (local.get 0) (i32.const 1))
+        )
+    )
+"#;
+
+#[test]
+fn compiled_funcs_are_inspected() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &mut &wasm[..]).unwrap();
+    let funcs = module.compiled_funcs().collect::<Vec<_>>();
+    assert_eq!(funcs.len(), 1);
+    let (func_index, func_type, func_body) = &funcs[0];
+    // The imported `$log` occupies the function index 0.
+    assert_eq!(*func_index, 1);
+    assert_eq!(func_type.params(), [ValueType::I32]);
+    assert_eq!(func_type.results(), [ValueType::I32]);
+
+    let header = engine.func_header(*func_body);
+    assert_eq!(header.len_params(), 1);
+    assert_eq!(header.len_locals(), 1);
+    assert!(header.max_stack_height() >= 2);
+
+    let instrs = engine.instr_vec(*func_body);
+    let metas = engine.instr_metas(*func_body);
+    assert_eq!(instrs.len(), metas.len());
+    let call = instrs
+        .iter()
+        .position(|instr| matches!(instr, Instruction::Call(_)))
+        .unwrap();
+    // The opcode of the Wasm `call` operator.
+    assert_eq!(metas[call].opcode(), 0x10);
+    assert!(metas
+        .windows(2)
+        .all(|pair| pair[0].source_pc() < pair[1].source_pc()));
+}
+
+#[test]
+fn data_segments_are_inspected() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &mut &wasm[..]).unwrap();
+    let segments = module.data_segments().collect::<Vec<_>>();
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].bytes(), b"wasmi");
+    match segments[0].kind() {
+        DataSegmentKind::Active(segment) => {
+            assert_eq!(segment.memory_index().into_u32(), 0);
+            assert_eq!(segment.offset().eval_const().map(u32::from), Some(8));
+        }
+        DataSegmentKind::Passive => panic!("expected an active data segment"),
+    }
+}
@@ -0,0 +1,200 @@
+//! Tests for the interactive step [`Debugger`].
+
+use std::sync::{Arc, Mutex};
+use wasmi::{
+    core::UntypedValue,
+    Breakpoint,
+    DebugAction,
+    DebugContext,
+    Debugger,
+    Engine,
+    Extern,
+    Linker,
+    Module,
+    PauseReason,
+    Store,
+    Value,
+};
+
+const WAT: &str = r#"
+    (module
+        (memory 1)
+        (data (i32.const 0) "\2a")
+        (global $g (mut i32) (i32.const 7))
+        (func $add (param $a i32) (param $b i32) (result i32) (local $t i32)
+            (local.set $t (i32.add (local.get $a) (local.get $b)))
+            (global.set $g (local.get $t))
+            (local.get $t)
+        )
+        (func (export "run") (param i32) (result i32)
+            (call $add (local.get 0) (i32.const 5))
+        )
+    )
+"#;
+
+/// A snapshot of the paused state as seen by the pause handler.
+#[derive(Debug, Clone)]
+struct Pause {
+    reason: PauseReason,
+    func_index: u32,
+    source_pc: u32,
+    call_depth: usize,
+    locals: Vec<i32>,
+    operands: Vec<i32>,
+    global: Option<i32>,
+    memory: Option<u8>,
+}
+
+impl Pause {
+    fn new(ctx: &DebugContext) -> Self {
+        let to_i32 = |values: &[UntypedValue]| {
+            values
+                .iter()
+                .map(|value| i32::from(*value))
+                .collect::<Vec<_>>()
+        };
+        Self {
+            reason: ctx.reason(),
+            func_index: ctx.func_index(),
+            source_pc: ctx.source_pc(),
+            call_depth: ctx.call_depth(),
+            locals: to_i32(ctx.locals()),
+            operands: to_i32(ctx.operands()),
+            global: ctx.global(0).and_then(|value| value.i32()),
+            memory: ctx.read_memory(0, 0, 1).map(|bytes| bytes[0]),
+        }
+    }
+}
+
+/// Runs `run(input)` with a [`Debugger`] using `breakpoints` and the `action` after every pause.
+fn debug(input: i32, breakpoints: &[Breakpoint], action: DebugAction) -> (i32, Vec<Pause>) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let pauses = Arc::new(Mutex::new(Vec::new()));
+    let mut debugger = Debugger::new({
+        let pauses = pauses.clone();
+        move |ctx| {
+            pauses.lock().unwrap().push(Pause::new(ctx));
+            action
+        }
+    });
+    for breakpoint in breakpoints {
+        debugger.add_breakpoint(*breakpoint);
+    }
+    store.set_debugger(debugger);
+    let result = instance
+        .get_export(&store, "run")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, input)
+        .unwrap();
+    let pauses = pauses.lock().unwrap().clone();
+    (result, pauses)
+}
+
+#[test]
+fn func_breakpoint_pauses_at_entry() {
+    let (result, pauses) = debug(10, &[Breakpoint::Func(0)], DebugAction::Continue);
+    assert_eq!(result, 15);
+    assert_eq!(pauses.len(), 1);
+    let pause = &pauses[0];
+    assert_eq!(pause.reason, PauseReason::Breakpoint(Breakpoint::Func(0)));
+    assert_eq!(pause.func_index, 0);
+    assert_eq!(pause.call_depth, 1);
+    assert_eq!(pause.locals, [10, 5, 0]);
+    assert!(pause.operands.is_empty());
+    assert_eq!(pause.global, Some(7));
+    assert_eq!(pause.memory, Some(42));
+}
+
+#[test]
+fn stepping_pauses_at_every_instruction() {
+    let (result, pauses) = debug(10, &[Breakpoint::Func(0)], DebugAction::Step);
+    assert_eq!(result, 15);
+    assert!(pauses.len() > 3);
+    assert_eq!(pauses[1].reason, PauseReason::Step);
+    // Operands are pushed and popped while stepping through `$add`.
+    assert!(pauses
+        .iter()
+        .any(|pause| pause.func_index == 0 && pause.operands == [10, 5]));
+    // The local variable and global variable are updated once the sum has been computed.
+    let last_in_add = pauses
+        .iter()
+        .rev()
+        .find(|pause| pause.func_index == 0)
+        .unwrap();
+    assert_eq!(last_in_add.locals, [10, 5, 15]);
+    assert_eq!(last_in_add.global, Some(15));
+    // Stepping continues in the caller after returning.
+    let last = pauses.last().unwrap();
+    assert_eq!(last.func_index, 1);
+    assert_eq!(last.call_depth, 0);
+    assert_eq!(last.locals, [10]);
+}
+
+#[test]
+fn instr_breakpoint_pauses_at_source_pc() {
+    let (_, steps) = debug(1, &[Breakpoint::Func(0)], DebugAction::Step);
+    let target = steps[2].clone();
+    let breakpoint = Breakpoint::Instr {
+        func_index: 0,
+        source_pc: target.source_pc,
+    };
+    let (result, pauses) = debug(1, &[breakpoint], DebugAction::Continue);
+    assert_eq!(result, 6);
+    assert_eq!(pauses.len(), 1);
+    assert_eq!(pauses[0].reason, PauseReason::Breakpoint(breakpoint));
+    assert_eq!(pauses[0].source_pc, target.source_pc);
+    assert_eq!(pauses[0].operands, target.operands);
+    // Breakpoints only match the function they have been set for.
+    let breakpoint = Breakpoint::Instr {
+        func_index: 1,
+        source_pc: target.source_pc,
+    };
+    let (_, pauses) = debug(1, &[breakpoint], DebugAction::Continue);
+    assert!(pauses.is_empty());
+}
+
+#[test]
+fn handler_can_manage_breakpoints() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let hits = Arc::new(Mutex::new(0));
+    let mut debugger = Debugger::new({
+        let hits = hits.clone();
+        move |ctx| {
+            *hits.lock().unwrap() += 1;
+            assert!(ctx.remove_breakpoint(Breakpoint::Func(0)));
+            DebugAction::Continue
+        }
+    });
+    debugger.add_breakpoint(Breakpoint::Func(0));
+    store.set_debugger(debugger);
+    let run = instance
+        .get_export(&store, "run")
+        .and_then(Extern::into_func)
+        .unwrap();
+    let mut results = [Value::I32(0)];
+    run.call(&mut store, &[Value::I32(1)], &mut results).unwrap();
+    run.call(&mut store, &[Value::I32(2)], &mut results).unwrap();
+    assert_eq!(results[0].i32(), Some(7));
+    assert_eq!(*hits.lock().unwrap(), 1);
+    assert_eq!(store.debugger_mut().unwrap().breakpoints().count(), 0);
+    assert!(store.take_debugger().is_some());
+}
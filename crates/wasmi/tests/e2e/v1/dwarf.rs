@@ -0,0 +1,232 @@
+//! Tests for the DWARF based source location mapping.
+
+use gimli::{
+    write::{
+        Address,
+        AttributeValue,
+        DwarfUnit,
+        EndianVec,
+        LineProgram,
+        LineString,
+        Sections,
+    },
+    Encoding,
+    Format,
+    LineEncoding,
+    LittleEndian,
+};
+use wasmi::{core::Trap, Engine, Extern, Linker, Module, Store};
+
+/// Module whose functions are mapped to `src/lib.rs` by [`with_dwarf`].
+const WAT: &str = r#"
+    (module
+        (func $inner (param i32) (result i32)
+            (drop (local.get 0))
+            (unreachable)
+        )
+        (func (export "run") (param i32) (result i32)
+            (call $inner (local.get 0))
+        )
+    )
+"#;
+
+/// Reads an unsigned LEB128 encoded `u32` at `pos` and advances `pos` past it.
+fn read_u32(wasm: &[u8], pos: &mut usize) -> u32 {
+    let mut result = 0;
+    let mut shift = 0;
+    loop {
+        let byte = wasm[*pos];
+        *pos += 1;
+        result |= u32::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return result;
+        }
+        shift += 7;
+    }
+}
+
+/// Returns the offset of the code section payload and the code section relative
+/// address range of every function body of `wasm`.
+fn code_layout(wasm: &[u8]) -> (usize, Vec<(u64, u64)>) {
+    let mut pos = 8;
+    loop {
+        let id = wasm[pos];
+        pos += 1;
+        let size = read_u32(wasm, &mut pos) as usize;
+        if id != 10 {
+            pos += size;
+            continue;
+        }
+        let code_offset = pos;
+        let len_bodies = read_u32(wasm, &mut pos);
+        let bodies = (0..len_bodies)
+            .map(|_| {
+                let size = read_u32(wasm, &mut pos) as usize;
+                let start = pos - code_offset;
+                pos += size;
+                (start as u64, (start + size) as u64)
+            })
+            .collect();
+        return (code_offset, bodies);
+    }
+}
+
+/// Appends a custom section with `name` and `data` to `wasm`.
+fn push_custom_section(wasm: &mut Vec<u8>, name: &str, data: &[u8]) {
+    let mut payload = Vec::new();
+    leb128_u32(&mut payload, name.len() as u32);
+    payload.extend_from_slice(name.as_bytes());
+    payload.extend_from_slice(data);
+    wasm.push(0x00);
+    leb128_u32(wasm, payload.len() as u32);
+    wasm.extend_from_slice(&payload);
+}
+
+fn leb128_u32(buffer: &mut Vec<u8>, mut value: u32) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            buffer.push(byte);
+            return;
+        }
+        buffer.push(byte | 0x80);
+    }
+}
+
+/// Returns `wasm` with DWARF sections that map its first function to line 3
+/// and its second function to line 8 of `src/lib.rs`.
+fn with_dwarf(mut wasm: Vec<u8>) -> Vec<u8> {
+    let (_, bodies) = code_layout(&wasm);
+    let encoding = Encoding {
+        format: Format::Dwarf32,
+        version: 4,
+        address_size: 4,
+    };
+    let mut dwarf = DwarfUnit::new(encoding);
+    let mut program = LineProgram::new(
+        encoding,
+        LineEncoding::default(),
+        LineString::String(b"/work".to_vec()),
+        LineString::String(b"lib.rs".to_vec()),
+        None,
+    );
+    let dir = program.add_directory(LineString::String(b"src".to_vec()));
+    let file = program.add_file(LineString::String(b"lib.rs".to_vec()), dir, None);
+    for (&(start, end), (line, column)) in bodies.iter().zip([(3, 5), (8, 9)]) {
+        program.begin_sequence(Some(Address::Constant(start)));
+        let row = program.row();
+        row.file = file;
+        row.line = line;
+        row.column = column;
+        program.generate_row();
+        program.end_sequence(end - start);
+    }
+    dwarf.unit.line_program = program;
+    let root = dwarf.unit.root();
+    for (&(start, end), name) in bodies.iter().zip(["inner", "outer"]) {
+        let id = dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let entry = dwarf.unit.get_mut(id);
+        entry.set(gimli::DW_AT_name, AttributeValue::String(name.into()));
+        entry.set(
+            gimli::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(start)),
+        );
+        entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(end - start));
+    }
+    let mut sections = Sections::new(EndianVec::new(LittleEndian));
+    dwarf.write(&mut sections).unwrap();
+    sections
+        .for_each(|id, data| {
+            if !data.slice().is_empty() {
+                push_custom_section(&mut wasm, id.name(), data.slice());
+            }
+            Ok::<_, gimli::Error>(())
+        })
+        .unwrap();
+    wasm
+}
+
+/// Instantiates `module` and calls its `run` export.
+fn run(module: &Module) -> (Store<()>, Trap) {
+    let mut store = Store::new(module.engine(), ());
+    let instance = <Linker<()>>::new(module.engine())
+        .instantiate(&mut store, module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let trap = instance
+        .get_export(&store, "run")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 0)
+        .unwrap_err();
+    (store, trap)
+}
+
+fn module(engine: &Engine, wasm: &[u8]) -> Module {
+    Module::new(engine, wasm).unwrap()
+}
+
+#[test]
+fn backtrace_frames_resolve_to_source_locations() {
+    let wasm = with_dwarf(wat::parse_str(WAT).unwrap());
+    let module = module(&Engine::default(), &wasm);
+    let debug_info = module.debug_info().unwrap();
+    let (_store, trap) = run(&module);
+    let frames = trap.backtrace().unwrap().frames();
+    let inner = debug_info.lookup(frames[0].source_pc()).unwrap();
+    assert_eq!(inner.file(), Some("src/lib.rs"));
+    assert_eq!(inner.line(), Some(3));
+    assert_eq!(inner.column(), Some(5));
+    assert_eq!(inner.function(), Some("inner"));
+    let outer = debug_info.lookup(frames[1].source_pc()).unwrap();
+    assert_eq!(outer.line(), Some(8));
+    assert_eq!(outer.function(), Some("outer"));
+    let display = debug_info
+        .display_backtrace(trap.backtrace().unwrap())
+        .to_string();
+    assert!(display.contains("at inner (src/lib.rs:3:5)"));
+    assert!(display.contains("at outer (src/lib.rs:8:9)"));
+}
+
+#[test]
+fn lookup_outside_of_code_is_none() {
+    let wasm = with_dwarf(wat::parse_str(WAT).unwrap());
+    let (code_offset, _) = code_layout(&wasm);
+    let module = module(&Engine::default(), &wasm);
+    let debug_info = module.debug_info().unwrap();
+    assert!(debug_info.lookup(0).is_none());
+    assert!(debug_info.lookup(code_offset as u32).is_none());
+    assert!(debug_info.lookup(wasm.len() as u32).is_none());
+}
+
+#[test]
+fn trace_entries_are_annotated() {
+    let wasm = with_dwarf(wat::parse_str(WAT).unwrap());
+    let module = module(&Engine::default(), &wasm);
+    let (store, _trap) = run(&module);
+    let json = store
+        .tracer
+        .to_json_with_debug_info(module.debug_info().unwrap());
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let logs = json["logs"].as_array().unwrap();
+    assert!(!logs.is_empty());
+    for log in logs {
+        assert_eq!(log["location"]["file"], "src/lib.rs");
+    }
+    let last = logs.last().unwrap();
+    assert_eq!(last["location"]["line"], 3);
+    assert_eq!(last["location"]["function"], "inner");
+}
+
+#[test]
+fn missing_or_malformed_dwarf_is_ignored() {
+    let engine = Engine::default();
+    let mut wasm = wat::parse_str(WAT).unwrap();
+    assert!(module(&engine, &wasm).debug_info().is_none());
+    push_custom_section(&mut wasm, ".debug_info", &[0xFF; 7]);
+    assert!(module(&engine, &wasm).debug_info().is_none());
+}
@@ -0,0 +1,136 @@
+//! Tests for epoch based interruption of Wasm executions.
+
+use std::{thread, time::Duration};
+use wasmi::{core::TrapCode, Caller, Config, Engine, Extern, Func, Linker, Module, Store};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "tick" (func $tick))
+        (func (export "spin")
+            (loop $continue
+                (br $continue)
+            )
+        )
+        (func $countdown (export "countdown") (param i32) (result i32)
+            (if (result i32) (i32.eqz (local.get 0))
+                (then (i32.const 0))
+                (else (call $countdown (i32.sub (local.get 0) (i32.const 1))))
+            )
+        )
+        (func (export "ticks") (param i32)
+            (loop $continue
+                (call $tick)
+                (br_if $continue
+                    (local.tee 0 (i32.sub (local.get 0) (i32.const 1)))
+                )
+            )
+        )
+    )
+"#;
+
+/// Instantiates [`WAT`] with epoch based interruption enabled.
+///
+/// The imported `tick` host function increments the epoch of the [`Engine`].
+fn setup() -> (Store<()>, impl Fn(&Store<()>, &str) -> Func) {
+    let mut config = Config::default();
+    config.epoch_interruption(true);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let mut linker = <Linker<()>>::new(&engine);
+    linker
+        .func_wrap("env", "tick", |caller: Caller<()>| {
+            caller.engine().increment_epoch()
+        })
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let func = move |store: &Store<()>, name: &str| {
+        instance
+            .get_export(store, name)
+            .and_then(Extern::into_func)
+            .unwrap()
+    };
+    (store, func)
+}
+
+/// Asserts that the `result` is a trap with [`TrapCode::Interrupted`].
+fn assert_interrupted<T: std::fmt::Debug>(result: Result<T, wasmi::core::Trap>) {
+    assert!(matches!(
+        result.unwrap_err().trap_code(),
+        Some(TrapCode::Interrupted)
+    ));
+}
+
+#[test]
+fn other_thread_interrupts_infinite_loop() {
+    let (mut store, func) = setup();
+    store.set_epoch_deadline(1);
+    let engine = store.engine().clone();
+    let interrupter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        engine.increment_epoch();
+    });
+    let spin = func(&store, "spin").typed::<(), ()>(&store).unwrap();
+    assert_interrupted(spin.call(&mut store, ()));
+    interrupter.join().unwrap();
+}
+
+#[test]
+fn deadline_is_checked_at_calls() {
+    let (mut store, func) = setup();
+    let countdown = func(&store, "countdown").typed::<i32, i32>(&store).unwrap();
+    // Without a deadline executions are never interrupted.
+    assert_eq!(countdown.call(&mut store, 100).unwrap(), 0);
+    store.set_epoch_deadline(0);
+    assert_interrupted(countdown.call(&mut store, 1));
+}
+
+#[test]
+fn deadline_is_relative_to_current_epoch() {
+    let (mut store, func) = setup();
+    let ticks = func(&store, "ticks").typed::<i32, ()>(&store).unwrap();
+    store.set_epoch_deadline(5);
+    ticks.call(&mut store, 4).unwrap();
+    // The epoch advanced by 4 ticks so another 4 ticks reach the deadline.
+    assert_interrupted(ticks.call(&mut store, 4));
+    store.set_epoch_deadline(5);
+    ticks.call(&mut store, 4).unwrap();
+}
+
+#[test]
+fn disabled_epoch_interruption_ignores_deadline() {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (func (export "run") (param i32)
+                (loop $continue
+                    (br_if $continue
+                        (local.tee 0 (i32.sub (local.get 0) (i32.const 1)))
+                    )
+                )
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    store.set_epoch_deadline(0);
+    engine.increment_epoch();
+    instance
+        .get_typed_func::<i32, ()>(&store, "run")
+        .unwrap()
+        .call(&mut store, 10)
+        .unwrap();
+}
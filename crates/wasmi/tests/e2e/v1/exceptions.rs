@@ -0,0 +1,360 @@
+//! Tests for the Wasm `exception-handling` proposal.
+
+use wasmi::{
+    core::{Trap, ValueType},
+    Caller,
+    Config,
+    Engine,
+    Exception,
+    Extern,
+    Func,
+    Instance,
+    Linker,
+    Module,
+    Store,
+    Tag,
+    TagType,
+    Value,
+};
+
+fn test_setup() -> (Store<()>, Linker<()>) {
+    let mut config = Config::default();
+    config.wasm_exceptions(true);
+    let engine = Engine::new(&config);
+    let store = Store::new(&engine, ());
+    let linker = <Linker<()>>::new(&engine);
+    (store, linker)
+}
+
+fn instantiate(store: &mut Store<()>, linker: &Linker<()>, wat: &str) -> Instance {
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(store.engine(), &mut &wasm[..]).unwrap();
+    linker
+        .instantiate(&mut *store, &module)
+        .unwrap()
+        .start(&mut *store)
+        .unwrap()
+}
+
+fn call_i32(
+    store: &mut Store<()>,
+    instance: Instance,
+    name: &str,
+    input: i32,
+) -> Result<i32, Trap> {
+    instance
+        .get_export(&*store, name)
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32>(&*store)
+        .unwrap()
+        .call(store, input)
+}
+
+#[test]
+fn exceptions_disabled_by_default() {
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (tag $e)
+            (func (throw $e))
+        )
+        "#,
+    )
+    .unwrap();
+    let engine = Engine::default();
+    assert!(Module::new(&engine, &mut &wasm[..]).is_err());
+}
+
+#[test]
+fn try_catch_works() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e (param i32))
+            (tag $other)
+            (func $maybe_throw (param i32)
+                (if (i32.ne (local.get 0) (i32.const 0))
+                    (then (throw $e (i32.mul (local.get 0) (i32.const 10))))
+                )
+            )
+            (func (export "run") (param i32) (result i32)
+                (try (result i32)
+                    (do
+                        (call $maybe_throw (local.get 0))
+                        (i32.const -1)
+                    )
+                    (catch $other (i32.const -2))
+                    (catch $e (i32.add (i32.const 1)))
+                )
+            )
+        )
+        "#,
+    );
+    assert_eq!(call_i32(&mut store, instance, "run", 0).unwrap(), -1);
+    assert_eq!(call_i32(&mut store, instance, "run", 4).unwrap(), 41);
+    assert_eq!(call_i32(&mut store, instance, "run", 7).unwrap(), 71);
+}
+
+#[test]
+fn catch_all_and_rethrow_work() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e (param i32))
+            (global $count (mut i32) (i32.const 0))
+            (func $inner (param i32)
+                (try
+                    (do (throw $e (local.get 0)))
+                    (catch_all
+                        (global.set $count (i32.add (global.get $count) (i32.const 1)))
+                        (rethrow 0)
+                    )
+                )
+            )
+            (func (export "run") (param i32) (result i32)
+                (try (result i32)
+                    (do
+                        (call $inner (local.get 0))
+                        (i32.const -1)
+                    )
+                    (catch $e (i32.add (global.get $count)))
+                )
+            )
+        )
+        "#,
+    );
+    assert_eq!(call_i32(&mut store, instance, "run", 10).unwrap(), 11);
+    assert_eq!(call_i32(&mut store, instance, "run", 20).unwrap(), 22);
+}
+
+#[test]
+fn delegate_works() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e (param i32))
+            (func (export "run") (param i32) (result i32)
+                (try $outer (result i32)
+                    (do
+                        (try (result i32)
+                            (do
+                                (try (result i32)
+                                    (do (throw $e (local.get 0)))
+                                    (delegate $outer)
+                                )
+                            )
+                            (catch $e (drop) (i32.const -1))
+                        )
+                    )
+                    (catch $e (i32.add (i32.const 100)))
+                )
+            )
+        )
+        "#,
+    );
+    assert_eq!(call_i32(&mut store, instance, "run", 5).unwrap(), 105);
+}
+
+#[test]
+fn branch_out_of_try_drops_handler() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e (param i32))
+            (func (export "run") (param i32) (result i32)
+                (block $exit
+                    (try
+                        (do (br $exit))
+                        (catch $e (return (i32.const -1)))
+                    )
+                )
+                (throw $e (local.get 0))
+            )
+        )
+        "#,
+    );
+    let trap = call_i32(&mut store, instance, "run", 3).unwrap_err();
+    let exception = trap.downcast_ref::<Exception>().unwrap();
+    assert_eq!(exception.values().len(), 1);
+    assert_eq!(exception.values()[0].i32(), Some(3));
+}
+
+#[test]
+fn branch_out_of_try_then_call_drops_handler() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e (param i32))
+            (func $throw (param i32)
+                (throw $e (local.get 0))
+            )
+            (func (export "run") (param i32) (result i32)
+                (block $exit
+                    (try
+                        (do (br $exit))
+                        (catch $e (return (i32.const -1)))
+                    )
+                )
+                (call $throw (local.get 0))
+                (i32.const 0)
+            )
+        )
+        "#,
+    );
+    let trap = call_i32(&mut store, instance, "run", 3).unwrap_err();
+    let exception = trap.downcast_ref::<Exception>().unwrap();
+    assert_eq!(exception.values().len(), 1);
+    assert_eq!(exception.values()[0].i32(), Some(3));
+}
+
+#[test]
+fn return_out_of_try_drops_handler() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e)
+            (func $return_in_try (result i32)
+                (try
+                    (do (return (i32.const 1)))
+                    (catch $e (return (i32.const 2)))
+                )
+                (i32.const 3)
+            )
+            (func $throw (throw $e))
+            (func $call_throw (call $throw))
+            (func (export "run") (param i32) (result i32)
+                (try
+                    (do
+                        (drop (call $return_in_try))
+                        (call $call_throw)
+                    )
+                    (catch $e (return (i32.const 42)))
+                )
+                (i32.const 0)
+            )
+        )
+        "#,
+    );
+    assert_eq!(call_i32(&mut store, instance, "run", 0).unwrap(), 42);
+}
+
+#[test]
+fn tail_call_out_of_try_drops_handler() {
+    let mut config = Config::default();
+    config.wasm_exceptions(true).wasm_tail_call(true);
+    let engine = Engine::new(&config);
+    let mut store = Store::new(&engine, ());
+    let linker = <Linker<()>>::new(&engine);
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e)
+            (func $one (result i32) (i32.const 1))
+            (func $tail_call_in_try (result i32)
+                (try
+                    (do (return_call $one))
+                    (catch $e (return (i32.const 2)))
+                )
+                (i32.const 3)
+            )
+            (func $throw (throw $e))
+            (func $call_throw (call $throw))
+            (func (export "run") (param i32) (result i32)
+                (try
+                    (do
+                        (drop (call $tail_call_in_try))
+                        (call $call_throw)
+                    )
+                    (catch $e (return (i32.const 42)))
+                )
+                (i32.const 0)
+            )
+        )
+        "#,
+    );
+    assert_eq!(call_i32(&mut store, instance, "run", 0).unwrap(), 42);
+}
+
+#[test]
+fn uncaught_exception_is_returned_as_trap() {
+    let (mut store, linker) = test_setup();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (tag $e (export "e") (param i32 i64))
+            (func (export "run") (param i32) (result i32)
+                (throw $e (local.get 0) (i64.const 42))
+            )
+        )
+        "#,
+    );
+    let tag = instance.get_tag(&store, "e").unwrap();
+    let trap = call_i32(&mut store, instance, "run", 7).unwrap_err();
+    assert!(trap.trap_code().is_none());
+    let exception = trap.downcast_ref::<Exception>().unwrap();
+    assert_eq!(exception.tag(), tag);
+    assert_eq!(exception.values().len(), 2);
+    assert_eq!(exception.values()[0].i32(), Some(7));
+    assert_eq!(exception.values()[1].i64(), Some(42));
+}
+
+#[test]
+fn host_thrown_exception_is_caught() {
+    let (mut store, mut linker) = test_setup();
+    let tag = Tag::new(&mut store, TagType::new([ValueType::I32]));
+    let host_fn = Func::wrap(
+        &mut store,
+        move |_caller: Caller<()>, input: i32| -> Result<(), Trap> {
+            if input != 0 {
+                return Err(Trap::from(Exception::new(tag, [Value::I32(input + 1)])));
+            }
+            Ok(())
+        },
+    );
+    linker.define("env", "e", tag).unwrap();
+    linker.define("env", "host_fn", host_fn).unwrap();
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (import "env" "e" (tag $e (param i32)))
+            (import "env" "host_fn" (func $host_fn (param i32)))
+            (func (export "run") (param i32) (result i32)
+                (try (result i32)
+                    (do
+                        (call $host_fn (local.get 0))
+                        (i32.const -1)
+                    )
+                    (catch $e)
+                )
+            )
+        )
+        "#,
+    );
+    assert_eq!(call_i32(&mut store, instance, "run", 0).unwrap(), -1);
+    assert_eq!(call_i32(&mut store, instance, "run", 5).unwrap(), 6);
+}
@@ -0,0 +1,135 @@
+//! Tests for individually priced instructions of the fuel cost schedule.
+
+use wasmi::{Config, Engine, FuelConsumptionMode, FuelCosts, InstrCost, Linker, Module, Store};
+
+/// Instantiates `wat` with fuel metering using the given [`FuelCosts`].
+///
+/// Returns the [`Store`] and the exported `"test"` function.
+fn setup(costs: FuelCosts, wat: &str) -> (Store<()>, wasmi::Func) {
+    let mut config = Config::default();
+    config
+        .consume_fuel(true)
+        .fuel_consumption_mode(FuelConsumptionMode::Eager)
+        .set_fuel_costs(costs);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let func = instance.get_func(&store, "test").unwrap();
+    (store, func)
+}
+
+const DIV: &str = r#"
+    (module
+        (func (export "test") (param i64 i64) (result i64)
+            (i64.div_u (local.get 0) (local.get 1))
+        )
+    )
+"#;
+
+const FILL: &str = r#"
+    (module
+        (memory 1)
+        (func (export "test")
+            (memory.fill (i32.const 0) (i32.const 0) (i32.const 1024))
+        )
+    )
+"#;
+
+/// Returns the fuel consumed by calling the `"test"` function of `wat` with `inputs`.
+fn consumed_fuel(costs: FuelCosts, wat: &str, inputs: &[wasmi::Value]) -> u64 {
+    let (mut store, func) = setup(costs, wat);
+    store.add_fuel(10_000).unwrap();
+    let len_results = func.ty(&store).results().len();
+    let mut outputs = vec![wasmi::Value::I32(0); len_results];
+    func.call(&mut store, inputs, &mut outputs).unwrap();
+    store.fuel_consumed().unwrap()
+}
+
+#[test]
+fn schedule_prices_instructions_individually() {
+    let inputs = [wasmi::Value::I64(10), wasmi::Value::I64(3)];
+    // 1 for the function body, 2 for `local.get`, 1 for `i64.div_u` and 1 for `return`.
+    assert_eq!(consumed_fuel(FuelCosts::default(), DIV, &inputs), 5);
+    let mut costs = FuelCosts::default();
+    costs.set_instr_cost(
+        "i64_div_u",
+        InstrCost {
+            base: Some(10),
+            items_per_fuel: None,
+        },
+    );
+    assert_eq!(consumed_fuel(costs, DIV, &inputs), 14);
+}
+
+#[test]
+fn schedule_prices_bulk_operations_individually() {
+    // 6 for the instructions and 1024 / 64 for the filled bytes.
+    assert_eq!(consumed_fuel(FuelCosts::default(), FILL, &[]), 22);
+    let costs: FuelCosts = serde_json::from_str(
+        r#"{
+            "instrs": {
+                "memory_fill": { "base": 5, "items_per_fuel": 8 }
+            }
+        }"#,
+    )
+    .unwrap();
+    // 10 for the instructions and 1024 / 8 for the filled bytes.
+    assert_eq!(consumed_fuel(costs, FILL, &[]), 138);
+}
+
+#[test]
+fn schedule_serde_roundtrip() {
+    let mut costs = FuelCosts::default();
+    costs.base = 2;
+    costs.set_instr_cost(
+        "call_indirect",
+        InstrCost {
+            base: Some(12),
+            items_per_fuel: None,
+        },
+    );
+    let json = serde_json::to_string(&costs).unwrap();
+    let decoded: FuelCosts = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, costs);
+    assert_eq!(
+        decoded
+            .instr_cost("call_indirect")
+            .and_then(|cost| cost.base),
+        Some(12)
+    );
+    // Unknown fields are rejected to catch typos in gas schedules.
+    assert!(serde_json::from_str::<FuelCosts>(r#"{ "bsae": 1 }"#).is_err());
+}
+
+#[test]
+fn schedule_rejects_unknown_instructions() {
+    let error = serde_json::from_str::<FuelCosts>(
+        r#"{
+            "instrs": {
+                "i64_divu": { "base": 8 }
+            }
+        }"#,
+    )
+    .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("unknown instruction name `i64_divu`"));
+}
+
+#[test]
+#[should_panic(expected = "unknown instruction name `memory_cpy`")]
+fn set_instr_cost_rejects_unknown_instructions() {
+    FuelCosts::default().set_instr_cost(
+        "memory_cpy",
+        InstrCost {
+            base: Some(4),
+            items_per_fuel: None,
+        },
+    );
+}
@@ -0,0 +1,207 @@
+//! Tests for nested fuel frames and fuel refunds.
+
+use wasmi::{
+    core::TrapCode,
+    errors::FuelError,
+    Caller,
+    Config,
+    Engine,
+    Extern,
+    Func,
+    Instance,
+    Linker,
+    Module,
+    Store,
+    Value,
+};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "sub_call" (func $sub_call (param i64 i32) (result i64)))
+        (func (export "work") (result i32)
+            (i32.add (i32.const 1) (i32.const 2))
+        )
+        (func (export "spin")
+            (loop $continue
+                (br $continue)
+            )
+        )
+        ;; Calls `work` if `$spin` is zero and `spin` otherwise with a fuel frame of `$limit`.
+        (func (export "parent") (param $limit i64) (param $spin i32) (result i64)
+            (call $sub_call (local.get $limit) (local.get $spin))
+        )
+    )
+"#;
+
+/// Instantiates [`WAT`] with fuel metering enabled.
+///
+/// The imported `sub_call` host function forwards a fuel frame to the
+/// exported `work` or `spin` functions and returns the consumed fuel.
+fn setup() -> (Store<()>, Instance) {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let mut linker = <Linker<()>>::new(&engine);
+    linker
+        .func_wrap(
+            "env",
+            "sub_call",
+            |mut caller: Caller<()>, limit: i64, spin: i32| -> i64 {
+                let name = if spin == 0 { "work" } else { "spin" };
+                let callee = caller.get_export(name).and_then(Extern::into_func).unwrap();
+                let mut outputs = [Value::I32(0)];
+                let len_outputs = callee.ty(&caller).results().len();
+                caller.push_fuel_frame(limit as u64).unwrap();
+                let result = callee.call(&mut caller, &[], &mut outputs[..len_outputs]);
+                let frame = caller.pop_fuel_frame().unwrap();
+                assert_eq!(result.is_err(), spin != 0);
+                frame.consumed() as i64
+            },
+        )
+        .unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+/// Returns the exported function `name` of the `instance`.
+fn func(store: &Store<()>, instance: Instance, name: &str) -> Func {
+    instance
+        .get_export(store, name)
+        .and_then(Extern::into_func)
+        .unwrap()
+}
+
+/// Returns the fuel remaining in the current fuel frame of the `store`.
+fn remaining(store: &mut Store<()>) -> u64 {
+    store.consume_fuel(0).unwrap()
+}
+
+/// Returns the fuel consumed by calling `work` in the root fuel frame.
+fn work_fuel() -> u64 {
+    let (mut store, instance) = setup();
+    let work = func(&store, instance, "work")
+        .typed::<(), i32>(&store)
+        .unwrap();
+    store.add_fuel(1_000).unwrap();
+    work.call(&mut store, ()).unwrap();
+    store.fuel_consumed().unwrap()
+}
+
+#[test]
+fn unused_fuel_is_refunded_to_parent() {
+    let work_fuel = work_fuel();
+    let (mut store, instance) = setup();
+    let work = func(&store, instance, "work")
+        .typed::<(), i32>(&store)
+        .unwrap();
+    store.add_fuel(100).unwrap();
+    store.push_fuel_frame(50).unwrap();
+    assert_eq!(store.fuel_frame_depth(), 1);
+    assert_eq!(remaining(&mut store), 50);
+    work.call(&mut store, ()).unwrap();
+    let frame = store.pop_fuel_frame().unwrap();
+    assert_eq!(store.fuel_frame_depth(), 0);
+    assert_eq!(frame.limit(), 50);
+    assert_eq!(frame.consumed(), work_fuel);
+    assert_eq!(frame.unused(), 50 - work_fuel);
+    assert_eq!(remaining(&mut store), 100 - work_fuel);
+    assert_eq!(store.fuel_consumed(), Some(work_fuel));
+}
+
+#[test]
+fn out_of_fuel_in_child_keeps_parent_fuel() {
+    let (mut store, instance) = setup();
+    let spin = func(&store, instance, "spin")
+        .typed::<(), ()>(&store)
+        .unwrap();
+    store.add_fuel(100).unwrap();
+    store.push_fuel_frame(30).unwrap();
+    let error = spin.call(&mut store, ()).unwrap_err();
+    assert!(matches!(error.trap_code(), Some(TrapCode::OutOfFuel)));
+    let frame = store.pop_fuel_frame().unwrap();
+    assert_eq!(frame.consumed(), 30);
+    assert_eq!(frame.unused(), 0);
+    assert_eq!(remaining(&mut store), 70);
+    assert_eq!(store.fuel_consumed(), Some(30));
+}
+
+#[test]
+fn host_forwards_fuel_frames_to_sub_calls() {
+    let work_fuel = work_fuel();
+    let (mut store, instance) = setup();
+    let parent = func(&store, instance, "parent")
+        .typed::<(i64, i32), i64>(&store)
+        .unwrap();
+    store.add_fuel(1_000).unwrap();
+    // The child runs out of fuel but the parent continues with its own fuel.
+    assert_eq!(parent.call(&mut store, (100, 1)).unwrap(), 100);
+    let consumed = store.fuel_consumed().unwrap();
+    assert_eq!(parent.call(&mut store, (100, 0)).unwrap(), work_fuel as i64);
+    // The parent `call` itself costs the same in both cases.
+    let parent_fuel = consumed - 100;
+    assert_eq!(
+        store.fuel_consumed().unwrap(),
+        consumed + parent_fuel + work_fuel
+    );
+    assert_eq!(store.fuel_frame_depth(), 0);
+}
+
+#[test]
+fn nested_fuel_frames() {
+    let (mut store, _instance) = setup();
+    store.add_fuel(100).unwrap();
+    store.push_fuel_frame(60).unwrap();
+    store.consume_fuel(10).unwrap();
+    store.push_fuel_frame(20).unwrap();
+    assert_eq!(store.fuel_frame_depth(), 2);
+    store.consume_fuel(5).unwrap();
+    assert_eq!(store.fuel_consumed(), Some(15));
+    let inner = store.pop_fuel_frame().unwrap();
+    assert_eq!(inner.consumed(), 5);
+    assert_eq!(remaining(&mut store), 45);
+    let outer = store.pop_fuel_frame().unwrap();
+    assert_eq!(outer.consumed(), 15);
+    assert_eq!(remaining(&mut store), 85);
+    assert_eq!(store.fuel_consumed(), Some(15));
+}
+
+#[test]
+fn refunds_are_capped_by_frame_consumption() {
+    let (mut store, _instance) = setup();
+    store.add_fuel(100).unwrap();
+    store.consume_fuel(20).unwrap();
+    store.push_fuel_frame(50).unwrap();
+    store.consume_fuel(10).unwrap();
+    // Only the 10 fuel consumed within the frame can be refunded.
+    assert_eq!(store.refund_fuel(25).unwrap(), 10);
+    store.consume_fuel(4).unwrap();
+    let frame = store.pop_fuel_frame().unwrap();
+    assert_eq!(frame.refunded(), 10);
+    assert_eq!(frame.consumed(), 4);
+    assert_eq!(store.fuel_consumed(), Some(24));
+    assert_eq!(store.refund_fuel(4).unwrap(), 4);
+    assert_eq!(store.fuel_consumed(), Some(20));
+}
+
+#[test]
+fn invalid_fuel_frame_operations() {
+    let (mut store, _instance) = setup();
+    store.add_fuel(10).unwrap();
+    assert!(matches!(
+        store.pop_fuel_frame(),
+        Err(FuelError::NoFuelFrame)
+    ));
+    assert!(matches!(
+        store.push_fuel_frame(11),
+        Err(FuelError::OutOfFuel)
+    ));
+    assert_eq!(store.fuel_frame_depth(), 0);
+    assert_eq!(remaining(&mut store), 10);
+}
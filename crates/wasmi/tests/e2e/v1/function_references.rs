@@ -0,0 +1,39 @@
+//! Tests for the unsupported Wasm `function-references` proposal.
+//!
+//! The `wasmparser-nostd` parser used by `wasmi` cannot decode typed function
+//! references. Therefore Wasm modules using them must be rejected up front.
+
+use wasmi::{Config, Engine, Module};
+
+/// Returns a Wasm module with a single `() -> funcref` function with the `body` operators.
+fn module_with_body(body: &[u8]) -> Vec<u8> {
+    let mut code = vec![0x00]; // no locals
+    code.extend_from_slice(body);
+    code.push(0x0B); // end
+    let mut wasm = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+    wasm.extend_from_slice(&[0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x70]); // type section
+    wasm.extend_from_slice(&[0x03, 0x02, 0x01, 0x00]); // function section
+    wasm.extend_from_slice(&[0x0A, code.len() as u8 + 2, 0x01, code.len() as u8]); // code section
+    wasm.extend_from_slice(&code);
+    wasm
+}
+
+#[test]
+fn typed_function_reference_operators_are_rejected() {
+    let mut config = Config::default();
+    config.wasm_tail_call(true);
+    let engine = Engine::new(&config);
+    // Control check: the module skeleton itself is valid.
+    let ref_null = [0xD0, 0x70];
+    Module::new(&engine, &module_with_body(&ref_null)[..]).unwrap();
+    let operators: [&[u8]; 4] = [
+        &[0xD3],             // ref.as_non_null
+        &[0xD4, 0x00],       // br_on_null 0
+        &[0x14, 0x00, 0x1A], // call_ref $0, drop
+        &[0x15, 0x00],       // return_call_ref $0
+    ];
+    for operator in operators {
+        let body = [&ref_null[..], operator].concat();
+        assert!(Module::new(&engine, &module_with_body(&body)[..]).is_err());
+    }
+}
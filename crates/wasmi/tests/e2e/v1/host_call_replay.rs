@@ -0,0 +1,231 @@
+//! Tests for recording and replaying host function calls via [`HostCallMode`].
+
+use wasmi::{
+    core::{Pages, Trap},
+    Caller,
+    Engine,
+    Extern,
+    Func,
+    HostCallDivergence,
+    HostCallLog,
+    HostCallMode,
+    Linker,
+    MemoryGrow,
+    Module,
+    Store,
+    TypedFunc,
+};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "fill" (func $fill (param i32 i32) (result i32)))
+        (import "env" "exit" (func $exit (param i32)))
+        (memory (export "memory") 1)
+        (func (export "run") (param i32) (result i32)
+            (i32.add
+                (call $fill (i32.const 16) (local.get 0))
+                (i32.load (i32.const 16))
+            )
+        )
+        (func (export "exit") (param i32)
+            (call $exit (local.get 0))
+        )
+    )
+"#;
+
+/// Instantiates [`WAT`] with a `fill` host function that fills memory with `0xAB`.
+///
+/// The host functions panic if `real_host` is `false` to assert they are never called.
+fn setup(real_host: bool) -> (Store<()>, TypedFunc<i32, i32>, TypedFunc<i32, ()>) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let fill = Func::wrap(
+        &mut store,
+        move |caller: Caller<()>, ptr: u32, len: u32| -> u32 {
+            assert!(real_host, "the real host must not be called during replay");
+            let memory = caller
+                .get_export("memory")
+                .and_then(Extern::into_memory)
+                .unwrap();
+            let mut caller = caller;
+            let (ptr, len) = (ptr as usize, len as usize);
+            memory.data_mut(&mut caller)[ptr..ptr + len].fill(0xAB);
+            len as u32
+        },
+    );
+    let exit = Func::wrap(&mut store, move |status: i32| -> Result<(), Trap> {
+        assert!(real_host, "the real host must not be called during replay");
+        Err(Trap::i32_exit(status))
+    });
+    let mut linker = <Linker<()>>::new(&engine);
+    linker.define("env", "fill", fill).unwrap();
+    linker.define("env", "exit", exit).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let run = instance.get_typed_func::<i32, i32>(&store, "run").unwrap();
+    let exit = instance.get_typed_func::<i32, ()>(&store, "exit").unwrap();
+    (store, run, exit)
+}
+
+/// Records a call to `run` with `len` and returns its result and the recorded log.
+fn record(len: i32) -> (i32, HostCallLog) {
+    let (mut store, run, _) = setup(true);
+    store.set_host_call_mode(HostCallMode::record());
+    let result = run.call(&mut store, len).unwrap();
+    let log = store.take_host_call_mode().unwrap().into_log();
+    (result, log)
+}
+
+#[test]
+fn host_calls_are_recorded() {
+    let (result, log) = record(3);
+    assert_eq!(result, 3 + 0x00AB_ABAB);
+    let calls = log.calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].name, "fill");
+    assert_eq!(calls[0].params, [16, 3]);
+    assert_eq!(calls[0].results, [3]);
+    assert_eq!(calls[0].memory_writes.len(), 1);
+    assert_eq!(calls[0].memory_writes[0].offset, 16);
+    assert_eq!(calls[0].memory_writes[0].data, [0xAB; 3]);
+    let json: serde_json::Value = serde_json::from_str(&log.to_json()).unwrap();
+    assert_eq!(json["calls"][0]["memory_writes"][0]["data"], "ababab");
+    assert_eq!(HostCallLog::from_json(&log.to_json()).unwrap(), log);
+}
+
+#[test]
+fn host_calls_are_replayed() {
+    let (expected, log) = record(4);
+    let (mut store, run, _) = setup(false);
+    store.set_host_call_mode(HostCallMode::replay(log));
+    // Note: The result is only equal if the recorded memory writes have been applied.
+    assert_eq!(run.call(&mut store, 4).unwrap(), expected);
+    assert_eq!(store.host_call_mode().unwrap().replayed(), Some(1));
+}
+
+#[test]
+fn host_call_exits_are_replayed() {
+    let (mut store, _, exit) = setup(true);
+    store.set_host_call_mode(HostCallMode::record());
+    let error = exit.call(&mut store, 7).unwrap_err();
+    assert_eq!(error.i32_exit_status(), Some(7));
+    let log = store.take_host_call_mode().unwrap().into_log();
+    assert_eq!(log.calls()[0].exit_status, Some(7));
+
+    let (mut store, _, exit) = setup(false);
+    store.set_host_call_mode(HostCallMode::replay(log));
+    let error = exit.call(&mut store, 7).unwrap_err();
+    assert_eq!(error.i32_exit_status(), Some(7));
+}
+
+#[test]
+fn host_call_divergence_is_detected() {
+    let (_, log) = record(4);
+    let (mut store, run, _) = setup(false);
+    store.set_host_call_mode(HostCallMode::replay(log.clone()));
+    let error = run.call(&mut store, 5).unwrap_err();
+    match error.downcast_ref::<HostCallDivergence>() {
+        Some(HostCallDivergence::Call {
+            index,
+            expected,
+            name,
+            params,
+        }) => {
+            assert_eq!(*index, 0);
+            assert_eq!(expected.as_ref(), Some(&log.calls()[0]));
+            assert_eq!(name, "fill");
+            assert_eq!(params, &[16, 5]);
+        }
+        _ => panic!("expected a host call divergence but found: {error}"),
+    }
+
+    // Calls beyond the end of the log diverge as well.
+    let (mut store, run, _) = setup(false);
+    store.set_host_call_mode(HostCallMode::replay(log));
+    run.call(&mut store, 4).unwrap();
+    let error = run.call(&mut store, 4).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<HostCallDivergence>(),
+        Some(HostCallDivergence::Call {
+            index: 1,
+            expected: None,
+            ..
+        })
+    ));
+}
+
+/// Instantiates a module whose `grow` host function grows memory by a page and writes `value` into it.
+fn setup_grow(real_host: bool) -> (Store<()>, TypedFunc<i32, i32>) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "grow" (func $grow (param i32)))
+            (memory (export "memory") 1 2)
+            (func (export "run") (param i32) (result i32)
+                (call $grow (local.get 0))
+                (i32.add (memory.size) (i32.load (i32.const 65536)))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let grow = Func::wrap(&mut store, move |mut caller: Caller<()>, value: i32| {
+        assert!(real_host, "the real host must not be called during replay");
+        let memory = caller
+            .get_export("memory")
+            .and_then(Extern::into_memory)
+            .unwrap();
+        memory.grow(&mut caller, Pages::from(1)).unwrap();
+        memory
+            .write(&mut caller, 65536, &value.to_le_bytes())
+            .unwrap();
+    });
+    let mut linker = <Linker<()>>::new(&engine);
+    linker.define("env", "grow", grow).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let run = instance.get_typed_func::<i32, i32>(&store, "run").unwrap();
+    (store, run)
+}
+
+#[test]
+fn host_call_memory_growth_is_replayed() {
+    let (mut store, run) = setup_grow(true);
+    store.set_host_call_mode(HostCallMode::record());
+    assert_eq!(run.call(&mut store, 40).unwrap(), 42);
+    let log = store.take_host_call_mode().unwrap().into_log();
+    let call = &log.calls()[0];
+    assert_eq!(call.memory_grows, [MemoryGrow { memory: 0, pages: 1 }]);
+    // Note: Only the bytes actually written by the host are recorded.
+    assert_eq!(call.memory_writes.len(), 1);
+    assert_eq!(call.memory_writes[0].offset, 65536);
+    assert_eq!(call.memory_writes[0].data, 40_i32.to_le_bytes());
+    assert_eq!(HostCallLog::from_json(&log.to_json()).unwrap(), log);
+
+    let (mut store, run) = setup_grow(false);
+    store.set_host_call_mode(HostCallMode::replay(log.clone()));
+    assert_eq!(run.call(&mut store, 40).unwrap(), 42);
+
+    // The second replayed growth exceeds the maximum of the linear memory.
+    let mut log = log;
+    log.calls.push(log.calls[0].clone());
+    let (mut store, run) = setup_grow(false);
+    store.set_host_call_mode(HostCallMode::replay(log));
+    assert_eq!(run.call(&mut store, 40).unwrap(), 42);
+    let error = run.call(&mut store, 40).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<HostCallDivergence>(),
+        Some(HostCallDivergence::MemoryGrow { index: 1, .. })
+    ));
+}
@@ -57,3 +57,55 @@ fn host_calls_wasm() {
     let result = wasm_fn.call(&mut store, input).unwrap();
     assert_eq!(result, expected);
 }
+
+#[test]
+fn host_calls_record_syscalls() {
+    let (mut store, mut linker) = test_setup();
+    let host_fn = Func::wrap(&mut store, |mut caller: Caller<()>, input: i32| -> i32 {
+        let result = input + 1;
+        caller.record_syscall("increment", &[input as u64], &[result as u64]);
+        result
+    });
+    linker.define("env", "increment", host_fn).unwrap();
+    let wasm = wat::parse_str(
+        r#"
+        (module
+            (import "env" "increment" (func $increment (param i32) (result i32)))
+            (func (export "wasm_fn") (param i32) (result i32)
+                (call $increment (call $increment (local.get 0)))
+            )
+        )
+        "#,
+    )
+    .unwrap();
+    let module = Module::new(store.engine(), &mut &wasm[..]).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let wasm_fn = instance
+        .get_export(&store, "wasm_fn")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32>(&store)
+        .unwrap();
+    assert_eq!(wasm_fn.call(&mut store, 1).unwrap(), 3);
+    let syscalls = store.tracer.syscalls();
+    assert_eq!(syscalls.len(), 2);
+    assert_eq!(syscalls[0].name, "increment");
+    assert_eq!(syscalls[0].params, [1]);
+    assert_eq!(syscalls[0].results, [2]);
+    assert_eq!(syscalls[1].params, [2]);
+    assert_eq!(syscalls[1].results, [3]);
+    assert!(syscalls[0].step < syscalls[1].step);
+    let trace: serde_json::Value = serde_json::from_str(&store.tracer.to_json()).unwrap();
+    assert_eq!(trace["syscalls"][1]["name"], "increment");
+}
+
+#[test]
+fn trace_always_lists_syscalls() {
+    let (store, _linker) = test_setup();
+    let trace: serde_json::Value = serde_json::from_str(&store.tracer.to_json()).unwrap();
+    assert_eq!(trace["syscalls"], serde_json::json!([]));
+}
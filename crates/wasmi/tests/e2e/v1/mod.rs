@@ -1,6 +1,23 @@
 mod fuel_consumption_mode;
 mod fuel_metering;
+mod fuel_costs;
+mod fuel_frames;
+mod async_host;
+mod backtrace;
+mod compiled_funcs;
+mod debugger;
+#[cfg(feature = "dwarf")]
+mod dwarf;
+mod epoch_interruption;
+mod exceptions;
 mod func;
+mod function_references;
+mod host_call_replay;
 mod host_calls_wasm;
+mod names;
+mod profiler;
+mod resource_limiter;
 mod resumable_call;
 mod simple_test;
+mod superinstructions;
+mod threads;
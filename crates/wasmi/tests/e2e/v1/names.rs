@@ -0,0 +1,85 @@
+//! Tests for the Wasm `name` custom section and custom sections in general.
+
+use wasmi::{Engine, Extern, Linker, Module, Store};
+
+/// Module with named functions, locals and globals as well as a custom section.
+const WAT: &str = r#"
+    (module $calc
+        (global $counter (mut i32) (i32.const 0))
+        (func $double (param $value i32) (result i32)
+            (i32.add (local.get $value) (local.get $value))
+        )
+        (func $run (export "run") (param $input i32) (result i32)
+            (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+            (call $double (local.get $input))
+        )
+        (@custom "producers" "wasmi")
+    )
+"#;
+
+fn module(engine: &Engine) -> Module {
+    let wasm = wat::parse_str(WAT).unwrap();
+    Module::new(engine, &mut &wasm[..]).unwrap()
+}
+
+#[test]
+fn name_section_is_decoded() {
+    let module = module(&Engine::default());
+    let names = module.names();
+    assert_eq!(names.module(), Some("calc"));
+    assert_eq!(names.func(0), Some("double"));
+    assert_eq!(names.func(1), Some("run"));
+    assert_eq!(names.func(2), None);
+    assert_eq!(names.local(0, 0), Some("value"));
+    assert_eq!(names.local(1, 0), Some("input"));
+    assert_eq!(names.global(0), Some("counter"));
+}
+
+#[test]
+fn custom_sections_are_exposed() {
+    let module = module(&Engine::default());
+    let producers = module
+        .custom_sections()
+        .find(|section| section.name() == "producers")
+        .unwrap();
+    assert_eq!(producers.data(), b"wasmi");
+    assert!(module
+        .custom_sections()
+        .any(|section| section.name() == "name"));
+}
+
+#[test]
+fn malformed_name_section_is_ignored() {
+    // A module consisting only of a `name` custom section with an invalid subsection size.
+    let wasm = [
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+        0x00, 0x07, 0x04, b'n', b'a', b'm', b'e', 0x01, 0xFF, // custom section
+    ];
+    let module = Module::new(&Engine::default(), &wasm[..]).unwrap();
+    assert_eq!(module.names().func(0), None);
+    assert_eq!(module.custom_sections().len(), 1);
+}
+
+#[test]
+fn tracer_uses_function_names() {
+    let engine = Engine::default();
+    let module = module(&engine);
+    let mut store = Store::new(&engine, ());
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let result = instance
+        .get_export(&store, "run")
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 21)
+        .unwrap();
+    assert_eq!(result, 42);
+    let json = store.tracer.to_json();
+    assert!(json.contains(r#""fn_name":"run""#));
+    assert!(json.contains(r#""fn_name":"double""#));
+}
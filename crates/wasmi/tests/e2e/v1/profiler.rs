@@ -0,0 +1,202 @@
+//! Tests for the instruction count and fuel [`Profiler`].
+
+use wasmi::{Caller, Config, Engine, Extern, Func, Linker, Module, ProfileWeight, Profiler, Store};
+
+const WAT: &str = r#"
+    (module
+        (import "env" "log" (func $log (param i32)))
+        (func $add (param i32 i32) (result i32)
+            (i32.add (local.get 0) (local.get 1))
+        )
+        (func $fac (param i64) (result i64)
+            (if (result i64) (i64.eqz (local.get 0))
+                (then (i64.const 1))
+                (else
+                    (i64.mul
+                        (local.get 0)
+                        (call $fac (i64.sub (local.get 0) (i64.const 1)))
+                    )
+                )
+            )
+        )
+        (func $run (export "run") (param i32) (result i32)
+            (call $log (local.get 0))
+            (drop (call $fac (i64.const 3)))
+            (call $add (local.get 0) (i32.const 1))
+        )
+        (func $trap (export "trap")
+            (drop (call $add (i32.const 1) (i32.const 2)))
+            (unreachable)
+        )
+    )
+"#;
+
+/// Instantiates [`WAT`] in a [`Store`] with an installed `profiler`.
+fn setup(config: &Config, profiler: Profiler) -> (Store<()>, Func, Func) {
+    let engine = Engine::new(config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    let log = Func::wrap_with_meta(
+        &mut store,
+        |_caller: Caller<()>, _value: i32| {},
+        String::from("log"),
+    );
+    let mut linker = <Linker<()>>::new(&engine);
+    linker.define("env", "log", log).unwrap();
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    store.set_profiler(profiler);
+    let func = |name| {
+        instance
+            .get_export(&store, name)
+            .and_then(Extern::into_func)
+            .unwrap()
+    };
+    let (run, trap) = (func("run"), func("trap"));
+    (store, run, trap)
+}
+
+/// Parses folded stacks into `(path, weight)` pairs.
+fn parse_folded(folded: &str) -> Vec<(String, u64)> {
+    folded
+        .lines()
+        .map(|line| {
+            let (path, weight) = line.rsplit_once(' ').unwrap();
+            (path.to_string(), weight.parse().unwrap())
+        })
+        .collect()
+}
+
+/// Returns the weight of the call `path` within `folded` or zero.
+fn weight_of(folded: &[(String, u64)], path: &str) -> u64 {
+    folded
+        .iter()
+        .find(|(p, _)| p == path)
+        .map(|(_, weight)| *weight)
+        .unwrap_or(0)
+}
+
+#[test]
+fn folded_stacks_follow_call_paths() {
+    let (mut store, run, _) = setup(&Config::default(), Profiler::new());
+    let result = run
+        .typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 41)
+        .unwrap();
+    assert_eq!(result, 42);
+    let profiler = store.profiler().unwrap();
+    let folded = parse_folded(&profiler.folded(ProfileWeight::Instructions));
+    assert!(weight_of(&folded, "run") > 0);
+    assert!(weight_of(&folded, "run;add") > 0);
+    assert!(weight_of(&folded, "run;fac") > 0);
+    assert!(weight_of(&folded, "run;fac;fac;fac;fac") > 0);
+    assert_eq!(weight_of(&folded, "run;fac;fac;fac;fac;fac"), 0);
+    // Host functions do not execute instructions and thus carry no weight.
+    assert_eq!(weight_of(&folded, "run;log"), 0);
+    let total: u64 = folded.iter().map(|(_, weight)| weight).sum();
+    let histogram = profiler.opcode_histogram();
+    assert_eq!(histogram.iter().map(|(_, count)| count).sum::<u64>(), total);
+    let count = |name| {
+        histogram
+            .iter()
+            .find(|(opcode, _)| *opcode == name)
+            .map(|(_, count)| *count)
+    };
+    assert_eq!(count("call"), Some(6));
+    assert_eq!(count("i64_mul"), Some(3));
+}
+
+#[test]
+fn funcs_aggregate_calls_and_instructions() {
+    let (mut store, run, _) = setup(&Config::default(), Profiler::new());
+    let run = run.typed::<i32, i32>(&store).unwrap();
+    run.call(&mut store, 1).unwrap();
+    run.call(&mut store, 2).unwrap();
+    let funcs = store.profiler().unwrap().funcs();
+    let func = |name| funcs.iter().find(|func| func.name() == name).unwrap();
+    assert_eq!(func("run").calls(), 2);
+    assert_eq!(func("add").calls(), 2);
+    assert_eq!(func("fac").calls(), 8);
+    assert_eq!(func("log").calls(), 2);
+    assert_eq!(func("log").index(), None);
+    assert_eq!(func("add").index(), Some(1));
+    // The root function includes the instructions of all its callees.
+    let total: u64 = funcs.iter().map(|func| func.self_instrs()).sum();
+    assert_eq!(func("run").total_instrs(), total);
+    assert_eq!(funcs[0].name(), "run");
+    // Recursive calls are only counted once for the total.
+    assert_eq!(func("fac").total_instrs(), func("fac").self_instrs());
+}
+
+#[test]
+fn fuel_is_attributed_to_call_paths() {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let (mut store, run, _) = setup(&config, Profiler::new());
+    store.add_fuel(10_000).unwrap();
+    run.typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 1)
+        .unwrap();
+    let consumed = store.fuel_consumed().unwrap();
+    let profiler = store.profiler().unwrap();
+    let folded = parse_folded(&profiler.folded(ProfileWeight::Fuel));
+    let total: u64 = folded.iter().map(|(_, weight)| weight).sum();
+    assert_eq!(total, consumed);
+    assert!(weight_of(&folded, "run;add") > 0);
+    let funcs = profiler.funcs();
+    let run = funcs.iter().find(|func| func.name() == "run").unwrap();
+    assert_eq!(run.total_fuel(), consumed);
+}
+
+#[test]
+fn sampling_records_every_nth_instruction() {
+    let (mut store, run, _) = setup(&Config::default(), Profiler::new());
+    run.typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 1)
+        .unwrap();
+    let exact: u64 = store
+        .profiler()
+        .unwrap()
+        .funcs()
+        .iter()
+        .map(|func| func.self_instrs())
+        .sum();
+    store.set_profiler(Profiler::sampling(4));
+    run.typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 1)
+        .unwrap();
+    let profiler = store.profiler().unwrap();
+    assert_eq!(profiler.sample_interval(), 4);
+    let sampled: u64 = profiler.funcs().iter().map(|func| func.self_instrs()).sum();
+    assert_eq!(sampled, exact / 4 * 4);
+}
+
+#[test]
+fn traps_do_not_corrupt_call_paths() {
+    let (mut store, run, trap) = setup(&Config::default(), Profiler::new());
+    trap.typed::<(), ()>(&store)
+        .unwrap()
+        .call(&mut store, ())
+        .unwrap_err();
+    run.typed::<i32, i32>(&store)
+        .unwrap()
+        .call(&mut store, 1)
+        .unwrap();
+    let profiler = store.profiler_mut().unwrap();
+    let folded = parse_folded(&profiler.folded(ProfileWeight::Instructions));
+    assert!(weight_of(&folded, "trap") > 0);
+    assert!(weight_of(&folded, "trap;add") > 0);
+    assert!(weight_of(&folded, "run") > 0);
+    assert!(folded.iter().all(|(path, _)| !path.starts_with("trap;run")));
+    profiler.reset();
+    assert!(profiler.folded(ProfileWeight::Instructions).is_empty());
+    assert!(profiler.funcs().is_empty());
+}
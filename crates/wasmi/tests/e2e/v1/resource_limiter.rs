@@ -0,0 +1,222 @@
+//! Tests for the [`ResourceLimiter`] of a [`Store`].
+
+use wasmi::{
+    core::{Pages, TrapCode, ValueType},
+    errors::{InstantiationError, MemoryError, TableError},
+    Config,
+    Engine,
+    Error,
+    Extern,
+    Instance,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    Store,
+    StoreLimits,
+    StoreLimitsBuilder,
+    Table,
+    TableType,
+    Value,
+};
+
+const WAT: &str = r#"
+    (module
+        (memory (export "memory") 1)
+        (table 1 funcref)
+        (func (export "memory_grow") (param i32) (result i32)
+            (memory.grow (local.get 0))
+        )
+        (func (export "table_grow") (param i32) (result i32)
+            (table.grow (ref.null func) (local.get 0))
+        )
+    )
+"#;
+
+/// Creates a new [`Store`] with the given `limits` installed.
+fn setup(limits: StoreLimits) -> (Store<StoreLimits>, Module) {
+    let engine = Engine::default();
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    (store, module)
+}
+
+/// Instantiates the `module` within the `store`.
+fn instantiate(store: &mut Store<StoreLimits>, module: &Module) -> Result<Instance, Error> {
+    <Linker<StoreLimits>>::new(store.engine())
+        .instantiate(&mut *store, module)?
+        .start(&mut *store)
+}
+
+/// Calls the exported grow function `name` of `instance` with `delta`.
+fn grow(
+    store: &mut Store<StoreLimits>,
+    instance: Instance,
+    name: &str,
+    delta: i32,
+) -> Result<i32, TrapCode> {
+    instance
+        .get_typed_func::<i32, i32>(&*store, name)
+        .unwrap()
+        .call(store, delta)
+        .map_err(|trap| trap.trap_code().unwrap())
+}
+
+#[test]
+fn denied_grow_returns_minus_one() {
+    let page = Pages::new(1).unwrap().to_bytes().unwrap();
+    let (mut store, module) = setup(
+        StoreLimitsBuilder::new()
+            .memory_size(2 * page)
+            .table_elements(3)
+            .build(),
+    );
+    let instance = instantiate(&mut store, &module).unwrap();
+    assert_eq!(grow(&mut store, instance, "memory_grow", 1).unwrap(), 1);
+    assert_eq!(grow(&mut store, instance, "memory_grow", 1).unwrap(), -1);
+    assert_eq!(grow(&mut store, instance, "table_grow", 2).unwrap(), 1);
+    assert_eq!(grow(&mut store, instance, "table_grow", 1).unwrap(), -1);
+    // Growing by zero never consults the limits.
+    assert_eq!(grow(&mut store, instance, "memory_grow", 0).unwrap(), 2);
+    assert_eq!(grow(&mut store, instance, "table_grow", 0).unwrap(), 3);
+}
+
+#[test]
+fn denied_grow_traps() {
+    let (mut store, module) = setup(
+        StoreLimitsBuilder::new()
+            .memory_size(0x1_0000)
+            .table_elements(1)
+            .trap_on_grow_failure(true)
+            .build(),
+    );
+    let instance = instantiate(&mut store, &module).unwrap();
+    assert!(matches!(
+        grow(&mut store, instance, "memory_grow", 1),
+        Err(TrapCode::GrowthOperationLimited)
+    ));
+    assert!(matches!(
+        grow(&mut store, instance, "table_grow", 1),
+        Err(TrapCode::GrowthOperationLimited)
+    ));
+}
+
+#[test]
+fn total_memory_size_spans_all_memories() {
+    let page = Pages::new(1).unwrap().to_bytes().unwrap();
+    let (mut store, module) = setup(StoreLimitsBuilder::new().total_memory_size(3 * page).build());
+    let instance = instantiate(&mut store, &module).unwrap();
+    let memory = Memory::new(&mut store, MemoryType::new(1, None).unwrap()).unwrap();
+    assert_eq!(store.data().total_memory_consumed(), 2 * page);
+    assert_eq!(grow(&mut store, instance, "memory_grow", 1).unwrap(), 1);
+    assert!(matches!(
+        memory.grow(&mut store, Pages::new(1).unwrap()),
+        Err(MemoryError::OutOfBoundsGrowth)
+    ));
+    assert!(matches!(
+        Memory::new(&mut store, MemoryType::new(1, None).unwrap()),
+        Err(MemoryError::ResourceLimiterDeniedAllocation)
+    ));
+    assert!(matches!(
+        instantiate(&mut store, &module),
+        Err(Error::Instantiation(InstantiationError::Memory(
+            MemoryError::ResourceLimiterDeniedAllocation
+        )))
+    ));
+}
+
+#[test]
+fn total_table_elements_spans_all_tables() {
+    let (mut store, module) = setup(StoreLimitsBuilder::new().total_table_elements(4).build());
+    let instance = instantiate(&mut store, &module).unwrap();
+    let ty = TableType::new(ValueType::FuncRef, 1, None);
+    let table = Table::new(&mut store, ty, Value::default(ValueType::FuncRef)).unwrap();
+    assert_eq!(store.data().total_table_elements_consumed(), 2);
+    assert_eq!(grow(&mut store, instance, "table_grow", 1).unwrap(), 1);
+    assert_eq!(store.data().total_table_elements_consumed(), 3);
+    assert!(table
+        .grow(&mut store, 2, Value::default(ValueType::FuncRef))
+        .is_err());
+    assert_eq!(grow(&mut store, instance, "table_grow", 2).unwrap(), -1);
+    assert_eq!(grow(&mut store, instance, "table_grow", 1).unwrap(), 2);
+    assert_eq!(store.data().total_table_elements_consumed(), 4);
+    assert!(matches!(
+        instantiate(&mut store, &module),
+        Err(Error::Instantiation(InstantiationError::Table(
+            TableError::ResourceLimiterDeniedAllocation
+        )))
+    ));
+}
+
+#[test]
+fn instance_count_is_limited() {
+    let (mut store, module) = setup(StoreLimitsBuilder::new().instances(2).build());
+    instantiate(&mut store, &module).unwrap();
+    instantiate(&mut store, &module).unwrap();
+    assert!(matches!(
+        instantiate(&mut store, &module),
+        Err(Error::Instantiation(InstantiationError::TooManyInstances))
+    ));
+}
+
+#[test]
+fn table_and_memory_counts_are_limited() {
+    let (mut store, module) = setup(StoreLimitsBuilder::new().tables(0).build());
+    assert!(matches!(
+        instantiate(&mut store, &module),
+        Err(Error::Instantiation(InstantiationError::TooManyTables))
+    ));
+    let (mut store, module) = setup(StoreLimitsBuilder::new().memories(1).build());
+    let instance = instantiate(&mut store, &module).unwrap();
+    assert!(matches!(
+        instantiate(&mut store, &module),
+        Err(Error::Instantiation(InstantiationError::TooManyMemories))
+    ));
+    // Rejected instantiations do not affect existing instances.
+    assert!(instance
+        .get_export(&store, "memory")
+        .and_then(Extern::into_memory)
+        .is_some());
+}
+
+#[test]
+fn late_limiter_sees_existing_instances() {
+    let (store, module) = setup(StoreLimitsBuilder::new().instances(1).build());
+    let engine = store.engine().clone();
+    let mut store = Store::new(&engine, store.into_data());
+    // No limiter is installed at this point but the instance is still accounted for.
+    instantiate(&mut store, &module).unwrap();
+    store.limiter(|limits| limits);
+    assert!(matches!(
+        instantiate(&mut store, &module),
+        Err(Error::Instantiation(InstantiationError::TooManyInstances))
+    ));
+}
+
+#[test]
+fn failed_instantiation_is_not_counted() {
+    let (mut store, module) = setup(StoreLimitsBuilder::new().instances(1).build());
+    let wasm = wat::parse_str(r#"(module (memory 1) (data (i32.const 0x1_0000) "x"))"#).unwrap();
+    let broken = Module::new(store.engine(), &wasm[..]).unwrap();
+    assert!(instantiate(&mut store, &broken).is_err());
+    instantiate(&mut store, &module).unwrap();
+}
+
+#[test]
+fn shared_memory_charges_maximum_size() {
+    let page = Pages::new(1).unwrap().to_bytes().unwrap();
+    let mut config = Config::default();
+    config.wasm_threads(true).deterministic_tracing(false);
+    let engine = Engine::new(&config);
+    let limits = StoreLimitsBuilder::new().total_memory_size(3 * page).build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    assert!(matches!(
+        Memory::new(&mut store, MemoryType::new_shared(1, 4).unwrap()),
+        Err(MemoryError::ResourceLimiterDeniedAllocation)
+    ));
+    Memory::new(&mut store, MemoryType::new_shared(1, 2).unwrap()).unwrap();
+    assert_eq!(store.data().total_memory_consumed(), 2 * page);
+}
@@ -0,0 +1,159 @@
+//! Tests for the superinstruction fusion pass.
+
+use wasmi::{Config, Engine, Extern, Linker, Module, Store};
+
+/// Module with functions that make heavy use of fusable instruction sequences.
+const WAT: &str = r#"
+    (module
+        (func (export "sum_below") (param $n i32) (result i32)
+            (local $i i32) (local $sum i32)
+            (block $exit
+                (loop $continue
+                    (br_if $exit (i32.ge_u (local.get $i) (local.get $n)))
+                    (local.set $sum
+                        (i32.add (local.get $sum) (i32.mul (local.get $i) (i32.const 3)))
+                    )
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))
+                    (br $continue)
+                )
+            )
+            (local.get $sum)
+        )
+        (func (export "mix") (param $x i64) (result i64)
+            (block $negative
+                (br_if $negative (i64.lt_s (local.get $x) (i64.const 0)))
+                (return
+                    (i64.xor
+                        (i64.rotl (local.get $x) (i64.const 13))
+                        (i64.shr_u (local.get $x) (i64.const 7))
+                    )
+                )
+            )
+            (i64.sub (local.get $x) (i64.const -42))
+        )
+        (func (export "fib") (param $n i64) (result i64)
+            (local $a i64) (local $b i64) (local $tmp i64)
+            (local.set $b (i64.const 1))
+            (block $exit
+                (loop $continue
+                    (br_if $exit (i64.eqz (local.get $n)))
+                    (local.set $tmp (i64.add (local.get $a) (local.get $b)))
+                    (local.set $a (local.get $b))
+                    (local.set $b (local.get $tmp))
+                    (local.set $n (i64.sub (local.get $n) (i64.const 1)))
+                    (br $continue)
+                )
+            )
+            (local.get $a)
+        )
+        (func (export "mix_locals") (param $x i32) (param $y i32) (result i32)
+            (i32.xor
+                (i32.rotl
+                    (i32.shr_u (local.get $x) (local.get $y))
+                    (local.get $y)
+                )
+                (i32.sub
+                    (i32.lt_s (local.get $y) (local.get $x))
+                    (i32.mul (local.get $x) (local.get $y))
+                )
+            )
+        )
+    )
+"#;
+
+/// Runs `f` on a [`Store`] with the superinstruction fusion pass set to `enable`.
+///
+/// Returns the result of `f` and the amount of consumed fuel.
+fn run<R>(enable: bool, f: impl FnOnce(&mut Store<()>, wasmi::Instance) -> R) -> (R, u64) {
+    let mut config = Config::default();
+    config
+        .deterministic_tracing(false)
+        .consume_fuel(true)
+        .superinstructions(enable);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str(WAT).unwrap();
+    let module = Module::new(&engine, &mut &wasm[..]).unwrap();
+    let mut store = Store::new(&engine, ());
+    store.add_fuel(u64::MAX / 2).unwrap();
+    let instance = <Linker<()>>::new(&engine)
+        .instantiate(&mut store, &module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    let result = f(&mut store, instance);
+    (result, store.fuel_consumed().unwrap())
+}
+
+fn sum_below(enable: bool, n: i32) -> (i32, u64) {
+    run(enable, |store, instance| {
+        instance
+            .get_export(&*store, "sum_below")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<i32, i32>(&*store)
+            .unwrap()
+            .call(store, n)
+            .unwrap()
+    })
+}
+
+fn mix(enable: bool, x: i64) -> (i64, u64) {
+    run(enable, |store, instance| {
+        instance
+            .get_export(&*store, "mix")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<i64, i64>(&*store)
+            .unwrap()
+            .call(store, x)
+            .unwrap()
+    })
+}
+
+fn fib(enable: bool, n: i64) -> (i64, u64) {
+    run(enable, |store, instance| {
+        instance
+            .get_export(&*store, "fib")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<i64, i64>(&*store)
+            .unwrap()
+            .call(store, n)
+            .unwrap()
+    })
+}
+
+fn mix_locals(enable: bool, x: i32, y: i32) -> (i32, u64) {
+    run(enable, |store, instance| {
+        instance
+            .get_export(&*store, "mix_locals")
+            .and_then(Extern::into_func)
+            .unwrap()
+            .typed::<(i32, i32), i32>(&*store)
+            .unwrap()
+            .call(store, (x, y))
+            .unwrap()
+    })
+}
+
+#[test]
+fn superinstructions_match_plain_instructions() {
+    for n in [0, 1, 2, 10, 1000] {
+        assert_eq!(sum_below(true, n), sum_below(false, n));
+    }
+    assert_eq!(sum_below(true, 10).0, 135);
+    for x in [0, 1, -1, 42, i64::MIN, i64::MAX] {
+        assert_eq!(mix(true, x), mix(false, x));
+    }
+}
+
+#[test]
+fn local_operand_fusion_matches_plain_instructions() {
+    for n in [0, 1, 2, 10, 50, 90] {
+        assert_eq!(fib(true, n), fib(false, n));
+    }
+    assert_eq!(fib(true, 50).0, 12_586_269_025);
+    for (x, y) in [(0, 0), (1, 2), (-7, 3), (i32::MAX, 31), (i32::MIN, -1)] {
+        assert_eq!(mix_locals(true, x, y), mix_locals(false, x, y));
+    }
+}
@@ -0,0 +1,528 @@
+//! Tests for the Wasm `threads` proposal.
+
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use wasmi::{
+    core::{Pages, TrapCode},
+    errors::MemoryError,
+    Config,
+    Engine,
+    Extern,
+    Instance,
+    Linker,
+    Memory,
+    MemoryType,
+    Module,
+    SharedMemory,
+    StdThreadParker,
+    Store,
+    StoreLimitsBuilder,
+    ThreadParker,
+    WaitResult,
+};
+
+fn engine() -> Engine {
+    let mut config = Config::default();
+    config.wasm_threads(true).deterministic_tracing(false);
+    Engine::new(&config)
+}
+
+fn instantiate(store: &mut Store<()>, linker: &Linker<()>, wat: &str) -> Instance {
+    let wasm = wat::parse_str(wat).unwrap();
+    let module = Module::new(store.engine(), &mut &wasm[..]).unwrap();
+    linker
+        .instantiate(&mut *store, &module)
+        .unwrap()
+        .start(&mut *store)
+        .unwrap()
+}
+
+fn call<Params, Results>(
+    store: &mut Store<()>,
+    instance: Instance,
+    name: &str,
+    params: Params,
+) -> Result<Results, wasmi::core::Trap>
+where
+    Params: wasmi::WasmParams,
+    Results: wasmi::WasmResults,
+{
+    instance
+        .get_export(&*store, name)
+        .and_then(Extern::into_func)
+        .unwrap()
+        .typed::<Params, Results>(&*store)
+        .unwrap()
+        .call(store, params)
+}
+
+/// Module importing a shared memory and exposing atomic `wait` and `notify`.
+const WAIT_NOTIFY: &str = r#"
+    (module
+        (import "env" "memory" (memory 1 1 shared))
+        (func (export "wait") (param i32 i32 i64) (result i32)
+            (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2))
+        )
+        (func (export "notify") (param i32 i32) (result i32)
+            (memory.atomic.notify (local.get 0) (local.get 1))
+        )
+    )
+"#;
+
+fn instantiate_with_shared(module: &Module, memory: SharedMemory) -> (Store<()>, Instance) {
+    let mut store = Store::new(module.engine(), ());
+    let memory = Memory::new_shared(&mut store, memory).unwrap();
+    let mut linker = <Linker<()>>::new(module.engine());
+    linker.define("env", "memory", memory).unwrap();
+    let instance = linker
+        .instantiate(&mut store, module)
+        .unwrap()
+        .start(&mut store)
+        .unwrap();
+    (store, instance)
+}
+
+fn wait_notify_module(engine: &Engine) -> Module {
+    let wasm = wat::parse_str(WAIT_NOTIFY).unwrap();
+    Module::new(engine, &mut &wasm[..]).unwrap()
+}
+
+#[test]
+fn threads_disabled_by_default() {
+    let wasm = wat::parse_str("(module (memory 1 1 shared))").unwrap();
+    let engine = Engine::default();
+    assert!(Module::new(&engine, &mut &wasm[..]).is_err());
+}
+
+#[test]
+fn shared_memory_rejected_in_deterministic_tracing() {
+    let mut config = Config::default();
+    config.wasm_threads(true);
+    let engine = Engine::new(&config);
+    let wasm = wat::parse_str("(module (memory 1 1 shared))").unwrap();
+    assert!(Module::new(&engine, &mut &wasm[..]).is_err());
+    let ty = MemoryType::new_shared(1, 1).unwrap();
+    assert!(SharedMemory::new(&engine, ty).is_err());
+}
+
+#[test]
+fn atomic_rmw_and_cmpxchg_work() {
+    let engine = engine();
+    let mut store = Store::new(&engine, ());
+    let linker = <Linker<()>>::new(&engine);
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (memory 1 1 shared)
+            (func (export "add") (param i32) (result i32)
+                (i32.atomic.rmw.add (i32.const 8) (local.get 0))
+            )
+            (func (export "load") (result i32)
+                (i32.atomic.load (i32.const 8))
+            )
+            (func (export "cmpxchg") (param i64 i64) (result i64)
+                (i64.atomic.rmw.cmpxchg (i32.const 16) (local.get 0) (local.get 1))
+            )
+            (func (export "xchg8") (param i32) (result i32)
+                (i32.atomic.rmw8.xchg_u (i32.const 3) (local.get 0))
+            )
+        )
+        "#,
+    );
+    assert_eq!(call::<i32, i32>(&mut store, instance, "add", 5).unwrap(), 0);
+    assert_eq!(call::<i32, i32>(&mut store, instance, "add", 7).unwrap(), 5);
+    assert_eq!(
+        call::<(), i32>(&mut store, instance, "load", ()).unwrap(),
+        12
+    );
+    assert_eq!(
+        call::<(i64, i64), i64>(&mut store, instance, "cmpxchg", (1, 42)).unwrap(),
+        0
+    );
+    assert_eq!(
+        call::<(i64, i64), i64>(&mut store, instance, "cmpxchg", (0, 42)).unwrap(),
+        0
+    );
+    assert_eq!(
+        call::<(i64, i64), i64>(&mut store, instance, "cmpxchg", (0, 7)).unwrap(),
+        42
+    );
+    // Narrow accesses wrap their operand and zero extend the previous value.
+    assert_eq!(
+        call::<i32, i32>(&mut store, instance, "xchg8", 0x1FF).unwrap(),
+        0
+    );
+    assert_eq!(
+        call::<i32, i32>(&mut store, instance, "xchg8", 0).unwrap(),
+        0xFF
+    );
+}
+
+#[test]
+fn unaligned_atomic_traps() {
+    let engine = engine();
+    let mut store = Store::new(&engine, ());
+    let linker = <Linker<()>>::new(&engine);
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (memory 1)
+            (func (export "load") (param i32) (result i32)
+                (i32.atomic.load (local.get 0))
+            )
+        )
+        "#,
+    );
+    assert_eq!(
+        call::<i32, i32>(&mut store, instance, "load", 4).unwrap(),
+        0
+    );
+    let trap = call::<i32, i32>(&mut store, instance, "load", 2).unwrap_err();
+    assert!(matches!(trap.trap_code(), Some(TrapCode::UnalignedAtomic)));
+    let trap = call::<i32, i32>(&mut store, instance, "load", 65536).unwrap_err();
+    assert!(matches!(trap.trap_code(), Some(TrapCode::MemoryOutOfBounds)));
+}
+
+#[test]
+fn wait_on_unshared_memory_traps() {
+    let engine = engine();
+    let mut store = Store::new(&engine, ());
+    let linker = <Linker<()>>::new(&engine);
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (memory 1)
+            (func (export "wait") (result i32)
+                (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const 0))
+            )
+            (func (export "notify") (result i32)
+                (memory.atomic.notify (i32.const 0) (i32.const 1))
+            )
+        )
+        "#,
+    );
+    let trap = call::<(), i32>(&mut store, instance, "wait", ()).unwrap_err();
+    assert!(matches!(trap.trap_code(), Some(TrapCode::ExpectedSharedMemory)));
+    assert_eq!(
+        call::<(), i32>(&mut store, instance, "notify", ()).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn wait_not_equal_and_timeout_work() {
+    let engine = engine();
+    let ty = MemoryType::new_shared(1, 1).unwrap();
+    let memory = SharedMemory::new(&engine, ty).unwrap();
+    let module = wait_notify_module(&engine);
+    let (mut store, instance) = instantiate_with_shared(&module, memory);
+    type Wait = (i32, i32, i64);
+    assert_eq!(
+        call::<Wait, i32>(&mut store, instance, "wait", (0, 1, -1)).unwrap(),
+        1
+    );
+    assert_eq!(
+        call::<Wait, i32>(&mut store, instance, "wait", (0, 0, 1_000)).unwrap(),
+        2
+    );
+    assert_eq!(
+        call::<(i32, i32), i32>(&mut store, instance, "notify", (0, 1)).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn wait_notify_across_threads_works() {
+    let engine = engine();
+    let ty = MemoryType::new_shared(1, 1).unwrap();
+    let memory = SharedMemory::new(&engine, ty).unwrap();
+    let module = Arc::new(wait_notify_module(&engine));
+    let waiter = {
+        let module = module.clone();
+        let memory = memory.clone();
+        thread::spawn(move || {
+            let (mut store, instance) = instantiate_with_shared(&module, memory);
+            call::<(i32, i32, i64), i32>(&mut store, instance, "wait", (0, 0, -1)).unwrap()
+        })
+    };
+    let (mut store, instance) = instantiate_with_shared(&module, memory);
+    // The waiter might not be parked yet so we retry until it has been woken up.
+    while call::<(i32, i32), i32>(&mut store, instance, "notify", (0, 1)).unwrap() == 0 {
+        thread::yield_now();
+    }
+    assert_eq!(waiter.join().unwrap(), 0);
+}
+
+/// Module that signals at address 4 before it waits at address 0 within a nested call.
+const WAIT_NESTED: &str = r#"
+    (module
+        (import "env" "memory" (memory 1 1 shared))
+        (func $wait (result i32)
+            (i32.atomic.store (i32.const 4) (i32.const 1))
+            (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const -1))
+        )
+        (func (export "wait_nested") (result i32)
+            (i32.add (call $wait) (i32.const 10))
+        )
+    )
+"#;
+
+#[test]
+fn modules_can_be_compiled_while_waiting() {
+    let engine = engine();
+    let ty = MemoryType::new_shared(1, 1).unwrap();
+    let memory = SharedMemory::new(&engine, ty).unwrap();
+    let wasm = wat::parse_str(WAIT_NESTED).unwrap();
+    let module = Module::new(&engine, &mut &wasm[..]).unwrap();
+    let waiter = {
+        let memory = memory.clone();
+        thread::spawn(move || {
+            let (mut store, instance) = instantiate_with_shared(&module, memory);
+            call::<(), i32>(&mut store, instance, "wait_nested", ()).unwrap()
+        })
+    };
+    let mut signal = [0x00_u8; 4];
+    while signal == [0x00; 4] {
+        thread::yield_now();
+        memory.read(4, &mut signal).unwrap();
+    }
+    // Compiles enough instructions to move the instructions of the waiting execution.
+    let drops = "(drop (i32.const 0))".repeat(10_000);
+    let wasm = wat::parse_str(format!("(module (func {drops}))")).unwrap();
+    for _ in 0..4 {
+        Module::new(&engine, &mut &wasm[..]).unwrap();
+    }
+    // The waiter has been registered before it released the engine for compilation.
+    assert_eq!(memory.atomic_notify(0, 1).unwrap(), 1);
+    assert_eq!(waiter.join().unwrap(), 10);
+}
+
+#[test]
+fn host_atomic_wait_and_notify_work() {
+    let engine = engine();
+    let ty = MemoryType::new_shared(1, 1).unwrap();
+    let memory = SharedMemory::new(&engine, ty).unwrap();
+    let parker: Arc<dyn ThreadParker> = Arc::new(StdThreadParker);
+    assert_eq!(
+        memory.atomic_wait32(0, 1, None, &parker).unwrap(),
+        WaitResult::Mismatch
+    );
+    assert_eq!(
+        memory
+            .atomic_wait64(8, 0, Some(Duration::from_millis(1)), &parker)
+            .unwrap(),
+        WaitResult::TimedOut
+    );
+    assert!(matches!(
+        memory.atomic_wait32(2, 0, None, &parker),
+        Err(TrapCode::UnalignedAtomic)
+    ));
+    assert!(matches!(
+        memory.atomic_notify(1 << 16, 1),
+        Err(TrapCode::MemoryOutOfBounds)
+    ));
+    let waiter = {
+        let memory = memory.clone();
+        let parker = parker.clone();
+        thread::spawn(move || memory.atomic_wait64(8, 0, None, &parker).unwrap())
+    };
+    // The waiter might not be parked yet so we retry until it has been woken up.
+    while memory.atomic_notify(8, 1).unwrap() == 0 {
+        thread::yield_now();
+    }
+    assert_eq!(waiter.join().unwrap(), WaitResult::Ok);
+}
+
+/// Module importing a growable shared memory.
+const GROW: &str = r#"
+    (module
+        (import "env" "memory" (memory 1 2 shared))
+        (func (export "grow") (param i32) (result i32)
+            (memory.grow (local.get 0))
+        )
+        (func (export "access_after_growth") (result i32)
+            ;; Signals that the linear memory has been accessed before it grew.
+            (i32.atomic.store (i32.const 0) (i32.const 1))
+            (loop $spin
+                (br_if $spin (i32.lt_u (memory.size) (i32.const 2)))
+            )
+            (i32.store (i32.const 65536) (i32.const 42))
+            (drop (i32.atomic.rmw.add (i32.const 65540) (i32.const 1)))
+            (i32.add
+                (i32.load (i32.const 65536))
+                (i32.atomic.load (i32.const 65540))
+            )
+        )
+    )
+"#;
+
+#[test]
+fn shared_memory_growth_is_observed_by_other_stores() {
+    let engine = engine();
+    let ty = MemoryType::new_shared(1, 2).unwrap();
+    let memory = SharedMemory::new(&engine, ty).unwrap();
+    let wasm = wat::parse_str(GROW).unwrap();
+    let module = Arc::new(Module::new(&engine, &mut &wasm[..]).unwrap());
+    let accessor = {
+        let module = module.clone();
+        let memory = memory.clone();
+        thread::spawn(move || {
+            let (mut store, instance) = instantiate_with_shared(&module, memory);
+            call::<(), i32>(&mut store, instance, "access_after_growth", ()).unwrap()
+        })
+    };
+    let (mut store, instance) = instantiate_with_shared(&module, memory.clone());
+    // Only grow the linear memory after the other thread has accessed it.
+    let mut signal = [0x00_u8; 4];
+    while signal == [0x00; 4] {
+        thread::yield_now();
+        memory.read(0, &mut signal).unwrap();
+    }
+    assert_eq!(
+        call::<i32, i32>(&mut store, instance, "grow", 1).unwrap(),
+        1
+    );
+    assert_eq!(accessor.join().unwrap(), 43);
+}
+
+#[test]
+fn shared_memory_accesses_work() {
+    let engine = engine();
+    let mut store = Store::new(&engine, ());
+    let linker = <Linker<()>>::new(&engine);
+    let instance = instantiate(
+        &mut store,
+        &linker,
+        r#"
+        (module
+            (memory (export "memory") 1 1 shared)
+            (data $data "\01\02\03\04")
+            (func (export "store") (param i32 i64)
+                (i64.store (local.get 0) (local.get 1))
+            )
+            (func (export "load") (param i32) (result i32)
+                (i32.load16_u (local.get 0))
+            )
+            (func (export "bulk")
+                (memory.fill (i32.const 16) (i32.const 0xFF) (i32.const 4))
+                (memory.copy (i32.const 24) (i32.const 16) (i32.const 2))
+                (memory.init $data (i32.const 32) (i32.const 1) (i32.const 3))
+            )
+        )
+        "#,
+    );
+    call::<(i32, i64), ()>(&mut store, instance, "store", (0, 0x0102_0304_0506_0708)).unwrap();
+    assert_eq!(call::<i32, i32>(&mut store, instance, "load", 2).unwrap(), 0x0506);
+    let trap = call::<i32, i32>(&mut store, instance, "load", 65535).unwrap_err();
+    assert!(matches!(trap.trap_code(), Some(TrapCode::MemoryOutOfBounds)));
+    let trap = call::<(i32, i64), ()>(&mut store, instance, "store", (65530, 0)).unwrap_err();
+    assert!(matches!(trap.trap_code(), Some(TrapCode::MemoryOutOfBounds)));
+    call::<(), ()>(&mut store, instance, "bulk", ()).unwrap();
+    let memory = instance.get_memory(&store, "memory").unwrap();
+    let mut buffer = [0x00_u8; 11];
+    memory.read(&store, 24, &mut buffer).unwrap();
+    assert_eq!(buffer, [0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 2, 3, 4]);
+    let shared = memory.shared(&store).unwrap();
+    shared.write(0, &[0xAA, 0xBB]).unwrap();
+    let mut buffer = [0x00_u8; 2];
+    memory.read(&store, 0, &mut buffer).unwrap();
+    assert_eq!(buffer, [0xAA, 0xBB]);
+    assert!(shared.write(65535, &[0x00, 0x00]).is_err());
+}
+
+#[test]
+#[should_panic]
+fn shared_memory_cannot_be_borrowed() {
+    let engine = engine();
+    let mut store = Store::new(&engine, ());
+    let memory = Memory::new(&mut store, MemoryType::new_shared(1, 1).unwrap()).unwrap();
+    memory.data(&store);
+}
+
+#[test]
+fn shared_memory_bytes_are_not_borrowed() {
+    let engine = engine();
+    let mut store = Store::new(&engine, 7_i32);
+    let memory = Memory::new(&mut store, MemoryType::new_shared(1, 2).unwrap()).unwrap();
+    assert!(memory.try_data(&store).is_none());
+    assert!(memory.try_data_mut(&mut store).is_none());
+    let (bytes, state) = memory.try_data_and_store_mut(&mut store);
+    assert!(bytes.is_none());
+    assert_eq!(*state, 7);
+    assert_eq!(memory.data_size(&store), 1 << 16);
+    memory.grow(&mut store, Pages::new(1).unwrap()).unwrap();
+    assert_eq!(memory.data_size(&store), 2 << 16);
+    let owned = Memory::new(&mut store, MemoryType::new(1, None).unwrap()).unwrap();
+    assert_eq!(owned.try_data(&store).map(<[u8]>::len), Some(1 << 16));
+}
+
+#[test]
+fn shared_memory_maximum_is_limited() {
+    let engine = engine();
+    let limits = StoreLimitsBuilder::new().memory_size(1 << 16).build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits| limits);
+    // The whole maximum size of a shared memory is allocated up front.
+    assert!(matches!(
+        Memory::new(&mut store, MemoryType::new_shared(1, 2).unwrap()),
+        Err(MemoryError::ResourceLimiterDeniedAllocation)
+    ));
+    let memory = Memory::new(&mut store, MemoryType::new_shared(1, 1).unwrap()).unwrap();
+    assert_eq!(store.data().total_memory_consumed(), 1 << 16);
+    assert!(memory.shared(&store).is_some());
+}
+
+/// A [`ThreadParker`] whose every park is a spurious wake-up and whose clock
+/// advances by one millisecond whenever it is read.
+#[derive(Default)]
+struct SpuriousParker {
+    now: Mutex<Duration>,
+    parks: Mutex<Vec<Option<Duration>>>,
+}
+
+impl ThreadParker for SpuriousParker {
+    fn unparker(&self) -> Box<dyn Fn() + Send + Sync> {
+        Box::new(|| {})
+    }
+
+    fn park(&self, timeout: Option<Duration>) {
+        self.parks.lock().unwrap().push(timeout);
+    }
+
+    fn now(&self) -> Duration {
+        let mut now = self.now.lock().unwrap();
+        *now += Duration::from_millis(1);
+        *now
+    }
+}
+
+#[test]
+fn wait_timeout_spans_spurious_wakeups() {
+    let engine = engine();
+    let ty = MemoryType::new_shared(1, 1).unwrap();
+    let memory = SharedMemory::new(&engine, ty).unwrap();
+    let module = wait_notify_module(&engine);
+    let (mut store, instance) = instantiate_with_shared(&module, memory);
+    let parker = Arc::new(SpuriousParker::default());
+    store.set_thread_parker(parker.clone());
+    let timeout = Duration::from_millis(10).as_nanos() as i64;
+    assert_eq!(
+        call::<(i32, i32, i64), i32>(&mut store, instance, "wait", (0, 0, timeout)).unwrap(),
+        2
+    );
+    let parks = parker.parks.lock().unwrap();
+    assert!(!parks.is_empty());
+    assert!(parks.len() < 10);
+    // Every park only waits for the time remaining until the deadline.
+    assert!(parks.windows(2).all(|parks| parks[1] < parks[0]));
+    assert!(parks[0] < Some(Duration::from_millis(10)));
+}
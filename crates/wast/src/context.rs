@@ -1,7 +1,7 @@
-use super::{TestDescriptor, TestError, TestProfile, TestSpan};
-use anyhow::Result;
+use crate::WastError;
 use std::collections::HashMap;
 use wasmi::{
+    core::{ValueType, F32, F64},
     Config,
     Engine,
     Extern,
@@ -18,38 +18,34 @@ use wasmi::{
     TableType,
     Value,
 };
-use wasmi_core::{ValueType, F32, F64};
-use wast::token::{Id, Span};
+use wast::token::Id;
 
-/// The context of a single Wasm test spec suite run.
+/// The context of a single `.wast` script run.
+///
+/// Holds all Wasm module instances defined by the script so far and
+/// provides the `spectest` host module expected by the Wasm spec test suite.
 #[derive(Debug)]
-pub struct TestContext<'a> {
-    /// The `wasmi` engine used for executing functions used during the test.
+pub struct WastContext {
+    /// The `wasmi` engine used for executing functions used during the run.
     engine: Engine,
-    /// The linker for linking together Wasm test modules.
+    /// The linker for linking together Wasm modules of the script.
     linker: Linker<()>,
-    /// The store to hold all runtime data during the test.
+    /// The store to hold all runtime data during the run.
     store: Store<()>,
-    /// The list of all encountered Wasm modules belonging to the test.
+    /// The list of all encountered Wasm modules belonging to the script.
     modules: Vec<Module>,
     /// The list of all instantiated modules.
     instances: HashMap<String, Instance>,
     /// The last touched module instance.
     last_instance: Option<Instance>,
-    /// Profiling during the Wasm spec test run.
-    profile: TestProfile,
     /// Intermediate results buffer that can be reused for calling Wasm functions.
     results: Vec<Value>,
-    /// The descriptor of the test.
-    ///
-    /// Useful for printing better debug messages in case of failure.
-    descriptor: &'a TestDescriptor,
 }
 
-impl<'a> TestContext<'a> {
-    /// Creates a new [`TestContext`] with the given [`TestDescriptor`].
-    pub fn new(descriptor: &'a TestDescriptor, config: Config) -> Self {
-        let engine = Engine::new(&config);
+impl WastContext {
+    /// Creates a new [`WastContext`] for the given [`Config`].
+    pub fn new(config: &Config) -> Self {
+        let engine = Engine::new(config);
         let mut linker = Linker::new(&engine);
         let mut store = Store::new(&engine, ());
         let default_memory = Memory::new(&mut store, MemoryType::new(1, Some(2)).unwrap()).unwrap();
@@ -101,33 +97,21 @@ impl<'a> TestContext<'a> {
         linker
             .define("spectest", "print_f64_f64", print_f64_f64)
             .unwrap();
-        TestContext {
+        WastContext {
             engine,
             linker,
             store,
             modules: Vec::new(),
             instances: HashMap::new(),
             last_instance: None,
-            profile: TestProfile::default(),
             results: Vec::new(),
-            descriptor,
         }
     }
 }
 
-impl TestContext<'_> {
-    /// Returns the file path of the associated `.wast` test file.
-    fn test_path(&self) -> &str {
-        self.descriptor.path()
-    }
-
-    /// Returns the [`TestDescriptor`] of the test context.
-    pub fn spanned(&self, span: Span) -> TestSpan {
-        self.descriptor.spanned(span)
-    }
-
-    /// Returns the [`Engine`] of the [`TestContext`].
-    fn engine(&self) -> &Engine {
+impl WastContext {
+    /// Returns the [`Engine`] of the [`WastContext`].
+    pub fn engine(&self) -> &Engine {
         &self.engine
     }
 
@@ -141,28 +125,32 @@ impl TestContext<'_> {
         &mut self.store
     }
 
-    /// Returns an exclusive reference to the test profile.
-    pub fn profile(&mut self) -> &mut TestProfile {
-        &mut self.profile
+    /// Returns an exclusive reference to the underlying [`Linker`].
+    ///
+    /// Useful to define additional host definitions imported by the Wasm modules of a script.
+    pub fn linker_mut(&mut self) -> &mut Linker<()> {
+        &mut self.linker
+    }
+
+    /// Returns the number of instructions traced so far by the [`Store`].
+    pub fn traced_steps(&self) -> usize {
+        self.store.tracer.logs().len()
     }
 
-    /// Compiles the Wasm module and stores it into the [`TestContext`].
+    /// Compiles the Wasm module and stores it into the [`WastContext`].
     ///
     /// # Errors
     ///
-    /// If creating the [`Module`] fails.
+    /// - If encoding the `.wast` module into `.wasm` fails.
+    /// - If creating or instantiating the [`Module`] fails.
     pub fn compile_and_instantiate(
         &mut self,
         mut module: wast::core::Module,
-    ) -> Result<Instance, TestError> {
+    ) -> Result<Instance, WastError> {
         let module_name = module.id.map(|id| id.name());
-        let wasm = module.encode().unwrap_or_else(|error| {
-            panic!(
-                "encountered unexpected failure to encode `.wast` module into `.wasm`:{}: {}",
-                self.test_path(),
-                error
-            )
-        });
+        let wasm = module.encode().map_err(|error| WastError::Encode {
+            message: error.to_string(),
+        })?;
         let module = Module::new(self.engine(), &wasm[..])?;
         let instance_pre = self.linker.instantiate(&mut self.store, &module)?;
         let instance = instance_pre.start(&mut self.store)?;
@@ -183,11 +171,11 @@ impl TestContext<'_> {
     /// # Errors
     ///
     /// If there is no registered module instance with the given name.
-    pub fn instance_by_name(&self, name: &str) -> Result<Instance, TestError> {
+    pub fn instance_by_name(&self, name: &str) -> Result<Instance, WastError> {
         self.instances
             .get(name)
             .copied()
-            .ok_or_else(|| TestError::InstanceNotRegistered {
+            .ok_or_else(|| WastError::InstanceNotRegistered {
                 name: name.to_owned(),
             })
     }
@@ -197,31 +185,28 @@ impl TestContext<'_> {
     /// # Errors
     ///
     /// If there have been no Wasm module instances registered so far.
-    pub fn instance_by_name_or_last(&self, name: Option<&str>) -> Result<Instance, TestError> {
+    pub fn instance_by_name_or_last(&self, name: Option<&str>) -> Result<Instance, WastError> {
         name.map(|name| self.instance_by_name(name))
-            .unwrap_or_else(|| self.last_instance.ok_or(TestError::NoModuleInstancesFound))
+            .unwrap_or_else(|| self.last_instance.ok_or(WastError::NoModuleInstancesFound))
     }
 
     /// Registers the given [`Instance`] with the given `name` and sets it as the last instance.
-    pub fn register_instance(&mut self, name: &str, instance: Instance) {
-        if self.instances.get(name).is_some() {
+    ///
+    /// # Errors
+    ///
+    /// If the exports of the [`Instance`] cannot be defined in the [`Linker`].
+    pub fn register_instance(&mut self, name: &str, instance: Instance) -> Result<(), WastError> {
+        if self.instances.contains_key(name) {
             // Already registered the instance.
-            return;
+            return Ok(());
         }
         self.instances.insert(name.to_string(), instance);
         for export in instance.exports(&self.store) {
             self.linker
-                .define(name, export.name(), export.clone().into_extern())
-                .unwrap_or_else(|error| {
-                    let field_name = export.name();
-                    let export = export.clone().into_extern();
-                    panic!(
-                        "failed to define export {name}::{field_name}: \
-                        {export:?}: {error}",
-                    )
-                });
+                .define(name, export.name(), export.into_extern())?;
         }
         self.last_instance = Some(instance);
+        Ok(())
     }
 
     /// Invokes the [`Func`] identified by `func_name` in [`Instance`] identified by `module_name`.
@@ -242,13 +227,13 @@ impl TestContext<'_> {
         module_name: Option<&str>,
         func_name: &str,
         args: &[Value],
-    ) -> Result<&[Value], TestError> {
+    ) -> Result<&[Value], WastError> {
         let instance = self.instance_by_name_or_last(module_name)?;
         let func = instance
             .get_export(&self.store, func_name)
             .and_then(Extern::into_func)
-            .ok_or_else(|| TestError::FuncNotFound {
-                module_name: module_name.map(|name| name.to_string()),
+            .ok_or_else(|| WastError::FuncNotFound {
+                module_name: module_name.map(ToString::to_string),
                 func_name: func_name.to_string(),
             })?;
         let len_results = func.ty(&self.store).results().len();
@@ -268,14 +253,14 @@ impl TestContext<'_> {
         &self,
         module_name: Option<Id>,
         global_name: &str,
-    ) -> Result<Value, TestError> {
+    ) -> Result<Value, WastError> {
         let module_name = module_name.map(|id| id.name());
         let instance = self.instance_by_name_or_last(module_name)?;
         let global = instance
             .get_export(&self.store, global_name)
             .and_then(Extern::into_global)
-            .ok_or_else(|| TestError::GlobalNotFound {
-                module_name: module_name.map(|name| name.to_string()),
+            .ok_or_else(|| WastError::GlobalNotFound {
+                module_name: module_name.map(ToString::to_string),
                 global_name: global_name.to_string(),
             })?;
         let value = global.get(&self.store);
@@ -0,0 +1,110 @@
+use std::{error::Error, fmt, fmt::Display, io};
+use wasmi::Error as WasmiError;
+
+/// Errors that may occur upon `.wast` script execution.
+#[derive(Debug)]
+pub enum WastError {
+    /// Failed to read the `.wast` script file.
+    Read {
+        path: String,
+        error: io::Error,
+    },
+    /// Failed to parse the `.wast` script.
+    Parse {
+        path: String,
+        message: String,
+    },
+    /// Failed to encode a `.wast` module into its `.wasm` binary representation.
+    Encode {
+        message: String,
+    },
+    /// A directive did not behave as asserted.
+    Assertion {
+        message: String,
+    },
+    /// A directive uses a feature that is not supported by `wasmi`.
+    Unsupported {
+        message: String,
+    },
+    Wasmi(WasmiError),
+    InstanceNotRegistered {
+        name: String,
+    },
+    NoModuleInstancesFound,
+    FuncNotFound {
+        module_name: Option<String>,
+        func_name: String,
+    },
+    GlobalNotFound {
+        module_name: Option<String>,
+        global_name: String,
+    },
+}
+
+impl WastError {
+    /// Creates a new [`WastError::Assertion`] with the given `message`.
+    pub(crate) fn assertion(message: impl Into<String>) -> Self {
+        Self::Assertion {
+            message: message.into(),
+        }
+    }
+
+    /// Creates a new [`WastError::Unsupported`] with the given `message`.
+    pub(crate) fn unsupported(message: impl Into<String>) -> Self {
+        Self::Unsupported {
+            message: message.into(),
+        }
+    }
+}
+
+impl Error for WastError {}
+
+impl Display for WastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, error } => {
+                write!(f, "failed to read `.wast` file {path}: {error}")
+            }
+            Self::Parse { path, message } => {
+                write!(f, "failed to parse `.wast` file {path}: {message}")
+            }
+            Self::Encode { message } => {
+                write!(f, "failed to encode `.wast` module into `.wasm`: {message}")
+            }
+            Self::Assertion { message } | Self::Unsupported { message } => {
+                write!(f, "{message}")
+            }
+            Self::InstanceNotRegistered { name } => {
+                write!(f, "missing module instance with name: {name}")
+            }
+            Self::NoModuleInstancesFound => {
+                write!(f, "found no module instances registered so far")
+            }
+            Self::FuncNotFound {
+                module_name,
+                func_name,
+            } => {
+                write!(f, "missing func exported as: {module_name:?}::{func_name}",)
+            }
+            Self::GlobalNotFound {
+                module_name,
+                global_name,
+            } => {
+                write!(
+                    f,
+                    "missing global variable exported as: {module_name:?}::{global_name}",
+                )
+            }
+            Self::Wasmi(wasmi_error) => Display::fmt(wasmi_error, f),
+        }
+    }
+}
+
+impl<E> From<E> for WastError
+where
+    E: Into<WasmiError>,
+{
+    fn from(error: E) -> Self {
+        Self::Wasmi(error.into())
+    }
+}
@@ -0,0 +1,43 @@
+//! Runner for WebAssembly `.wast` script files on the `wasmi` interpreter.
+//!
+//! Scripts are executed directive by directive via [`run_wast_file`] or [`run_wast`]
+//! and produce a [`WastReport`] with the [`Outcome`] of every directive instead of
+//! stopping at the first failure. Directives using features not supported by `wasmi`,
+//! such as quoted modules or the component model, are reported as skipped.
+//!
+//! The `spectest` host module expected by the official Wasm spec test suite is
+//! always provided. Additional host definitions can be added via
+//! [`WastContext::linker_mut`] before calling [`WastContext::run`].
+//!
+//! # Tracing
+//!
+//! Every `wasmi` [`Store`](wasmi::Store) traces the executed instructions.
+//! Each [`DirectiveReport`] records the number of instructions traced while
+//! executing it so that assertion files can verify that the traced execution
+//! produces the asserted results. Running the same script with and without
+//! [`Config::deterministic_tracing`](wasmi::Config::deterministic_tracing)
+//! and comparing the reports via [`WastReport::diff`] shows whether the
+//! deterministic tracing mode changes the semantics of any directive.
+
+#![warn(
+    clippy::cast_lossless,
+    clippy::missing_errors_doc,
+    clippy::used_underscore_binding,
+    clippy::redundant_closure_for_method_calls,
+    clippy::type_repetition_in_bounds,
+    clippy::inconsistent_struct_constructor,
+    clippy::default_trait_access,
+    clippy::items_after_statements
+)]
+
+mod context;
+mod error;
+mod report;
+mod run;
+
+pub use self::{
+    context::WastContext,
+    error::WastError,
+    report::{DirectiveKind, DirectiveReport, Outcome, WastReport},
+    run::{run_wast, run_wast_file},
+};
@@ -0,0 +1,178 @@
+use serde::Serialize;
+use std::fmt::{self, Display};
+
+/// The kind of a `.wast` directive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectiveKind {
+    Module,
+    QuoteModule,
+    Register,
+    Invoke,
+    AssertReturn,
+    AssertTrap,
+    AssertExhaustion,
+    AssertMalformed,
+    AssertInvalid,
+    AssertUnlinkable,
+    AssertException,
+}
+
+impl DirectiveKind {
+    /// Returns the name of the directive as written in `.wast` files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Module => "module",
+            Self::QuoteModule => "module quote",
+            Self::Register => "register",
+            Self::Invoke => "invoke",
+            Self::AssertReturn => "assert_return",
+            Self::AssertTrap => "assert_trap",
+            Self::AssertExhaustion => "assert_exhaustion",
+            Self::AssertMalformed => "assert_malformed",
+            Self::AssertInvalid => "assert_invalid",
+            Self::AssertUnlinkable => "assert_unlinkable",
+            Self::AssertException => "assert_exception",
+        }
+    }
+}
+
+impl Display for DirectiveKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The outcome of executing a single `.wast` directive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", content = "message", rename_all = "snake_case")]
+pub enum Outcome {
+    /// The directive behaved as asserted.
+    Passed,
+    /// The directive did not behave as asserted.
+    Failed(String),
+    /// The directive was not executed since `wasmi` does not support it.
+    Skipped(String),
+}
+
+/// The report of a single executed `.wast` directive.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectiveReport {
+    /// The kind of the directive.
+    pub kind: DirectiveKind,
+    /// The 1-based line of the directive within the `.wast` file.
+    pub line: usize,
+    /// The 1-based column of the directive within the `.wast` file.
+    pub col: usize,
+    /// The outcome of the directive.
+    #[serde(flatten)]
+    pub outcome: Outcome,
+    /// The number of Wasm instructions traced while executing the directive.
+    pub traced_steps: usize,
+}
+
+/// The report of a `.wast` script run listing the outcome of every directive.
+#[derive(Debug, Clone, Serialize)]
+pub struct WastReport {
+    /// The path of the `.wast` file.
+    pub path: String,
+    /// The reports of all directives in the order of their execution.
+    pub directives: Vec<DirectiveReport>,
+}
+
+/// The JSON representation of a [`WastReport`] including its summary.
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    path: &'a str,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    directives: &'a [DirectiveReport],
+}
+
+impl WastReport {
+    /// Returns the number of directives with the given `outcome` kind.
+    fn count(&self, f: impl Fn(&Outcome) -> bool) -> usize {
+        self.directives
+            .iter()
+            .filter(|directive| f(&directive.outcome))
+            .count()
+    }
+
+    /// Returns the number of passed directives.
+    pub fn len_passed(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Passed))
+    }
+
+    /// Returns the number of failed directives.
+    pub fn len_failed(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Failed(_)))
+    }
+
+    /// Returns the number of skipped directives.
+    pub fn len_skipped(&self) -> usize {
+        self.count(|outcome| matches!(outcome, Outcome::Skipped(_)))
+    }
+
+    /// Returns `true` if no directive failed.
+    pub fn is_success(&self) -> bool {
+        self.len_failed() == 0
+    }
+
+    /// Returns an iterator over the reports of all failed directives.
+    pub fn failures(&self) -> impl Iterator<Item = &DirectiveReport> {
+        self.directives
+            .iter()
+            .filter(|directive| matches!(directive.outcome, Outcome::Failed(_)))
+    }
+
+    /// Returns the summary line of the report.
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} passed, {} failed, {} skipped",
+            self.path,
+            self.len_passed(),
+            self.len_failed(),
+            self.len_skipped()
+        )
+    }
+
+    /// Returns the pairs of directive reports of `self` and `other` with differing outcomes.
+    ///
+    /// # Note
+    ///
+    /// Both reports are expected to stem from runs of the same `.wast` script.
+    /// Directives present in only one of the reports are not compared.
+    pub fn diff<'a>(
+        &'a self,
+        other: &'a WastReport,
+    ) -> impl Iterator<Item = (&'a DirectiveReport, &'a DirectiveReport)> {
+        self.directives
+            .iter()
+            .zip(&other.directives)
+            .filter(|(lhs, rhs)| lhs.outcome != rhs.outcome)
+    }
+
+    /// Serializes the report including its summary as JSON.
+    pub fn to_json(&self) -> String {
+        let report = JsonReport {
+            path: &self.path,
+            passed: self.len_passed(),
+            failed: self.len_failed(),
+            skipped: self.len_skipped(),
+            directives: &self.directives,
+        };
+        serde_json::to_string(&report).unwrap()
+    }
+}
+
+impl Display for DirectiveReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{} {}: ", self.line, self.col, self.kind)?;
+        match &self.outcome {
+            Outcome::Passed => write!(f, "ok"),
+            Outcome::Failed(message) => write!(f, "FAILED: {message}"),
+            Outcome::Skipped(reason) => write!(f, "skipped: {reason}"),
+        }
+    }
+}
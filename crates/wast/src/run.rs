@@ -0,0 +1,366 @@
+use crate::{DirectiveKind, DirectiveReport, Outcome, WastContext, WastError, WastReport};
+use std::{fs, path::Path};
+use wasmi::{
+    core::{F32, F64},
+    Config,
+    ExternRef,
+    FuncRef,
+    Store,
+    Value,
+};
+use wast::{
+    core::{HeapType, NanPattern, WastArgCore, WastRetCore},
+    lexer::Lexer,
+    parser::{self, ParseBuffer},
+    QuoteWat,
+    Wast,
+    WastArg,
+    WastDirective,
+    WastExecute,
+    WastInvoke,
+    WastRet,
+    Wat,
+};
+
+/// Reads and runs the `.wast` file at `path` with a fresh [`WastContext`] for the [`Config`].
+///
+/// # Errors
+///
+/// - If the `.wast` file cannot be read.
+/// - If the `.wast` file cannot be parsed.
+pub fn run_wast_file(path: impl AsRef<Path>, config: &Config) -> Result<WastReport, WastError> {
+    let path = path.as_ref().display().to_string();
+    let wast = fs::read_to_string(&path).map_err(|error| WastError::Read {
+        path: path.clone(),
+        error,
+    })?;
+    run_wast(&path, &wast, config)
+}
+
+/// Runs the `.wast` script `wast` with a fresh [`WastContext`] for the [`Config`].
+///
+/// The `path` is only used for reporting.
+///
+/// # Errors
+///
+/// If the `.wast` script cannot be parsed.
+pub fn run_wast(path: &str, wast: &str, config: &Config) -> Result<WastReport, WastError> {
+    WastContext::new(config).run(path, wast)
+}
+
+impl WastContext {
+    /// Runs all directives of the `.wast` script `wast` in this [`WastContext`].
+    ///
+    /// Directives are executed in order even if some of them fail.
+    /// The `path` is only used for reporting.
+    ///
+    /// # Errors
+    ///
+    /// If the `.wast` script cannot be parsed.
+    pub fn run(&mut self, path: &str, wast: &str) -> Result<WastReport, WastError> {
+        let parse_error = |error: wast::Error| WastError::Parse {
+            path: path.to_string(),
+            message: error.to_string(),
+        };
+        let mut lexer = Lexer::new(wast);
+        lexer.allow_confusing_unicode(true);
+        let parse_buffer = ParseBuffer::new_with_lexer(lexer).map_err(parse_error)?;
+        let script = parser::parse::<Wast>(&parse_buffer).map_err(parse_error)?;
+        let mut directives = Vec::new();
+        for directive in script.directives {
+            let (line, col) = directive.span().linecol_in(wast);
+            let steps_before = self.traced_steps();
+            let (kind, result) = self.execute_directive(directive);
+            let outcome = match result {
+                Ok(()) => Outcome::Passed,
+                Err(WastError::Unsupported { message }) => Outcome::Skipped(message),
+                Err(error) => Outcome::Failed(error.to_string()),
+            };
+            directives.push(DirectiveReport {
+                kind,
+                line: line + 1,
+                col: col + 1,
+                outcome,
+                traced_steps: self.traced_steps() - steps_before,
+            });
+        }
+        Ok(WastReport {
+            path: path.to_string(),
+            directives,
+        })
+    }
+
+    /// Executes a single `.wast` directive and returns its kind and result.
+    ///
+    /// # Note
+    ///
+    /// Directives using features unsupported by `wasmi` yield [`WastError::Unsupported`].
+    fn execute_directive(
+        &mut self,
+        directive: WastDirective,
+    ) -> (DirectiveKind, Result<(), WastError>) {
+        match directive {
+            WastDirective::Wat(QuoteWat::Wat(Wat::Module(module))) => (
+                DirectiveKind::Module,
+                self.compile_and_instantiate(module).map(|_| ()),
+            ),
+            WastDirective::Wat(_) => {
+                // For the purpose of testing `wasmi` we are not
+                // interested in parsing `.wat` files, therefore
+                // we ignore this case for now.
+                // This might change once wasmi supports `.wat` files.
+                (
+                    DirectiveKind::QuoteModule,
+                    Err(WastError::unsupported("quoted and component modules")),
+                )
+            }
+            WastDirective::AssertMalformed {
+                module: QuoteWat::Wat(Wat::Module(module)),
+                message,
+                ..
+            } => (
+                DirectiveKind::AssertMalformed,
+                self.module_compilation_fails(module, message),
+            ),
+            WastDirective::AssertMalformed { .. } => (
+                DirectiveKind::AssertMalformed,
+                Err(WastError::unsupported("quoted and component modules")),
+            ),
+            WastDirective::AssertInvalid {
+                module, message, ..
+            } => {
+                let result = match extract_module(module) {
+                    Some(module) => self.module_compilation_fails(module, message),
+                    None => Err(WastError::unsupported("quoted and component modules")),
+                };
+                (DirectiveKind::AssertInvalid, result)
+            }
+            WastDirective::Register { name, module, .. } => {
+                let module_name = module.map(|id| id.name());
+                let result = self
+                    .instance_by_name_or_last(module_name)
+                    .and_then(|instance| self.register_instance(name, instance));
+                (DirectiveKind::Register, result)
+            }
+            WastDirective::Invoke(wast_invoke) => (
+                DirectiveKind::Invoke,
+                self.execute_wast_invoke(wast_invoke).map(|_| ()),
+            ),
+            WastDirective::AssertTrap { exec, message, .. } => {
+                let result = match self.execute_wast_execute(exec) {
+                    Ok(results) => Err(WastError::assertion(format!(
+                        "expected to trap with message '{message}' but succeeded with: {results:?}"
+                    ))),
+                    Err(error) => assert_trap(error, message),
+                };
+                (DirectiveKind::AssertTrap, result)
+            }
+            WastDirective::AssertReturn {
+                exec,
+                results: expected,
+                ..
+            } => {
+                let result = self
+                    .execute_wast_execute(exec)
+                    .and_then(|results| assert_results(self.store(), &results, &expected));
+                (DirectiveKind::AssertReturn, result)
+            }
+            WastDirective::AssertExhaustion { call, message, .. } => {
+                let result = match self.execute_wast_invoke(call) {
+                    Ok(results) => Err(WastError::assertion(format!(
+                        "expected to fail due to resource exhaustion '{message}' but succeeded with: {results:?}"
+                    ))),
+                    Err(error) => assert_trap(error, message),
+                };
+                (DirectiveKind::AssertExhaustion, result)
+            }
+            WastDirective::AssertUnlinkable {
+                module: Wat::Module(module),
+                message,
+                ..
+            } => (
+                DirectiveKind::AssertUnlinkable,
+                self.module_compilation_fails(module, message),
+            ),
+            WastDirective::AssertUnlinkable { .. } => (
+                DirectiveKind::AssertUnlinkable,
+                Err(WastError::unsupported("component modules")),
+            ),
+            WastDirective::AssertException { exec, .. } => {
+                let result = match self.execute_wast_execute(exec) {
+                    Ok(results) => Err(WastError::assertion(format!(
+                        "expected to fail due to exception but succeeded with: {results:?}"
+                    ))),
+                    Err(error @ WastError::Unsupported { .. }) => Err(error),
+                    Err(_) => Ok(()),
+                };
+                (DirectiveKind::AssertException, result)
+            }
+        }
+    }
+
+    /// Asserts that compiling or instantiating the `module` fails.
+    fn module_compilation_fails(
+        &mut self,
+        module: wast::core::Module,
+        expected_message: &str,
+    ) -> Result<(), WastError> {
+        match self.compile_and_instantiate(module) {
+            Ok(_) => Err(WastError::assertion(format!(
+                "succeeded to instantiate module but should have failed with: {expected_message}"
+            ))),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn execute_wast_execute(&mut self, execute: WastExecute) -> Result<Vec<Value>, WastError> {
+        match execute {
+            WastExecute::Invoke(invoke) => self.execute_wast_invoke(invoke),
+            WastExecute::Wat(Wat::Module(module)) => {
+                self.compile_and_instantiate(module).map(|_| Vec::new())
+            }
+            WastExecute::Wat(Wat::Component(_)) => Err(WastError::unsupported(
+                "`wasmi` does not support the Wasm `component-model` proposal",
+            )),
+            WastExecute::Get { module, global } => {
+                self.get_global(module, global).map(|result| vec![result])
+            }
+        }
+    }
+
+    fn execute_wast_invoke(&mut self, invoke: WastInvoke) -> Result<Vec<Value>, WastError> {
+        let module_name = invoke.module.map(|id| id.name());
+        let field_name = invoke.name;
+        let mut args = <Vec<Value>>::new();
+        for arg in invoke.args {
+            let value = match arg {
+                WastArg::Core(arg) => value(self.store_mut(), &arg).ok_or_else(|| {
+                    WastError::unsupported(format!(
+                        "encountered unsupported WastArgCore argument: {arg:?}"
+                    ))
+                })?,
+                WastArg::Component(arg) => {
+                    return Err(WastError::unsupported(format!(
+                        "`wasmi` does not support the Wasm `component-model` but found {arg:?}"
+                    )))
+                }
+            };
+            args.push(value);
+        }
+        self.invoke(module_name, field_name, &args)
+            .map(<[Value]>::to_vec)
+    }
+}
+
+/// Asserts that the `error` is a trap with the expected `message`.
+///
+/// # Errors
+///
+/// - If the `error` is not a trap.
+/// - If the trap message of the `error` is not as expected.
+fn assert_trap(error: WastError, message: &str) -> Result<(), WastError> {
+    match error {
+        WastError::Wasmi(error) if error.to_string().contains(message) => Ok(()),
+        WastError::Wasmi(error) => Err(WastError::assertion(format!(
+            "the directive trapped as expected but with an unexpected message\n\
+                expected: {message},\n\
+                encountered: {error}",
+        ))),
+        error @ WastError::Unsupported { .. } => Err(error),
+        unexpected => Err(WastError::assertion(format!(
+            "encountered unexpected error: \n\t\
+                found: '{unexpected}'\n\t\
+                expected: trap with message '{message}'",
+        ))),
+    }
+}
+
+/// Asserts that `results` match the `expected` values.
+///
+/// # Errors
+///
+/// If the `results` do not match the `expected` values.
+fn assert_results(
+    store: &Store<()>,
+    results: &[Value],
+    expected: &[WastRet],
+) -> Result<(), WastError> {
+    if results.len() != expected.len() {
+        return Err(WastError::assertion(format!(
+            "expected {} results but found {}: {results:?}",
+            expected.len(),
+            results.len()
+        )));
+    }
+    for (result, expected) in results.iter().zip(expected) {
+        let expected = match expected {
+            WastRet::Core(expected) => expected,
+            WastRet::Component(expected) => {
+                return Err(WastError::unsupported(format!(
+                    "`wasmi` does not support the Wasm `component-model` proposal but found {expected:?}"
+                )))
+            }
+        };
+        let matches = match (result, expected) {
+            (Value::I32(result), WastRetCore::I32(expected)) => result == expected,
+            (Value::I64(result), WastRetCore::I64(expected)) => result == expected,
+            (Value::F32(result), WastRetCore::F32(expected)) => match expected {
+                NanPattern::CanonicalNan | NanPattern::ArithmeticNan => result.is_nan(),
+                NanPattern::Value(expected) => result.to_bits() == expected.bits,
+            },
+            (Value::F64(result), WastRetCore::F64(expected)) => match expected {
+                NanPattern::CanonicalNan | NanPattern::ArithmeticNan => result.is_nan(),
+                NanPattern::Value(expected) => result.to_bits() == expected.bits,
+            },
+            (Value::FuncRef(funcref), WastRetCore::RefNull(Some(HeapType::Func))) => {
+                funcref.is_null()
+            }
+            (Value::ExternRef(externref), WastRetCore::RefNull(Some(HeapType::Extern))) => {
+                externref.is_null()
+            }
+            (Value::ExternRef(externref), WastRetCore::RefExtern(expected)) => {
+                externref
+                    .data(store)
+                    .and_then(|data| data.downcast_ref::<u32>())
+                    == Some(expected)
+            }
+            _ => false,
+        };
+        if !matches {
+            return Err(WastError::assertion(format!(
+                "encountered mismatch in evaluation. expected {expected:?} but found {result:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn extract_module(quote_wat: QuoteWat) -> Option<wast::core::Module> {
+    match quote_wat {
+        QuoteWat::Wat(Wat::Module(module)) => Some(module),
+        QuoteWat::Wat(Wat::Component(_))
+        | QuoteWat::QuoteModule(_, _)
+        | QuoteWat::QuoteComponent(_, _) => {
+            // We currently do not allow parsing `.wat` Wasm modules in `v1`
+            // therefore checks based on malformed `.wat` modules are uninteresting
+            // to us at the moment.
+            // This might become interesting once `v1` starts support parsing `.wat`
+            // Wasm modules.
+            None
+        }
+    }
+}
+
+/// Converts the [`WastArgCore`] into a [`wasmi::Value`] if possible.
+fn value(ctx: &mut Store<()>, value: &WastArgCore) -> Option<Value> {
+    Some(match value {
+        WastArgCore::I32(arg) => Value::I32(*arg),
+        WastArgCore::I64(arg) => Value::I64(*arg),
+        WastArgCore::F32(arg) => Value::F32(F32::from_bits(arg.bits)),
+        WastArgCore::F64(arg) => Value::F64(F64::from_bits(arg.bits)),
+        WastArgCore::RefNull(HeapType::Func) => Value::FuncRef(FuncRef::null()),
+        WastArgCore::RefNull(HeapType::Extern) => Value::ExternRef(ExternRef::null()),
+        WastArgCore::RefExtern(value) => Value::ExternRef(ExternRef::new(ctx, *value)),
+        _ => return None,
+    })
+}
@@ -0,0 +1,85 @@
+use wasmi::Config;
+use wasmi_wast::{run_wast, DirectiveKind, Outcome, WastError};
+
+const SCRIPT: &str = r#"
+(module
+    (memory (export "memory") 1)
+    (global $counter (export "counter") (mut i32) (i32.const 0))
+    (func (export "add") (param i32 i32) (result i32)
+        (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+        (i32.store (i32.const 0) (local.get 0))
+        (i32.add (local.get 0) (local.get 1))
+    )
+    (func (export "div") (param i32 i32) (result i32)
+        (i32.div_s (local.get 0) (local.get 1))
+    )
+)
+(assert_return (invoke "add" (i32.const 1) (i32.const 2)) (i32.const 3))
+(assert_return (get "counter") (i32.const 1))
+(assert_trap (invoke "div" (i32.const 1) (i32.const 0)) "integer divide by zero")
+(module quote "(func)")
+(assert_return (invoke "add" (i32.const 1) (i32.const 2)) (i32.const 4))
+(assert_invalid (module (func (result i32))) "type mismatch")
+"#;
+
+#[test]
+fn run_wast_reports_every_directive() {
+    let report = run_wast("script.wast", SCRIPT, &Config::default()).unwrap();
+    let kinds = report
+        .directives
+        .iter()
+        .map(|directive| directive.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            DirectiveKind::Module,
+            DirectiveKind::AssertReturn,
+            DirectiveKind::AssertReturn,
+            DirectiveKind::AssertTrap,
+            DirectiveKind::QuoteModule,
+            DirectiveKind::AssertReturn,
+            DirectiveKind::AssertInvalid,
+        ]
+    );
+    assert_eq!(report.len_passed(), 5);
+    assert_eq!(report.len_failed(), 1);
+    assert_eq!(report.len_skipped(), 1);
+    assert!(!report.is_success());
+    let failure = report.failures().next().unwrap();
+    assert_eq!((failure.line, failure.col), (18, 2));
+    assert!(matches!(&failure.outcome, Outcome::Failed(message) if message.contains("mismatch")));
+    assert!(report.directives[1].traced_steps > 0);
+    assert_eq!(report.directives[2].traced_steps, 0);
+}
+
+#[test]
+fn run_wast_to_json() {
+    let report = run_wast("script.wast", SCRIPT, &Config::default()).unwrap();
+    let json = serde_json::from_str::<serde_json::Value>(&report.to_json()).unwrap();
+    assert_eq!(json["path"], "script.wast");
+    assert_eq!(json["passed"], 5);
+    assert_eq!(json["failed"], 1);
+    assert_eq!(json["skipped"], 1);
+    assert_eq!(json["directives"][1]["kind"], "assert_return");
+    assert_eq!(json["directives"][1]["outcome"], "passed");
+    assert_eq!(json["directives"][4]["outcome"], "skipped");
+    assert_eq!(json["directives"][5]["outcome"], "failed");
+    assert_eq!(json["directives"][5]["line"], 18);
+}
+
+#[test]
+fn deterministic_tracing_preserves_outcomes() {
+    let traced = run_wast("script.wast", SCRIPT, &Config::default()).unwrap();
+    let mut config = Config::default();
+    config.deterministic_tracing(false).superinstructions(true);
+    let untraced = run_wast("script.wast", SCRIPT, &config).unwrap();
+    assert_eq!(traced.directives.len(), untraced.directives.len());
+    assert_eq!(traced.diff(&untraced).count(), 0);
+}
+
+#[test]
+fn run_wast_parse_error() {
+    let error = run_wast("broken.wast", "(module", &Config::default()).unwrap_err();
+    assert!(matches!(error, WastError::Parse { path, .. } if path == "broken.wast"));
+}
@@ -42,6 +42,13 @@ void set_wasm_binary (
     uint8_t * wasm_binary,
     size_t wasm_binary_length);
 
+/** \brief
+ *  Executes the `main` function of the Wasm module of the engine.
+ * 
+ *  Returns `0` if `main` returned, the exit status if a host function
+ *  exited the execution and `-1` if the execution trapped.
+ *  The backtrace of a trap is available via `last_backtrace`.
+ */
 int32_t compute_result (
     int32_t engine_id);
 
@@ -54,6 +61,15 @@ uint32_t get_last_pc (
 Vec_uint8_t compute_trace (
     int32_t engine_id);
 
+/** \brief
+ *  Returns the Wasm backtrace of the last trapped `compute_result` or `compute_trace`
+ *  call as a JSON array of `{ func_index, func_name, source_pc }` frames, innermost first.
+ * 
+ *  Returns an empty vector if the last call did not trap.
+ */
+Vec_uint8_t last_backtrace (
+    int32_t engine_id);
+
 Vec_uint8_t memory_data (
     int32_t engine_id);
 